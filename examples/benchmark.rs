@@ -3,6 +3,8 @@
 // Test scenarios:
 // 1. Single-shot: new pipeline → capture(), measure end-to-end latency
 // 2. Continuous capture: continuous frames after pipeline warm-up, measure steady-state latency
+// 3. Readback depth: grab() throughput with 1, 2 and 3 frames in flight
+// 4. Readback strategy: capture() latency with staging copies vs mappable default textures
 //
 // Execute on both monitor and window, results saved to tests/results/
 //
@@ -12,7 +14,9 @@ use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::time::Instant;
 
-use hdrcapture::pipeline::{CapturePipeline, CapturePolicy};
+use hdrcapture::pipeline::{
+    CapturePipeline, CapturePipelineBuilder, CapturePolicy, ReadbackStrategy,
+};
 
 // ---------------------------------------------------------------------------
 // Configuration
@@ -107,6 +111,19 @@ fn create_pipeline(target: &Target) -> Option<CapturePipeline> {
     }
 }
 
+fn target_builder(target: &Target) -> CapturePipelineBuilder {
+    match target {
+        Target::Monitor(idx) => CapturePipelineBuilder::new().monitor(*idx),
+        Target::Window(name) => CapturePipelineBuilder::new()
+            .window_process(*name)
+            .window_index(Some(0)),
+    }
+}
+
+fn create_pipeline_with_depth(target: &Target, depth: usize) -> Option<CapturePipeline> {
+    target_builder(target).readback_depth(depth).build().ok()
+}
+
 fn target_label(target: &Target) -> String {
     match target {
         Target::Monitor(idx) => format!("monitor_{}", idx),
@@ -206,6 +223,87 @@ fn bench_streaming(target: &Target, use_capture: bool, report: &mut String) {
     write!(report, "{s}").unwrap();
 }
 
+/// Readback depth: grab() latency and throughput per number of in-flight frames
+fn bench_readback_depth(target: &Target, report: &mut String) {
+    let label = target_label(target);
+
+    for depth in 1..=3 {
+        let mut pipeline = match create_pipeline_with_depth(target, depth) {
+            Some(p) => p,
+            None => return,
+        };
+        for _ in 0..WARMUP_FRAMES {
+            pipeline.grab().unwrap();
+        }
+
+        let mut durations = Vec::with_capacity(STREAMING_FRAMES);
+        let mut resolution = String::new();
+        let start = Instant::now();
+        for _ in 0..STREAMING_FRAMES {
+            let t = Instant::now();
+            let frame = pipeline.grab().unwrap();
+            durations.push(t.elapsed().as_secs_f64() * 1000.0);
+            resolution = format!("{}x{}", frame.width, frame.height);
+            std::hint::black_box(&frame.data);
+        }
+        let fps = STREAMING_FRAMES as f64 / start.elapsed().as_secs_f64();
+
+        let stats = compute_stats(&mut durations);
+        let mut s = format_stats(
+            &format!("{} grab readback_depth={}", label, depth),
+            &resolution,
+            STREAMING_FRAMES,
+            &stats,
+        );
+        writeln!(s, "  throughput: {:.1} fps", fps).unwrap();
+        print!("{s}");
+        write!(report, "{s}").unwrap();
+    }
+}
+
+/// Readback strategy: capture() latency with staging copies vs mappable default textures
+fn bench_readback_strategy(target: &Target, report: &mut String) {
+    let label = target_label(target);
+
+    for strategy in [
+        ReadbackStrategy::StagingCopy,
+        ReadbackStrategy::MappableDirect,
+    ] {
+        let mut pipeline = match target_builder(target).readback_strategy(strategy).build() {
+            Ok(p) => p,
+            Err(e) => {
+                let msg = format!("SKIPPED: {} {:?}: {:#}\n", label, strategy, e);
+                print!("{msg}");
+                write!(report, "{msg}").unwrap();
+                continue;
+            }
+        };
+        for _ in 0..WARMUP_FRAMES {
+            pipeline.capture().unwrap();
+        }
+
+        let mut durations = Vec::with_capacity(STREAMING_FRAMES);
+        let mut resolution = String::new();
+        for _ in 0..STREAMING_FRAMES {
+            let t = Instant::now();
+            let frame = pipeline.capture().unwrap();
+            durations.push(t.elapsed().as_secs_f64() * 1000.0);
+            resolution = format!("{}x{}", frame.width, frame.height);
+            std::hint::black_box(&frame.data);
+        }
+
+        let stats = compute_stats(&mut durations);
+        let s = format_stats(
+            &format!("{} capture readback={:?}", label, strategy),
+            &resolution,
+            STREAMING_FRAMES,
+            &stats,
+        );
+        print!("{s}");
+        write!(report, "{s}").unwrap();
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Entry point
 // ---------------------------------------------------------------------------
@@ -234,6 +332,8 @@ fn main() {
         bench_single_shot(target, &mut report);
         bench_streaming(target, true, &mut report);
         bench_streaming(target, false, &mut report);
+        bench_readback_depth(target, &mut report);
+        bench_readback_strategy(target, &mut report);
 
         writeln!(report).unwrap();
         println!();
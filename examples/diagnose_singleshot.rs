@@ -16,7 +16,9 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use hdrcapture::capture::wgc::{CaptureTarget, WGCCapture};
-use hdrcapture::capture::{enable_dpi_awareness, find_monitor, init_capture, CapturePolicy};
+use hdrcapture::capture::{
+    enable_dpi_awareness, find_monitor, init_capture, CapturePolicy, SessionOptions,
+};
 use hdrcapture::d3d11::create_d3d11_device;
 use hdrcapture::d3d11::texture::TextureReader;
 
@@ -57,6 +59,7 @@ fn main() {
             &d3d_ctx,
             CaptureTarget::Monitor(hmonitor),
             CapturePolicy::Auto,
+            &SessionOptions::default(),
         )
         .unwrap();
         let init_ms = t.elapsed().as_secs_f64() * 1000.0;
@@ -16,7 +16,9 @@
 use std::fs;
 use std::time::Instant;
 
-use hdrcapture::capture::{find_monitor, init_capture, CapturePolicy, CaptureTarget};
+use hdrcapture::capture::{
+    find_monitor, init_capture, CapturePolicy, CaptureTarget, SessionOptions,
+};
 use hdrcapture::color::tone_map::ToneMapPass;
 use hdrcapture::color::white_level::query_sdr_white_level;
 use hdrcapture::color::{ColorFrame, ColorPixelFormat};
@@ -33,8 +35,13 @@ fn main() {
     println!("SDR white level: {:.1} nits", sdr_white);
 
     // --- 1. Capture HDR frame (RGBA16F) ---
-    let capture = init_capture(&d3d, CaptureTarget::Monitor(hmonitor), CapturePolicy::Hdr)
-        .expect("init capture");
+    let capture = init_capture(
+        &d3d,
+        CaptureTarget::Monitor(hmonitor),
+        CapturePolicy::Hdr,
+        &SessionOptions::default(),
+    )
+    .expect("init capture");
     capture.start().expect("start");
 
     // Wait for first frame
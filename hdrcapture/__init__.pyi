@@ -6,14 +6,18 @@ tone-mapping from HDR to SDR when using ``mode='auto'``.
 """
 
 import numpy as np
-from typing import Literal
+from typing import BinaryIO, Literal
 from numpy.typing import NDArray
 
+class ProtectedContentError(RuntimeError):
+    """Window content is protected (DRM / HDCP or excluded from capture)
+    and captures as black. Raised only with ``detect_protected=True``."""
+
 class CapturedFrame:
     """A single captured frame holding pixel data.
 
-    Pixel format is either ``bgra8`` (8-bit SDR) or ``rgba16f``
-    (16-bit half-float HDR, scRGB linear).
+    Pixel format is ``bgra8`` (8-bit SDR), ``rgba16f`` (16-bit half-float
+    HDR, scRGB linear) or ``rgb10a2`` (10-bit HDR10, see ``hdr10=``).
     """
 
     @property
@@ -32,33 +36,297 @@ class CapturedFrame:
         ...
 
     @property
-    def format(self) -> Literal["bgra8", "rgba16f"]:
-        """Pixel format: ``'bgra8'`` for SDR, ``'rgba16f'`` for HDR."""
+    def format(self) -> Literal["bgra8", "rgba16f", "rgb10a2"]:
+        """Pixel format: ``'bgra8'`` for SDR, ``'rgba16f'`` or ``'rgb10a2'`` for HDR.
+
+        ``'rgb10a2'`` packs 10-bit BT.2020 PQ (HDR10) color and 2-bit alpha
+        into 4 bytes per pixel, red in the low bits.
+        """
+        ...
+
+    @property
+    def stride(self) -> int:
+        """Bytes per row of pixel data (rows are tightly packed)."""
+        ...
+
+    @property
+    def color_space(self) -> Literal["scrgb", "rec2020", "srgb"]:
+        """Encoding of ``rgba16f`` data (always ``'scrgb'`` for ``bgra8`` and
+        ``rgb10a2``, which is HDR10).
+
+        ``'scrgb'``: linear BT.709, 1.0 = 80 nits. ``'rec2020'``: linear
+        BT.2020 primaries. ``'srgb'``: sRGB transfer curve. See the
+        ``color_space`` argument of ``capture.monitor()``.
+        """
+        ...
+
+    @property
+    def source_color_space(self) -> Literal["srgb", "pq_rec2020", "scrgb", "unknown"]:
+        """Color space the source display is driven with.
+
+        ``'pq_rec2020'`` for an HDR10 display, ``'srgb'`` for SDR. Describes the
+        display signal, not the pixel data (see ``color_space``). ``'unknown'``
+        when Windows doesn't report it or monitors of a desktop capture differ.
+        """
+        ...
+
+    @property
+    def dpi_scale(self) -> float:
+        """Display scale factor of the source monitor (``1.0`` = 100%).
+
+        Frame pixels are physical; divide coordinates by this to position
+        overlays in logical (DPI-unaware) coordinates. ``1.0`` for desktop
+        captures whose monitors use different scales.
+        """
+        ...
+
+    @property
+    def bits_per_channel(self) -> int:
+        """Bits per channel of the source display signal (``0`` when unknown)."""
+        ...
+
+    @property
+    def warning(
+        self,
+    ) -> Literal["target_minimized", "session_restarted", "blank_frame"] | None:
+        """Non-fatal capture condition, or ``None``.
+
+        ``'target_minimized'``: the target window is minimized and this is the
+        last frame captured before it was.
+        ``'session_restarted'``: the watchdog restarted a capture session that
+        had stopped delivering frames; this is the first frame after it.
+        ``'blank_frame'``: the frame is effectively black, typically protected
+        (DRM / HDCP) content; only with *blank_threshold*. It takes
+        precedence when another condition applies to the same frame.
+        """
+        ...
+
+    def is_blank(self, threshold: float = 0.02) -> bool:
+        """Whether no pixel is brighter than *threshold* (alpha ignored).
+
+        Protected (DRM / HDCP) windows such as video players capture as solid
+        black. *threshold* is a fraction of full scale for bgra8 / rgb10a2
+        (0.0 = exactly black) and a linear scRGB value for rgba16f.
+        """
+        ...
+
+    def pixel(self, x: int, y: int) -> tuple[int, int, int, int] | tuple[float, float, float, float]:
+        """Value of the pixel at (*x*, *y*) as an ``(r, g, b, a)`` tuple.
+
+        bgra8 frames return sRGB-encoded 0–255 ints. rgba16f / rgb10a2 frames
+        return linear r, g, b in nits (absolute, e.g. 203.0 for HDR reference
+        white) and alpha as a float, 1.0 = opaque.
+
+        Raises:
+            ValueError: If (*x*, *y*) is outside the frame.
+        """
+        ...
+
+    @property
+    def alpha(self) -> Literal["ignore", "premultiplied", "straight"]:
+        """Alpha channel meaning.
+
+        ``'ignore'`` (default): alpha is not meaningful; PNG / BMP / TIFF / EXR
+        are saved fully opaque. ``'premultiplied'``: captured with
+        ``preserve_alpha=True``. ``'straight'``: after ``unpremultiply()``.
+        """
+        ...
+
+    def unpremultiply(self) -> "CapturedFrame":
+        """Return a copy with straight (non-premultiplied) alpha.
+
+        WGC delivers premultiplied color; PNG / TIFF expect straight alpha.
+        Frames that aren't premultiplied are returned unchanged.
+        """
         ...
 
-    def save(self, path: str) -> None:
+    def rotate(self, degrees: Literal[0, 90, 180, 270]) -> "CapturedFrame":
+        """Return a copy rotated clockwise by *degrees*.
+
+        Width and height trade places for 90 and 270. Pixels are moved
+        exactly, for both ``bgra8`` and ``rgba16f``.
+        """
+        ...
+
+    def flip(self, axis: Literal["horizontal", "vertical"]) -> "CapturedFrame":
+        """Return a mirrored copy.
+
+        ``'horizontal'`` mirrors left ↔ right, ``'vertical'`` top ↔ bottom
+        (e.g. for bottom-up OpenGL texture uploads).
+        """
+        ...
+
+    def save(
+        self,
+        path: str | BinaryIO,
+        *,
+        format: str | None = None,
+        icc_profile: str | None = None,
+        compression: Literal["none", "rle", "zip", "piz"] | None = None,
+        quality: int | None = None,
+        assume_srgb: bool | None = None,
+        strict: bool = False,
+    ) -> None:
         """Save frame to file. Format is determined by extension.
 
         SDR-only formats (bgra8):
-          ``.png``, ``.bmp``, ``.jpg`` / ``.jpeg``, ``.tiff`` / ``.tif``
+          ``.png``, ``.bmp``, ``.jpg`` / ``.jpeg``, ``.tiff`` / ``.tif``,
+          ``.webp`` (lossless)
+
+        HDR frames (rgba16f / rgb10a2) saved to these formats are tone-mapped
+        to bgra8 first, like ``to_sdr()``.
 
-        HDR-capable formats (bgra8 and rgba16f):
+        PNG, JPEG and WebP embed an ICC profile: sRGB by default, or the monitor's
+        color profile for frames captured unconverted from an SDR monitor.
+
+        HDR-capable formats (bgra8, rgba16f and rgb10a2; rgb10a2 is decoded
+        to scRGB except for HEIF):
           ``.jxr`` — JPEG XR (Windows native, viewable in Photos app)
+          ``.heic`` / ``.heif`` — HEIF; HDR is written as 10-bit HDR10 with a
+          BT.2020 / PQ ``nclx`` colour box
+          (requires the HEVC Video Extensions from the Microsoft Store)
           ``.exr`` — OpenEXR (industry standard for HDR/VFX)
+          ``.hdr`` — Radiance RGBE (compact shared-exponent format, no alpha;
+          bgra8 values are written as-is, not linearized)
+
+        *path* may also be a writable binary file object (``io.BytesIO``, an
+        open file, a ``zipfile`` entry); the frame is encoded in memory and
+        passed to its ``write()``. Encoder options apply as for a path with
+        the extension *format*.
+
+        Args:
+            path: Output file path, or a binary file object.
+            format: Format extension for file objects, e.g. ``"png"`` (the
+                default) or ``"exr"``. Paths use their extension instead.
+            icc_profile: Path to an ``.icc`` / ``.icm`` file to embed instead
+                (PNG / JPEG only).
+            compression: EXR compression (``.exr`` only, all lossless).
+                ``'rle'`` is the default; ``'zip'`` / ``'piz'`` give much
+                smaller HDR files at a higher encode cost; ``'none'`` is the
+                fastest to write.
+            quality: JPEG quality, 1–100 (``.jpg`` / ``.jpeg`` only). Defaults
+                to 90.
+            assume_srgb: Convert bgra8 values from sRGB to linear light when
+                writing EXR (``.exr`` only). Defaults to True, as EXR readers
+                expect linear data; False writes the encoded values as-is.
+            strict: Raise instead of tone-mapping when saving HDR data to an
+                SDR-only format.
 
         Raises:
-            RuntimeError: If saving rgba16f data to an SDR-only format.
+            RuntimeError: If *strict* is True and HDR (rgba16f / rgb10a2) data
+                is saved to an SDR-only format.
+            TypeError: If *path* is neither a str nor has ``write()``.
+            ValueError: If *compression* is invalid or the output is not EXR,
+                or if *quality* is out of range or the output is not JPEG,
+                or if *assume_srgb* is given for output that is not EXR,
+                or if *format* is unknown or combined with a path.
         """
         ...
 
-    def ndarray(self) -> NDArray[np.uint8] | NDArray[np.float16]:
-        """Convert to numpy array, shape ``(H, W, 4)``.
+    def save_auto(self, dir: str, prefix: str = "", ext: str = "png") -> str:
+        """Save into *dir* under a timestamped name and return the path.
+
+        Names look like ``shot_2024-06-01_12-30-45-123.png`` (local capture
+        time, milliseconds last); ``_1``, ``_2``, ... is appended when the
+        name is taken. *dir* is created if missing.
 
-        - ``bgra8``: dtype ``uint8``, BGRA channel order
-        - ``rgba16f``: dtype ``float16``, RGBA channel order
+        Raises:
+            RuntimeError: If saving fails (see :meth:`save`).
+        """
+        ...
+
+    def to_clipboard(self) -> None:
+        """Copy the frame to the Windows clipboard, ready to paste.
+
+        Offered as a bitmap (``CF_DIBV5``) and as PNG. HDR frames are
+        tone-mapped to SDR first, since the clipboard can't hold HDR. That
+        creates a temporary GPU device per call; while a capture is open,
+        ``capture.copy_to_clipboard(frame)`` reuses its device.
+
+        Raises:
+            RuntimeError: If the clipboard is busy or the frame uses a
+                non-scRGB ``color_space``.
+        """
+        ...
+
+    def to_bytes(self, format: str = "png") -> bytes:
+        """Encode the frame in memory and return the file contents.
+
+        Same formats and behavior as ``save()``, without writing to disk.
+
+        Args:
+            format: Format extension, e.g. ``"png"``, ``"jpg"``, ``"jxr"``,
+                ``"exr"``, ``"hdr"`` or ``"heic"``.
+
+        Raises:
+            ValueError: If the format is unknown.
+            RuntimeError: If encoding fails.
         """
         ...
 
+    def diff(self, other: CapturedFrame) -> tuple[int, float, CapturedFrame | None]:
+        """Compare with another frame pixel by pixel (visual-regression testing).
+
+        Color channels are compared exactly; alpha only when either frame has
+        meaningful alpha (``alpha != "ignore"``).
+
+        Returns:
+            ``(num_different, max_delta, diff_frame)``: the number of changed
+            pixels, the largest channel difference (0–255 for ``bgra8``, scRGB
+            units for ``rgba16f``, 10-bit code steps for ``rgb10a2``), and a ``bgra8`` frame showing changed
+            pixels in magenta over a dimmed copy of this frame (``None`` when
+            the frames are identical).
+
+        Raises:
+            ValueError: If the frames differ in size or pixel format.
+        """
+        ...
+
+    def luminance_stats(self) -> dict[str, float]:
+        """Luminance statistics, computed on demand by scanning every pixel.
+
+        Returns:
+            ``{"max_nits", "p99_nits", "fraction_above_sdr"}``.
+            ``fraction_above_sdr`` is the share of pixels brighter than the
+            display's SDR white level; it is always ``0.0`` for ``bgra8``
+            frames. A non-zero value on an ``rgba16f`` frame means the capture
+            contains genuine HDR highlights.
+        """
+        ...
+
+    def ndarray(
+        self, channels: Literal[3, 4] = 4, order: Literal["rgb", "bgr"] | None = None
+    ) -> NDArray[np.uint8] | NDArray[np.float16] | NDArray[np.uint16]:
+        """Convert to numpy array, shape ``(H, W, channels)``.
+
+        - ``bgra8``: dtype ``uint8``, BGRA channel order by default
+        - ``rgba16f``: dtype ``float16``, RGBA channel order by default
+        - ``rgb10a2``: dtype ``uint16``, unpacked PQ codes (0–1023) and alpha
+          (0–3), RGBA channel order by default
+
+        Args:
+            channels: ``4`` keeps alpha; ``3`` drops it.
+            order: ``"rgb"`` or ``"bgr"``; ``None`` gives RGB with 3 channels
+                and keeps the native order with 4. Reordering happens during
+                the copy, in Rust.
+
+        Example:
+            ``frame.ndarray(channels=3)`` gives the ``(H, W, 3)`` RGB array
+            most CV / ML libraries expect; OpenCV wants ``order="bgr"``.
+        """
+        ...
+
+    def buffer(self) -> memoryview:
+        """Read-only ``memoryview`` over the raw pixel bytes, without copying.
+
+        Unsigned bytes, ``height * stride`` long, laid out as ``format`` says.
+        The view keeps the frame's pixel buffer alive until released. Use it
+        to hash or forward frames without the copy ``ndarray()`` makes.
+        """
+        ...
+
+    def __buffer__(self, flags: int, /) -> memoryview: ...
+
     def __array__(
         self, dtype: object = None, copy: object = None
     ) -> NDArray[np.uint8] | NDArray[np.float16]:
@@ -80,7 +348,11 @@ class capture:
         with capture.monitor(0) as cap:
             frame = cap.capture()
 
-    Not thread-safe — use from the creating thread only.
+    The pipeline runs on a dedicated worker thread. Each call sends one
+    request and blocks (GIL released) until it is answered, so an instance
+    may be shared across threads; concurrent calls are served one at a time.
+    Calls that time out leave their request running; with 4 such requests
+    pending, the next call waits for the oldest first (backpressure).
 
     If the display environment changes (HDR toggled, monitor
     plugged/unplugged), discard this instance and create a new one.
@@ -89,7 +361,28 @@ class capture:
     @staticmethod
     def monitor(
         index: int = 0,
-        mode: Literal["auto", "hdr", "sdr"] = "auto",
+        mode: Literal["auto", "hdr", "sdr", "prefer_hdr"] = "auto",
+        *,
+        name: str | None = None,
+        at: tuple[int, int] | None = None,
+        border: bool = False,
+        lut: str | None = None,
+        size: tuple[int, int] | None = None,
+        fit: Literal["stretch", "letterbox"] = "stretch",
+        max_size: tuple[int, int] | None = None,
+        backend: Literal["auto", "wgc", "duplication"] = "auto",
+        color_space: Literal["scrgb", "rec2020", "srgb"] = "scrgb",
+        sdr_white: float | None = None,
+        brightness: float = 0.0,
+        contrast: float = 1.0,
+        gamma: float = 1.0,
+        hdr10: bool = False,
+        format: Literal["bgra8", "rgba16f", "rgb10a2"] | None = None,
+        watchdog: float | None = None,
+        blank_threshold: float | None = None,
+        rotate: Literal[0, 90, 180, 270] = 0,
+        flip: Literal["horizontal", "vertical"] | None = None,
+        auto_rotate: bool = False,
     ) -> "capture":
         """Create a capture pipeline for a monitor.
 
@@ -97,7 +390,81 @@ class capture:
             index: Monitor index (system enumeration order).
             mode: ``'auto'`` adapts to HDR state (default),
                   ``'hdr'`` forces 16-bit float output,
-                  ``'sdr'`` forces 8-bit output.
+                  ``'sdr'`` forces 8-bit output,
+                  ``'prefer_hdr'`` is ``'hdr'`` on an HDR monitor and
+                  ``'sdr'`` otherwise.
+            name: Monitor GDI device name (e.g. ``"\\\\.\\DISPLAY1"``).
+                  Stable across enumeration order changes; overrides *index*.
+            at: Virtual-desktop point ``(x, y)`` in physical pixels; captures
+                  the monitor containing it, e.g. ``(1920, 0)`` for the display
+                  right of a 1080p primary. Overrides *index*; can't be
+                  combined with *name*.
+            border: Keep the yellow capture border visible. When hiding is
+                  not permitted by the OS, the border stays visible and a
+                  warning is printed.
+            lut: Path to a 3D ``.cube`` LUT applied on the GPU after
+                  HDR→SDR conversion. Not applied to ``rgba16f`` output.
+            size: Fixed ``(width, height)`` output size. Every frame is scaled
+                on the GPU (bilinear), regardless of the source size.
+            fit: Aspect handling for ``size``: ``"stretch"`` fills the output;
+                ``"letterbox"`` keeps the aspect ratio with black bars.
+            max_size: Largest ``(width, height)`` output. Only frames bigger
+                than that are scaled down on the GPU, to the largest size that
+                fits with the aspect ratio kept, e.g. ``(1920, 1080)`` streams
+                a 4K display at 1080p and leaves a 1080p one alone. Ignored
+                when *size* is set.
+            backend: Capture API. ``'auto'`` uses Windows Graphics Capture
+                and falls back to DXGI Desktop Duplication when WGC cannot
+                start (default). ``'wgc'`` and ``'duplication'`` force one.
+                Duplication never includes the cursor and ignores *border*.
+            color_space: Encoding of ``mode="hdr"`` output, converted on the
+                GPU. ``'scrgb'``: linear BT.709 as composed by DWM (default).
+                ``'rec2020'``: linear BT.2020 primaries. ``'srgb'``: BT.709
+                with the sRGB curve, extended beyond [0, 1]. All keep
+                1.0 = 80 nits. Non-scRGB frames can only be saved as ``.exr``.
+            sdr_white: SDR white level in nits used to tone-map HDR content in
+                ``mode="auto"``. Defaults to the Windows "SDR content
+                brightness" setting; set it when that query misreports (HDR
+                captures look washed out).
+            brightness: Offset added to 8-bit output on the GPU, from -1.0
+                to 1.0 (default 0.0). Applied last, after tone-mapping and
+                *lut*; ``rgba16f`` output is not adjusted.
+            contrast: Scale of 8-bit output around mid-gray (default 1.0).
+            gamma: Midtone gamma of 8-bit output; values above 1.0 brighten
+                dark scenes while keeping black and white fixed (default 1.0).
+                Applied before *contrast* and *brightness*. With all three
+                at their defaults the pass is skipped.
+            hdr10: Capture HDR as 10-bit HDR10 (BT.2020, PQ) instead of
+                16-bit scRGB, halving frame size. ``mode="hdr"`` frames are
+                then ``rgb10a2``; ``mode="auto"`` tone-maps from the 10-bit
+                surface. *color_space* does not apply. Windows Graphics
+                Capture only; Desktop Duplication keeps ``rgba16f``.
+            format: Capture surface format, instead of the one *mode* picks
+                from the monitor's HDR state (default ``None``). *mode* still
+                decides the processing: ``mode="hdr", format="rgba16f"``
+                returns 16-bit scRGB even on an SDR monitor (SDR content in
+                0..1) without the mode mismatch warning, ``"auto"``
+                tone-maps HDR surfaces and ``"sdr"`` clips them.
+                ``format="bgra8"`` can't be combined with ``mode="hdr"``.
+                Overrides *hdr10*; Windows Graphics Capture only.
+            watchdog: Seconds a frame the session owes (after a display change
+                or a timed-out wait) may be missing before the capture
+                session is restarted (WGC only; off by default). Guards
+                long-running ``grab()`` loops against WGC silently stopping;
+                the first frame after a restart has
+                ``warning == "session_restarted"``. Static content owes no
+                frames and never triggers a restart.
+            blank_threshold: Tag new frames no brighter than this (see
+                ``CapturedFrame.is_blank``) with ``warning == "blank_frame"``,
+                e.g. ``0.02``. Off by default.
+            rotate: Rotate every frame clockwise on the GPU, before *size*
+                scaling (so *size* is the rotated output size).
+            flip: Mirror every frame after rotating: ``'horizontal'`` or
+                ``'vertical'``.
+            auto_rotate: Undo the monitor's display rotation, so frames of a
+                panel set to portrait or flipped orientation that arrive in
+                the panel's native orientation come out upright. Applied
+                before *rotate* / *flip*; re-read after display changes.
         """
         ...
 
@@ -107,9 +474,31 @@ class capture:
         *,
         pid: int | None = None,
         hwnd: int | None = None,
+        app_id: str | None = None,
         index: int | None = None,
-        mode: Literal["auto", "hdr", "sdr"] = "auto",
+        mode: Literal["auto", "hdr", "sdr", "prefer_hdr"] = "auto",
         headless: bool = True,
+        border: bool = False,
+        lut: str | None = None,
+        crop: tuple[int, int, int, int] | None = None,
+        preserve_alpha: bool = False,
+        size: tuple[int, int] | None = None,
+        fit: Literal["stretch", "letterbox"] = "stretch",
+        max_size: tuple[int, int] | None = None,
+        color_space: Literal["scrgb", "rec2020", "srgb"] = "scrgb",
+        sdr_white: float | None = None,
+        brightness: float = 0.0,
+        contrast: float = 1.0,
+        gamma: float = 1.0,
+        hdr10: bool = False,
+        format: Literal["bgra8", "rgba16f", "rgb10a2"] | None = None,
+        include_popups: bool = False,
+        watchdog: float | None = None,
+        blank_threshold: float | None = None,
+        detect_protected: bool = False,
+        rotate: Literal[0, 90, 180, 270] = 0,
+        flip: Literal["horizontal", "vertical"] | None = None,
+        drop_stale_after_resize: bool = True,
     ) -> "capture":
         """Create a capture pipeline for a window.
 
@@ -117,13 +506,76 @@ class capture:
             process: Target process name (e.g. ``"notepad.exe"``).
             pid: Target process id.
             hwnd: Target window handle.
+            app_id: AppUserModelID of a packaged (UWP / Store) app, e.g.
+                ``"Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"``. Finds
+                the ``ApplicationFrameHost`` frame the app is shown in; the
+                frame's title bar is part of the capture.
             index: Ranked window index within candidate windows.
             mode: Capture mode (see ``monitor()``).
             headless: Crop title bar and borders in window mode.
+            border: Keep the yellow capture border visible.
+            lut: Path to a 3D ``.cube`` LUT (see ``monitor()``).
+            crop: ``(x, y, width, height)`` sub-rect of the client area to
+                capture. Relative to the client area's top-left, so it follows
+                the window; clamped to the client bounds.
+            preserve_alpha: Keep per-pixel alpha for layered / transparent
+                windows. Frames are premultiplied; see
+                ``CapturedFrame.unpremultiply()``.
+            size: Fixed output size (see ``monitor()``); stays constant when
+                the window is resized.
+            fit: Aspect handling for ``size`` (see ``monitor()``).
+            max_size: Output size cap (see ``monitor()``); a window resized
+                beyond it is scaled down.
+            color_space: Encoding of ``mode="hdr"`` output (see ``monitor()``).
+            sdr_white: Tone-mapping SDR white level in nits (see ``monitor()``).
+            brightness: 8-bit output brightness offset (see ``monitor()``).
+            contrast: 8-bit output contrast (see ``monitor()``).
+            gamma: 8-bit output midtone gamma (see ``monitor()``).
+            hdr10: 10-bit HDR10 capture (see ``monitor()``).
+            format: Forced capture surface format (see ``monitor()``).
+            include_popups: Capture the window together with its owned popups
+                (menus, tooltips, detached toolbars). Needs Windows 11;
+                ignored on older builds, where popups are never captured.
+            watchdog: Session restart after frame starvation (see ``monitor()``).
+            blank_threshold: Blank (protected content) detection (see ``monitor()``).
+            detect_protected: Fail instead of returning black frames when the
+                window's content is protected: excluded from capture by the
+                app, or (DRM / HDCP) a visible window of at least 64×64 that
+                delivers 5 exactly-black frames in a row (default False).
+                Fades and black video trip the heuristic too; prefer
+                *blank_threshold* for windows that can be legitimately black.
+            rotate: Clockwise GPU rotation (see ``monitor()``).
+            flip: Mirror after rotating (see ``monitor()``).
+            drop_stale_after_resize: Skip the first frame after the window is
+                resized, which may still show the old content. Costs about one
+                VSync per resize; pass False for the lowest latency when
+                resizes are rare and an occasional stale frame is acceptable.
 
         Notes:
             Selector priority is ``hwnd > pid > process``.
             At least one of ``hwnd``, ``pid``, or ``process`` must be provided.
+
+        Raises:
+            ProtectedContentError: From ``capture()`` / ``grab()`` when
+                *detect_protected* is on and the window's content can't be
+                captured.
+        """
+        ...
+
+    @staticmethod
+    def desktop(mode: Literal["auto", "hdr", "sdr", "prefer_hdr"] = "auto") -> "capture":
+        """Create a capture pipeline spanning all monitors.
+
+        Each ``capture()`` / ``grab()`` returns one frame covering the whole
+        virtual desktop, with every monitor placed at its desktop coordinates.
+        Areas not covered by any monitor are black. Monitors are read back
+        one by one and stitched on the CPU, adding a full-frame copy per
+        capture; use ``monitor()`` when frame rate matters.
+
+        Args:
+            mode: Capture mode (see ``monitor()``). In ``"auto"`` mode HDR
+                monitors are tone-mapped to SDR so the composite is uniformly
+                ``bgra8``; ``"hdr"`` captures every monitor as ``rgba16f``.
         """
         ...
 
@@ -132,19 +584,204 @@ class capture:
         """Whether the target monitor has HDR enabled."""
         ...
 
-    def capture(self) -> CapturedFrame:
+    @property
+    def refresh_rate_hz(self) -> float | None:
+        """Refresh rate (Hz) of the monitor showing the target, or ``None``.
+
+        Read from the active display mode, so fractional rates such as
+        59.94 are reported exactly.
+        """
+        ...
+
+    @property
+    def measured_fps(self) -> float | None:
+        """Rolling average rate at which new frames arrive, or ``None``.
+
+        Matches the refresh rate while content changes every VSync, drops
+        for static content, and varies with the present rate on VRR
+        displays. ``None`` until two frames have arrived.
+        """
+        ...
+
+    def pause(self) -> None:
+        """Stop capturing while keeping the capture session alive.
+
+        For apps that toggle capture on and off: ``resume()`` is instant,
+        unlike opening a new ``capture``. While paused no frames are
+        processed.
+
+        After ``pause()``, ``capture()``, ``grab()`` and the other capture
+        methods raise ``RuntimeError`` until ``resume()``. Pausing twice is a
+        no-op.
+        """
+        ...
+
+    def resume(self) -> None:
+        """Continue after ``pause()``.
+
+        Frames queued while paused are discarded, so the next ``grab()``
+        returns a frame rendered after this call (or the last frame when the
+        content is static).
+        """
+        ...
+
+    @property
+    def paused(self) -> bool:
+        """Whether the capture is paused."""
+        ...
+
+    def exclude_windows(self, hwnds: list[int]) -> None:
+        """Hide windows of this process (overlays, recording UI) from the capture.
+
+        Replaces the previously excluded set; an empty list shows every
+        window again. Uses ``SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE)``,
+        so only this process's own windows can be excluded, and while set
+        they are hidden from every screen capture, not just this one. Their
+        previous state is restored on ``close()``. Windows 10 before 2004
+        captures them as black rectangles instead.
+
+        Args:
+            hwnds: Window handles to hide.
+
+        Raises:
+            RuntimeError: For window captures, invalid handles, or windows
+                owned by another process.
+        """
+        ...
+
+    def copy_to_clipboard(self, frame: CapturedFrame) -> None:
+        """Copy a frame to the Windows clipboard, ready to paste.
+
+        Same result as ``frame.to_clipboard()``, but HDR frames are
+        tone-mapped on this capture's GPU device instead of a temporary one.
+
+        Args:
+            frame: Frame to copy (from any capture).
+
+        Raises:
+            RuntimeError: If the clipboard is busy or the frame uses a
+                non-scRGB ``color_space``.
+        """
+        ...
+
+    def stats(self) -> dict[str, int]:
+        """Capture health counters since the pipeline was created.
+
+        Keys: ``frames_received`` (frames taken from the WGC pool),
+        ``frames_dropped_on_drain`` (frames discarded unread: backlog
+        skipped for a newer one, or still queued on resume, resize,
+        restart or close),
+        ``pool_recreates`` (resizes), ``cache_fallbacks`` (calls answered
+        with the previous frame), ``timeouts`` (waits that expired) and
+        ``session_restarts`` (sessions restarted by the watchdog).
+        """
+        ...
+
+    def capture(self, timeout: float | None = None) -> CapturedFrame:
         """Screenshot mode: drain stale frames, wait for a fresh one.
 
         Guarantees the returned frame was generated after this call.
         Latency is roughly one VSync period. Releases the GIL.
+
+        Args:
+            timeout: Seconds to wait for the capture worker. Raises
+                ``TimeoutError`` on expiry; the capture stays usable and the
+                late result is discarded. ``None`` waits forever.
         """
         ...
 
-    def grab(self) -> CapturedFrame:
+    def grab(self, timeout: float | None = None) -> CapturedFrame:
         """Streaming mode: return the latest available frame.
 
         May return a frame generated before this call. Lower latency
         than ``capture()``. Releases the GIL.
+
+        Args:
+            timeout: Seconds to wait, as in ``capture()``.
+        """
+        ...
+
+    def next_frame(
+        self, mode: Literal["fresh", "latest"] = "fresh", timeout: float | None = None
+    ) -> CapturedFrame:
+        """Return the next frame with an explicit freshness mode.
+
+        ``"fresh"`` is the same as ``capture()``, ``"latest"`` the same as
+        ``grab()``. Releases the GIL.
+
+        Args:
+            mode: ``"fresh"`` (generated after the call) or ``"latest"``
+                (newest available, lower latency).
+            timeout: Seconds to wait, as in ``capture()``.
+        """
+        ...
+
+    def frames(
+        self, max_frames: int | None = None, fps: float | None = None
+    ) -> FrameIterator:
+        """Iterate over streamed frames, calling ``grab()`` for each one.
+
+        Ending or breaking out of the loop leaves the capture open, so it
+        can be iterated again.
+
+        Args:
+            max_frames: Stop after this many frames (default: never stop).
+            fps: Cap the rate to this many frames per second.
+
+        Raises:
+            ValueError: If ``fps`` is not positive.
+
+        Example::
+
+            for frame in cap.frames(max_frames=100, fps=30):
+                process(frame.ndarray())
+        """
+        ...
+
+    def grab_changed(self, timeout: float | None = None) -> CapturedFrame | None:
+        """Streaming mode with change detection.
+
+        Like ``grab()``, but returns ``None`` when the frame content is
+        identical to the previous ``grab_changed()`` result. A GPU checksum
+        is compared first, so unchanged frames skip the full readback —
+        a large saving for mostly-static targets. Calling ``capture()`` or
+        ``grab()`` in between, or changing the policy, LUT, tone-map or output
+        size, makes the next processed frame report a change.
+
+        Args:
+            timeout: Seconds to wait, as in ``capture()``.
+        """
+        ...
+
+    def try_grab(self, timeout: float | None = None) -> CapturedFrame | None:
+        """Non-blocking grab.
+
+        Returns the newest frame that arrived since the previous call, or
+        ``None`` immediately when there is none — it never waits for the
+        compositor. Use it to poll from a UI or game loop. A resize or a
+        minimized target also returns ``None``.
+
+        Args:
+            timeout: Seconds to wait for the capture worker, as in
+                ``capture()``.
+        """
+        ...
+
+    def capture_stable(self, max_wait: float = 2.0, settle: float = 0.1) -> CapturedFrame:
+        """Capture once the target has stopped animating.
+
+        Takes a fresh frame, then grabs one every ``settle`` seconds until two
+        in a row are near-identical (compared on a sparse pixel sample) and
+        returns the later one. After ``max_wait`` seconds the latest frame is
+        returned anyway. Useful for UI screenshots that should show the end of
+        a fade or transition rather than an intermediate frame.
+
+        Args:
+            max_wait: Longest time to wait for the content to settle (seconds).
+            settle: Interval between the two compared frames (seconds).
+
+        Raises:
+            ValueError: If ``max_wait`` or ``settle`` is negative.
         """
         ...
 
@@ -152,7 +789,20 @@ class capture:
         """Release capture resources.
 
         After calling ``close()``, any further method call raises
-        ``RuntimeError``.
+        ``RuntimeError`` until ``reopen()`` is called.
+        """
+        ...
+
+    def reopen(self) -> None:
+        """Start capturing again with the original target and options.
+
+        Revives a capture after ``close()`` (or leaving a ``with`` block)
+        without re-specifying its arguments. The target is resolved again,
+        so a window selected by ``process`` may pick a different window.
+        An open capture is closed first, restarting its session.
+
+        Raises:
+            RuntimeError: If the target can no longer be captured.
         """
         ...
 
@@ -160,18 +810,93 @@ class capture:
     def __exit__(self, exc_type: object, exc_val: object, exc_tb: object) -> bool: ...
     def __repr__(self) -> str: ...
 
+class FrameIterator:
+    """Iterator returned by ``capture.frames()``."""
+
+    def __iter__(self) -> FrameIterator: ...
+    def __next__(self) -> CapturedFrame: ...
+
+class ReplayBuffer:
+    """Rolling buffer of the last *seconds* of a monitor.
+
+    Captures on a background thread at *fps* and keeps the newest
+    ``ceil(seconds * fps)`` frames, evicting the oldest. Static content is
+    repeated (same ``timestamp``), so a full buffer always spans *seconds*
+    unless *max_bytes* evicts frames earlier.
+
+    Example::
+
+        with hdrcapture.ReplayBuffer(monitor=0, seconds=10, fps=30) as replay:
+            ...  # something worth keeping happens
+            for i, frame in enumerate(replay.dump()):
+                frame.save(f"clip_{i:04}.png")
+    """
+
+    def __init__(
+        self,
+        monitor: int = 0,
+        seconds: float = 10.0,
+        fps: int = 30,
+        *,
+        max_bytes: int | None = None,
+    ) -> None:
+        """Start buffering a monitor.
+
+        Args:
+            monitor: Monitor index.
+            seconds: Length of the buffered window.
+            fps: Capture rate, 1 to 240.
+            max_bytes: Cap on the buffered pixel data. Frames hold full
+                pixels (10 s of 4K HDR at 30 fps is about 20 GB); the oldest
+                are evicted to stay under the cap, so the buffer then spans
+                less than *seconds*. Unlimited by default.
+
+        Raises:
+            RuntimeError: If the monitor can't be captured, *fps* is out of
+                range or *max_bytes* is 0.
+        """
+        ...
+
+    def dump(self) -> list[CapturedFrame]:
+        """Buffered frames, oldest first.
+
+        Cheap: frames share pixel data with the buffer. Capture keeps running.
+        """
+        ...
+
+    @property
+    def capacity(self) -> int:
+        """Maximum number of frames kept (``ceil(seconds * fps)``)."""
+        ...
+
+    @property
+    def max_bytes(self) -> int | None:
+        """Cap on the buffered pixel data in bytes, or ``None``."""
+        ...
+
+    def close(self) -> None:
+        """Stop capturing. Frames returned by ``dump()`` stay valid."""
+        ...
+
+    def __len__(self) -> int: ...
+    def __enter__(self) -> "ReplayBuffer": ...
+    def __exit__(self, exc_type: object, exc_val: object, exc_tb: object) -> bool: ...
+
 def screenshot(
     monitor: int = 0,
     window: str | None = None,
     pid: int | None = None,
     hwnd: int | None = None,
     index: int | None = None,
-    mode: Literal["auto", "hdr", "sdr"] = "auto",
+    mode: Literal["auto", "hdr", "sdr", "prefer_hdr"] = "auto",
     headless: bool = True,
+    border: bool = False,
+    lut: str | None = None,
 ) -> CapturedFrame:
     """One-shot capture of a monitor or window.
 
-    Creates and destroys a pipeline internally (~70 ms cold start).
+    Captures one frame on a single-use pipeline (~70 ms cold start) and
+    returns it before the capture session is torn down.
     For repeated captures, use the ``capture`` class instead.
 
     Args:
@@ -182,8 +907,55 @@ def screenshot(
         index: Ranked window index within candidate windows.
         mode: Capture mode — ``'auto'``, ``'hdr'``, or ``'sdr'``.
         headless: Crop title bar and borders for window capture.
+        border: Keep the yellow capture border visible.
+        lut: Path to a 3D ``.cube`` LUT applied to SDR output.
 
     Returns:
         A ``CapturedFrame`` that can be saved or converted to numpy.
     """
     ...
+
+def supported_formats() -> list[dict[str, str | bool]]:
+    """List save formats and whether they work on this system.
+
+    ``.jxr`` and ``.heic`` depend on installed WIC codecs and are probed once
+    per process with a tiny in-memory encode; the result is cached. If COM
+    can't be set up on the calling thread they are reported unavailable and
+    probed again on the next call.
+
+    Returns:
+        One dict per format: ``ext`` (e.g. ``"png"``), ``supports_hdr``,
+        ``supports_sdr`` and ``available``. For HEIF, ``supports_hdr`` reflects
+        whether 10-bit encoding works on this system.
+    """
+    ...
+
+def save_batch(items: list[tuple[CapturedFrame, str]]) -> list[str | None]:
+    """Save many frames in parallel.
+
+    Encodes on a small thread pool (one thread per CPU core) with the GIL
+    released; useful for saving a burst of captures at once. The format of each
+    file is chosen by its extension, as in ``CapturedFrame.save()``.
+
+    Args:
+        items: ``(frame, path)`` pairs.
+
+    Returns:
+        One entry per item, in order: ``None`` on success, otherwise the error
+        message. A failed file doesn't stop the others.
+    """
+    ...
+
+def watch_display_changes() -> None:
+    """Follow display setting changes in running captures.
+
+    Starts a background listener for ``WM_DISPLAYCHANGE`` / ``WM_SETTINGCHANGE``.
+    Afterwards every capture re-reads HDR state (``is_hdr``) and SDR white
+    level on its next call after the user changes display settings. The frame
+    format chosen at creation is kept; recreate the capture after toggling HDR
+    to switch between tone-mapped and native output. Calling it again is a no-op.
+
+    Raises:
+        RuntimeError: If the listener window can't be created.
+    """
+    ...
@@ -1,10 +1,23 @@
 // Capture engine module
 
+pub mod cadence;
+pub mod display_events;
+pub mod duplication;
+pub mod exclusion;
 pub mod policy;
 pub mod target;
 pub mod wgc;
 
 // Re-export commonly used types and functions
-pub use policy::CapturePolicy;
-pub use target::{enable_dpi_awareness, find_monitor, find_window, WindowSelector};
-pub use wgc::{init_capture, CaptureTarget, WGCCapture};
+pub use display_events::{display_generation, watch_display_changes};
+pub use duplication::DuplicationCapture;
+pub use exclusion::{exclusion_supported, CaptureExclusion};
+pub use policy::{CaptureBackend, CapturePolicy, ForegroundPolicy, MinimizedPolicy};
+pub use target::{
+    enable_dpi_awareness, find_monitor, find_monitor_at, find_monitor_by_name,
+    find_monitor_by_os_number, find_monitor_info, find_window, is_app_frame_window,
+    is_capture_excluded, is_child_window, is_foreground_on_monitor, is_foreground_window,
+    is_window_shown, monitor_device_name, monitor_dpi_scale, monitor_infos, monitor_orientation,
+    monitors, root_window, window_app_id, window_title, MonitorInfo, MonitorOrder, WindowSelector,
+};
+pub use wgc::{init_capture, CaptureTarget, SessionOptions, WGCCapture};
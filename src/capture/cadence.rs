@@ -0,0 +1,74 @@
+// Frame cadence tracking from WGC frame timestamps.
+//
+// DWM only delivers a frame when the target's content changes, so the measured
+// rate equals the display refresh rate for constantly animating content and
+// drops below it for static content. On variable-refresh (VRR) displays the
+// rate follows the content's present rate.
+
+use std::collections::VecDeque;
+
+/// Number of recent frame timestamps kept for the rolling average.
+const CADENCE_WINDOW: usize = 32;
+
+/// Rolling window of frame arrival timestamps (seconds, QPC-based).
+#[derive(Debug, Default)]
+pub struct FrameCadence {
+    timestamps: VecDeque<f64>,
+}
+
+impl FrameCadence {
+    /// Record one frame timestamp.
+    ///
+    /// Non-increasing timestamps (clock reset, duplicate delivery) restart the window.
+    pub fn record(&mut self, timestamp: f64) {
+        if self
+            .timestamps
+            .back()
+            .is_some_and(|&last| timestamp <= last)
+        {
+            self.timestamps.clear();
+        }
+        if self.timestamps.len() == CADENCE_WINDOW {
+            self.timestamps.pop_front();
+        }
+        self.timestamps.push_back(timestamp);
+    }
+
+    /// Average frames per second over the window, `None` until two frames are seen.
+    pub fn fps(&self) -> Option<f64> {
+        let (first, last) = (self.timestamps.front()?, self.timestamps.back()?);
+        let span = last - first;
+        if span <= 0.0 {
+            return None;
+        }
+        Some((self.timestamps.len() - 1) as f64 / span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_steady_cadence() {
+        let mut cadence = FrameCadence::default();
+        assert_eq!(cadence.fps(), None);
+        // 100 frames at 144 Hz; only the last CADENCE_WINDOW are kept.
+        for i in 0..100 {
+            cadence.record(10.0 + i as f64 / 144.0);
+        }
+        let fps = cadence.fps().unwrap();
+        assert!((fps - 144.0).abs() < 1e-6, "{fps}");
+    }
+
+    #[test]
+    fn test_non_increasing_timestamp_restarts_window() {
+        let mut cadence = FrameCadence::default();
+        cadence.record(1.0);
+        cadence.record(1.5);
+        cadence.record(0.5);
+        assert_eq!(cadence.fps(), None);
+        cadence.record(0.6);
+        assert!((cadence.fps().unwrap() - 10.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,120 @@
+// Display configuration change notifications
+//
+// Pipelines query HDR state and SDR white level when they are created. A
+// long-running capture that spans the user toggling HDR or moving the SDR
+// brightness slider would otherwise keep acting on the old values.
+//
+// watch_display_changes() starts a hidden top-level window on a dedicated
+// thread (message-only windows don't receive broadcasts). WM_DISPLAYCHANGE and
+// WM_SETTINGCHANGE bump a global generation counter; pipelines compare it on
+// each capture call and re-query display state when it moved.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+
+use anyhow::{anyhow, Context, Result};
+use windows::core::w;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG,
+    WINDOW_EX_STYLE, WM_DISPLAYCHANGE, WM_SETTINGCHANGE, WNDCLASSW, WS_OVERLAPPED,
+};
+
+/// Incremented on every display configuration change seen by the watcher.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Whether the watcher thread is running (or being started).
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Start listening for display configuration changes.
+///
+/// Once enabled, every pipeline re-queries HDR state and SDR white level on
+/// its next capture call after the user changes display settings. Idempotent;
+/// the watcher thread runs until the process exits.
+pub fn watch_display_changes() -> Result<()> {
+    if STARTED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("hdrcapture-display-events".into())
+        .spawn(move || {
+            let hwnd = match create_listener_window() {
+                Ok(hwnd) => {
+                    let _ = ready_tx.send(Ok(()));
+                    hwnd
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            let mut msg = MSG::default();
+            // SAFETY: standard message loop for a window owned by this thread.
+            unsafe {
+                while GetMessageW(&mut msg, Some(hwnd), 0, 0).0 > 0 {
+                    DispatchMessageW(&msg);
+                }
+            }
+        });
+
+    let started = spawned
+        .context("failed to spawn display change watcher thread")
+        .and_then(|_| {
+            ready_rx
+                .recv()
+                .map_err(|_| anyhow!("display change watcher thread exited during startup"))?
+        });
+    if started.is_err() {
+        STARTED.store(false, Ordering::Release);
+    }
+    started
+}
+
+/// Current display configuration generation.
+///
+/// Stays 0 unless [`watch_display_changes`] is enabled.
+pub fn display_generation() -> u64 {
+    GENERATION.load(Ordering::Acquire)
+}
+
+fn create_listener_window() -> Result<HWND> {
+    let class_name = w!("hdrcapture_display_events");
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(wnd_proc),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    // SAFETY: class and window name are static wide strings; the window is
+    // never shown and lives on this thread for the rest of the process.
+    unsafe {
+        // Registration only fails if the class already exists (previous failed
+        // start); CreateWindowExW reports any real problem.
+        RegisterClassW(&class);
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!(""),
+            WS_OVERLAPPED,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .context("CreateWindowExW for display change listener failed")
+    }
+}
+
+extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_DISPLAYCHANGE || msg == WM_SETTINGCHANGE {
+        GENERATION.fetch_add(1, Ordering::AcqRel);
+        log::debug!("display configuration changed (message 0x{msg:04x})");
+    }
+    // SAFETY: forwarding the unmodified message to the default handler.
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
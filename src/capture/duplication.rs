@@ -0,0 +1,265 @@
+// DXGI Desktop Duplication backend (monitor capture only)
+//
+// Fallback for systems where Windows Graphics Capture is unavailable or blocked
+// by policy. Frames are plain ID3D11Texture2D surfaces, same as WGC, so the
+// color pipeline is shared. Differences from WGC:
+// - Monitors only, and the cursor is never part of the image.
+// - With IDXGIOutput5 (Windows 10 1703+) an HDR desktop is duplicated as scRGB
+//   RGBA16F, same as WGC. Without it only BGRA8 is available, which on an HDR
+//   display is DWM's clipped SDR conversion. `Hdr` on an SDR display yields
+//   BGRA8 (DXGI does not up-convert).
+// - Rotated displays return the unrotated desktop surface.
+// - The duplication is invalidated by mode changes, UAC prompts and exclusive
+//   fullscreen apps (DXGI_ERROR_ACCESS_LOST) and is recreated on the next call,
+//   retrying while the desktop is still switching (secure desktop, mode change).
+
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use windows::core::Interface;
+use windows::Win32::Graphics::Direct3D11::{ID3D11Device, ID3D11Texture2D};
+use windows::Win32::Graphics::Dxgi::Common::{
+    DXGI_FORMAT, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R16G16B16A16_FLOAT,
+};
+use windows::Win32::Graphics::Dxgi::{
+    IDXGIOutput, IDXGIOutput1, IDXGIOutput5, IDXGIOutputDuplication, IDXGIResource,
+    DXGI_ERROR_ACCESS_LOST, DXGI_ERROR_WAIT_TIMEOUT, DXGI_OUTDUPL_FRAME_INFO,
+};
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
+
+use super::cadence::FrameCadence;
+use super::policy::CapturePolicy;
+use super::wgc::output_is_hdr;
+use crate::d3d11::{find_output, D3D11Context};
+
+/// Pause between attempts to recreate a lost duplication.
+const RECREATE_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// One desktop image acquired from the duplication.
+///
+/// The texture stays valid until the next `acquire()` or `release_frame()`.
+pub struct DuplicatedFrame {
+    pub texture: ID3D11Texture2D,
+    /// Present time (seconds), relative to system boot time (QPC)
+    pub timestamp: f64,
+}
+
+/// Desktop Duplication session for one monitor
+pub struct DuplicationCapture {
+    device: ID3D11Device,
+    output: IDXGIOutput,
+    duplication: IDXGIOutputDuplication,
+    /// Formats offered to `DuplicateOutput1`, in preference order
+    formats: Vec<DXGI_FORMAT>,
+    /// Whether the target monitor has HDR enabled (detected once at init)
+    target_hdr: bool,
+    /// A frame is acquired and must be released before the next acquire
+    holding_frame: bool,
+    /// First acquire after (re)creation returns the current desktop even if
+    /// nothing was presented since
+    first_frame: bool,
+    qpc_frequency: f64,
+    cadence: FrameCadence,
+    frames_received: usize,
+    recreates: usize,
+}
+
+impl DuplicationCapture {
+    /// Duplicate the output driving `hmonitor`.
+    ///
+    /// The D3D11 device must be on the adapter that drives the monitor.
+    pub fn new(d3d_ctx: &D3D11Context, hmonitor: HMONITOR, policy: CapturePolicy) -> Result<Self> {
+        let output = find_output(&d3d_ctx.dxgi_device, hmonitor)?.context(
+            "Desktop Duplication requires the D3D11 device on the adapter driving the monitor",
+        )?;
+        let target_hdr = output_is_hdr(&output).unwrap_or(false);
+        let formats = match (policy, target_hdr) {
+            (CapturePolicy::Sdr, _) | (CapturePolicy::Auto | CapturePolicy::PreferHdr, false) => {
+                vec![DXGI_FORMAT_B8G8R8A8_UNORM]
+            }
+            (CapturePolicy::Auto | CapturePolicy::PreferHdr, true) => {
+                vec![DXGI_FORMAT_R16G16B16A16_FLOAT]
+            }
+            (CapturePolicy::Hdr, _) => {
+                vec![DXGI_FORMAT_R16G16B16A16_FLOAT, DXGI_FORMAT_B8G8R8A8_UNORM]
+            }
+        };
+        let duplication = duplicate_output(&d3d_ctx.device, &output, &formats)?;
+
+        let mut frequency = 0i64;
+        // SAFETY: QueryPerformanceFrequency writes to a caller-provided i64.
+        unsafe { QueryPerformanceFrequency(&mut frequency)? };
+
+        Ok(Self {
+            device: d3d_ctx.device.clone(),
+            output,
+            duplication,
+            formats,
+            target_hdr,
+            holding_frame: false,
+            first_frame: true,
+            qpc_frequency: frequency as f64,
+            cadence: FrameCadence::default(),
+            frames_received: 0,
+            recreates: 0,
+        })
+    }
+
+    /// Desktop size in pixels (unrotated).
+    pub fn size(&self) -> (u32, u32) {
+        // SAFETY: GetDesc only reads duplication properties.
+        let desc = unsafe { self.duplication.GetDesc() };
+        (desc.ModeDesc.Width, desc.ModeDesc.Height)
+    }
+
+    /// Whether the target monitor has HDR enabled.
+    pub fn is_hdr(&self) -> bool {
+        self.target_hdr
+    }
+
+    /// Wait up to `timeout` for a new desktop image.
+    ///
+    /// Releases the previously acquired frame first. Returns `None` when
+    /// nothing was presented in time (static desktop). A lost duplication is
+    /// recreated and waited on again; recreation is retried until `timeout`
+    /// runs out, since it fails while the secure desktop or a mode change is
+    /// still active. Pointer-only updates are skipped.
+    pub fn acquire(&mut self, timeout: Duration) -> Result<Option<DuplicatedFrame>> {
+        self.release_frame();
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let timeout_ms = remaining.as_millis().min(u32::MAX as u128) as u32;
+            let mut info = DXGI_OUTDUPL_FRAME_INFO::default();
+            let mut resource: Option<IDXGIResource> = None;
+
+            // SAFETY: AcquireNextFrame writes to caller-provided info / resource;
+            // the previous frame was released above.
+            let acquired = unsafe {
+                self.duplication
+                    .AcquireNextFrame(timeout_ms, &mut info, &mut resource)
+            };
+            match acquired {
+                Ok(()) => self.holding_frame = true,
+                Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT => return Ok(None),
+                Err(e) if e.code() == DXGI_ERROR_ACCESS_LOST => {
+                    self.recreate_until(deadline)?;
+                    if Instant::now() >= deadline {
+                        return Ok(None);
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e).context("AcquireNextFrame failed"),
+            }
+
+            if info.LastPresentTime == 0 && !self.first_frame {
+                // Only the pointer moved; the desktop image is unchanged.
+                self.release_frame();
+                if remaining.is_zero() {
+                    return Ok(None);
+                }
+                continue;
+            }
+
+            let texture: ID3D11Texture2D = resource
+                .context("AcquireNextFrame returned no desktop resource")?
+                .cast()?;
+            let present_time = if info.LastPresentTime != 0 {
+                info.LastPresentTime
+            } else {
+                let mut now = 0i64;
+                // SAFETY: QueryPerformanceCounter writes to a caller-provided i64.
+                unsafe { QueryPerformanceCounter(&mut now)? };
+                now
+            };
+            let timestamp = present_time as f64 / self.qpc_frequency;
+
+            self.first_frame = false;
+            self.frames_received += 1;
+            self.cadence.record(timestamp);
+            return Ok(Some(DuplicatedFrame { texture, timestamp }));
+        }
+    }
+
+    /// Release the acquired frame (no-op when none is held).
+    pub fn release_frame(&mut self) {
+        if self.holding_frame {
+            // SAFETY: paired with a successful AcquireNextFrame.
+            let _ = unsafe { self.duplication.ReleaseFrame() };
+            self.holding_frame = false;
+        }
+    }
+
+    /// Rolling average rate of new desktop images (frames per second).
+    pub fn measured_fps(&self) -> Option<f64> {
+        self.cadence.fps()
+    }
+
+    /// Number of desktop images acquired.
+    pub fn frames_received(&self) -> usize {
+        self.frames_received
+    }
+
+    /// Number of times the duplication was recreated after access loss.
+    pub fn recreates(&self) -> usize {
+        self.recreates
+    }
+
+    /// Recreate the duplication, retrying until `deadline`; the last error
+    /// is returned once it passes.
+    fn recreate_until(&mut self, deadline: Instant) -> Result<()> {
+        loop {
+            match self.recreate() {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Err(e);
+                    }
+                    log::debug!("{e:#}; retrying");
+                    std::thread::sleep(RECREATE_RETRY_INTERVAL.min(remaining));
+                }
+            }
+        }
+    }
+
+    fn recreate(&mut self) -> Result<()> {
+        // The lost duplication's frame is gone; don't release it.
+        self.holding_frame = false;
+        self.duplication = duplicate_output(&self.device, &self.output, &self.formats)
+            .context("failed to recreate Desktop Duplication after access loss")?;
+        self.first_frame = true;
+        self.recreates += 1;
+        log::debug!("desktop duplication recreated after access loss");
+        Ok(())
+    }
+}
+
+impl Drop for DuplicationCapture {
+    fn drop(&mut self) {
+        self.release_frame();
+    }
+}
+
+/// Create the duplication, preferring `DuplicateOutput1` (format-aware, HDR).
+fn duplicate_output(
+    device: &ID3D11Device,
+    output: &IDXGIOutput,
+    formats: &[DXGI_FORMAT],
+) -> Result<IDXGIOutputDuplication> {
+    // SAFETY: DuplicateOutput1 / DuplicateOutput take a valid device on the
+    // output's adapter; failures (unsupported, access denied) map to Err.
+    unsafe {
+        if let Ok(output5) = output.cast::<IDXGIOutput5>() {
+            return output5
+                .DuplicateOutput1(device, 0, formats)
+                .context("DuplicateOutput1 failed");
+        }
+        output
+            .cast::<IDXGIOutput1>()?
+            .DuplicateOutput(device)
+            .context("DuplicateOutput failed")
+    }
+}
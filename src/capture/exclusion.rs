@@ -0,0 +1,122 @@
+// Hiding the caller's own windows (overlays, recording UI) from screen capture
+
+use anyhow::{bail, Context, Result};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetWindowDisplayAffinity, GetWindowThreadProcessId, IsWindow, SetWindowDisplayAffinity,
+    WDA_EXCLUDEFROMCAPTURE, WINDOW_DISPLAY_AFFINITY,
+};
+
+/// Whether this OS build removes `WDA_EXCLUDEFROMCAPTURE` windows from
+/// captures (Windows 10 2004, UniversalApiContract 10, and later).
+///
+/// Older builds treat the flag as `WDA_MONITOR`: the window is still covered,
+/// but shows up as a black rectangle instead of the content behind it.
+pub fn exclusion_supported() -> bool {
+    use windows::Foundation::Metadata::ApiInformation;
+
+    ApiInformation::IsApiContractPresentByMajor(
+        &windows::core::HSTRING::from("Windows.Foundation.UniversalApiContract"),
+        10,
+    )
+    .unwrap_or(false)
+}
+
+/// Windows of this process hidden from screen capture with
+/// `SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE)`.
+///
+/// The affinity is a window property, not a session setting: while applied,
+/// the windows are hidden from every capture (WGC, Desktop Duplication,
+/// other screen recorders), not only from one pipeline. Their previous
+/// affinity is restored when they are removed with [`set`](Self::set) and
+/// on drop.
+#[derive(Debug, Default)]
+pub struct CaptureExclusion {
+    /// Excluded windows with the affinity they had before.
+    windows: Vec<(HWND, u32)>,
+}
+
+impl CaptureExclusion {
+    /// Exclude exactly `hwnds` from capture: windows no longer listed get
+    /// their previous affinity back, newly listed ones are excluded. Windows
+    /// in both sets stay hidden throughout.
+    ///
+    /// Windows can only change their own process's affinity, so handles of
+    /// other processes are rejected before anything changes.
+    pub fn set(&mut self, hwnds: &[HWND]) -> Result<()> {
+        for &hwnd in hwnds {
+            check_own_window(hwnd)?;
+        }
+        if !hwnds.is_empty() && !exclusion_supported() {
+            log::warn!(
+                "excluding windows from capture needs Windows 10 2004 or later; \
+                 excluded windows are captured as black rectangles instead"
+            );
+        }
+
+        self.windows.retain(|&(hwnd, previous)| {
+            if hwnds.contains(&hwnd) {
+                return true;
+            }
+            restore(hwnd, previous);
+            false
+        });
+        for &hwnd in hwnds {
+            if self.windows.iter().any(|&(excluded, _)| excluded == hwnd) {
+                continue;
+            }
+            let mut previous = 0u32;
+            // SAFETY: hwnd is a valid window of this process; writes to a local u32.
+            unsafe { GetWindowDisplayAffinity(hwnd, &mut previous) }
+                .context("GetWindowDisplayAffinity failed")?;
+            // SAFETY: hwnd is a valid window of this process.
+            unsafe { SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE) }
+                .with_context(|| format!("failed to exclude window {:?} from capture", hwnd.0))?;
+            self.windows.push((hwnd, previous));
+        }
+        Ok(())
+    }
+
+    /// Windows currently excluded.
+    pub fn windows(&self) -> Vec<HWND> {
+        self.windows.iter().map(|&(hwnd, _)| hwnd).collect()
+    }
+}
+
+/// Fail unless `hwnd` is a live window owned by this process.
+fn check_own_window(hwnd: HWND) -> Result<()> {
+    // SAFETY: IsWindow accepts any handle value.
+    if !unsafe { IsWindow(Some(hwnd)).as_bool() } {
+        bail!("Invalid window handle: {:?}", hwnd.0);
+    }
+    let mut pid = 0u32;
+    // SAFETY: writes the owning process id to a local u32.
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    // SAFETY: GetCurrentProcessId has no preconditions.
+    if pid != unsafe { GetCurrentProcessId() } {
+        bail!(
+            "window {:?} belongs to process {}; only this process's own windows \
+             can be excluded from capture",
+            hwnd.0,
+            pid
+        );
+    }
+    Ok(())
+}
+
+/// Put back the affinity `hwnd` had before it was excluded (best-effort).
+fn restore(hwnd: HWND, previous: u32) {
+    // SAFETY: fails harmlessly if the window was destroyed meanwhile.
+    unsafe {
+        let _ = SetWindowDisplayAffinity(hwnd, WINDOW_DISPLAY_AFFINITY(previous));
+    }
+}
+
+impl Drop for CaptureExclusion {
+    fn drop(&mut self) {
+        for &(hwnd, previous) in &self.windows {
+            restore(hwnd, previous);
+        }
+    }
+}
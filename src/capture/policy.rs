@@ -1,24 +1,112 @@
+use std::time::Duration;
+
 /// Capture mode selected by caller.
 ///
 /// - `Auto`: HDR environment → tone-map to 8-bit SDR; SDR environment → direct 8-bit.
 /// - `Hdr`: Force 16-bit float capture, pass through raw HDR data (no tone-map).
 /// - `Sdr`: Force 8-bit capture regardless of environment.
+/// - `PreferHdr`: HDR environment → same as `Hdr`; SDR environment → same as
+///   `Sdr`. The best quality the target offers, without a 16-bit buffer of
+///   SDR content. Decided when the session starts (see [`resolve`](Self::resolve)).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CapturePolicy {
     #[default]
     Auto,
     Hdr,
     Sdr,
+    PreferHdr,
 }
 
 impl CapturePolicy {
-    /// Parse from a mode string ("auto", "hdr", "sdr").
+    /// Parse from a mode string ("auto", "hdr", "sdr", "prefer_hdr").
     pub fn from_mode(mode: &str) -> Option<Self> {
         match mode {
             "auto" => Some(Self::Auto),
             "hdr" => Some(Self::Hdr),
             "sdr" => Some(Self::Sdr),
+            "prefer_hdr" => Some(Self::PreferHdr),
             _ => None,
         }
     }
+
+    /// Concrete policy for a target with the given HDR state: `PreferHdr`
+    /// becomes `Hdr` or `Sdr`, other policies are returned unchanged.
+    pub fn resolve(self, target_hdr: bool) -> Self {
+        match self {
+            Self::PreferHdr if target_hdr => Self::Hdr,
+            Self::PreferHdr => Self::Sdr,
+            policy => policy,
+        }
+    }
+}
+
+/// What `capture()` / `grab()` do while the target window is minimized.
+///
+/// Minimized windows stop producing new content, and the surface WGC hands
+/// out meanwhile is stale or tiny.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MinimizedPolicy {
+    /// Return the last good frame, tagged with `CaptureWarning::TargetMinimized`.
+    /// Errors if nothing has been captured yet.
+    #[default]
+    ReturnCached,
+    /// Fail immediately.
+    Error,
+    /// Block until the window is restored, failing after the given timeout.
+    WaitRestore(Duration),
+}
+
+/// Whether `capture()` / `grab()` require the target to be in the foreground.
+///
+/// A window target is in the foreground when it (or its top-level window)
+/// has focus; a monitor target when the focused window is on that monitor.
+/// The focused window is on top of all but always-on-top windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForegroundPolicy {
+    /// Capture regardless of focus.
+    #[default]
+    Ignore,
+    /// Fail with `CaptureError::NotForeground`.
+    Error,
+    /// Block until the target is in the foreground, failing with
+    /// `CaptureError::NotForeground` after the given timeout.
+    Wait(Duration),
+}
+
+/// Capture API used for monitor targets.
+///
+/// Windows are always captured with WGC; Desktop Duplication only supports
+/// whole monitors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureBackend {
+    /// Windows Graphics Capture; falls back to Desktop Duplication for
+    /// monitors when WGC is unavailable (older builds, blocked by policy).
+    #[default]
+    Auto,
+    /// Windows Graphics Capture only.
+    Wgc,
+    /// DXGI Desktop Duplication (`IDXGIOutputDuplication`) only.
+    ///
+    /// The cursor is never captured, and `show_border` / `buffer_count` have
+    /// no effect.
+    DesktopDuplication,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefer_hdr_resolves_by_target() {
+        assert_eq!(CapturePolicy::PreferHdr.resolve(true), CapturePolicy::Hdr);
+        assert_eq!(CapturePolicy::PreferHdr.resolve(false), CapturePolicy::Sdr);
+        for policy in [CapturePolicy::Auto, CapturePolicy::Hdr, CapturePolicy::Sdr] {
+            assert_eq!(policy.resolve(true), policy);
+            assert_eq!(policy.resolve(false), policy);
+        }
+        assert_eq!(
+            CapturePolicy::from_mode("prefer_hdr"),
+            Some(CapturePolicy::PreferHdr)
+        );
+    }
 }
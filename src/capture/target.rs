@@ -2,20 +2,33 @@
 
 use anyhow::{bail, Context, Result};
 use std::collections::HashSet;
-use windows::core::BOOL;
-use windows::Win32::Foundation::{CloseHandle, HANDLE, HWND, LPARAM, RECT};
+use windows::core::{w, BOOL, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, ERROR_SUCCESS, HANDLE, HWND, LPARAM, POINT, RECT};
 use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::Storage::EnhancedStorage::PKEY_AppUserModel_ID;
+use windows::Win32::Storage::Packaging::Appx::GetApplicationUserModelId;
 use windows::Win32::System::Diagnostics::ToolHelp::{
     CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
 };
+use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
 use windows::Win32::UI::HiDpi::{
-    SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+    GetDpiForMonitor, SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+    MDT_EFFECTIVE_DPI,
 };
+use windows::Win32::UI::Shell::PropertiesSystem::{IPropertyStore, SHGetPropertyStoreForWindow};
 use windows::Win32::UI::WindowsAndMessaging::{
-    EnumWindows, GetWindowLongPtrW, GetWindowRect, GetWindowThreadProcessId, IsIconic, IsWindow,
-    IsWindowVisible, GWL_EXSTYLE, WS_EX_TOOLWINDOW,
+    EnumWindows, FindWindowExW, GetAncestor, GetClassNameW, GetForegroundWindow,
+    GetWindowDisplayAffinity, GetWindowLongPtrW, GetWindowRect, GetWindowTextLengthW,
+    GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, GA_ROOT,
+    GWL_EXSTYLE, GWL_STYLE, MONITORINFOF_PRIMARY, WDA_NONE, WS_CHILD, WS_EX_TOOLWINDOW,
 };
 
+use crate::capture::wgc::output_display_format;
+use crate::color::white_level::{
+    self, display_config_device_names, display_path_states, DEFAULT_SDR_WHITE_NITS,
+};
+use crate::d3d11::orient::Rotation;
+
 // ---------------------------------------------------------------------------
 // DPI
 // ---------------------------------------------------------------------------
@@ -31,13 +44,32 @@ pub fn enable_dpi_awareness() {
     }
 }
 
+/// DPI of 100% scaling.
+const BASE_DPI: f32 = 96.0;
+
+/// Display scale factor of a monitor (1.0 = 100%, 1.5 = 150%).
+///
+/// Divide physical pixel coordinates by this to get the logical coordinates
+/// DPI-unaware applications see. Returns 1.0 if the DPI can't be queried.
+pub fn monitor_dpi_scale(hmonitor: HMONITOR) -> f32 {
+    let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+    // SAFETY: out-pointers reference valid locals; an invalid monitor handle
+    // only makes the call fail.
+    match unsafe { GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) } {
+        Ok(()) if dpi_x > 0 => dpi_x as f32 / BASE_DPI,
+        _ => 1.0,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Monitor lookup
 // ---------------------------------------------------------------------------
 
 /// Find monitor by index
 ///
-/// Indices are ordered by system enumeration order, not guaranteed that `0` is the primary monitor.
+/// Indices are ordered by system enumeration order ([`MonitorOrder::Enumeration`]), not
+/// guaranteed that `0` is the primary monitor or the display Windows numbers "1"; see
+/// [`find_monitor_by_os_number`] for that numbering.
 pub fn find_monitor(index: usize) -> Result<HMONITOR> {
     let monitors = enumerate_monitors()?;
 
@@ -54,6 +86,293 @@ pub fn find_monitor(index: usize) -> Result<HMONITOR> {
     })
 }
 
+/// A monitor with the properties capture decisions usually depend on.
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+    pub handle: HMONITOR,
+    /// Index in [`find_monitor`] order.
+    pub index: usize,
+    /// GDI device name (e.g. `\\.\DISPLAY1`), for [`find_monitor_by_name`].
+    pub name: String,
+    /// Virtual-desktop bounds (`MONITORINFO.rcMonitor`), physical pixels when
+    /// per-monitor DPI awareness is enabled.
+    pub rect: RECT,
+    /// Desktop width and height in the same units as `rect`.
+    pub width: u32,
+    pub height: u32,
+    /// Whether this is the primary monitor.
+    pub primary: bool,
+    /// Whether the monitor's DXGI output is driven in HDR10, the same check
+    /// `CapturePolicy::Auto` / `PreferHdr` make when building a pipeline.
+    /// Falls back to the Windows HDR setting when no DXGI output drives it.
+    pub hdr: bool,
+    /// SDR white level in nits (80 when unknown).
+    pub sdr_white_nits: f32,
+}
+
+/// All monitors with their [`MonitorInfo`], in [`find_monitor`] order.
+///
+/// HDR state comes from the DXGI outputs, white level from a single
+/// DisplayConfig query.
+pub fn monitor_infos() -> Result<Vec<MonitorInfo>> {
+    let monitors = enumerate_monitors()?;
+    if monitors.is_empty() {
+        bail!("No monitors detected");
+    }
+    let states = display_path_states();
+    let outputs = crate::d3d11::all_outputs().unwrap_or_else(|e| {
+        log::warn!("DXGI output enumeration failed, using Windows HDR setting: {e:#}");
+        Vec::new()
+    });
+    monitors
+        .into_iter()
+        .enumerate()
+        .map(|(index, handle)| {
+            // SAFETY: GetMonitorInfoW writes to a caller-provided MONITORINFOEXW.
+            // cbSize must be set correctly before the call.
+            let info = unsafe {
+                let mut info = MONITORINFOEXW::default();
+                info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+                if !GetMonitorInfoW(handle, &mut info.monitorInfo).as_bool() {
+                    bail!("GetMonitorInfoW failed for {:?}", handle.0);
+                }
+                info
+            };
+            let state = states
+                .iter()
+                .find(|(device, _)| *device == info.szDevice)
+                .map(|&(_, state)| state);
+            let rect = info.monitorInfo.rcMonitor;
+            Ok(MonitorInfo {
+                handle,
+                index,
+                name: String::from_utf16_lossy(&info.szDevice)
+                    .trim_end_matches('\0')
+                    .to_string(),
+                rect,
+                width: (rect.right - rect.left) as u32,
+                height: (rect.bottom - rect.top) as u32,
+                primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+                hdr: outputs
+                    .iter()
+                    .find(|(monitor, _)| *monitor == handle)
+                    .and_then(|(_, output)| output_display_format(output).ok())
+                    .map_or(state.is_some_and(|s| s.hdr), |f| f.is_hdr()),
+                sdr_white_nits: state.map_or(DEFAULT_SDR_WHITE_NITS, |s| s.sdr_white_nits),
+            })
+        })
+        .collect()
+}
+
+/// Find monitor by index, with its [`MonitorInfo`].
+///
+/// Same index as [`find_monitor`]; saves separate HDR and white level
+/// queries when choosing a capture policy before building a pipeline.
+pub fn find_monitor_info(index: usize) -> Result<MonitorInfo> {
+    let mut monitors = monitor_infos()?;
+    let count = monitors.len();
+    if index >= count {
+        bail!("Monitor index {} out of range (found {})", index, count);
+    }
+    Ok(monitors.swap_remove(index))
+}
+
+/// Order of monitor indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MonitorOrder {
+    /// `EnumDisplayMonitors` order, used by [`find_monitor`] and
+    /// [`monitor_layout`]. Stable while the display setup doesn't change,
+    /// but unrelated to the numbers Windows shows.
+    #[default]
+    Enumeration,
+    /// Order of the active DisplayConfig paths, which follows the numbers
+    /// Windows gives its displays: index 0 is the monitor labelled "1" under
+    /// Settings → Display → Identify. Windows has no API for those labels, so
+    /// this is the closest public equivalent. Monitors without a DisplayConfig path (rare, e.g.
+    /// while a display is being reconfigured) follow in enumeration order.
+    OsNumber,
+}
+
+/// All monitors in `order`.
+pub fn monitors(order: MonitorOrder) -> Result<Vec<HMONITOR>> {
+    let monitors = enumerate_monitors()?;
+    if monitors.is_empty() {
+        bail!("No monitors detected");
+    }
+    match order {
+        MonitorOrder::Enumeration => Ok(monitors),
+        MonitorOrder::OsNumber => {
+            let paths = display_config_device_names().context("QueryDisplayConfig failed")?;
+            let named = monitors
+                .into_iter()
+                .map(|hmonitor| Ok((hmonitor, monitor_device_name(hmonitor)?)))
+                .collect::<Result<Vec<_>>>()?;
+            let path_names: Vec<String> = paths
+                .iter()
+                .map(|name| {
+                    String::from_utf16_lossy(name)
+                        .trim_end_matches('\0')
+                        .to_string()
+                })
+                .collect();
+            Ok(order_by_names(named, &path_names))
+        }
+    }
+}
+
+/// Find a monitor by the number Windows shows for it (1-based, as under
+/// Settings → Display → Identify).
+///
+/// Same as index `number - 1` in [`MonitorOrder::OsNumber`].
+pub fn find_monitor_by_os_number(number: usize) -> Result<HMONITOR> {
+    if number == 0 {
+        bail!("Monitor numbers start at 1");
+    }
+    let monitors = monitors(MonitorOrder::OsNumber)?;
+    monitors
+        .get(number - 1)
+        .copied()
+        .with_context(|| format!("No monitor number {} (found {})", number, monitors.len()))
+}
+
+/// Find monitor by GDI device name (e.g. `\\.\DISPLAY1`).
+///
+/// Unlike index-based lookup, device names stay stable across enumeration order changes.
+/// Matching is case-insensitive.
+pub fn find_monitor_by_name(name: &str) -> Result<HMONITOR> {
+    let monitors = enumerate_monitors()?;
+
+    if monitors.is_empty() {
+        bail!("No monitors detected");
+    }
+
+    let mut available = Vec::with_capacity(monitors.len());
+    for hmonitor in monitors {
+        let device = monitor_device_name(hmonitor)?;
+        if device.eq_ignore_ascii_case(name) {
+            return Ok(hmonitor);
+        }
+        available.push(device);
+    }
+
+    bail!(
+        "No monitor named \"{}\" (available: {})",
+        name,
+        available.join(", ")
+    )
+}
+
+/// Find the monitor containing a virtual-desktop point (physical pixels).
+///
+/// The primary monitor's top-left is `(0, 0)`; monitors left of or above it
+/// have negative coordinates. Fails when the point lies in a gap between
+/// monitors or outside the desktop.
+pub fn find_monitor_at(x: i32, y: i32) -> Result<HMONITOR> {
+    // SAFETY: MonitorFromPoint only reads the point; DEFAULTTONULL returns a
+    // null handle instead of the nearest monitor.
+    let hmonitor = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONULL) };
+    if hmonitor.is_invalid() {
+        bail!("No monitor at virtual desktop point ({}, {})", x, y);
+    }
+    Ok(hmonitor)
+}
+
+/// Get the GDI device name (e.g. `\\.\DISPLAY1`) of a monitor handle.
+pub fn monitor_device_name(hmonitor: HMONITOR) -> Result<String> {
+    let device = white_level::monitor_device_name(hmonitor)
+        .with_context(|| format!("GetMonitorInfoW failed for {:?}", hmonitor.0))?;
+    Ok(String::from_utf16_lossy(&device)
+        .trim_end_matches('\0')
+        .to_string())
+}
+
+/// Rotation of a monitor's current display mode and its desktop size.
+///
+/// The rotation (`DEVMODE.dmDisplayOrientation`) is how far the desktop is
+/// turned clockwise on the panel: frames delivered in the panel's native
+/// orientation need that rotation to read upright. The size is in desktop
+/// orientation (portrait for a 90° / 270° landscape panel).
+pub fn monitor_orientation(hmonitor: HMONITOR) -> Result<(Rotation, (u32, u32))> {
+    let device: Vec<u16> = monitor_device_name(hmonitor)?
+        .encode_utf16()
+        .chain(Some(0))
+        .collect();
+    let mut mode = DEVMODEW {
+        dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+        ..Default::default()
+    };
+    // SAFETY: device is a NUL-terminated name that outlives the call; mode is
+    // a caller-provided DEVMODEW with dmSize set.
+    unsafe {
+        if !EnumDisplaySettingsW(PCWSTR(device.as_ptr()), ENUM_CURRENT_SETTINGS, &mut mode)
+            .as_bool()
+        {
+            bail!("EnumDisplaySettingsW failed for {:?}", hmonitor.0);
+        }
+        let rotation = match mode.Anonymous1.Anonymous2.dmDisplayOrientation {
+            DMDO_90 => Rotation::Cw90,
+            DMDO_180 => Rotation::Cw180,
+            DMDO_270 => Rotation::Cw270,
+            _ => Rotation::None,
+        };
+        Ok((rotation, (mode.dmPelsWidth, mode.dmPelsHeight)))
+    }
+}
+
+/// All monitors with their virtual-desktop bounds (`MONITORINFO.rcMonitor`).
+///
+/// Order matches [`find_monitor`] indices. Coordinates are physical pixels when
+/// per-monitor DPI awareness is enabled.
+pub fn monitor_layout() -> Result<Vec<(HMONITOR, RECT)>> {
+    let monitors = enumerate_monitors()?;
+
+    if monitors.is_empty() {
+        bail!("No monitors detected");
+    }
+
+    monitors
+        .into_iter()
+        .map(|hmonitor| {
+            // SAFETY: GetMonitorInfoW writes to a caller-provided MONITORINFO.
+            // cbSize must be set correctly before the call.
+            unsafe {
+                let mut info = MONITORINFO {
+                    cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+                    ..Default::default()
+                };
+                if !GetMonitorInfoW(hmonitor, &mut info).as_bool() {
+                    bail!("GetMonitorInfoW failed for {:?}", hmonitor.0);
+                }
+                Ok((hmonitor, info.rcMonitor))
+            }
+        })
+        .collect()
+}
+
+/// Monitors that show part of a window (its root window's bounds).
+///
+/// Empty when the window is off-screen or its bounds can't be queried.
+pub fn window_monitors(hwnd: HWND) -> Vec<HMONITOR> {
+    let mut rect = RECT::default();
+    // SAFETY: GetWindowRect writes to a caller-provided RECT; an invalid
+    // window only makes the call fail.
+    if unsafe { GetWindowRect(root_window(hwnd), &mut rect) }.is_err() {
+        return Vec::new();
+    }
+    let mut monitors: Vec<HMONITOR> = Vec::new();
+    // SAFETY: same callback contract as enumerate_monitors(); the clip rect
+    // limits enumeration to monitors intersecting the window.
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            Some(HDC::default()),
+            Some(&rect),
+            Some(enum_monitor_proc),
+            LPARAM(&mut monitors as *mut _ as isize),
+        );
+    }
+    monitors
+}
+
 // --- Internal enumeration ---
 
 fn enumerate_monitors() -> Result<Vec<HMONITOR>> {
@@ -74,6 +393,24 @@ fn enumerate_monitors() -> Result<Vec<HMONITOR>> {
     }
 }
 
+/// Sort `monitors` (handle, GDI device name) into the order of `names`
+/// (case-insensitive); monitors not listed keep their relative order at the end.
+fn order_by_names<T>(monitors: Vec<(T, String)>, names: &[String]) -> Vec<T> {
+    let rank = |device: &str| {
+        names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(device))
+            .unwrap_or(names.len())
+    };
+    let mut ranked: Vec<(usize, T)> = monitors
+        .into_iter()
+        .map(|(monitor, device)| (rank(&device), monitor))
+        .collect();
+    // Stable: unlisted monitors stay in enumeration order.
+    ranked.sort_by_key(|&(rank, _)| rank);
+    ranked.into_iter().map(|(_, monitor)| monitor).collect()
+}
+
 unsafe extern "system" fn enum_monitor_proc(
     hmonitor: HMONITOR,
     _: HDC,
@@ -97,6 +434,9 @@ pub enum WindowSelector {
     Hwnd(HWND),
     Pid(u32),
     Process(String),
+    /// Packaged (UWP / Store) app by Application User Model ID, e.g.
+    /// `"Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"`.
+    AppUserModelId(String),
 }
 
 /// Find window by unified selector + optional ranked index.
@@ -104,6 +444,11 @@ pub enum WindowSelector {
 /// Routing:
 /// - `Hwnd`: validate and return directly.
 /// - `Pid`/`Process`: enumerate candidate windows, rank heuristically, then pick by index.
+/// - `AppUserModelId`: same, over the visible top-level windows showing that app,
+///   including the `ApplicationFrameHost` frames that host UWP apps.
+///
+/// Selecting a UWP app by process or handle logs a warning, since the app's
+/// own top-level window is usually not the visual that is on screen.
 ///
 /// # Examples
 /// ```no_run
@@ -112,15 +457,25 @@ pub enum WindowSelector {
 /// let hwnd = find_window(WindowSelector::Process("notepad.exe".to_string()), Some(1)).unwrap();
 /// ```
 pub fn find_window(selector: WindowSelector, index: Option<usize>) -> Result<HWND> {
-    match selector {
-        WindowSelector::Hwnd(hwnd) => validate_window(hwnd),
+    let hwnd = match selector {
+        WindowSelector::AppUserModelId(app_id) => {
+            let windows = enumerate_app_windows(&app_id)?;
+            if windows.is_empty() {
+                bail!("No window found for app \"{}\"", app_id);
+            }
+            return pick_window(&windows, index).with_context(|| {
+                let idx = index.unwrap_or(0);
+                format!("Window index {} out of range for app \"{}\"", idx, app_id)
+            });
+        }
+        WindowSelector::Hwnd(hwnd) => validate_window(hwnd)?,
         WindowSelector::Pid(pid) => {
             let mut pids = HashSet::new();
             pids.insert(pid);
             pick_ranked_window(&pids, index).with_context(|| {
                 let idx = index.unwrap_or(0);
                 format!("Window index {} out of range for pid {}", idx, pid)
-            })
+            })?
         }
         WindowSelector::Process(process) => {
             let pids = get_pids(&process)?;
@@ -133,9 +488,11 @@ pub fn find_window(selector: WindowSelector, index: Option<usize>) -> Result<HWN
                     "Window index {} out of range for process \"{}\"",
                     idx, process
                 )
-            })
+            })?
         }
-    }
+    };
+    warn_if_packaged(hwnd);
+    Ok(hwnd)
 }
 
 /// Validate and normalize an HWND.
@@ -147,6 +504,78 @@ pub fn validate_window(hwnd: HWND) -> Result<HWND> {
     Ok(hwnd)
 }
 
+/// Whether `hwnd` is a child window (`WS_CHILD`), e.g. a control or embedded panel.
+///
+/// WGC only captures top-level windows; children are captured through their
+/// root window and cropped to the child's client area.
+pub fn is_child_window(hwnd: HWND) -> bool {
+    // SAFETY: GetWindowLongPtrW only reads window state; invalid HWND returns 0.
+    let style = unsafe { GetWindowLongPtrW(hwnd, GWL_STYLE) } as u32;
+    (style & WS_CHILD.0) != 0
+}
+
+/// Whether the top-level window containing `hwnd` opted out of capture with
+/// `SetWindowDisplayAffinity` (`WDA_MONITOR` / `WDA_EXCLUDEFROMCAPTURE`).
+///
+/// WGC delivers such windows as black (or not at all).
+pub fn is_capture_excluded(hwnd: HWND) -> bool {
+    let mut affinity = 0u32;
+    // SAFETY: writes to a local u32; fails for invalid handles.
+    let queried = unsafe { GetWindowDisplayAffinity(root_window(hwnd), &mut affinity) };
+    queried.is_ok() && affinity != WDA_NONE.0
+}
+
+/// Whether the top-level window containing `hwnd` is visible and not minimized.
+pub fn is_window_shown(hwnd: HWND) -> bool {
+    let root = root_window(hwnd);
+    // SAFETY: both calls only read window state; invalid handles return false.
+    unsafe { IsWindowVisible(root).as_bool() && !IsIconic(root).as_bool() }
+}
+
+/// Whether the top-level window containing `hwnd` is the foreground window.
+pub fn is_foreground_window(hwnd: HWND) -> bool {
+    // SAFETY: only reads the foreground window; returns null when none has focus.
+    let foreground = unsafe { GetForegroundWindow() };
+    !foreground.is_invalid() && root_window(foreground) == root_window(hwnd)
+}
+
+/// Whether the foreground window is (mostly) on `hmonitor`.
+pub fn is_foreground_on_monitor(hmonitor: HMONITOR) -> bool {
+    // SAFETY: only reads window state; MonitorFromWindow returns null for a
+    // null window with MONITOR_DEFAULTTONULL.
+    unsafe {
+        let foreground = GetForegroundWindow();
+        !foreground.is_invalid() && MonitorFromWindow(foreground, MONITOR_DEFAULTTONULL) == hmonitor
+    }
+}
+
+/// Title of the top-level window containing `hwnd`; `None` when it has none.
+pub fn window_title(hwnd: HWND) -> Option<String> {
+    let root = root_window(hwnd);
+    // SAFETY: GetWindowTextW writes at most `buf.len()` UTF-16 units,
+    // including the terminator; invalid handles return 0.
+    unsafe {
+        let len = GetWindowTextLengthW(root);
+        if len <= 0 {
+            return None;
+        }
+        let mut buf = vec![0u16; len as usize + 1];
+        let copied = GetWindowTextW(root, &mut buf);
+        (copied > 0).then(|| String::from_utf16_lossy(&buf[..copied as usize]))
+    }
+}
+
+/// Top-level window containing `hwnd` (`hwnd` itself for top-level windows).
+pub fn root_window(hwnd: HWND) -> HWND {
+    // SAFETY: GetAncestor only reads the window tree; returns null on failure.
+    let root = unsafe { GetAncestor(hwnd, GA_ROOT) };
+    if root.is_invalid() {
+        hwnd
+    } else {
+        root
+    }
+}
+
 fn pick_ranked_window(pids: &HashSet<u32>, index: Option<usize>) -> Result<HWND> {
     let windows = enumerate_windows(pids)?;
     if windows.is_empty() {
@@ -254,6 +683,17 @@ unsafe extern "system" fn enum_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
     GetWindowThreadProcessId(hwnd, Some(&mut pid));
 
     if pid != 0 && ctx.pids.contains(&pid) {
+        ctx.candidates.push(rank_window(hwnd));
+    }
+
+    BOOL(1)
+}
+
+/// Heuristic score of a candidate window: visible, non-tool, non-minimized
+/// and larger windows rank higher.
+fn rank_window(hwnd: HWND) -> WindowCandidate {
+    // SAFETY: these calls only read window state; an invalid HWND yields defaults.
+    unsafe {
         let visible = IsWindowVisible(hwnd).as_bool();
         let minimized = IsIconic(hwnd).as_bool();
         let exstyle = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32;
@@ -280,12 +720,157 @@ unsafe extern "system" fn enum_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
         }
         score += (area / 10_000).min(5_000);
 
-        ctx.candidates.push(WindowCandidate { hwnd, score, area });
+        WindowCandidate { hwnd, score, area }
+    }
+}
+
+// --- Packaged (UWP) apps ---
+//
+// A UWP app's content is composed into a frame window owned by
+// ApplicationFrameHost.exe (class `ApplicationFrameWindow`), which also draws
+// the title bar. The app process itself owns a `Windows.UI.Core.CoreWindow`
+// that is reparented into that frame while shown; captured on its own it is
+// usually blank or stale.
+
+const APP_FRAME_CLASS: &str = "ApplicationFrameWindow";
+const CORE_WINDOW_CLASS: &str = "Windows.UI.Core.CoreWindow";
+/// [`CORE_WINDOW_CLASS`] as a wide string for window lookups.
+const CORE_WINDOW_CLASS_W: PCWSTR = w!("Windows.UI.Core.CoreWindow");
+
+/// Maximum AUMID length including the terminator (`APPLICATION_USER_MODEL_ID_MAX_LENGTH`).
+const APP_USER_MODEL_ID_MAX_LENGTH: usize = 130;
+
+/// Window class name, empty if it can't be queried.
+fn window_class(hwnd: HWND) -> String {
+    let mut buf = [0u16; 256];
+    // SAFETY: GetClassNameW writes at most buf.len() UTF-16 units.
+    let len = unsafe { GetClassNameW(hwnd, &mut buf) };
+    String::from_utf16_lossy(&buf[..len.max(0) as usize])
+}
+
+/// Whether `hwnd` is an `ApplicationFrameHost` frame hosting a UWP app.
+pub fn is_app_frame_window(hwnd: HWND) -> bool {
+    window_class(hwnd) == APP_FRAME_CLASS
+}
+
+/// Application User Model ID of the app shown in `hwnd`, if it is packaged.
+///
+/// Uses the window's own AUMID property when set, else the AUMID of the
+/// hosted UWP app (for frame windows) or of the owning process.
+pub fn window_app_id(hwnd: HWND) -> Option<String> {
+    if let Some(id) = window_app_id_property(hwnd) {
+        return Some(id);
+    }
+    let owner = if is_app_frame_window(hwnd) {
+        // SAFETY: FindWindowExW only reads the window tree.
+        unsafe { FindWindowExW(Some(hwnd), None, CORE_WINDOW_CLASS_W, PCWSTR::null()) }.ok()?
+    } else {
+        hwnd
+    };
+    let mut pid = 0u32;
+    // SAFETY: pid is a valid out-pointer; an invalid HWND leaves it 0.
+    unsafe { GetWindowThreadProcessId(owner, Some(&mut pid)) };
+    process_app_id(pid)
+}
+
+/// `System.AppUserModel.ID` from the window's property store.
+fn window_app_id_property(hwnd: HWND) -> Option<String> {
+    // SAFETY: SHGetPropertyStoreForWindow returns an owned COM interface;
+    // GetValue copies the property into an owned PROPVARIANT.
+    let value = unsafe {
+        let store: IPropertyStore = SHGetPropertyStoreForWindow(hwnd).ok()?;
+        store.GetValue(&PKEY_AppUserModel_ID).ok()?
+    };
+    let id = value.to_string();
+    (!id.is_empty()).then_some(id)
+}
+
+/// AUMID of a packaged process; `None` for regular desktop processes.
+fn process_app_id(pid: u32) -> Option<String> {
+    if pid == 0 {
+        return None;
+    }
+    // SAFETY: OpenProcess returns an owned handle, closed below; the AUMID
+    // buffer length is passed in `len`.
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; APP_USER_MODEL_ID_MAX_LENGTH];
+        let mut len = buf.len() as u32;
+        let status = GetApplicationUserModelId(process, &mut len, Some(PWSTR(buf.as_mut_ptr())));
+        let _ = CloseHandle(process);
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+        // `len` includes the terminator.
+        Some(String::from_utf16_lossy(
+            &buf[..len.saturating_sub(1) as usize],
+        ))
+    }
+}
+
+/// Top-level windows showing the app `app_id` (case-insensitive), ranked.
+///
+/// Only visible windows are candidates: hidden top-level windows outnumber
+/// them many times over, and each AUMID lookup opens a property store.
+fn enumerate_app_windows(app_id: &str) -> Result<Vec<HWND>> {
+    let mut all = Vec::new();
+    // SAFETY: lparam points to `all`, alive for the synchronous EnumWindows call.
+    unsafe {
+        EnumWindows(
+            Some(collect_window_proc),
+            LPARAM(&mut all as *mut _ as isize),
+        )
+        .context("EnumWindows failed")?;
     }
 
+    let mut ranked: Vec<WindowCandidate> = all
+        .into_iter()
+        // SAFETY: IsWindowVisible only reads window state.
+        .filter(|&hwnd| unsafe { IsWindowVisible(hwnd) }.as_bool())
+        .filter(|&hwnd| window_app_id(hwnd).is_some_and(|id| id.eq_ignore_ascii_case(app_id)))
+        .map(rank_window)
+        .collect();
+    ranked.sort_by(compare_candidate);
+    Ok(ranked.into_iter().map(|c| c.hwnd).collect())
+}
+
+unsafe extern "system" fn collect_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    // SAFETY: lparam points to a Vec<HWND> in enumerate_app_windows(), same
+    // lifetime and single-thread guarantees as enum_monitor_proc.
+    let windows = &mut *(lparam.0 as *mut Vec<HWND>);
+    windows.push(hwnd);
     BOOL(1)
 }
 
+/// Warn when a window picked by process, pid or handle belongs to a UWP app.
+fn warn_if_packaged(hwnd: HWND) {
+    let class = window_class(hwnd);
+    if class == APP_FRAME_CLASS {
+        log::warn!(
+            "window {:?} is an ApplicationFrameHost frame: the capture includes its title bar{}",
+            hwnd.0,
+            app_id_hint(hwnd)
+        );
+    } else if class == CORE_WINDOW_CLASS && !is_child_window(hwnd) {
+        log::warn!(
+            "window {:?} is the core window of a UWP app, which is shown inside an \
+             ApplicationFrameHost frame; capturing it directly may give a blank or stale frame{}",
+            hwnd.0,
+            app_id_hint(hwnd)
+        );
+    }
+}
+
+fn app_id_hint(hwnd: HWND) -> String {
+    match window_app_id(hwnd) {
+        Some(id) => format!(
+            " (select the app with WindowSelector::AppUserModelId(\"{}\"))",
+            id
+        ),
+        None => String::new(),
+    }
+}
+
 /// Compare two candidates for stable priority ordering.
 ///
 /// Order keys:
@@ -332,12 +917,73 @@ mod tests {
         assert!(!hmonitor.0.is_null(), "Monitor handle should be valid");
     }
 
+    #[test]
+    fn test_os_number_order_covers_all_monitors() {
+        enable_dpi_awareness();
+        let mut enumerated = monitors(MonitorOrder::Enumeration).unwrap();
+        let mut numbered = monitors(MonitorOrder::OsNumber).unwrap();
+        assert_eq!(find_monitor_by_os_number(1).unwrap(), numbered[0]);
+        assert!(find_monitor_by_os_number(0).is_err());
+        enumerated.sort_by_key(|m| m.0 as usize);
+        numbered.sort_by_key(|m| m.0 as usize);
+        assert_eq!(enumerated, numbered);
+    }
+
+    #[test]
+    fn test_find_monitor_info_matches_find_monitor() {
+        enable_dpi_awareness();
+        let info = find_monitor_info(0).unwrap();
+        assert_eq!(info.handle, find_monitor(0).unwrap());
+        assert_eq!(info.name, monitor_device_name(info.handle).unwrap());
+        assert!(info.width > 0 && info.height > 0);
+        assert!(info.sdr_white_nits > 0.0);
+
+        let all = monitor_infos().unwrap();
+        assert_eq!(all.iter().filter(|m| m.primary).count(), 1);
+        assert!(find_monitor_info(all.len()).is_err());
+    }
+
+    #[test]
+    fn test_order_by_names() {
+        let monitors = ["\\\\.\\DISPLAY1", "\\\\.\\DISPLAY2", "\\\\.\\DISPLAY3"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (i, name.to_string()))
+            .collect();
+        let paths = ["\\\\.\\display3", "\\\\.\\DISPLAY1"].map(String::from);
+        // DISPLAY2 has no path and goes last.
+        assert_eq!(order_by_names(monitors, &paths), [2, 0, 1]);
+    }
+
     #[test]
     fn test_find_monitor_out_of_range() {
         let result = find_monitor(999);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_find_monitor_by_name_roundtrip() {
+        enable_dpi_awareness();
+        let hmonitor = find_monitor(0).unwrap();
+        let name = monitor_device_name(hmonitor).unwrap();
+        assert!(
+            name.starts_with("\\\\.\\"),
+            "Unexpected device name: {}",
+            name
+        );
+        assert_eq!(find_monitor_by_name(&name).unwrap(), hmonitor);
+        assert_eq!(
+            find_monitor_by_name(&name.to_lowercase()).unwrap(),
+            hmonitor
+        );
+    }
+
+    #[test]
+    fn test_find_monitor_by_name_unknown() {
+        let result = find_monitor_by_name("\\\\.\\DISPLAY999");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_find_window_not_found() {
         let result = find_window(
@@ -356,4 +1002,40 @@ mod tests {
         );
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_find_window_unknown_app_id() {
+        let result = find_window(
+            WindowSelector::AppUserModelId("__Nonexistent.App_12345!App".to_string()),
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_core_window_class_names_match() {
+        // SAFETY: w! yields a static NUL-terminated string.
+        let wide = unsafe { CORE_WINDOW_CLASS_W.to_string() }.unwrap();
+        assert_eq!(wide, CORE_WINDOW_CLASS);
+    }
+
+    #[test]
+    fn test_child_window_root() {
+        use windows::core::{w, PCWSTR};
+        use windows::Win32::UI::WindowsAndMessaging::{FindWindowExW, FindWindowW};
+
+        // Taskbar (explorer.exe) and its notification-area child control.
+        let Ok(taskbar) = (unsafe { FindWindowW(w!("Shell_TrayWnd"), PCWSTR::null()) }) else {
+            println!("SKIPPED: no taskbar window");
+            return;
+        };
+        let child =
+            unsafe { FindWindowExW(Some(taskbar), None, w!("TrayNotifyWnd"), PCWSTR::null()) }
+                .expect("taskbar has no TrayNotifyWnd child");
+
+        assert!(!is_child_window(taskbar));
+        assert!(is_child_window(child));
+        assert_eq!(root_window(child), taskbar);
+        assert_eq!(root_window(taskbar), taskbar);
+    }
 }
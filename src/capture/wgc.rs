@@ -4,8 +4,10 @@
 // Uses FrameArrived event + WaitForSingleObject for zero-latency frame waiting.
 
 use anyhow::{bail, Context, Result};
+use std::cell::{Cell, RefCell};
+use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use windows::core::Interface;
 use windows::Foundation::TypedEventHandler;
 use windows::Graphics::Capture::{
@@ -17,16 +19,18 @@ use windows::Graphics::SizeInt32;
 use windows::Win32::Foundation::{CloseHandle, HANDLE, HWND, POINT, RECT};
 use windows::Win32::Graphics::Direct3D11::{ID3D11Texture2D, D3D11_BOX};
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
-use windows::Win32::Graphics::Dxgi::Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
-use windows::Win32::Graphics::Dxgi::IDXGIOutput6;
+use windows::Win32::Graphics::Dxgi::{IDXGIOutput, IDXGIOutput6};
 use windows::Win32::Graphics::Gdi::{MonitorFromWindow, HMONITOR, MONITOR_DEFAULTTONEAREST};
-use windows::Win32::System::Threading::{CreateEventW, SetEvent, WaitForSingleObject};
+use windows::Win32::System::Threading::{CreateEventW, ResetEvent, SetEvent, WaitForSingleObject};
 use windows::Win32::System::WinRT::Direct3D11::IDirect3DDxgiInterfaceAccess;
 use windows::Win32::System::WinRT::Graphics::Capture::IGraphicsCaptureItemInterop;
 use windows::Win32::UI::WindowsAndMessaging::{GetClientRect, IsIconic};
 
+use super::cadence::FrameCadence;
 use super::policy::CapturePolicy;
-use crate::d3d11::D3D11Context;
+use super::target::{root_window, window_monitors};
+use crate::color::{ColorPixelFormat, DisplayColorSpace, DisplayFormat};
+use crate::d3d11::{find_output, D3D11Context};
 
 // ---------------------------------------------------------------------------
 // Public types
@@ -37,10 +41,66 @@ use crate::d3d11::D3D11Context;
 pub enum CaptureTarget {
     /// Monitor capture
     Monitor(HMONITOR),
-    /// Window capture
+    /// Window capture. Child windows (`WS_CHILD`) are captured through their
+    /// root window and cropped to their client area.
     Window(HWND),
 }
 
+/// WGC session settings applied once in `init_capture`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionOptions {
+    /// Keep the yellow capture border visible (default: false).
+    ///
+    /// Some compliance / recording scenarios require a visible capture indicator.
+    pub show_border: bool,
+    /// Include the mouse cursor in captured frames (default: true).
+    pub cursor: bool,
+    /// Capture a window together with its owned popups: menus, tooltips,
+    /// detached toolbars (default: false). Window capture only.
+    ///
+    /// Requires Windows 11 (`IncludeSecondaryWindows`); older builds never
+    /// include popups.
+    pub include_popups: bool,
+    /// Number of buffers in the WGC frame pool (default: 2, max: 8).
+    ///
+    /// DWM drops new frames while every buffer is held by the consumer. More
+    /// buffers absorb bursts from a slow consumer without drops, at the cost
+    /// of one surface of VRAM each and a deeper backlog for `grab()` to drain.
+    pub buffer_count: u32,
+    /// Request HDR surfaces as 10-bit HDR10 (R10G10B10A2: BT.2020, PQ)
+    /// instead of RGBA16F scRGB (default: false).
+    ///
+    /// Halves the size of HDR frames. Only affects the formats chosen for HDR
+    /// capture; BGRA8 surfaces stay BGRA8.
+    pub hdr10: bool,
+    /// Frame pool format to use instead of the one the policy picks
+    /// (default: `None`).
+    ///
+    /// The policy normally derives the surface format from the target's HDR
+    /// state; forcing one decouples the two, e.g. RGBA16F surfaces on an SDR
+    /// monitor. Takes precedence over `hdr10`.
+    pub force_format: Option<ColorPixelFormat>,
+}
+
+/// Default WGC frame pool buffer count.
+pub const DEFAULT_FRAME_BUFFERS: u32 = 2;
+
+/// Upper bound on the WGC frame pool buffer count.
+pub const MAX_FRAME_BUFFERS: u32 = 8;
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            show_border: false,
+            cursor: true,
+            include_popups: false,
+            buffer_count: DEFAULT_FRAME_BUFFERS,
+            hdr10: false,
+            force_format: None,
+        }
+    }
+}
+
 /// Window geometry queried once per frame.
 ///
 /// Contains both the frame bounds size (for resize detection) and the
@@ -59,14 +119,23 @@ pub struct WindowGeometry {
 // WGC capture session
 // ---------------------------------------------------------------------------
 
+/// User callback set with [`WGCCapture::on_frame`].
+type FrameCallback = Arc<Mutex<dyn FnMut(Direct3D11CaptureFrame) + Send>>;
+
 /// WGC capture session
 pub struct WGCCapture {
     /// Holds ownership, stops capture on drop
     _item: GraphicsCaptureItem,
     frame_pool: Direct3D11CaptureFramePool,
     session: GraphicsCaptureSession,
-    /// FrameArrived callback token (for unregistering on drop)
-    frame_arrived_token: i64,
+    /// FrameArrived callback token (for unregistering on drop); `None` while
+    /// paused
+    frame_arrived_token: Option<i64>,
+    /// FrameArrived token of the user callback set with `on_frame()`
+    callback_token: Option<i64>,
+    /// The `on_frame()` callback, locked while it runs; `close()` takes the
+    /// lock to wait for a running call
+    callback: Option<FrameCallback>,
     /// FrameArrived signal event (kernel object, for WaitForSingleObject)
     frame_event: HANDLE,
     /// Indicates teardown has started (callback should stop signaling)
@@ -76,11 +145,24 @@ pub struct WGCCapture {
     pool_height: u32,
     /// Whether the target monitor has HDR enabled (detected once at init)
     target_hdr: bool,
-    /// Window handle for client area cropping (None for monitor capture)
+    /// Captured top-level window (None for monitor capture)
     window_handle: Option<HWND>,
+    /// Window whose client area is the crop region: the target itself, or a
+    /// child window captured through its root
+    client_handle: Option<HWND>,
     /// Stored for frame pool Recreate()
     direct3d_device: windows::Graphics::DirectX::Direct3D11::IDirect3DDevice,
     pixel_format: DirectXPixelFormat,
+    /// Frame pool buffer count, reused on Recreate()
+    buffer_count: i32,
+    /// Arrival timestamps of frames taken from the pool
+    cadence: RefCell<FrameCadence>,
+    /// Frames taken from the pool
+    frames_received: Cell<usize>,
+    /// Frames discarded unread by `flush_frames()`
+    frames_flushed: Cell<usize>,
+    /// Frame pool recreations
+    pool_recreates: usize,
 }
 
 impl WGCCapture {
@@ -90,6 +172,74 @@ impl WGCCapture {
         Ok(())
     }
 
+    /// Stop signaling frame arrivals without tearing the session down
+    ///
+    /// Detaches the FrameArrived handler, so `wait_for_frame()` times out and
+    /// no frame is processed. The session stays alive: DWM keeps filling the
+    /// pool until every buffer holds a frame, then drops new ones, which
+    /// costs next to nothing. Callbacks set with `on_frame()` keep running.
+    pub fn pause(&mut self) -> Result<()> {
+        if let Some(token) = self.frame_arrived_token.take() {
+            self.frame_pool.RemoveFrameArrived(token)?;
+        }
+        Ok(())
+    }
+
+    /// Stop the session and release the frame pool's surfaces
+    ///
+    /// Detaches all FrameArrived handlers, then closes session and pool so
+    /// DWM stops capturing now rather than when the last COM reference goes
+    /// (a frame still held by a caller keeps its surface). Waits for an
+    /// `on_frame()` callback still running on a pool thread; none starts
+    /// afterwards. Frames still queued count as flushed (see
+    /// [`frames_flushed`](Self::frames_flushed)). The capture can't be
+    /// restarted afterwards. Called by
+    /// `Drop`; calling it again is a no-op.
+    pub fn close(&mut self) {
+        if self.shutting_down.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        if let Some(token) = self.frame_arrived_token.take() {
+            let _ = self.frame_pool.RemoveFrameArrived(token);
+        }
+        if let Some(token) = self.callback_token.take() {
+            let _ = self.frame_pool.RemoveFrameArrived(token);
+        }
+        // A running callback holds the lock; later ones see `shutting_down`
+        // under it and return without calling.
+        if let Some(callback) = self.callback.take() {
+            drop(callback.lock());
+        }
+        self.flush_frames();
+        let _ = self.session.Close();
+        let _ = self.frame_pool.Close();
+    }
+
+    /// Undo [`pause`](Self::pause)
+    ///
+    /// Discards the frames queued while paused, so the next frame taken from
+    /// the pool was rendered after this call.
+    pub fn resume(&mut self) -> Result<()> {
+        if self.frame_arrived_token.is_some() {
+            return Ok(());
+        }
+        self.flush_frames();
+        // SAFETY: frame_event is created in init_capture, lifetime covers entire WGCCapture
+        unsafe {
+            ResetEvent(self.frame_event)?;
+        }
+        self.frame_arrived_token = Some(
+            self.frame_pool
+                .FrameArrived(&signal_frame_event(self.frame_event, &self.shutting_down))?,
+        );
+        Ok(())
+    }
+
+    /// Whether [`pause`](Self::pause) is in effect
+    pub fn is_paused(&self) -> bool {
+        self.frame_arrived_token.is_none()
+    }
+
     /// Current pool size (may change after resize detection)
     pub fn pool_size(&self) -> (u32, u32) {
         (self.pool_width, self.pool_height)
@@ -100,6 +250,13 @@ impl WGCCapture {
         self.window_handle.is_some()
     }
 
+    /// Returns true when the target window is minimized (always false for monitors).
+    pub fn is_minimized(&self) -> bool {
+        // SAFETY: IsIconic only reads window state; a stale HWND returns false.
+        self.window_handle
+            .is_some_and(|hwnd| unsafe { IsIconic(hwnd).as_bool() })
+    }
+
     /// Query all window geometry needed per frame in a single pass.
     ///
     /// Returns frame bounds size (for resize detection) and client area crop box
@@ -138,9 +295,11 @@ impl WGCCapture {
                 return None;
             }
 
-            // Client rect + screen offset — for crop box calculation
+            // Client rect + screen offset — for crop box calculation.
+            // For child targets this is the child's client area inside the root.
+            let client_hwnd = self.client_handle.unwrap_or(hwnd);
             let mut client_rect = RECT::default();
-            if GetClientRect(hwnd, &mut client_rect).is_err() {
+            if GetClientRect(client_hwnd, &mut client_rect).is_err() {
                 return Some(WindowGeometry {
                     frame_width,
                     frame_height,
@@ -162,7 +321,9 @@ impl WGCCapture {
             }
 
             let mut upper_left = POINT { x: 0, y: 0 };
-            if !windows::Win32::Graphics::Gdi::ClientToScreen(hwnd, &mut upper_left).as_bool() {
+            if !windows::Win32::Graphics::Gdi::ClientToScreen(client_hwnd, &mut upper_left)
+                .as_bool()
+            {
                 return Some(WindowGeometry {
                     frame_width,
                     frame_height,
@@ -232,13 +393,25 @@ impl WGCCapture {
             Height: height as i32,
         };
 
-        self.frame_pool
-            .Recreate(&self.direct3d_device, self.pixel_format, 2, new_size)?;
+        self.frame_pool.Recreate(
+            &self.direct3d_device,
+            self.pixel_format,
+            self.buffer_count,
+            new_size,
+        )?;
+        log::debug!(
+            "frame pool recreated: {}x{} -> {}x{}",
+            self.pool_width,
+            self.pool_height,
+            width,
+            height
+        );
         self.pool_width = width;
         self.pool_height = height;
+        self.pool_recreates += 1;
 
         // Drain stale frames from pre-recreate generation.
-        while self.frame_pool.TryGetNextFrame().is_ok() {}
+        self.flush_frames();
 
         Ok(true)
     }
@@ -253,7 +426,103 @@ impl WGCCapture {
     /// Returns the raw `Direct3D11CaptureFrame`, caller controls its lifetime.
     /// Must complete access to the underlying surface (e.g., CopyResource) before frame is dropped.
     pub fn try_get_next_frame(&self) -> Result<Direct3D11CaptureFrame> {
-        Ok(self.frame_pool.TryGetNextFrame()?)
+        let frame = self.frame_pool.TryGetNextFrame()?;
+        self.frames_received.set(self.frames_received.get() + 1);
+        if let Ok(time) = frame.SystemRelativeTime() {
+            self.cadence
+                .borrow_mut()
+                .record(time.Duration as f64 / 10_000_000.0);
+        }
+        Ok(frame)
+    }
+
+    /// Rolling average rate at which DWM delivers frames (frames per second).
+    ///
+    /// Counts every frame taken from the pool, including ones drained and
+    /// discarded by `capture()` / `grab()`. `None` until two frames arrived.
+    pub fn measured_fps(&self) -> Option<f64> {
+        self.cadence.borrow().fps()
+    }
+
+    /// Number of frames taken with `try_get_next_frame()` or discarded by a
+    /// flush.
+    pub fn frames_received(&self) -> usize {
+        self.frames_received.get()
+    }
+
+    /// Number of queued frames discarded unread on resume, frame pool
+    /// recreation and close.
+    pub fn frames_flushed(&self) -> usize {
+        self.frames_flushed.get()
+    }
+
+    /// Discard every queued frame, counting them as received and flushed.
+    fn flush_frames(&self) {
+        while self.frame_pool.TryGetNextFrame().is_ok() {
+            self.frames_received.set(self.frames_received.get() + 1);
+            self.frames_flushed.set(self.frames_flushed.get() + 1);
+        }
+    }
+
+    /// Number of frame pool recreations after resize.
+    pub fn pool_recreates(&self) -> usize {
+        self.pool_recreates
+    }
+
+    /// Run `callback` for every frame as it arrives, instead of polling.
+    ///
+    /// The frame pool is free-threaded, so the callback runs on a WGC
+    /// thread-pool thread, not the thread that owns this capture. It receives
+    /// each frame taken from the pool and must finish with the surface (e.g.
+    /// `CopyResource`) before returning. Keep it fast and non-blocking: while
+    /// it runs, the frame holds a pool buffer and DWM drops new frames once
+    /// all buffers are held. A panic is caught and logged at `error` (see
+    /// `catch_callback_panic`); the callback is not invoked again after one.
+    ///
+    /// Frames handed to the callback are no longer available to
+    /// `try_get_next_frame()`, so don't mix the two. Replaces any previous
+    /// callback. [`close`](Self::close) and `Drop` wait for a running call to
+    /// return, so the callback must not wait on the thread dropping the
+    /// capture; it is never invoked after they return.
+    pub fn on_frame<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut(Direct3D11CaptureFrame) + Send + 'static,
+    {
+        let callback: FrameCallback = Arc::new(Mutex::new(callback));
+        let shared = Arc::clone(&callback);
+        let shutting_down = Arc::clone(&self.shutting_down);
+        let handler =
+            TypedEventHandler::<Direct3D11CaptureFramePool, windows::core::IInspectable>::new(
+                move |pool, _| {
+                    catch_callback_panic("on_frame callback", || {
+                        let Some(pool) = pool.as_ref() else {
+                            return Ok(());
+                        };
+                        // One FrameArrived can cover several queued frames. A
+                        // panic poisons the mutex, which skips the callback from
+                        // then on.
+                        while !shutting_down.load(Ordering::Relaxed) {
+                            let Ok(frame) = pool.TryGetNextFrame() else {
+                                break;
+                            };
+                            if let Ok(mut callback) = shared.lock() {
+                                if shutting_down.load(Ordering::Relaxed) {
+                                    break;
+                                }
+                                callback(frame);
+                            }
+                        }
+                        Ok(())
+                    })
+                },
+            );
+
+        if let Some(token) = self.callback_token.take() {
+            self.frame_pool.RemoveFrameArrived(token)?;
+        }
+        self.callback_token = Some(self.frame_pool.FrameArrived(&handler)?);
+        self.callback = Some(callback);
+        Ok(())
     }
 
     /// Wait for next frame arrival (blocking, with timeout)
@@ -295,9 +564,7 @@ impl WGCCapture {
 
 impl Drop for WGCCapture {
     fn drop(&mut self) {
-        self.shutting_down.store(true, Ordering::Relaxed);
-
-        let _ = self.frame_pool.RemoveFrameArrived(self.frame_arrived_token);
+        self.close();
 
         if !self.frame_event.is_invalid() {
             // SAFETY: frame_event is a valid handle we created, only close once
@@ -312,23 +579,59 @@ impl Drop for WGCCapture {
 // Capture initialization
 // ---------------------------------------------------------------------------
 
-/// Try to disable the yellow capture border (best-effort).
-///
-/// Uses WinRT `ApiInformation` to check if `IsBorderRequired` exists before
-/// calling it. Silently does nothing on Windows 10 1903/1909 where the
-/// property is absent — the yellow border stays visible (cosmetic only).
-fn try_disable_border(session: &GraphicsCaptureSession) {
+/// Check whether a `GraphicsCaptureSession` property exists on this OS build.
+fn session_property_present(property: &str) -> bool {
     use windows::Foundation::Metadata::ApiInformation;
 
-    let supported = ApiInformation::IsPropertyPresent(
+    ApiInformation::IsPropertyPresent(
         &windows::core::HSTRING::from("Windows.Graphics.Capture.GraphicsCaptureSession"),
-        &windows::core::HSTRING::from("IsBorderRequired"),
+        &windows::core::HSTRING::from(property),
     )
-    .unwrap_or(false);
+    .unwrap_or(false)
+}
+
+/// Apply the capture border setting (best-effort).
+///
+/// Uses WinRT `ApiInformation` to check if `IsBorderRequired` exists before
+/// calling it. On Windows 10 1903/1909 the property is absent and the border
+/// is always visible (cosmetic only). When the OS refuses to hide the border
+/// (e.g. missing borderless capture access), capture proceeds with the border
+/// shown and a warning is emitted instead of failing init.
+fn apply_border_setting(session: &GraphicsCaptureSession, show_border: bool) {
+    if !session_property_present("IsBorderRequired") {
+        return;
+    }
+
+    if let Err(e) = session.SetIsBorderRequired(show_border) {
+        if !show_border {
+            log::warn!("failed to hide capture border, keeping it visible: {e}");
+        }
+    }
+}
+
+/// Apply the cursor capture setting (best-effort).
+///
+/// `IsCursorCaptureEnabled` requires Windows 10 2004+; on older builds the
+/// cursor is always captured.
+fn apply_cursor_setting(session: &GraphicsCaptureSession, cursor: bool) {
+    if !session_property_present("IsCursorCaptureEnabled") {
+        return;
+    }
+    let _ = session.SetIsCursorCaptureEnabled(cursor);
+}
 
-    if supported {
-        let _ = session.SetIsBorderRequired(false);
+/// Apply the secondary window (owned popups) setting (best-effort).
+///
+/// `IncludeSecondaryWindows` requires Windows 11; the setting is always
+/// written when present so the result doesn't depend on the OS default.
+fn apply_popup_setting(session: &GraphicsCaptureSession, include_popups: bool) {
+    if !session_property_present("IncludeSecondaryWindows") {
+        if include_popups {
+            log::debug!("IncludeSecondaryWindows unavailable; popups are not captured");
+        }
+        return;
     }
+    let _ = session.SetIncludeSecondaryWindows(include_popups);
 }
 
 /// Create GraphicsCaptureItem from monitor handle
@@ -370,33 +673,54 @@ fn create_capture_item_for_window(hwnd: HWND) -> Result<GraphicsCaptureItem> {
 /// # Arguments
 /// * `d3d_ctx` - D3D11 device context
 /// * `target` - Capture target (monitor or window)
+/// * `policy` - Capture policy, selects the frame pool pixel format
+/// * `options` - Session settings (capture border, cursor, buffer count,
+///   forced pixel format)
 pub fn init_capture(
     d3d_ctx: &D3D11Context,
     target: CaptureTarget,
     policy: CapturePolicy,
+    options: &SessionOptions,
 ) -> Result<WGCCapture> {
-    // 1. Create GraphicsCaptureItem based on target type
+    // 1. Create GraphicsCaptureItem based on target type.
+    // WGC rejects child windows; capture their root and crop to the child.
     let item = match target {
         CaptureTarget::Monitor(monitor) => create_capture_item_for_monitor(monitor)?,
-        CaptureTarget::Window(hwnd) => create_capture_item_for_window(hwnd)?,
+        CaptureTarget::Window(hwnd) => create_capture_item_for_window(root_window(hwnd))?,
     };
 
     let size = item.Size()?;
 
     // 2. Create FramePool format.
-    // Sdr: always BGRA8. Hdr: always R16G16B16A16_FLOAT.
-    // Auto: follow target monitor HDR state.
+    // Sdr: always BGRA8. Hdr: always R16G16B16A16_FLOAT (R10G10B10A2 with hdr10).
+    // Auto / PreferHdr: follow target monitor HDR state.
+    // A forced format overrides all of the above.
+    // WGC frames carry no color space. R10G10B10A2 surfaces are assumed to
+    // hold HDR10 (PQ, BT.2020 primaries), DWM's encoding for 10-bit HDR
+    // output; hdr10_decode.hlsl and the RGB10A2 encoders rely on it. There is
+    // no API to confirm it per frame.
     let is_hdr = target_is_hdr(d3d_ctx, target).unwrap_or(false);
-    let pixel_format = match (policy, is_hdr) {
-        (CapturePolicy::Sdr, _) => DirectXPixelFormat::B8G8R8A8UIntNormalized,
-        (CapturePolicy::Hdr, _) => DirectXPixelFormat::R16G16B16A16Float,
-        (CapturePolicy::Auto, true) => DirectXPixelFormat::R16G16B16A16Float,
-        (CapturePolicy::Auto, false) => DirectXPixelFormat::B8G8R8A8UIntNormalized,
+    let hdr_format = if options.hdr10 {
+        DirectXPixelFormat::R10G10B10A2UIntNormalized
+    } else {
+        DirectXPixelFormat::R16G16B16A16Float
     };
+    let pixel_format = match (options.force_format, policy, is_hdr) {
+        (Some(ColorPixelFormat::Bgra8), _, _) => DirectXPixelFormat::B8G8R8A8UIntNormalized,
+        (Some(ColorPixelFormat::Rgba16f), _, _) => DirectXPixelFormat::R16G16B16A16Float,
+        (Some(ColorPixelFormat::Rgb10a2), _, _) => DirectXPixelFormat::R10G10B10A2UIntNormalized,
+        (None, CapturePolicy::Sdr, _) => DirectXPixelFormat::B8G8R8A8UIntNormalized,
+        (None, CapturePolicy::Hdr, _) => hdr_format,
+        (None, CapturePolicy::Auto | CapturePolicy::PreferHdr, true) => hdr_format,
+        (None, CapturePolicy::Auto | CapturePolicy::PreferHdr, false) => {
+            DirectXPixelFormat::B8G8R8A8UIntNormalized
+        }
+    };
+    let buffer_count = options.buffer_count.clamp(1, MAX_FRAME_BUFFERS) as i32;
     let frame_pool = Direct3D11CaptureFramePool::CreateFreeThreaded(
         &d3d_ctx.direct3d_device,
         pixel_format,
-        2, // Buffer count
+        buffer_count,
         size,
     )?;
 
@@ -406,78 +730,200 @@ pub fn init_capture(
         unsafe { CreateEventW(None, false, false, None).context("Failed to create frame event")? };
 
     // 4. Register FrameArrived callback: only SetEvent, no D3D operations
-    // Convert HANDLE to usize for closure, bypassing Send restriction.
-    // SAFETY: Kernel event handles are thread-safe, SetEvent can be called from any thread.
     let shutting_down = Arc::new(AtomicBool::new(false));
-    let shutting_down_cb = Arc::clone(&shutting_down);
-    let event_ptr = frame_event.0 as usize;
-    let frame_arrived_token = frame_pool.FrameArrived(&TypedEventHandler::<
-        Direct3D11CaptureFramePool,
-        windows::core::IInspectable,
-    >::new(move |_, _| {
-        if !shutting_down_cb.load(Ordering::Relaxed) {
-            unsafe {
-                if SetEvent(HANDLE(event_ptr as *mut _)).is_err() {
-                    eprintln!("hdrcapture: SetEvent failed in FrameArrived callback");
-                }
-            }
-        }
-        Ok(())
-    }))?;
+    let frame_arrived_token =
+        frame_pool.FrameArrived(&signal_frame_event(frame_event, &shutting_down))?;
 
     let session = frame_pool.CreateCaptureSession(&item)?;
-    // Best-effort: hide the yellow capture border.
-    // Requires Windows 10 2004+ (IGraphicsCaptureSession2). On 1903/1909 the
+    // Best-effort: show or hide the yellow capture border.
+    // Requires Windows 10 2004+ (IGraphicsCaptureSession3). On 1903/1909 the
     // property doesn't exist and the border stays visible — cosmetic only.
     // Uses WinRT ApiInformation to detect availability at runtime, matching
     // the approach used by OBS Studio.
-    try_disable_border(&session);
+    apply_border_setting(&session, options.show_border);
+    apply_cursor_setting(&session, options.cursor);
+    if matches!(target, CaptureTarget::Window(_)) {
+        apply_popup_setting(&session, options.include_popups);
+    }
 
-    let window_handle = match target {
-        CaptureTarget::Window(hwnd) => Some(hwnd),
-        CaptureTarget::Monitor(_) => None,
+    let (window_handle, client_handle) = match target {
+        CaptureTarget::Window(hwnd) => (Some(root_window(hwnd)), Some(hwnd)),
+        CaptureTarget::Monitor(_) => (None, None),
     };
 
     Ok(WGCCapture {
         _item: item,
         frame_pool,
         session,
-        frame_arrived_token,
+        frame_arrived_token: Some(frame_arrived_token),
+        callback_token: None,
+        callback: None,
         frame_event,
         shutting_down,
         pool_width: size.Width as u32,
         pool_height: size.Height as u32,
         target_hdr: is_hdr,
         window_handle,
+        client_handle,
         direct3d_device: d3d_ctx.direct3d_device.clone(),
         pixel_format,
+        buffer_count,
+        cadence: RefCell::new(FrameCadence::default()),
+        frames_received: Cell::new(0),
+        frames_flushed: Cell::new(0),
+        pool_recreates: 0,
+    })
+}
+
+/// FrameArrived handler that sets `frame_event` until teardown starts.
+fn signal_frame_event(
+    frame_event: HANDLE,
+    shutting_down: &Arc<AtomicBool>,
+) -> TypedEventHandler<Direct3D11CaptureFramePool, windows::core::IInspectable> {
+    // Convert HANDLE to usize for closure, bypassing Send restriction.
+    // SAFETY: Kernel event handles are thread-safe, SetEvent can be called from any thread.
+    let shutting_down = Arc::clone(shutting_down);
+    let event_ptr = frame_event.0 as usize;
+    TypedEventHandler::new(move |_, _| {
+        catch_callback_panic("FrameArrived handler", || {
+            if !shutting_down.load(Ordering::Relaxed) {
+                unsafe {
+                    if SetEvent(HANDLE(event_ptr as *mut _)).is_err() {
+                        log::warn!("SetEvent failed in FrameArrived callback");
+                    }
+                }
+            }
+            Ok(())
+        })
     })
 }
 
-fn target_is_hdr(d3d_ctx: &D3D11Context, target: CaptureTarget) -> Result<bool> {
-    let target_monitor = match target {
-        CaptureTarget::Monitor(hmonitor) => hmonitor,
-        CaptureTarget::Window(hwnd) => unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) },
+/// Run the body of a WinRT event handler, turning a panic into a logged error.
+///
+/// Handlers run on WGC thread-pool threads and are called through COM: a panic
+/// must not unwind across that boundary (it would abort the process). Every
+/// FrameArrived handler goes through this, so code added to them later is
+/// covered. The panic is logged at `error` and the event reported as handled.
+fn catch_callback_panic(
+    name: &str,
+    body: impl FnOnce() -> windows::core::Result<()>,
+) -> windows::core::Result<()> {
+    match std::panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("non-string panic payload");
+            log::error!("panic in {name}: {message}");
+            Ok(())
+        }
+    }
+}
+
+/// Whether `target` is captured as shown on an HDR monitor (see [`hdr_reference_monitor`]).
+pub(crate) fn target_is_hdr(d3d_ctx: &D3D11Context, target: CaptureTarget) -> Result<bool> {
+    Ok(target_display_format(d3d_ctx, target)?.is_hdr())
+}
+
+/// Signal format of the monitor whose HDR state `target` follows (default
+/// when not found; see [`hdr_reference_monitor`]).
+pub(crate) fn target_display_format(
+    d3d_ctx: &D3D11Context,
+    target: CaptureTarget,
+) -> Result<DisplayFormat> {
+    monitor_display_format(d3d_ctx, hdr_reference_monitor(d3d_ctx, target).0)
+}
+
+/// Monitor whose HDR state and SDR white level a capture of `target` follows.
+///
+/// A window follows the monitor showing most of it, unless it straddles
+/// monitors of which only some are in HDR mode: then an HDR one wins, so
+/// `Auto` keeps the float format and no highlights are lost. The part on
+/// the SDR monitor is still tone-mapped with the HDR monitor's white level,
+/// which can differ from how that monitor shows it. The flag reports such
+/// a mixed straddle.
+pub(crate) fn hdr_reference_monitor(
+    d3d_ctx: &D3D11Context,
+    target: CaptureTarget,
+) -> (HMONITOR, bool) {
+    let hwnd = match target {
+        CaptureTarget::Monitor(hmonitor) => return (hmonitor, false),
+        CaptureTarget::Window(hwnd) => hwnd,
     };
-    if target_monitor.is_invalid() {
-        return Ok(false);
+    // SAFETY: MonitorFromWindow accepts any HWND and falls back to the nearest monitor.
+    let nearest = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+    let spanned = window_monitors(hwnd);
+    if spanned.len() < 2 {
+        return (nearest, false);
     }
 
-    let adapter = unsafe { d3d_ctx.dxgi_device.GetAdapter()? };
+    let is_hdr = |hmonitor| monitor_display_format(d3d_ctx, hmonitor).is_ok_and(|f| f.is_hdr());
+    let hdr: Vec<HMONITOR> = spanned.iter().copied().filter(|&m| is_hdr(m)).collect();
+    if hdr.is_empty() || hdr.len() == spanned.len() {
+        return (nearest, false);
+    }
+    let chosen = if hdr.contains(&nearest) {
+        nearest
+    } else {
+        hdr[0]
+    };
+    (chosen, true)
+}
 
-    let mut i = 0;
-    while let Ok(output) = unsafe { adapter.EnumOutputs(i) } {
-        let desc = unsafe { output.GetDesc()? };
-        if desc.Monitor == target_monitor {
-            let output6: IDXGIOutput6 = match output.cast() {
-                Ok(v) => v,
-                Err(_) => return Ok(false),
-            };
-            let desc1 = unsafe { output6.GetDesc1()? };
-            return Ok(desc1.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020);
-        }
-        i += 1;
+/// Signal format of a monitor (default when not driven by the device's adapter).
+fn monitor_display_format(d3d_ctx: &D3D11Context, hmonitor: HMONITOR) -> Result<DisplayFormat> {
+    if hmonitor.is_invalid() {
+        return Ok(DisplayFormat::default());
     }
+    let Some(output) = find_output(&d3d_ctx.dxgi_device, hmonitor)? else {
+        return Ok(DisplayFormat::default());
+    };
+    output_display_format(&output)
+}
+
+/// Whether a DXGI output is in HDR10 (PQ / BT.2020) mode.
+pub(crate) fn output_is_hdr(output: &IDXGIOutput) -> Result<bool> {
+    Ok(output_display_format(output)?.is_hdr())
+}
+
+/// Color space and bit depth a DXGI output is driven with.
+///
+/// Default (unknown) without `IDXGIOutput6` (pre-1703 Windows).
+pub(crate) fn output_display_format(output: &IDXGIOutput) -> Result<DisplayFormat> {
+    let output6: IDXGIOutput6 = match output.cast() {
+        Ok(v) => v,
+        Err(_) => return Ok(DisplayFormat::default()),
+    };
+    let desc1 = unsafe { output6.GetDesc1()? };
+    Ok(DisplayFormat {
+        color_space: DisplayColorSpace::from_dxgi(desc1.ColorSpace),
+        bits_per_channel: desc1.BitsPerColor,
+    })
+}
 
-    Ok(false)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callback_panic_is_contained() {
+        assert!(catch_callback_panic("test", || Ok(())).is_ok());
+        assert!(catch_callback_panic("test", || panic!("boom")).is_ok());
+
+        // A panic inside the locked callback poisons it for later events.
+        let callback = Mutex::new(|| panic!("boom"));
+        let run = || {
+            catch_callback_panic("test", || {
+                if let Ok(callback) = callback.lock() {
+                    callback();
+                }
+                Ok(())
+            })
+        };
+        assert!(run().is_ok());
+        assert!(callback.is_poisoned());
+        assert!(run().is_ok());
+    }
 }
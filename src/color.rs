@@ -1,18 +1,81 @@
+pub mod adjust;
+pub mod alpha;
+pub mod color_space;
+pub mod convert;
+pub mod exposure;
+pub mod hdr10;
+pub mod icc;
+pub mod luminance;
+pub mod lut;
 pub mod tone_map;
 pub mod white_level;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use windows::Win32::Graphics::Direct3D11::ID3D11Texture2D;
 
 use crate::capture::CapturePolicy;
 
-pub use tone_map::ToneMapPass;
+pub use adjust::{SdrAdjust, SdrAdjustPass};
+pub use alpha::AlphaMode;
+pub use color_space::{ColorSpacePass, DisplayColorSpace, DisplayFormat, OutputColorSpace};
+pub use exposure::AutoExposure;
+pub use icc::ColorProfile;
+pub use luminance::FrameLuminanceStats;
+pub use lut::{CubeLut, LutPass};
+pub use tone_map::{tonemap_buffer, ToneMapOperator, ToneMapPass};
+
+/// scRGB reference: 1.0 = 80 nits.
+pub const SCRGB_NITS: f32 = 80.0;
 
 /// Pixel format used by color pipeline input/output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColorPixelFormat {
     Bgra8,
     Rgba16f,
+    /// Packed R10G10B10A2 holding HDR10 (BT.2020 primaries, PQ signal).
+    Rgb10a2,
+}
+
+impl ColorPixelFormat {
+    /// Parse a format name: `"bgra8"`, `"rgba16f"` or `"rgb10a2"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "bgra8" => Self::Bgra8,
+            "rgba16f" => Self::Rgba16f,
+            "rgb10a2" => Self::Rgb10a2,
+            _ => return None,
+        })
+    }
+
+    /// Bytes per pixel of tightly packed data in this format.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Bgra8 | Self::Rgb10a2 => 4,
+            Self::Rgba16f => 8,
+        }
+    }
+
+    /// Check that `data` holds exactly `width` × `height` tightly packed pixels.
+    ///
+    /// CPU consumers (encoders, numpy export) index pixels by position; a
+    /// truncated or oversized buffer is reported here instead of panicking
+    /// or silently writing a partial image.
+    pub fn check_len(self, data: &[u8], width: u32, height: u32) -> Result<()> {
+        let expected = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|n| n.checked_mul(self.bytes_per_pixel()));
+        if expected != Some(data.len()) {
+            bail!(
+                "{:?} pixel data for {}x{} must be {} bytes, got {}",
+                self,
+                width,
+                height,
+                expected.map_or_else(|| "overflowing".to_string(), |n| n.to_string()),
+                data.len()
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Frame container passed through color pipeline.
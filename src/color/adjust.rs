@@ -0,0 +1,266 @@
+// Brightness / contrast / gamma post-adjustment of SDR output.
+//
+// SdrAdjust holds the user settings; SdrAdjustPass applies them to BGRA8
+// frames on the GPU as the last color step, after tone-mapping and the LUT.
+// The pipeline skips the pass entirely while the settings are identity.
+
+use anyhow::{bail, Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM;
+
+use crate::d3d11::compute::{self, ComputeShader};
+
+/// Simple tone tweak of the final SDR frame, on display-encoded values (0–1).
+///
+/// Applied in order: gamma, contrast, brightness; the result is clamped to
+/// 0–1. [`SdrAdjust::default`] is the identity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdrAdjust {
+    /// Offset added to every channel, -1.0–1.0 (0.0 = unchanged).
+    pub brightness: f32,
+    /// Scale around mid-gray, >= 0.0 (1.0 = unchanged).
+    pub contrast: f32,
+    /// Midtone gamma, > 0.0; values above 1.0 brighten (1.0 = unchanged).
+    pub gamma: f32,
+}
+
+impl Default for SdrAdjust {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl SdrAdjust {
+    /// True when applying the adjustment would not change any pixel.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reject non-finite or out-of-range values.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.brightness.is_finite() && (-1.0..=1.0).contains(&self.brightness)) {
+            bail!(
+                "brightness must be between -1.0 and 1.0, got {}",
+                self.brightness
+            );
+        }
+        if !(self.contrast.is_finite() && self.contrast >= 0.0) {
+            bail!("contrast must be >= 0.0, got {}", self.contrast);
+        }
+        if !(self.gamma.is_finite() && self.gamma > 0.0) {
+            bail!("gamma must be a positive number, got {}", self.gamma);
+        }
+        Ok(())
+    }
+
+    /// Adjust one display-encoded channel value (CPU reference of the shader).
+    pub fn apply(&self, value: f32) -> f32 {
+        let v = value.clamp(0.0, 1.0).powf(1.0 / self.gamma);
+        ((v - 0.5) * self.contrast + 0.5 + self.brightness).clamp(0.0, 1.0)
+    }
+
+    fn params(&self) -> AdjustParams {
+        AdjustParams {
+            brightness: self.brightness,
+            contrast: self.contrast,
+            inv_gamma: 1.0 / self.gamma,
+            _pad: 0.0,
+        }
+    }
+}
+
+/// Constant buffer layout matching HLSL `AdjustParams`.
+#[repr(C)]
+struct AdjustParams {
+    brightness: f32,
+    contrast: f32,
+    inv_gamma: f32,
+    _pad: f32,
+}
+
+/// GPU brightness / contrast / gamma pass: BGRA8 → BGRA8.
+///
+/// Output texture is lazily created and reused when dimensions match.
+pub struct SdrAdjustPass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    cbuffer: ID3D11Buffer,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    width: u32,
+    height: u32,
+}
+
+impl SdrAdjustPass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::SDR_ADJUST_HLSL, "main")?;
+
+        let cb_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of::<AdjustParams>() as u32,
+            Usage: D3D11_USAGE_DYNAMIC,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as u32,
+            CPUAccessFlags: D3D11_CPU_ACCESS_WRITE.0 as u32,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+
+        // SAFETY: cb_desc is fully initialized; CreateBuffer allocates a GPU resource.
+        let cbuffer = unsafe {
+            let mut buf = None;
+            device
+                .CreateBuffer(&cb_desc, None, Some(&mut buf))
+                .context("CreateBuffer for SDR adjust cbuffer failed")?;
+            buf.unwrap()
+        };
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            cbuffer,
+            output_cache: None,
+        })
+    }
+
+    fn update_cbuffer(&self, params: AdjustParams) -> Result<()> {
+        // SAFETY: Map/Unmap pattern for DYNAMIC buffer with WRITE_DISCARD.
+        // The buffer is sized to AdjustParams.
+        unsafe {
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(
+                    &self.cbuffer,
+                    0,
+                    D3D11_MAP_WRITE_DISCARD,
+                    0,
+                    Some(&mut mapped),
+                )
+                .context("Map SDR adjust cbuffer failed")?;
+            std::ptr::write(mapped.pData as *mut AdjustParams, params);
+            self.context.Unmap(&self.cbuffer, 0);
+        }
+        Ok(())
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height {
+                return Ok(());
+            }
+        }
+
+        let (texture, uav) =
+            compute::create_output(&self.device, width, height, DXGI_FORMAT_B8G8R8A8_UNORM)?;
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            width,
+            height,
+        });
+        Ok(())
+    }
+
+    /// Apply `adjust` to a BGRA8 texture, returning a new BGRA8 texture.
+    pub fn execute(
+        &mut self,
+        input: &ID3D11Texture2D,
+        width: u32,
+        height: u32,
+        adjust: &SdrAdjust,
+    ) -> Result<ID3D11Texture2D> {
+        self.ensure_output(width, height)?;
+        self.update_cbuffer(adjust.params())?;
+
+        let srv = compute::create_srv(&self.device, input)?;
+        let cache = self.output_cache.as_ref().unwrap();
+
+        // SAFETY: cbuffer is a valid D3D11 buffer, binding to CS stage slot 0.
+        unsafe {
+            self.context
+                .CSSetConstantBuffers(0, Some(&[Some(self.cbuffer.clone())]));
+        }
+
+        compute::dispatch(&self.context, &self.shader, &srv, &cache.uav, width, height);
+
+        // SAFETY: Unbinding prevents resource hazards.
+        unsafe {
+            let no_cb: [Option<ID3D11Buffer>; 1] = [None];
+            self.context.CSSetConstantBuffers(0, Some(&no_cb));
+        }
+
+        Ok(cache.texture.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_leaves_values_unchanged() {
+        let adjust = SdrAdjust::default();
+        assert!(adjust.is_identity());
+        for v in [0.0, 0.2, 0.5, 1.0] {
+            assert!((adjust.apply(v) - v).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_adjustments() {
+        let brighter = SdrAdjust {
+            brightness: 0.1,
+            ..Default::default()
+        };
+        assert!((brighter.apply(0.5) - 0.6).abs() < 1e-6);
+        assert_eq!(brighter.apply(0.95), 1.0);
+
+        let flat = SdrAdjust {
+            contrast: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(flat.apply(0.1), 0.5);
+
+        // Gamma 2.0 lifts the midtones but keeps black and white fixed.
+        let lifted = SdrAdjust {
+            gamma: 2.0,
+            ..Default::default()
+        };
+        assert!((lifted.apply(0.25) - 0.5).abs() < 1e-6);
+        assert_eq!(lifted.apply(0.0), 0.0);
+        assert_eq!(lifted.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(SdrAdjust::default().validate().is_ok());
+        for bad in [
+            SdrAdjust {
+                brightness: 1.5,
+                ..Default::default()
+            },
+            SdrAdjust {
+                contrast: -1.0,
+                ..Default::default()
+            },
+            SdrAdjust {
+                gamma: 0.0,
+                ..Default::default()
+            },
+            SdrAdjust {
+                gamma: f32::NAN,
+                ..Default::default()
+            },
+        ] {
+            assert!(bad.validate().is_err(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Alpha channel semantics for captured frames.
+//
+// DWM composes with premultiplied alpha and WGC hands out its surfaces as-is.
+// For ordinary windows and monitors the alpha channel is not meaningful, so by
+// default encoders write opaque pixels. Layered / transparent windows carry
+// real per-pixel alpha, which is kept when the pipeline preserves it.
+
+use half::f16;
+
+use super::{hdr10, ColorPixelFormat};
+
+/// Meaning of the alpha channel in frame data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlphaMode {
+    /// Alpha is not meaningful; encoders write fully opaque pixels.
+    #[default]
+    Ignore,
+    /// Color channels are premultiplied by alpha (as captured by WGC).
+    Premultiplied,
+    /// Color channels are independent of alpha (what PNG / TIFF expect).
+    Straight,
+}
+
+/// Convert premultiplied pixels to straight alpha in place.
+///
+/// Fully transparent pixels become transparent black.
+pub fn unpremultiply(data: &mut [u8], format: ColorPixelFormat) {
+    match format {
+        ColorPixelFormat::Bgra8 => {
+            for px in data.chunks_exact_mut(4) {
+                let a = px[3] as u32;
+                if a == 255 {
+                    continue;
+                }
+                for c in &mut px[..3] {
+                    *c = if a == 0 {
+                        0
+                    } else {
+                        ((*c as u32 * 255 + a / 2) / a).min(255) as u8
+                    };
+                }
+            }
+        }
+        ColorPixelFormat::Rgba16f => {
+            for px in data.chunks_exact_mut(8) {
+                let a = f16::from_le_bytes([px[6], px[7]]).to_f32();
+                if a == 1.0 {
+                    continue;
+                }
+                for i in (0..6).step_by(2) {
+                    let c = f16::from_le_bytes([px[i], px[i + 1]]).to_f32();
+                    let straight = if a > 0.0 { c / a } else { 0.0 };
+                    px[i..i + 2].copy_from_slice(&f16::from_f32(straight).to_le_bytes());
+                }
+            }
+        }
+        ColorPixelFormat::Rgb10a2 => {
+            for px in data.chunks_exact_mut(4) {
+                let pixel = [px[0], px[1], px[2], px[3]];
+                if hdr10::unpack(pixel)[3] == 3 {
+                    continue;
+                }
+                // Premultiplication applies to linear light, not the PQ signal.
+                let [r, g, b, a] = hdr10::pixel_to_scrgb(pixel);
+                let straight = if a > 0.0 {
+                    [r / a, g / a, b / a, a]
+                } else {
+                    [0.0; 4]
+                };
+                px.copy_from_slice(&hdr10::scrgb_to_pixel(straight));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unpremultiply_bgra8() {
+        // ~50% alpha: premultiplied (64, 100, 127) → straight (128, 199, 253).
+        let mut data = vec![64, 100, 127, 128, 10, 20, 30, 255, 9, 9, 9, 0];
+        unpremultiply(&mut data, ColorPixelFormat::Bgra8);
+        assert_eq!(data, vec![128, 199, 253, 128, 10, 20, 30, 255, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_unpremultiply_rgba16f() {
+        let mut data: Vec<u8> = [0.25f32, 0.5, 2.0, 0.5]
+            .iter()
+            .flat_map(|&c| f16::from_f32(c).to_le_bytes())
+            .collect();
+        unpremultiply(&mut data, ColorPixelFormat::Rgba16f);
+        let ch = |i: usize| f16::from_le_bytes([data[i * 2], data[i * 2 + 1]]).to_f32();
+        assert_eq!([ch(0), ch(1), ch(2), ch(3)], [0.5, 1.0, 4.0, 0.5]);
+    }
+}
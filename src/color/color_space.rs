@@ -0,0 +1,306 @@
+// Output color space stage for HDR (RGBA16F) frames.
+//
+// WGC delivers HDR surfaces as scRGB: linear, BT.709 primaries, 1.0 = 80 nits.
+// OutputColorSpace makes that explicit and lets color-pipeline users ask for
+// linear Rec.2020 or sRGB-encoded values instead. ColorSpacePass applies the
+// gamut matrix and transfer curve on the GPU; scRGB output skips the pass.
+//
+// DisplayFormat describes the other end: the signal the source display is
+// driven with (SDR sRGB, HDR10 PQ / BT.2020), as reported by DXGI.
+
+use anyhow::{Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::{
+    DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709, DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020,
+    DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709, DXGI_COLOR_SPACE_TYPE, DXGI_FORMAT_R16G16B16A16_FLOAT,
+};
+
+use crate::d3d11::compute::{self, ComputeShader};
+
+/// BT.709 → BT.2020 primaries conversion for linear RGB (ITU-R BT.2087).
+const BT709_TO_BT2020: [[f32; 3]; 3] = [
+    [0.627_404, 0.329_282, 0.043_314],
+    [0.069_097, 0.919_540, 0.011_361],
+    [0.016_392, 0.088_013, 0.895_595],
+];
+
+const IDENTITY: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// CIE 1931 xy of the BT.709 red, green, blue primaries and D65 white.
+const BT709_PRIMARIES: [[f32; 2]; 4] = [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06], [0.3127, 0.3290]];
+
+/// CIE 1931 xy of the BT.2020 red, green, blue primaries and D65 white.
+const BT2020_PRIMARIES: [[f32; 2]; 4] = [
+    [0.708, 0.292],
+    [0.170, 0.797],
+    [0.131, 0.046],
+    [0.3127, 0.3290],
+];
+
+/// Color encoding of RGBA16F (HDR) output frames.
+///
+/// All variants keep the scRGB luminance scale (1.0 = 80 nits) and may hold
+/// values above 1.0 (HDR highlights) or below 0.0 (out of gamut). BGRA8 output
+/// is always display-encoded sRGB and is not affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputColorSpace {
+    /// Linear, BT.709 primaries: exactly what DWM composes (no conversion).
+    #[default]
+    ScrgbLinear,
+    /// Linear, BT.2020 primaries. Wide-gamut content lands inside [0, 1].
+    Rec2020Linear,
+    /// BT.709 primaries with the sRGB transfer curve, mirrored for negative
+    /// values and extended above 1.0.
+    SrgbEncoded,
+}
+
+impl OutputColorSpace {
+    /// Parse a color space name: `"scrgb"`, `"rec2020"` or `"srgb"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "scrgb" => Self::ScrgbLinear,
+            "rec2020" => Self::Rec2020Linear,
+            "srgb" => Self::SrgbEncoded,
+            _ => return None,
+        })
+    }
+
+    /// Name accepted by [`from_name`](Self::from_name).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ScrgbLinear => "scrgb",
+            Self::Rec2020Linear => "rec2020",
+            Self::SrgbEncoded => "srgb",
+        }
+    }
+
+    /// CIE 1931 xy chromaticities of the red, green and blue primaries and
+    /// the white point, e.g. for the EXR `chromaticities` attribute.
+    pub fn primaries(self) -> [[f32; 2]; 4] {
+        match self {
+            Self::Rec2020Linear => BT2020_PRIMARIES,
+            Self::ScrgbLinear | Self::SrgbEncoded => BT709_PRIMARIES,
+        }
+    }
+
+    fn gamut_matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Self::Rec2020Linear => BT709_TO_BT2020,
+            Self::ScrgbLinear | Self::SrgbEncoded => IDENTITY,
+        }
+    }
+}
+
+/// Color space of the signal driving the source display.
+///
+/// Describes the display, not the frame data: WGC composes HDR displays to
+/// scRGB regardless, see [`OutputColorSpace`] for how frame data is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayColorSpace {
+    /// SDR: sRGB transfer curve, BT.709 primaries.
+    Srgb,
+    /// HDR10: PQ (SMPTE ST 2084) transfer curve, BT.2020 primaries.
+    PqRec2020,
+    /// Linear scRGB (BT.709 primaries).
+    Scrgb,
+    /// Not reported (no `IDXGIOutput6`, mixed displays) or another DXGI color space.
+    #[default]
+    Unknown,
+}
+
+impl DisplayColorSpace {
+    /// Map a DXGI output color space.
+    pub fn from_dxgi(color_space: DXGI_COLOR_SPACE_TYPE) -> Self {
+        match color_space {
+            DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709 => Self::Srgb,
+            DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020 => Self::PqRec2020,
+            DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709 => Self::Scrgb,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Short name: `"srgb"`, `"pq_rec2020"`, `"scrgb"` or `"unknown"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Srgb => "srgb",
+            Self::PqRec2020 => "pq_rec2020",
+            Self::Scrgb => "scrgb",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Signal format of the source display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DisplayFormat {
+    pub color_space: DisplayColorSpace,
+    /// Bits per color channel of the display signal (0 when unknown).
+    pub bits_per_channel: u32,
+}
+
+impl DisplayFormat {
+    /// Whether the display runs in HDR10 mode.
+    pub fn is_hdr(self) -> bool {
+        self.color_space == DisplayColorSpace::PqRec2020
+    }
+}
+
+/// Constant buffer layout matching HLSL `ColorSpaceParams`.
+#[repr(C)]
+struct ColorSpaceParams {
+    gamut_rows: [[f32; 4]; 3],
+    encode_srgb: u32,
+    _pad: [u32; 3],
+}
+
+/// GPU pass: scRGB RGBA16F → RGBA16F in the requested color space.
+pub struct ColorSpacePass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    cbuffer: ID3D11Buffer,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    width: u32,
+    height: u32,
+}
+
+impl ColorSpacePass {
+    pub fn new(
+        device: &ID3D11Device,
+        context: &ID3D11DeviceContext,
+        color_space: OutputColorSpace,
+    ) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::COLOR_SPACE_HLSL, "main")?;
+
+        let m = color_space.gamut_matrix();
+        let params = ColorSpaceParams {
+            gamut_rows: m.map(|[r, g, b]| [r, g, b, 0.0]),
+            encode_srgb: (color_space == OutputColorSpace::SrgbEncoded) as u32,
+            _pad: [0; 3],
+        };
+        let cb_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of::<ColorSpaceParams>() as u32,
+            Usage: D3D11_USAGE_IMMUTABLE,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+        let cb_data = D3D11_SUBRESOURCE_DATA {
+            pSysMem: &params as *const _ as *const _,
+            SysMemPitch: 0,
+            SysMemSlicePitch: 0,
+        };
+
+        // SAFETY: cb_desc and cb_data are fully initialized; params outlives the call.
+        let cbuffer = unsafe {
+            let mut buf = None;
+            device
+                .CreateBuffer(&cb_desc, Some(&cb_data), Some(&mut buf))
+                .context("CreateBuffer for color space cbuffer failed")?;
+            buf.unwrap()
+        };
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            cbuffer,
+            output_cache: None,
+        })
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height {
+                return Ok(());
+            }
+        }
+
+        let (texture, uav) =
+            compute::create_output(&self.device, width, height, DXGI_FORMAT_R16G16B16A16_FLOAT)?;
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            width,
+            height,
+        });
+        Ok(())
+    }
+
+    /// Convert a scRGB RGBA16F texture, returning a new RGBA16F texture.
+    pub fn execute(
+        &mut self,
+        input: &ID3D11Texture2D,
+        width: u32,
+        height: u32,
+    ) -> Result<ID3D11Texture2D> {
+        self.ensure_output(width, height)?;
+
+        let srv = compute::create_srv(&self.device, input)?;
+        let cache = self.output_cache.as_ref().unwrap();
+
+        // SAFETY: cbuffer is a valid D3D11 buffer, binding to CS stage slot 0.
+        unsafe {
+            self.context
+                .CSSetConstantBuffers(0, Some(&[Some(self.cbuffer.clone())]));
+        }
+
+        compute::dispatch(&self.context, &self.shader, &srv, &cache.uav, width, height);
+
+        // SAFETY: Unbinding prevents resource hazards.
+        unsafe {
+            let no_cb: [Option<ID3D11Buffer>; 1] = [None];
+            self.context.CSSetConstantBuffers(0, Some(&no_cb));
+        }
+
+        Ok(cache.texture.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bt2020_matrix_preserves_white() {
+        // D65 white has equal RGB in both gamuts, so every row sums to 1.
+        for row in BT709_TO_BT2020 {
+            let sum: f32 = row.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-4, "{row:?}");
+        }
+    }
+
+    #[test]
+    fn test_color_space_names_round_trip() {
+        for cs in [
+            OutputColorSpace::ScrgbLinear,
+            OutputColorSpace::Rec2020Linear,
+            OutputColorSpace::SrgbEncoded,
+        ] {
+            assert_eq!(OutputColorSpace::from_name(cs.name()), Some(cs));
+        }
+        assert_eq!(OutputColorSpace::from_name("p3"), None);
+    }
+
+    #[test]
+    fn test_display_color_space_from_dxgi() {
+        assert_eq!(
+            DisplayColorSpace::from_dxgi(DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020),
+            DisplayColorSpace::PqRec2020
+        );
+        assert_eq!(
+            DisplayColorSpace::from_dxgi(DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709),
+            DisplayColorSpace::Srgb
+        );
+        assert_eq!(
+            DisplayColorSpace::from_dxgi(DXGI_COLOR_SPACE_TYPE(-1)),
+            DisplayColorSpace::Unknown
+        );
+    }
+}
@@ -0,0 +1,109 @@
+// CPU conversions of frame bytes.
+//
+// Frames are stored as BGRA (what DXGI and WGC produce); most encoders and
+// other crates expect RGBA or RGB. Shared by the `image`-crate encoders and
+// `CapturedFrame::to_rgba8` / `to_rgb8`. RGBA16F frames are byte buffers too;
+// `f16_values` is the one checked place they are viewed as `f16`.
+
+use std::borrow::Cow;
+
+use anyhow::{bail, Result};
+use half::f16;
+
+/// BGRA8 → tightly packed RGBA8. With `opaque`, alpha is written as 255.
+pub fn bgra8_to_rgba8(data: &[u8], opaque: bool) -> Vec<u8> {
+    let mut rgba = data.to_vec();
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        if opaque {
+            pixel[3] = 255;
+        }
+    }
+    rgba
+}
+
+/// BGRA8 → tightly packed RGB8, dropping alpha.
+pub fn bgra8_to_rgb8(data: &[u8]) -> Vec<u8> {
+    data.chunks_exact(4)
+        .flat_map(|px| [px[2], px[1], px[0]])
+        .collect()
+}
+
+/// View RGBA16F bytes as `f16` values.
+///
+/// Borrows when `data` is 2-byte aligned (always the case for frame
+/// buffers) and decodes into a copy otherwise. Errors on an odd length
+/// rather than dropping the trailing byte.
+pub fn f16_values(data: &[u8]) -> Result<Cow<'_, [f16]>> {
+    if !data.len().is_multiple_of(2) {
+        bail!(
+            "RGBA16F data must be an even number of bytes, got {}",
+            data.len()
+        );
+    }
+    if !(data.as_ptr() as usize).is_multiple_of(std::mem::align_of::<f16>()) {
+        return Ok(Cow::Owned(
+            data.chunks_exact(2)
+                .map(|b| f16::from_le_bytes([b[0], b[1]]))
+                .collect(),
+        ));
+    }
+    // SAFETY: length and alignment are checked above; f16 is
+    // repr(transparent) over u16, so every bit pattern is a valid value.
+    // Frames are little-endian on every Windows target.
+    let values = unsafe { std::slice::from_raw_parts(data.as_ptr() as *const f16, data.len() / 2) };
+    Ok(Cow::Borrowed(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f16_values_checks_length_and_alignment() {
+        let values = [1.0f32, -0.5, 2.0].map(f16::from_f32);
+        // One spare byte in front gives a misaligned view of the same values.
+        let mut bytes = vec![0u8];
+        bytes.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+
+        for data in [&bytes[1..], &bytes[..6]] {
+            let cast = f16_values(data).unwrap();
+            let expected: Vec<f16> = data
+                .chunks_exact(2)
+                .map(|b| f16::from_le_bytes([b[0], b[1]]))
+                .collect();
+            assert_eq!(&*cast, expected.as_slice());
+        }
+        assert_eq!(&*f16_values(&bytes[1..]).unwrap(), &values[..]);
+        assert!(f16_values(&bytes[1..6]).is_err());
+        assert!(f16_values(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_f16_values_length_sweep() {
+        let bytes: Vec<u8> = (0..=65).collect();
+        for offset in [0, 1] {
+            for len in 0..=64 {
+                let data = &bytes[offset..offset + len];
+                match f16_values(data) {
+                    Ok(values) => {
+                        assert!(len.is_multiple_of(2), "len {len} offset {offset}");
+                        assert_eq!(values.len(), len / 2);
+                        for (value, b) in values.iter().zip(data.chunks_exact(2)) {
+                            assert_eq!(value.to_bits(), u16::from_le_bytes([b[0], b[1]]));
+                        }
+                    }
+                    Err(_) => assert!(!len.is_multiple_of(2), "len {len} offset {offset}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bgra8_channel_order() {
+        let bgra = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(bgra8_to_rgba8(&bgra, false), [3, 2, 1, 4, 7, 6, 5, 8]);
+        assert_eq!(bgra8_to_rgba8(&bgra, true), [3, 2, 1, 255, 7, 6, 5, 255]);
+        assert_eq!(bgra8_to_rgb8(&bgra), [3, 2, 1, 7, 6, 5]);
+    }
+}
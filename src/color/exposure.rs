@@ -0,0 +1,355 @@
+// Auto-exposure for HDR→SDR tone-mapping.
+//
+// HistogramPass bins the luminance of an scRGB frame on the GPU (see
+// luminance_histogram.hlsl) and reads back only the 256-byte histogram, a
+// frame or two late so the CPU never waits for the GPU.
+// AutoExposure turns the histogram's median into an exposure multiplier that
+// ToneMapPass applies before its curve, limited in range and smoothed across
+// frames so a cursor blink or a scrolling page doesn't pump the brightness.
+
+use std::collections::VecDeque;
+
+use anyhow::{bail, Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_R32_TYPELESS;
+use windows::Win32::Graphics::Dxgi::DXGI_ERROR_WAS_STILL_DRAWING;
+
+use crate::d3d11::compute::{self, ComputeShader};
+
+/// Number of histogram bins; `BIN_COUNT` in the shader.
+pub const HISTOGRAM_BINS: usize = 64;
+
+/// Log2 luminance range (nits) split by bins 1..; `LOG2_MIN_NITS` /
+/// `LOG2_MAX_NITS` in the shader. Bin 0 holds black (< 1/16 nit).
+const LOG2_MIN_NITS: f32 = -4.0;
+const LOG2_MAX_NITS: f32 = 14.0;
+
+/// Staging buffers the histogram is copied into, so up to this many
+/// histograms can be in flight before the oldest unread one is dropped.
+const STAGING_RING: usize = 3;
+
+/// Pixel counts per log2-luminance bin, see [`HistogramPass`].
+pub type LuminanceHistogram = [u32; HISTOGRAM_BINS];
+
+/// Auto-exposure settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoExposure {
+    /// Luminance the frame's median is brought to, as a fraction of SDR
+    /// white (default 0.2: the median lands near sRGB mid-gray).
+    pub target: f32,
+    /// Lowest exposure multiplier (default 0.25, two stops darker).
+    pub min: f32,
+    /// Highest exposure multiplier (default 4.0, two stops brighter).
+    pub max: f32,
+    /// Fraction of the remaining distance (in stops) closed per frame,
+    /// `0 < adaptation <= 1` (default 0.1); 1.0 jumps to the target at once.
+    pub adaptation: f32,
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self {
+            target: 0.2,
+            min: 0.25,
+            max: 4.0,
+            adaptation: 0.1,
+        }
+    }
+}
+
+impl AutoExposure {
+    /// Check the settings are usable.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.target.is_finite() && self.target > 0.0) {
+            bail!("auto-exposure target must be positive, got {}", self.target);
+        }
+        if !(self.min > 0.0 && self.min <= self.max && self.max.is_finite()) {
+            bail!(
+                "auto-exposure range must satisfy 0 < min <= max, got {}..{}",
+                self.min,
+                self.max
+            );
+        }
+        if !(self.adaptation > 0.0 && self.adaptation <= 1.0) {
+            bail!(
+                "auto-exposure adaptation must be in (0, 1], got {}",
+                self.adaptation
+            );
+        }
+        Ok(())
+    }
+
+    /// Exposure that brings the median of `histogram` to the target, within
+    /// `min..=max`. `None` for an all-black frame, which carries no exposure
+    /// information.
+    pub fn target_exposure(
+        &self,
+        histogram: &LuminanceHistogram,
+        sdr_white_nits: f32,
+    ) -> Option<f32> {
+        let median = median_nits(histogram)?;
+        Some((self.target * sdr_white_nits / median).clamp(self.min, self.max))
+    }
+
+    /// Step `current` toward `target` by `adaptation`, in stops.
+    pub fn adapt(&self, current: f32, target: f32) -> f32 {
+        let (current, target) = (current.log2(), target.log2());
+        (current + (target - current) * self.adaptation).exp2()
+    }
+}
+
+/// Median luminance (nits) of the non-black pixels in `histogram`, at the
+/// center of the bin it falls in. `None` when every pixel is black.
+pub fn median_nits(histogram: &LuminanceHistogram) -> Option<f32> {
+    let lit = &histogram[1..];
+    let total: u64 = lit.iter().map(|&n| n as u64).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut seen = 0u64;
+    for (i, &count) in lit.iter().enumerate() {
+        seen += count as u64;
+        if seen * 2 >= total {
+            let stops = (LOG2_MAX_NITS - LOG2_MIN_NITS) / (HISTOGRAM_BINS - 1) as f32;
+            return Some((LOG2_MIN_NITS + (i as f32 + 0.5) * stops).exp2());
+        }
+    }
+    None
+}
+
+/// GPU luminance histogram of an scRGB R16G16B16A16_FLOAT texture.
+pub struct HistogramPass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    result: ID3D11Buffer,
+    result_uav: ID3D11UnorderedAccessView,
+    staging: Vec<ID3D11Buffer>,
+    /// Staging slots holding a queued copy, oldest first.
+    pending: VecDeque<usize>,
+    /// Staging slot the next copy goes into.
+    next: usize,
+}
+
+impl HistogramPass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader =
+            ComputeShader::compile(device, crate::shader::LUMINANCE_HISTOGRAM_HLSL, "main")?;
+
+        let bytes = (HISTOGRAM_BINS * 4) as u32;
+        let result_desc = D3D11_BUFFER_DESC {
+            ByteWidth: bytes,
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_UNORDERED_ACCESS.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: D3D11_RESOURCE_MISC_BUFFER_ALLOW_RAW_VIEWS.0 as u32,
+            StructureByteStride: 0,
+        };
+        let staging_desc = D3D11_BUFFER_DESC {
+            ByteWidth: bytes,
+            Usage: D3D11_USAGE_STAGING,
+            BindFlags: 0,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+        let uav_desc = D3D11_UNORDERED_ACCESS_VIEW_DESC {
+            Format: DXGI_FORMAT_R32_TYPELESS,
+            ViewDimension: D3D11_UAV_DIMENSION_BUFFER,
+            Anonymous: D3D11_UNORDERED_ACCESS_VIEW_DESC_0 {
+                Buffer: D3D11_BUFFER_UAV {
+                    FirstElement: 0,
+                    NumElements: HISTOGRAM_BINS as u32,
+                    Flags: D3D11_BUFFER_UAV_FLAG_RAW.0 as u32,
+                },
+            },
+        };
+
+        // SAFETY: descriptors are fully initialized; the UAV describes the raw
+        // result buffer created just before it.
+        let (result, result_uav) = unsafe {
+            let mut result = None;
+            device
+                .CreateBuffer(&result_desc, None, Some(&mut result))
+                .context("CreateBuffer for histogram failed")?;
+            let result = result.unwrap();
+
+            let mut uav = None;
+            device
+                .CreateUnorderedAccessView(&result, Some(&uav_desc), Some(&mut uav))
+                .context("CreateUnorderedAccessView for histogram failed")?;
+            (result, uav.unwrap())
+        };
+        let staging = (0..STAGING_RING)
+            .map(|_| {
+                let mut staging = None;
+                // SAFETY: the descriptor is fully initialized.
+                unsafe { device.CreateBuffer(&staging_desc, None, Some(&mut staging)) }
+                    .context("CreateBuffer for histogram staging failed")?;
+                Ok(staging.unwrap())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            result,
+            result_uav,
+            staging,
+            pending: VecDeque::with_capacity(STAGING_RING),
+            next: 0,
+        })
+    }
+
+    /// Queue the luminance histogram of `input` and return the newest earlier
+    /// one the GPU has finished, or `None` when none is ready.
+    ///
+    /// With `wait`, blocks for the histogram of `input` itself instead and
+    /// drops the older ones still queued.
+    pub fn execute(
+        &mut self,
+        input: &ID3D11Texture2D,
+        wait: bool,
+    ) -> Result<Option<LuminanceHistogram>> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe {
+            input.GetDesc(&mut desc);
+        }
+
+        let srv = compute::create_srv(&self.device, input)?;
+        // SAFETY: the UAV is a valid view of `result`; clearing zeroes every bin.
+        unsafe {
+            self.context
+                .ClearUnorderedAccessViewUint(&self.result_uav, &[0; 4]);
+        }
+        compute::dispatch(
+            &self.context,
+            &self.shader,
+            &srv,
+            &self.result_uav,
+            desc.Width,
+            desc.Height,
+        );
+
+        let slot = self.next;
+        self.next = (slot + 1) % STAGING_RING;
+        // A copy still unread in this slot never became ready; the new one
+        // replaces it.
+        self.pending.retain(|&s| s != slot);
+        // SAFETY: CopyResource between equally sized buffers.
+        unsafe {
+            self.context.CopyResource(&self.staging[slot], &self.result);
+        }
+
+        if wait {
+            self.pending.clear();
+            return self.read_slot(slot, true);
+        }
+        // Copies finish in order: the newest ready one supersedes the older.
+        for i in (0..self.pending.len()).rev() {
+            if let Some(histogram) = self.read_slot(self.pending[i], false)? {
+                self.pending.drain(..=i);
+                self.pending.push_back(slot);
+                return Ok(Some(histogram));
+            }
+        }
+        self.pending.push_back(slot);
+        Ok(None)
+    }
+
+    /// Read the histogram copied into staging `slot`; without `wait`, `None`
+    /// while the GPU hasn't finished the copy.
+    fn read_slot(&self, slot: usize, wait: bool) -> Result<Option<LuminanceHistogram>> {
+        let flags = if wait {
+            0
+        } else {
+            D3D11_MAP_FLAG_DO_NOT_WAIT.0 as u32
+        };
+        // SAFETY: the staging buffer holds HISTOGRAM_BINS u32 values; the
+        // mapped pointer is only read before Unmap.
+        unsafe {
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            match self.context.Map(
+                &self.staging[slot],
+                0,
+                D3D11_MAP_READ,
+                flags,
+                Some(&mut mapped),
+            ) {
+                Ok(()) => {}
+                Err(e) if e.code() == DXGI_ERROR_WAS_STILL_DRAWING => return Ok(None),
+                Err(e) => return Err(e).context("Map histogram staging buffer failed"),
+            }
+            let histogram = std::ptr::read_unaligned(mapped.pData as *const LuminanceHistogram);
+            self.context.Unmap(&self.staging[slot], 0);
+            Ok(Some(histogram))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Histogram with every pixel in the bin containing `nits`.
+    fn single_bin(nits: f32, count: u32) -> LuminanceHistogram {
+        let stops = (LOG2_MAX_NITS - LOG2_MIN_NITS) / (HISTOGRAM_BINS - 1) as f32;
+        let bin = 1 + ((nits.log2() - LOG2_MIN_NITS) / stops) as usize;
+        let mut histogram = [0; HISTOGRAM_BINS];
+        histogram[bin] = count;
+        histogram
+    }
+
+    #[test]
+    fn test_median_ignores_black() {
+        let mut histogram = single_bin(100.0, 10);
+        histogram[0] = 1000;
+        let median = median_nits(&histogram).unwrap();
+        // Within one bin (2^(18/63) ≈ 1.22x) of the input.
+        assert!((80.0..125.0).contains(&median), "median {median}");
+
+        let mut black = [0; HISTOGRAM_BINS];
+        black[0] = 50;
+        assert_eq!(median_nits(&black), None);
+    }
+
+    #[test]
+    fn test_target_exposure_clamps_and_adapts() {
+        let settings = AutoExposure::default();
+        settings.validate().unwrap();
+
+        // Median at the target (0.2 × 200 nits) needs no correction.
+        let exposure = settings
+            .target_exposure(&single_bin(40.0, 1), 200.0)
+            .unwrap();
+        assert!((0.8..1.25).contains(&exposure), "exposure {exposure}");
+        // A very dark scene hits the upper limit, a very bright one the lower.
+        assert_eq!(
+            settings.target_exposure(&single_bin(0.1, 1), 200.0),
+            Some(4.0)
+        );
+        assert_eq!(
+            settings.target_exposure(&single_bin(5000.0, 1), 200.0),
+            Some(0.25)
+        );
+
+        // One step covers `adaptation` of the distance in stops: 1.0 → 4.0 is
+        // two stops, a tenth of which is 0.2 stops.
+        let stepped = settings.adapt(1.0, 4.0);
+        assert!((stepped - 0.2f32.exp2()).abs() < 1e-5, "stepped {stepped}");
+        let instant = AutoExposure {
+            adaptation: 1.0,
+            ..settings
+        };
+        assert!((instant.adapt(1.0, 4.0) - 4.0).abs() < 1e-5);
+
+        let invalid = AutoExposure {
+            min: 2.0,
+            max: 1.0,
+            ..settings
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,238 @@
+// HDR10 pixel encoding: BT.2020 primaries, SMPTE ST 2084 (PQ) signal,
+// packed as R10G10B10A2 (10 bits per color, 2 bits of alpha, red in the low
+// bits of a little-endian u32).
+//
+// Conversions to and from scRGB on the CPU (HEIF export, saving and
+// analyzing `Rgb10a2` frames) and Hdr10DecodePass, which decodes 10-bit
+// surfaces on the GPU so they can be tone-mapped like RGBA16F ones.
+
+use anyhow::Result;
+use half::f16;
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_R16G16B16A16_FLOAT;
+
+use crate::d3d11::compute::{self, ComputeShader};
+
+use super::{ColorFrame, SCRGB_NITS};
+
+/// BT.709 → BT.2020 primaries (linear light).
+const BT709_TO_BT2020: [[f32; 3]; 3] = [
+    [0.627_404, 0.329_282, 0.043_314],
+    [0.069_097, 0.919_540, 0.011_361],
+    [0.016_392, 0.088_013, 0.895_595],
+];
+
+/// BT.2020 → BT.709 primaries (linear light), inverse of `BT709_TO_BT2020`.
+const BT2020_TO_BT709: [[f32; 3]; 3] = [
+    [1.660_491, -0.587_641, -0.072_850],
+    [-0.124_550, 1.132_9, -0.008_349],
+    [-0.018_151, -0.100_579, 1.118_73],
+];
+
+const M1: f32 = 0.159_301_76;
+const M2: f32 = 78.843_75;
+const C1: f32 = 0.835_937_5;
+const C2: f32 = 18.851_563;
+const C3: f32 = 18.687_5;
+
+/// SMPTE ST 2084 inverse EOTF: absolute nits → PQ signal (0..=1).
+pub fn pq_encode(nits: f32) -> f32 {
+    let y = (nits / 10_000.0).clamp(0.0, 1.0);
+    let yp = y.powf(M1);
+    ((C1 + C2 * yp) / (1.0 + C3 * yp)).powf(M2)
+}
+
+/// SMPTE ST 2084 EOTF: PQ signal (0..=1) → absolute nits.
+pub fn pq_decode(signal: f32) -> f32 {
+    let p = signal.clamp(0.0, 1.0).powf(1.0 / M2);
+    10_000.0 * ((p - C1).max(0.0) / (C2 - C3 * p)).powf(1.0 / M1)
+}
+
+/// Split a packed pixel into its 10-bit R, G, B codes and 2-bit alpha.
+pub fn unpack(pixel: [u8; 4]) -> [u16; 4] {
+    let v = u32::from_le_bytes(pixel);
+    [
+        (v & 1023) as u16,
+        ((v >> 10) & 1023) as u16,
+        ((v >> 20) & 1023) as u16,
+        (v >> 30) as u16,
+    ]
+}
+
+/// Pack 10-bit R, G, B codes and a 2-bit alpha (values are masked).
+pub fn pack([r, g, b, a]: [u16; 4]) -> [u8; 4] {
+    let v = (r as u32 & 1023)
+        | (g as u32 & 1023) << 10
+        | (b as u32 & 1023) << 20
+        | (a as u32 & 3) << 30;
+    v.to_le_bytes()
+}
+
+/// Decode one packed HDR10 pixel to linear scRGB `[r, g, b, a]`.
+pub fn pixel_to_scrgb(pixel: [u8; 4]) -> [f32; 4] {
+    let [r, g, b, a] = unpack(pixel);
+    let rgb2020 = [r, g, b].map(|code| pq_decode(code as f32 / 1023.0));
+    let [r, g, b] = BT2020_TO_BT709
+        .map(|row| (row[0] * rgb2020[0] + row[1] * rgb2020[1] + row[2] * rgb2020[2]) / SCRGB_NITS);
+    [r, g, b, a as f32 / 3.0]
+}
+
+/// Encode one linear scRGB pixel as packed HDR10.
+///
+/// PQ is absolute, so there is no SDR white normalization; colors outside
+/// BT.2020 and values above 10,000 nits are clamped.
+pub fn scrgb_to_pixel([r, g, b, a]: [f32; 4]) -> [u8; 4] {
+    let [r, g, b] = BT709_TO_BT2020.map(|row| {
+        let linear = row[0] * r + row[1] * g + row[2] * b;
+        (pq_encode(linear * SCRGB_NITS) * 1023.0).round() as u16
+    });
+    pack([r, g, b, (a.clamp(0.0, 1.0) * 3.0).round() as u16])
+}
+
+/// Convert scRGB half-float RGBA to packed HDR10.
+pub fn scrgb_to_hdr10(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2);
+    for px in data.chunks_exact(8) {
+        let ch = |i: usize| f16::from_le_bytes([px[i], px[i + 1]]).to_f32();
+        out.extend_from_slice(&scrgb_to_pixel([ch(0), ch(2), ch(4), ch(6)]));
+    }
+    out
+}
+
+/// Convert packed HDR10 to scRGB half-float RGBA (twice the size).
+pub fn hdr10_to_scrgb(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() * 2);
+    for px in data.chunks_exact(4) {
+        let rgba = pixel_to_scrgb([px[0], px[1], px[2], px[3]]);
+        for c in rgba {
+            out.extend_from_slice(&f16::from_f32(c).to_le_bytes());
+        }
+    }
+    out
+}
+
+/// GPU decode pass: HDR10 R10G10B10A2_UNORM → scRGB R16G16B16A16_FLOAT.
+///
+/// Output texture is lazily created and reused when dimensions match.
+pub struct Hdr10DecodePass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    width: u32,
+    height: u32,
+}
+
+impl Hdr10DecodePass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::HDR10_DECODE_HLSL, "main")?;
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            output_cache: None,
+        })
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height {
+                return Ok(());
+            }
+        }
+
+        let (texture, uav) =
+            compute::create_output(&self.device, width, height, DXGI_FORMAT_R16G16B16A16_FLOAT)?;
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            width,
+            height,
+        });
+        Ok(())
+    }
+
+    /// Decode an HDR10 frame, returning an scRGB RGBA16F texture.
+    pub fn execute(&mut self, input: &ColorFrame) -> Result<ID3D11Texture2D> {
+        self.ensure_output(input.width, input.height)?;
+
+        let srv = compute::create_srv(&self.device, &input.texture)?;
+        let cache = self.output_cache.as_ref().unwrap();
+        compute::dispatch(
+            &self.context,
+            &self.shader,
+            &srv,
+            &cache.uav,
+            input.width,
+            input.height,
+        );
+        Ok(cache.texture.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(r: f32, g: f32, b: f32) -> Vec<u8> {
+        [r, g, b, 1.0]
+            .iter()
+            .flat_map(|&c| f16::from_f32(c).to_le_bytes())
+            .collect()
+    }
+
+    fn unpack_bytes(word: &[u8]) -> [u16; 4] {
+        unpack(word.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_pq_reference_points() {
+        assert!(pq_encode(0.0) < 1e-6);
+        assert!((pq_encode(10_000.0) - 1.0).abs() < 1e-5);
+        // 100 nits ≈ PQ 0.508 (code 520 in 10-bit full range).
+        assert!((pq_encode(100.0) - 0.508).abs() < 0.002);
+        for nits in [0.5, 80.0, 203.0, 1000.0, 4000.0] {
+            let round_trip = pq_decode(pq_encode(nits));
+            assert!(
+                (round_trip - nits).abs() / nits < 1e-3,
+                "{nits}: {round_trip}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_hdr10_packing_white() {
+        // scRGB 1.25 = 100 nits, neutral → equal channels, opaque alpha.
+        let out = scrgb_to_hdr10(&pixel(1.25, 1.25, 1.25));
+        let [r, g, b, a] = unpack_bytes(&out);
+        assert!((r as i32 - 520).abs() <= 1, "r={r}");
+        assert_eq!(r, g);
+        assert_eq!(g, b);
+        assert_eq!(a, 3);
+    }
+
+    #[test]
+    fn test_hdr10_clamps_negative_and_overrange() {
+        let [r, g, b, _] = unpack_bytes(&scrgb_to_hdr10(&pixel(-1.0, -1.0, -1.0)));
+        assert_eq!([r, g, b], [0; 3]);
+        let [r, g, b, _] = unpack_bytes(&scrgb_to_hdr10(&pixel(500.0, 500.0, 500.0)));
+        assert_eq!([r, g, b], [1023; 3]);
+    }
+
+    #[test]
+    fn test_hdr10_decode_round_trip() {
+        let packed = scrgb_to_hdr10(&pixel(2.5, 0.5, 0.125));
+        let decoded = hdr10_to_scrgb(&packed);
+        let ch = |i: usize| f16::from_le_bytes([decoded[i], decoded[i + 1]]).to_f32();
+        // 10-bit PQ steps are ~1% apart in this range.
+        for (i, expected) in [(0, 2.5), (2, 0.5), (4, 0.125)] {
+            assert!((ch(i) - expected).abs() / expected < 0.02, "{i}: {}", ch(i));
+        }
+        assert_eq!(ch(6), 1.0);
+    }
+}
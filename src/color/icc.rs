@@ -0,0 +1,273 @@
+// ICC color profiles for SDR image output.
+//
+// PNG/JPEG written without a profile are interpreted by color-managed viewers
+// as "whatever the viewer's display is", which shifts colors on wide-gamut
+// panels. Frames are tagged with a ColorProfile and the encoder embeds it:
+// - Srgb: compact built-in sRGB v2 profile (generated once, ~2.6 KB)
+// - Icc: raw profile bytes (display profile or user-supplied file)
+// - Untagged: write no profile (legacy behavior)
+
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{bail, Context, Result};
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Graphics::Gdi::{CreateDCW, DeleteDC, HMONITOR};
+use windows::Win32::UI::ColorSystem::GetICMProfileW;
+
+use crate::capture::target::monitor_device_name;
+
+/// Color profile attached to SDR output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ColorProfile {
+    /// No profile is embedded.
+    Untagged,
+    /// Built-in sRGB (IEC 61966-2-1) profile.
+    #[default]
+    Srgb,
+    /// Arbitrary ICC profile bytes.
+    Icc(Arc<[u8]>),
+}
+
+impl ColorProfile {
+    /// Load an ICC profile from disk.
+    ///
+    /// Validates the header signature only; the profile is embedded verbatim.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read ICC profile '{}'", path.display()))?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Wrap raw ICC profile bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        if bytes.len() < 132 || &bytes[36..40] != b"acsp" {
+            bail!("not an ICC profile (missing 'acsp' signature)");
+        }
+        Ok(Self::Icc(bytes.into()))
+    }
+
+    /// Profile bytes to embed, or `None` for `Untagged`.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Untagged => None,
+            Self::Srgb => Some(srgb_profile()),
+            Self::Icc(bytes) => Some(bytes),
+        }
+    }
+}
+
+/// Query the ICC profile Windows associates with a monitor.
+///
+/// Returns `None` when no profile is associated or it cannot be read.
+pub fn query_display_profile(monitor: HMONITOR) -> Option<ColorProfile> {
+    let device_name = monitor_device_name(monitor).ok()?;
+    let path = icm_profile_path(&device_name)?;
+    let bytes = std::fs::read(path).ok()?;
+    ColorProfile::from_bytes(bytes).ok()
+}
+
+/// Internal: device name → display DC → GetICMProfileW.
+fn icm_profile_path(device_name: &str) -> Option<String> {
+    let driver: Vec<u16> = "DISPLAY\0".encode_utf16().collect();
+    let device: Vec<u16> = device_name.encode_utf16().chain(Some(0)).collect();
+
+    // SAFETY: Both strings are NUL-terminated and outlive the calls. The DC is
+    // released with DeleteDC before returning.
+    unsafe {
+        let hdc = CreateDCW(
+            PCWSTR(driver.as_ptr()),
+            PCWSTR(device.as_ptr()),
+            PCWSTR::null(),
+            None,
+        );
+        if hdc.is_invalid() {
+            return None;
+        }
+
+        let mut buf = [0u16; 260];
+        let mut len = buf.len() as u32;
+        let ok = GetICMProfileW(hdc, &mut len, Some(PWSTR(buf.as_mut_ptr())));
+        let _ = DeleteDC(hdc);
+        if !ok.as_bool() {
+            return None;
+        }
+
+        let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+        Some(String::from_utf16_lossy(&buf[..end]))
+    }
+}
+
+/// Built-in sRGB ICC profile bytes.
+pub fn srgb_profile() -> &'static [u8] {
+    static PROFILE: OnceLock<Vec<u8>> = OnceLock::new();
+    PROFILE.get_or_init(build_srgb_profile)
+}
+
+// --- Profile construction (ICC.1:2001-04, version 2.1) ---
+
+/// D50 PCS illuminant.
+const D50: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// sRGB primaries, Bradford-adapted to D50.
+const SRGB_RED: [f64; 3] = [0.4360747, 0.2225045, 0.0139322];
+const SRGB_GREEN: [f64; 3] = [0.3850649, 0.7168786, 0.0971045];
+const SRGB_BLUE: [f64; 3] = [0.1430804, 0.0606169, 0.7141733];
+
+/// Number of entries in the sampled sRGB TRC.
+const TRC_ENTRIES: usize = 1024;
+
+fn s15_fixed16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz_tag(xyz: [f64; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for v in xyz {
+        tag.extend_from_slice(&s15_fixed16(v));
+    }
+    tag
+}
+
+fn desc_tag(text: &str) -> Vec<u8> {
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+    tag.extend_from_slice(text.as_bytes());
+    tag.push(0);
+    // Empty Unicode (language code + count) and ScriptCode (code + count + 67 bytes).
+    tag.extend_from_slice(&[0; 8]);
+    tag.extend_from_slice(&[0; 3]);
+    tag.extend_from_slice(&[0; 67]);
+    tag
+}
+
+fn text_tag(text: &str) -> Vec<u8> {
+    let mut tag = b"text\0\0\0\0".to_vec();
+    tag.extend_from_slice(text.as_bytes());
+    tag.push(0);
+    tag
+}
+
+fn srgb_trc_tag() -> Vec<u8> {
+    let mut tag = b"curv\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(TRC_ENTRIES as u32).to_be_bytes());
+    for i in 0..TRC_ENTRIES {
+        let v = i as f64 / (TRC_ENTRIES - 1) as f64;
+        let linear = if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        };
+        tag.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+    tag
+}
+
+fn build_srgb_profile() -> Vec<u8> {
+    let trc = srgb_trc_tag();
+    // rTRC/gTRC/bTRC share one curve; duplicate signatures point at the same data.
+    let tags: [(&[u8; 4], Vec<u8>); 9] = [
+        (b"desc", desc_tag("sRGB")),
+        (b"cprt", text_tag("No copyright, use freely")),
+        (b"wtpt", xyz_tag(D50)),
+        (b"rXYZ", xyz_tag(SRGB_RED)),
+        (b"gXYZ", xyz_tag(SRGB_GREEN)),
+        (b"bXYZ", xyz_tag(SRGB_BLUE)),
+        (b"rTRC", trc.clone()),
+        (b"gTRC", Vec::new()),
+        (b"bTRC", Vec::new()),
+    ];
+
+    let table_len = 4 + tags.len() * 12;
+    let mut table = Vec::with_capacity(table_len);
+    let mut data = Vec::new();
+    table.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+
+    let mut trc_offset = 0u32;
+    for (sig, body) in &tags {
+        let (offset, size) = if body.is_empty() {
+            (trc_offset, trc.len() as u32)
+        } else {
+            let offset = (128 + table_len + data.len()) as u32;
+            data.extend_from_slice(body);
+            // Tag data is 4-byte aligned.
+            while data.len() % 4 != 0 {
+                data.push(0);
+            }
+            (offset, body.len() as u32)
+        };
+        if *sig == b"rTRC" {
+            trc_offset = offset;
+        }
+        table.extend_from_slice(*sig);
+        table.extend_from_slice(&offset.to_be_bytes());
+        table.extend_from_slice(&size.to_be_bytes());
+    }
+
+    let total = 128 + table.len() + data.len();
+    let mut header = Vec::with_capacity(128);
+    header.extend_from_slice(&(total as u32).to_be_bytes());
+    header.extend_from_slice(&[0; 4]); // CMM type
+    header.extend_from_slice(&[0x02, 0x10, 0x00, 0x00]); // version 2.1
+    header.extend_from_slice(b"mntr");
+    header.extend_from_slice(b"RGB ");
+    header.extend_from_slice(b"XYZ ");
+    for v in [2024u16, 1, 1, 0, 0, 0] {
+        header.extend_from_slice(&v.to_be_bytes());
+    }
+    header.extend_from_slice(b"acsp");
+    header.extend_from_slice(b"MSFT");
+    header.extend_from_slice(&[0; 4]); // flags
+    header.extend_from_slice(&[0; 8]); // manufacturer + model
+    header.extend_from_slice(&[0; 8]); // attributes
+    header.extend_from_slice(&[0; 4]); // rendering intent: perceptual
+    for v in D50 {
+        header.extend_from_slice(&s15_fixed16(v));
+    }
+    header.extend_from_slice(&[0; 4]); // creator
+    header.resize(128, 0);
+
+    let mut profile = header;
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn be_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_srgb_profile_header() {
+        let profile = srgb_profile();
+        assert_eq!(be_u32(profile, 0) as usize, profile.len());
+        assert_eq!(&profile[36..40], b"acsp");
+        assert_eq!(&profile[12..16], b"mntr");
+        assert_eq!(&profile[16..20], b"RGB ");
+    }
+
+    #[test]
+    fn test_srgb_profile_tags_in_bounds() {
+        let profile = srgb_profile();
+        let count = be_u32(profile, 128) as usize;
+        assert_eq!(count, 9);
+        for i in 0..count {
+            let entry = 132 + i * 12;
+            let offset = be_u32(profile, entry + 4) as usize;
+            let size = be_u32(profile, entry + 8) as usize;
+            assert_eq!(offset % 4, 0);
+            assert!(offset + size <= profile.len());
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_non_icc() {
+        assert!(ColorProfile::from_bytes(vec![0; 200]).is_err());
+        assert!(ColorProfile::from_bytes(srgb_profile().to_vec()).is_ok());
+    }
+}
@@ -0,0 +1,149 @@
+// Luminance statistics for captured frames.
+//
+// CPU scan over readback data, run on demand so the capture path stays fast.
+// Rgba16f frames are scRGB (linear BT.709, 1.0 = 80 nits); Rgb10a2 frames are
+// HDR10 (absolute PQ); BGRA8 frames are sRGB-encoded with 1.0 = SDR white.
+
+use half::f16;
+
+use super::{hdr10, ColorPixelFormat, SCRGB_NITS};
+
+/// Luminance summary of one frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameLuminanceStats {
+    /// Brightest pixel luminance (nits).
+    pub max_nits: f32,
+    /// 99th percentile pixel luminance (nits).
+    pub p99_nits: f32,
+    /// Fraction of pixels brighter than the SDR white level (0.0..=1.0).
+    pub fraction_above_sdr: f32,
+}
+
+/// Compute luminance statistics from raw frame bytes.
+///
+/// `sdr_white_nits` is the display's SDR white level; BGRA8 pixels are scaled
+/// so that 255 maps to it, and it is the threshold for `fraction_above_sdr`.
+pub fn frame_stats(
+    data: &[u8],
+    format: ColorPixelFormat,
+    sdr_white_nits: f32,
+) -> FrameLuminanceStats {
+    let mut nits: Vec<f32> = match format {
+        ColorPixelFormat::Rgba16f => data
+            .chunks_exact(8)
+            .map(|px| {
+                let ch = |i: usize| f16::from_le_bytes([px[i], px[i + 1]]).to_f32();
+                // f32::max also maps NaN (possible in scRGB) to 0.
+                luminance(ch(0), ch(2), ch(4)).max(0.0) * SCRGB_NITS
+            })
+            .collect(),
+        ColorPixelFormat::Rgb10a2 => data
+            .chunks_exact(4)
+            .map(|px| {
+                let [r, g, b, _] = hdr10::pixel_to_scrgb([px[0], px[1], px[2], px[3]]);
+                luminance(r, g, b).max(0.0) * SCRGB_NITS
+            })
+            .collect(),
+        ColorPixelFormat::Bgra8 => {
+            let lut: Vec<f32> = (0..=255u8)
+                .map(|v| srgb_to_linear(v as f32 / 255.0))
+                .collect();
+            data.chunks_exact(4)
+                .map(|px| {
+                    let (b, g, r) = (
+                        lut[px[0] as usize],
+                        lut[px[1] as usize],
+                        lut[px[2] as usize],
+                    );
+                    luminance(r, g, b) * sdr_white_nits
+                })
+                .collect()
+        }
+    };
+
+    if nits.is_empty() {
+        return FrameLuminanceStats {
+            max_nits: 0.0,
+            p99_nits: 0.0,
+            fraction_above_sdr: 0.0,
+        };
+    }
+
+    let total = nits.len();
+    let max_nits = nits.iter().copied().fold(0.0, f32::max);
+    // Small tolerance so BGRA8 white (exactly sdr_white_nits) doesn't count as above.
+    let threshold = sdr_white_nits * 1.001;
+    let above = nits.iter().filter(|&&v| v > threshold).count();
+
+    let p99_index = ((total - 1) as f64 * 0.99).round() as usize;
+    let (_, p99, _) = nits.select_nth_unstable_by(p99_index, f32::total_cmp);
+
+    FrameLuminanceStats {
+        max_nits,
+        p99_nits: *p99,
+        fraction_above_sdr: above as f32 / total as f32,
+    }
+}
+
+/// BT.709 relative luminance of linear RGB.
+fn luminance(r: f32, g: f32, b: f32) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// sRGB transfer function (EOTF): encoded 0..=1 to linear light.
+pub(crate) fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba16f(pixels: &[[f32; 4]]) -> Vec<u8> {
+        pixels
+            .iter()
+            .flat_map(|px| px.iter().flat_map(|&c| f16::from_f32(c).to_le_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_scrgb_stats() {
+        // 99 pixels at SDR white (scRGB 1.0 = 80 nits), one highlight at 1000 nits.
+        let mut pixels = vec![[1.0, 1.0, 1.0, 1.0]; 99];
+        pixels.push([12.5, 12.5, 12.5, 1.0]);
+        let stats = frame_stats(&rgba16f(&pixels), ColorPixelFormat::Rgba16f, 80.0);
+
+        assert!((stats.max_nits - 1000.0).abs() < 1.0, "{stats:?}");
+        assert!((stats.p99_nits - 80.0).abs() < 0.1, "{stats:?}");
+        assert!((stats.fraction_above_sdr - 0.01).abs() < 1e-6, "{stats:?}");
+    }
+
+    #[test]
+    fn test_hdr10_stats() {
+        let white_1000 = hdr10::scrgb_to_pixel([12.5, 12.5, 12.5, 1.0]);
+        let stats = frame_stats(&white_1000, ColorPixelFormat::Rgb10a2, 200.0);
+
+        assert!((stats.max_nits - 1000.0).abs() < 10.0, "{stats:?}");
+        assert_eq!(stats.fraction_above_sdr, 1.0);
+    }
+
+    #[test]
+    fn test_bgra8_never_above_sdr() {
+        let data = [255u8, 255, 255, 255].repeat(16);
+        let stats = frame_stats(&data, ColorPixelFormat::Bgra8, 200.0);
+
+        assert!((stats.max_nits - 200.0).abs() < 0.01, "{stats:?}");
+        assert_eq!(stats.fraction_above_sdr, 0.0);
+    }
+
+    #[test]
+    fn test_empty_frame() {
+        let stats = frame_stats(&[], ColorPixelFormat::Rgba16f, 80.0);
+        assert_eq!(stats.max_nits, 0.0);
+        assert_eq!(stats.fraction_above_sdr, 0.0);
+    }
+}
@@ -0,0 +1,377 @@
+// 3D LUT grading stage.
+//
+// CubeLut parses Adobe/Resolve `.cube` files (3D only). LutPass uploads the
+// table into a 3D texture once and applies it to BGRA8 frames with trilinear
+// sampling, after HDR→SDR conversion so the LUT sees display-encoded color.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use half::f16;
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::{
+    DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R16G16B16A16_FLOAT,
+};
+
+use crate::d3d11::compute::{self, ComputeShader};
+
+/// Largest accepted `LUT_3D_SIZE` (the .cube spec maximum).
+const MAX_LUT_SIZE: u32 = 256;
+
+/// Parsed 3D `.cube` LUT.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeLut {
+    /// Entries per axis.
+    pub size: u32,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    /// `size³` RGB entries, red index varying fastest.
+    pub table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Load and parse a `.cube` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read LUT '{}'", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid LUT '{}'", path.display()))
+    }
+
+    /// Parse `.cube` text.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let keyword = parts.next().unwrap_or_default();
+            let line_no = line_no + 1;
+
+            match keyword {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => bail!("line {}: 1D LUTs are not supported", line_no),
+                "LUT_3D_SIZE" => {
+                    let n: u32 = parts
+                        .next()
+                        .and_then(|v| v.parse().ok())
+                        .with_context(|| format!("line {}: invalid LUT_3D_SIZE", line_no))?;
+                    if !(2..=MAX_LUT_SIZE).contains(&n) {
+                        bail!(
+                            "line {}: LUT_3D_SIZE {} out of range (2..={})",
+                            line_no,
+                            n,
+                            MAX_LUT_SIZE
+                        );
+                    }
+                    size = Some(n);
+                }
+                "DOMAIN_MIN" => domain_min = parse_triplet(parts, line_no)?,
+                "DOMAIN_MAX" => domain_max = parse_triplet(parts, line_no)?,
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                    let rgb = parse_triplet(line.split_whitespace(), line_no)?;
+                    table.push(rgb);
+                }
+                // Unknown keywords (e.g. LUT_IN_VIDEO_RANGE) are ignored per spec.
+                _ => {}
+            }
+        }
+
+        let size = size.context("missing LUT_3D_SIZE")?;
+        let expected = (size as usize).pow(3);
+        if table.len() != expected {
+            bail!(
+                "expected {} entries for LUT_3D_SIZE {}, found {}",
+                expected,
+                size,
+                table.len()
+            );
+        }
+        if (0..3).any(|i| domain_max[i] <= domain_min[i]) {
+            bail!("DOMAIN_MAX must be greater than DOMAIN_MIN on every channel");
+        }
+
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+}
+
+fn parse_triplet<'a>(mut parts: impl Iterator<Item = &'a str>, line_no: usize) -> Result<[f32; 3]> {
+    let mut out = [0.0; 3];
+    for v in &mut out {
+        *v = parts
+            .next()
+            .and_then(|s| s.parse().ok())
+            .with_context(|| format!("line {}: expected three numbers", line_no))?;
+    }
+    Ok(out)
+}
+
+/// Constant buffer layout matching HLSL `LutParams`.
+#[repr(C)]
+struct LutParams {
+    domain_min: [f32; 3],
+    lut_size: f32,
+    domain_max: [f32; 3],
+    _pad: f32,
+}
+
+/// GPU LUT pass: BGRA8 → graded BGRA8.
+///
+/// The LUT texture is immutable; load a new pass to change the LUT.
+pub struct LutPass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    cbuffer: ID3D11Buffer,
+    lut_srv: ID3D11ShaderResourceView,
+    sampler: ID3D11SamplerState,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    width: u32,
+    height: u32,
+}
+
+impl LutPass {
+    pub fn new(
+        device: &ID3D11Device,
+        context: &ID3D11DeviceContext,
+        lut: &CubeLut,
+    ) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::LUT_APPLY_HLSL, "main")?;
+
+        let params = LutParams {
+            domain_min: lut.domain_min,
+            lut_size: lut.size as f32,
+            domain_max: lut.domain_max,
+            _pad: 0.0,
+        };
+        let cb_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of::<LutParams>() as u32,
+            Usage: D3D11_USAGE_IMMUTABLE,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+        let cb_data = D3D11_SUBRESOURCE_DATA {
+            pSysMem: &params as *const _ as *const _,
+            SysMemPitch: 0,
+            SysMemSlicePitch: 0,
+        };
+
+        // SAFETY: cb_desc and cb_data are fully initialized; params outlives the call.
+        let cbuffer = unsafe {
+            let mut buf = None;
+            device
+                .CreateBuffer(&cb_desc, Some(&cb_data), Some(&mut buf))
+                .context("CreateBuffer for LUT cbuffer failed")?;
+            buf.unwrap()
+        };
+
+        let lut_srv = create_lut_texture(device, lut)?;
+
+        let sampler_desc = D3D11_SAMPLER_DESC {
+            Filter: D3D11_FILTER_MIN_MAG_MIP_LINEAR,
+            AddressU: D3D11_TEXTURE_ADDRESS_CLAMP,
+            AddressV: D3D11_TEXTURE_ADDRESS_CLAMP,
+            AddressW: D3D11_TEXTURE_ADDRESS_CLAMP,
+            MaxLOD: f32::MAX,
+            ..Default::default()
+        };
+        // SAFETY: sampler_desc is fully initialized.
+        let sampler = unsafe {
+            let mut s = None;
+            device
+                .CreateSamplerState(&sampler_desc, Some(&mut s))
+                .context("CreateSamplerState for LUT failed")?;
+            s.unwrap()
+        };
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            cbuffer,
+            lut_srv,
+            sampler,
+            output_cache: None,
+        })
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height {
+                return Ok(());
+            }
+        }
+
+        let (texture, uav) =
+            compute::create_output(&self.device, width, height, DXGI_FORMAT_B8G8R8A8_UNORM)?;
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            width,
+            height,
+        });
+        Ok(())
+    }
+
+    /// Apply the LUT to a BGRA8 texture, returning a new BGRA8 texture.
+    pub fn execute(
+        &mut self,
+        input: &ID3D11Texture2D,
+        width: u32,
+        height: u32,
+    ) -> Result<ID3D11Texture2D> {
+        self.ensure_output(width, height)?;
+
+        let srv = compute::create_srv(&self.device, input)?;
+        let cache = self.output_cache.as_ref().unwrap();
+
+        // SAFETY: All bound objects are valid; slots match the HLSL registers
+        // (b0, t1, s0). t0/u0 are bound by compute::dispatch.
+        unsafe {
+            self.context
+                .CSSetConstantBuffers(0, Some(&[Some(self.cbuffer.clone())]));
+            self.context
+                .CSSetShaderResources(1, Some(&[Some(self.lut_srv.clone())]));
+            self.context
+                .CSSetSamplers(0, Some(&[Some(self.sampler.clone())]));
+        }
+
+        compute::dispatch(&self.context, &self.shader, &srv, &cache.uav, width, height);
+
+        // SAFETY: Unbinding prevents resource hazards.
+        unsafe {
+            let no_cb: [Option<ID3D11Buffer>; 1] = [None];
+            let no_srv: [Option<ID3D11ShaderResourceView>; 1] = [None];
+            let no_sampler: [Option<ID3D11SamplerState>; 1] = [None];
+            self.context.CSSetConstantBuffers(0, Some(&no_cb));
+            self.context.CSSetShaderResources(1, Some(&no_srv));
+            self.context.CSSetSamplers(0, Some(&no_sampler));
+        }
+
+        Ok(cache.texture.clone())
+    }
+}
+
+/// Upload the LUT table as an immutable RGBA16F 3D texture.
+fn create_lut_texture(device: &ID3D11Device, lut: &CubeLut) -> Result<ID3D11ShaderResourceView> {
+    let n = lut.size;
+    let texels: Vec<f16> = lut
+        .table
+        .iter()
+        .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 1.0])
+        .map(f16::from_f32)
+        .collect();
+
+    let desc = D3D11_TEXTURE3D_DESC {
+        Width: n,
+        Height: n,
+        Depth: n,
+        MipLevels: 1,
+        Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
+        Usage: D3D11_USAGE_IMMUTABLE,
+        BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+        CPUAccessFlags: 0,
+        MiscFlags: 0,
+    };
+    // .cube order (red fastest, then green, then blue) maps directly onto
+    // x / y / z of the 3D texture.
+    let data = D3D11_SUBRESOURCE_DATA {
+        pSysMem: texels.as_ptr() as *const _,
+        SysMemPitch: n * 8,
+        SysMemSlicePitch: n * n * 8,
+    };
+
+    // SAFETY: desc is fully initialized; texels holds n³ RGBA16F texels and
+    // outlives the call. The SRV references the created texture.
+    unsafe {
+        let mut tex = None;
+        device
+            .CreateTexture3D(&desc, Some(&data), Some(&mut tex))
+            .context("CreateTexture3D for LUT failed")?;
+        let tex = tex.unwrap();
+
+        let mut srv = None;
+        device
+            .CreateShaderResourceView(&tex, None, Some(&mut srv))
+            .context("CreateShaderResourceView for LUT failed")?;
+        Ok(srv.unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTITY_2: &str = "\
+TITLE \"identity\"
+# comment
+LUT_3D_SIZE 2
+
+0 0 0
+1 0 0
+0 1 0
+1 1 0
+0 0 1
+1 0 1
+0 1 1
+1 1 1
+";
+
+    #[test]
+    fn test_parse_identity_cube() {
+        let lut = CubeLut::parse(IDENTITY_2).unwrap();
+        assert_eq!(lut.size, 2);
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+        assert_eq!(lut.table.len(), 8);
+        // Red varies fastest.
+        assert_eq!(lut.table[1], [1.0, 0.0, 0.0]);
+        assert_eq!(lut.table[2], [0.0, 1.0, 0.0]);
+        assert_eq!(lut.table[4], [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_parse_domain() {
+        let text = IDENTITY_2.replace(
+            "LUT_3D_SIZE 2",
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2",
+        );
+        let lut = CubeLut::parse(&text).unwrap();
+        assert_eq!(lut.domain_max, [2.0; 3]);
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_entry_count() {
+        let text = IDENTITY_2.replace("1 1 1\n", "");
+        let err = CubeLut::parse(&text).unwrap_err();
+        assert!(err.to_string().contains("expected 8 entries"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_1d() {
+        assert!(CubeLut::parse("LUT_1D_SIZE 4\n0 0 0\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_requires_size() {
+        assert!(CubeLut::parse("0 0 0\n1 1 1\n").is_err());
+    }
+}
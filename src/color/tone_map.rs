@@ -4,20 +4,52 @@
 // process() dispatches the compute shader for Auto+Rgba16f frames,
 // passes through all other combinations unchanged.
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use half::f16;
+use windows::Win32::Graphics::Direct3D::{D3D_SIT_CBUFFER, D3D_SIT_TEXTURE, D3D_SIT_UAV_RWTYPED};
 use windows::Win32::Graphics::Direct3D11::*;
-use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM;
+use windows::Win32::Graphics::Dxgi::Common::{
+    DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R16G16B16A16_FLOAT, DXGI_SAMPLE_DESC,
+};
 
 use crate::capture::CapturePolicy;
-use crate::d3d11::compute::{self, ComputeShader};
+use crate::d3d11::compute::{self, ComputeShader, ThreadGroupSize};
+use crate::d3d11::texture::TextureReader;
+use crate::d3d11::{create_d3d11_device, D3D11Context};
 
+use super::exposure::{AutoExposure, HistogramPass};
+use super::hdr10::Hdr10DecodePass;
 use super::{ColorFrame, ColorPixelFormat};
 
 /// Constant buffer layout matching HLSL `ToneMapParams`.
 #[repr(C)]
 struct ToneMapParams {
     sdr_white_nits: f32,
-    _pad: [f32; 3],
+    exposure: f32,
+    _pad: [f32; 2],
+}
+
+/// Built-in HDR→SDR tone-map curves (see [`crate::shader`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapOperator {
+    /// DWM-equivalent: hard clip at SDR white, then sRGB encode.
+    Clip,
+    /// Linear up to a knee, then a soft shoulder roll-off.
+    Shoulder,
+    /// BT.2390 EETF (the pipeline default).
+    #[default]
+    Eetf,
+}
+
+impl ToneMapOperator {
+    /// HLSL source of the operator's compute shader.
+    pub fn hlsl(self) -> &'static str {
+        match self {
+            Self::Clip => crate::shader::HDR_TONEMAP_HLSL,
+            Self::Shoulder => crate::shader::HDR_TONEMAP_SHOULDER_HLSL,
+            Self::Eetf => crate::shader::HDR_TONEMAP_EETF_HLSL,
+        }
+    }
 }
 
 /// GPU tone-map pass: scRGB R16G16B16A16_FLOAT → BGRA8.
@@ -27,10 +59,28 @@ struct ToneMapParams {
 pub struct ToneMapPass {
     device: ID3D11Device,
     context: ID3D11DeviceContext,
+    /// HLSL source, kept to recompile for another thread group size.
+    hlsl: String,
     shader: ComputeShader,
+    /// Benchmark thread group sizes on the next `execute` (see `with_auto_tune`).
+    auto_tune: bool,
     cbuffer: ID3D11Buffer,
     /// Cached output texture + UAV, rebuilt on size change.
     output_cache: Option<OutputCache>,
+    /// HDR10 → scRGB decode ahead of the shader, created on the first
+    /// `Rgb10a2` input.
+    hdr10_decode: Option<Hdr10DecodePass>,
+    /// Multiplier applied to scRGB input before the curve (1.0 = none).
+    exposure: f32,
+    /// Per-frame exposure from a luminance histogram (Some when enabled).
+    auto_exposure: Option<AutoExposure>,
+    /// Histogram pass for `auto_exposure`, created on first use.
+    histogram: Option<HistogramPass>,
+    /// Whether `exposure` has adapted to a frame since auto-exposure was
+    /// enabled; the first frame jumps straight to its target.
+    exposure_primed: bool,
+    /// Exposure used instead of `exposure` while set (bracketing).
+    exposure_override: Option<f32>,
 }
 
 struct OutputCache {
@@ -43,18 +93,42 @@ struct OutputCache {
 impl ToneMapPass {
     /// Create a new tone-map pass with the default (BT.2390 EETF) shader.
     pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
-        Self::with_shader(device, context, crate::shader::HDR_TONEMAP_EETF_HLSL)
+        Self::with_operator(device, context, ToneMapOperator::default())
+    }
+
+    /// Create a tone-map pass running one of the built-in operators.
+    pub fn with_operator(
+        device: &ID3D11Device,
+        context: &ID3D11DeviceContext,
+        operator: ToneMapOperator,
+    ) -> Result<Self> {
+        Self::with_shader(device, context, operator.hlsl())
     }
 
     /// Create a tone-map pass with a custom HLSL shader source.
+    ///
+    /// The shader must define a `main` entry point and use the same interface
+    /// as the built-in shaders:
+    /// - `Texture2D<float4> : register(t0)` — scRGB input
+    /// - `RWTexture2D<float4> : register(u0)` — BGRA8 output
+    /// - optional `cbuffer : register(b0) { float sdr_white_nits; float exposure; float2 _pad; }`
+    ///   (`exposure` multiplies the input, see [`set_exposure`](Self::set_exposure);
+    ///   shaders that leave it in padding simply ignore exposure)
+    ///
+    /// Declaring `[numthreads(THREAD_GROUP_X, THREAD_GROUP_Y, 1)]` lets
+    /// [`set_thread_group`](Self::set_thread_group) and auto-tuning resize
+    /// the thread group; a literal `numthreads` is dispatched as declared.
+    ///
+    /// Compile errors carry the `D3DCompile` diagnostic text.
     pub fn with_shader(
         device: &ID3D11Device,
         context: &ID3D11DeviceContext,
         hlsl: &str,
     ) -> Result<Self> {
         let shader = ComputeShader::compile(device, hlsl, "main")?;
+        validate_bindings(&shader)?;
 
-        // Create constant buffer (16 bytes, one float + padding)
+        // Create constant buffer (16 bytes: white level, exposure, padding)
         let cb_desc = D3D11_BUFFER_DESC {
             ByteWidth: std::mem::size_of::<ToneMapParams>() as u32,
             Usage: D3D11_USAGE_DYNAMIC,
@@ -76,13 +150,123 @@ impl ToneMapPass {
         Ok(Self {
             device: device.clone(),
             context: context.clone(),
+            hlsl: hlsl.to_string(),
             shader,
+            auto_tune: false,
             cbuffer,
             output_cache: None,
+            hdr10_decode: None,
+            exposure: 1.0,
+            auto_exposure: None,
+            histogram: None,
+            exposure_primed: false,
+            exposure_override: None,
         })
     }
 
-    /// Update the constant buffer with the current SDR white level.
+    /// Pick the thread group size by benchmarking on the first frame.
+    ///
+    /// The first `execute` compiles the shader for each of
+    /// [`ThreadGroupSize::CANDIDATES`] (up to four `D3DCompile` runs) and
+    /// times a burst of dispatches of each on its input, waiting for the GPU
+    /// in between. That frame is slower by the sum of those compilations, which
+    /// can add up to tens or hundreds of milliseconds. The result is cached
+    /// per GPU, so later passes with the same shader compile once without
+    /// measuring.
+    pub fn with_auto_tune(mut self, enabled: bool) -> Self {
+        self.auto_tune = enabled;
+        self
+    }
+
+    /// Thread group size the shader runs with.
+    pub fn thread_group(&self) -> ThreadGroupSize {
+        self.shader.thread_group()
+    }
+
+    /// Recompile the shader for thread group `size` (default 8×8).
+    ///
+    /// On error the pass keeps its current shader.
+    pub fn set_thread_group(&mut self, size: ThreadGroupSize) -> Result<()> {
+        let shader = ComputeShader::compile_with(&self.device, &self.hlsl, "main", size)?;
+        validate_bindings(&shader)?;
+        self.shader = shader;
+        self.auto_tune = false;
+        Ok(())
+    }
+
+    /// Exposure multiplier applied to the scRGB input (1.0 by default).
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    /// Scale the scRGB input by `exposure` before the tone curve.
+    ///
+    /// Replaced on every frame while auto-exposure is on.
+    pub fn set_exposure(&mut self, exposure: f32) -> Result<()> {
+        if !(exposure.is_finite() && exposure > 0.0) {
+            bail!("exposure must be positive, got {exposure}");
+        }
+        self.exposure = exposure;
+        Ok(())
+    }
+
+    /// Derive the exposure from each frame's luminance histogram (`None`
+    /// turns it off and returns to exposure 1.0).
+    ///
+    /// Costs one small compute dispatch and a 256-byte copy per frame. The
+    /// copy is read back without waiting, so each frame adapts to the
+    /// histogram of a frame or two before; only the first frame after
+    /// enabling (until one isn't black) waits for its own.
+    pub fn set_auto_exposure(&mut self, settings: Option<AutoExposure>) -> Result<()> {
+        if let Some(settings) = &settings {
+            settings.validate()?;
+        } else if self.auto_exposure.is_some() {
+            self.exposure = 1.0;
+        }
+        self.auto_exposure = settings;
+        self.exposure_primed = false;
+        Ok(())
+    }
+
+    /// Tone-map with `exposure` instead of the pass's own until cleared with
+    /// `None`, e.g. to render one frame at several exposures.
+    ///
+    /// [`exposure`](Self::exposure) and its auto-exposure adaptation are
+    /// left alone meanwhile: no histogram is taken.
+    pub fn set_exposure_override(&mut self, exposure: Option<f32>) -> Result<()> {
+        if let Some(exposure) = exposure {
+            if !(exposure.is_finite() && exposure > 0.0) {
+                bail!("exposure must be positive, got {exposure}");
+            }
+        }
+        self.exposure_override = exposure;
+        Ok(())
+    }
+
+    /// Adapt `exposure` toward the target for `input` (scRGB).
+    fn update_exposure(&mut self, input: &ID3D11Texture2D, sdr_white_nits: f32) -> Result<()> {
+        let Some(settings) = self.auto_exposure else {
+            return Ok(());
+        };
+        if self.histogram.is_none() {
+            self.histogram = Some(HistogramPass::new(&self.device, &self.context)?);
+        }
+        let pass = self.histogram.as_mut().unwrap();
+        let Some(histogram) = pass.execute(input, !self.exposure_primed)? else {
+            return Ok(());
+        };
+        if let Some(target) = settings.target_exposure(&histogram, sdr_white_nits) {
+            self.exposure = if self.exposure_primed {
+                settings.adapt(self.exposure, target)
+            } else {
+                target
+            };
+            self.exposure_primed = true;
+        }
+        Ok(())
+    }
+
+    /// Update the constant buffer with the current SDR white level and exposure.
     fn update_cbuffer(&self, sdr_white_nits: f32) -> Result<()> {
         // SAFETY: Map/Unmap pattern for DYNAMIC buffer with WRITE_DISCARD.
         // The buffer is 16 bytes, matching ToneMapParams layout.
@@ -100,6 +284,7 @@ impl ToneMapPass {
 
             let params = mapped.pData as *mut ToneMapParams;
             (*params).sdr_white_nits = sdr_white_nits;
+            (*params).exposure = self.exposure_override.unwrap_or(self.exposure);
 
             self.context.Unmap(&self.cbuffer, 0);
         }
@@ -128,10 +313,28 @@ impl ToneMapPass {
 
     /// Execute tone-map: scRGB float16 input → BGRA8 output texture.
     ///
-    /// Returns the output texture. The input frame's texture must remain
-    /// valid until this call returns (GPU work is synchronous on immediate context).
+    /// `Rgb10a2` (HDR10) input is decoded to scRGB first, so the shader always
+    /// sees scRGB. Returns the output texture. The input frame's texture must
+    /// remain valid until this call returns (GPU work is synchronous on
+    /// immediate context).
     pub fn execute(&mut self, input: &ColorFrame, sdr_white_nits: f32) -> Result<ID3D11Texture2D> {
+        if input.format == ColorPixelFormat::Rgb10a2 {
+            if self.hdr10_decode.is_none() {
+                self.hdr10_decode = Some(Hdr10DecodePass::new(&self.device, &self.context)?);
+            }
+            let decode = self.hdr10_decode.as_mut().unwrap();
+            let scrgb = ColorFrame {
+                texture: decode.execute(input)?,
+                format: ColorPixelFormat::Rgba16f,
+                ..*input
+            };
+            return self.execute(&scrgb, sdr_white_nits);
+        }
+
         self.ensure_output(input.width, input.height)?;
+        if self.exposure_override.is_none() {
+            self.update_exposure(&input.texture, sdr_white_nits)?;
+        }
         self.update_cbuffer(sdr_white_nits)?;
 
         let srv = compute::create_srv(&self.device, &input.texture)?;
@@ -144,6 +347,28 @@ impl ToneMapPass {
                 .CSSetConstantBuffers(0, Some(&[Some(self.cbuffer.clone())]));
         }
 
+        if std::mem::take(&mut self.auto_tune) {
+            let tuned =
+                compute::auto_tune(&self.device, &self.context, &self.hlsl, "main", |shader| {
+                    compute::dispatch(
+                        &self.context,
+                        shader,
+                        &srv,
+                        &cache.uav,
+                        input.width,
+                        input.height,
+                    );
+                    Ok(())
+                });
+            match tuned {
+                Ok(shader) => self.shader = shader,
+                Err(e) => log::warn!(
+                    "tone-map auto-tune failed, keeping {}: {e:#}",
+                    self.thread_group()
+                ),
+            }
+        }
+
         compute::dispatch(
             &self.context,
             &self.shader,
@@ -164,9 +389,38 @@ impl ToneMapPass {
     }
 }
 
+/// Check that a custom shader only uses the resources this pass binds.
+fn validate_bindings(shader: &ComputeShader) -> Result<()> {
+    let bindings = shader.bindings();
+    let has = |kind, slot| bindings.iter().any(|b| b.kind == kind && b.slot == slot);
+
+    if !has(D3D_SIT_TEXTURE, 0) {
+        bail!("tone-map shader must read its input from a Texture2D at register(t0)");
+    }
+    if !has(D3D_SIT_UAV_RWTYPED, 0) {
+        bail!("tone-map shader must write its output to a RWTexture2D at register(u0)");
+    }
+    for b in bindings {
+        let expected = matches!(
+            (b.kind, b.slot),
+            (D3D_SIT_TEXTURE, 0) | (D3D_SIT_UAV_RWTYPED, 0) | (D3D_SIT_CBUFFER, 0)
+        );
+        if !expected {
+            bail!(
+                "tone-map shader binds unsupported resource '{}' (type {}, slot {}); \
+                 only t0, u0 and b0 are bound by the pipeline",
+                b.name,
+                b.kind.0,
+                b.slot
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Color processing entry point.
 ///
-/// - `Auto + Rgba16f`: run GPU tone-map, output BGRA8 texture.
+/// - `Auto + Rgba16f` / `Auto + Rgb10a2`: run GPU tone-map, output BGRA8 texture.
 /// - All other combinations: pass-through unchanged.
 pub fn process(
     frame: ColorFrame,
@@ -175,9 +429,8 @@ pub fn process(
     sdr_white_nits: f32,
 ) -> Result<ColorFrame> {
     match (policy, frame.format) {
-        (CapturePolicy::Auto, ColorPixelFormat::Rgba16f) => {
-            let pass =
-                tone_map_pass.expect("ToneMapPass required for Auto+Rgba16f but not provided");
+        (CapturePolicy::Auto, ColorPixelFormat::Rgba16f | ColorPixelFormat::Rgb10a2) => {
+            let pass = tone_map_pass.expect("ToneMapPass required for Auto+HDR but not provided");
             let output_texture = pass.execute(&frame, sdr_white_nits)?;
             Ok(ColorFrame {
                 texture: output_texture,
@@ -191,14 +444,131 @@ pub fn process(
     }
 }
 
+/// Tone-map scRGB RGBA16F pixels already on the CPU to BGRA8.
+///
+/// Runs `operator` on a temporary D3D11 device, so it costs a device creation
+/// per call: meant for one-off conversions of captured frames (clipboard
+/// export, format conversion), not for per-frame use. With a device at hand,
+/// use [`tone_map_pixels_on`].
+pub fn tone_map_pixels(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    sdr_white_nits: f32,
+    operator: ToneMapOperator,
+) -> Result<Vec<u8>> {
+    let ctx = create_d3d11_device()?;
+    tone_map_pixels_on(&ctx, data, width, height, sdr_white_nits, operator)
+}
+
+/// Like [`tone_map_pixels`], on an existing device (e.g. a pipeline's).
+pub fn tone_map_pixels_on(
+    ctx: &D3D11Context,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    sdr_white_nits: f32,
+    operator: ToneMapOperator,
+) -> Result<Vec<u8>> {
+    let required = width as usize * height as usize * 8;
+    if data.len() < required {
+        bail!(
+            "RGBA16F buffer too small: required={}, got={}",
+            required,
+            data.len()
+        );
+    }
+
+    let desc = D3D11_TEXTURE2D_DESC {
+        Width: width,
+        Height: height,
+        MipLevels: 1,
+        ArraySize: 1,
+        Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: 1,
+            Quality: 0,
+        },
+        Usage: D3D11_USAGE_IMMUTABLE,
+        BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+        CPUAccessFlags: 0,
+        MiscFlags: 0,
+    };
+    let init = D3D11_SUBRESOURCE_DATA {
+        pSysMem: data.as_ptr() as *const _,
+        SysMemPitch: width * 8,
+        SysMemSlicePitch: 0,
+    };
+    // SAFETY: desc is fully initialized; init points to at least
+    // `width * 8 * height` bytes (checked above), read during the call.
+    let texture = unsafe {
+        let mut tex = None;
+        ctx.device
+            .CreateTexture2D(&desc, Some(&init), Some(&mut tex))
+            .context("CreateTexture2D for tone-map upload failed")?;
+        tex.unwrap()
+    };
+
+    let mut pass = ToneMapPass::with_operator(&ctx.device, &ctx.context, operator)?;
+    let output = pass.execute(
+        &ColorFrame {
+            texture,
+            width,
+            height,
+            timestamp: 0.0,
+            format: ColorPixelFormat::Rgba16f,
+        },
+        sdr_white_nits,
+    )?;
+    TextureReader::new(ctx.device.clone(), ctx.context.clone()).read_texture(&output)
+}
+
+/// Tone-map an scRGB RGBA16F buffer from any source to BGRA8.
+///
+/// Exposes the GPU tone-mapper without screen capture: `data` holds exactly
+/// `width * height` pixels of R, G, B, A in linear scRGB (1.0 = 80 nits), row
+/// after row. Same cost as [`tone_map_pixels`]: a D3D11 device per call.
+///
+/// # Examples
+/// ```no_run
+/// # use half::f16;
+/// # use hdrcapture::color::ToneMapOperator;
+/// let pixels = vec![f16::from_f32(2.0); 64 * 64 * 4];
+/// let bgra = hdrcapture::tonemap_buffer(&pixels, 64, 64, ToneMapOperator::Eetf, 240.0).unwrap();
+/// assert_eq!(bgra.len(), 64 * 64 * 4);
+/// ```
+pub fn tonemap_buffer(
+    data: &[f16],
+    width: u32,
+    height: u32,
+    operator: ToneMapOperator,
+    sdr_white_nits: f32,
+) -> Result<Vec<u8>> {
+    if width == 0 || height == 0 {
+        bail!("tonemap_buffer: width and height must be non-zero");
+    }
+    let required = width as usize * height as usize * 4;
+    if data.len() != required {
+        bail!(
+            "tonemap_buffer: {}x{} RGBA needs {} f16 values, got {}",
+            width,
+            height,
+            required,
+            data.len()
+        );
+    }
+    if !(sdr_white_nits.is_finite() && sdr_white_nits > 0.0) {
+        bail!("tonemap_buffer: sdr_white_nits must be positive, got {sdr_white_nits}");
+    }
+    // SAFETY: f16 is repr(transparent) over u16, so the slice is
+    // `data.len() * 2` initialized bytes; u8 has no alignment requirement.
+    let bytes = unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * 2) };
+    tone_map_pixels(bytes, width, height, sdr_white_nits, operator)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::d3d11::create_d3d11_device;
-    use crate::d3d11::texture::TextureReader;
-    use windows::Win32::Graphics::Dxgi::Common::{
-        DXGI_FORMAT_R16G16B16A16_FLOAT, DXGI_SAMPLE_DESC,
-    };
 
     /// Test tone-map pass with a known scRGB input.
     #[test]
@@ -280,4 +650,196 @@ mod tests {
         assert!(r > b, "R should be greater than B for orange input");
         assert!(a > 200, "A should be near 255");
     }
+
+    #[test]
+    fn test_tonemap_pass_thread_group() {
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let mut pass = ToneMapPass::new(&ctx.device, &ctx.context).expect("ToneMapPass creation");
+        assert_eq!(pass.thread_group(), ThreadGroupSize::default());
+
+        pass.set_thread_group(ThreadGroupSize::new(16, 16))
+            .expect("set_thread_group");
+        assert_eq!(pass.thread_group(), ThreadGroupSize::new(16, 16));
+        assert!(pass.set_thread_group(ThreadGroupSize::new(64, 64)).is_err());
+        assert_eq!(pass.thread_group(), ThreadGroupSize::new(16, 16));
+
+        // 37×23 doesn't divide evenly by any candidate size.
+        let data = vec![0x3C00u16.to_ne_bytes(); 37 * 23 * 4].concat();
+        let texture = upload_rgba16f(&ctx.device, &data, 37, 23);
+        let mut pass = pass.with_auto_tune(true);
+        let frame = ColorFrame {
+            texture,
+            width: 37,
+            height: 23,
+            timestamp: 0.0,
+            format: ColorPixelFormat::Rgba16f,
+        };
+        let output = pass.execute(&frame, 80.0).expect("Tone-map execute");
+        assert!(ThreadGroupSize::CANDIDATES.contains(&pass.thread_group()));
+
+        let mut reader = TextureReader::new(ctx.device.clone(), ctx.context.clone());
+        let out = reader.read_texture(&output).expect("Readback");
+        // Every pixel written, including the partial groups at the edges.
+        assert!(out.chunks_exact(4).all(|px| px[3] == 255));
+    }
+
+    #[test]
+    fn test_auto_exposure_brightens_dark_frame() {
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let mut pass = ToneMapPass::new(&ctx.device, &ctx.context).expect("ToneMapPass creation");
+        let mut reader = TextureReader::new(ctx.device.clone(), ctx.context.clone());
+
+        // Uniform 4-nit gray (scRGB 0.05), far below the 16-nit target at 80-nit white.
+        let pixel = [0.05, 0.05, 0.05, 1.0].map(|v| f16::from_f32(v).to_bits().to_ne_bytes());
+        let data = vec![pixel.concat(); 16 * 16].concat();
+        let frame = ColorFrame {
+            texture: upload_rgba16f(&ctx.device, &data, 16, 16),
+            width: 16,
+            height: 16,
+            timestamp: 0.0,
+            format: ColorPixelFormat::Rgba16f,
+        };
+
+        let plain = reader
+            .read_texture(&pass.execute(&frame, 80.0).expect("execute"))
+            .expect("Readback");
+        assert_eq!(pass.exposure(), 1.0);
+
+        pass.set_auto_exposure(Some(AutoExposure::default()))
+            .expect("set_auto_exposure");
+        let exposed = reader
+            .read_texture(&pass.execute(&frame, 80.0).expect("execute"))
+            .expect("Readback");
+        // First frame jumps to the target, capped at +2 stops.
+        assert!(pass.exposure() > 3.0, "exposure {}", pass.exposure());
+        assert!(exposed[0] > plain[0], "{} vs {}", exposed[0], plain[0]);
+
+        pass.set_auto_exposure(None).expect("disable");
+        assert_eq!(pass.exposure(), 1.0);
+    }
+
+    #[test]
+    fn test_histogram_reads_back_late() {
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let mut pass = HistogramPass::new(&ctx.device, &ctx.context).expect("HistogramPass");
+
+        // Uniform 80-nit white (scRGB 1.0).
+        let pixel = [1.0, 1.0, 1.0, 1.0].map(|v| f16::from_f32(v).to_bits().to_ne_bytes());
+        let data = vec![pixel.concat(); 16 * 16].concat();
+        let texture = upload_rgba16f(&ctx.device, &data, 16, 16);
+
+        let first = pass
+            .execute(&texture, true)
+            .expect("execute")
+            .expect("waited histogram");
+        assert_eq!(first.iter().sum::<u32>(), 16 * 16);
+
+        // The histogram just queued is never returned without waiting...
+        assert_eq!(pass.execute(&texture, false).expect("execute"), None);
+        // ...an earlier one is, once the GPU has finished it.
+        let late = (0..100)
+            .find_map(|_| {
+                unsafe { ctx.context.Flush() };
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                pass.execute(&texture, false).expect("execute")
+            })
+            .expect("late histogram");
+        assert_eq!(late, first);
+    }
+
+    fn upload_rgba16f(
+        device: &ID3D11Device,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> ID3D11Texture2D {
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: 0,
+        };
+        let init = D3D11_SUBRESOURCE_DATA {
+            pSysMem: data.as_ptr() as *const _,
+            SysMemPitch: width * 8,
+            SysMemSlicePitch: 0,
+        };
+        unsafe {
+            let mut tex = None;
+            device
+                .CreateTexture2D(&desc, Some(&init), Some(&mut tex))
+                .expect("Create input texture");
+            tex.unwrap()
+        }
+    }
+
+    #[test]
+    fn test_tone_map_pixels_matches_pass_output_size() {
+        // 3×2 scRGB orange, (1.0, 0.5, 0.0, 1.0).
+        let pixel: [u16; 4] = [0x3C00, 0x3800, 0x0000, 0x3C00];
+        let data: Vec<u8> = (0..6)
+            .flat_map(|_| pixel.iter().flat_map(|v| v.to_ne_bytes()))
+            .collect();
+
+        let out = tone_map_pixels(&data, 3, 2, 80.0, ToneMapOperator::default())
+            .expect("tone_map_pixels");
+        assert_eq!(out.len(), 3 * 2 * 4);
+        assert!(out[2] > out[0], "R should exceed B for orange input");
+        assert!(tone_map_pixels(&data[..8], 3, 2, 80.0, ToneMapOperator::Clip).is_err());
+    }
+
+    #[test]
+    fn test_tonemap_buffer_checks_dimensions() {
+        let data = vec![f16::ONE; 3 * 2 * 4];
+        let op = ToneMapOperator::Clip;
+        assert!(tonemap_buffer(&data[..20], 3, 2, op, 80.0).is_err());
+        assert!(tonemap_buffer(&data, 0, 2, op, 80.0).is_err());
+        assert!(tonemap_buffer(&data, 3, 2, op, 0.0).is_err());
+
+        let out = tonemap_buffer(&data, 3, 2, op, 80.0).expect("tonemap_buffer");
+        assert_eq!(out.len(), 3 * 2 * 4);
+    }
+
+    #[test]
+    fn test_with_shader_reports_compile_error() {
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let err = ToneMapPass::with_shader(&ctx.device, &ctx.context, "this is not hlsl")
+            .err()
+            .expect("invalid HLSL must fail");
+        assert!(
+            err.to_string().contains("Shader compilation failed"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_with_shader_rejects_missing_output_binding() {
+        const NO_OUTPUT_HLSL: &str = r#"
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u1);
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    OutputTexture[id.xy] = InputTexture[id.xy];
+}
+"#;
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let err = ToneMapPass::with_shader(&ctx.device, &ctx.context, NO_OUTPUT_HLSL)
+            .err()
+            .expect("u1 output must be rejected");
+        assert!(
+            err.to_string().contains("register(u0)"),
+            "unexpected error: {err}"
+        );
+    }
 }
@@ -1,13 +1,17 @@
 // SDR white level query via Windows DisplayConfig API.
 //
 // Returns the SDR content brightness (nits) configured for a given monitor.
-// Used to normalize scRGB pixel values before tone-mapping.
+// Used to normalize scRGB pixel values before tone-mapping. The same display
+// path also carries the active refresh rate, and the path order gives the
+// monitor numbering Windows shows in display settings.
 
 use windows::Win32::Devices::Display::{
     DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+    DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO,
     DISPLAYCONFIG_DEVICE_INFO_GET_SDR_WHITE_LEVEL, DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
-    DISPLAYCONFIG_DEVICE_INFO_HEADER, DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO,
-    DISPLAYCONFIG_SDR_WHITE_LEVEL, DISPLAYCONFIG_SOURCE_DEVICE_NAME, QDC_ONLY_ACTIVE_PATHS,
+    DISPLAYCONFIG_DEVICE_INFO_HEADER, DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO,
+    DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_SDR_WHITE_LEVEL,
+    DISPLAYCONFIG_SOURCE_DEVICE_NAME, QDC_ONLY_ACTIVE_PATHS,
 };
 use windows::Win32::Foundation::ERROR_SUCCESS;
 use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, HMONITOR, MONITORINFOEXW};
@@ -23,6 +27,49 @@ pub fn query_sdr_white_level(monitor: HMONITOR) -> f32 {
     get_sdr_white_nits(monitor).unwrap_or(DEFAULT_SDR_WHITE_NITS)
 }
 
+/// Query the active refresh rate (Hz) of the given monitor.
+///
+/// Reads the exact rational rate of the current mode from the DisplayConfig
+/// path (e.g. 59.94 rather than 60). DXGI only exposes the mode list, not the
+/// active mode, without a swap chain. Returns `None` if the query fails.
+pub fn query_refresh_rate(monitor: HMONITOR) -> Option<f64> {
+    let device_name = monitor_device_name(monitor)?;
+    let path = find_display_config_path(&device_name)?;
+    let rate = path.targetInfo.refreshRate;
+    if rate.Numerator == 0 || rate.Denominator == 0 {
+        return None;
+    }
+    Some(rate.Numerator as f64 / rate.Denominator as f64)
+}
+
+/// HDR state and SDR white level of an active display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayPathState {
+    /// Windows HDR is on for the display (advanced color enabled, not just
+    /// the wide-color mode of auto color management on an SDR display).
+    pub hdr: bool,
+    /// SDR white level in nits (`DEFAULT_SDR_WHITE_NITS` if unavailable).
+    pub sdr_white_nits: f32,
+}
+
+/// [`DisplayPathState`] of every active display with its GDI device name,
+/// from a single DisplayConfig query. Empty if the query fails.
+pub fn display_path_states() -> Vec<([u16; 32], DisplayPathState)> {
+    let Some(paths) = query_active_paths() else {
+        return Vec::new();
+    };
+    paths
+        .iter()
+        .filter_map(|path| {
+            let state = DisplayPathState {
+                hdr: query_hdr_from_path(path).unwrap_or(false),
+                sdr_white_nits: query_white_level_from_path(path).unwrap_or(DEFAULT_SDR_WHITE_NITS),
+            };
+            Some((source_device_name(path)?, state))
+        })
+        .collect()
+}
+
 /// Internal: resolve HMONITOR → device name → DisplayConfig path → SDR white level.
 fn get_sdr_white_nits(monitor: HMONITOR) -> Option<f32> {
     let device_name = monitor_device_name(monitor)?;
@@ -31,8 +78,8 @@ fn get_sdr_white_nits(monitor: HMONITOR) -> Option<f32> {
     Some(nits)
 }
 
-/// Get the GDI device name for a monitor handle.
-fn monitor_device_name(monitor: HMONITOR) -> Option<[u16; 32]> {
+/// Get the GDI device name for a monitor handle (NUL-padded UTF-16).
+pub fn monitor_device_name(monitor: HMONITOR) -> Option<[u16; 32]> {
     // SAFETY: GetMonitorInfoW writes to a caller-provided MONITORINFOEXW.
     // cbSize must be set correctly before the call.
     unsafe {
@@ -45,8 +92,26 @@ fn monitor_device_name(monitor: HMONITOR) -> Option<[u16; 32]> {
     }
 }
 
+/// GDI device names (e.g. `\\.\DISPLAY1`) of the active display paths, in
+/// DisplayConfig path order. `None` if the query fails.
+pub fn display_config_device_names() -> Option<Vec<[u16; 32]>> {
+    Some(
+        query_active_paths()?
+            .iter()
+            .filter_map(source_device_name)
+            .collect(),
+    )
+}
+
 /// Find the DISPLAYCONFIG_PATH_INFO matching a GDI device name.
 fn find_display_config_path(device_name: &[u16; 32]) -> Option<DISPLAYCONFIG_PATH_INFO> {
+    query_active_paths()?
+        .into_iter()
+        .find(|path| source_device_name(path).as_ref() == Some(device_name))
+}
+
+/// All active DisplayConfig paths, in the order Windows reports them.
+fn query_active_paths() -> Option<Vec<DISPLAYCONFIG_PATH_INFO>> {
     // SAFETY: GetDisplayConfigBufferSizes and QueryDisplayConfig are Win32 APIs
     // that write to caller-provided buffers. We allocate sufficient space based
     // on the returned counts.
@@ -74,30 +139,53 @@ fn find_display_config_path(device_name: &[u16; 32]) -> Option<DISPLAYCONFIG_PAT
             return None;
         }
         paths.truncate(num_paths as usize);
+        Some(paths)
+    }
+}
 
-        // Match path by source device name
-        for path in &paths {
-            let mut source_name = DISPLAYCONFIG_SOURCE_DEVICE_NAME {
-                header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
-                    r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
-                    size: std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32,
-                    adapterId: path.sourceInfo.adapterId,
-                    id: path.sourceInfo.id,
-                },
-                ..Default::default()
-            };
-
-            if DisplayConfigGetDeviceInfo(&mut source_name.header) != 0 {
-                continue;
-            }
+/// GDI device name of the source (desktop) side of a path.
+fn source_device_name(path: &DISPLAYCONFIG_PATH_INFO) -> Option<[u16; 32]> {
+    let mut source_name = DISPLAYCONFIG_SOURCE_DEVICE_NAME {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+            r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+            size: std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32,
+            adapterId: path.sourceInfo.adapterId,
+            id: path.sourceInfo.id,
+        },
+        ..Default::default()
+    };
+    // SAFETY: DisplayConfigGetDeviceInfo writes to a caller-provided struct
+    // whose header.size and header.type are set.
+    if unsafe { DisplayConfigGetDeviceInfo(&mut source_name.header) } != 0 {
+        return None;
+    }
+    Some(source_name.viewGdiDeviceName)
+}
 
-            if source_name.viewGdiDeviceName == *device_name {
-                return Some(*path);
-            }
+/// Query whether HDR is on for a resolved display config path.
+fn query_hdr_from_path(path: &DISPLAYCONFIG_PATH_INFO) -> Option<bool> {
+    /// `advancedColorEnabled` and `wideColorEnforced` bits of the info flags.
+    const ADVANCED_COLOR_ENABLED: u32 = 1 << 1;
+    const WIDE_COLOR_ENFORCED: u32 = 1 << 2;
+
+    let mut info = DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+            r#type: DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO,
+            size: std::mem::size_of::<DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO>() as u32,
+            adapterId: path.targetInfo.adapterId,
+            id: path.targetInfo.id,
+        },
+        ..Default::default()
+    };
+    // SAFETY: DisplayConfigGetDeviceInfo writes to a caller-provided struct
+    // whose header.size and header.type are set; the flags union is a u32.
+    unsafe {
+        if DisplayConfigGetDeviceInfo(&mut info.header) != 0 {
+            return None;
         }
+        let flags = info.Anonymous.value;
+        Some(flags & ADVANCED_COLOR_ENABLED != 0 && flags & WIDE_COLOR_ENFORCED == 0)
     }
-
-    None
 }
 
 /// Query SDR white level from a resolved display config path.
@@ -144,4 +232,13 @@ mod tests {
             nits
         );
     }
+
+    #[test]
+    fn test_query_refresh_rate() {
+        enable_dpi_awareness();
+        let monitor = find_monitor(0).expect("No monitor found");
+        let hz = query_refresh_rate(monitor).expect("refresh rate query failed");
+        println!("Monitor 0 refresh rate: {:.3} Hz", hz);
+        assert!((20.0..=1000.0).contains(&hz), "refresh rate {} Hz", hz);
+    }
 }
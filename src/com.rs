@@ -0,0 +1,51 @@
+// COM apartment setup.
+//
+// WGC (CreateForMonitor / CreateForWindow), the WinRT D3D device wrapper and
+// WIC all need COM on the calling thread. Threads this crate spawns join the
+// MTA for their lifetime; pipelines created on a caller's thread take a
+// reference on whatever apartment is there, or join the MTA if there is none.
+
+use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+
+/// RAII guard for COM initialization (MTA) on the current thread.
+///
+/// `CoUninitialize` runs on drop, so the guard must be dropped on the thread
+/// that created it and after every COM object it covers.
+pub(crate) struct ComGuard;
+
+impl ComGuard {
+    /// Join the MTA; fails if the thread is already in another apartment.
+    ///
+    /// For threads this crate spawns.
+    pub(crate) fn init_mta() -> windows::core::Result<Self> {
+        // SAFETY: CoInitializeEx initializes COM on this thread; paired with
+        // CoUninitialize in Drop (also after S_FALSE, which takes a reference).
+        unsafe { CoInitializeEx(None, COINIT_MULTITHREADED).ok()? };
+        Ok(Self)
+    }
+
+    /// Make sure COM is usable on a caller-owned thread.
+    ///
+    /// Joins the MTA (or takes another reference on it). A thread that is
+    /// already a single-threaded apartment (e.g. a UI thread) is left as is:
+    /// WGC and D3D11 work there too, and `None` is returned since there is
+    /// nothing to uninitialize.
+    pub(crate) fn ensure() -> windows::core::Result<Option<Self>> {
+        // SAFETY: as in init_mta; RPC_E_CHANGED_MODE takes no reference.
+        let hr = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        if hr == RPC_E_CHANGED_MODE {
+            log::debug!("thread is already single-threaded apartment; using it for capture");
+            return Ok(None);
+        }
+        hr.ok()?;
+        Ok(Some(Self))
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        // SAFETY: Paired with a successful CoInitializeEx in init_mta / ensure.
+        unsafe { CoUninitialize() };
+    }
+}
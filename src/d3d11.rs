@@ -1,6 +1,10 @@
 // D3D11 device creation and management
 
+pub mod checksum;
 pub mod compute;
+pub mod nv12;
+pub mod orient;
+pub mod scale;
 pub mod texture;
 
 use anyhow::Context;
@@ -22,13 +26,43 @@ pub struct D3D11Context {
 
 /// Create D3D11 device
 pub fn create_d3d11_device() -> anyhow::Result<D3D11Context> {
+    create_device(None)
+}
+
+/// Create D3D11 device on a specific DXGI adapter.
+///
+/// `index` follows `IDXGIFactory1::EnumAdapters1` order (`0` is usually the
+/// adapter driving the primary display). Useful on hybrid-GPU laptops to pick
+/// the integrated or discrete GPU explicitly.
+pub fn create_d3d11_device_on_adapter(index: usize) -> anyhow::Result<D3D11Context> {
+    // SAFETY: CreateDXGIFactory1 / EnumAdapters1 are plain COM factory calls;
+    // a missing index returns DXGI_ERROR_NOT_FOUND which maps to Err.
+    let adapter: IDXGIAdapter = unsafe {
+        let factory: IDXGIFactory1 =
+            CreateDXGIFactory1().context("Failed to create DXGI factory")?;
+        factory
+            .EnumAdapters1(index as u32)
+            .with_context(|| format!("DXGI adapter index {} not found", index))?
+            .cast()?
+    };
+    create_device(Some(&adapter))
+}
+
+fn create_device(adapter: Option<&IDXGIAdapter>) -> anyhow::Result<D3D11Context> {
+    // An explicit adapter requires D3D_DRIVER_TYPE_UNKNOWN.
+    let driver_type = if adapter.is_some() {
+        D3D_DRIVER_TYPE_UNKNOWN
+    } else {
+        D3D_DRIVER_TYPE_HARDWARE
+    };
+
     let (device, context) = unsafe {
         let mut device = None;
         let mut context = None;
 
         D3D11CreateDevice(
-            None,
-            D3D_DRIVER_TYPE_HARDWARE,
+            adapter,
+            driver_type,
             HMODULE::default(),
             D3D11_CREATE_DEVICE_BGRA_SUPPORT,
             Some(&[D3D_FEATURE_LEVEL_11_0]),
@@ -43,6 +77,7 @@ pub fn create_d3d11_device() -> anyhow::Result<D3D11Context> {
     };
 
     let dxgi_device: IDXGIDevice = device.cast().unwrap();
+    log_adapter_info(&dxgi_device);
 
     let direct3d_device: IDirect3DDevice = unsafe {
         // SAFETY: dxgi_device is a valid IDXGIDevice from D3D11CreateDevice.
@@ -60,6 +95,66 @@ pub fn create_d3d11_device() -> anyhow::Result<D3D11Context> {
     })
 }
 
+/// Find the DXGI output of the device's adapter that drives `hmonitor`.
+///
+/// Returns `None` when the monitor is attached to a different adapter
+/// (e.g. a render-only GPU on a hybrid laptop).
+pub fn find_output(
+    dxgi_device: &IDXGIDevice,
+    hmonitor: windows::Win32::Graphics::Gdi::HMONITOR,
+) -> anyhow::Result<Option<IDXGIOutput>> {
+    // SAFETY: GetAdapter / EnumOutputs / GetDesc only read adapter topology;
+    // EnumOutputs returns DXGI_ERROR_NOT_FOUND past the last output.
+    unsafe {
+        let adapter = dxgi_device.GetAdapter()?;
+        let mut i = 0;
+        while let Ok(output) = adapter.EnumOutputs(i) {
+            if output.GetDesc()?.Monitor == hmonitor {
+                return Ok(Some(output));
+            }
+            i += 1;
+        }
+    }
+    Ok(None)
+}
+
+/// DXGI outputs of every adapter, with the monitor each drives, without
+/// creating a device.
+pub fn all_outputs() -> anyhow::Result<Vec<(windows::Win32::Graphics::Gdi::HMONITOR, IDXGIOutput)>>
+{
+    let mut outputs = Vec::new();
+    // SAFETY: CreateDXGIFactory1 returns an owned factory; EnumAdapters1 /
+    // EnumOutputs / GetDesc only read adapter topology and return
+    // DXGI_ERROR_NOT_FOUND past the last entry.
+    unsafe {
+        let factory: IDXGIFactory1 = CreateDXGIFactory1()?;
+        let mut a = 0;
+        while let Ok(adapter) = factory.EnumAdapters1(a) {
+            let mut i = 0;
+            while let Ok(output) = adapter.EnumOutputs(i) {
+                outputs.push((output.GetDesc()?.Monitor, output));
+                i += 1;
+            }
+            a += 1;
+        }
+    }
+    Ok(outputs)
+}
+
+/// Log which GPU the device was created on (best-effort).
+fn log_adapter_info(dxgi_device: &IDXGIDevice) {
+    // SAFETY: GetAdapter / GetDesc only read adapter properties.
+    let Ok(desc) = (unsafe { dxgi_device.GetAdapter().and_then(|a| a.GetDesc()) }) else {
+        return;
+    };
+    let name = String::from_utf16_lossy(&desc.Description);
+    log::info!(
+        "D3D11 device created on {} ({} MB VRAM)",
+        name.trim_end_matches('\0'),
+        desc.DedicatedVideoMemory / 1024 / 1024
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,189 @@
+// GPU content checksum: detect unchanged frames without a full readback.
+//
+// A compute pass hashes every pixel and reduces the hashes into a 64-bit value
+// held in an 8-byte UAV buffer; only those 8 bytes are copied back to the CPU.
+// Against a 4K BGRA8 readback (~33 MB) this is a negligible transfer, so
+// mostly-static targets can skip readback for frames whose content matches.
+
+use anyhow::{Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_R32_TYPELESS;
+
+use super::compute::{self, ComputeShader};
+
+/// Size of the checksum result: two 32-bit lanes.
+const CHECKSUM_BYTES: u32 = 8;
+
+/// GPU checksum pass over a BGRA8 or RGBA16F texture.
+///
+/// Equal content always yields an equal checksum. Different content almost
+/// always changes it, but the hash is not collision-resistant, so a change
+/// can in rare cases go unnoticed.
+pub struct ChecksumPass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    result: ID3D11Buffer,
+    result_uav: ID3D11UnorderedAccessView,
+    staging: ID3D11Buffer,
+}
+
+impl ChecksumPass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::CHECKSUM_HLSL, "main")?;
+
+        let result_desc = D3D11_BUFFER_DESC {
+            ByteWidth: CHECKSUM_BYTES,
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_UNORDERED_ACCESS.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: D3D11_RESOURCE_MISC_BUFFER_ALLOW_RAW_VIEWS.0 as u32,
+            StructureByteStride: 0,
+        };
+        let staging_desc = D3D11_BUFFER_DESC {
+            ByteWidth: CHECKSUM_BYTES,
+            Usage: D3D11_USAGE_STAGING,
+            BindFlags: 0,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+        let uav_desc = D3D11_UNORDERED_ACCESS_VIEW_DESC {
+            Format: DXGI_FORMAT_R32_TYPELESS,
+            ViewDimension: D3D11_UAV_DIMENSION_BUFFER,
+            Anonymous: D3D11_UNORDERED_ACCESS_VIEW_DESC_0 {
+                Buffer: D3D11_BUFFER_UAV {
+                    FirstElement: 0,
+                    NumElements: CHECKSUM_BYTES / 4,
+                    Flags: D3D11_BUFFER_UAV_FLAG_RAW.0 as u32,
+                },
+            },
+        };
+
+        // SAFETY: descriptors are fully initialized; the UAV describes the raw
+        // result buffer created just before it.
+        let (result, result_uav, staging) = unsafe {
+            let mut result = None;
+            device
+                .CreateBuffer(&result_desc, None, Some(&mut result))
+                .context("CreateBuffer for checksum result failed")?;
+            let result = result.unwrap();
+
+            let mut uav = None;
+            device
+                .CreateUnorderedAccessView(&result, Some(&uav_desc), Some(&mut uav))
+                .context("CreateUnorderedAccessView for checksum result failed")?;
+
+            let mut staging = None;
+            device
+                .CreateBuffer(&staging_desc, None, Some(&mut staging))
+                .context("CreateBuffer for checksum staging failed")?;
+            (result, uav.unwrap(), staging.unwrap())
+        };
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            result,
+            result_uav,
+            staging,
+        })
+    }
+
+    /// Checksum of `input`'s content. Blocks until the GPU has finished.
+    pub fn execute(&mut self, input: &ID3D11Texture2D) -> Result<u64> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe {
+            input.GetDesc(&mut desc);
+        }
+
+        let srv = compute::create_srv(&self.device, input)?;
+        // SAFETY: the UAV is a valid view of `result`; clearing resets both lanes.
+        unsafe {
+            self.context
+                .ClearUnorderedAccessViewUint(&self.result_uav, &[0; 4]);
+        }
+        compute::dispatch(
+            &self.context,
+            &self.shader,
+            &srv,
+            &self.result_uav,
+            desc.Width,
+            desc.Height,
+        );
+
+        // SAFETY: CopyResource between equally sized buffers, then a blocking
+        // Map/Unmap of the 8-byte staging buffer.
+        unsafe {
+            self.context.CopyResource(&self.staging, &self.result);
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(&self.staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+                .context("Map checksum staging buffer failed")?;
+            let lanes = std::ptr::read_unaligned(mapped.pData as *const [u32; 2]);
+            self.context.Unmap(&self.staging, 0);
+            Ok(((lanes[1] as u64) << 32) | lanes[0] as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::d3d11::create_d3d11_device;
+    use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_SAMPLE_DESC};
+
+    fn bgra_texture(device: &ID3D11Device, pixels: &[u8], width: u32) -> ID3D11Texture2D {
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: pixels.len() as u32 / 4 / width,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: 0,
+        };
+        let init = D3D11_SUBRESOURCE_DATA {
+            pSysMem: pixels.as_ptr() as *const _,
+            SysMemPitch: width * 4,
+            SysMemSlicePitch: 0,
+        };
+        unsafe {
+            let mut tex = None;
+            device
+                .CreateTexture2D(&desc, Some(&init), Some(&mut tex))
+                .expect("Create input texture");
+            tex.unwrap()
+        }
+    }
+
+    #[test]
+    fn test_checksum_detects_single_pixel_change() {
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let mut pass = ChecksumPass::new(&ctx.device, &ctx.context).expect("ChecksumPass");
+
+        // 20×12 crosses thread group boundaries in both directions.
+        let mut pixels = vec![200u8; 20 * 12 * 4];
+        let a = pass
+            .execute(&bgra_texture(&ctx.device, &pixels, 20))
+            .unwrap();
+        let b = pass
+            .execute(&bgra_texture(&ctx.device, &pixels, 20))
+            .unwrap();
+        assert_eq!(a, b);
+
+        pixels[(11 * 20 + 19) * 4] = 201;
+        let c = pass
+            .execute(&bgra_texture(&ctx.device, &pixels, 20))
+            .unwrap();
+        assert_ne!(a, c);
+    }
+}
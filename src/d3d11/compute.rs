@@ -2,41 +2,153 @@
 //
 // Designed for single-pass image processing (tone-map, format conversion).
 // Reuses the existing D3D11Context device and immediate context.
+//
+// Thread group size: shaders may size `numthreads` with the THREAD_GROUP_X /
+// THREAD_GROUP_Y defines, set at compile time; `dispatch` uses the size
+// reflected from the bytecode, so shaders with literal sizes work unchanged.
+// `auto_tune` benchmarks a few sizes and caches the fastest per GPU.
+//
+// 8-bit SDR inputs can be sampled through an sRGB view (SrvFormat::SrgbDecode)
+// so filtering passes work in linear light instead of on encoded values.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
-use windows::core::PCSTR;
-use windows::Win32::Graphics::Direct3D::Fxc::{D3DCompile, D3DCOMPILE_OPTIMIZATION_LEVEL3};
-use windows::Win32::Graphics::Direct3D::ID3DBlob;
+use windows::core::{Interface, BOOL, PCSTR};
+use windows::Win32::Graphics::Direct3D::Fxc::{
+    D3DCompile, D3DReflect, D3DCOMPILE_OPTIMIZATION_LEVEL3,
+};
+use windows::Win32::Graphics::Direct3D::{
+    ID3DBlob, D3D11_SRV_DIMENSION_TEXTURE2D, D3D_SHADER_INPUT_TYPE, D3D_SHADER_MACRO,
+};
 use windows::Win32::Graphics::Direct3D11::*;
-use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT;
+use windows::Win32::Graphics::Dxgi::Common::*;
+use windows::Win32::Graphics::Dxgi::IDXGIDevice;
+
+/// Timed dispatches per candidate in [`auto_tune`].
+const TUNE_ITERATIONS: u32 = 16;
+
+/// Longest wait for the GPU to drain while tuning.
+const GPU_IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Compute thread group dimensions, `[numthreads(x, y, 1)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThreadGroupSize {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl ThreadGroupSize {
+    pub const fn new(x: u32, y: u32) -> Self {
+        Self { x, y }
+    }
+
+    /// Sizes [`auto_tune`] benchmarks: 8×8 (the default), 16×8, 16×16 and 32×8.
+    pub const CANDIDATES: [Self; 4] = [
+        Self::new(8, 8),
+        Self::new(16, 8),
+        Self::new(16, 16),
+        Self::new(32, 8),
+    ];
+
+    /// Check the size against the cs_5_0 limits (1024 threads per group).
+    pub fn validate(self) -> Result<()> {
+        if self.x == 0 || self.y == 0 {
+            bail!("thread group size must be at least 1x1, got {self}");
+        }
+        if self.x * self.y > D3D11_CS_THREAD_GROUP_MAX_THREADS_PER_GROUP {
+            bail!(
+                "thread group {self} exceeds {} threads per group",
+                D3D11_CS_THREAD_GROUP_MAX_THREADS_PER_GROUP
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Default for ThreadGroupSize {
+    fn default() -> Self {
+        Self::new(8, 8)
+    }
+}
 
-/// Thread group size matching our HLSL shaders.
-const THREAD_GROUP_SIZE: u32 = 8;
+impl std::fmt::Display for ThreadGroupSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.x, self.y)
+    }
+}
 
 /// Compiled compute shader, ready to dispatch.
 pub struct ComputeShader {
     shader: ID3D11ComputeShader,
+    bindings: Vec<ResourceBinding>,
+    /// `numthreads` of the compiled bytecode (x, y; z is 1).
+    thread_group: ThreadGroupSize,
+}
+
+/// Resource bound by a compiled shader, from shader reflection.
+///
+/// Resources the compiler eliminated as unused are not reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceBinding {
+    pub name: String,
+    pub kind: D3D_SHADER_INPUT_TYPE,
+    /// Register index (`t0` → 0).
+    pub slot: u32,
 }
 
 impl ComputeShader {
     /// Compile HLSL source into a compute shader.
     ///
     /// `entry_point` is the shader entry function name (e.g. "main").
+    /// Uses the default 8×8 thread group, see [`compile_with`](Self::compile_with).
     pub fn compile(device: &ID3D11Device, hlsl: &str, entry_point: &str) -> Result<Self> {
+        Self::compile_with(device, hlsl, entry_point, ThreadGroupSize::default())
+    }
+
+    /// Compile with `THREAD_GROUP_X` / `THREAD_GROUP_Y` defined to `group`.
+    ///
+    /// Only shaders declaring `[numthreads(THREAD_GROUP_X, THREAD_GROUP_Y, 1)]`
+    /// change size; [`dispatch`] follows whatever size was compiled.
+    pub fn compile_with(
+        device: &ID3D11Device,
+        hlsl: &str,
+        entry_point: &str,
+        group: ThreadGroupSize,
+    ) -> Result<Self> {
+        group.validate()?;
         let mut blob: Option<ID3DBlob> = None;
         let mut error_blob: Option<ID3DBlob> = None;
 
         let entry = format!("{}\0", entry_point);
         let target = b"cs_5_0\0";
+        let (group_x, group_y) = (format!("{}\0", group.x), format!("{}\0", group.y));
+        let defines = [
+            D3D_SHADER_MACRO {
+                Name: PCSTR(b"THREAD_GROUP_X\0".as_ptr()),
+                Definition: PCSTR(group_x.as_ptr()),
+            },
+            D3D_SHADER_MACRO {
+                Name: PCSTR(b"THREAD_GROUP_Y\0".as_ptr()),
+                Definition: PCSTR(group_y.as_ptr()),
+            },
+            // Null terminator
+            D3D_SHADER_MACRO::default(),
+        ];
 
         // SAFETY: D3DCompile reads from hlsl slice and writes to COM blobs.
-        // All pointers are valid for the duration of the call.
+        // All pointers (including the NUL-terminated define strings) are
+        // valid for the duration of the call.
         let hr = unsafe {
             D3DCompile(
                 hlsl.as_ptr() as *const _,
                 hlsl.len(),
                 None,
-                None,
+                Some(defines.as_ptr()),
                 None,
                 PCSTR(entry.as_ptr()),
                 PCSTR(target.as_ptr()),
@@ -62,37 +174,285 @@ impl ComputeShader {
         let blob = blob.context("D3DCompile succeeded but returned no bytecode")?;
 
         // SAFETY: blob contains valid compiled bytecode from D3DCompile.
-        let shader = unsafe {
+        let bytecode = unsafe {
             let ptr = blob.GetBufferPointer();
             let len = blob.GetBufferSize();
-            let bytecode = std::slice::from_raw_parts(ptr as *const u8, len);
+            std::slice::from_raw_parts(ptr as *const u8, len)
+        };
+
+        // SAFETY: bytecode is valid for the lifetime of blob.
+        let shader = unsafe {
             let mut cs = None;
             device
                 .CreateComputeShader(bytecode, None, Some(&mut cs))
                 .context("CreateComputeShader failed")?;
             cs.unwrap()
         };
+        let (bindings, thread_group) = reflect(bytecode)?;
+
+        Ok(Self {
+            shader,
+            bindings,
+            thread_group,
+        })
+    }
 
-        Ok(Self { shader })
+    /// Resources referenced by the shader.
+    pub fn bindings(&self) -> &[ResourceBinding] {
+        &self.bindings
+    }
+
+    /// Thread group size the shader was compiled with.
+    pub fn thread_group(&self) -> ThreadGroupSize {
+        self.thread_group
     }
 }
 
+/// Bound resources and thread group size of compiled bytecode via D3DReflect.
+fn reflect(bytecode: &[u8]) -> Result<(Vec<ResourceBinding>, ThreadGroupSize)> {
+    // SAFETY: bytecode is a valid DXBC blob. D3DReflect writes an AddRef'd
+    // interface pointer into `reflector`, which Option<Interface> takes ownership of.
+    // Binding names point into the reflector's storage and are copied before it drops.
+    unsafe {
+        let mut reflector: Option<ID3D11ShaderReflection> = None;
+        D3DReflect(
+            bytecode.as_ptr() as *const _,
+            bytecode.len(),
+            &ID3D11ShaderReflection::IID,
+            &mut reflector as *mut _ as *mut *mut core::ffi::c_void,
+        )
+        .context("D3DReflect failed")?;
+        let reflector = reflector.context("D3DReflect returned no interface")?;
+
+        let mut desc = D3D11_SHADER_DESC::default();
+        reflector
+            .GetDesc(&mut desc)
+            .context("shader reflection GetDesc failed")?;
+
+        let mut bindings = Vec::with_capacity(desc.BoundResources as usize);
+        for i in 0..desc.BoundResources {
+            let mut bind = D3D11_SHADER_INPUT_BIND_DESC::default();
+            reflector
+                .GetResourceBindingDesc(i, &mut bind)
+                .context("shader reflection GetResourceBindingDesc failed")?;
+            bindings.push(ResourceBinding {
+                name: bind.Name.to_string().unwrap_or_default(),
+                kind: bind.Type,
+                slot: bind.BindPoint,
+            });
+        }
+
+        let (mut x, mut y) = (0u32, 0u32);
+        reflector.GetThreadGroupSize(Some(&mut x), Some(&mut y), None);
+        Ok((bindings, ThreadGroupSize::new(x.max(1), y.max(1))))
+    }
+}
+
+/// Compile `hlsl` with the fastest thread group size for this GPU.
+///
+/// `run` dispatches a compiled candidate once on representative input,
+/// binding any resources beyond t0/u0 itself. Each of
+/// [`ThreadGroupSize::CANDIDATES`] is warmed up and then timed over a burst of
+/// dispatches; sizes that fail to compile are skipped. The winner is cached
+/// per adapter and shader source for the life of the process, so later
+/// passes on the same GPU compile it directly without benchmarking.
+pub fn auto_tune(
+    device: &ID3D11Device,
+    context: &ID3D11DeviceContext,
+    hlsl: &str,
+    entry_point: &str,
+    mut run: impl FnMut(&ComputeShader) -> Result<()>,
+) -> Result<ComputeShader> {
+    static TUNED: OnceLock<Mutex<HashMap<(i64, u64), ThreadGroupSize>>> = OnceLock::new();
+    let tuned = TUNED.get_or_init(Default::default);
+
+    let key = (adapter_luid(device)?, source_hash(hlsl, entry_point));
+    let cached = tuned.lock().unwrap().get(&key).copied();
+    if let Some(group) = cached {
+        return ComputeShader::compile_with(device, hlsl, entry_point, group);
+    }
+
+    let mut best: Option<(Duration, ComputeShader)> = None;
+    let mut measured = Vec::new();
+    for group in ThreadGroupSize::CANDIDATES {
+        let shader = match ComputeShader::compile_with(device, hlsl, entry_point, group) {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::debug!("thread group {group} skipped: {e:#}");
+                continue;
+            }
+        };
+        // Shaders with a literal `numthreads` compile to one size only.
+        if measured.contains(&shader.thread_group()) {
+            continue;
+        }
+        measured.push(shader.thread_group());
+        run(&shader)?;
+        wait_for_gpu(device, context)?;
+        let start = Instant::now();
+        for _ in 0..TUNE_ITERATIONS {
+            run(&shader)?;
+        }
+        wait_for_gpu(device, context)?;
+        let elapsed = start.elapsed();
+        log::debug!(
+            "thread group {group}: {:.3} ms per dispatch",
+            elapsed.as_secs_f64() * 1000.0 / f64::from(TUNE_ITERATIONS)
+        );
+        if best.as_ref().is_none_or(|(fastest, _)| elapsed < *fastest) {
+            best = Some((elapsed, shader));
+        }
+    }
+
+    let (_, shader) = best.context("no thread group size compiled")?;
+    log::debug!("auto-tuned thread group: {}", shader.thread_group());
+    tuned.lock().unwrap().insert(key, shader.thread_group());
+    Ok(shader)
+}
+
+/// Adapter LUID of `device`, as one integer.
+fn adapter_luid(device: &ID3D11Device) -> Result<i64> {
+    // SAFETY: GetAdapter / GetDesc only read adapter properties.
+    let desc = unsafe { device.cast::<IDXGIDevice>()?.GetAdapter()?.GetDesc()? };
+    let luid = desc.AdapterLuid;
+    Ok((i64::from(luid.HighPart) << 32) | i64::from(luid.LowPart))
+}
+
+fn source_hash(hlsl: &str, entry_point: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (hlsl, entry_point).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Block until the GPU has finished all work submitted on `context`.
+fn wait_for_gpu(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<()> {
+    let desc = D3D11_QUERY_DESC {
+        Query: D3D11_QUERY_EVENT,
+        MiscFlags: 0,
+    };
+    // SAFETY: desc is fully initialized; GetData writes one BOOL into `done`,
+    // whose size is passed alongside.
+    unsafe {
+        let mut query = None;
+        device
+            .CreateQuery(&desc, Some(&mut query))
+            .context("CreateQuery failed")?;
+        let query = query.context("CreateQuery returned no query")?;
+        context.End(&query);
+
+        let deadline = Instant::now() + GPU_IDLE_TIMEOUT;
+        loop {
+            let mut done = BOOL(0);
+            context.GetData(
+                &query,
+                Some(&mut done as *mut BOOL as *mut _),
+                std::mem::size_of::<BOOL>() as u32,
+                0,
+            )?;
+            if done.as_bool() {
+                return Ok(());
+            }
+            if Instant::now() > deadline {
+                bail!("GPU did not finish within {:?}", GPU_IDLE_TIMEOUT);
+            }
+            std::thread::yield_now();
+        }
+    }
+}
+
+/// How a shader resource view decodes 8-bit color on sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SrvFormat {
+    /// The texture's own format, `*_UNORM` for a `*_TYPELESS` texture:
+    /// sRGB-encoded SDR samples as sRGB values.
+    #[default]
+    Native,
+    /// `*_UNORM_SRGB` view of an 8-bit BGRA / RGBA texture: the hardware
+    /// converts sRGB to linear on every load and sample, so filtering and
+    /// blending happen in linear light. The texture must be `*_TYPELESS` (see
+    /// [`create_output_with`]); fully typed `*_UNORM` textures, such as WGC
+    /// frame surfaces, need a `CopyResource` into one first. UAVs can't be
+    /// sRGB, so a pass reading this way encodes back to sRGB before storing.
+    SrgbDecode,
+}
+
 /// Create a SRV for an existing texture (read-only input).
 pub fn create_srv(
     device: &ID3D11Device,
     texture: &ID3D11Texture2D,
 ) -> Result<ID3D11ShaderResourceView> {
+    create_srv_with(device, texture, SrvFormat::Native)
+}
+
+/// Create a SRV for an existing texture, decoding as `format` says.
+///
+/// The view format is always spelled out: a null view desc is invalid on
+/// the `*_TYPELESS` textures of [`create_output_with`].
+pub fn create_srv_with(
+    device: &ID3D11Device,
+    texture: &ID3D11Texture2D,
+    format: SrvFormat,
+) -> Result<ID3D11ShaderResourceView> {
+    let mut desc = D3D11_TEXTURE2D_DESC::default();
+    // SAFETY: GetDesc writes into a caller-provided struct.
+    unsafe { texture.GetDesc(&mut desc) };
+    let view_desc = D3D11_SHADER_RESOURCE_VIEW_DESC {
+        Format: match format {
+            SrvFormat::Native => native_view_format(desc.Format),
+            SrvFormat::SrgbDecode => srgb_view_format(desc.Format)?,
+        },
+        ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
+        Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
+            Texture2D: D3D11_TEX2D_SRV {
+                MostDetailedMip: 0,
+                MipLevels: desc.MipLevels,
+            },
+        },
+    };
+
     // SAFETY: texture is a valid D3D11 resource; CreateShaderResourceView
-    // reads the texture desc and creates a COM view object.
+    // reads the view desc, whose format is cast-compatible with the texture,
+    // and creates a COM view object.
     unsafe {
         let mut srv = None;
         device
-            .CreateShaderResourceView(texture, None, Some(&mut srv))
+            .CreateShaderResourceView(texture, Some(&view_desc), Some(&mut srv))
             .context("CreateShaderResourceView failed")?;
         Ok(srv.unwrap())
     }
 }
 
+/// Plain view format for a texture of `format`: `*_UNORM` for the 8-bit
+/// typeless formats, the format itself otherwise.
+fn native_view_format(format: DXGI_FORMAT) -> DXGI_FORMAT {
+    match format {
+        DXGI_FORMAT_B8G8R8A8_TYPELESS => DXGI_FORMAT_B8G8R8A8_UNORM,
+        DXGI_FORMAT_R8G8B8A8_TYPELESS => DXGI_FORMAT_R8G8B8A8_UNORM,
+        format => format,
+    }
+}
+
+/// sRGB view format for a texture of `format`.
+fn srgb_view_format(format: DXGI_FORMAT) -> Result<DXGI_FORMAT> {
+    match format {
+        DXGI_FORMAT_B8G8R8A8_TYPELESS | DXGI_FORMAT_B8G8R8A8_UNORM_SRGB => {
+            Ok(DXGI_FORMAT_B8G8R8A8_UNORM_SRGB)
+        }
+        DXGI_FORMAT_R8G8B8A8_TYPELESS | DXGI_FORMAT_R8G8B8A8_UNORM_SRGB => {
+            Ok(DXGI_FORMAT_R8G8B8A8_UNORM_SRGB)
+        }
+        DXGI_FORMAT_B8G8R8A8_UNORM | DXGI_FORMAT_R8G8B8A8_UNORM => bail!(
+            "sRGB view needs a typeless texture, got fully typed {:?}; copy it into a \
+             texture from create_output_with(.., true) first",
+            format
+        ),
+        _ => bail!(
+            "sRGB view needs an 8-bit BGRA / RGBA texture, got {:?}",
+            format
+        ),
+    }
+}
+
 /// Create a UAV-bindable output texture and its UAV.
 ///
 /// The output texture has the same dimensions as `width` × `height`
@@ -103,12 +463,38 @@ pub fn create_output(
     height: u32,
     format: DXGI_FORMAT,
 ) -> Result<(ID3D11Texture2D, ID3D11UnorderedAccessView)> {
+    create_output_with(device, width, height, format, false)
+}
+
+/// Create an output texture and its UAV, optionally sRGB-sampleable.
+///
+/// With `srgb_sampled`, a `B8G8R8A8_UNORM` / `R8G8B8A8_UNORM` texture is
+/// created `*_TYPELESS` (the UAV still writes `format`), so a later pass can
+/// read it through [`SrvFormat::SrgbDecode`], or as `*_UNORM` through
+/// [`SrvFormat::Native`]. `CopyResource` to and from `*_UNORM` textures and
+/// BGRA readback work as for a typed texture.
+pub fn create_output_with(
+    device: &ID3D11Device,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+    srgb_sampled: bool,
+) -> Result<(ID3D11Texture2D, ID3D11UnorderedAccessView)> {
+    let texture_format = match (srgb_sampled, format) {
+        (false, _) => format,
+        (true, DXGI_FORMAT_B8G8R8A8_UNORM) => DXGI_FORMAT_B8G8R8A8_TYPELESS,
+        (true, DXGI_FORMAT_R8G8B8A8_UNORM) => DXGI_FORMAT_R8G8B8A8_TYPELESS,
+        (true, _) => bail!(
+            "sRGB sampling needs an 8-bit UNORM format, got {:?}",
+            format
+        ),
+    };
     let desc = D3D11_TEXTURE2D_DESC {
         Width: width,
         Height: height,
         MipLevels: 1,
         ArraySize: 1,
-        Format: format,
+        Format: texture_format,
         SampleDesc: windows::Win32::Graphics::Dxgi::Common::DXGI_SAMPLE_DESC {
             Count: 1,
             Quality: 0,
@@ -118,6 +504,13 @@ pub fn create_output(
         CPUAccessFlags: 0,
         MiscFlags: 0,
     };
+    let uav_desc = D3D11_UNORDERED_ACCESS_VIEW_DESC {
+        Format: format,
+        ViewDimension: D3D11_UAV_DIMENSION_TEXTURE2D,
+        Anonymous: D3D11_UNORDERED_ACCESS_VIEW_DESC_0 {
+            Texture2D: D3D11_TEX2D_UAV { MipSlice: 0 },
+        },
+    };
 
     // SAFETY: desc is fully initialized; CreateTexture2D allocates a GPU resource.
     let texture = unsafe {
@@ -128,11 +521,12 @@ pub fn create_output(
         tex.unwrap()
     };
 
-    // SAFETY: texture is a valid D3D11 resource with UAV bind flag.
+    // SAFETY: texture is a valid D3D11 resource with UAV bind flag; the UAV
+    // format is the texture's format or a typed member of its typeless family.
     let uav = unsafe {
         let mut uav = None;
         device
-            .CreateUnorderedAccessView(&texture, None, Some(&mut uav))
+            .CreateUnorderedAccessView(&texture, Some(&uav_desc), Some(&mut uav))
             .context("CreateUnorderedAccessView failed")?;
         uav.unwrap()
     };
@@ -143,7 +537,7 @@ pub fn create_output(
 /// Dispatch a compute shader over a width × height image.
 ///
 /// Binds input SRV to t0, output UAV to u0, dispatches with ceil-division
-/// thread groups, then unbinds resources.
+/// by the shader's thread group size, then unbinds resources.
 pub fn dispatch(
     context: &ID3D11DeviceContext,
     shader: &ComputeShader,
@@ -152,8 +546,8 @@ pub fn dispatch(
     width: u32,
     height: u32,
 ) {
-    let groups_x = width.div_ceil(THREAD_GROUP_SIZE);
-    let groups_y = height.div_ceil(THREAD_GROUP_SIZE);
+    let groups_x = width.div_ceil(shader.thread_group.x);
+    let groups_y = height.div_ceil(shader.thread_group.y);
 
     // SAFETY: All COM objects are valid. Bind → Dispatch → Unbind is the
     // standard D3D11 compute pattern. Unbinding prevents resource hazards.
@@ -275,4 +669,74 @@ void main(uint3 id : SV_DispatchThreadID)
         assert_eq!(u16_data[60], 0x3C00, "Last pixel R");
         assert_eq!(u16_data[61], 0x3800, "Last pixel G");
     }
+
+    #[test]
+    fn test_srgb_srv_decodes_to_linear() {
+        let ctx = create_d3d11_device().expect("D3D11 device creation failed");
+        let shader =
+            ComputeShader::compile(&ctx.device, IDENTITY_HLSL, "main").expect("Shader compile");
+
+        // 2x2 BGRA8 at sRGB 188/255, which is 0.5 in linear light.
+        let (input, input_uav) =
+            create_output_with(&ctx.device, 2, 2, DXGI_FORMAT_B8G8R8A8_UNORM, true)
+                .expect("Create typeless input");
+        drop(input_uav);
+        let pixels = [188u8; 16];
+        unsafe {
+            ctx.context
+                .UpdateSubresource(&input, 0, None, pixels.as_ptr() as *const _, 8, 0);
+        }
+
+        let (output, uav) =
+            create_output(&ctx.device, 2, 2, DXGI_FORMAT_R16G16B16A16_FLOAT).expect("Output");
+        let mut reader = TextureReader::new(ctx.device.clone(), ctx.context.clone());
+        let mut first_red = |format| {
+            let srv = create_srv_with(&ctx.device, &input, format).expect("Create SRV");
+            dispatch(&ctx.context, &shader, &srv, &uav, 2, 2);
+            let bytes = reader.read_texture(&output).expect("Readback");
+            half::f16::from_le_bytes([bytes[0], bytes[1]]).to_f32()
+        };
+
+        let encoded = first_red(SrvFormat::Native);
+        assert!((encoded - 188.0 / 255.0).abs() < 1e-3, "native {encoded}");
+        let linear = first_red(SrvFormat::SrgbDecode);
+        assert!((linear - 0.5).abs() < 0.01, "sRGB-decoded {linear}");
+
+        // Fully typed textures can't be viewed as sRGB.
+        let (typed, _) =
+            create_output(&ctx.device, 2, 2, DXGI_FORMAT_B8G8R8A8_UNORM).expect("Typed");
+        assert!(create_srv_with(&ctx.device, &typed, SrvFormat::SrgbDecode).is_err());
+    }
+
+    #[test]
+    fn test_thread_group_size_validate() {
+        assert!(ThreadGroupSize::default().validate().is_ok());
+        assert!(ThreadGroupSize::new(32, 32).validate().is_ok());
+        assert!(ThreadGroupSize::new(64, 32).validate().is_err());
+        assert!(ThreadGroupSize::new(0, 8).validate().is_err());
+    }
+
+    #[test]
+    fn test_compile_with_thread_group_define() {
+        const SIZED_HLSL: &str = r#"
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+
+[numthreads(THREAD_GROUP_X, THREAD_GROUP_Y, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    OutputTexture[id.xy] = InputTexture[id.xy];
+}
+"#;
+        let ctx = create_d3d11_device().expect("D3D11 device creation failed");
+        let group = ThreadGroupSize::new(32, 8);
+        let shader = ComputeShader::compile_with(&ctx.device, SIZED_HLSL, "main", group)
+            .expect("Shader compile");
+        assert_eq!(shader.thread_group(), group);
+
+        // Literal numthreads is reported as declared, whatever was requested.
+        let literal = ComputeShader::compile_with(&ctx.device, IDENTITY_HLSL, "main", group)
+            .expect("Shader compile");
+        assert_eq!(literal.thread_group(), ThreadGroupSize::new(8, 8));
+    }
 }
@@ -0,0 +1,208 @@
+// GPU NV12 conversion: BGRA8 frames in the layout video encoders take.
+//
+// A compute pass writes luma and interleaved chroma into one R8 texture shaped
+// like NV12 memory (Y rows, then UV rows), so a single staging copy brings the
+// whole frame back and no CPU color conversion is left to do.
+
+use anyhow::{bail, Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::{
+    DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R8_UNORM, DXGI_SAMPLE_DESC,
+};
+
+use super::compute::{self, ComputeShader};
+
+/// Bytes of a `width` × `height` NV12 frame: the full-resolution Y plane,
+/// then `ceil(height / 2)` rows of `2 * ceil(width / 2)` interleaved U, V bytes.
+pub fn nv12_frame_bytes(width: u32, height: u32) -> usize {
+    let (w, h) = (width as usize, height as usize);
+    w * h + 2 * w.div_ceil(2) * h.div_ceil(2)
+}
+
+/// GPU BGRA8 → NV12 pass (BT.709 limited range) with its own readback.
+///
+/// The R8 output and staging textures are created on first use and reused
+/// while the frame size stays the same.
+pub struct Nv12Pass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    staging: ID3D11Texture2D,
+    width: u32,
+    height: u32,
+}
+
+impl Nv12Pass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::BGRA_TO_NV12_HLSL, "main")?;
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            output_cache: None,
+        })
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height {
+                return Ok(());
+            }
+        }
+
+        // Wide enough for the chroma rows of odd widths, tall enough for both planes.
+        let (tex_width, tex_height) = (width.div_ceil(2) * 2, height + height.div_ceil(2));
+        let (texture, uav) =
+            compute::create_output(&self.device, tex_width, tex_height, DXGI_FORMAT_R8_UNORM)?;
+        let staging_desc = D3D11_TEXTURE2D_DESC {
+            Width: tex_width,
+            Height: tex_height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_R8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_STAGING,
+            BindFlags: 0,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+            MiscFlags: 0,
+        };
+        // SAFETY: staging_desc is fully initialized; CreateTexture2D allocates a GPU resource.
+        let staging = unsafe {
+            let mut tex = None;
+            self.device
+                .CreateTexture2D(&staging_desc, None, Some(&mut tex))
+                .context("CreateTexture2D for NV12 staging failed")?;
+            tex.unwrap()
+        };
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            staging,
+            width,
+            height,
+        });
+        Ok(())
+    }
+
+    /// Convert a BGRA8 `input` to NV12 and read it back, tightly packed
+    /// (see [`nv12_frame_bytes`]). Blocks until the GPU has finished.
+    pub fn execute(&mut self, input: &ID3D11Texture2D) -> Result<Vec<u8>> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe {
+            input.GetDesc(&mut desc);
+        }
+        if desc.Format != DXGI_FORMAT_B8G8R8A8_UNORM {
+            bail!(
+                "NV12 conversion needs a BGRA8 texture, got {:?}",
+                desc.Format
+            );
+        }
+        let (width, height) = (desc.Width, desc.Height);
+        self.ensure_output(width, height)?;
+        let cache = self.output_cache.as_ref().expect("output created above");
+
+        let srv = compute::create_srv(&self.device, input)?;
+        // One thread per 2×2 block.
+        compute::dispatch(
+            &self.context,
+            &self.shader,
+            &srv,
+            &cache.uav,
+            width.div_ceil(2),
+            height.div_ceil(2),
+        );
+
+        let (w, h) = (width as usize, height as usize);
+        let chroma_row = w.div_ceil(2) * 2;
+        let mut out = Vec::with_capacity(nv12_frame_bytes(width, height));
+        // SAFETY: CopyResource between equally sized R8 textures, then a
+        // blocking Map; every row read lies within RowPitch × texture height.
+        unsafe {
+            self.context.CopyResource(&cache.staging, &cache.texture);
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(&cache.staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+                .context("Map NV12 staging texture failed")?;
+            let pitch = mapped.RowPitch as usize;
+            let base = mapped.pData as *const u8;
+            for row in 0..h {
+                out.extend_from_slice(std::slice::from_raw_parts(base.add(row * pitch), w));
+            }
+            for row in h..h + h.div_ceil(2) {
+                out.extend_from_slice(std::slice::from_raw_parts(
+                    base.add(row * pitch),
+                    chroma_row,
+                ));
+            }
+            self.context.Unmap(&cache.staging, 0);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::d3d11::create_d3d11_device;
+
+    fn bgra_texture(device: &ID3D11Device, pixels: &[u8], width: u32) -> ID3D11Texture2D {
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: pixels.len() as u32 / 4 / width,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: 0,
+        };
+        let init = D3D11_SUBRESOURCE_DATA {
+            pSysMem: pixels.as_ptr() as *const _,
+            SysMemPitch: width * 4,
+            SysMemSlicePitch: 0,
+        };
+        unsafe {
+            let mut tex = None;
+            device
+                .CreateTexture2D(&desc, Some(&init), Some(&mut tex))
+                .expect("Create input texture");
+            tex.unwrap()
+        }
+    }
+
+    #[test]
+    fn test_nv12_levels_and_layout() {
+        let ctx = create_d3d11_device().expect("D3D11 device");
+        let mut pass = Nv12Pass::new(&ctx.device, &ctx.context).expect("Nv12Pass");
+
+        // 3×1: white, black, pure red. Odd width → 2 chroma pairs.
+        let bgra = [255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 255, 255];
+        let out = pass.execute(&bgra_texture(&ctx.device, &bgra, 3)).unwrap();
+        assert_eq!(out.len(), nv12_frame_bytes(3, 1));
+        assert_eq!(out.len(), 3 + 4);
+
+        let near = |got: u8, want: u8| got.abs_diff(want) <= 1;
+        // Limited range luma: white 235, black 16; red 16 + 0.2126 × 219.
+        assert!(near(out[0], 235) && near(out[1], 16) && near(out[2], 63));
+        // White + black average to neutral gray: no chroma.
+        assert!(near(out[3], 128) && near(out[4], 128));
+        // Red: U below neutral, maximum V.
+        assert!(out[5] < 128);
+        assert!(near(out[6], 240));
+    }
+}
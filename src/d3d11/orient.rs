@@ -0,0 +1,392 @@
+// GPU orientation stage: 90-degree rotations and flips of a texture.
+//
+// OrientPass holds the compiled shader and a cached output texture, created once
+// per pipeline. Works for both BGRA8 and RGBA16F (output keeps the input format).
+// The same pixel mapping backs the CPU `CapturedFrame::rotate` / `flip`.
+
+use anyhow::{Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT;
+
+use super::compute::{self, ComputeShader};
+
+/// Clockwise rotation by a multiple of 90 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+impl Rotation {
+    /// Parse clockwise degrees: 0, 90, 180 or 270 (negative values count
+    /// counter-clockwise, e.g. -90 = 270).
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        Some(match degrees.rem_euclid(360) {
+            0 => Self::None,
+            90 => Self::Cw90,
+            180 => Self::Cw180,
+            270 => Self::Cw270,
+            _ => return None,
+        })
+    }
+
+    /// Clockwise angle in degrees.
+    pub fn degrees(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Cw90 => 90,
+            Self::Cw180 => 180,
+            Self::Cw270 => 270,
+        }
+    }
+
+    /// Rotation that undoes this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Cw90 => Self::Cw270,
+            Self::Cw270 => Self::Cw90,
+            other => other,
+        }
+    }
+
+    /// This rotation followed by `next`.
+    pub fn then(self, next: Self) -> Self {
+        Self::from_degrees((self.degrees() + next.degrees()) as i32).unwrap_or_default()
+    }
+
+    /// Whether width and height trade places.
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Self::Cw90 | Self::Cw270)
+    }
+}
+
+/// Mirror direction of a flip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// Mirror left ↔ right.
+    Horizontal,
+    /// Mirror top ↔ bottom (e.g. bottom-up rows for OpenGL texture upload).
+    Vertical,
+}
+
+impl Axis {
+    /// Parse `"horizontal"` or `"vertical"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "horizontal" => Some(Self::Horizontal),
+            "vertical" => Some(Self::Vertical),
+            _ => None,
+        }
+    }
+}
+
+/// Orientation change applied to a frame: rotation first, then an optional flip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FrameTransform {
+    pub rotation: Rotation,
+    pub flip: Option<Axis>,
+}
+
+impl FrameTransform {
+    /// Rotation only.
+    pub fn rotate(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            flip: None,
+        }
+    }
+
+    /// Flip only.
+    pub fn flip(axis: Axis) -> Self {
+        Self {
+            rotation: Rotation::None,
+            flip: Some(axis),
+        }
+    }
+
+    /// Whether the transform leaves every pixel in place.
+    pub fn is_identity(self) -> bool {
+        self.rotation == Rotation::None && self.flip.is_none()
+    }
+
+    /// Size of a transformed `width` × `height` image.
+    pub fn output_size(self, width: u32, height: u32) -> (u32, u32) {
+        if self.rotation.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Source pixel shown at output pixel (`x`, `y`) of a transformed
+    /// `width` × `height` source image.
+    pub fn source_pixel(self, x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+        let (sx, sy) = self.source_pixel_signed(x as i64, y as i64, width as i64, height as i64);
+        (sx as u32, sy as u32)
+    }
+
+    /// `source_pixel` on signed coordinates, linear in (`x`, `y`) so it also
+    /// yields the shader's affine steps.
+    fn source_pixel_signed(self, x: i64, y: i64, width: i64, height: i64) -> (i64, i64) {
+        let (out_w, out_h) = if self.rotation.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        // Undo the flip (applied last) on output coordinates.
+        let (rx, ry) = match self.flip {
+            Some(Axis::Horizontal) => (out_w - 1 - x, y),
+            Some(Axis::Vertical) => (x, out_h - 1 - y),
+            None => (x, y),
+        };
+        // Undo the clockwise rotation.
+        match self.rotation {
+            Rotation::None => (rx, ry),
+            Rotation::Cw90 => (ry, height - 1 - rx),
+            Rotation::Cw180 => (width - 1 - rx, height - 1 - ry),
+            Rotation::Cw270 => (width - 1 - ry, rx),
+        }
+    }
+
+    fn params(self, width: u32, height: u32) -> OrientParams {
+        let (w, h) = (width as i64, height as i64);
+        let at = |x, y| self.source_pixel_signed(x, y, w, h);
+        let (o, dx, dy) = (at(0, 0), at(1, 0), at(0, 1));
+        let (out_w, out_h) = self.output_size(width, height);
+        OrientParams {
+            dst_size: [out_w, out_h],
+            origin: [o.0 as i32, o.1 as i32],
+            step_x: [(dx.0 - o.0) as i32, (dx.1 - o.1) as i32],
+            step_y: [(dy.0 - o.0) as i32, (dy.1 - o.1) as i32],
+        }
+    }
+}
+
+/// Constant buffer layout matching HLSL `OrientParams`.
+#[repr(C)]
+struct OrientParams {
+    dst_size: [u32; 2],
+    origin: [i32; 2],
+    step_x: [i32; 2],
+    step_y: [i32; 2],
+}
+
+/// GPU rotate / flip pass.
+///
+/// Output texture is lazily created and reused when size and format match.
+pub struct OrientPass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    cbuffer: ID3D11Buffer,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+}
+
+impl OrientPass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::ORIENT_HLSL, "main")?;
+
+        let cb_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of::<OrientParams>() as u32,
+            Usage: D3D11_USAGE_DYNAMIC,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as u32,
+            CPUAccessFlags: D3D11_CPU_ACCESS_WRITE.0 as u32,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+
+        // SAFETY: cb_desc is fully initialized; CreateBuffer allocates a GPU resource.
+        let cbuffer = unsafe {
+            let mut buf = None;
+            device
+                .CreateBuffer(&cb_desc, None, Some(&mut buf))
+                .context("CreateBuffer for orient cbuffer failed")?;
+            buf.unwrap()
+        };
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            cbuffer,
+            output_cache: None,
+        })
+    }
+
+    fn update_cbuffer(&self, params: OrientParams) -> Result<()> {
+        // SAFETY: Map/Unmap pattern for DYNAMIC buffer with WRITE_DISCARD.
+        // The buffer is sized to OrientParams.
+        unsafe {
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(
+                    &self.cbuffer,
+                    0,
+                    D3D11_MAP_WRITE_DISCARD,
+                    0,
+                    Some(&mut mapped),
+                )
+                .context("Map orient cbuffer failed")?;
+            std::ptr::write(mapped.pData as *mut OrientParams, params);
+            self.context.Unmap(&self.cbuffer, 0);
+        }
+        Ok(())
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32, format: DXGI_FORMAT) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height && cache.format == format {
+                return Ok(());
+            }
+        }
+
+        let (texture, uav) = compute::create_output(&self.device, width, height, format)?;
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            width,
+            height,
+            format,
+        });
+        Ok(())
+    }
+
+    /// Apply `transform` to a `width` × `height` texture.
+    ///
+    /// The output has the size from [`FrameTransform::output_size`] and the
+    /// same format as `input`.
+    pub fn execute(
+        &mut self,
+        input: &ID3D11Texture2D,
+        width: u32,
+        height: u32,
+        transform: FrameTransform,
+    ) -> Result<ID3D11Texture2D> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe {
+            input.GetDesc(&mut desc);
+        }
+
+        let (out_w, out_h) = transform.output_size(width, height);
+        self.ensure_output(out_w, out_h, desc.Format)?;
+        self.update_cbuffer(transform.params(width, height))?;
+
+        let srv = compute::create_srv(&self.device, input)?;
+        let cache = self.output_cache.as_ref().unwrap();
+
+        // SAFETY: cbuffer is a valid D3D11 buffer, binding to CS stage slot 0.
+        unsafe {
+            self.context
+                .CSSetConstantBuffers(0, Some(&[Some(self.cbuffer.clone())]));
+        }
+
+        compute::dispatch(&self.context, &self.shader, &srv, &cache.uav, out_w, out_h);
+
+        // SAFETY: Unbinding prevents resource hazards.
+        unsafe {
+            let no_cb: [Option<ID3D11Buffer>; 1] = [None];
+            self.context.CSSetConstantBuffers(0, Some(&no_cb));
+        }
+
+        Ok(cache.texture.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Transform a 3×2 image of pixel indices 0..6 on the CPU.
+    fn apply(transform: FrameTransform) -> Vec<u32> {
+        let (w, h) = (3, 2);
+        let (out_w, out_h) = transform.output_size(w, h);
+        let mut out = Vec::new();
+        for y in 0..out_h {
+            for x in 0..out_w {
+                let (sx, sy) = transform.source_pixel(x, y, w, h);
+                out.push(sy * w + sx);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_rotations_and_flips() {
+        // 0 1 2
+        // 3 4 5
+        assert_eq!(
+            apply(FrameTransform::rotate(Rotation::Cw90)),
+            [3, 0, 4, 1, 5, 2]
+        );
+        assert_eq!(
+            apply(FrameTransform::rotate(Rotation::Cw180)),
+            [5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(
+            apply(FrameTransform::rotate(Rotation::Cw270)),
+            [2, 5, 1, 4, 0, 3]
+        );
+        assert_eq!(
+            apply(FrameTransform::flip(Axis::Horizontal)),
+            [2, 1, 0, 5, 4, 3]
+        );
+        assert_eq!(
+            apply(FrameTransform::flip(Axis::Vertical)),
+            [3, 4, 5, 0, 1, 2]
+        );
+
+        // Rotation first, then the flip.
+        let both = FrameTransform {
+            rotation: Rotation::Cw90,
+            flip: Some(Axis::Vertical),
+        };
+        assert_eq!(apply(both), [5, 2, 4, 1, 3, 0]);
+    }
+
+    #[test]
+    fn test_shader_params_match_cpu_mapping() {
+        for rotation in [
+            Rotation::None,
+            Rotation::Cw90,
+            Rotation::Cw180,
+            Rotation::Cw270,
+        ] {
+            for flip in [None, Some(Axis::Horizontal), Some(Axis::Vertical)] {
+                let t = FrameTransform { rotation, flip };
+                let p = t.params(3, 2);
+                let (out_w, out_h) = t.output_size(3, 2);
+                assert_eq!(p.dst_size, [out_w, out_h]);
+                for y in 0..out_h {
+                    for x in 0..out_w {
+                        let sx = p.origin[0] + x as i32 * p.step_x[0] + y as i32 * p.step_y[0];
+                        let sy = p.origin[1] + x as i32 * p.step_x[1] + y as i32 * p.step_y[1];
+                        let (ex, ey) = t.source_pixel(x, y, 3, 2);
+                        assert_eq!((sx as u32, sy as u32), (ex, ey), "{t:?} at ({x}, {y})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotation_degrees() {
+        assert_eq!(Rotation::from_degrees(-90), Some(Rotation::Cw270));
+        assert_eq!(Rotation::from_degrees(450), Some(Rotation::Cw90));
+        assert_eq!(Rotation::from_degrees(45), None);
+        assert_eq!(Rotation::Cw90.inverse(), Rotation::Cw270);
+        assert_eq!(Rotation::Cw180.degrees(), 180);
+        assert_eq!(Rotation::Cw270.then(Rotation::Cw180), Rotation::Cw90);
+    }
+}
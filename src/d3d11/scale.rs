@@ -0,0 +1,270 @@
+// GPU resize stage: bilinear scale of a texture into a new output texture.
+//
+// ScalePass holds the compiled shader and a cached output texture, created once
+// per pipeline. Works for both BGRA8 and RGBA16F (output keeps the input format).
+
+use anyhow::{Context, Result};
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT;
+
+use super::compute::{self, ComputeShader};
+
+/// Constant buffer layout matching HLSL `ScaleParams`.
+#[repr(C)]
+struct ScaleParams {
+    src_size: [u32; 2],
+    dst_size: [u32; 2],
+    rect_origin: [u32; 2],
+    rect_size: [u32; 2],
+}
+
+/// Destination sub-rectangle that receives the scaled image.
+///
+/// Pixels outside the rect are filled with opaque black.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScaleRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Aspect handling when scaling to a fixed output size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleFit {
+    /// Fill the whole output, distorting the aspect ratio if needed.
+    #[default]
+    Stretch,
+    /// Preserve aspect ratio; center the image with black bars.
+    Letterbox,
+}
+
+impl ScaleRect {
+    /// Rect that `src_width` × `src_height` content occupies in a
+    /// `dst_width` × `dst_height` output under `fit`.
+    fn fit(
+        src_width: u32,
+        src_height: u32,
+        dst_width: u32,
+        dst_height: u32,
+        fit: ScaleFit,
+    ) -> Self {
+        let full = Self {
+            x: 0,
+            y: 0,
+            width: dst_width,
+            height: dst_height,
+        };
+        if fit == ScaleFit::Stretch || src_width == 0 || src_height == 0 {
+            return full;
+        }
+
+        // Compare aspect ratios in u64 to avoid float rounding at exact fits.
+        let (sw, sh, dw, dh) = (
+            src_width as u64,
+            src_height as u64,
+            dst_width as u64,
+            dst_height as u64,
+        );
+        let (width, height) = if sw * dh > sh * dw {
+            // Wider than the output: full width, bars top and bottom.
+            (dst_width, ((sh * dw + sw / 2) / sw).max(1) as u32)
+        } else {
+            // Taller (or equal): full height, bars left and right.
+            (((sw * dh + sh / 2) / sh).max(1) as u32, dst_height)
+        };
+        Self {
+            x: (dst_width - width) / 2,
+            y: (dst_height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+/// GPU bilinear resize pass.
+///
+/// Output texture is lazily created and reused when size and format match.
+pub struct ScalePass {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    shader: ComputeShader,
+    cbuffer: ID3D11Buffer,
+    output_cache: Option<OutputCache>,
+}
+
+struct OutputCache {
+    texture: ID3D11Texture2D,
+    uav: ID3D11UnorderedAccessView,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+}
+
+impl ScalePass {
+    pub fn new(device: &ID3D11Device, context: &ID3D11DeviceContext) -> Result<Self> {
+        let shader = ComputeShader::compile(device, crate::shader::SCALE_BILINEAR_HLSL, "main")?;
+
+        let cb_desc = D3D11_BUFFER_DESC {
+            ByteWidth: std::mem::size_of::<ScaleParams>() as u32,
+            Usage: D3D11_USAGE_DYNAMIC,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as u32,
+            CPUAccessFlags: D3D11_CPU_ACCESS_WRITE.0 as u32,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
+
+        // SAFETY: cb_desc is fully initialized; CreateBuffer allocates a GPU resource.
+        let cbuffer = unsafe {
+            let mut buf = None;
+            device
+                .CreateBuffer(&cb_desc, None, Some(&mut buf))
+                .context("CreateBuffer for scale cbuffer failed")?;
+            buf.unwrap()
+        };
+
+        Ok(Self {
+            device: device.clone(),
+            context: context.clone(),
+            shader,
+            cbuffer,
+            output_cache: None,
+        })
+    }
+
+    fn update_cbuffer(&self, params: ScaleParams) -> Result<()> {
+        // SAFETY: Map/Unmap pattern for DYNAMIC buffer with WRITE_DISCARD.
+        // The buffer is sized to ScaleParams.
+        unsafe {
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(
+                    &self.cbuffer,
+                    0,
+                    D3D11_MAP_WRITE_DISCARD,
+                    0,
+                    Some(&mut mapped),
+                )
+                .context("Map scale cbuffer failed")?;
+            std::ptr::write(mapped.pData as *mut ScaleParams, params);
+            self.context.Unmap(&self.cbuffer, 0);
+        }
+        Ok(())
+    }
+
+    fn ensure_output(&mut self, width: u32, height: u32, format: DXGI_FORMAT) -> Result<()> {
+        if let Some(ref cache) = self.output_cache {
+            if cache.width == width && cache.height == height && cache.format == format {
+                return Ok(());
+            }
+        }
+
+        let (texture, uav) = compute::create_output(&self.device, width, height, format)?;
+        self.output_cache = Some(OutputCache {
+            texture,
+            uav,
+            width,
+            height,
+            format,
+        });
+        Ok(())
+    }
+
+    /// Scale `input` to a `dst_width` × `dst_height` texture under `fit`.
+    ///
+    /// The output texture has the same format as `input`.
+    pub fn execute(
+        &mut self,
+        input: &ID3D11Texture2D,
+        dst_width: u32,
+        dst_height: u32,
+        fit: ScaleFit,
+    ) -> Result<ID3D11Texture2D> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe {
+            input.GetDesc(&mut desc);
+        }
+        let rect = ScaleRect::fit(desc.Width, desc.Height, dst_width, dst_height, fit);
+
+        self.ensure_output(dst_width, dst_height, desc.Format)?;
+        self.update_cbuffer(ScaleParams {
+            src_size: [desc.Width, desc.Height],
+            dst_size: [dst_width, dst_height],
+            rect_origin: [rect.x, rect.y],
+            rect_size: [rect.width.max(1), rect.height.max(1)],
+        })?;
+
+        let srv = compute::create_srv(&self.device, input)?;
+        let cache = self.output_cache.as_ref().unwrap();
+
+        // SAFETY: cbuffer is a valid D3D11 buffer, binding to CS stage slot 0.
+        unsafe {
+            self.context
+                .CSSetConstantBuffers(0, Some(&[Some(self.cbuffer.clone())]));
+        }
+
+        compute::dispatch(
+            &self.context,
+            &self.shader,
+            &srv,
+            &cache.uav,
+            dst_width,
+            dst_height,
+        );
+
+        // SAFETY: Unbinding prevents resource hazards.
+        unsafe {
+            let no_cb: [Option<ID3D11Buffer>; 1] = [None];
+            self.context.CSSetConstantBuffers(0, Some(&no_cb));
+        }
+
+        Ok(cache.texture.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_stretch_fills_output() {
+        let rect = ScaleRect::fit(1920, 1080, 640, 640, ScaleFit::Stretch);
+        assert_eq!(
+            rect,
+            ScaleRect {
+                x: 0,
+                y: 0,
+                width: 640,
+                height: 640
+            }
+        );
+    }
+
+    #[test]
+    fn test_fit_letterbox_wide_and_tall() {
+        // 16:9 into square: bars top and bottom.
+        let rect = ScaleRect::fit(1920, 1080, 640, 640, ScaleFit::Letterbox);
+        assert_eq!(
+            rect,
+            ScaleRect {
+                x: 0,
+                y: 140,
+                width: 640,
+                height: 360
+            }
+        );
+
+        // Portrait into 16:9: bars left and right.
+        let rect = ScaleRect::fit(1080, 1920, 1920, 1080, ScaleFit::Letterbox);
+        assert_eq!(
+            rect,
+            ScaleRect {
+                x: 656,
+                y: 0,
+                width: 608,
+                height: 1080
+            }
+        );
+    }
+}
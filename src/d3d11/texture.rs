@@ -1,6 +1,7 @@
 // Texture creation and readback utility functions
 
 use anyhow::{bail, Context, Result};
+use windows::core::Interface;
 use windows::Win32::Graphics::Direct3D11::*;
 use windows::Win32::Graphics::Dxgi::Common::*;
 
@@ -9,10 +10,60 @@ fn bytes_per_pixel(format: DXGI_FORMAT) -> Result<usize> {
     match format {
         DXGI_FORMAT_R16G16B16A16_FLOAT => Ok(8), // 4 × f16
         DXGI_FORMAT_B8G8R8A8_UNORM => Ok(4),     // 4 × u8
+        DXGI_FORMAT_B8G8R8A8_TYPELESS => Ok(4),  // sRGB-sampleable BGRA8 output
+        DXGI_FORMAT_R10G10B10A2_UNORM => Ok(4),  // 3 × 10 bits + 2 bits
         _ => bail!("Unsupported DXGI_FORMAT: {:?}", format),
     }
 }
 
+/// How full-frame readback moves texture data to the CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadbackStrategy {
+    /// Copy into a STAGING texture and `Map` it. Works on every GPU and is
+    /// the fastest path on discrete GPUs.
+    StagingCopy,
+    /// Copy into a CPU-readable DEFAULT texture and read it with
+    /// `ID3D11Device3::ReadFromSubresource`, which writes tightly packed rows
+    /// straight into the destination. Needs D3D11.3 `MapOnDefaultTextures`;
+    /// faster on integrated (UMA) GPUs, where CPU and GPU share memory.
+    MappableDirect,
+}
+
+impl ReadbackStrategy {
+    /// `MappableDirect` on UMA GPUs that can map default textures,
+    /// `StagingCopy` otherwise.
+    ///
+    /// Pipelines use this when no strategy is configured; before it existed
+    /// they always used `StagingCopy`.
+    pub fn detect(device: &ID3D11Device) -> Self {
+        match d3d11_options2(device) {
+            Some(options)
+                if options.UnifiedMemoryArchitecture.as_bool()
+                    && options.MapOnDefaultTextures.as_bool() =>
+            {
+                Self::MappableDirect
+            }
+            _ => Self::StagingCopy,
+        }
+    }
+}
+
+/// D3D11.3 feature data, or `None` on pre-Windows 10 runtimes.
+fn d3d11_options2(device: &ID3D11Device) -> Option<D3D11_FEATURE_DATA_D3D11_OPTIONS2> {
+    let mut options = D3D11_FEATURE_DATA_D3D11_OPTIONS2::default();
+    // SAFETY: `options` is the struct D3D11_FEATURE_D3D11_OPTIONS2 expects and
+    // the size passed matches it.
+    unsafe {
+        device.CheckFeatureSupport(
+            D3D11_FEATURE_D3D11_OPTIONS2,
+            &mut options as *mut _ as *mut _,
+            std::mem::size_of::<D3D11_FEATURE_DATA_D3D11_OPTIONS2>() as u32,
+        )
+    }
+    .ok()?;
+    Some(options)
+}
+
 /// Texture reader: responsible for reading GPU texture data back to CPU
 ///
 /// Staging texture is created on demand and cached for reuse, automatically rebuilt when size/format changes.
@@ -21,6 +72,19 @@ pub struct TextureReader {
     device: ID3D11Device,
     context: ID3D11DeviceContext,
     staging_texture: Option<ID3D11Texture2D>,
+    /// Staging textures for pipelined readback, one per in-flight frame
+    /// (see [`queue_copy`](Self::queue_copy)); created on first use.
+    ring: Vec<Option<StagingSlot>>,
+    /// Full mip chain for [`read_texture_mip`](Self::read_texture_mip) and the
+    /// staging texture of the level last read; created on first use.
+    mips: Option<MipChain>,
+    mip_staging: Option<StagingSlot>,
+    /// Full-frame readback path; see [`set_strategy`](Self::set_strategy).
+    strategy: ReadbackStrategy,
+    /// Set with `MappableDirect`: the device interface for ReadFromSubresource
+    /// and the CPU-readable DEFAULT texture it reads from.
+    device3: Option<ID3D11Device3>,
+    direct_texture: Option<StagingSlot>,
     buffer: Vec<u8>,
     width: u32,
     height: u32,
@@ -33,6 +97,12 @@ impl TextureReader {
             device,
             context,
             staging_texture: None,
+            ring: Vec::new(),
+            mips: None,
+            mip_staging: None,
+            strategy: ReadbackStrategy::StagingCopy,
+            device3: None,
+            direct_texture: None,
             buffer: Vec::new(),
             width: 0,
             height: 0,
@@ -40,6 +110,106 @@ impl TextureReader {
         }
     }
 
+    /// Full-frame readback path ([`read_texture_into`](Self::read_texture_into) /
+    /// [`read_texture`](Self::read_texture)).
+    pub fn strategy(&self) -> ReadbackStrategy {
+        self.strategy
+    }
+
+    /// Select the full-frame readback path (default: `StagingCopy`).
+    ///
+    /// Fails for `MappableDirect` when the device can't map default textures.
+    /// Subsampled, region, mip and pipelined readback always use staging
+    /// textures.
+    pub fn set_strategy(&mut self, strategy: ReadbackStrategy) -> Result<()> {
+        if strategy == ReadbackStrategy::MappableDirect && self.device3.is_none() {
+            if !d3d11_options2(&self.device).is_some_and(|o| o.MapOnDefaultTextures.as_bool()) {
+                bail!("GPU can't map default textures; use ReadbackStrategy::StagingCopy");
+            }
+            self.device3 = Some(
+                self.device
+                    .cast::<ID3D11Device3>()
+                    .context("ID3D11Device3 not available")?,
+            );
+        }
+        self.strategy = strategy;
+        Ok(())
+    }
+
+    /// Pre-create the texture full-frame readback copies into, so the first
+    /// frame doesn't pay for it.
+    pub fn prepare(&mut self, width: u32, height: u32, format: DXGI_FORMAT) -> Result<()> {
+        match self.strategy {
+            ReadbackStrategy::StagingCopy => self.ensure_staging_texture(width, height, format),
+            ReadbackStrategy::MappableDirect => self.ensure_direct_texture(width, height, format),
+        }
+    }
+
+    /// Ensure the CPU-readable DEFAULT texture exists and matches size/format
+    fn ensure_direct_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: DXGI_FORMAT,
+    ) -> Result<()> {
+        let reuse = self
+            .direct_texture
+            .as_ref()
+            .is_some_and(|s| s.width == width && s.height == height && s.format == format);
+        if !reuse {
+            self.direct_texture = Some(StagingSlot {
+                texture: create_readback_texture(
+                    &self.device,
+                    width,
+                    height,
+                    format,
+                    D3D11_USAGE_DEFAULT,
+                )?,
+                width,
+                height,
+                format,
+            });
+        }
+        Ok(())
+    }
+
+    /// `MappableDirect` readback of a whole texture into `dst` (exactly
+    /// `width * height * bpp` bytes).
+    fn read_direct_into(
+        &mut self,
+        source_texture: &ID3D11Texture2D,
+        desc: &D3D11_TEXTURE2D_DESC,
+        dst: &mut [u8],
+    ) -> Result<()> {
+        self.ensure_direct_texture(desc.Width, desc.Height, desc.Format)?;
+        let texture = &self.direct_texture.as_ref().unwrap().texture;
+        let device3 = self
+            .device3
+            .as_ref()
+            .context("MappableDirect readback without ID3D11Device3")?;
+        let row_bytes = dst.len() / desc.Height as usize;
+
+        // SAFETY: the texture matches the source's size and format; Map with no
+        //         output pointer only makes it CPU-readable, and ReadFromSubresource
+        //         writes `height` rows of `row_bytes` into dst, which holds exactly that.
+        unsafe {
+            self.context.CopyResource(texture, source_texture);
+            self.context
+                .Map(texture, 0, D3D11_MAP_READ, 0, None)
+                .context("Failed to map readback texture")?;
+            device3.ReadFromSubresource(
+                dst.as_mut_ptr() as *mut _,
+                row_bytes as u32,
+                dst.len() as u32,
+                texture,
+                0,
+                None,
+            );
+            self.context.Unmap(texture, 0);
+        }
+        Ok(())
+    }
+
     /// Ensure Staging Texture exists and matches size/format
     pub fn ensure_staging_texture(
         &mut self,
@@ -55,35 +225,10 @@ impl TextureReader {
             return Ok(());
         }
 
-        let desc = D3D11_TEXTURE2D_DESC {
-            Width: width,
-            Height: height,
-            MipLevels: 1,
-            ArraySize: 1,
-            Format: format,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
-            },
-            Usage: D3D11_USAGE_STAGING,
-            BindFlags: 0,
-            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
-            MiscFlags: 0,
-        };
-
-        // SAFETY: `desc` is fully initialized with valid fields and `self.device` is a live D3D11 device;
-        // CreateTexture2D writes to local `texture` only and returns a COM-owned object on success.
-        unsafe {
-            let mut texture = None;
-            self.device
-                .CreateTexture2D(&desc, None, Some(&mut texture))
-                .context("Failed to create staging texture")?;
-
-            self.staging_texture = Some(texture.unwrap());
-            self.width = width;
-            self.height = height;
-            self.format = format;
-        }
+        self.staging_texture = Some(create_staging_texture(&self.device, width, height, format)?);
+        self.width = width;
+        self.height = height;
+        self.format = format;
 
         // Pre-allocate buffer (auto-adjusted on size change, never shrinks)
         let required = width as usize * height as usize * bytes_per_pixel(format)?;
@@ -109,9 +254,6 @@ impl TextureReader {
 
         let bpp = bytes_per_pixel(desc.Format)?;
 
-        self.ensure_staging_texture(desc.Width, desc.Height, desc.Format)?;
-        let staging = self.staging_texture.as_ref().unwrap();
-
         let row_bytes = desc.Width as usize * bpp;
         let height = desc.Height as usize;
         let required = row_bytes * height;
@@ -123,54 +265,429 @@ impl TextureReader {
             );
         }
 
+        if self.strategy == ReadbackStrategy::MappableDirect {
+            self.read_direct_into(source_texture, &desc, &mut dst[..required])?;
+            return Ok(required);
+        }
+
+        self.ensure_staging_texture(desc.Width, desc.Height, desc.Format)?;
+        let staging = self.staging_texture.as_ref().unwrap();
+
+        copy_and_map(&self.context, staging, source_texture, |src, row_pitch| {
+            // Copy row by row to destination buffer, stripping RowPitch trailing padding
+            for y in 0..height {
+                // SAFETY: src points to mapped GPU memory, row_pitch * y + row_bytes is within mapped range;
+                //         dst has been validated to hold at least `required` bytes above.
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        src.add(y * row_pitch),
+                        dst.as_mut_ptr().add(y * row_bytes),
+                        row_bytes,
+                    );
+                }
+            }
+        })?;
+
+        Ok(required)
+    }
+
+    /// Read data from GPU texture to CPU
+    ///
+    /// Returns an owned `Vec<u8>` with RowPitch padding stripped, each row exactly `width * bytes_per_pixel` bytes.
+    /// Internally reuses scratch buffer to avoid repeated allocations, performs one memcpy to transfer ownership on return.
+    pub fn read_texture(&mut self, source_texture: &ID3D11Texture2D) -> Result<Vec<u8>> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
         unsafe {
-            // GPU -> Staging copy
-            self.context.CopyResource(staging, source_texture);
+            source_texture.GetDesc(&mut desc);
+        }
+
+        let bpp = bytes_per_pixel(desc.Format)?;
+
+        if self.strategy == ReadbackStrategy::MappableDirect {
+            let mut dst = vec![0u8; desc.Width as usize * desc.Height as usize * bpp];
+            self.read_direct_into(source_texture, &desc, &mut dst)?;
+            return Ok(dst);
+        }
+
+        self.ensure_staging_texture(desc.Width, desc.Height, desc.Format)?;
+        let staging = self.staging_texture.as_ref().unwrap();
+
+        let row_bytes = desc.Width as usize * bpp;
+        let height = desc.Height as usize;
+        let buffer = &mut self.buffer;
+        copy_and_map(&self.context, staging, source_texture, |src, row_pitch| {
+            // Copy row by row to scratch buffer, stripping RowPitch trailing padding
+            for y in 0..height {
+                // SAFETY: src points to mapped GPU memory, row_pitch * y + row_bytes is within mapped range;
+                //         self.buffer has been pre-allocated with sufficient space in ensure_staging_texture.
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        src.add(y * row_pitch),
+                        buffer.as_mut_ptr().add(y * row_bytes),
+                        row_bytes,
+                    );
+                }
+            }
+        })?;
+
+        // Transfer ownership: copy from scratch buffer and return
+        Ok(self.buffer[..row_bytes * height].to_vec())
+    }
+
+    /// Read data from GPU texture to CPU with a caller-chosen row stride
+    ///
+    /// For consumers with stride requirements (e.g. encoders wanting 16- or
+    /// 64-byte aligned rows): rows land `target_stride` bytes apart, so no second
+    /// copy is needed to re-pad them. A stride wider than `width * bpp` zero-fills
+    /// the tail of each row; a narrower one truncates rows to `target_stride`
+    /// bytes. `0` means tightly packed. Returns the buffer
+    /// (`stride * height` bytes) and the stride used.
+    pub fn read_texture_with_stride(
+        &mut self,
+        source_texture: &ID3D11Texture2D,
+        target_stride: usize,
+    ) -> Result<(Vec<u8>, usize)> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe {
+            source_texture.GetDesc(&mut desc);
+        }
+
+        let bpp = bytes_per_pixel(desc.Format)?;
+        let row_bytes = desc.Width as usize * bpp;
+        let stride = if target_stride == 0 {
+            row_bytes
+        } else {
+            target_stride
+        };
+        let height = desc.Height as usize;
+        let mut dst = vec![0u8; stride * height];
+
+        if self.strategy == ReadbackStrategy::MappableDirect && stride >= row_bytes {
+            self.ensure_direct_texture(desc.Width, desc.Height, desc.Format)?;
+            let texture = &self.direct_texture.as_ref().unwrap().texture;
+            let device3 = self
+                .device3
+                .as_ref()
+                .context("MappableDirect readback without ID3D11Device3")?;
+            // SAFETY: as in read_direct_into; ReadFromSubresource writes `height`
+            //         rows of `row_bytes` at `stride` intervals, within dst.
+            unsafe {
+                self.context.CopyResource(texture, source_texture);
+                self.context
+                    .Map(texture, 0, D3D11_MAP_READ, 0, None)
+                    .context("Failed to map readback texture")?;
+                device3.ReadFromSubresource(
+                    dst.as_mut_ptr() as *mut _,
+                    stride as u32,
+                    dst.len() as u32,
+                    texture,
+                    0,
+                    None,
+                );
+                self.context.Unmap(texture, 0);
+            }
+            return Ok((dst, stride));
+        }
+
+        self.ensure_staging_texture(desc.Width, desc.Height, desc.Format)?;
+        let staging = self.staging_texture.as_ref().unwrap();
+
+        let copy_bytes = row_bytes.min(stride);
+        copy_and_map(&self.context, staging, source_texture, |src, row_pitch| {
+            // Copy row by row at the target stride; the rest of each row stays zero
+            for y in 0..height {
+                // SAFETY: src points to mapped GPU memory, row_pitch * y + copy_bytes is within
+                //         mapped range; dst holds `stride * height` bytes and copy_bytes <= stride.
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        src.add(y * row_pitch),
+                        dst.as_mut_ptr().add(y * stride),
+                        copy_bytes,
+                    );
+                }
+            }
+        })?;
+
+        Ok((dst, stride))
+    }
+
+    /// Read every `row_step`-th row and `col_step`-th column of a GPU texture to CPU
+    ///
+    /// For cheap low-resolution previews: the whole texture still goes through the
+    /// staging copy, but only the sampled pixels are copied out of mapped memory,
+    /// which is much cheaper than a full readback plus a CPU resize.
+    /// Returns the tightly packed pixels and their (reduced) width and height.
+    pub fn read_texture_subsampled(
+        &mut self,
+        source_texture: &ID3D11Texture2D,
+        row_step: u32,
+        col_step: u32,
+    ) -> Result<(Vec<u8>, u32, u32)> {
+        if row_step == 0 || col_step == 0 {
+            bail!(
+                "Subsampling steps must be at least 1: row_step={}, col_step={}",
+                row_step,
+                col_step
+            );
+        }
+
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe {
+            source_texture.GetDesc(&mut desc);
+        }
+
+        let bpp = bytes_per_pixel(desc.Format)?;
+
+        self.ensure_staging_texture(desc.Width, desc.Height, desc.Format)?;
+        let staging = self.staging_texture.as_ref().unwrap();
+
+        let (width, height) = subsampled_size(desc.Width, desc.Height, row_step, col_step);
+        let mut dst = vec![0u8; width as usize * height as usize * bpp];
+        let row_bytes = desc.Width as usize * bpp;
+        let src_height = desc.Height as usize;
+        copy_and_map(&self.context, staging, source_texture, |src, row_pitch| {
+            // SAFETY: src points to the mapped staging texture, whose rows are row_pitch
+            //         bytes apart; the last row ends row_bytes after its start.
+            let src = unsafe {
+                std::slice::from_raw_parts(src, row_pitch * (src_height - 1) + row_bytes)
+            };
+            subsample(
+                src,
+                row_pitch,
+                desc.Width,
+                desc.Height,
+                bpp,
+                row_step,
+                col_step,
+                &mut dst,
+            );
+        })?;
+
+        Ok((dst, width, height))
+    }
+
+    /// Read the largest mip level of a GPU texture that fits in `max_dim` × `max_dim`
+    ///
+    /// The texture is copied into level 0 of a full mip chain, `GenerateMips`
+    /// box-filters the chain on the GPU, and only the chosen level is read back,
+    /// so a thumbnail or a 1×1 average costs a fraction of a full readback.
+    /// Filtering averages the stored values: sRGB-encoded BGRA8 and PQ-encoded
+    /// RGB10A2 average in their encoded space, RGBA16F in linear light.
+    /// Returns the tightly packed pixels and the level's width and height.
+    pub fn read_texture_mip(
+        &mut self,
+        source_texture: &ID3D11Texture2D,
+        max_dim: u32,
+    ) -> Result<(Vec<u8>, u32, u32)> {
+        if max_dim == 0 {
+            bail!("Mip size limit must be at least 1");
+        }
+
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe {
+            source_texture.GetDesc(&mut desc);
+        }
+        let bpp = bytes_per_pixel(desc.Format)?;
+
+        let reuse = self.mips.as_ref().is_some_and(|m| {
+            m.width == desc.Width && m.height == desc.Height && m.format == desc.Format
+        });
+        if !reuse {
+            self.mips = Some(MipChain::new(
+                &self.device,
+                desc.Width,
+                desc.Height,
+                desc.Format,
+            )?);
+        }
+        let mips = self.mips.as_ref().unwrap();
+
+        let (level, width, height) = mip_level_for(desc.Width, desc.Height, max_dim);
+        let reuse = self
+            .mip_staging
+            .as_ref()
+            .is_some_and(|s| s.width == width && s.height == height && s.format == desc.Format);
+        if !reuse {
+            self.mip_staging = Some(StagingSlot {
+                texture: create_staging_texture(&self.device, width, height, desc.Format)?,
+                width,
+                height,
+                format: desc.Format,
+            });
+        }
+        let staging = &self.mip_staging.as_ref().unwrap().texture;
+
+        let row_bytes = width as usize * bpp;
+        let height_rows = height as usize;
+        let mut dst = vec![0u8; row_bytes * height_rows];
+
+        // SAFETY: level 0 of the chain matches the source's size and format and
+        //         the staging texture matches `level`; the mapped pointer is only
+        //         read before Unmap and dst holds row_bytes * height bytes.
+        unsafe {
+            self.context
+                .CopySubresourceRegion(&mips.texture, 0, 0, 0, 0, source_texture, 0, None);
+            self.context.GenerateMips(&mips.srv);
+            self.context
+                .CopySubresourceRegion(staging, 0, 0, 0, 0, &mips.texture, level, None);
 
-            // Map memory for CPU read access
             let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
             self.context
                 .Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
                 .context("Failed to map staging texture")?;
-
+            let src = mapped.pData as *const u8;
             let row_pitch = mapped.RowPitch as usize;
+            for y in 0..height_rows {
+                std::ptr::copy_nonoverlapping(
+                    src.add(y * row_pitch),
+                    dst.as_mut_ptr().add(y * row_bytes),
+                    row_bytes,
+                );
+            }
+            self.context.Unmap(staging, 0);
+        }
 
-            // Copy row by row to destination buffer, stripping RowPitch trailing padding
+        Ok((dst, width, height))
+    }
+
+    /// Start copying a GPU texture into staging slot `slot` without waiting for it
+    ///
+    /// The copy runs on the GPU while the caller goes on; read it back with
+    /// [`read_queued_into`](Self::read_queued_into), ideally a frame or more
+    /// later so the Map doesn't stall. Each slot holds one frame until it is
+    /// read and is rebuilt when the source size/format changes.
+    pub fn queue_copy(&mut self, source_texture: &ID3D11Texture2D, slot: usize) -> Result<()> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe {
+            source_texture.GetDesc(&mut desc);
+        }
+        bytes_per_pixel(desc.Format)?;
+
+        if self.ring.len() <= slot {
+            self.ring.resize_with(slot + 1, || None);
+        }
+        let reuse = self.ring[slot].as_ref().is_some_and(|s| {
+            s.width == desc.Width && s.height == desc.Height && s.format == desc.Format
+        });
+        if !reuse {
+            self.ring[slot] = Some(StagingSlot {
+                texture: create_staging_texture(
+                    &self.device,
+                    desc.Width,
+                    desc.Height,
+                    desc.Format,
+                )?,
+                width: desc.Width,
+                height: desc.Height,
+                format: desc.Format,
+            });
+        }
+        let staging = &self.ring[slot].as_ref().unwrap().texture;
+
+        // SAFETY: staging matches the source's size and format (checked above).
+        unsafe {
+            self.context.CopyResource(staging, source_texture);
+        }
+        Ok(())
+    }
+
+    /// Read a frame queued with [`queue_copy`](Self::queue_copy) to CPU
+    ///
+    /// Blocks until the copy has finished. Writes row-stripped bytes into
+    /// `dst` and returns the written byte count.
+    pub fn read_queued_into(&mut self, slot: usize, dst: &mut [u8]) -> Result<usize> {
+        let Some(Some(staging)) = self.ring.get(slot) else {
+            bail!("No frame queued in staging slot {}", slot);
+        };
+
+        let row_bytes = staging.width as usize * bytes_per_pixel(staging.format)?;
+        let height = staging.height as usize;
+        let required = row_bytes * height;
+        if dst.len() < required {
+            bail!(
+                "Destination buffer too small: required={}, got={}",
+                required,
+                dst.len()
+            );
+        }
+
+        // SAFETY: the mapped pointer is only read before Unmap; rows are
+        //         row_pitch bytes apart and dst holds `required` bytes.
+        unsafe {
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(&staging.texture, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+                .context("Failed to map staging texture")?;
             let src = mapped.pData as *const u8;
+            let row_pitch = mapped.RowPitch as usize;
             for y in 0..height {
-                // SAFETY: src points to mapped GPU memory, row_pitch * y + row_bytes is within mapped range;
-                //         dst has been validated to hold at least `required` bytes above.
                 std::ptr::copy_nonoverlapping(
                     src.add(y * row_pitch),
                     dst.as_mut_ptr().add(y * row_bytes),
                     row_bytes,
                 );
             }
-
-            self.context.Unmap(staging, 0);
+            self.context.Unmap(&staging.texture, 0);
         }
 
         Ok(required)
     }
 
-    /// Read data from GPU texture to CPU
+    /// Read a sub-rectangle of a GPU texture to CPU
     ///
-    /// Returns an owned `Vec<u8>` with RowPitch padding stripped, each row exactly `width * bytes_per_pixel` bytes.
-    /// Internally reuses scratch buffer to avoid repeated allocations, performs one memcpy to transfer ownership on return.
-    pub fn read_texture(&mut self, source_texture: &ID3D11Texture2D) -> Result<Vec<u8>> {
+    /// Copies only `region` to a staging texture sized to it (`CopySubresourceRegion`),
+    /// so reading e.g. a window-sized area of a monitor texture moves a fraction of the
+    /// data. Returns an owned `Vec<u8>` with rows of exactly `region width * bpp` bytes.
+    pub fn read_texture_region(
+        &mut self,
+        source_texture: &ID3D11Texture2D,
+        region: D3D11_BOX,
+    ) -> Result<Vec<u8>> {
         let mut desc = D3D11_TEXTURE2D_DESC::default();
         unsafe {
             source_texture.GetDesc(&mut desc);
         }
 
-        let bpp = bytes_per_pixel(desc.Format)?;
+        if region.left >= region.right
+            || region.top >= region.bottom
+            || region.right > desc.Width
+            || region.bottom > desc.Height
+            || region.front != 0
+            || region.back != 1
+        {
+            bail!(
+                "Region ({}, {})-({}, {}) is empty or outside the {}x{} texture",
+                region.left,
+                region.top,
+                region.right,
+                region.bottom,
+                desc.Width,
+                desc.Height
+            );
+        }
 
-        self.ensure_staging_texture(desc.Width, desc.Height, desc.Format)?;
+        let bpp = bytes_per_pixel(desc.Format)?;
+        let width = region.right - region.left;
+        let height = region.bottom - region.top;
+        self.ensure_staging_texture(width, height, desc.Format)?;
         let staging = self.staging_texture.as_ref().unwrap();
 
+        let row_bytes = width as usize * bpp;
+        let height = height as usize;
+        let mut dst = vec![0u8; row_bytes * height];
+
         unsafe {
-            // GPU -> Staging copy
-            self.context.CopyResource(staging, source_texture);
+            // GPU -> Staging copy of the region only, placed at (0, 0)
+            self.context.CopySubresourceRegion(
+                staging,
+                0,
+                0,
+                0,
+                0,
+                source_texture,
+                0,
+                Some(&region),
+            );
 
             // Map memory for CPU read access
             let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
@@ -179,55 +696,224 @@ impl TextureReader {
                 .context("Failed to map staging texture")?;
 
             let row_pitch = mapped.RowPitch as usize;
-            let row_bytes = desc.Width as usize * bpp;
-            let height = desc.Height as usize;
 
-            // Copy row by row to scratch buffer, stripping RowPitch trailing padding
+            // Copy row by row, stripping RowPitch trailing padding
             let src = mapped.pData as *const u8;
             for y in 0..height {
-                // SAFETY: src points to mapped GPU memory, row_pitch * y + row_bytes is within mapped range;
-                //         self.buffer has been pre-allocated with sufficient space in ensure_staging_texture.
+                // SAFETY: src points to mapped GPU memory of a width x height staging
+                //         texture; dst holds exactly row_bytes * height bytes.
                 std::ptr::copy_nonoverlapping(
                     src.add(y * row_pitch),
-                    self.buffer.as_mut_ptr().add(y * row_bytes),
+                    dst.as_mut_ptr().add(y * row_bytes),
                     row_bytes,
                 );
             }
 
             self.context.Unmap(staging, 0);
+        }
+
+        Ok(dst)
+    }
+}
 
-            // Transfer ownership: copy from scratch buffer and return
-            Ok(self.buffer[..row_bytes * height].to_vec())
+/// One staging texture of the pipelined readback ring.
+struct StagingSlot {
+    texture: ID3D11Texture2D,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+}
+
+/// Texture with a full mip chain, filled by `GenerateMips`.
+struct MipChain {
+    texture: ID3D11Texture2D,
+    srv: ID3D11ShaderResourceView,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+}
+
+impl MipChain {
+    fn new(device: &ID3D11Device, width: u32, height: u32, format: DXGI_FORMAT) -> Result<Self> {
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width,
+            Height: height,
+            // 0 = the full chain down to 1x1.
+            MipLevels: 0,
+            ArraySize: 1,
+            Format: format,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_DEFAULT,
+            // GenerateMips renders each level from the one above.
+            BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32 | D3D11_BIND_RENDER_TARGET.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: D3D11_RESOURCE_MISC_GENERATE_MIPS.0 as u32,
+        };
+
+        // SAFETY: `desc` is fully initialized and `device` is a live D3D11 device;
+        // both calls write only to the local out-parameters.
+        unsafe {
+            let mut texture = None;
+            device
+                .CreateTexture2D(&desc, None, Some(&mut texture))
+                .context("Failed to create mip chain texture")?;
+            let texture = texture.unwrap();
+            let mut srv = None;
+            device
+                .CreateShaderResourceView(&texture, None, Some(&mut srv))
+                .context("Failed to create mip chain SRV")?;
+            Ok(Self {
+                texture,
+                srv: srv.unwrap(),
+                width,
+                height,
+                format,
+            })
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::d3d11::create_d3d11_device;
+/// First mip level of a `width`x`height` texture whose sides are both at most
+/// `max_dim` (≥ 1), with that level's width and height.
+pub fn mip_level_for(width: u32, height: u32, max_dim: u32) -> (u32, u32, u32) {
+    let (mut level, mut width, mut height) = (0, width, height);
+    while width.max(height) > max_dim {
+        width = (width / 2).max(1);
+        height = (height / 2).max(1);
+        level += 1;
+    }
+    (level, width, height)
+}
 
-    #[test]
-    fn test_texture_readback_row_stripped() {
-        let d3d_ctx = create_d3d11_device().unwrap();
-        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+/// Create a CPU-readable staging texture.
+fn create_staging_texture(
+    device: &ID3D11Device,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+) -> Result<ID3D11Texture2D> {
+    create_readback_texture(device, width, height, format, D3D11_USAGE_STAGING)
+}
 
-        // 2x2 R16G16B16A16_FLOAT, all red pixels
-        // f16: 1.0 = 0x3C00, 0.0 = 0x0000
-        let pixel_red: [u16; 4] = [0x3C00, 0x0000, 0x0000, 0x3C00];
-        let mut init_data = Vec::new();
-        for _ in 0..4 {
-            init_data.extend_from_slice(&pixel_red);
+/// Create a CPU-readable texture: STAGING, or DEFAULT for `MappableDirect`.
+fn create_readback_texture(
+    device: &ID3D11Device,
+    width: u32,
+    height: u32,
+    format: DXGI_FORMAT,
+    usage: D3D11_USAGE,
+) -> Result<ID3D11Texture2D> {
+    let desc = D3D11_TEXTURE2D_DESC {
+        Width: width,
+        Height: height,
+        MipLevels: 1,
+        ArraySize: 1,
+        Format: format,
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: 1,
+            Quality: 0,
+        },
+        Usage: usage,
+        BindFlags: 0,
+        CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+        MiscFlags: 0,
+    };
+
+    // SAFETY: `desc` is fully initialized with valid fields and `device` is a live D3D11 device;
+    // CreateTexture2D writes to local `texture` only and returns a COM-owned object on success.
+    unsafe {
+        let mut texture = None;
+        device
+            .CreateTexture2D(&desc, None, Some(&mut texture))
+            .context("Failed to create readback texture")?;
+        Ok(texture.unwrap())
+    }
+}
+
+/// Copy `source_texture` to `staging`, map it for reading and call `f` with the
+/// mapped base pointer and RowPitch; unmaps afterwards.
+fn copy_and_map<T>(
+    context: &ID3D11DeviceContext,
+    staging: &ID3D11Texture2D,
+    source_texture: &ID3D11Texture2D,
+    f: impl FnOnce(*const u8, usize) -> T,
+) -> Result<T> {
+    // SAFETY: staging matches the source's size and format (ensure_staging_texture);
+    //         the mapped pointer is only used by `f`, before Unmap.
+    unsafe {
+        // GPU -> Staging copy
+        context.CopyResource(staging, source_texture);
+
+        // Map memory for CPU read access
+        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+        context
+            .Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+            .context("Failed to map staging texture")?;
+        let result = f(mapped.pData as *const u8, mapped.RowPitch as usize);
+        context.Unmap(staging, 0);
+        Ok(result)
+    }
+}
+
+/// Size of a `width`x`height` image keeping every `row_step`-th row and
+/// `col_step`-th column (starting with the first).
+pub fn subsampled_size(width: u32, height: u32, row_step: u32, col_step: u32) -> (u32, u32) {
+    (width.div_ceil(col_step), height.div_ceil(row_step))
+}
+
+/// Copy every `row_step`-th row and `col_step`-th pixel of `src` (rows `pitch`
+/// bytes apart) into `dst`, tightly packed at the size from [`subsampled_size`].
+#[allow(clippy::too_many_arguments)]
+pub fn subsample(
+    src: &[u8],
+    pitch: usize,
+    width: u32,
+    height: u32,
+    bpp: usize,
+    row_step: u32,
+    col_step: u32,
+    dst: &mut [u8],
+) {
+    let row_bytes = width as usize * bpp;
+    let (out_width, _) = subsampled_size(width, height, row_step, col_step);
+    let out_row_bytes = out_width as usize * bpp;
+    let rows = (0..height as usize).step_by(row_step as usize);
+    for (y, out_row) in rows.zip(dst.chunks_exact_mut(out_row_bytes)) {
+        let row = &src[y * pitch..y * pitch + row_bytes];
+        if col_step == 1 {
+            out_row.copy_from_slice(row);
+            continue;
+        }
+        let pixels = row.chunks_exact(bpp).step_by(col_step as usize);
+        for (px, out_px) in pixels.zip(out_row.chunks_exact_mut(bpp)) {
+            out_px.copy_from_slice(px);
         }
+    }
+}
 
-        let init_bytes: Vec<u8> = init_data.iter().flat_map(|v| v.to_ne_bytes()).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::d3d11::create_d3d11_device;
 
+    /// Upload `bytes` (rows `pitch` bytes apart) into a new single-mip texture.
+    fn upload_texture(
+        device: &ID3D11Device,
+        width: u32,
+        height: u32,
+        format: DXGI_FORMAT,
+        bytes: &[u8],
+        pitch: u32,
+    ) -> ID3D11Texture2D {
         let desc = D3D11_TEXTURE2D_DESC {
-            Width: 2,
-            Height: 2,
+            Width: width,
+            Height: height,
             MipLevels: 1,
             ArraySize: 1,
-            Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
+            Format: format,
             SampleDesc: DXGI_SAMPLE_DESC {
                 Count: 1,
                 Quality: 0,
@@ -237,21 +923,46 @@ mod tests {
             CPUAccessFlags: 0,
             MiscFlags: 0,
         };
-
         let subresource_data = D3D11_SUBRESOURCE_DATA {
-            pSysMem: init_bytes.as_ptr() as *const _,
-            SysMemPitch: 16, // 2 pixels × 8 bytes
+            pSysMem: bytes.as_ptr() as *const _,
+            SysMemPitch: pitch,
             SysMemSlicePitch: 0,
         };
-
+        let mut texture = None;
+        // SAFETY: `bytes` outlives the call and holds `height` rows of `pitch` bytes.
         unsafe {
-            let mut texture = None;
-            d3d_ctx
-                .device
+            device
                 .CreateTexture2D(&desc, Some(&subresource_data), Some(&mut texture))
                 .unwrap();
-            let texture = texture.unwrap();
+        }
+        texture.unwrap()
+    }
+
+    #[test]
+    fn test_texture_readback_row_stripped() {
+        let d3d_ctx = create_d3d11_device().unwrap();
+        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
 
+        // 2x2 R16G16B16A16_FLOAT, all red pixels
+        // f16: 1.0 = 0x3C00, 0.0 = 0x0000
+        let pixel_red: [u16; 4] = [0x3C00, 0x0000, 0x0000, 0x3C00];
+        let mut init_data = Vec::new();
+        for _ in 0..4 {
+            init_data.extend_from_slice(&pixel_red);
+        }
+
+        let init_bytes: Vec<u8> = init_data.iter().flat_map(|v| v.to_ne_bytes()).collect();
+
+        let texture = upload_texture(
+            &d3d_ctx.device,
+            2,
+            2,
+            DXGI_FORMAT_R16G16B16A16_FLOAT,
+            &init_bytes,
+            16, // 2 pixels × 8 bytes
+        );
+
+        unsafe {
             let data = reader.read_texture(&texture).unwrap();
 
             // After stripping padding, data size should be exactly 2 × 2 × 8 = 32 bytes
@@ -269,4 +980,176 @@ mod tests {
             assert_eq!(u16_data[9], 0x0000); // G
         }
     }
+
+    #[test]
+    fn test_direct_readback_matches_staging() {
+        let d3d_ctx = create_d3d11_device().unwrap();
+        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+        if reader
+            .set_strategy(ReadbackStrategy::MappableDirect)
+            .is_err()
+        {
+            return; // GPU can't map default textures
+        }
+
+        // 3x2 BGRA8 with distinct bytes; 12-byte rows are padded on the GPU.
+        let init_bytes: Vec<u8> = (0..24).collect();
+        let texture = upload_texture(
+            &d3d_ctx.device,
+            3,
+            2,
+            DXGI_FORMAT_B8G8R8A8_UNORM,
+            &init_bytes,
+            12,
+        );
+
+        assert_eq!(reader.read_texture(&texture).unwrap(), init_bytes);
+        let mut dst = [0u8; 24];
+        assert_eq!(reader.read_texture_into(&texture, &mut dst).unwrap(), 24);
+        assert_eq!(dst.as_slice(), init_bytes);
+    }
+
+    #[test]
+    fn test_readback_with_stride_pads_and_truncates() {
+        let d3d_ctx = create_d3d11_device().unwrap();
+        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+
+        // 3x2 BGRA8: 12-byte rows, not a multiple of 16.
+        let init_bytes: Vec<u8> = (0..24).collect();
+        let texture = upload_texture(
+            &d3d_ctx.device,
+            3,
+            2,
+            DXGI_FORMAT_B8G8R8A8_UNORM,
+            &init_bytes,
+            12,
+        );
+
+        let (padded, stride) = reader.read_texture_with_stride(&texture, 16).unwrap();
+        assert_eq!(stride, 16);
+        assert_eq!(padded.len(), 32);
+        assert_eq!(&padded[..12], &init_bytes[..12]);
+        assert_eq!(&padded[12..16], [0; 4]);
+        assert_eq!(&padded[16..28], &init_bytes[12..]);
+
+        let (truncated, stride) = reader.read_texture_with_stride(&texture, 8).unwrap();
+        assert_eq!(stride, 8);
+        assert_eq!(&truncated[..8], &init_bytes[..8]);
+        assert_eq!(&truncated[8..], &init_bytes[12..20]);
+
+        let (tight, stride) = reader.read_texture_with_stride(&texture, 0).unwrap();
+        assert_eq!((tight, stride), (init_bytes, 12));
+    }
+
+    #[test]
+    fn test_mip_level_for_fits_max_dim() {
+        assert_eq!(mip_level_for(1920, 1080, 4096), (0, 1920, 1080));
+        assert_eq!(mip_level_for(1920, 1080, 256), (3, 240, 135));
+        assert_eq!(mip_level_for(1920, 1080, 1), (10, 1, 1));
+        assert_eq!(mip_level_for(7, 1, 2), (2, 1, 1));
+    }
+
+    #[test]
+    fn test_mip_readback_averages_texture() {
+        let d3d_ctx = create_d3d11_device().unwrap();
+        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+
+        // 4x4 BGRA8: left half white, right half black.
+        let init_bytes: Vec<u8> = (0..16)
+            .flat_map(|i| match i % 4 {
+                0 | 1 => [255, 255, 255, 255],
+                _ => [0, 0, 0, 255],
+            })
+            .collect();
+        let texture = upload_texture(
+            &d3d_ctx.device,
+            4,
+            4,
+            DXGI_FORMAT_B8G8R8A8_UNORM,
+            &init_bytes,
+            16,
+        );
+
+        let (half, w, h) = reader.read_texture_mip(&texture, 2).unwrap();
+        assert_eq!((w, h), (2, 2));
+        assert_eq!(&half[..8], [255, 255, 255, 255, 0, 0, 0, 255]);
+
+        let (avg, w, h) = reader.read_texture_mip(&texture, 1).unwrap();
+        assert_eq!((w, h), (1, 1));
+        assert!(
+            avg[..3].iter().all(|&c| (127..=128).contains(&c)),
+            "{avg:?}"
+        );
+        assert_eq!(avg[3], 255);
+    }
+
+    #[test]
+    fn test_subsample_skips_rows_columns_and_padding() {
+        // 3x3 single-byte "pixels" with one byte of row padding (pitch 4)
+        let src = [0, 1, 2, 99, 10, 11, 12, 99, 20, 21, 22];
+        assert_eq!(subsampled_size(3, 3, 2, 2), (2, 2));
+        let mut dst = [0u8; 4];
+        subsample(&src, 4, 3, 3, 1, 2, 2, &mut dst);
+        assert_eq!(dst, [0, 2, 20, 22]);
+
+        let mut dst = [0u8; 6];
+        subsample(&src, 4, 3, 3, 1, 2, 1, &mut dst);
+        assert_eq!(dst, [0, 1, 2, 20, 21, 22]);
+    }
+
+    #[test]
+    fn test_texture_region_readback() {
+        let d3d_ctx = create_d3d11_device().unwrap();
+        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+
+        // 4x2 BGRA8, pixel (x, y) = [x, y, 0, 255]
+        let init_bytes: Vec<u8> = (0..2u8)
+            .flat_map(|y| (0..4u8).flat_map(move |x| [x, y, 0, 255]))
+            .collect();
+        let texture = upload_texture(
+            &d3d_ctx.device,
+            4,
+            2,
+            DXGI_FORMAT_B8G8R8A8_UNORM,
+            &init_bytes,
+            16,
+        );
+
+        let region = D3D11_BOX {
+            left: 1,
+            top: 1,
+            front: 0,
+            right: 3,
+            bottom: 2,
+            back: 1,
+        };
+        let data = reader.read_texture_region(&texture, region).unwrap();
+        assert_eq!(data, vec![1, 1, 0, 255, 2, 1, 0, 255]);
+
+        let outside = D3D11_BOX { right: 5, ..region };
+        assert!(reader.read_texture_region(&texture, outside).is_err());
+    }
+
+    #[test]
+    fn test_queued_readback_keeps_frames_apart() {
+        let d3d_ctx = create_d3d11_device().unwrap();
+        let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+
+        // Two 1x1 BGRA8 textures with different colors, in flight together.
+        let textures: Vec<_> = [[1u8, 2, 3, 255], [4, 5, 6, 255]]
+            .iter()
+            .map(|pixel| {
+                upload_texture(&d3d_ctx.device, 1, 1, DXGI_FORMAT_B8G8R8A8_UNORM, pixel, 4)
+            })
+            .collect();
+
+        reader.queue_copy(&textures[0], 0).unwrap();
+        reader.queue_copy(&textures[1], 1).unwrap();
+        let mut dst = [0u8; 4];
+        assert_eq!(reader.read_queued_into(0, &mut dst).unwrap(), 4);
+        assert_eq!(dst, [1, 2, 3, 255]);
+        reader.read_queued_into(1, &mut dst).unwrap();
+        assert_eq!(dst, [4, 5, 6, 255]);
+        assert!(reader.read_queued_into(2, &mut dst).is_err());
+    }
 }
@@ -0,0 +1,220 @@
+// Capture on a global hotkey
+//
+// on_hotkey() registers a system-wide hotkey with RegisterHotKey on a
+// dedicated thread. The hotkey is bound to that thread (no window), so
+// WM_HOTKEY lands in its message queue; each press runs a single-shot capture
+// (grab_once) and hands the frame to the callback on the same thread.
+// Dropping the returned handle posts WM_QUIT, which ends the loop and
+// unregisters the hotkey.
+
+use std::ops::BitOr;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use anyhow::{anyhow, Context, Result};
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT,
+    MOD_SHIFT, MOD_WIN,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetMessageW, PeekMessageW, PostThreadMessageW, MSG, PM_NOREMOVE, WM_HOTKEY, WM_QUIT,
+};
+
+use crate::com::ComGuard;
+use crate::pipeline::{grab_once, CapturePipelineBuilder, CapturedFrame};
+
+/// Hotkey id; unique per thread, and each hotkey gets its own thread.
+const HOTKEY_ID: i32 = 1;
+
+/// Modifier keys that must be held with the hotkey.
+///
+/// Combine with `|`, e.g. `Modifiers::CONTROL | Modifiers::SHIFT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers(u32);
+
+impl Modifiers {
+    pub const NONE: Self = Self(0);
+    pub const ALT: Self = Self(MOD_ALT.0);
+    pub const CONTROL: Self = Self(MOD_CONTROL.0);
+    pub const SHIFT: Self = Self(MOD_SHIFT.0);
+    pub const WIN: Self = Self(MOD_WIN.0);
+
+    /// Whether every modifier in `other` is also in `self`.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A registered hotkey; dropping it unregisters the hotkey and stops its thread.
+///
+/// Don't drop the handle from inside its own callback: the thread can't
+/// wait for itself. Let the callback signal another thread instead.
+pub struct HotkeyHandle {
+    thread_id: u32,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HotkeyHandle {
+    /// Unregister the hotkey and wait for a capture in progress to finish.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        // SAFETY: posting to a thread id we own; fails only if the thread
+        // already exited, in which case join returns immediately.
+        let posted = unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) };
+        if let Err(e) = posted {
+            log::debug!("failed to post WM_QUIT to hotkey thread: {e}");
+        }
+        let _ = thread.join();
+    }
+}
+
+impl Drop for HotkeyHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Capture monitor 0 every time the hotkey is pressed.
+///
+/// `vk` is a Windows virtual-key code (e.g. `0x2C` for Print Screen,
+/// `b'S' as u32` for S). Holding the keys down doesn't repeat. See
+/// [`on_hotkey_with`] for other targets and options.
+///
+/// # Examples
+/// ```no_run
+/// use hdrcapture::hotkey::{on_hotkey, Modifiers};
+///
+/// let handle = on_hotkey(Modifiers::CONTROL | Modifiers::SHIFT, b'S' as u32, |frame| {
+///     frame.save("hotkey.png").unwrap();
+/// })
+/// .unwrap();
+/// std::thread::park(); // keep the process alive; drop `handle` to stop
+/// # drop(handle);
+/// ```
+pub fn on_hotkey<F>(modifiers: Modifiers, vk: u32, callback: F) -> Result<HotkeyHandle>
+where
+    F: FnMut(CapturedFrame) + Send + 'static,
+{
+    on_hotkey_with(
+        CapturePipelineBuilder::new().monitor(0),
+        modifiers,
+        vk,
+        callback,
+    )
+}
+
+/// Capture `target` every time the hotkey is pressed.
+///
+/// Each press runs [`grab_once`] on a fresh single-use pipeline, so nothing
+/// is held between presses and a window that was closed and reopened is
+/// found again. The callback runs on the hotkey thread; presses during a
+/// slow callback are handled after it returns. Capture failures are logged
+/// at `warn` and the loop keeps going.
+///
+/// Fails if the combination is already registered by another application.
+pub fn on_hotkey_with<F>(
+    target: CapturePipelineBuilder,
+    modifiers: Modifiers,
+    vk: u32,
+    mut callback: F,
+) -> Result<HotkeyHandle>
+where
+    F: FnMut(CapturedFrame) + Send + 'static,
+{
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let thread = std::thread::Builder::new()
+        .name("hdrcapture-hotkey".into())
+        .spawn(move || {
+            let setup = ComGuard::init_mta()
+                .context("COM init failed")
+                .and_then(|guard| Ok((register(modifiers, vk)?, guard)));
+            let _com_guard = match setup {
+                Ok((thread_id, guard)) => {
+                    let _ = ready_tx.send(Ok(thread_id));
+                    guard
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+
+            let mut msg = MSG::default();
+            // SAFETY: standard message loop over this thread's own queue.
+            while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {
+                if msg.message != WM_HOTKEY {
+                    continue;
+                }
+                match grab_once(target.clone()) {
+                    Ok(frame) => callback(frame),
+                    Err(e) => log::warn!("hotkey capture failed: {e:#}"),
+                }
+            }
+            // SAFETY: unregisters the hotkey this thread registered.
+            let _ = unsafe { UnregisterHotKey(None, HOTKEY_ID) };
+        })
+        .context("failed to spawn hotkey thread")?;
+
+    let thread_id = ready_rx
+        .recv()
+        .map_err(|_| anyhow!("hotkey thread exited during startup"))??;
+    Ok(HotkeyHandle {
+        thread_id,
+        thread: Some(thread),
+    })
+}
+
+/// Create this thread's message queue and register the hotkey on it.
+fn register(modifiers: Modifiers, vk: u32) -> Result<u32> {
+    let mut msg = MSG::default();
+    // SAFETY: PeekMessageW only forces creation of this thread's queue, so
+    // WM_QUIT posted right after startup isn't lost; RegisterHotKey binds
+    // the hotkey to this thread.
+    unsafe {
+        let _ = PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE);
+        RegisterHotKey(
+            None,
+            HOTKEY_ID,
+            HOT_KEY_MODIFIERS(modifiers.0) | MOD_NOREPEAT,
+            vk,
+        )
+        .with_context(|| {
+            format!(
+                "RegisterHotKey failed (modifiers 0x{:x}, key 0x{vk:02x}); is it already in use?",
+                modifiers.0
+            )
+        })?;
+        Ok(GetCurrentThreadId())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modifiers_combine() {
+        let mods = Modifiers::CONTROL | Modifiers::SHIFT;
+        assert!(mods.contains(Modifiers::CONTROL));
+        assert!(mods.contains(Modifiers::SHIFT));
+        assert!(!mods.contains(Modifiers::ALT));
+        assert!(mods.contains(Modifiers::NONE));
+        assert_eq!(Modifiers::default(), Modifiers::NONE);
+    }
+}
@@ -1,18 +1,185 @@
 // Image encoding module.
 //
-// Unified save() entry point dispatches by file extension:
-// - Standard formats (png, bmp, jpg, tiff): `basic` submodule via `image` crate, BGRA8 only
+// Unified save() entry point dispatches by file extension; encode() produces
+// the same output in memory (Cursor for `image`/`exr`, HGLOBAL stream for WIC):
+// - Standard formats (png, bmp, jpg, tiff, webp): `basic` submodule via `image` crate, BGRA8 only;
+//   PNG/JPEG/WebP embed an ICC profile (sRGB unless the caller supplies another)
 // - JPEG XR (.jxr): `jxr` submodule via WIC COM API, supports both BGRA8 and RGBA16F
+// - HEIF (.heic/.heif): `heif` submodule via WIC, 8-bit SDR or 10-bit HDR10
+// - Radiance (.hdr): `hdr` submodule, RGBE shared-exponent, both BGRA8 and RGBA16F
+//
+// clipboard puts a BGRA8 image on the Windows clipboard (CF_DIBV5 + PNG).
+//
+// RGB10A2 (HDR10) frames go wherever RGBA16F does: HEIF stores them as-is, the
+// other HDR encoders decode them to scRGB first.
+//
+// Provenance (capture time, source, software) is written to EXR and JPEG XR
+// output when a `SaveMetadata` is passed; see the `metadata` submodule.
+//
+// Alpha: PNG/BMP/TIFF/WebP/EXR write opaque pixels unless the frame's AlphaMode says
+// alpha is meaningful; JPEG and Radiance never have alpha; JXR/HEIF store it as-is.
 
 pub mod basic;
+pub mod clipboard;
 pub mod exr;
+pub mod hdr;
+pub mod heif;
 pub mod jxr;
+mod metadata;
+mod wic;
 
 use std::path::Path;
+use std::sync::OnceLock;
 
 use anyhow::{bail, Result};
 
-use crate::color::ColorPixelFormat;
+use crate::color::{AlphaMode, ColorPixelFormat, ColorProfile};
+pub use basic::{JpegOptions, WebpOptions};
+pub use exr::{ExrCompression, ExrOptions};
+pub use metadata::SaveMetadata;
+
+/// Optional settings for [`save_with`], [`encode_with`] and
+/// [`CapturedFrame::save_with`](crate::pipeline::CapturedFrame::save_with).
+///
+/// Each encoder setting only applies to its own format and is ignored by
+/// the others, so one `SaveOptions` can be reused across extensions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaveOptions {
+    /// Profile embedded in PNG / JPEG / WebP output (default: sRGB, or the
+    /// frame's own `color_profile` when saving a `CapturedFrame`).
+    pub profile: Option<ColorProfile>,
+    /// `.exr` settings, e.g. ZIP for smaller archives or no compression for
+    /// the fastest writes.
+    pub exr: ExrOptions,
+    /// `.jpg` settings.
+    pub jpeg: JpegOptions,
+    /// `.webp` settings.
+    pub webp: WebpOptions,
+    /// Provenance written to `.exr` and `.jxr` output (none by default).
+    pub metadata: SaveMetadata,
+}
+
+/// Output image container format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Bmp,
+    Jpeg,
+    Tiff,
+    Webp,
+    Jxr,
+    Exr,
+    Heif,
+    Hdr,
+}
+
+impl ImageFormat {
+    /// Format for a file extension (without the dot, case-insensitive).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        Some(match ext.to_ascii_lowercase().as_str() {
+            "png" => Self::Png,
+            "bmp" => Self::Bmp,
+            "jpg" | "jpeg" => Self::Jpeg,
+            "tiff" | "tif" => Self::Tiff,
+            "webp" => Self::Webp,
+            "jxr" => Self::Jxr,
+            "exr" => Self::Exr,
+            "heic" | "heif" => Self::Heif,
+            "hdr" => Self::Hdr,
+            _ => return None,
+        })
+    }
+
+    /// Whether the format can only store BGRA8 (SDR) frames.
+    pub fn is_sdr_only(self) -> bool {
+        matches!(
+            self,
+            Self::Png | Self::Bmp | Self::Jpeg | Self::Tiff | Self::Webp
+        )
+    }
+
+    /// Canonical file extension (without the dot).
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Bmp => "bmp",
+            Self::Jpeg => "jpg",
+            Self::Tiff => "tiff",
+            Self::Webp => "webp",
+            Self::Jxr => "jxr",
+            Self::Exr => "exr",
+            Self::Heif => "heic",
+            Self::Hdr => "hdr",
+        }
+    }
+}
+
+/// Runtime capabilities of one output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatInfo {
+    pub format: ImageFormat,
+    /// Canonical file extension (without the dot).
+    pub ext: &'static str,
+    /// Can store RGBA16F (HDR) frames on this system.
+    pub supports_hdr: bool,
+    /// Can store BGRA8 (SDR) frames.
+    pub supports_sdr: bool,
+    /// Encoder is installed and working on this system.
+    pub available: bool,
+}
+
+/// All output formats with their availability on this system.
+///
+/// `image`/`exr`/Radiance formats are built in. JPEG XR and HEIF depend on WIC codecs
+/// (HEIF needs the HEVC Video Extensions, and Windows 11 for 10-bit HDR), so
+/// they are probed once with a tiny in-memory encode and the result is cached.
+/// When COM or the WIC factory can't be set up on the calling thread, they
+/// are reported unavailable without caching, and the next call probes again.
+pub fn supported_formats() -> Vec<FormatInfo> {
+    static PROBED: OnceLock<Vec<FormatInfo>> = OnceLock::new();
+    if let Some(formats) = PROBED.get() {
+        return formats.clone();
+    }
+    if !wic::factory_available() {
+        return probe_formats(false);
+    }
+    PROBED.get_or_init(|| probe_formats(true)).clone()
+}
+
+/// Availability of every format; the WIC codecs are only probed when `wic_ok`
+/// says the factory works.
+fn probe_formats(wic_ok: bool) -> Vec<FormatInfo> {
+    const PROBE_SIZE: u32 = 16;
+    let sdr = vec![0u8; (PROBE_SIZE * PROBE_SIZE * 4) as usize];
+    let hdr = vec![0u8; (PROBE_SIZE * PROBE_SIZE * 8) as usize];
+    let probe =
+        |encode: fn(&[u8], u32, u32, ColorPixelFormat) -> Result<Vec<u8>>,
+         data: &[u8],
+         format: ColorPixelFormat| { encode(data, PROBE_SIZE, PROBE_SIZE, format).is_ok() };
+
+    let jxr = wic_ok && probe(jxr::encode_jxr, &sdr, ColorPixelFormat::Bgra8);
+    let heif_sdr = wic_ok && probe(heif::encode_heif, &sdr, ColorPixelFormat::Bgra8);
+    let heif_hdr = heif_sdr && probe(heif::encode_heif, &hdr, ColorPixelFormat::Rgba16f);
+
+    let info = |format: ImageFormat, supports_hdr: bool, available: bool| FormatInfo {
+        format,
+        ext: format.extension(),
+        supports_hdr,
+        supports_sdr: true,
+        available,
+    };
+    vec![
+        info(ImageFormat::Png, false, true),
+        info(ImageFormat::Bmp, false, true),
+        info(ImageFormat::Jpeg, false, true),
+        info(ImageFormat::Tiff, false, true),
+        info(ImageFormat::Webp, false, true),
+        info(ImageFormat::Jxr, jxr, jxr),
+        info(ImageFormat::Exr, true, true),
+        info(ImageFormat::Heif, heif_hdr, heif_sdr),
+        info(ImageFormat::Hdr, true, true),
+    ]
+}
 
 /// Save pixel data to file. Format is determined by extension.
 ///
@@ -21,8 +188,17 @@ use crate::color::ColorPixelFormat;
 /// - `.bmp` — BMP (lossless, BGRA8 only)
 /// - `.jpg` / `.jpeg` — JPEG (lossy, BGRA8 only)
 /// - `.tiff` / `.tif` — TIFF (lossless, BGRA8 only)
+/// - `.webp` — WebP (lossless, BGRA8 only)
 /// - `.jxr` — JPEG XR (lossless, BGRA8 and RGBA16F)
 /// - `.exr` — OpenEXR (lossless, BGRA8 and RGBA16F)
+/// - `.heic` / `.heif` — HEIF (lossy, BGRA8 and RGBA16F as 10-bit HDR10;
+///   needs the HEVC Video Extensions)
+/// - `.hdr` — Radiance RGBE (BGRA8 and RGBA16F, no alpha)
+///
+/// RGB10A2 (HDR10) frames are accepted wherever RGBA16F is.
+///
+/// PNG, JPEG and WebP output is tagged with the built-in sRGB profile. The alpha
+/// channel is written as given.
 pub fn save(
     path: &Path,
     data: &[u8],
@@ -30,6 +206,50 @@ pub fn save(
     height: u32,
     format: ColorPixelFormat,
 ) -> Result<()> {
+    save_with_profile(
+        path,
+        data,
+        width,
+        height,
+        format,
+        &ColorProfile::Srgb,
+        AlphaMode::Straight,
+    )
+}
+
+/// Like [`save`], embedding `profile` in PNG/JPEG/WebP output.
+///
+/// Other formats ignore the profile. With `AlphaMode::Ignore`, PNG/BMP/TIFF/WebP/EXR
+/// output is written fully opaque.
+pub fn save_with_profile(
+    path: &Path,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    profile: &ColorProfile,
+    alpha: AlphaMode,
+) -> Result<()> {
+    let options = SaveOptions {
+        profile: Some(profile.clone()),
+        ..Default::default()
+    };
+    save_with(path, data, width, height, format, alpha, &options)
+}
+
+/// Like [`save_with_profile`], with the profile, encoder settings and
+/// metadata taken from `options`.
+pub fn save_with(
+    path: &Path,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    alpha: AlphaMode,
+    options: &SaveOptions,
+) -> Result<()> {
+    format.check_len(data, width, height)?;
+
     // Auto-create parent directories if they don't exist
     if let Some(parent) = path.parent() {
         if !parent.as_os_str().is_empty() {
@@ -43,15 +263,249 @@ pub fn save(
         .unwrap_or("")
         .to_ascii_lowercase();
 
-    match ext.as_str() {
-        "jxr" => jxr::save_jxr(path, data, width, height, format),
-        "exr" => exr::save_exr(path, data, width, height, format),
-        "png" | "bmp" | "jpg" | "jpeg" | "tiff" | "tif" => {
-            basic::save(path, data, width, height, format)
-        }
-        _ => bail!(
-            "unsupported extension '.{}'; supported: .png .bmp .jpg .tiff (SDR), .jxr .exr (HDR/SDR)",
+    let Some(image_format) = ImageFormat::from_extension(&ext) else {
+        bail!(
+            "unsupported extension '.{}'; supported: .png .bmp .jpg .tiff .webp (SDR), .jxr .exr .heic .hdr (HDR/SDR)",
             ext
+        );
+    };
+
+    let metadata = &options.metadata;
+    match image_format {
+        ImageFormat::Jxr => jxr::save_jxr(path, data, width, height, format, metadata),
+        ImageFormat::Exr => exr::save_exr(
+            path,
+            data,
+            width,
+            height,
+            format,
+            alpha,
+            options.exr,
+            metadata,
+        ),
+        ImageFormat::Heif => heif::save_heif(path, data, width, height, format),
+        ImageFormat::Hdr => hdr::save_hdr(path, data, width, height, format),
+        ImageFormat::Png
+        | ImageFormat::Bmp
+        | ImageFormat::Jpeg
+        | ImageFormat::Tiff
+        | ImageFormat::Webp => basic::save(
+            path,
+            data,
+            width,
+            height,
+            format,
+            options.profile.as_ref().unwrap_or(&ColorProfile::Srgb),
+            alpha,
+            options.jpeg,
+            options.webp,
         ),
     }
 }
+
+/// Encode pixel data into an in-memory buffer, without touching disk.
+///
+/// Same format support and profile / alpha handling as [`save_with_profile`].
+pub fn encode(
+    image_format: ImageFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    profile: &ColorProfile,
+    alpha: AlphaMode,
+) -> Result<Vec<u8>> {
+    let options = SaveOptions {
+        profile: Some(profile.clone()),
+        ..Default::default()
+    };
+    encode_with(image_format, data, width, height, format, alpha, &options)
+}
+
+/// Like [`encode`], with the profile, encoder settings and metadata taken
+/// from `options`, as [`save_with`] applies them.
+pub fn encode_with(
+    image_format: ImageFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    alpha: AlphaMode,
+    options: &SaveOptions,
+) -> Result<Vec<u8>> {
+    format.check_len(data, width, height)?;
+    match image_format {
+        ImageFormat::Jxr => jxr::encode_jxr(data, width, height, format),
+        ImageFormat::Exr => exr::encode_exr(
+            data,
+            width,
+            height,
+            format,
+            alpha,
+            options.exr,
+            &options.metadata,
+        ),
+        ImageFormat::Heif => heif::encode_heif(data, width, height, format),
+        ImageFormat::Hdr => hdr::encode_hdr(data, width, height, format),
+        ImageFormat::Png
+        | ImageFormat::Bmp
+        | ImageFormat::Jpeg
+        | ImageFormat::Tiff
+        | ImageFormat::Webp => basic::encode(
+            image_format,
+            data,
+            width,
+            height,
+            format,
+            options.profile.as_ref().unwrap_or(&ColorProfile::Srgb),
+            alpha,
+            options.jpeg,
+            options.webp,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_format_extension_round_trip() {
+        for format in [
+            ImageFormat::Png,
+            ImageFormat::Bmp,
+            ImageFormat::Jpeg,
+            ImageFormat::Tiff,
+            ImageFormat::Webp,
+            ImageFormat::Jxr,
+            ImageFormat::Exr,
+            ImageFormat::Heif,
+            ImageFormat::Hdr,
+        ] {
+            assert_eq!(
+                ImageFormat::from_extension(format.extension()),
+                Some(format)
+            );
+        }
+        assert_eq!(ImageFormat::from_extension("JPEG"), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::from_extension("avif"), None);
+    }
+
+    #[test]
+    fn test_encode_rejects_wrong_length_data() {
+        // Every wrong length up to twice the right one, from an aligned and a
+        // misaligned start: the length check runs before any encoder.
+        let (width, height) = (3, 2);
+        let buffer = [0u8; 3 * 2 * 8 * 2 + 3];
+        let profile = ColorProfile::Srgb;
+        for image_format in [
+            ImageFormat::Png,
+            ImageFormat::Bmp,
+            ImageFormat::Jpeg,
+            ImageFormat::Tiff,
+            ImageFormat::Webp,
+            ImageFormat::Jxr,
+            ImageFormat::Exr,
+            ImageFormat::Heif,
+            ImageFormat::Hdr,
+        ] {
+            for format in [
+                ColorPixelFormat::Bgra8,
+                ColorPixelFormat::Rgba16f,
+                ColorPixelFormat::Rgb10a2,
+            ] {
+                let expected = (width * height) as usize * format.bytes_per_pixel();
+                for offset in [0, 1] {
+                    for len in (0..=2 * expected + 1).filter(|&len| len != expected) {
+                        let data = &buffer[offset..offset + len];
+                        let result = encode(
+                            image_format,
+                            data,
+                            width,
+                            height,
+                            format,
+                            &profile,
+                            AlphaMode::Ignore,
+                        );
+                        assert!(
+                            result.is_err(),
+                            "{image_format:?} {format:?} len {len} offset {offset}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_png_in_memory() {
+        let data = [0u8, 0, 255, 255].repeat(4);
+        let bytes = encode(
+            ImageFormat::Png,
+            &data,
+            2,
+            2,
+            ColorPixelFormat::Bgra8,
+            &ColorProfile::Untagged,
+            AlphaMode::Ignore,
+        )
+        .unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn test_encode_webp_in_memory() {
+        let data = [0u8, 0, 255, 255].repeat(4);
+        let bytes = encode(
+            ImageFormat::Webp,
+            &data,
+            2,
+            2,
+            ColorPixelFormat::Bgra8,
+            &ColorProfile::Untagged,
+            AlphaMode::Ignore,
+        )
+        .unwrap();
+        assert_eq!(&bytes[..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WEBP");
+
+        let hdr = encode(
+            ImageFormat::Webp,
+            &[0u8; 32],
+            2,
+            2,
+            ColorPixelFormat::Rgba16f,
+            &ColorProfile::Untagged,
+            AlphaMode::Ignore,
+        );
+        assert!(hdr.is_err());
+    }
+
+    #[test]
+    fn test_save_jpeg_quality() {
+        // Noisy content so quality actually changes the file size.
+        let data: Vec<u8> = (0..32 * 32 * 4).map(|i| (i * 37 % 251) as u8).collect();
+        let dir = std::env::temp_dir().join(format!("hdrcapture_jpeg_{}", std::process::id()));
+        let save_at = |quality: u8| {
+            let path = dir.join(format!("q{quality}.jpg"));
+            let options = SaveOptions {
+                jpeg: JpegOptions { quality },
+                ..Default::default()
+            };
+            save_with(
+                &path,
+                &data,
+                32,
+                32,
+                ColorPixelFormat::Bgra8,
+                AlphaMode::Ignore,
+                &options,
+            )
+            .map(|()| std::fs::metadata(&path).unwrap().len())
+        };
+
+        assert!(save_at(20).unwrap() < save_at(95).unwrap());
+        assert!(save_at(0).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,18 +1,57 @@
 // Standard image format encoding via the `image` crate.
 //
 // Supports BGRA8 (SDR) frames only:
-// - PNG  (lossless)
+// - PNG  (lossless, iCCP chunk)
 // - BMP  (lossless)
-// - JPEG (lossy)
+// - JPEG (lossy, APP2 ICC marker)
 // - TIFF (lossless)
+// - WebP (lossless, ICCP chunk)
 
+use std::io::{Cursor, Seek, Write};
 use std::path::Path;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::{CompressionType, FilterType, PngEncoder};
-use image::{ExtendedColorType, ImageEncoder, ImageFormat};
+use image::codecs::webp::WebPEncoder;
+use image::{ExtendedColorType, ImageEncoder};
 
-use crate::color::ColorPixelFormat;
+use super::ImageFormat;
+use crate::color::{convert, AlphaMode, ColorPixelFormat, ColorProfile};
+
+/// JPEG encoder settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JpegOptions {
+    /// Quality, 1–100 (default: 90). Higher is larger and closer to the source.
+    pub quality: u8,
+}
+
+impl Default for JpegOptions {
+    fn default() -> Self {
+        Self { quality: 90 }
+    }
+}
+
+/// WebP encoder settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebpOptions {
+    /// Lossless encoding (default: true).
+    pub lossless: bool,
+    /// Lossy quality, 0–100 (default: 80). Ignored when `lossless` is set.
+    ///
+    /// The `image` crate only ships a lossless WebP encoder, so lossy output
+    /// is currently rejected.
+    pub quality: u8,
+}
+
+impl Default for WebpOptions {
+    fn default() -> Self {
+        Self {
+            lossless: true,
+            quality: 80,
+        }
+    }
+}
 
 /// SDR format variants handled by the `image` crate.
 enum SdrFormat {
@@ -20,33 +59,92 @@ enum SdrFormat {
     Bmp,
     Jpeg,
     Tiff,
+    Webp,
+}
+
+impl SdrFormat {
+    fn from_image_format(format: ImageFormat) -> Result<Self> {
+        Ok(match format {
+            ImageFormat::Png => Self::Png,
+            ImageFormat::Bmp => Self::Bmp,
+            ImageFormat::Jpeg => Self::Jpeg,
+            ImageFormat::Tiff => Self::Tiff,
+            ImageFormat::Webp => Self::Webp,
+            other => bail!("basic: unsupported format {:?}", other),
+        })
+    }
 }
 
 /// Save a BGRA8 frame using the `image` crate.
 ///
 /// The target format is inferred from the file extension.
+/// `profile` is embedded in PNG, JPEG and WebP output; BMP and TIFF are written untagged.
+/// With `AlphaMode::Ignore` the alpha channel is written as 255.
+/// `jpeg` only applies to `.jpg` output, `webp` to `.webp`.
 /// Errors if the pixel format is not BGRA8.
+#[allow(clippy::too_many_arguments)]
 pub fn save(
     path: &Path,
     data: &[u8],
     width: u32,
     height: u32,
     format: ColorPixelFormat,
+    profile: &ColorProfile,
+    alpha: AlphaMode,
+    jpeg: JpegOptions,
+    webp: WebpOptions,
 ) -> Result<()> {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
         .to_ascii_lowercase();
+    let image_format = ImageFormat::from_extension(&ext)
+        .with_context(|| format!("basic: unsupported extension '.{}'", ext))?;
+    let sdr_fmt = SdrFormat::from_image_format(image_format)?;
+    check_bgra8(&ext, format)?;
 
-    let sdr_fmt = match ext.as_str() {
-        "png" => SdrFormat::Png,
-        "bmp" => SdrFormat::Bmp,
-        "jpg" | "jpeg" => SdrFormat::Jpeg,
-        "tiff" | "tif" => SdrFormat::Tiff,
-        _ => bail!("basic: unsupported extension '.{}'", ext),
-    };
+    let file = std::fs::File::create(path)?;
+    let writer = std::io::BufWriter::new(file);
+    write(
+        writer, sdr_fmt, data, width, height, profile, alpha, jpeg, webp,
+    )
+}
 
+/// Encode a BGRA8 frame into an in-memory buffer.
+///
+/// Same behavior as [`save`], with the format given explicitly.
+#[allow(clippy::too_many_arguments)]
+pub fn encode(
+    image_format: ImageFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    profile: &ColorProfile,
+    alpha: AlphaMode,
+    jpeg: JpegOptions,
+    webp: WebpOptions,
+) -> Result<Vec<u8>> {
+    let sdr_fmt = SdrFormat::from_image_format(image_format)?;
+    check_bgra8(image_format.extension(), format)?;
+
+    let mut buffer = Vec::new();
+    write(
+        Cursor::new(&mut buffer),
+        sdr_fmt,
+        data,
+        width,
+        height,
+        profile,
+        alpha,
+        jpeg,
+        webp,
+    )?;
+    Ok(buffer)
+}
+
+fn check_bgra8(ext: &str, format: ColorPixelFormat) -> Result<()> {
     if format != ColorPixelFormat::Bgra8 {
         bail!(
             "{} only supports BGRA8 (SDR) frames; this frame is {:?}. Use .jxr for HDR data.",
@@ -54,43 +152,64 @@ pub fn save(
             format
         );
     }
+    Ok(())
+}
 
-    // BGRA → RGBA
-    let mut rgba = data.to_vec();
-    for pixel in rgba.chunks_exact_mut(4) {
-        pixel.swap(0, 2);
+#[allow(clippy::too_many_arguments)]
+fn write<W: Write + Seek>(
+    mut writer: W,
+    sdr_fmt: SdrFormat,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    profile: &ColorProfile,
+    alpha: AlphaMode,
+    jpeg: JpegOptions,
+    webp: WebpOptions,
+) -> Result<()> {
+    if matches!(sdr_fmt, SdrFormat::Jpeg) && !(1..=100).contains(&jpeg.quality) {
+        bail!("JPEG quality must be 1-100, got {}", jpeg.quality);
+    }
+    if matches!(sdr_fmt, SdrFormat::Webp) && !webp.lossless {
+        bail!(
+            "lossy WebP (quality {}) is not supported by the built-in encoder; \
+             use lossless WebP or .jpg",
+            webp.quality
+        );
     }
 
-    let file = std::fs::File::create(path)?;
-    let mut writer = std::io::BufWriter::new(file);
+    let rgba = convert::bgra8_to_rgba8(data, alpha == AlphaMode::Ignore);
 
     match sdr_fmt {
         SdrFormat::Png => {
-            let encoder =
+            let mut encoder =
                 PngEncoder::new_with_quality(writer, CompressionType::Fast, FilterType::Sub);
+            if let Some(icc) = profile.bytes() {
+                encoder.set_icc_profile(icc.to_vec())?;
+            }
             encoder.write_image(&rgba, width, height, ExtendedColorType::Rgba8)?;
         }
         SdrFormat::Jpeg => {
             // JPEG doesn't support alpha; strip to RGB
-            let rgb: Vec<u8> = rgba
-                .chunks_exact(4)
-                .flat_map(|px| &px[..3])
-                .copied()
-                .collect();
-            image::write_buffer_with_format(
-                &mut writer,
-                &rgb,
-                width,
-                height,
-                ExtendedColorType::Rgb8,
-                ImageFormat::Jpeg,
-            )?;
+            let rgb = convert::bgra8_to_rgb8(data);
+            let mut encoder = JpegEncoder::new_with_quality(&mut writer, jpeg.quality);
+            if let Some(icc) = profile.bytes() {
+                encoder.set_icc_profile(icc.to_vec())?;
+            }
+            encoder.write_image(&rgb, width, height, ExtendedColorType::Rgb8)?;
+        }
+        SdrFormat::Webp => {
+            let mut encoder = WebPEncoder::new_lossless(writer);
+            if let Some(icc) = profile.bytes() {
+                encoder.set_icc_profile(icc.to_vec())?;
+            }
+            encoder.write_image(&rgba, width, height, ExtendedColorType::Rgba8)?;
         }
         _ => {
             let img_fmt = match sdr_fmt {
-                SdrFormat::Bmp => ImageFormat::Bmp,
-                SdrFormat::Tiff => ImageFormat::Tiff,
-                SdrFormat::Png | SdrFormat::Jpeg => unreachable!(),
+                SdrFormat::Bmp => image::ImageFormat::Bmp,
+                SdrFormat::Tiff => image::ImageFormat::Tiff,
+                SdrFormat::Png | SdrFormat::Jpeg | SdrFormat::Webp => unreachable!(),
             };
             image::write_buffer_with_format(
                 &mut writer,
@@ -0,0 +1,166 @@
+// Clipboard export.
+//
+// An image is put on the clipboard in two formats so every paste target finds one:
+// - CF_DIBV5: 32-bit bitmap with an alpha mask, tagged sRGB (Paint, Office, most native apps)
+// - "PNG": registered format holding a PNG file (browsers, chat apps, image editors
+//   that ignore DIB alpha)
+// Only BGRA8 is accepted: the clipboard has no HDR bitmap format, callers tone-map first.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use windows::core::w;
+use windows::Win32::Foundation::{GlobalFree, HANDLE};
+use windows::Win32::Graphics::Gdi::{BITMAPV5HEADER, BI_BITFIELDS, LCS_GM_IMAGES};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+};
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+use windows::Win32::UI::ColorSystem::LCS_sRGB;
+
+use crate::color::AlphaMode;
+
+/// Standard clipboard format id of `CF_DIBV5` (winuser.h).
+const CF_DIBV5: u32 = 17;
+
+/// Another process may hold the clipboard briefly (clipboard managers, RDP).
+const OPEN_RETRIES: usize = 10;
+const OPEN_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Replace the clipboard contents with a BGRA8 image.
+///
+/// `png` is the same image encoded as PNG, offered alongside the bitmap.
+/// With `AlphaMode::Ignore` the bitmap is written opaque; other modes must
+/// already be straight alpha.
+pub fn set_image(data: &[u8], width: u32, height: u32, alpha: AlphaMode, png: &[u8]) -> Result<()> {
+    let dib = dibv5(data, width, height, alpha)?;
+
+    open_clipboard()?;
+    // SAFETY: the clipboard is open on this thread until CloseClipboard below.
+    let result = unsafe {
+        EmptyClipboard()
+            .context("EmptyClipboard failed")
+            .and_then(|_| set_data(CF_DIBV5, &dib))
+            .and_then(|_| {
+                let png_format = RegisterClipboardFormatW(w!("PNG"));
+                if png_format == 0 {
+                    bail!("RegisterClipboardFormatW(\"PNG\") failed");
+                }
+                set_data(png_format, png)
+            })
+    };
+    // SAFETY: paired with the successful OpenClipboard above.
+    let _ = unsafe { CloseClipboard() };
+    result
+}
+
+fn open_clipboard() -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        // SAFETY: no owner window; the clipboard is associated with this thread.
+        match unsafe { OpenClipboard(None) } {
+            Ok(()) => return Ok(()),
+            Err(_) if attempt + 1 < OPEN_RETRIES => {
+                attempt += 1;
+                std::thread::sleep(OPEN_RETRY_DELAY);
+            }
+            Err(e) => return Err(e).context("clipboard is held by another application"),
+        }
+    }
+}
+
+/// Copy `bytes` into a movable global block and hand it to the clipboard.
+///
+/// The clipboard must be open on the calling thread.
+fn set_data(format: u32, bytes: &[u8]) -> Result<()> {
+    // SAFETY: the block is allocated with bytes.len() bytes and locked while
+    // written. On success the clipboard owns it; on failure it is freed here.
+    unsafe {
+        let hglobal = GlobalAlloc(GMEM_MOVEABLE, bytes.len()).context("GlobalAlloc failed")?;
+        let ptr = GlobalLock(hglobal) as *mut u8;
+        if ptr.is_null() {
+            let _ = GlobalFree(Some(hglobal));
+            bail!("GlobalLock failed");
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+        let _ = GlobalUnlock(hglobal);
+
+        if let Err(e) = SetClipboardData(format, Some(HANDLE(hglobal.0))) {
+            let _ = GlobalFree(Some(hglobal));
+            return Err(e).context("SetClipboardData failed");
+        }
+    }
+    Ok(())
+}
+
+/// Packed `CF_DIBV5`: BITMAPV5HEADER followed by bottom-up BGRA rows.
+fn dibv5(data: &[u8], width: u32, height: u32, alpha: AlphaMode) -> Result<Vec<u8>> {
+    let row_bytes = width as usize * 4;
+    let image_bytes = row_bytes * height as usize;
+    if data.len() < image_bytes {
+        bail!(
+            "BGRA8 buffer too small: required={}, got={}",
+            image_bytes,
+            data.len()
+        );
+    }
+
+    let header = BITMAPV5HEADER {
+        bV5Size: std::mem::size_of::<BITMAPV5HEADER>() as u32,
+        bV5Width: width as i32,
+        // Positive height: bottom-up, which every consumer understands.
+        bV5Height: height as i32,
+        bV5Planes: 1,
+        bV5BitCount: 32,
+        bV5Compression: BI_BITFIELDS,
+        bV5SizeImage: image_bytes as u32,
+        bV5RedMask: 0x00FF_0000,
+        bV5GreenMask: 0x0000_FF00,
+        bV5BlueMask: 0x0000_00FF,
+        bV5AlphaMask: 0xFF00_0000,
+        bV5CSType: LCS_sRGB.0 as u32,
+        bV5Intent: LCS_GM_IMAGES as u32,
+        ..Default::default()
+    };
+
+    let header_bytes = header.bV5Size as usize;
+    let mut dib = Vec::with_capacity(header_bytes + image_bytes);
+    // SAFETY: BITMAPV5HEADER is a repr(C) plain-data struct.
+    dib.extend_from_slice(unsafe {
+        std::slice::from_raw_parts(&header as *const _ as *const u8, header_bytes)
+    });
+    for row in data[..image_bytes].chunks_exact(row_bytes).rev() {
+        dib.extend_from_slice(row);
+    }
+    if alpha == AlphaMode::Ignore {
+        for px in dib[header_bytes..].chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+    }
+    Ok(dib)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dibv5_is_bottom_up_and_opaque() {
+        // 1×2: top row blue, bottom row red, both with zero alpha.
+        let data = [255, 0, 0, 0, 0, 0, 255, 0];
+        let dib = dibv5(&data, 1, 2, AlphaMode::Ignore).unwrap();
+
+        let header = std::mem::size_of::<BITMAPV5HEADER>();
+        assert_eq!(header, 124);
+        assert_eq!(dib.len(), header + 8);
+        assert_eq!(&dib[header..], &[0, 0, 255, 255, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_dibv5_keeps_straight_alpha() {
+        let data = [10, 20, 30, 40];
+        let dib = dibv5(&data, 1, 1, AlphaMode::Straight).unwrap();
+        assert_eq!(&dib[124..], &data);
+        assert!(dibv5(&data, 2, 1, AlphaMode::Straight).is_err());
+    }
+}
@@ -1,88 +1,427 @@
 // OpenEXR (.exr) encoding via the `exr` crate.
 //
-// Supports both BGRA8 (SDR) and RGBA16F (HDR) pixel data.
+// Supports BGRA8 (SDR) and RGBA16F / RGB10A2 (HDR) pixel data.
 // EXR is the industry standard for HDR imagery in VFX, compositing,
 // and professional editing tools (Photoshop, DaVinci Resolve, Blender, Nuke).
 
+use std::io::{BufWriter, Cursor, Seek, Write};
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use exr::meta::attribute::Chromaticities;
 use exr::prelude::*;
 
-use crate::color::ColorPixelFormat;
+use crate::color::convert::f16_values;
+use crate::color::hdr10::hdr10_to_scrgb;
+use crate::color::luminance::srgb_to_linear;
+use crate::color::{AlphaMode, ColorPixelFormat, OutputColorSpace};
+use crate::image::SaveMetadata;
+
+/// Pixel data compression for EXR output. All modes are lossless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExrCompression {
+    /// Uncompressed: largest files, fastest to write.
+    None,
+    /// Run-length encoding in 64×64 tiles: fast, good on flat UI content.
+    #[default]
+    Rle,
+    /// Deflate over 16-line blocks: small files, slowest to write.
+    Zip,
+    /// Wavelet + Huffman in 256×256 tiles: best ratio on photographic / noisy content.
+    Piz,
+}
+
+impl ExrCompression {
+    /// Parse a compression name: `"none"`, `"rle"`, `"zip"` or `"piz"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "none" => Self::None,
+            "rle" => Self::Rle,
+            "zip" => Self::Zip,
+            "piz" => Self::Piz,
+            _ => return None,
+        })
+    }
+
+    fn encoding(self) -> Encoding {
+        match self {
+            Self::None => Encoding::UNCOMPRESSED,
+            Self::Rle => Encoding::FAST_LOSSLESS,
+            Self::Zip => Encoding::SMALL_LOSSLESS,
+            Self::Piz => Encoding::SMALL_FAST_LOSSLESS,
+        }
+    }
+}
+
+/// EXR encoder settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExrOptions {
+    pub compression: ExrCompression,
+    /// Treat BGRA8 values as sRGB-encoded and convert them to linear light
+    /// (default), as EXR readers (Nuke, Blender, Resolve) expect. When false,
+    /// the 8-bit values are only scaled to 0.0–1.0. No effect on RGBA16F,
+    /// which is already linear.
+    pub assume_srgb: bool,
+    /// Color encoding of RGBA16F data, recorded as the `chromaticities`
+    /// header attribute (default scRGB: BT.709 primaries). Saving a
+    /// `CapturedFrame` sets it from the frame. BGRA8 and RGB10A2 data is
+    /// always tagged BT.709.
+    pub color_space: OutputColorSpace,
+}
+
+impl Default for ExrOptions {
+    fn default() -> Self {
+        Self {
+            compression: ExrCompression::default(),
+            assume_srgb: true,
+            color_space: OutputColorSpace::default(),
+        }
+    }
+}
 
 /// Save pixel data as OpenEXR (.exr) file.
 ///
-/// - `Bgra8`: converted to `f32` RGBA channels (0.0–1.0).
+/// - `Bgra8`: converted to `f32` RGBA channels (0.0–1.0), linearized unless
+///   `options.assume_srgb` is false.
 /// - `Rgba16f`: written as `f16` RGBA channels (native half-float).
+/// - `Rgb10a2`: HDR10 decoded to scRGB, then written like `Rgba16f`.
+///
+/// The `chromaticities` attribute records the primaries: BT.709, or those of
+/// `options.color_space` for RGBA16F data. With `AlphaMode::Ignore` the alpha
+/// channel is written as 1.0. `metadata`
+/// goes into the header: `capDate` (UTC, `utcOffset` 0), `software` and a
+/// custom `captureSource` text attribute.
+#[allow(clippy::too_many_arguments)]
 pub fn save_exr(
     path: &Path,
     data: &[u8],
     width: u32,
     height: u32,
     format: ColorPixelFormat,
+    alpha: AlphaMode,
+    options: ExrOptions,
+    metadata: &SaveMetadata,
 ) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("failed to create '{}'", path.display()))?;
+    write(
+        BufWriter::new(file),
+        data,
+        (width, height),
+        format,
+        alpha,
+        options,
+        metadata,
+    )
+}
+
+/// Encode pixel data as OpenEXR into an in-memory buffer.
+///
+/// Same conversions, `options` and header metadata as [`save_exr`].
+pub fn encode_exr(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    alpha: AlphaMode,
+    options: ExrOptions,
+    metadata: &SaveMetadata,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    write(
+        Cursor::new(&mut buffer),
+        data,
+        (width, height),
+        format,
+        alpha,
+        options,
+        metadata,
+    )?;
+    Ok(buffer)
+}
+
+fn write(
+    writer: impl Write + Seek,
+    data: &[u8],
+    (width, height): (u32, u32),
+    format: ColorPixelFormat,
+    alpha: AlphaMode,
+    options: ExrOptions,
+    metadata: &SaveMetadata,
+) -> Result<()> {
+    format.check_len(data, width, height)?;
     let (w, h) = (width as usize, height as usize);
+    let opaque = alpha == AlphaMode::Ignore;
+    let encoding = options.compression.encoding();
+    let color_space = match format {
+        ColorPixelFormat::Rgba16f => options.color_space,
+        ColorPixelFormat::Bgra8 | ColorPixelFormat::Rgb10a2 => OutputColorSpace::ScrgbLinear,
+    };
+    let attributes = (layer_attributes(metadata), chromaticities(color_space));
 
     match format {
-        ColorPixelFormat::Bgra8 => save_bgra8(path, data, w, h),
-        ColorPixelFormat::Rgba16f => save_rgba16f(path, data, w, h),
+        ColorPixelFormat::Bgra8 => write_bgra8(
+            writer,
+            data,
+            w,
+            h,
+            opaque,
+            encoding,
+            options.assume_srgb,
+            attributes,
+        ),
+        ColorPixelFormat::Rgba16f => {
+            write_rgba16f(writer, data, w, h, opaque, encoding, attributes)
+        }
+        ColorPixelFormat::Rgb10a2 => write_rgba16f(
+            writer,
+            &hdr10_to_scrgb(data),
+            w,
+            h,
+            opaque,
+            encoding,
+            attributes,
+        ),
+    }
+}
+
+/// The `chromaticities` header attribute for `color_space`.
+fn chromaticities(color_space: OutputColorSpace) -> Chromaticities {
+    let [red, green, blue, white] = color_space.primaries().map(|[x, y]| Vec2(x, y));
+    Chromaticities {
+        red,
+        green,
+        blue,
+        white,
     }
 }
 
+/// Header attributes carrying `metadata`.
+fn layer_attributes(metadata: &SaveMetadata) -> LayerAttributes {
+    // EXR text is a byte string; UTF-8 keeps non-ASCII window titles intact.
+    let text = |s: &str| Text::from_slice_unchecked(s.as_bytes());
+    let mut attributes = LayerAttributes::default();
+    if let Some(date) = metadata.date_time() {
+        attributes.capture_date = Some(text(&date));
+        attributes.utc_offset = Some(0.0);
+    }
+    attributes.software_name = metadata.software.as_deref().map(text);
+    if let Some(source) = &metadata.source {
+        attributes.other.insert(
+            Text::from("captureSource"),
+            AttributeValue::Text(text(source)),
+        );
+    }
+    attributes
+}
+
+/// 8-bit color channel value → EXR float, optionally through the sRGB EOTF.
+fn channel_table(assume_srgb: bool) -> [f32; 256] {
+    std::array::from_fn(|v| {
+        let encoded = v as f32 / 255.0;
+        if assume_srgb {
+            srgb_to_linear(encoded)
+        } else {
+            encoded
+        }
+    })
+}
+
 /// Write BGRA8 data as f32 RGBA EXR.
-fn save_bgra8(path: &Path, data: &[u8], w: usize, h: usize) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+fn write_bgra8(
+    writer: impl Write + Seek,
+    data: &[u8],
+    w: usize,
+    h: usize,
+    opaque: bool,
+    encoding: Encoding,
+    assume_srgb: bool,
+    (attributes, chromaticities): (LayerAttributes, Chromaticities),
+) -> Result<()> {
+    let table = channel_table(assume_srgb);
     let channels = SpecificChannels::rgba(|Vec2(x, y)| {
         let offset = (y * w + x) * 4;
-        let b = data[offset] as f32 / 255.0;
-        let g = data[offset + 1] as f32 / 255.0;
-        let r = data[offset + 2] as f32 / 255.0;
-        let a = data[offset + 3] as f32 / 255.0;
+        let b = table[data[offset] as usize];
+        let g = table[data[offset + 1] as usize];
+        let r = table[data[offset + 2] as usize];
+        let a = if opaque {
+            1.0
+        } else {
+            data[offset + 3] as f32 / 255.0
+        };
         (r, g, b, a)
     });
 
-    let image = Image::from_channels((w, h), channels);
+    let mut image = Image::from_encoded_channels((w, h), encoding, channels);
+    image.layer_data.attributes = attributes;
+    image.attributes.chromaticities = Some(chromaticities);
     image
         .write()
-        .to_file(path)
+        .to_buffered(writer)
         .context("failed to write EXR (BGRA8)")?;
 
     Ok(())
 }
 
 /// Write RGBA16F data as f16 RGBA EXR.
-fn save_rgba16f(path: &Path, data: &[u8], w: usize, h: usize) -> Result<()> {
-    // Reinterpret byte slice as f16 (2 bytes each, 4 channels = 8 bytes per pixel)
-    let pixels: &[f16] = bytemuck_cast_f16(data);
+fn write_rgba16f(
+    writer: impl Write + Seek,
+    data: &[u8],
+    w: usize,
+    h: usize,
+    opaque: bool,
+    encoding: Encoding,
+    (attributes, chromaticities): (LayerAttributes, Chromaticities),
+) -> Result<()> {
+    // 4 f16 channels = 8 bytes per pixel
+    let pixels = f16_values(data)?;
 
     let channels = SpecificChannels::rgba(|Vec2(x, y)| {
         let offset = (y * w + x) * 4;
         let r = pixels[offset];
         let g = pixels[offset + 1];
         let b = pixels[offset + 2];
-        let a = pixels[offset + 3];
+        let a = if opaque { f16::ONE } else { pixels[offset + 3] };
         (r, g, b, a)
     });
 
-    let image = Image::from_channels((w, h), channels);
+    let mut image = Image::from_encoded_channels((w, h), encoding, channels);
+    image.layer_data.attributes = attributes;
+    image.attributes.chromaticities = Some(chromaticities);
     image
         .write()
-        .to_file(path)
+        .to_buffered(writer)
         .context("failed to write EXR (RGBA16F)")?;
 
     Ok(())
 }
 
-/// Reinterpret a `&[u8]` as `&[f16]`.
-///
-/// # Panics
-/// Panics if the byte slice length is not a multiple of 2 or is misaligned.
-fn bytemuck_cast_f16(data: &[u8]) -> &[f16] {
-    assert!(
-        data.len().is_multiple_of(2),
-        "byte slice length must be even for f16 cast"
-    );
-    // SAFETY: f16 is 2 bytes, repr(transparent) over u16. The exr crate re-exports `half::f16`.
-    // GPU-allocated buffers from D3D11 are at least 16-byte aligned.
-    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const f16, data.len() / 2) }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_from_name() {
+        assert_eq!(ExrCompression::from_name("ZIP"), Some(ExrCompression::Zip));
+        assert_eq!(
+            ExrCompression::from_name("none"),
+            Some(ExrCompression::None)
+        );
+        assert_eq!(ExrCompression::from_name("dwaa"), None);
+    }
+
+    #[test]
+    fn test_bgra8_channels_linearized_by_default() {
+        assert!(ExrOptions::default().assume_srgb);
+
+        let linear = channel_table(true);
+        assert_eq!((linear[0], linear[255]), (0.0, 1.0));
+        // sRGB mid-gray 128 is ~21.6% linear light.
+        assert!((linear[128] - 0.2158).abs() < 1e-3);
+
+        let encoded = channel_table(false);
+        assert_eq!(encoded[128], 128.0 / 255.0);
+    }
+
+    #[test]
+    fn test_uncompressed_is_larger_than_zip() {
+        // Flat gray 64×64 BGRA8 frame compresses well under ZIP.
+        let data = vec![128u8; 64 * 64 * 4];
+        let encode = |compression| {
+            let options = ExrOptions {
+                compression,
+                ..Default::default()
+            };
+            encode_exr(
+                &data,
+                64,
+                64,
+                ColorPixelFormat::Bgra8,
+                AlphaMode::Ignore,
+                options,
+                &SaveMetadata::default(),
+            )
+            .unwrap()
+            .len()
+        };
+        assert!(encode(ExrCompression::Zip) < encode(ExrCompression::None));
+    }
+
+    #[test]
+    fn test_metadata_written_to_header() {
+        let metadata = SaveMetadata {
+            captured_at: Some(std::time::UNIX_EPOCH),
+            source: Some("Notepad — 無題".to_string()),
+            ..SaveMetadata::new()
+        };
+        let mut buffer = Vec::new();
+        write(
+            Cursor::new(&mut buffer),
+            &[0u8; 4 * 4 * 8],
+            (4, 4),
+            ColorPixelFormat::Rgba16f,
+            AlphaMode::Ignore,
+            ExrOptions::default(),
+            &metadata,
+        )
+        .unwrap();
+
+        let meta = MetaData::read_from_buffered(Cursor::new(&buffer), false).unwrap();
+        let attributes = &meta.headers[0].own_attributes;
+        let bytes = |text: &Text| String::from_utf8(text.as_slice().to_vec()).unwrap();
+        assert_eq!(
+            bytes(attributes.capture_date.as_ref().unwrap()),
+            "1970:01:01 00:00:00"
+        );
+        assert_eq!(
+            bytes(attributes.software_name.as_ref().unwrap()),
+            metadata.software.unwrap()
+        );
+        match attributes.other.get(&Text::from("captureSource")) {
+            Some(AttributeValue::Text(source)) => assert_eq!(bytes(source), "Notepad — 無題"),
+            other => panic!("captureSource missing: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_chromaticities_follow_color_space() {
+        let encode = |format: ColorPixelFormat, color_space| {
+            let options = ExrOptions {
+                color_space,
+                ..Default::default()
+            };
+            let data = vec![0u8; 2 * 2 * format.bytes_per_pixel()];
+            let bytes = encode_exr(
+                &data,
+                2,
+                2,
+                format,
+                AlphaMode::Ignore,
+                options,
+                &SaveMetadata::default(),
+            )
+            .unwrap();
+            let meta = MetaData::read_from_buffered(Cursor::new(&bytes), false).unwrap();
+            let red = meta.headers[0]
+                .shared_attributes
+                .chromaticities
+                .unwrap()
+                .red;
+            (red.0, red.1)
+        };
+        assert_eq!(
+            encode(ColorPixelFormat::Rgba16f, OutputColorSpace::ScrgbLinear),
+            (0.64, 0.33)
+        );
+        assert_eq!(
+            encode(ColorPixelFormat::Rgba16f, OutputColorSpace::Rec2020Linear),
+            (0.708, 0.292)
+        );
+        // Only RGBA16F data carries the configured color space.
+        assert_eq!(
+            encode(ColorPixelFormat::Bgra8, OutputColorSpace::Rec2020Linear),
+            (0.64, 0.33)
+        );
+    }
 }
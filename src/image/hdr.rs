@@ -0,0 +1,240 @@
+// Radiance RGBE (.hdr) encoding.
+//
+// Each pixel is stored as three 8-bit mantissas sharing one 8-bit exponent,
+// so a 4-byte pixel covers a huge dynamic range at ~1% precision. Scanlines
+// use the "new" run-length encoding understood by every reader (Photoshop,
+// GIMP, Blender, the `image` crate). RGBE has no alpha; it is dropped.
+
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use half::f16;
+
+use crate::color::{hdr10, ColorPixelFormat};
+
+/// Runs shorter than this are cheaper to store as literal bytes.
+const MIN_RUN: usize = 4;
+
+/// Scanline widths the RLE scheme can encode; others are written flat.
+const RLE_WIDTHS: std::ops::RangeInclusive<usize> = 8..=0x7fff;
+
+/// Save pixel data as a Radiance RGBE (.hdr) file.
+///
+/// - `Rgba16f`: linear scRGB values; negative components (out-of-gamut
+///   colors) are clamped to 0.
+/// - `Bgra8`: the 8-bit values scaled to 0.0–1.0 as-is, without
+///   linearization — still a valid (low dynamic range) RGBE image.
+/// - `Rgb10a2`: HDR10 decoded to linear scRGB, then as `Rgba16f`.
+pub fn save_hdr(
+    path: &Path,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+) -> Result<()> {
+    check_image(data, width, height, format)?;
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("failed to create '{}'", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    write(&mut writer, data, width, height, format)?;
+    writer.flush().context("failed to write HDR")?;
+    Ok(())
+}
+
+/// Encode pixel data as Radiance RGBE into an in-memory buffer.
+pub fn encode_hdr(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+) -> Result<Vec<u8>> {
+    check_image(data, width, height, format)?;
+    let mut buffer = Vec::new();
+    write(&mut buffer, data, width, height, format)?;
+    Ok(buffer)
+}
+
+/// Reject empty images and buffers that don't match the dimensions, which
+/// the scanline loop would otherwise panic on or truncate.
+fn check_image(data: &[u8], width: u32, height: u32, format: ColorPixelFormat) -> Result<()> {
+    if width == 0 || height == 0 {
+        bail!("HDR image must be at least 1x1, got {}x{}", width, height);
+    }
+    format.check_len(data, width, height)
+}
+
+fn write(
+    mut writer: impl Write,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+) -> Result<()> {
+    let w = width as usize;
+    write!(
+        writer,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        height, width
+    )
+    .context("failed to write HDR header")?;
+
+    let bpp = match format {
+        ColorPixelFormat::Bgra8 | ColorPixelFormat::Rgb10a2 => 4,
+        ColorPixelFormat::Rgba16f => 8,
+    };
+    let mut scanline = Vec::with_capacity(w * 4);
+    let mut encoded = Vec::with_capacity(w * 4 + 4);
+    for row in data.chunks_exact(w * bpp) {
+        scanline.clear();
+        for pixel in row.chunks_exact(bpp) {
+            let [r, g, b] = match format {
+                ColorPixelFormat::Bgra8 => [pixel[2], pixel[1], pixel[0]].map(|v| v as f32 / 255.0),
+                ColorPixelFormat::Rgba16f => {
+                    [0, 2, 4].map(|i| f16::from_le_bytes([pixel[i], pixel[i + 1]]).to_f32())
+                }
+                ColorPixelFormat::Rgb10a2 => {
+                    let [r, g, b, _] =
+                        hdr10::pixel_to_scrgb([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                    [r, g, b]
+                }
+            };
+            scanline.extend_from_slice(&rgbe(r, g, b));
+        }
+
+        encoded.clear();
+        if RLE_WIDTHS.contains(&w) {
+            encode_scanline_rle(&scanline, w, &mut encoded);
+        } else {
+            encoded.extend_from_slice(&scanline);
+        }
+        writer
+            .write_all(&encoded)
+            .context("failed to write HDR scanline")?;
+    }
+    Ok(())
+}
+
+/// Shared-exponent encoding of one linear RGB pixel.
+fn rgbe(r: f32, g: f32, b: f32) -> [u8; 4] {
+    // NaN fails every comparison and ends up as 0 like negative values.
+    let [r, g, b] = [r, g, b].map(|c| if c > 0.0 { c } else { 0.0 });
+    let max = r.max(g).max(b);
+    if max < 1e-32 {
+        return [0; 4];
+    }
+    // max = mantissa * 2^exponent with mantissa in [0.5, 1).
+    let mut exponent = max.log2().floor() as i32 + 1;
+    if max / 2f32.powi(exponent) >= 1.0 {
+        exponent += 1;
+    }
+    let exponent = exponent.clamp(-128, 127);
+    let scale = 256.0 / 2f32.powi(exponent);
+    let quantize = |c: f32| (c * scale).min(255.0) as u8;
+    [
+        quantize(r),
+        quantize(g),
+        quantize(b),
+        (exponent + 128) as u8,
+    ]
+}
+
+/// Append one scanline of RGBE pixels in the new run-length encoding:
+/// a 4-byte marker, then each of the four components run-length coded.
+fn encode_scanline_rle(scanline: &[u8], width: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8]);
+    let mut component = Vec::with_capacity(width);
+    for c in 0..4 {
+        component.clear();
+        component.extend(scanline.iter().skip(c).step_by(4));
+        encode_component_rle(&component, out);
+    }
+}
+
+/// Run-length code one component: `128 + n, value` repeats `value` n times
+/// (n ≤ 127); `n, bytes…` copies n literal bytes (n ≤ 128).
+fn encode_component_rle(data: &[u8], out: &mut Vec<u8>) {
+    let run_at = |start: usize| {
+        data[start..]
+            .iter()
+            .take(127)
+            .take_while(|&&v| v == data[start])
+            .count()
+    };
+
+    let mut literal_start = 0;
+    let mut pos = 0;
+    while pos < data.len() {
+        let run = run_at(pos);
+        if run < MIN_RUN {
+            pos += run;
+            continue;
+        }
+        write_literals(&data[literal_start..pos], out);
+        out.extend_from_slice(&[128 + run as u8, data[pos]]);
+        pos += run;
+        literal_start = pos;
+    }
+    write_literals(&data[literal_start..], out);
+}
+
+fn write_literals(mut data: &[u8], out: &mut Vec<u8>) {
+    while !data.is_empty() {
+        let n = data.len().min(128);
+        out.push(n as u8);
+        out.extend_from_slice(&data[..n]);
+        data = &data[n..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgbe_shared_exponent() {
+        assert_eq!(rgbe(1.0, 0.5, 0.25), [128, 64, 32, 129]);
+        assert_eq!(rgbe(0.0, 0.0, 0.0), [0; 4]);
+        assert_eq!(rgbe(-1.0, f32::NAN, 0.0), [0; 4]);
+        // 12.5 scRGB (1000 nits) = 0.78125 * 2^4.
+        assert_eq!(rgbe(12.5, 0.0, 0.0), [200, 0, 0, 132]);
+    }
+
+    #[test]
+    fn test_component_rle() {
+        let mut out = Vec::new();
+        encode_component_rle(&[7, 7, 7, 7, 7, 1, 2, 3, 3], &mut out);
+        assert_eq!(out, [133, 7, 4, 1, 2, 3, 3]);
+    }
+
+    #[test]
+    fn test_hdr_rejects_bad_dimensions() {
+        assert!(encode_hdr(&[], 0, 1, ColorPixelFormat::Bgra8).is_err());
+        assert!(encode_hdr(&[], 1, 0, ColorPixelFormat::Bgra8).is_err());
+        assert!(encode_hdr(&[0; 12], 2, 2, ColorPixelFormat::Bgra8).is_err());
+    }
+
+    #[test]
+    fn test_hdr_decodes_with_image_crate() {
+        // 16×2 RGBA16F: a bright gradient row, then a flat row (RLE runs).
+        let mut data = Vec::new();
+        for y in 0..2 {
+            for x in 0..16 {
+                let v = if y == 0 { x as f32 * 0.75 } else { 0.5 };
+                for c in [v, v * 0.5, 0.25, 1.0] {
+                    data.extend_from_slice(&f16::from_f32(c).to_le_bytes());
+                }
+            }
+        }
+        let bytes = encode_hdr(&data, 16, 2, ColorPixelFormat::Rgba16f).unwrap();
+        assert!(bytes.starts_with(b"#?RADIANCE\n"));
+
+        let decoded = image::load_from_memory_with_format(&bytes, image::ImageFormat::Hdr).unwrap();
+        let decoded = decoded.to_rgb32f();
+        assert_eq!(decoded.dimensions(), (16, 2));
+        let px = decoded.get_pixel(10, 0);
+        assert!((px[0] - 7.5).abs() / 7.5 < 0.01, "{px:?}");
+        assert!((px[1] - 3.75).abs() / 3.75 < 0.01, "{px:?}");
+        assert_eq!(decoded.get_pixel(3, 1)[0], 0.5);
+    }
+}
@@ -0,0 +1,121 @@
+// HEIF (.heic / .heif) encoding via Windows Imaging Component (WIC).
+//
+// - BGRA8: 8-bit HEVC, passed through as 32bppBGRA.
+// - RGBA16F: converted on the CPU from scRGB to 10-bit BT.2020 PQ (HDR10)
+//   and written as 32bppR10G10B10A2HDR10.
+// - RGB10A2: already HDR10, written as 32bppR10G10B10A2HDR10 unchanged.
+//
+// HDR output gets an `nclx` colour box (BT.2020 / PQ) added after encoding;
+// WIC only signals HDR10 inside the HEVC stream, which most readers ignore.
+//
+// WIC only ships the HEIF container; encoding needs the "HEVC Video
+// Extensions" package from the Microsoft Store.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use windows::core::HRESULT;
+use windows::Win32::Graphics::Imaging::{
+    GUID_ContainerFormatHeif, GUID_WICPixelFormat32bppBGRA,
+    GUID_WICPixelFormat32bppR10G10B10A2HDR10,
+};
+
+use self::nclx::Nclx;
+use super::wic::{self, WicFrame};
+use crate::color::hdr10::scrgb_to_hdr10;
+use crate::color::ColorPixelFormat;
+
+mod nclx;
+
+/// WIC component (codec) not installed.
+const WINCODEC_ERR_COMPONENTNOTFOUND: HRESULT = HRESULT(0x88982F50_u32 as i32);
+
+/// Save pixel data as HEIF.
+///
+/// Returns an actionable error when the HEVC codec is not installed.
+pub fn save_heif(
+    path: &Path,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+) -> Result<()> {
+    // Encoded in memory so the `nclx` box can be added before anything is
+    // written; this also leaves no empty file behind when the codec is missing.
+    let bytes = encode_heif(data, width, height, format)?;
+    std::fs::write(path, bytes).with_context(|| format!("failed to write '{}'", path.display()))
+}
+
+/// Encode pixel data as HEIF into an in-memory buffer.
+pub fn encode_heif(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+) -> Result<Vec<u8>> {
+    let bytes = with_heif_frame(data, width, height, format, |frame| {
+        wic::encode_to_vec(frame, "HEIF")
+    })?;
+    if format == ColorPixelFormat::Bgra8 {
+        return Ok(bytes);
+    }
+    Ok(match nclx::add_nclx(&bytes, Nclx::HDR10) {
+        Ok(tagged) => tagged,
+        Err(e) => {
+            log::warn!("HEIF written without nclx colour box: {e:#}");
+            bytes
+        }
+    })
+}
+
+/// Build the WIC frame for `format` (packing HDR10 if needed), run `encode`
+/// on it, and attach codec guidance to errors.
+fn with_heif_frame<R>(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    encode: impl FnOnce(WicFrame<'_>) -> Result<R>,
+) -> Result<R> {
+    let (pixel_format, data) = match format {
+        ColorPixelFormat::Bgra8 => (GUID_WICPixelFormat32bppBGRA, Cow::Borrowed(data)),
+        ColorPixelFormat::Rgba16f => (
+            GUID_WICPixelFormat32bppR10G10B10A2HDR10,
+            Cow::Owned(scrgb_to_hdr10(data)),
+        ),
+        ColorPixelFormat::Rgb10a2 => (
+            GUID_WICPixelFormat32bppR10G10B10A2HDR10,
+            Cow::Borrowed(data),
+        ),
+    };
+    let mut result = encode(WicFrame {
+        container: GUID_ContainerFormatHeif,
+        pixel_format,
+        width,
+        height,
+        stride: width * 4,
+        data: &data,
+        metadata: None,
+    });
+    if format != ColorPixelFormat::Bgra8 {
+        result = result
+            .context("10-bit HDR HEIF encoding requires Windows 11 with HEVC Video Extensions");
+    }
+
+    result.map_err(|e| {
+        if is_missing_codec(&e) {
+            e.context(
+                "HEIF encoder not available: install \"HEVC Video Extensions\" \
+                 from the Microsoft Store, or save as .jxr / .exr instead",
+            )
+        } else {
+            e
+        }
+    })
+}
+
+fn is_missing_codec(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<windows::core::Error>()
+        .is_some_and(|we| we.code() == WINCODEC_ERR_COMPONENTNOTFOUND)
+}
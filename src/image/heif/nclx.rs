@@ -0,0 +1,373 @@
+// `nclx` colour box for HEIF files written by WIC.
+//
+// WIC's HEIF encoder signals HDR10 only in the HEVC bitstream (VUI). Readers
+// such as libheif, browsers and macOS take colour from the `colr` item
+// property instead and treat an untagged image as sRGB, so PQ / BT.2020
+// content shows washed out. `add_nclx` appends a `colr` box of type `nclx`
+// to `ipco`, associates it with the primary item in `ipma`, and fixes up the
+// enclosing box sizes and the absolute `iloc` offsets that point behind it.
+
+use anyhow::{bail, ensure, Context, Result};
+
+/// Colour signalling of an `nclx` box (ITU-T H.273 code points).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Nclx {
+    pub primaries: u16,
+    pub transfer: u16,
+    pub matrix: u16,
+    pub full_range: bool,
+}
+
+impl Nclx {
+    /// HDR10: BT.2020 primaries, PQ (SMPTE ST 2084), BT.2020 non-constant
+    /// luminance matrix, limited range — what WIC's HDR10 encode produces.
+    pub const HDR10: Self = Self {
+        primaries: 9,
+        transfer: 16,
+        matrix: 9,
+        full_range: false,
+    };
+
+    fn to_box(self) -> Vec<u8> {
+        let mut b = Vec::with_capacity(19);
+        b.extend_from_slice(&19u32.to_be_bytes());
+        b.extend_from_slice(b"colr");
+        b.extend_from_slice(b"nclx");
+        b.extend_from_slice(&self.primaries.to_be_bytes());
+        b.extend_from_slice(&self.transfer.to_be_bytes());
+        b.extend_from_slice(&self.matrix.to_be_bytes());
+        b.push(if self.full_range { 0x80 } else { 0 });
+        b
+    }
+}
+
+/// Return a copy of the HEIF `file` whose primary item carries `nclx`.
+///
+/// Files that already have an `nclx` property are returned unchanged.
+pub(super) fn add_nclx(file: &[u8], nclx: Nclx) -> Result<Vec<u8>> {
+    let top = boxes(file, 0, file.len())?;
+    let meta = find(&top, b"meta")?;
+    // `meta` is a full box: version + flags precede its children.
+    ensure!(meta.body + 4 <= meta.end, "truncated 'meta' box");
+    let meta_children = boxes(file, meta.body + 4, meta.end)?;
+    let primary = primary_item(file, find(&meta_children, b"pitm")?)?;
+    let iprp = find(&meta_children, b"iprp")?;
+    let iprp_children = boxes(file, iprp.body, iprp.end)?;
+    let ipco = find(&iprp_children, b"ipco")?;
+    let ipma = find(&iprp_children, b"ipma")?;
+
+    let properties = boxes(file, ipco.body, ipco.end)?;
+    if properties
+        .iter()
+        .any(|p| &p.kind == b"colr" && file.get(p.body..p.body + 4) == Some(b"nclx"))
+    {
+        return Ok(file.to_vec());
+    }
+
+    let colr = nclx.to_box();
+    // Property indices in `ipma` are 1-based.
+    let entry = ipma_entry(file, ipma, primary, properties.len() + 1)?;
+
+    // Both insertions are expressed in original-file offsets.
+    let mut inserts = [(ipco.end, colr), (entry.insert_at, entry.association)];
+    let shift = |offset: u64| {
+        offset
+            + inserts
+                .iter()
+                .filter(|(at, _)| offset >= *at as u64)
+                .map(|(_, bytes)| bytes.len() as u64)
+                .sum::<u64>()
+    };
+
+    // Patch values in place first (positions are still the original ones),
+    // then splice the new bytes in from the back.
+    let mut out = file.to_vec();
+    out[entry.count_at] += 1;
+    let grow_ipco = inserts[0].1.len();
+    let grow_ipma = inserts[1].1.len();
+    grow(&mut out, ipco, grow_ipco)?;
+    grow(&mut out, ipma, grow_ipma)?;
+    grow(&mut out, iprp, grow_ipco + grow_ipma)?;
+    grow(&mut out, meta, grow_ipco + grow_ipma)?;
+    if let Ok(iloc) = find(&meta_children, b"iloc") {
+        shift_iloc(file, &mut out, iloc, shift)?;
+    }
+
+    inserts.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    for (at, bytes) in inserts {
+        out.splice(at..at, bytes);
+    }
+    Ok(out)
+}
+
+/// One ISOBMFF box within the file.
+#[derive(Debug, Clone, Copy)]
+struct BoxRange {
+    start: usize,
+    body: usize,
+    end: usize,
+    kind: [u8; 4],
+}
+
+/// Boxes laid out back to back in `data[start..end]`.
+fn boxes(data: &[u8], start: usize, end: usize) -> Result<Vec<BoxRange>> {
+    let mut out = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        let header = data.get(pos..pos + 8).context("truncated box header")?;
+        let size = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+        let kind: [u8; 4] = header[4..].try_into().unwrap();
+        let box_end = match size {
+            0 => end,
+            1 => bail!("64-bit box sizes are not supported"),
+            n => pos + n,
+        };
+        ensure!(
+            box_end >= pos + 8 && box_end <= end,
+            "box '{}' overruns its parent",
+            String::from_utf8_lossy(&kind)
+        );
+        out.push(BoxRange {
+            start: pos,
+            body: pos + 8,
+            end: box_end,
+            kind,
+        });
+        pos = box_end;
+    }
+    Ok(out)
+}
+
+fn find(boxes: &[BoxRange], kind: &[u8; 4]) -> Result<BoxRange> {
+    boxes
+        .iter()
+        .find(|b| &b.kind == kind)
+        .copied()
+        .with_context(|| format!("no '{}' box", String::from_utf8_lossy(kind)))
+}
+
+/// Add `by` bytes to the size field of `b` (a size of 0 runs to the end of
+/// its parent and needs no update).
+fn grow(out: &mut [u8], b: BoxRange, by: usize) -> Result<()> {
+    let size = u32::from_be_bytes(out[b.start..b.start + 4].try_into().unwrap());
+    if size != 0 {
+        let size = u32::try_from(size as usize + by).context("box too large")?;
+        out[b.start..b.start + 4].copy_from_slice(&size.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Primary item ID from `pitm`.
+fn primary_item(file: &[u8], pitm: BoxRange) -> Result<u64> {
+    let mut r = Reader::new(file, pitm);
+    let version = r.uint(1)?;
+    r.skip(3)?;
+    r.uint(if version == 0 { 2 } else { 4 })
+}
+
+/// Location of the primary item's entry in `ipma`.
+struct IpmaEntry {
+    /// Offset of the entry's association count byte.
+    count_at: usize,
+    /// Offset just past the entry's last association.
+    insert_at: usize,
+    /// Encoded association for the new property.
+    association: Vec<u8>,
+}
+
+fn ipma_entry(file: &[u8], ipma: BoxRange, item: u64, index: usize) -> Result<IpmaEntry> {
+    let mut r = Reader::new(file, ipma);
+    let version = r.uint(1)?;
+    let flags = r.uint(3)?;
+    let id_bytes = if version < 1 { 2 } else { 4 };
+    let wide = flags & 1 != 0;
+    for _ in 0..r.uint(4)? {
+        let id = r.uint(id_bytes)?;
+        let count_at = r.pos;
+        let count = r.uint(1)? as usize;
+        r.skip(count * if wide { 2 } else { 1 })?;
+        if id != item {
+            continue;
+        }
+        ensure!(count < 255, "item {item} has too many properties");
+        // Top bit is the `essential` flag; colour info is not essential.
+        let association = if wide {
+            ensure!(index < 0x8000, "too many item properties");
+            (index as u16).to_be_bytes().to_vec()
+        } else {
+            ensure!(index < 0x80, "too many item properties");
+            vec![index as u8]
+        };
+        return Ok(IpmaEntry {
+            count_at,
+            insert_at: r.pos,
+            association,
+        });
+    }
+    bail!("primary item {item} has no property associations")
+}
+
+/// Move the absolute file offsets in `iloc` (construction method 0) that
+/// point behind the inserted bytes.
+fn shift_iloc(
+    file: &[u8],
+    out: &mut [u8],
+    iloc: BoxRange,
+    shift: impl Fn(u64) -> u64,
+) -> Result<()> {
+    let mut r = Reader::new(file, iloc);
+    let version = r.uint(1)?;
+    r.skip(3)?;
+    let sizes = r.uint(1)? as usize;
+    let (offset_size, length_size) = (sizes >> 4, sizes & 15);
+    let sizes = r.uint(1)? as usize;
+    let base_offset_size = sizes >> 4;
+    let index_size = if version == 1 || version == 2 {
+        sizes & 15
+    } else {
+        0
+    };
+    let id_bytes = if version < 2 { 2 } else { 4 };
+
+    for _ in 0..r.uint(id_bytes)? {
+        r.skip(id_bytes)?;
+        let method = if version == 1 || version == 2 {
+            r.uint(2)? & 15
+        } else {
+            0
+        };
+        r.skip(2)?; // data_reference_index
+        let base_at = r.pos;
+        let base = r.uint(base_offset_size)?;
+        let mut extents = Vec::new();
+        for _ in 0..r.uint(2)? {
+            r.skip(index_size)?;
+            extents.push((r.pos, r.uint(offset_size)?));
+            r.skip(length_size)?;
+        }
+        // Methods 1 and 2 address `idat` / other items, not the file.
+        if method != 0 {
+            continue;
+        }
+        if base_offset_size > 0 {
+            // Extents are relative to the base: moving it moves the item.
+            write_uint(out, base_at, base_offset_size, shift(base))?;
+        } else {
+            for (at, offset) in extents {
+                write_uint(out, at, offset_size, shift(offset))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_uint(out: &mut [u8], at: usize, bytes: usize, value: u64) -> Result<()> {
+    if bytes == 0 {
+        return Ok(());
+    }
+    ensure!(
+        bytes == 8 || value >> (8 * bytes) == 0,
+        "iloc offset no longer fits in {bytes} bytes"
+    );
+    out[at..at + bytes].copy_from_slice(&value.to_be_bytes()[8 - bytes..]);
+    Ok(())
+}
+
+/// Big-endian reader over one box's payload.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], b: BoxRange) -> Self {
+        Self {
+            data,
+            pos: b.body,
+            end: b.end,
+        }
+    }
+
+    fn uint(&mut self, bytes: usize) -> Result<u64> {
+        ensure!(self.pos + bytes <= self.end, "truncated box payload");
+        let value = self.data[self.pos..self.pos + bytes]
+            .iter()
+            .fold(0u64, |v, &b| (v << 8) | b as u64);
+        self.pos += bytes;
+        Ok(value)
+    }
+
+    fn skip(&mut self, bytes: usize) -> Result<()> {
+        ensure!(self.pos + bytes <= self.end, "truncated box payload");
+        self.pos += bytes;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bx(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut b = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        b.extend_from_slice(kind);
+        b.extend_from_slice(body);
+        b
+    }
+
+    /// Minimal single-item HEIF: `ftyp`, `meta` (`pitm`, `iloc`, `iprp`)
+    /// and an `mdat` holding `payload`, addressed by an absolute offset.
+    fn heif(payload: &[u8], mdat_offset: u32) -> Vec<u8> {
+        let pitm = bx(b"pitm", &[0, 0, 0, 0, 0, 1]);
+        let mut iloc = vec![0, 0, 0, 0, 0x44, 0x00, 0, 1, 0, 1, 0, 0, 0, 1];
+        iloc.extend_from_slice(&mdat_offset.to_be_bytes());
+        iloc.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        let iloc = bx(b"iloc", &iloc);
+        let ipco = bx(b"ipco", &bx(b"ispe", &[0; 12]));
+        let ipma = bx(b"ipma", &[0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 1, 0x81]);
+        let iprp = bx(b"iprp", &[ipco, ipma].concat());
+        let meta = bx(b"meta", &[vec![0; 4], pitm, iloc, iprp].concat());
+        [bx(b"ftyp", b"heic\0\0\0\0"), meta, bx(b"mdat", payload)].concat()
+    }
+
+    #[test]
+    fn test_add_nclx_tags_primary_item() {
+        let payload = b"hevc-bitstream";
+        let len = heif(payload, 0).len() - payload.len();
+        let file = heif(payload, len as u32);
+        let out = add_nclx(&file, Nclx::HDR10).unwrap();
+        assert_eq!(out.len(), file.len() + 19 + 1);
+
+        let top = boxes(&out, 0, out.len()).unwrap();
+        let meta = find(&top, b"meta").unwrap();
+        let meta_children = boxes(&out, meta.body + 4, meta.end).unwrap();
+        let iprp = find(&meta_children, b"iprp").unwrap();
+        let iprp_children = boxes(&out, iprp.body, iprp.end).unwrap();
+        let ipco = find(&iprp_children, b"ipco").unwrap();
+        let properties = boxes(&out, ipco.body, ipco.end).unwrap();
+        let colr = find(&properties, b"colr").unwrap();
+        assert_eq!(
+            &out[colr.body..colr.end],
+            &[b'n', b'c', b'l', b'x', 0, 9, 0, 16, 0, 9, 0]
+        );
+
+        // Association count went 1 → 2, the new entry points at property 2.
+        let ipma = find(&iprp_children, b"ipma").unwrap();
+        assert_eq!(&out[ipma.body + 10..ipma.end], &[2, 0x81, 2]);
+
+        // The iloc offset still lands on the payload.
+        let iloc = find(&meta_children, b"iloc").unwrap();
+        let offset =
+            u32::from_be_bytes(out[iloc.body + 14..iloc.body + 18].try_into().unwrap()) as usize;
+        assert_eq!(&out[offset..offset + payload.len()], payload);
+
+        assert_eq!(add_nclx(&out, Nclx::HDR10).unwrap(), out);
+    }
+
+    #[test]
+    fn test_add_nclx_rejects_non_heif() {
+        assert!(add_nclx(b"not an iso bmff file", Nclx::HDR10).is_err());
+        assert!(add_nclx(&bx(b"ftyp", b"heic"), Nclx::HDR10).is_err());
+    }
+}
@@ -1,32 +1,28 @@
 // JPEG XR (.jxr) encoding via Windows Imaging Component (WIC).
 //
-// Supports both BGRA8 and RGBA16F pixel data.
+// Supports both BGRA8 and RGBA16F pixel data; RGB10A2 (HDR10) frames are
+// decoded to scRGB RGBA16F first, since JPEG XR has no PQ pixel format.
 // JPEG XR (HD Photo) is the only widely-supported HDR image format on Windows,
 // natively viewable in Photos app and supported by all WIC-based tools.
 
+use std::borrow::Cow;
 use std::path::Path;
 
-use anyhow::{bail, Context, Result};
-use windows::core::{GUID, PCWSTR};
+use anyhow::Result;
 use windows::Win32::Graphics::Imaging::{
-    CLSID_WICImagingFactory, GUID_ContainerFormatWmp, GUID_WICPixelFormat32bppBGRA,
-    GUID_WICPixelFormat64bppRGBAHalf, IWICBitmapFrameEncode, IWICImagingFactory,
-    WICBitmapEncoderNoCache,
-};
-use windows::Win32::System::Com::StructuredStorage::IPropertyBag2;
-use windows::Win32::System::Com::{
-    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    GUID_ContainerFormatWmp, GUID_WICPixelFormat32bppBGRA, GUID_WICPixelFormat64bppRGBAHalf,
 };
 
+use super::wic::{self, WicFrame};
+use crate::color::hdr10::hdr10_to_scrgb;
 use crate::color::ColorPixelFormat;
-
-/// GENERIC_WRITE access flag (0x40000000).
-/// Defined here to avoid pulling in Win32_Storage_FileSystem feature.
-const GENERIC_WRITE: u32 = 0x40000000;
+use crate::image::SaveMetadata;
 
 /// Save pixel data as JPEG XR (.jxr) file.
 ///
-/// Supports both `Bgra8` (32bpp) and `Rgba16f` (64bpp half-float) formats.
+/// Supports both `Bgra8` (32bpp) and `Rgba16f` (64bpp half-float) formats;
+/// `Rgb10a2` is stored as half-float scRGB. Non-empty `metadata` is written
+/// as DateTime, Software and ImageDescription (source) tags.
 /// Uses WIC COM API; COM is initialized per-call (safe if already initialized).
 pub fn save_jxr(
     path: &Path,
@@ -34,87 +30,49 @@ pub fn save_jxr(
     width: u32,
     height: u32,
     format: ColorPixelFormat,
+    metadata: &SaveMetadata,
 ) -> Result<()> {
-    let (pixel_format, stride) = match format {
-        ColorPixelFormat::Bgra8 => (GUID_WICPixelFormat32bppBGRA, width * 4),
-        ColorPixelFormat::Rgba16f => (GUID_WICPixelFormat64bppRGBAHalf, width * 8),
+    let (data, format) = jxr_input(data, format);
+    let frame = WicFrame {
+        metadata: Some(metadata),
+        ..jxr_frame(&data, width, height, format)
     };
+    wic::encode(path, frame, "JXR")
+}
 
-    let expected_len = stride as usize * height as usize;
-    if data.len() < expected_len {
-        bail!(
-            "pixel data too short: expected {} bytes ({}x{}x{}bpp), got {}",
-            expected_len,
-            width,
-            height,
-            stride / width,
-            data.len()
-        );
-    }
-
-    // SAFETY: All WIC calls operate on COM objects created in this scope.
-    // CoInitializeEx returns Ok for S_OK and S_FALSE (already initialized).
-    // Err means incompatible apartment model (e.g. RPC_E_CHANGED_MODE).
-    unsafe {
-        CoInitializeEx(None, COINIT_MULTITHREADED)
-            .ok()
-            .context("COM initialization failed (apartment model conflict?)")?;
-
-        let factory: IWICImagingFactory =
-            CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)
-                .context("Failed to create WIC imaging factory")?;
-
-        // Create output stream
-        let stream = factory.CreateStream()?;
-        let wide_path = to_wide(path);
-        stream.InitializeFromFilename(PCWSTR(wide_path.as_ptr()), GENERIC_WRITE)?;
-
-        // Create JPEG XR encoder
-        let encoder = factory.CreateEncoder(&GUID_ContainerFormatWmp, std::ptr::null())?;
-        encoder.Initialize(&stream, WICBitmapEncoderNoCache)?;
-
-        // Create frame
-        let mut frame: Option<IWICBitmapFrameEncode> = None;
-        let mut props: Option<IPropertyBag2> = None;
-        encoder.CreateNewFrame(&mut frame, &mut props)?;
-        let frame = frame.context("WIC CreateNewFrame returned null")?;
-
-        // Initialize frame with default properties
-        if let Some(ref props) = props {
-            frame.Initialize(props)?;
-        }
-
-        // Set frame dimensions and pixel format
-        frame.SetSize(width, height)?;
-        let mut fmt: GUID = pixel_format;
-        frame.SetPixelFormat(&mut fmt)?;
-
-        // Verify WIC accepted our format (it may silently convert)
-        if fmt != pixel_format {
-            bail!(
-                "WIC rejected pixel format for JXR encoding; \
-                 requested {:?}, got {:?}",
-                pixel_format,
-                fmt
-            );
-        }
-
-        // Write pixel data
-        frame.WritePixels(height, stride, data)?;
+/// Encode pixel data as JPEG XR into an in-memory buffer.
+pub fn encode_jxr(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+) -> Result<Vec<u8>> {
+    let (data, format) = jxr_input(data, format);
+    wic::encode_to_vec(jxr_frame(&data, width, height, format), "JXR")
+}
 
-        // Commit frame and encoder
-        frame.Commit()?;
-        encoder.Commit()?;
+/// Pixel data in a format JPEG XR can store.
+fn jxr_input(data: &[u8], format: ColorPixelFormat) -> (Cow<'_, [u8]>, ColorPixelFormat) {
+    match format {
+        ColorPixelFormat::Rgb10a2 => (Cow::Owned(hdr10_to_scrgb(data)), ColorPixelFormat::Rgba16f),
+        _ => (Cow::Borrowed(data), format),
     }
-
-    Ok(())
 }
 
-/// Convert a Path to a null-terminated UTF-16 string for Win32 APIs.
-fn to_wide(path: &Path) -> Vec<u16> {
-    use std::os::windows::ffi::OsStrExt;
-    path.as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
+fn jxr_frame(data: &[u8], width: u32, height: u32, format: ColorPixelFormat) -> WicFrame<'_> {
+    let (pixel_format, stride) = match format {
+        ColorPixelFormat::Bgra8 => (GUID_WICPixelFormat32bppBGRA, width * 4),
+        ColorPixelFormat::Rgba16f => (GUID_WICPixelFormat64bppRGBAHalf, width * 8),
+        ColorPixelFormat::Rgb10a2 => unreachable!("decoded to RGBA16F by jxr_input"),
+    };
+
+    WicFrame {
+        container: GUID_ContainerFormatWmp,
+        pixel_format,
+        width,
+        height,
+        stride,
+        data,
+        metadata: None,
+    }
 }
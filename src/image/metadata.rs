@@ -0,0 +1,102 @@
+// Provenance metadata written alongside pixel data.
+//
+// EXR stores it as header attributes (`capDate` / `utcOffset`, `software`,
+// and a custom `captureSource` text attribute); JPEG XR as EXIF-style IFD
+// tags (DateTime, Software, ImageDescription) through WIC metadata queries.
+// Other formats ignore it.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Optional provenance attributes for saved files: when and where a frame was
+/// captured, and by what.
+///
+/// Written to `.exr` and `.jxr` output; other formats ignore it. The default
+/// value is empty and writes nothing. See
+/// [`CapturePipeline::save_metadata`](crate::pipeline::CapturePipeline::save_metadata)
+/// for metadata filled in from a captured frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveMetadata {
+    /// Wall-clock capture time, stored in UTC.
+    pub captured_at: Option<SystemTime>,
+    /// Source monitor device name (e.g. `\\.\DISPLAY1`) or window title.
+    pub source: Option<String>,
+    /// Producing software, e.g. `"hdrcapture 0.4.0"`.
+    pub software: Option<String>,
+}
+
+impl SaveMetadata {
+    /// Metadata naming this library (and its version) as the producing software.
+    pub fn new() -> Self {
+        Self {
+            software: Some(format!("hdrcapture {}", env!("CARGO_PKG_VERSION"))),
+            ..Self::default()
+        }
+    }
+
+    /// Whether no attribute is set.
+    pub fn is_empty(&self) -> bool {
+        self.captured_at.is_none() && self.source.is_none() && self.software.is_none()
+    }
+
+    /// `captured_at` as `YYYY:MM:DD hh:mm:ss` in UTC, the date format of EXIF
+    /// DateTime and the OpenEXR `capDate` attribute.
+    pub(crate) fn date_time(&self) -> Option<String> {
+        let secs = self
+            .captured_at?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        let time = secs % 86_400;
+        Some(format!(
+            "{year:04}:{month:02}:{day:02} {:02}:{:02}:{:02}",
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        ))
+    }
+}
+
+/// Gregorian (year, month, day) of a day count since 1970-01-01
+/// (Howard Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_date_time_format() {
+        let at = |secs| SaveMetadata {
+            captured_at: Some(UNIX_EPOCH + Duration::from_secs(secs)),
+            ..SaveMetadata::default()
+        };
+        assert_eq!(at(0).date_time().unwrap(), "1970:01:01 00:00:00");
+        // 2024-02-29 13:45:07 UTC (leap day)
+        assert_eq!(
+            at(1_709_214_307).date_time().unwrap(),
+            "2024:02:29 13:45:07"
+        );
+        assert_eq!(SaveMetadata::default().date_time(), None);
+    }
+
+    #[test]
+    fn test_new_names_library() {
+        let metadata = SaveMetadata::new();
+        assert!(!metadata.is_empty());
+        assert!(metadata.software.unwrap().starts_with("hdrcapture "));
+        assert!(SaveMetadata::default().is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+// Shared WIC (Windows Imaging Component) single-frame encode path.
+//
+// Used by the JPEG XR and HEIF writers: create factory → stream → encoder →
+// frame, negotiate the pixel format, write pixels, commit. The stream is a
+// file, or an HGLOBAL memory stream for in-memory encoding. Provenance
+// metadata goes through the frame's metadata query writer.
+
+use std::ffi::CString;
+use std::mem::ManuallyDrop;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use windows::core::{w, GUID, PCWSTR, PSTR};
+use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::Graphics::Imaging::{
+    CLSID_WICImagingFactory, IWICBitmapFrameEncode, IWICImagingFactory, IWICMetadataQueryWriter,
+    WICBitmapEncoderNoCache,
+};
+use windows::Win32::System::Com::StructuredStorage::{
+    CreateStreamOnHGlobal, IPropertyBag2, PROPVARIANT, PROPVARIANT_0, PROPVARIANT_0_0,
+    PROPVARIANT_0_0_0,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, IStream, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    STATFLAG_NONAME, STATSTG, STREAM_SEEK_SET,
+};
+use windows::Win32::System::Variant::VT_LPSTR;
+
+use super::SaveMetadata;
+
+/// GENERIC_WRITE access flag (0x40000000).
+/// Defined here to avoid pulling in Win32_Storage_FileSystem feature.
+const GENERIC_WRITE: u32 = 0x40000000;
+
+/// Single-frame encode request.
+pub(super) struct WicFrame<'a> {
+    /// WIC container format (e.g. `GUID_ContainerFormatWmp`).
+    pub container: GUID,
+    /// WIC pixel format of `data`.
+    pub pixel_format: GUID,
+    pub width: u32,
+    pub height: u32,
+    /// Bytes per row of `data`.
+    pub stride: u32,
+    pub data: &'a [u8],
+    /// Provenance tags to write, if the container supports them.
+    pub metadata: Option<&'a SaveMetadata>,
+}
+
+/// Encode one frame into `path` with the given WIC container.
+///
+/// COM is initialized per-call (safe if already initialized).
+/// `label` names the format in error messages (e.g. "JXR").
+pub(super) fn encode(path: &Path, frame: WicFrame<'_>, label: &str) -> Result<()> {
+    frame.validate()?;
+
+    // SAFETY: WIC calls operate on COM objects created in this scope;
+    // `wide_path` is NUL-terminated and outlives InitializeFromFilename.
+    unsafe {
+        let factory = create_factory()?;
+        let stream = factory.CreateStream()?;
+        let wide_path = to_wide(path);
+        stream.InitializeFromFilename(PCWSTR(wide_path.as_ptr()), GENERIC_WRITE)?;
+        write_frame(&factory, &stream, frame, label)
+    }
+}
+
+/// Encode one frame into an in-memory buffer with the given WIC container.
+pub(super) fn encode_to_vec(frame: WicFrame<'_>, label: &str) -> Result<Vec<u8>> {
+    frame.validate()?;
+
+    // SAFETY: WIC calls operate on COM objects created in this scope. The
+    // HGLOBAL stream owns its memory and frees it on release; Read copies out
+    // at most `size` bytes into a buffer of that length.
+    unsafe {
+        let factory = create_factory()?;
+        let stream = CreateStreamOnHGlobal(HGLOBAL::default(), true)
+            .context("Failed to create memory stream")?;
+        write_frame(&factory, &stream, frame, label)?;
+
+        let mut stat = STATSTG::default();
+        stream.Stat(&mut stat, STATFLAG_NONAME)?;
+        let size = usize::try_from(stat.cbSize).context("encoded image too large")?;
+        stream.Seek(0, STREAM_SEEK_SET, None)?;
+
+        let mut buffer = vec![0u8; size];
+        let mut read = 0u32;
+        stream
+            .Read(
+                buffer.as_mut_ptr().cast(),
+                size as u32,
+                Some(&mut read as *mut u32),
+            )
+            .ok()?;
+        buffer.truncate(read as usize);
+        Ok(buffer)
+    }
+}
+
+impl WicFrame<'_> {
+    fn validate(&self) -> Result<()> {
+        let expected_len = self.stride as usize * self.height as usize;
+        if self.data.len() < expected_len {
+            bail!(
+                "pixel data too short: expected {} bytes ({}x{}x{}bpp), got {}",
+                expected_len,
+                self.width,
+                self.height,
+                self.stride / self.width.max(1),
+                self.data.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Initialize COM and create the WIC factory.
+///
+/// # Safety
+/// Calls COM APIs; returns owned COM objects only.
+unsafe fn create_factory() -> Result<IWICImagingFactory> {
+    // CoInitializeEx returns Ok for S_OK and S_FALSE (already initialized).
+    // Err means incompatible apartment model (e.g. RPC_E_CHANGED_MODE).
+    CoInitializeEx(None, COINIT_MULTITHREADED)
+        .ok()
+        .context("COM initialization failed (apartment model conflict?)")?;
+
+    CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)
+        .context("Failed to create WIC imaging factory")
+}
+
+/// Whether COM and the WIC factory can be set up on this thread.
+///
+/// Fails for reasons unrelated to installed codecs (an STA thread, COM not
+/// yet available), so callers shouldn't remember a `false`.
+pub(super) fn factory_available() -> bool {
+    // SAFETY: the factory is created and released right away.
+    unsafe { create_factory().is_ok() }
+}
+
+/// Encoder → frame → pixels → commit, into an already-open stream.
+///
+/// # Safety
+/// `factory` and `stream` must be valid COM objects.
+unsafe fn write_frame(
+    factory: &IWICImagingFactory,
+    stream: &IStream,
+    frame: WicFrame<'_>,
+    label: &str,
+) -> Result<()> {
+    // Create encoder
+    let encoder = factory.CreateEncoder(&frame.container, std::ptr::null())?;
+    encoder.Initialize(stream, WICBitmapEncoderNoCache)?;
+
+    // Create frame
+    let mut frame_encode: Option<IWICBitmapFrameEncode> = None;
+    let mut props: Option<IPropertyBag2> = None;
+    encoder.CreateNewFrame(&mut frame_encode, &mut props)?;
+    let frame_encode = frame_encode.context("WIC CreateNewFrame returned null")?;
+
+    // Initialize frame with default properties
+    if let Some(ref props) = props {
+        frame_encode.Initialize(props)?;
+    }
+
+    // Set frame dimensions and pixel format
+    frame_encode.SetSize(frame.width, frame.height)?;
+    let mut fmt: GUID = frame.pixel_format;
+    frame_encode.SetPixelFormat(&mut fmt)?;
+
+    // Verify WIC accepted our format (it may silently convert)
+    if fmt != frame.pixel_format {
+        bail!(
+            "WIC rejected pixel format for {} encoding; \
+             requested {:?}, got {:?}",
+            label,
+            frame.pixel_format,
+            fmt
+        );
+    }
+
+    if let Some(metadata) = frame.metadata.filter(|m| !m.is_empty()) {
+        // Best-effort: the image is still valid without its metadata.
+        if let Err(e) = write_metadata(&frame_encode, metadata) {
+            log::warn!("Failed to write {label} metadata: {e:#}");
+        }
+    }
+
+    // Write pixel data
+    frame_encode.WritePixels(frame.height, frame.stride, frame.data)?;
+
+    // Commit frame and encoder
+    frame_encode.Commit()?;
+    encoder.Commit()?;
+    Ok(())
+}
+
+/// Write `metadata` as IFD tags: DateTime (306), Software (305) and
+/// ImageDescription (270, the capture source).
+///
+/// # Safety
+/// `frame_encode` must be an initialized, uncommitted frame.
+unsafe fn write_metadata(
+    frame_encode: &IWICBitmapFrameEncode,
+    metadata: &SaveMetadata,
+) -> Result<()> {
+    let writer = frame_encode.GetMetadataQueryWriter()?;
+    if let Some(date) = metadata.date_time() {
+        set_text(&writer, w!("/ifd/{ushort=306}"), &date)?;
+    }
+    if let Some(software) = &metadata.software {
+        set_text(&writer, w!("/ifd/{ushort=305}"), software)?;
+    }
+    if let Some(source) = &metadata.source {
+        set_text(&writer, w!("/ifd/{ushort=270}"), source)?;
+    }
+    Ok(())
+}
+
+/// Set an ASCII-typed tag. Non-ASCII text is stored as UTF-8 bytes, which
+/// EXIF readers commonly accept.
+///
+/// # Safety
+/// `writer` must be a valid metadata query writer.
+unsafe fn set_text(writer: &IWICMetadataQueryWriter, query: PCWSTR, text: &str) -> Result<()> {
+    let text = CString::new(text).context("metadata text contains a NUL byte")?;
+    // VT_LPSTR borrowing `text`; ManuallyDrop keeps PropVariantClear from
+    // freeing memory it doesn't own. WIC copies the value.
+    let value = ManuallyDrop::new(PROPVARIANT {
+        Anonymous: PROPVARIANT_0 {
+            Anonymous: ManuallyDrop::new(PROPVARIANT_0_0 {
+                vt: VT_LPSTR,
+                wReserved1: 0,
+                wReserved2: 0,
+                wReserved3: 0,
+                Anonymous: PROPVARIANT_0_0_0 {
+                    pszVal: PSTR(text.as_ptr() as *mut u8),
+                },
+            }),
+        },
+    });
+    writer.SetMetadataByName(query, &*value)?;
+    Ok(())
+}
+
+/// Convert a Path to a null-terminated UTF-16 string for Win32 APIs.
+fn to_wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
@@ -12,8 +12,7 @@
 //! use hdrcapture::pipeline::{CapturePipeline, CapturePolicy};
 //!
 //! // One-shot screenshot
-//! let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
-//! let frame = cap.capture().unwrap();
+//! let frame = hdrcapture::screenshot_monitor(0, CapturePolicy::Auto).unwrap();
 //! frame.save("screenshot.png").unwrap();
 //!
 //! // Reusable pipeline
@@ -21,14 +20,66 @@
 //! let frame = cap.capture().unwrap();
 //! println!("{}x{}", frame.width, frame.height);
 //! ```
+//!
+//! For more options (cursor, border, downscale, adapter), use the builder:
+//!
+//! ```no_run
+//! use hdrcapture::pipeline::{CapturePipelineBuilder, CapturePolicy};
+//!
+//! let mut cap = CapturePipelineBuilder::new()
+//!     .monitor(0)
+//!     .policy(CapturePolicy::Auto)
+//!     .cursor(false)
+//!     .downscale(2)
+//!     .build()
+//!     .unwrap();
+//! let frame = cap.capture().unwrap();
+//!
+//! // One frame with builder options, on a single-use pipeline
+//! let frame = hdrcapture::grab_once(CapturePipelineBuilder::new().monitor(0)).unwrap();
+//! ```
+//!
+#![cfg_attr(
+    feature = "hotkey",
+    doc = "With the `hotkey` feature, [`on_hotkey`] captures on a global hotkey press"
+)]
+#![cfg_attr(feature = "hotkey", doc = "(see the [`hotkey`] module).")]
+//! [`ReplayBuffer`] keeps the last N seconds of a target in memory for
+//! "save the last clip" features.
+//! [`CapturePipeline::stream_to`](pipeline::CapturePipeline::stream_to) feeds
+//! a paced frame stream to an external encoder through a
+//! [`video::VideoSink`], e.g. raw frames for ffmpeg with
+//! [`video::RawVideoWriter`]. For hardware encoders,
+//! [`CapturePipeline::capture_nv12`](pipeline::CapturePipeline::capture_nv12)
+//! converts frames to NV12 on the GPU.
+//!
+//! ## Logging
+//!
+//! Diagnostics go through the [`log`](https://docs.rs/log) facade: `info` for
+//! the GPU in use, `warn` for recoverable failures (e.g. the capture border
+//! can't be hidden), `debug` for frame pool recreation and buffer pool growth.
+//! Nothing is printed unless the application installs a logger.
 
 #![cfg(windows)]
 
 pub mod capture;
 pub mod color;
+mod com;
 pub mod d3d11;
+#[cfg(feature = "hotkey")]
+pub mod hotkey;
 pub mod image;
 pub mod memory;
 pub mod pipeline;
 mod python;
+pub mod replay;
 pub mod shader;
+pub mod video;
+
+pub use capture::watch_display_changes;
+pub use color::tonemap_buffer;
+#[cfg(feature = "hotkey")]
+pub use hotkey::on_hotkey;
+pub use image::{supported_formats, FormatInfo};
+pub use pipeline::{grab_once, save_batch, screenshot_monitor, screenshot_window};
+pub use replay::ReplayBuffer;
@@ -1,3 +1,5 @@
 pub mod elastic_pool;
+pub mod shared_memory;
 
 pub use elastic_pool::{ElasticBufferPool, PoolStats, PooledBuffer};
+pub use shared_memory::{SharedFrame, SharedFrameHeader, SharedMemoryReader, SharedMemoryWriter};
@@ -52,6 +52,8 @@ impl Group {
 }
 
 struct State {
+    /// Size every buffer handed out by `acquire()` has.
+    frame_bytes: usize,
     groups: Vec<Group>,
     total_frames: usize,
     release_streak: usize,
@@ -79,22 +81,29 @@ impl State {
         (step * 2).div_ceil(5).max(2) // ceil(step * 0.4)
     }
 
-    fn append_group(&mut self, size: usize, frame_bytes: usize) {
+    fn append_group(&mut self, size: usize) {
+        let frame_bytes = self.frame_bytes;
         self.groups.push(Group::new(size, frame_bytes));
         self.total_frames += size;
         self.expand_count += 1;
         self.alloc_count += size;
+        log::debug!(
+            "buffer pool grew by {} frames to {} ({} bytes each)",
+            size,
+            self.total_frames,
+            frame_bytes
+        );
     }
 }
 
 pub struct ElasticBufferPool {
-    frame_bytes: usize,
     state: Mutex<State>,
 }
 
 impl ElasticBufferPool {
     pub fn new(frame_bytes: usize) -> Arc<Self> {
         let state = State {
+            frame_bytes,
             groups: vec![Group::new(INITIAL_FRAMES, frame_bytes)],
             total_frames: INITIAL_FRAMES,
             release_streak: 0,
@@ -104,22 +113,52 @@ impl ElasticBufferPool {
             alloc_count: INITIAL_FRAMES,
         };
         Arc::new(Self {
-            frame_bytes,
             state: Mutex::new(state),
         })
     }
 
+    /// Change the size of buffers handed out from now on.
+    ///
+    /// Keeps the group structure and the allocations: free buffers are resized
+    /// when next acquired (growing in place when capacity allows), and buffers
+    /// still held by published frames are resized when they come back. Frames
+    /// already published keep their data at the old size.
+    pub fn resize_frame_bytes(&self, frame_bytes: usize) {
+        let mut state = self.state.lock().expect("pool mutex poisoned");
+        if state.frame_bytes != frame_bytes {
+            log::debug!(
+                "buffer pool frame size changed from {} to {} bytes",
+                state.frame_bytes,
+                frame_bytes
+            );
+            state.frame_bytes = frame_bytes;
+        }
+    }
+
+    /// Size of buffers handed out by `acquire()`.
+    pub fn frame_bytes(&self) -> usize {
+        self.state.lock().expect("pool mutex poisoned").frame_bytes
+    }
+
     pub fn acquire(self: &Arc<Self>) -> PooledBuffer {
         let mut state = self.state.lock().expect("pool mutex poisoned");
         state.acquire_count += 1;
 
         if state.free_frames() < state.low_watermark() {
             let step = state.current_step();
-            state.append_group(step, self.frame_bytes);
+            state.append_group(step);
         }
 
+        let frame_bytes = state.frame_bytes;
         for (idx, group) in state.groups.iter_mut().enumerate().rev() {
-            if let Some(data) = group.free.pop() {
+            if let Some(mut data) = group.free.pop() {
+                // Free buffers keep their old size across resize_frame_bytes()
+                // and published frames come back truncated; fix up on reuse.
+                // reserve_exact avoids doubling multi-MB frames for small growth.
+                if data.len() != frame_bytes {
+                    data.reserve_exact(frame_bytes.saturating_sub(data.len()));
+                    data.resize(frame_bytes, 0);
+                }
                 group.borrowed += 1;
                 return PooledBuffer {
                     data: Some(data),
@@ -131,7 +170,7 @@ impl ElasticBufferPool {
 
         // Defensive fallback: append one more group, then take from it.
         let step = state.current_step();
-        state.append_group(step, self.frame_bytes);
+        state.append_group(step);
         let idx = state.groups.len() - 1;
         let group = &mut state.groups[idx];
         let data = group
@@ -158,11 +197,7 @@ impl ElasticBufferPool {
         }
     }
 
-    fn release_inner(&self, group_idx: usize, mut data: Vec<u8>) {
-        if data.len() != self.frame_bytes {
-            data.resize(self.frame_bytes, 0);
-        }
-
+    fn release_inner(&self, group_idx: usize, data: Vec<u8>) {
         let mut state = self.state.lock().expect("pool mutex poisoned");
         if let Some(group) = state.groups.get_mut(group_idx) {
             if group.borrowed > 0 {
@@ -188,6 +223,7 @@ impl ElasticBufferPool {
                 }
                 let _ = state.groups.pop();
                 state.shrink_count += 1;
+                log::debug!("buffer pool shrank to {} frames", state.total_frames);
                 state.release_streak = 0;
             }
         } else {
@@ -297,4 +333,25 @@ mod tests {
         assert!(stats.shrink_count >= 1);
         assert!(stats.total_frames >= INITIAL_FRAMES);
     }
+
+    #[test]
+    fn test_resize_frame_bytes_keeps_published_frames() {
+        let pool = ElasticBufferPool::new(1024);
+        let mut published = pool.acquire();
+        published.as_mut_slice().fill(7);
+
+        pool.resize_frame_bytes(4096);
+        let grown = pool.acquire();
+        assert_eq!(grown.as_slice().len(), 4096);
+        // A frame published before the resize keeps its size and content.
+        assert_eq!(published.as_slice().len(), 1024);
+        assert!(published.as_slice().iter().all(|&b| b == 7));
+
+        drop(published);
+        drop(grown);
+        let bufs: Vec<_> = (0..INITIAL_FRAMES).map(|_| pool.acquire()).collect();
+        assert!(bufs.iter().all(|b| b.as_slice().len() == 4096));
+        // The resize reused the initial buffers instead of rebuilding the pool.
+        assert_eq!(pool.stats().alloc_count, INITIAL_FRAMES);
+    }
 }
@@ -0,0 +1,455 @@
+// Named shared-memory segment for handing frames to another process
+//
+// Layout: a 64-byte `SharedFrameHeader` followed by `capacity` bytes of pixel
+// data (rows tightly packed, `stride` bytes each). The header's `sequence`
+// is a seqlock: the writer makes it odd before touching the frame and even
+// (+2 per frame) once the frame is complete. A reader copies the frame,
+// re-reads `sequence`, and retries when it changed or was odd.
+//
+// A segment can't grow in place. When frames outgrow it, the writer sets
+// `SHARED_FRAME_RETIRED` in `flags` and closes it; the name becomes free for
+// a larger segment once every reader has closed the old one as well.
+
+use std::ptr::{addr_of, addr_of_mut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{bail, Context, Result};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{
+    CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE, INVALID_HANDLE_VALUE,
+};
+use windows::Win32::System::Memory::{
+    CreateFileMappingW, MapViewOfFile, OpenFileMappingW, UnmapViewOfFile, FILE_MAP_ALL_ACCESS,
+    FILE_MAP_READ, MEMORY_MAPPED_VIEW_ADDRESS, PAGE_READWRITE,
+};
+
+use crate::color::ColorPixelFormat;
+
+/// `magic` value of a segment written by this crate ("HDRC", little-endian).
+pub const SHARED_FRAME_MAGIC: u32 = u32::from_le_bytes(*b"HDRC");
+
+/// Layout version; bumped on incompatible header changes.
+pub const SHARED_FRAME_VERSION: u32 = 1;
+
+/// `flags` bit: the writer abandoned this segment (frames outgrew it) and
+/// will recreate it under the same name; readers should close and reopen.
+pub const SHARED_FRAME_RETIRED: u32 = 1;
+
+/// Seqlock read attempts before [`SharedMemoryReader::read`] gives up.
+const MAX_READ_ATTEMPTS: usize = 1000;
+
+/// Header at the start of a shared frame segment; pixel data follows at
+/// offset `size_of::<SharedFrameHeader>()` (64).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SharedFrameHeader {
+    /// [`SHARED_FRAME_MAGIC`].
+    pub magic: u32,
+    /// [`SHARED_FRAME_VERSION`].
+    pub version: u32,
+    /// Seqlock counter: odd while a frame is being written; frames published
+    /// so far = `sequence / 2`.
+    pub sequence: u64,
+    /// Bytes available for pixel data.
+    pub capacity: u64,
+    /// Bytes of pixel data in the current frame (`stride * height`).
+    pub data_len: u64,
+    /// Frame timestamp (seconds, QPC clock; see `CapturedFrame::timestamp`).
+    pub timestamp: f64,
+    pub width: u32,
+    pub height: u32,
+    /// 0 = BGRA8, 1 = RGBA16F, 2 = RGB10A2 (see [`format_code`]).
+    pub format: u32,
+    /// Bytes per row.
+    pub stride: u32,
+    /// [`SHARED_FRAME_RETIRED`] or 0.
+    pub flags: u32,
+    pub reserved: u32,
+}
+
+/// Header `format` code of a pixel format.
+pub fn format_code(format: ColorPixelFormat) -> u32 {
+    match format {
+        ColorPixelFormat::Bgra8 => 0,
+        ColorPixelFormat::Rgba16f => 1,
+        ColorPixelFormat::Rgb10a2 => 2,
+    }
+}
+
+/// Writer side of a named shared frame segment.
+///
+/// The segment lives as long as any process has it open; dropping the writer
+/// closes this process's view and handle.
+pub struct SharedMemoryWriter {
+    name: String,
+    handle: HANDLE,
+    view: MEMORY_MAPPED_VIEW_ADDRESS,
+    capacity: usize,
+    sequence: u64,
+}
+
+// SAFETY: the handle and view belong to this writer alone and aren't tied to
+// the creating thread; writes need `&mut self`.
+unsafe impl Send for SharedMemoryWriter {}
+
+impl SharedMemoryWriter {
+    /// Create segment `name` with room for `capacity` bytes of pixel data.
+    ///
+    /// `name` is a kernel object name, e.g. `"hdrcapture_frames"` or
+    /// `"Local\\hdrcapture_frames"`. Fails if a segment of that name already
+    /// exists, since its size may not match.
+    pub fn create(name: &str, capacity: usize) -> Result<Self> {
+        let total = (HEADER_SIZE + capacity) as u64;
+        // SAFETY: creates a pagefile-backed mapping; the handle is owned by
+        // the returned writer and closed on every error path below.
+        let handle = unsafe {
+            CreateFileMappingW(
+                INVALID_HANDLE_VALUE,
+                None,
+                PAGE_READWRITE,
+                (total >> 32) as u32,
+                total as u32,
+                &HSTRING::from(name),
+            )
+        }
+        .with_context(|| format!("CreateFileMappingW failed for '{}'", name))?;
+        // SAFETY: reads this thread's last error, set by CreateFileMappingW.
+        if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+            // SAFETY: closing the handle opened above.
+            let _ = unsafe { CloseHandle(handle) };
+            bail!("shared memory segment '{}' already exists", name);
+        }
+
+        // SAFETY: maps the whole mapping created above.
+        let view = unsafe { MapViewOfFile(handle, FILE_MAP_ALL_ACCESS, 0, 0, 0) };
+        if view.Value.is_null() {
+            let err = windows::core::Error::from_thread();
+            // SAFETY: closing the handle opened above.
+            let _ = unsafe { CloseHandle(handle) };
+            return Err(err).with_context(|| format!("MapViewOfFile failed for '{}'", name));
+        }
+
+        let header = view.Value as *mut SharedFrameHeader;
+        // SAFETY: the view is page-aligned and at least HEADER_SIZE bytes;
+        // nobody else has written to the fresh (zeroed) segment.
+        unsafe {
+            header.write(SharedFrameHeader {
+                magic: SHARED_FRAME_MAGIC,
+                version: SHARED_FRAME_VERSION,
+                capacity: capacity as u64,
+                ..Default::default()
+            });
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            handle,
+            view,
+            capacity,
+            sequence: 0,
+        })
+    }
+
+    /// Segment name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Bytes available for pixel data.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Publish one frame of tightly packed pixel data.
+    pub fn write(
+        &mut self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        format: ColorPixelFormat,
+        timestamp: f64,
+    ) -> Result<()> {
+        format.check_len(data, width, height)?;
+        if data.len() > self.capacity {
+            bail!(
+                "{}x{} frame ({} bytes) doesn't fit shared memory segment '{}' ({} bytes)",
+                width,
+                height,
+                data.len(),
+                self.name,
+                self.capacity
+            );
+        }
+
+        let header = self.view.Value as *mut SharedFrameHeader;
+        // SAFETY: `sequence` is 8-byte aligned at offset 8 of the page-aligned
+        // view; other processes only access it atomically as well.
+        let sequence = unsafe { &*(addr_of_mut!((*header).sequence) as *const AtomicU64) };
+        sequence.store(self.sequence + 1, Ordering::Relaxed);
+        std::sync::atomic::fence(Ordering::Release);
+
+        // SAFETY: the header fields and `data.len() <= capacity` bytes after
+        // the header are inside the view; readers detect the torn state
+        // through the odd sequence.
+        unsafe {
+            addr_of_mut!((*header).data_len).write(data.len() as u64);
+            addr_of_mut!((*header).timestamp).write(timestamp);
+            addr_of_mut!((*header).width).write(width);
+            addr_of_mut!((*header).height).write(height);
+            addr_of_mut!((*header).format).write(format_code(format));
+            addr_of_mut!((*header).stride).write(width * format.bytes_per_pixel() as u32);
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                (self.view.Value as *mut u8).add(HEADER_SIZE),
+                data.len(),
+            );
+        }
+
+        self.sequence += 2;
+        sequence.store(self.sequence, Ordering::Release);
+        Ok(())
+    }
+
+    /// Mark the segment as abandoned (see [`SHARED_FRAME_RETIRED`]) and close it.
+    pub fn retire(self) {
+        let header = self.view.Value as *mut SharedFrameHeader;
+        // SAFETY: `flags` is inside the header of the view this writer owns;
+        // readers only test it after a frame read.
+        unsafe { addr_of_mut!((*header).flags).write_volatile(SHARED_FRAME_RETIRED) };
+        std::sync::atomic::fence(Ordering::Release);
+    }
+}
+
+impl Drop for SharedMemoryWriter {
+    fn drop(&mut self) {
+        // SAFETY: unmapping the view and closing the handle this writer owns.
+        unsafe {
+            let _ = UnmapViewOfFile(self.view);
+            let _ = CloseHandle(self.handle);
+        }
+    }
+}
+
+/// One frame copied out of a shared frame segment.
+#[derive(Debug, Clone)]
+pub struct SharedFrame {
+    /// Header as of this frame (`sequence` identifies it).
+    pub header: SharedFrameHeader,
+    /// Pixel data, `header.data_len` bytes.
+    pub data: Vec<u8>,
+}
+
+/// Reader side of a segment created by [`SharedMemoryWriter`], e.g. in the
+/// consumer process.
+pub struct SharedMemoryReader {
+    handle: HANDLE,
+    view: MEMORY_MAPPED_VIEW_ADDRESS,
+    capacity: usize,
+}
+
+// SAFETY: the handle and the read-only view belong to this reader alone.
+unsafe impl Send for SharedMemoryReader {}
+
+impl SharedMemoryReader {
+    /// Open existing segment `name` read-only.
+    ///
+    /// Fails if it doesn't exist or wasn't written by this crate's layout
+    /// version.
+    pub fn open(name: &str) -> Result<Self> {
+        // SAFETY: opens a named mapping; the handle is owned by the returned
+        // reader and closed on every error path below.
+        let handle = unsafe { OpenFileMappingW(FILE_MAP_READ.0, false, &HSTRING::from(name)) }
+            .with_context(|| format!("OpenFileMappingW failed for '{}'", name))?;
+        // SAFETY: maps the whole mapping opened above.
+        let view = unsafe { MapViewOfFile(handle, FILE_MAP_READ, 0, 0, 0) };
+        if view.Value.is_null() {
+            let err = windows::core::Error::from_thread();
+            // SAFETY: closing the handle opened above.
+            let _ = unsafe { CloseHandle(handle) };
+            return Err(err).with_context(|| format!("MapViewOfFile failed for '{}'", name));
+        }
+        let mut reader = Self {
+            handle,
+            view,
+            capacity: 0,
+        };
+
+        let header = reader.header();
+        // SAFETY: the writer initializes magic, version and capacity before
+        // anyone can open the segment, and never changes them.
+        let (magic, version, capacity) = unsafe {
+            (
+                addr_of!((*header).magic).read(),
+                addr_of!((*header).version).read(),
+                addr_of!((*header).capacity).read(),
+            )
+        };
+        if magic != SHARED_FRAME_MAGIC || version != SHARED_FRAME_VERSION {
+            bail!(
+                "'{}' is not a version {} hdrcapture frame segment",
+                name,
+                SHARED_FRAME_VERSION
+            );
+        }
+        reader.capacity = capacity as usize;
+        Ok(reader)
+    }
+
+    /// Bytes available for pixel data.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Frames published so far.
+    pub fn frames_published(&self) -> u64 {
+        self.sequence().load(Ordering::Acquire) / 2
+    }
+
+    /// Copy the newest complete frame; `None` before the first one.
+    ///
+    /// Retries while the writer is mid-frame. Fails when the segment was
+    /// retired (see [`SHARED_FRAME_RETIRED`]): drop the reader and open the
+    /// name again to follow the writer to its larger segment.
+    pub fn read(&self) -> Result<Option<SharedFrame>> {
+        let header = self.header();
+        let pixels = (self.view.Value as *const u8).wrapping_add(HEADER_SIZE);
+        let frame = read_consistent(self.sequence(), || {
+            // SAFETY: the header and `capacity` bytes after it are inside the
+            // view. Torn values are discarded by `read_consistent`, and
+            // `data_len` is clamped so a torn one can't read past the view.
+            unsafe {
+                let header = header.read_volatile();
+                let len = (header.data_len as usize).min(self.capacity);
+                let data = std::slice::from_raw_parts(pixels, len).to_vec();
+                SharedFrame { header, data }
+            }
+        })
+        .context("shared memory writer kept the frame busy")?;
+        if frame.header.flags & SHARED_FRAME_RETIRED != 0 {
+            bail!("shared memory segment was retired; reopen it");
+        }
+        Ok((frame.header.sequence != 0).then_some(frame))
+    }
+
+    fn header(&self) -> *const SharedFrameHeader {
+        self.view.Value as *const SharedFrameHeader
+    }
+
+    fn sequence(&self) -> &AtomicU64 {
+        // SAFETY: as in `SharedMemoryWriter::write`; the writer only accesses
+        // `sequence` atomically.
+        unsafe { &*(addr_of!((*self.header()).sequence) as *const AtomicU64) }
+    }
+}
+
+impl Drop for SharedMemoryReader {
+    fn drop(&mut self) {
+        // SAFETY: unmapping the view and closing the handle this reader owns.
+        unsafe {
+            let _ = UnmapViewOfFile(self.view);
+            let _ = CloseHandle(self.handle);
+        }
+    }
+}
+
+/// Seqlock read: run `copy` until it ran against an even `sequence` that
+/// didn't move meanwhile. `None` after [`MAX_READ_ATTEMPTS`] torn attempts.
+fn read_consistent<T>(sequence: &AtomicU64, mut copy: impl FnMut() -> T) -> Option<T> {
+    for _ in 0..MAX_READ_ATTEMPTS {
+        let before = sequence.load(Ordering::Acquire);
+        if before % 2 == 1 {
+            std::hint::spin_loop();
+            continue;
+        }
+        let value = copy();
+        std::sync::atomic::fence(Ordering::Acquire);
+        if sequence.load(Ordering::Relaxed) == before {
+            return Some(value);
+        }
+    }
+    None
+}
+
+const HEADER_SIZE: usize = std::mem::size_of::<SharedFrameHeader>();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_layout() {
+        assert_eq!(HEADER_SIZE, 64);
+        assert_eq!(std::mem::offset_of!(SharedFrameHeader, sequence), 8);
+        assert_eq!(std::mem::offset_of!(SharedFrameHeader, timestamp), 32);
+        assert_eq!(std::mem::offset_of!(SharedFrameHeader, stride), 52);
+        assert_eq!(SHARED_FRAME_MAGIC.to_le_bytes(), *b"HDRC");
+    }
+
+    #[test]
+    fn test_write_frame_to_segment() {
+        let name = format!("hdrcapture_test_{}", std::process::id());
+        let mut writer = SharedMemoryWriter::create(&name, 16).unwrap();
+        assert!(SharedMemoryWriter::create(&name, 16).is_err());
+
+        writer
+            .write(&[7; 16], 2, 2, ColorPixelFormat::Bgra8, 1.5)
+            .unwrap();
+        let header = unsafe { *(writer.view.Value as *const SharedFrameHeader) };
+        assert_eq!(header.sequence, 2);
+        assert_eq!((header.width, header.height, header.stride), (2, 2, 8));
+        assert_eq!(header.data_len, 16);
+
+        let too_big = writer.write(&[0; 32], 4, 2, ColorPixelFormat::Bgra8, 2.0);
+        assert!(too_big.is_err());
+
+        let reader = SharedMemoryReader::open(&name).unwrap();
+        assert_eq!(reader.capacity(), 16);
+        let frame = reader.read().unwrap().unwrap();
+        assert_eq!(frame.header.sequence, 2);
+        assert_eq!(frame.header.timestamp, 1.5);
+        assert_eq!(frame.data, [7; 16]);
+
+        writer.retire();
+        assert!(reader.read().is_err());
+    }
+
+    #[test]
+    fn test_read_consistent_retries_torn_reads() {
+        let sequence = AtomicU64::new(4);
+        let mut attempts = 0;
+        // The first copy overlaps a write (sequence moves on), so it's retried.
+        let value = read_consistent(&sequence, || {
+            attempts += 1;
+            if attempts == 1 {
+                sequence.fetch_add(2, Ordering::Relaxed);
+            }
+            attempts
+        });
+        assert_eq!(value, Some(2));
+
+        // A writer stuck mid-frame (odd sequence) never yields a value.
+        let sequence = AtomicU64::new(5);
+        assert_eq!(read_consistent(&sequence, || ()), None);
+    }
+
+    #[test]
+    fn test_reader_never_sees_torn_frames() {
+        let name = format!("hdrcapture_test_torn_{}", std::process::id());
+        let mut writer = SharedMemoryWriter::create(&name, 4096).unwrap();
+        let reader = SharedMemoryReader::open(&name).unwrap();
+        let writing = std::thread::spawn(move || {
+            for i in 0..2000u32 {
+                let fill = (i % 251) as u8;
+                writer
+                    .write(&[fill; 4096], 32, 32, ColorPixelFormat::Bgra8, i as f64)
+                    .unwrap();
+            }
+        });
+        while !writing.is_finished() {
+            if let Ok(Some(frame)) = reader.read() {
+                assert!(frame.data.iter().all(|&b| b == frame.data[0]));
+            }
+        }
+        writing.join().unwrap();
+    }
+}
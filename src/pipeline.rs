@@ -5,6 +5,8 @@
 // - grab(): drain backlog and take last frame, suitable for continuous capture (lower latency)
 // Frame lifetime covers CopyResource, ensuring DWM won't overwrite the surface being read.
 
+use std::cell::Cell;
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -15,27 +17,98 @@ use windows::Win32::Graphics::Direct3D11::{
     D3D11_USAGE_DEFAULT,
 };
 use windows::Win32::Graphics::Dxgi::Common::{
-    DXGI_FORMAT, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R16G16B16A16_FLOAT,
+    DXGI_FORMAT, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R10G10B10A2_UNORM,
+    DXGI_FORMAT_R16G16B16A16_FLOAT,
 };
 
-use crate::capture::wgc::{CaptureTarget, WGCCapture, WindowGeometry};
-pub use crate::capture::CapturePolicy;
-use crate::capture::{enable_dpi_awareness, find_monitor, find_window, init_capture};
-use crate::color::white_level;
-use crate::color::{self, ColorFrame, ColorPixelFormat, ToneMapPass};
+use crate::capture::wgc::{
+    hdr_reference_monitor, target_display_format, CaptureTarget, SessionOptions, WGCCapture,
+    WindowGeometry,
+};
+use crate::capture::{
+    display_generation, enable_dpi_awareness, find_monitor, find_monitor_at, find_monitor_by_name,
+    find_monitor_by_os_number, find_window, init_capture, is_capture_excluded, is_child_window,
+    is_foreground_on_monitor, is_foreground_window, is_window_shown, monitor_device_name,
+    monitor_dpi_scale, monitor_orientation, window_title, CaptureExclusion,
+};
+pub use crate::capture::{CaptureBackend, CapturePolicy, ForegroundPolicy, MinimizedPolicy};
+use crate::color::{
+    self, AlphaMode, ColorFrame, ColorPixelFormat, ColorProfile, ColorSpacePass, CubeLut,
+    DisplayFormat, LutPass, SdrAdjustPass, ToneMapPass,
+};
+use crate::color::{icc, white_level};
+pub use crate::color::{
+    AutoExposure, DisplayColorSpace, OutputColorSpace, SdrAdjust, ToneMapOperator,
+};
+use crate::com::ComGuard;
+pub use crate::d3d11::compute::ThreadGroupSize;
+use crate::d3d11::nv12::Nv12Pass;
+use crate::d3d11::orient::OrientPass;
+pub use crate::d3d11::orient::{Axis, FrameTransform, Rotation};
+pub use crate::d3d11::scale::ScaleFit;
+use crate::d3d11::scale::ScalePass;
+pub use crate::d3d11::texture::ReadbackStrategy;
 use crate::d3d11::texture::TextureReader;
 use crate::d3d11::{create_d3d11_device, D3D11Context};
-use crate::memory::ElasticBufferPool;
+use crate::image::SaveMetadata;
+use crate::memory::{ElasticBufferPool, SharedMemoryWriter};
 
+mod batch;
+mod bracket;
 mod build;
+mod builder;
+mod changed;
+mod close;
 mod crop;
+mod desktop;
+mod diff;
+mod exclude;
+mod foreground;
 mod frame_sync;
+mod minimized;
 mod modes;
+mod multi;
+mod nv12;
+mod oneshot;
+mod orient;
+mod pause;
+mod poll;
+mod preview;
 mod process;
+mod protected;
+mod publish;
+mod readback;
+mod shared;
+mod source;
+mod stable;
+mod stream;
+mod thumbnail;
+mod tonemap;
 mod types;
+mod watchdog;
 
-pub use types::{CapturedFrame, SharedFrameData};
-use types::{CropCache, RawFrame};
+pub use batch::save_batch;
+pub use bracket::BracketedCapture;
+pub use builder::CapturePipelineBuilder;
+use builder::PipelineConfig;
+use changed::ChangeDetector;
+use crop::{content_box, sub_box};
+pub use desktop::DesktopPipeline;
+pub use diff::DiffResult;
+pub use multi::MultiCapture;
+pub use oneshot::{grab_once, screenshot_monitor, screenshot_window};
+use orient::query_display_orientation;
+use publish::Publisher;
+use readback::InFlightFrame;
+pub use shared::{FrameTexture, SharedTexture};
+use source::CaptureSource;
+#[cfg(test)]
+use types::test_frame;
+pub use types::{
+    CaptureError, CaptureStats, CaptureWarning, CapturedFrame, FrameMode, Pixel, SharedFrameData,
+};
+use types::{CropCache, GpuFrame, RawFrame, Readback};
+use watchdog::Watchdog;
 
 /// First frame wait timeout
 const FIRST_FRAME_TIMEOUT: Duration = Duration::from_secs(1);
@@ -47,9 +120,17 @@ const FRESH_FRAME_TIMEOUT: Duration = Duration::from_millis(50);
 /// Maximum retries when resize keeps changing during transition.
 const RESIZE_RETRY_LIMIT: usize = 3;
 
+/// How often the SDR white level is re-queried on HDR targets. The Windows
+/// "SDR content brightness" slider sends no display change notification.
+const WHITE_LEVEL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Capture pipeline
 ///
-/// Wraps D3D11 device, WGC capture session, and texture reader, providing one-liner screenshot capability.
+/// Wraps D3D11 device, capture session (WGC or Desktop Duplication), and texture reader, providing one-liner screenshot capability.
+///
+/// Creating a pipeline initializes COM on the calling thread (joining the
+/// MTA unless the thread already has an apartment) and releases it on drop.
+/// The pipeline is `!Send`: use it and drop it on the thread that created it.
 ///
 /// # Examples
 /// ```no_run
@@ -59,10 +140,19 @@ const RESIZE_RETRY_LIMIT: usize = 3;
 /// println!("{}x{}, {} bytes", frame.width, frame.height, frame.data.len());
 /// ```
 pub struct CapturePipeline {
-    _d3d_ctx: D3D11Context,
+    d3d_ctx: D3D11Context,
     policy: CapturePolicy,
-    capture: WGCCapture,
+    capture: CaptureSource,
+    /// Capture target, kept for live display queries (refresh rate).
+    target: CaptureTarget,
     reader: TextureReader,
+    /// Frames queued on the staging ring and not read yet (`readback_depth` > 1).
+    in_flight: VecDeque<InFlightFrame>,
+    /// Staging ring slot the next queued frame is copied to.
+    next_slot: usize,
+    /// Whether the current call may return an earlier frame from the ring
+    /// (`grab()` only; `capture()` always reads its own frame).
+    pipelined_readback: bool,
     output_pool: Arc<ElasticBufferPool>,
     output_frame_bytes: usize,
     /// First call flag. First frame after StartCapture() is naturally fresh,
@@ -72,10 +162,29 @@ pub struct CapturePipeline {
     cached_frame: Option<CapturedFrame>,
     /// GPU tone-map pass (Some when Auto policy may produce Rgba16f).
     tone_map_pass: Option<ToneMapPass>,
-    /// SDR white level in nits, queried at pipeline creation.
+    /// Whether the frame pool was created for HDR surfaces (`Hdr`, `Auto` on
+    /// an HDR target, or a forced HDR format). Fixed for the session; decides
+    /// which policies `set_policy` can switch to.
+    hdr_source: bool,
+    /// Hard-clip HDR→SDR pass for `Sdr` on an HDR frame pool (set at runtime,
+    /// or built with a forced HDR format).
+    clip_pass: Option<ToneMapPass>,
+    /// SDR white level in nits, queried at pipeline creation, after display
+    /// changes (see `watch_display_changes`) and every
+    /// `WHITE_LEVEL_POLL_INTERVAL` while the target is HDR.
     sdr_white_nits: f32,
-    /// Whether the target monitor has HDR enabled (detected once at init).
+    /// When `sdr_white_nits` was last queried.
+    white_level_checked: Instant,
+    /// ICC profile Windows associates with the target monitor, if any.
+    /// Tagged onto frames tone-mapped from HDR.
+    display_profile: Option<ColorProfile>,
+    /// Whether the target monitor has HDR enabled (detected at init, refreshed
+    /// after display changes).
     target_hdr: bool,
+    /// Signal format of the target monitor, tagged onto frames.
+    display_format: DisplayFormat,
+    /// Display configuration generation the state above was queried at.
+    display_generation: u64,
     /// Crop to client area in window capture (remove title bar / borders).
     headless: bool,
     /// Cached crop texture for client area cropping (window capture only).
@@ -84,7 +193,52 @@ pub struct CapturePipeline {
     /// One-shot guard for grab(): when resize is observed, force next call to
     /// wait for a fresh frame before using backlog frames.
     force_fresh: bool,
+    /// GPU 3D LUT pass applied to SDR output (Some when a LUT is loaded).
+    lut_pass: Option<LutPass>,
+    /// GPU brightness / contrast / gamma pass for SDR output (Some once a
+    /// non-identity `SdrAdjust` has been set).
+    sdr_adjust_pass: Option<SdrAdjustPass>,
+    /// GPU rotate / flip pass (Some when a transform or `auto_rotate` is configured).
+    orient_pass: Option<OrientPass>,
+    /// Display rotation and desktop size of the target monitor (Some with
+    /// `auto_rotate` on monitor targets; refreshed after display changes).
+    display_orientation: Option<(Rotation, (u32, u32))>,
+    /// GPU resize pass (Some when output scaling is configured).
+    scale_pass: Option<ScalePass>,
+    /// GPU color space pass for RGBA16F output (Some unless scRGB is requested).
+    color_space_pass: Option<ColorSpacePass>,
+    /// GPU checksum state for `grab_changed()` (created on first use).
+    change_detector: Option<ChangeDetector>,
+    /// GPU NV12 conversion for `capture_nv12()` (created on first use).
+    nv12_pass: Option<Nv12Pass>,
+    /// Private copy of the last `capture_shared()` frame, for static content.
+    shared_cache: Option<GpuFrame>,
+    /// Private copy of the raw frame `capture_bracketed()` last tone-mapped.
+    bracket_source: Option<RawFrame>,
+    /// Brightest sampled pixel of `bracket_source`, in nits.
+    bracket_peak_nits: f32,
+    /// Session restart on frame starvation (Some when configured).
+    watchdog: Option<Watchdog>,
+    /// Consecutive all-black frames from a shown window (protected content
+    /// detection).
+    black_frames: u32,
+    /// Shared-memory segment new frames are published to (see
+    /// `publish_to_shared_memory`).
+    shared_memory: Option<Publisher>,
+    /// Warning for the next processed frame (set by a watchdog restart).
+    pending_warning: Option<CaptureWarning>,
+    /// Set by `pause()`: capture calls fail until `resume()`.
+    paused: bool,
+    /// Own windows hidden from the capture (see `exclude_windows`).
+    exclusion: CaptureExclusion,
+    /// Options the pipeline was built with.
+    config: PipelineConfig,
+    /// Pipeline-side health counters (source-side counts live in `capture`).
+    stats: Cell<CaptureStats>,
     /// Prevent Send + Sync: pipeline holds thread-affine COM resources
     /// (ID3D11DeviceContext) that must not cross thread boundaries.
     _not_send_sync: PhantomData<*const ()>,
+    /// COM reference taken on the creating thread. Declared last so it is
+    /// dropped after every COM object above.
+    _com: Option<ComGuard>,
 }
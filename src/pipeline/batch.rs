@@ -0,0 +1,95 @@
+use super::*;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::com::ComGuard;
+
+/// Save many frames in parallel, e.g. a burst captured with `grab()`.
+///
+/// Frames are encoded on up to one thread per CPU core (never more threads
+/// than frames). Each thread initializes COM for the WIC encoders (`.jxr`,
+/// `.heic`). Returns one result per input, in input order; a failed file
+/// doesn't stop the others.
+///
+/// # Examples
+/// ```no_run
+/// # use hdrcapture::pipeline::{CapturePipeline, CapturePolicy};
+/// let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+/// let frames = (0..30)
+///     .map(|i| Ok((cap.grab()?, format!("shot_{i:02}.png").into())))
+///     .collect::<anyhow::Result<Vec<_>>>()
+///     .unwrap();
+/// for result in hdrcapture::save_batch(frames) {
+///     result.unwrap();
+/// }
+/// ```
+pub fn save_batch(frames: Vec<(CapturedFrame, PathBuf)>) -> Vec<Result<()>> {
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(frames.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<()>>>> = Mutex::new(frames.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let com = ComGuard::init_mta();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((frame, path)) = frames.get(index) else {
+                        break;
+                    };
+                    let result = match &com {
+                        Ok(_) => frame
+                            .save(path)
+                            .with_context(|| format!("failed to save {}", path.display())),
+                        Err(e) => Err(anyhow::anyhow!("COM init failed: {e}")),
+                    };
+                    results.lock().expect("batch results poisoned")[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .expect("batch results poisoned")
+        .into_iter()
+        .map(|result| result.expect("every frame is visited once"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_batch_reports_results_in_order() {
+        let dir = std::env::temp_dir().join(format!("hdrcapture_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let frames = vec![
+            (
+                test_frame(2, 2, ColorPixelFormat::Bgra8, &[0; 16]),
+                dir.join("a.png"),
+            ),
+            (
+                test_frame(2, 2, ColorPixelFormat::Bgra8, &[0; 16]),
+                dir.join("b.unknown"),
+            ),
+            (
+                test_frame(2, 2, ColorPixelFormat::Bgra8, &[0; 16]),
+                dir.join("c.bmp"),
+            ),
+        ];
+        let results = save_batch(frames);
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert!(dir.join("a.png").exists() && dir.join("c.bmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
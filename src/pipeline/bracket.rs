@@ -0,0 +1,162 @@
+use super::modes::STABLE_FRAME_TIMEOUT_ERR;
+use super::*;
+use crate::color::luminance;
+
+/// Largest exposure offset `capture_bracketed` accepts, in stops.
+const MAX_BRACKET_STOPS: f32 = 10.0;
+
+/// Every n-th row and column of the bracket source is sampled for its peak.
+const PEAK_SAMPLE_STEP: u32 = 4;
+
+/// Result of [`CapturePipeline::capture_bracketed`].
+#[derive(Clone)]
+pub struct BracketedCapture {
+    /// One SDR frame per requested stop, in order.
+    pub frames: Vec<CapturedFrame>,
+    /// Brightest pixel of the source frame in nits, sampled on every
+    /// 4th row and column (isolated highlights smaller than that can be
+    /// missed).
+    pub peak_nits: f32,
+    /// Stops of highlight above SDR white in the source frame,
+    /// `log2(peak_nits / sdr_white_nits)`, 0 when nothing exceeds it.
+    /// Stops below `-headroom_stops` only darken the frame further: no
+    /// highlight needs that much to come back into range.
+    pub headroom_stops: f32,
+}
+
+impl CapturePipeline {
+    /// Capture one HDR frame and tone-map it at several exposures.
+    ///
+    /// Returns one SDR frame per entry of `stops`, in order: each is the
+    /// same captured frame with its scRGB light scaled by `2^stop` before
+    /// the tone curve, relative to the exposure [`capture`](Self::capture)
+    /// uses (1.0, or the adapted one with auto-exposure). `[-2.0, 0.0, 2.0]`
+    /// gives a classic three-shot bracket for exposure fusion. The source
+    /// frame's measured highlight headroom comes with the frames, so
+    /// callers can pick negative stops that actually recover detail.
+    ///
+    /// The frame is picked like `capture()` and copied once (plus one
+    /// subsampled readback to measure its peak); only the
+    /// tone-map and the later GPU stages (LUT, adjustments, scaling) run per
+    /// stop. Auto-exposure doesn't adapt to bracketed frames, and they don't
+    /// become the static-content fallback of `capture()` / `grab()`. With no
+    /// new frame (static content) the previous bracket's frame is reused.
+    ///
+    /// Needs `CapturePolicy::Auto` on an HDR target and the WGC backend.
+    pub fn capture_bracketed(&mut self, stops: &[f32]) -> Result<BracketedCapture> {
+        if stops.is_empty() {
+            bail!("capture_bracketed needs at least one exposure stop");
+        }
+        if let Some(stop) = stops
+            .iter()
+            .find(|s| !(s.is_finite() && s.abs() <= MAX_BRACKET_STOPS))
+        {
+            bail!(
+                "exposure stops must be within ±{}, got {}",
+                MAX_BRACKET_STOPS,
+                stop
+            );
+        }
+        if self.paused {
+            return Err(CaptureError::Paused.into());
+        }
+        if self.policy != CapturePolicy::Auto || self.tone_map_pass.is_none() {
+            bail!("capture_bracketed needs CapturePolicy::Auto on an HDR target");
+        }
+        if matches!(self.capture, CaptureSource::Duplication(_)) {
+            bail!("capture_bracketed requires the WGC backend");
+        }
+        self.refresh_display_state();
+        self.check_watchdog()?;
+
+        if let Some(raw) = self.next_shared_raw()? {
+            self.keep_bracket_source(raw)?;
+        } else if self.bracket_source.is_none() {
+            let frame = self.hard_wait_frame(FIRST_FRAME_TIMEOUT)?;
+            let raw = self
+                .resolve_frame_after_resize(frame, FIRST_FRAME_TIMEOUT, false)?
+                .ok_or_else(|| anyhow::anyhow!(STABLE_FRAME_TIMEOUT_ERR))?;
+            self.keep_bracket_source(raw)?;
+        }
+
+        let base = self.exposure();
+        self.pipelined_readback = false;
+        let frames = stops
+            .iter()
+            .map(|&stop| self.process_bracket(base * stop.exp2()))
+            .collect::<Result<Vec<_>>>();
+        if let Some(pass) = self.tone_map_pass.as_mut() {
+            pass.set_exposure_override(None)?;
+        }
+        let peak_nits = self.bracket_peak_nits;
+        Ok(BracketedCapture {
+            frames: frames?,
+            peak_nits,
+            headroom_stops: (peak_nits / self.sdr_white_nits).log2().max(0.0),
+        })
+    }
+
+    /// Copy `raw` into the private bracket source, reusing its texture when
+    /// size and format match: the frame pool recycles the capture surface.
+    fn keep_bracket_source(&mut self, raw: RawFrame) -> Result<()> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe { raw.texture.GetDesc(&mut desc) };
+        let reusable = self.bracket_source.take().filter(|source| {
+            let mut kept = D3D11_TEXTURE2D_DESC::default();
+            // SAFETY: as above.
+            unsafe { source.texture.GetDesc(&mut kept) };
+            (kept.Width, kept.Height, kept.Format) == (desc.Width, desc.Height, desc.Format)
+        });
+        let texture = match reusable {
+            Some(source) => source.texture,
+            None => {
+                let desc = D3D11_TEXTURE2D_DESC {
+                    Usage: D3D11_USAGE_DEFAULT,
+                    BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+                    CPUAccessFlags: 0,
+                    MiscFlags: 0,
+                    ..desc
+                };
+                // SAFETY: desc is a fully initialized copy of the source's.
+                unsafe {
+                    let mut tex = None;
+                    self.d3d_ctx
+                        .device
+                        .CreateTexture2D(&desc, None, Some(&mut tex))
+                        .context("CreateTexture2D for bracket source failed")?;
+                    tex.unwrap()
+                }
+            }
+        };
+        // SAFETY: both textures have the same size and format.
+        unsafe { self.d3d_ctx.context.CopyResource(&texture, &raw.texture) };
+        let (data, _, _) =
+            self.reader
+                .read_texture_subsampled(&texture, PEAK_SAMPLE_STEP, PEAK_SAMPLE_STEP)?;
+        self.bracket_peak_nits =
+            luminance::frame_stats(&data, raw.format, self.sdr_white_nits).max_nits;
+        self.bracket_source = Some(RawFrame { texture, ..raw });
+        Ok(())
+    }
+
+    /// Tone-map the bracket source at `exposure` and read it back.
+    fn process_bracket(&mut self, exposure: f32) -> Result<CapturedFrame> {
+        if let Some(pass) = self.tone_map_pass.as_mut() {
+            pass.set_exposure_override(Some(exposure))?;
+        }
+        let source = self
+            .bracket_source
+            .as_ref()
+            .expect("bracket source kept before processing");
+        let raw = RawFrame {
+            texture: source.texture.clone(),
+            ..*source
+        };
+        let processed = self.process_gpu(raw)?;
+        let (frame, data) = self
+            .read_back(processed)?
+            .expect("synchronous readback always returns the frame");
+        Ok(self.captured_frame(&frame, data, frame.width, frame.height, None))
+    }
+}
@@ -1,11 +1,9 @@
 use super::*;
-use crate::capture::WindowSelector;
-use windows::Win32::Foundation::HWND;
 
 impl CapturePipeline {
     pub(super) fn frame_bytes(width: u32, height: u32, format: ColorPixelFormat) -> usize {
         let bpp = match format {
-            ColorPixelFormat::Bgra8 => 4,
+            ColorPixelFormat::Bgra8 | ColorPixelFormat::Rgb10a2 => 4,
             ColorPixelFormat::Rgba16f => 8,
         };
         width as usize * height as usize * bpp
@@ -15,6 +13,7 @@ impl CapturePipeline {
         match format {
             DXGI_FORMAT_B8G8R8A8_UNORM => Ok(ColorPixelFormat::Bgra8),
             DXGI_FORMAT_R16G16B16A16_FLOAT => Ok(ColorPixelFormat::Rgba16f),
+            DXGI_FORMAT_R10G10B10A2_UNORM => Ok(ColorPixelFormat::Rgb10a2),
             _ => bail!("Unsupported DXGI_FORMAT for color pipeline: {:?}", format),
         }
     }
@@ -22,16 +21,32 @@ impl CapturePipeline {
     /// Create capture pipeline by monitor index
     ///
     /// Indices are ordered by system enumeration, not guaranteed that `0` is the primary monitor.
+    /// Thin wrapper over [`CapturePipelineBuilder`].
     pub fn monitor(index: usize, policy: CapturePolicy) -> Result<Self> {
-        enable_dpi_awareness();
-        let hmonitor = find_monitor(index)?;
-        let sdr_white_nits = white_level::query_sdr_white_level(hmonitor);
-        Self::new(
-            CaptureTarget::Monitor(hmonitor),
-            policy,
-            sdr_white_nits,
-            false,
-        )
+        CapturePipelineBuilder::new()
+            .monitor(index)
+            .policy(policy)
+            .build()
+    }
+
+    /// Create capture pipeline by monitor GDI device name (e.g. `\\.\DISPLAY1`).
+    ///
+    /// Device names are stable across enumeration order changes, so the same
+    /// physical display is captured regardless of hotplug / reboot ordering.
+    pub fn monitor_by_name(name: &str, policy: CapturePolicy) -> Result<Self> {
+        CapturePipelineBuilder::new()
+            .monitor_name(name)
+            .policy(policy)
+            .build()
+    }
+
+    /// Create capture pipeline for the monitor containing virtual-desktop
+    /// point `(x, y)`, e.g. `(1920, 0)` for the display right of a 1080p primary.
+    pub fn monitor_at(x: i32, y: i32, policy: CapturePolicy) -> Result<Self> {
+        CapturePipelineBuilder::new()
+            .monitor_at(x, y)
+            .policy(policy)
+            .build()
     }
 
     /// Create window capture pipeline by selector inputs.
@@ -41,6 +56,7 @@ impl CapturePipeline {
     /// `index` is the ranked window index within the selected candidate set.
     /// When `index` is `None`, the highest-ranked window is selected.
     /// `headless` controls whether to crop the title bar and borders (default: true).
+    /// Thin wrapper over [`CapturePipelineBuilder`].
     pub fn window(
         process: Option<&str>,
         pid: Option<u32>,
@@ -49,81 +65,187 @@ impl CapturePipeline {
         policy: CapturePolicy,
         headless: bool,
     ) -> Result<Self> {
-        enable_dpi_awareness();
-        let selector = if let Some(raw_hwnd) = hwnd {
-            WindowSelector::Hwnd(HWND(raw_hwnd as *mut core::ffi::c_void))
+        let builder = CapturePipelineBuilder::new();
+        let builder = if let Some(raw_hwnd) = hwnd {
+            builder.window_hwnd(raw_hwnd)
         } else if let Some(pid) = pid {
-            WindowSelector::Pid(pid)
+            builder.window_pid(pid)
         } else if let Some(process) = process {
-            WindowSelector::Process(process.to_string())
+            builder.window_process(process)
         } else {
             bail!("window target requires one of: hwnd, pid, process");
         };
 
-        let hwnd = find_window(selector, index)?;
-        let hmonitor = unsafe {
-            windows::Win32::Graphics::Gdi::MonitorFromWindow(
-                hwnd,
-                windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST,
-            )
-        };
-        let sdr_white_nits = white_level::query_sdr_white_level(hmonitor);
-        Self::new(
-            CaptureTarget::Window(hwnd),
-            policy,
-            sdr_white_nits,
-            headless,
-        )
+        builder
+            .window_index(index)
+            .policy(policy)
+            .headless(headless)
+            .build()
     }
 
-    fn new(
+    pub(super) fn new(
+        com: Option<ComGuard>,
+        d3d_ctx: D3D11Context,
         target: CaptureTarget,
-        policy: CapturePolicy,
         sdr_white_nits: f32,
-        headless: bool,
+        display_profile: Option<ColorProfile>,
+        mut config: PipelineConfig,
     ) -> Result<Self> {
-        let d3d_ctx = create_d3d11_device()?;
-        let capture = init_capture(&d3d_ctx, target, policy)?;
+        let capture = CaptureSource::open(&d3d_ctx, target, &config)?;
         let target_hdr = capture.is_hdr();
-        capture.start()?;
+        let hdr_source = config.hdr_surfaces(target_hdr);
+        // Settle PreferHdr now that the frame pool format is known; session
+        // restarts reuse the result, so the frame pool format stays fixed.
+        let policy = config.policy.resolve(hdr_source);
+        config.policy = policy;
+        let display_format = target_display_format(&d3d_ctx, target).unwrap_or_default();
+        if target_hdr
+            && config.sdr_white_nits.is_none()
+            && sdr_white_nits == white_level::DEFAULT_SDR_WHITE_NITS
+        {
+            log::warn!(
+                "SDR white level query returned the {} nit default on an HDR monitor; \
+                 tone-mapped captures may look too bright or washed out. Set it with \
+                 sdr_white_nits() if Windows reports a different SDR content brightness",
+                white_level::DEFAULT_SDR_WHITE_NITS
+            );
+        }
         // Create reader after start() to let DWM start preparing first frame as early as possible
         let mut reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+        let strategy = config
+            .readback_strategy
+            .unwrap_or_else(|| ReadbackStrategy::detect(&d3d_ctx.device));
+        reader.set_strategy(strategy)?;
+        log::debug!("readback strategy: {:?}", strategy);
 
-        // Pre-create Staging Texture to avoid ~11ms creation overhead on first frame readback.
-        // Hdr mode outputs R16G16B16A16_FLOAT (8 bpp); Auto/Sdr output BGRA8 (4 bpp).
-        let (w, h) = capture.pool_size();
-        let (staging_format, bpp) = if policy == CapturePolicy::Hdr {
-            (DXGI_FORMAT_R16G16B16A16_FLOAT, 8)
+        // Pre-create the readback texture to avoid ~11ms creation overhead on first frame readback.
+        // Hdr mode outputs R16G16B16A16_FLOAT (8 bpp) or, with hdr10 or a forced
+        // format, R10G10B10A2 (4 bpp); Auto/Sdr output BGRA8 (4 bpp).
+        let (pool_w, pool_h) = capture.size();
+        let (w, h) = config.transform.output_size(pool_w, pool_h);
+        let (w, h) = config.output_size(w, h);
+        let output_format = if policy == CapturePolicy::Hdr {
+            config.hdr_format()
         } else {
-            (DXGI_FORMAT_B8G8R8A8_UNORM, 4)
+            ColorPixelFormat::Bgra8
+        };
+        let (staging_format, bpp) = match output_format {
+            ColorPixelFormat::Bgra8 => (DXGI_FORMAT_B8G8R8A8_UNORM, 4),
+            ColorPixelFormat::Rgba16f => (DXGI_FORMAT_R16G16B16A16_FLOAT, 8),
+            ColorPixelFormat::Rgb10a2 => (DXGI_FORMAT_R10G10B10A2_UNORM, 4),
         };
-        reader.ensure_staging_texture(w, h, staging_format)?;
+        reader.prepare(w, h, staging_format)?;
         let output_frame_bytes = w as usize * h as usize * bpp;
         let output_pool = ElasticBufferPool::new(output_frame_bytes);
 
-        // Create tone-map pass only for Auto (may need HDR->SDR conversion)
-        let tone_map_pass = if policy == CapturePolicy::Auto {
-            Some(ToneMapPass::new(&d3d_ctx.device, &d3d_ctx.context)?)
+        // Create tone-map pass only for Auto on an HDR frame pool; a BGRA8 frame
+        // pool never needs it.
+        let tone_map_pass = if policy == CapturePolicy::Auto && hdr_source {
+            let pass = ToneMapPass::new(&d3d_ctx.device, &d3d_ctx.context)?;
+            Some(tonemap::configure_auto_tone_map(pass, &config)?)
+        } else {
+            None
+        };
+        // Sdr only meets HDR surfaces when the format is forced: clip them
+        // like a runtime switch to Sdr does.
+        let clip_pass = if policy == CapturePolicy::Sdr && hdr_source {
+            let pass = ToneMapPass::with_operator(
+                &d3d_ctx.device,
+                &d3d_ctx.context,
+                ToneMapOperator::Clip,
+            )?;
+            Some(tonemap::configure_tone_map(pass, &config)?)
+        } else {
+            None
+        };
+
+        let lut_pass = match &config.lut {
+            Some(path) => {
+                let lut = CubeLut::from_file(path)?;
+                Some(LutPass::new(&d3d_ctx.device, &d3d_ctx.context, &lut)?)
+            }
+            None => None,
+        };
+
+        let sdr_adjust_pass = if config.sdr_adjust.is_identity() {
+            None
+        } else {
+            Some(SdrAdjustPass::new(&d3d_ctx.device, &d3d_ctx.context)?)
+        };
+
+        let display_orientation = query_display_orientation(target, &config);
+        let auto_rotate = config.auto_rotate && matches!(target, CaptureTarget::Monitor(_));
+        let orient_pass = if !config.transform.is_identity() || auto_rotate {
+            Some(OrientPass::new(&d3d_ctx.device, &d3d_ctx.context)?)
+        } else {
+            None
+        };
+
+        let scale_pass = if config.scales() {
+            Some(ScalePass::new(&d3d_ctx.device, &d3d_ctx.context)?)
+        } else {
+            None
+        };
+
+        // Only Hdr produces RGBA16F output; scRGB is what WGC delivers already.
+        let color_space_pass = if policy == CapturePolicy::Hdr
+            && config.color_space != OutputColorSpace::ScrgbLinear
+        {
+            Some(ColorSpacePass::new(
+                &d3d_ctx.device,
+                &d3d_ctx.context,
+                config.color_space,
+            )?)
         } else {
             None
         };
 
         Ok(Self {
-            _d3d_ctx: d3d_ctx,
+            d3d_ctx,
             policy,
             capture,
+            target,
             reader,
+            in_flight: VecDeque::new(),
+            next_slot: 0,
+            pipelined_readback: false,
             output_pool,
             output_frame_bytes,
             first_call: true,
             cached_frame: None,
             tone_map_pass,
+            hdr_source,
+            clip_pass,
             sdr_white_nits,
+            white_level_checked: Instant::now(),
+            display_profile,
             target_hdr,
-            headless,
+            display_format,
+            display_generation: display_generation(),
+            headless: config.headless,
             crop_texture: None,
             force_fresh: false,
+            lut_pass,
+            sdr_adjust_pass,
+            orient_pass,
+            display_orientation,
+            scale_pass,
+            color_space_pass,
+            change_detector: None,
+            nv12_pass: None,
+            shared_cache: None,
+            bracket_source: None,
+            bracket_peak_nits: 0.0,
+            watchdog: config.watchdog.map(Watchdog::new),
+            black_frames: 0,
+            shared_memory: None,
+            pending_warning: None,
+            paused: false,
+            exclusion: CaptureExclusion::default(),
+            config,
+            stats: Cell::new(CaptureStats::default()),
             _not_send_sync: PhantomData,
+            _com: com,
         })
     }
 }
@@ -0,0 +1,865 @@
+use std::path::PathBuf;
+
+use super::*;
+use crate::capture::wgc::MAX_FRAME_BUFFERS;
+use crate::capture::{is_child_window, WindowSelector};
+use crate::d3d11::create_d3d11_device_on_adapter;
+use windows::Win32::Foundation::HWND;
+
+/// Deepest supported readback pipeline (see [`CapturePipelineBuilder::readback_depth`]).
+const MAX_READBACK_DEPTH: usize = 3;
+
+/// Target recorded by the builder, resolved in `build()`.
+#[derive(Debug, Clone)]
+enum TargetSpec {
+    Monitor(usize),
+    MonitorNumber(usize),
+    MonitorName(String),
+    MonitorAt(i32, i32),
+    WindowProcess(String),
+    WindowPid(u32),
+    WindowHwnd(isize),
+    WindowAppId(String),
+}
+
+/// Non-target pipeline configuration, consumed by `CapturePipeline::new`.
+#[derive(Debug, Clone)]
+pub(super) struct PipelineConfig {
+    pub(super) policy: CapturePolicy,
+    /// Capture API used to acquire frames.
+    pub(super) backend: CaptureBackend,
+    pub(super) headless: bool,
+    pub(super) session: SessionOptions,
+    /// Integer downscale divisor applied on the GPU (1 = native size).
+    pub(super) downscale: u32,
+    /// Fixed output size `(width, height, fit)`; overrides `downscale`.
+    pub(super) resize: Option<(u32, u32, ScaleFit)>,
+    /// Largest output `(width, height)`; bigger frames are scaled down to fit.
+    pub(super) max_resolution: Option<(u32, u32)>,
+    /// DXGI adapter index (`None` = system default hardware adapter).
+    pub(super) adapter: Option<usize>,
+    /// `.cube` LUT applied to SDR output.
+    pub(super) lut: Option<PathBuf>,
+    /// Brightness / contrast / gamma applied to SDR output.
+    pub(super) sdr_adjust: SdrAdjust,
+    /// Client-relative sub-rect `(x, y, width, height)` for window capture.
+    pub(super) crop: Option<(u32, u32, u32, u32)>,
+    /// Behavior while the target window is minimized.
+    pub(super) minimized: MinimizedPolicy,
+    /// Behavior while the target is not in the foreground.
+    pub(super) foreground: ForegroundPolicy,
+    /// Skip the first frame after a frame pool recreate (resize).
+    pub(super) drop_stale_after_resize: bool,
+    /// Keep the captured alpha channel (layered / transparent windows).
+    pub(super) preserve_alpha: bool,
+    /// Color encoding of RGBA16F output.
+    pub(super) color_space: OutputColorSpace,
+    /// Manual SDR white level (nits); `None` queries the Windows setting.
+    pub(super) sdr_white_nits: Option<f32>,
+    /// Pipeline lives for exactly one frame (see [`grab_once`](super::grab_once)).
+    pub(super) single_shot: bool,
+    /// Restart the WGC session after this long without a frame.
+    pub(super) watchdog: Option<Duration>,
+    /// Tag frames no brighter than this with `CaptureWarning::BlankFrame`.
+    pub(super) blank_threshold: Option<f32>,
+    /// Fail with `CaptureError::ProtectedContent` for protected windows.
+    pub(super) detect_protected: bool,
+    /// Frames in flight between GPU copy and CPU read (1 = synchronous).
+    pub(super) readback_depth: usize,
+    /// Full-frame readback path; `None` picks one for the GPU.
+    pub(super) readback_strategy: Option<ReadbackStrategy>,
+    /// Rotation / flip applied on the GPU before scaling.
+    pub(super) transform: FrameTransform,
+    /// Undo the target monitor's display rotation (monitor targets only).
+    pub(super) auto_rotate: bool,
+    /// Fixed thread group size of the tone-map shader (`None` = 8×8).
+    pub(super) tonemap_thread_group: Option<ThreadGroupSize>,
+    /// Benchmark tone-map thread group sizes on the first HDR frame.
+    pub(super) tonemap_auto_tune: bool,
+    /// Per-frame tone-map exposure from a luminance histogram.
+    pub(super) auto_exposure: Option<AutoExposure>,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            policy: CapturePolicy::Auto,
+            backend: CaptureBackend::Auto,
+            headless: true,
+            session: SessionOptions::default(),
+            downscale: 1,
+            resize: None,
+            max_resolution: None,
+            adapter: None,
+            lut: None,
+            sdr_adjust: SdrAdjust::default(),
+            crop: None,
+            minimized: MinimizedPolicy::default(),
+            foreground: ForegroundPolicy::default(),
+            drop_stale_after_resize: true,
+            preserve_alpha: false,
+            color_space: OutputColorSpace::ScrgbLinear,
+            sdr_white_nits: None,
+            single_shot: false,
+            watchdog: None,
+            blank_threshold: None,
+            detect_protected: false,
+            readback_depth: 1,
+            readback_strategy: None,
+            transform: FrameTransform::default(),
+            auto_rotate: false,
+            tonemap_thread_group: None,
+            tonemap_auto_tune: false,
+            auto_exposure: None,
+        }
+    }
+}
+
+impl PipelineConfig {
+    /// Output frame size for a given source size after GPU scaling.
+    pub(super) fn output_size(&self, width: u32, height: u32) -> (u32, u32) {
+        if let Some((out_w, out_h, _)) = self.resize {
+            return (out_w, out_h);
+        }
+        let (width, height) = if self.downscale <= 1 {
+            (width, height)
+        } else {
+            (
+                (width / self.downscale).max(1),
+                (height / self.downscale).max(1),
+            )
+        };
+        match self.max_resolution {
+            Some((max_w, max_h)) => fit_within(width, height, max_w, max_h),
+            None => (width, height),
+        }
+    }
+
+    /// Whether frames may need the GPU scale pass.
+    pub(super) fn scales(&self) -> bool {
+        self.downscale > 1 || self.resize.is_some() || self.max_resolution.is_some()
+    }
+
+    /// Whether the frame pool holds HDR surfaces on a target with the given
+    /// HDR state: the forced format if any, else what the policy picks.
+    pub(super) fn hdr_surfaces(&self, target_hdr: bool) -> bool {
+        match self.session.force_format {
+            Some(format) => format != ColorPixelFormat::Bgra8,
+            None => match self.policy.resolve(target_hdr) {
+                CapturePolicy::Hdr => true,
+                CapturePolicy::Auto => target_hdr,
+                CapturePolicy::Sdr | CapturePolicy::PreferHdr => false,
+            },
+        }
+    }
+
+    /// Format of HDR surfaces, which `CapturePolicy::Hdr` passes through.
+    pub(super) fn hdr_format(&self) -> ColorPixelFormat {
+        match self.session.force_format {
+            Some(format) => format,
+            None if self.session.hdr10 => ColorPixelFormat::Rgb10a2,
+            None => ColorPixelFormat::Rgba16f,
+        }
+    }
+}
+
+/// `width` × `height` scaled down to fit `max_width` × `max_height` with its
+/// aspect ratio kept; unchanged when it already fits.
+fn fit_within(width: u32, height: u32, max_width: u32, max_height: u32) -> (u32, u32) {
+    if width <= max_width && height <= max_height {
+        return (width, height);
+    }
+    // Compare aspect ratios in u64 to avoid float rounding at exact fits.
+    let (w, h, mw, mh) = (
+        width as u64,
+        height as u64,
+        max_width as u64,
+        max_height as u64,
+    );
+    if w * mh > h * mw {
+        // Width-limited.
+        (max_width, ((h * mw + w / 2) / w).max(1) as u32)
+    } else {
+        (((w * mh + h / 2) / h).max(1) as u32, max_height)
+    }
+}
+
+/// Builder for [`CapturePipeline`].
+///
+/// Replaces the positional constructors as the home for all pipeline options.
+/// Exactly one target must be selected; the last target setter wins.
+///
+/// # Examples
+/// ```no_run
+/// # use hdrcapture::pipeline::{CapturePipelineBuilder, CapturePolicy};
+/// let mut pipeline = CapturePipelineBuilder::new()
+///     .window_process("notepad.exe")
+///     .policy(CapturePolicy::Auto)
+///     .headless(true)
+///     .cursor(false)
+///     .build()
+///     .unwrap();
+/// let frame = pipeline.capture().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CapturePipelineBuilder {
+    target: Option<TargetSpec>,
+    window_index: Option<usize>,
+    config: PipelineConfig,
+}
+
+impl CapturePipelineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture a monitor by enumeration index.
+    ///
+    /// Enumeration order need not match the numbers Windows shows; see
+    /// [`monitor_number`](Self::monitor_number).
+    pub fn monitor(mut self, index: usize) -> Self {
+        self.target = Some(TargetSpec::Monitor(index));
+        self
+    }
+
+    /// Capture a monitor by the number Windows shows for it (1-based, as in
+    /// Settings → Display → Identify); see
+    /// [`find_monitor_by_os_number`](crate::capture::find_monitor_by_os_number).
+    pub fn monitor_number(mut self, number: usize) -> Self {
+        self.target = Some(TargetSpec::MonitorNumber(number));
+        self
+    }
+
+    /// Capture a monitor by GDI device name (e.g. `\\.\DISPLAY1`).
+    pub fn monitor_name(mut self, name: impl Into<String>) -> Self {
+        self.target = Some(TargetSpec::MonitorName(name.into()));
+        self
+    }
+
+    /// Capture the monitor containing virtual-desktop point `(x, y)`.
+    ///
+    /// Suits fixed installations that know their display arrangement; see
+    /// [`find_monitor_at`](crate::capture::find_monitor_at).
+    pub fn monitor_at(mut self, x: i32, y: i32) -> Self {
+        self.target = Some(TargetSpec::MonitorAt(x, y));
+        self
+    }
+
+    /// Capture the highest-ranked window of a process (by executable name).
+    pub fn window_process(mut self, process: impl Into<String>) -> Self {
+        self.target = Some(TargetSpec::WindowProcess(process.into()));
+        self
+    }
+
+    /// Capture the highest-ranked window of a process (by pid).
+    pub fn window_pid(mut self, pid: u32) -> Self {
+        self.target = Some(TargetSpec::WindowPid(pid));
+        self
+    }
+
+    /// Capture a window by raw handle.
+    ///
+    /// Child windows (controls, embedded panels) are captured through their
+    /// top-level root and always cropped to the child's client area.
+    pub fn window_hwnd(mut self, hwnd: isize) -> Self {
+        self.target = Some(TargetSpec::WindowHwnd(hwnd));
+        self
+    }
+
+    /// Capture the highest-ranked window of a packaged (UWP / Store) app by
+    /// Application User Model ID.
+    ///
+    /// Finds the `ApplicationFrameHost` frame the app is shown in, which
+    /// selecting the app's own process can't: its top-level window is not the
+    /// visual on screen. The frame's title bar is part of the capture.
+    pub fn window_app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.target = Some(TargetSpec::WindowAppId(app_id.into()));
+        self
+    }
+
+    /// Capture a window by unified selector.
+    pub fn window(self, selector: WindowSelector) -> Self {
+        match selector {
+            WindowSelector::Hwnd(hwnd) => self.window_hwnd(hwnd.0 as isize),
+            WindowSelector::Pid(pid) => self.window_pid(pid),
+            WindowSelector::Process(process) => self.window_process(process),
+            WindowSelector::AppUserModelId(app_id) => self.window_app_id(app_id),
+        }
+    }
+
+    /// Ranked window index within the candidate set (process / pid / app id targets).
+    pub fn window_index(mut self, index: Option<usize>) -> Self {
+        self.window_index = index;
+        self
+    }
+
+    /// Capture policy (default: `Auto`).
+    ///
+    /// Also picks the frame pool format; see
+    /// [`CapturePipeline::set_policy`] for switching later.
+    pub fn policy(mut self, policy: CapturePolicy) -> Self {
+        self.config.policy = policy;
+        self
+    }
+
+    /// Capture API (default: `Auto` = WGC, falling back to Desktop
+    /// Duplication for monitors when WGC cannot start).
+    ///
+    /// See [`CaptureBackend`] for the differences between the two.
+    pub fn backend(mut self, backend: CaptureBackend) -> Self {
+        self.config.backend = backend;
+        self
+    }
+
+    /// Crop title bar and borders in window capture (default: true).
+    ///
+    /// Can be toggled later with [`CapturePipeline::set_headless`].
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.config.headless = headless;
+        self
+    }
+
+    /// Include the mouse cursor (default: true).
+    pub fn cursor(mut self, cursor: bool) -> Self {
+        self.config.session.cursor = cursor;
+        self
+    }
+
+    /// Capture the window together with its owned popups such as menus and
+    /// tooltips (default: false).
+    ///
+    /// Window capture only; needs Windows 11 and is ignored on older builds.
+    pub fn include_popups(mut self, include_popups: bool) -> Self {
+        self.config.session.include_popups = include_popups;
+        self
+    }
+
+    /// Keep the yellow capture border visible (default: false).
+    pub fn show_border(mut self, show_border: bool) -> Self {
+        self.config.session.show_border = show_border;
+        self
+    }
+
+    /// Number of buffers in the WGC frame pool (default: 2, range 1..=8).
+    ///
+    /// With 2 buffers, DWM drops frames whenever the consumer falls behind by
+    /// more than one frame. A deeper pool tolerates bursts and slow consumers
+    /// with zero drops, but each buffer costs one frame of VRAM and `grab()`
+    /// may have to drain a longer backlog. Streaming/recording use cases that
+    /// value completeness over latency benefit most.
+    pub fn buffer_count(mut self, count: u32) -> Self {
+        self.config.session.buffer_count = count;
+        self
+    }
+
+    /// Capture HDR as 10-bit HDR10 instead of RGBA16F scRGB (default: false).
+    ///
+    /// The frame pool delivers `R10G10B10A2` surfaces (BT.2020 primaries, PQ),
+    /// half the size of RGBA16F: `CapturePolicy::Hdr` frames are
+    /// `ColorPixelFormat::Rgb10a2`, and `Auto` tone-maps from the 10-bit
+    /// surface. `output_color_space` does not apply to HDR10 frames. WGC
+    /// backend only; Desktop Duplication keeps RGBA16F.
+    pub fn hdr10(mut self, hdr10: bool) -> Self {
+        self.config.session.hdr10 = hdr10;
+        self
+    }
+
+    /// Capture into `format` surfaces regardless of the policy and the
+    /// target's HDR state (default: chosen by the policy).
+    ///
+    /// The policy still decides what happens to the pixels, only the frame
+    /// pool format is pinned:
+    /// - `Hdr` passes the surfaces through, so `Rgba16f` gives scRGB frames
+    ///   even on an SDR monitor (SDR content in 0..1, no headroom), without
+    ///   the mismatch warnings. Fails with `Bgra8`.
+    /// - `Auto` tone-maps HDR surfaces to BGRA8 as on an HDR monitor.
+    /// - `Sdr` clips HDR surfaces at SDR white.
+    /// - `PreferHdr` becomes `Hdr` for HDR formats and `Sdr` for `Bgra8`.
+    ///
+    /// Overrides [`hdr10`](Self::hdr10). WGC backend only: Desktop
+    /// Duplication picks its own formats, so `CaptureBackend::Auto` does not
+    /// fall back to it.
+    pub fn force_format(mut self, format: ColorPixelFormat) -> Self {
+        self.config.session.force_format = Some(format);
+        self
+    }
+
+    /// Downscale output by an integer divisor on the GPU (default: 1 = native).
+    ///
+    /// `2` halves both dimensions. Bilinear filtering.
+    pub fn downscale(mut self, factor: u32) -> Self {
+        self.config.downscale = factor;
+        self
+    }
+
+    /// Scale every frame to a fixed `width` × `height` on the GPU.
+    ///
+    /// Output size stays constant when the window resizes, for consumers that
+    /// need a fixed buffer (model input, video encoder). `fit` chooses between
+    /// stretching and letterboxing. Takes precedence over `downscale` and
+    /// `max_resolution`.
+    pub fn resize_output(mut self, width: u32, height: u32, fit: ScaleFit) -> Self {
+        self.config.resize = Some((width, height, fit));
+        self
+    }
+
+    /// Cap the output size at `width` × `height`, scaling larger frames down
+    /// on the GPU.
+    ///
+    /// Unlike `downscale`, only frames that exceed the cap are scaled, to the
+    /// largest size that fits with the aspect ratio kept: a 4K display capped
+    /// at 1920 × 1080 streams at 1080p, a 1080p one is left alone. Applies
+    /// after `downscale`; `resize_output` takes precedence.
+    pub fn max_resolution(mut self, width: u32, height: u32) -> Self {
+        self.config.max_resolution = Some((width, height));
+        self
+    }
+
+    /// Create the D3D11 device on a specific DXGI adapter index.
+    ///
+    /// HDR detection only sees outputs attached to the chosen adapter; on a
+    /// render-only GPU the target is reported as SDR.
+    pub fn adapter(mut self, index: usize) -> Self {
+        self.config.adapter = Some(index);
+        self
+    }
+
+    /// Apply a 3D `.cube` LUT to SDR output on the GPU.
+    ///
+    /// Runs after HDR→SDR conversion; RGBA16F output (`CapturePolicy::Hdr`) is
+    /// not graded. The file is parsed in `build()`.
+    pub fn lut(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.lut = Some(path.into());
+        self
+    }
+
+    /// Brightness / contrast / gamma tweak of SDR output on the GPU.
+    ///
+    /// Runs after HDR→SDR conversion and the LUT; RGBA16F output
+    /// (`CapturePolicy::Hdr`) is not adjusted. The identity (default) skips
+    /// the pass. Can also be changed later with
+    /// [`CapturePipeline::set_sdr_adjust`].
+    pub fn sdr_adjust(mut self, adjust: SdrAdjust) -> Self {
+        self.config.sdr_adjust = adjust;
+        self
+    }
+
+    /// Capture only a sub-rect of the window's client area.
+    ///
+    /// `x`/`y` are relative to the client area's top-left corner, so the region
+    /// follows the window when it moves. The rect is clamped to the client
+    /// bounds every frame. Implies client-area cropping regardless of
+    /// `headless`; window targets only. For frames whose client area can't be
+    /// determined, the rect is taken relative to the whole captured surface.
+    pub fn crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.config.crop = Some((x, y, width, height));
+        self
+    }
+
+    /// Behavior while the target window is minimized (default: `ReturnCached`).
+    pub fn minimized(mut self, policy: MinimizedPolicy) -> Self {
+        self.config.minimized = policy;
+        self
+    }
+
+    /// Only capture while the target is in the foreground (default: `Ignore`).
+    ///
+    /// For automation that should only record its own app while it is in
+    /// use: with `Error` or `Wait`, `capture()` / `grab()` check the focused
+    /// window first and fail with [`CaptureError::NotForeground`].
+    ///
+    /// This gates on focus, not on visibility. Window captures never show
+    /// overlapping windows, so a background window captures the same as a
+    /// focused one. Monitor targets count as foreground while the focused
+    /// window is on that monitor; always-on-top windows can still cover it.
+    pub fn require_foreground(mut self, policy: ForegroundPolicy) -> Self {
+        self.config.foreground = policy;
+        self
+    }
+
+    /// Skip the first frame after the target is resized (default: true).
+    ///
+    /// After a resize the frame pool is recreated, and its first frame may
+    /// still show the old content stretched to the new size. Dropping it costs
+    /// about one VSync per resize; disable for the lowest latency when resizes
+    /// are rare and an occasional stale frame is acceptable. WGC backend only.
+    pub fn drop_stale_after_resize(mut self, drop: bool) -> Self {
+        self.config.drop_stale_after_resize = drop;
+        self
+    }
+
+    /// Keep the captured alpha channel (default: false).
+    ///
+    /// For layered / transparent windows (`WS_EX_LAYERED`). Frames are tagged
+    /// `AlphaMode::Premultiplied` because WGC delivers DWM's premultiplied
+    /// surface; use `CapturedFrame::unpremultiply` before saving to PNG / TIFF.
+    /// Without it, alpha is treated as meaningless and saved opaque.
+    pub fn preserve_alpha(mut self, preserve: bool) -> Self {
+        self.config.preserve_alpha = preserve;
+        self
+    }
+
+    /// Color encoding of RGBA16F output (default: `ScrgbLinear`).
+    ///
+    /// Only applies with `CapturePolicy::Hdr`; BGRA8 output is always sRGB.
+    /// Conversion runs in a GPU pass after scaling. See [`OutputColorSpace`].
+    pub fn output_color_space(mut self, color_space: OutputColorSpace) -> Self {
+        self.config.color_space = color_space;
+        self
+    }
+
+    /// SDR white level (nits) used to tone-map HDR content, instead of the
+    /// Windows "SDR content brightness" setting.
+    ///
+    /// For systems where the query misreports (returns the 80-nit default
+    /// and HDR captures look washed out). Only affects `CapturePolicy::Auto`
+    /// on HDR targets. Can also be changed later with
+    /// [`CapturePipeline::set_sdr_white_nits`].
+    pub fn sdr_white_nits(mut self, nits: f32) -> Self {
+        self.config.sdr_white_nits = Some(nits);
+        self
+    }
+
+    /// Restart the capture session when a frame it owes doesn't arrive within
+    /// `timeout` (default: off).
+    ///
+    /// WGC occasionally stops delivering frames after display changes, and
+    /// `grab()` then returns the cached frame forever. A frame is owed after
+    /// a display change (DWM redraws) or a wait for one that timed out; with
+    /// a watchdog, a `capture()` / `grab()` call that finds such a frame
+    /// missing for `timeout` recreates the session in place, and the next
+    /// frame carries `CaptureWarning::SessionRestarted`. Static content owes
+    /// no frames and never triggers a restart. WGC backend only; minimized
+    /// windows don't count as starvation.
+    pub fn watchdog(mut self, timeout: Duration) -> Self {
+        self.config.watchdog = Some(timeout);
+        self
+    }
+
+    /// Tag effectively black frames with `CaptureWarning::BlankFrame`
+    /// (default: off).
+    ///
+    /// Protected (DRM / HDCP) windows such as video players capture as solid
+    /// black instead of failing. With detection on, every new frame for
+    /// which [`CapturedFrame::is_blank(threshold)`](CapturedFrame::is_blank)
+    /// holds carries the warning, so callers can skip saving it. Costs a CPU
+    /// scan that stops at the first bright pixel.
+    pub fn blank_detection(mut self, threshold: f32) -> Self {
+        self.config.blank_threshold = Some(threshold);
+        self
+    }
+
+    /// Fail with [`CaptureError::ProtectedContent`] instead of returning black
+    /// frames of protected window content (default: false).
+    ///
+    /// A window that opted out of capture (`SetWindowDisplayAffinity`) fails
+    /// on the first frame. DRM / HDCP video gives no signal besides black
+    /// output, so a visible, non-minimized window of at least 64×64 pixels
+    /// that delivers 5 new, exactly black frames in a row is reported too;
+    /// single-frame captures only catch the first case. Fades, loading
+    /// screens and black video trip that heuristic as well, so only enable
+    /// it for windows that are never legitimately all black; otherwise
+    /// prefer [`blank_detection`](Self::blank_detection), which only tags
+    /// frames. Ignored for monitors.
+    pub fn detect_protected(mut self, enabled: bool) -> Self {
+        self.config.detect_protected = enabled;
+        self
+    }
+
+    /// Number of frames in flight in the GPU→CPU readback (default: 1, range
+    /// 1..=3).
+    ///
+    /// With 1, every frame is copied to a staging texture and read at once,
+    /// so the CPU waits for the GPU copy. With 2 (double-buffered) or 3
+    /// (triple), `grab()` queues the copy of the new frame and reads the one
+    /// queued `depth - 1` calls earlier, which is usually finished: higher
+    /// throughput, at the cost of `depth - 1` frames of latency and one
+    /// staging texture per slot. After the pipeline starts or a `capture()`
+    /// call, `grab()` repeats the previous frame until the ring is full
+    /// again; when static content brings no new frame, it returns the
+    /// queued frames oldest first instead. `capture()` is unaffected and
+    /// always returns its own fresh frame. `grab_changed()` reads new frames
+    /// back directly, since the checksum that decides whether a frame changed
+    /// must belong to the frame it returns; queued frames left by earlier
+    /// `grab()` calls are still drained and reported as changed.
+    pub fn readback_depth(mut self, depth: usize) -> Self {
+        self.config.readback_depth = depth;
+        self
+    }
+
+    /// How frames are copied to the CPU (default: detected per GPU).
+    ///
+    /// By default integrated (UMA) GPUs that can map default textures use
+    /// `MappableDirect` and all others `StagingCopy`; see
+    /// [`ReadbackStrategy::detect`]. Forcing `MappableDirect` on a GPU without
+    /// support fails in `build()`. Only synchronous readback is affected;
+    /// `readback_depth` > 1 always uses staging textures.
+    ///
+    /// Earlier versions used `StagingCopy` on every GPU, so integrated GPUs
+    /// now take a different readback path by default. Pass `StagingCopy` to
+    /// keep the old behavior.
+    pub fn readback_strategy(mut self, strategy: ReadbackStrategy) -> Self {
+        self.config.readback_strategy = Some(strategy);
+        self
+    }
+
+    /// Rotate and / or flip every frame on the GPU (default: identity).
+    ///
+    /// Applied after tone mapping and LUT grading, before `downscale` /
+    /// `resize`, so a `resize` size refers to the transformed frame.
+    pub fn transform(mut self, transform: FrameTransform) -> Self {
+        self.config.transform = transform;
+        self
+    }
+
+    /// Undo the target monitor's display rotation (default: false).
+    ///
+    /// Frames that arrive in the panel's native orientation (e.g. a landscape
+    /// panel set to portrait) are rotated upright, before any `transform`.
+    /// Frames WGC already delivers in desktop orientation, detected by a
+    /// frame pool the size of the desktop, are left alone. The rotation is
+    /// re-read after display changes. Ignored for window targets.
+    pub fn auto_rotate(mut self, enable: bool) -> Self {
+        self.config.auto_rotate = enable;
+        self
+    }
+
+    /// Thread group size of the HDR→SDR tone-map compute shader (default: 8×8).
+    ///
+    /// Larger groups (16×16, 32×8) are faster on some GPUs for large HDR
+    /// frames. Takes precedence over [`tonemap_auto_tune`](Self::tonemap_auto_tune).
+    pub fn tonemap_thread_group(mut self, size: ThreadGroupSize) -> Self {
+        self.config.tonemap_thread_group = Some(size);
+        self
+    }
+
+    /// Pick the tone-map thread group size by benchmarking (default: false).
+    ///
+    /// The first tone-mapped frame compiles the shader for up to four sizes
+    /// and times each on the GPU, keeping the fastest. The compilations
+    /// dominate: that one frame can take tens or hundreds of milliseconds
+    /// longer. The result is cached per GPU for the process, so later
+    /// pipelines compile the winner once and skip the benchmark.
+    pub fn tonemap_auto_tune(mut self, enable: bool) -> Self {
+        self.config.tonemap_auto_tune = enable;
+        self
+    }
+
+    /// Adjust tone-map exposure to each frame's content (default: false).
+    ///
+    /// See [`CapturePipeline::set_auto_exposure`].
+    pub fn auto_exposure(mut self, enable: bool) -> Self {
+        self.config.auto_exposure = enable.then(AutoExposure::default);
+        self
+    }
+
+    /// Tune the pipeline for a single frame followed by teardown.
+    pub(crate) fn single_shot(mut self) -> Self {
+        self.config.single_shot = true;
+        self
+    }
+
+    /// Resolve the target and create the pipeline.
+    pub fn build(self) -> Result<CapturePipeline> {
+        if self.config.downscale == 0 {
+            bail!("downscale factor must be >= 1");
+        }
+        if matches!(self.config.resize, Some((0, _, _) | (_, 0, _))) {
+            bail!("output width and height must be >= 1");
+        }
+        if matches!(self.config.max_resolution, Some((0, _) | (_, 0))) {
+            bail!("maximum resolution width and height must be >= 1");
+        }
+        if !(1..=MAX_FRAME_BUFFERS).contains(&self.config.session.buffer_count) {
+            bail!(
+                "buffer count must be between 1 and {}, got {}",
+                MAX_FRAME_BUFFERS,
+                self.config.session.buffer_count
+            );
+        }
+        if let Some(format) = self.config.session.force_format {
+            if format == ColorPixelFormat::Bgra8 && self.config.policy == CapturePolicy::Hdr {
+                bail!(
+                    "CapturePolicy::Hdr needs an HDR capture format, got {:?}",
+                    format
+                );
+            }
+            if self.config.backend == CaptureBackend::DesktopDuplication {
+                bail!("a forced capture format is only supported by the WGC backend");
+            }
+        }
+        if matches!(self.config.crop, Some((_, _, 0, _) | (_, _, _, 0))) {
+            bail!("crop width and height must be >= 1");
+        }
+        if let Some(nits) = self.config.sdr_white_nits {
+            validate_sdr_white_nits(nits)?;
+        }
+        self.config.sdr_adjust.validate()?;
+        if let Some(size) = self.config.tonemap_thread_group {
+            size.validate()?;
+        }
+        if !(1..=MAX_READBACK_DEPTH).contains(&self.config.readback_depth) {
+            bail!(
+                "readback depth must be between 1 and {}, got {}",
+                MAX_READBACK_DEPTH,
+                self.config.readback_depth
+            );
+        }
+        if let Some(threshold) = self.config.blank_threshold {
+            if !(threshold.is_finite() && threshold >= 0.0) {
+                bail!("blank threshold must be a finite value >= 0, got {threshold}");
+            }
+        }
+
+        enable_dpi_awareness();
+        let target = self
+            .target
+            .context("no capture target set; call monitor() or window_*() first")?;
+
+        let capture_target = match target {
+            TargetSpec::Monitor(index) => CaptureTarget::Monitor(find_monitor(index)?),
+            TargetSpec::MonitorNumber(number) => {
+                CaptureTarget::Monitor(find_monitor_by_os_number(number)?)
+            }
+            TargetSpec::MonitorName(name) => CaptureTarget::Monitor(find_monitor_by_name(&name)?),
+            TargetSpec::MonitorAt(x, y) => CaptureTarget::Monitor(find_monitor_at(x, y)?),
+            TargetSpec::WindowProcess(process) => {
+                resolve_window(WindowSelector::Process(process), self.window_index)?
+            }
+            TargetSpec::WindowPid(pid) => {
+                resolve_window(WindowSelector::Pid(pid), self.window_index)?
+            }
+            TargetSpec::WindowHwnd(raw_hwnd) => resolve_window(
+                WindowSelector::Hwnd(HWND(raw_hwnd as *mut core::ffi::c_void)),
+                self.window_index,
+            )?,
+            TargetSpec::WindowAppId(app_id) => {
+                resolve_window(WindowSelector::AppUserModelId(app_id), self.window_index)?
+            }
+        };
+
+        let mut config = self.config;
+        if let CaptureTarget::Window(hwnd) = capture_target {
+            if config.backend == CaptureBackend::DesktopDuplication {
+                bail!("Desktop Duplication only supports monitor capture");
+            }
+            // A child is only a region of its root's surface; always crop to it.
+            if is_child_window(hwnd) {
+                config.headless = true;
+            }
+        }
+        if config.single_shot {
+            // One frame is read while the surface is still held; a second
+            // buffer would only cost VRAM and allocation time.
+            config.session.buffer_count = 1;
+        }
+        if matches!(capture_target, CaptureTarget::Monitor(_)) {
+            // Monitor capture has no title bar to crop.
+            config.headless = false;
+            if config.crop.is_some() {
+                bail!("crop is only supported for window capture");
+            }
+        }
+
+        // Before the device: its WinRT wrapper already needs COM.
+        let com = ComGuard::ensure().context("COM initialization failed")?;
+        let d3d_ctx = match config.adapter {
+            Some(index) => create_d3d11_device_on_adapter(index)?,
+            None => create_d3d11_device()?,
+        };
+        let (hmonitor, mixed_hdr) = hdr_reference_monitor(&d3d_ctx, capture_target);
+        if mixed_hdr {
+            log::warn!(
+                "target window spans HDR and SDR monitors; capturing with the HDR monitor's \
+                 format and SDR white level, so the part on the SDR monitor may look different"
+            );
+        }
+        let sdr_white_nits = config
+            .sdr_white_nits
+            .unwrap_or_else(|| white_level::query_sdr_white_level(hmonitor));
+        let display_profile = icc::query_display_profile(hmonitor);
+        CapturePipeline::new(
+            com,
+            d3d_ctx,
+            capture_target,
+            sdr_white_nits,
+            display_profile,
+            config,
+        )
+    }
+}
+
+/// Reject white levels the tone-map shader can't divide by.
+pub(super) fn validate_sdr_white_nits(nits: f32) -> Result<()> {
+    if !(nits.is_finite() && nits > 0.0) {
+        bail!(
+            "SDR white level must be a positive number of nits, got {}",
+            nits
+        );
+    }
+    Ok(())
+}
+
+fn resolve_window(selector: WindowSelector, index: Option<usize>) -> Result<CaptureTarget> {
+    Ok(CaptureTarget::Window(find_window(selector, index)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_resolution_caps_large_sources_only() {
+        let config = PipelineConfig {
+            max_resolution: Some((1920, 1080)),
+            ..Default::default()
+        };
+        // 4K → 1080p; a source within the cap is left alone.
+        assert_eq!(config.output_size(3840, 2160), (1920, 1080));
+        assert_eq!(config.output_size(1280, 720), (1280, 720));
+        // Aspect ratio kept: ultrawide is width-limited, portrait height-limited.
+        assert_eq!(config.output_size(5120, 1440), (1920, 540));
+        assert_eq!(config.output_size(2160, 3840), (608, 1080));
+
+        // Applied after downscale; resize wins.
+        let config = PipelineConfig {
+            downscale: 2,
+            ..config
+        };
+        assert_eq!(config.output_size(3840, 2160), (1920, 1080));
+        assert_eq!(config.output_size(7680, 4320), (1920, 1080));
+        let config = PipelineConfig {
+            resize: Some((2560, 1440, ScaleFit::Stretch)),
+            ..config
+        };
+        assert_eq!(config.output_size(3840, 2160), (2560, 1440));
+    }
+
+    #[test]
+    fn test_forced_format_overrides_policy_surfaces() {
+        let config = |policy, force_format| PipelineConfig {
+            policy,
+            session: SessionOptions {
+                hdr10: true,
+                force_format,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // Policy-derived: follows the target's HDR state.
+        assert!(!config(CapturePolicy::Auto, None).hdr_surfaces(false));
+        assert!(config(CapturePolicy::PreferHdr, None).hdr_surfaces(true));
+        assert!(!config(CapturePolicy::Sdr, None).hdr_surfaces(true));
+        assert_eq!(
+            config(CapturePolicy::Hdr, None).hdr_format(),
+            ColorPixelFormat::Rgb10a2
+        );
+
+        // Forced: fp16 on an SDR target, BGRA8 on an HDR one; wins over hdr10.
+        let fp16 = config(CapturePolicy::Auto, Some(ColorPixelFormat::Rgba16f));
+        assert!(fp16.hdr_surfaces(false));
+        assert_eq!(fp16.hdr_format(), ColorPixelFormat::Rgba16f);
+        let bgra = config(CapturePolicy::PreferHdr, Some(ColorPixelFormat::Bgra8));
+        assert!(!bgra.hdr_surfaces(true));
+    }
+}
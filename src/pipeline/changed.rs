@@ -0,0 +1,102 @@
+use super::*;
+use crate::d3d11::checksum::ChecksumPass;
+
+/// GPU checksum state behind [`CapturePipeline::grab_changed`].
+pub(super) struct ChangeDetector {
+    pass: ChecksumPass,
+    /// `(checksum, width, height)` of the last frame processed by `grab_changed()`.
+    last: Option<(u64, u32, u32)>,
+    /// Set while `grab_changed()` runs.
+    active: bool,
+    /// Whether the current `grab_changed()` call read back a new frame.
+    changed: bool,
+}
+
+impl ChangeDetector {
+    fn new(d3d_ctx: &D3D11Context) -> Result<Self> {
+        Ok(Self {
+            pass: ChecksumPass::new(&d3d_ctx.device, &d3d_ctx.context)?,
+            last: None,
+            active: false,
+            changed: false,
+        })
+    }
+
+    /// Checksum `raw` and report whether it matches the previous frame.
+    ///
+    /// Frames processed outside `grab_changed()` are not checksummed; they reset
+    /// the comparison so the next `grab_changed()` always reports a change.
+    pub(super) fn is_unchanged(&mut self, raw: &RawFrame) -> Result<bool> {
+        if !self.active {
+            self.last = None;
+            return Ok(false);
+        }
+        let key = (self.pass.execute(&raw.texture)?, raw.width, raw.height);
+        let unchanged = self.last == Some(key);
+        self.last = Some(key);
+        self.changed = !unchanged;
+        Ok(unchanged)
+    }
+
+    /// Report a frame drained from the readback ring as new. It was queued by
+    /// an earlier `grab()` and never checksummed, but the caller hasn't seen it.
+    pub(super) fn mark_drained(&mut self) {
+        if self.active {
+            self.changed = true;
+        }
+    }
+}
+
+impl CapturePipeline {
+    /// Streaming mode with change detection: `None` when nothing changed.
+    ///
+    /// Like [`grab`](Self::grab), but a GPU pass first computes a 64-bit
+    /// checksum of the frame and reads back only those 8 bytes. When it matches
+    /// the previous `grab_changed()` frame, the full readback is skipped and
+    /// `None` is returned; a static screen with no new frames also returns
+    /// `None`. For mostly-static targets this removes nearly all readback cost.
+    ///
+    /// The checksum covers the captured surface, cursor included when enabled.
+    /// Calling `capture()` / `grab()` in between, or changing what frames are
+    /// processed into (policy, LUT, tone-map, output size), makes the next
+    /// processed frame report a change. New frames are read back directly,
+    /// without the `readback_depth` ring, so the reported change always
+    /// belongs to the returned frame.
+    pub fn grab_changed(&mut self) -> Result<Option<CapturedFrame>> {
+        if self.change_detector.is_none() {
+            self.change_detector = Some(ChangeDetector::new(&self.d3d_ctx)?);
+        }
+        if let Some(detector) = self.change_detector.as_mut() {
+            detector.active = true;
+            detector.changed = false;
+        }
+
+        let result = self.grab();
+
+        let detector = self
+            .change_detector
+            .as_mut()
+            .expect("change detector created above");
+        detector.active = false;
+        let frame = result?;
+        Ok(detector.changed.then_some(frame))
+    }
+
+    /// Make the next `grab_changed()` frame report a change: output settings
+    /// changed, so an unchanged surface no longer means an unchanged result.
+    pub(super) fn invalidate_change_detection(&mut self) {
+        if let Some(detector) = self.change_detector.as_mut() {
+            detector.last = None;
+        }
+    }
+
+    /// Whether a `grab_changed()` call is running.
+    pub(super) fn detecting_changes(&self) -> bool {
+        self.change_detector.as_ref().is_some_and(|d| d.active)
+    }
+
+    /// Last processed frame, if any.
+    pub(super) fn last_frame(&self) -> Option<CapturedFrame> {
+        self.cached_frame.clone()
+    }
+}
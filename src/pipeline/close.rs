@@ -0,0 +1,36 @@
+use super::*;
+
+impl CapturePipeline {
+    /// Stop capturing and release the pipeline's OS resources.
+    ///
+    /// Equivalent to dropping the pipeline, for call sites that want the
+    /// teardown spelled out (the Python `close()` runs the same path). In
+    /// order: the shared memory segment from
+    /// [`publish_to_shared_memory`](Self::publish_to_shared_memory) is
+    /// unmapped and its handle closed, the capture session and frame pool are
+    /// closed (or the Desktop Duplication image released), queued GPU work is
+    /// flushed so the textures go with the device, windows hidden with
+    /// [`exclude_windows`](Self::exclude_windows) get their display affinity
+    /// back, and COM is released on the creating thread. The pipeline starts
+    /// no threads of its own; [`ReplayBuffer`](crate::ReplayBuffer) and
+    /// hotkey handles stop and join theirs when dropped.
+    pub fn close(self) {
+        drop(self);
+    }
+}
+
+impl Drop for CapturePipeline {
+    fn drop(&mut self) {
+        // Readers of the segment see it go away before the source stops.
+        self.shared_memory = None;
+        self.in_flight.clear();
+        self.capture.close();
+        // SAFETY: the immediate context belongs to this thread; unbinding and
+        // submitting pending work lets the driver free resources released
+        // with the remaining fields instead of at some later flush.
+        unsafe {
+            self.d3d_ctx.context.ClearState();
+            self.d3d_ctx.context.Flush();
+        }
+    }
+}
@@ -1,6 +1,78 @@
 use super::*;
 
+/// Offset a client-relative `(x, y, width, height)` rect into `client_box`,
+/// clamped so the result stays inside the client area and is at least 1x1.
+pub(super) fn sub_box(client_box: D3D11_BOX, crop: (u32, u32, u32, u32)) -> D3D11_BOX {
+    let (x, y, width, height) = crop;
+    let client_w = client_box.right - client_box.left;
+    let client_h = client_box.bottom - client_box.top;
+
+    let left = client_box.left + x.min(client_w.saturating_sub(1));
+    let top = client_box.top + y.min(client_h.saturating_sub(1));
+    D3D11_BOX {
+        left,
+        top,
+        front: 0,
+        right: left
+            .saturating_add(width.max(1))
+            .min(client_box.right)
+            .max(left + 1),
+        bottom: top
+            .saturating_add(height.max(1))
+            .min(client_box.bottom)
+            .max(top + 1),
+        back: 1,
+    }
+}
+
+/// The whole content of `frame`'s surface, for cropping when the client area
+/// is unknown.
+pub(super) fn content_box(
+    frame: &windows::Graphics::Capture::Direct3D11CaptureFrame,
+) -> Result<D3D11_BOX> {
+    let size = frame.ContentSize()?;
+    Ok(D3D11_BOX {
+        left: 0,
+        top: 0,
+        front: 0,
+        right: size.Width.max(1) as u32,
+        bottom: size.Height.max(1) as u32,
+        back: 1,
+    })
+}
+
 impl CapturePipeline {
+    /// Whether window frames are cropped to the client area.
+    pub fn headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Switch between client-area and full-window frames without
+    /// restarting the capture session.
+    ///
+    /// Applies from the next frame read from the session; while the window
+    /// is static, the cached frame keeps its old framing until it redraws.
+    /// Ignored for monitor targets ([`headless`](Self::headless) keeps its
+    /// value). With `crop` configured the setting is recorded but frames
+    /// don't change, since the crop rect is always client-relative. Turning
+    /// it off for a child window returns an error: its frames are a region
+    /// of the root window's surface.
+    /// See [`CapturePipelineBuilder::headless`].
+    pub fn set_headless(&mut self, headless: bool) -> Result<()> {
+        let CaptureTarget::Window(hwnd) = self.target else {
+            return Ok(());
+        };
+        if !headless && is_child_window(hwnd) {
+            bail!("headless can't be disabled for child windows");
+        }
+        if headless != self.headless {
+            self.headless = headless;
+            // The client box changes the crop size; rebuild on the next frame.
+            self.crop_texture = None;
+        }
+        Ok(())
+    }
+
     /// Ensure a crop texture exists with the given dimensions and format.
     /// Reuses the cached texture if dimensions and format match.
     fn ensure_crop_texture(
@@ -34,7 +106,7 @@ impl CapturePipeline {
         // SAFETY: desc is fully initialized; CreateTexture2D allocates a GPU resource.
         let texture = unsafe {
             let mut tex = None;
-            self._d3d_ctx
+            self.d3d_ctx
                 .device
                 .CreateTexture2D(&desc, None, Some(&mut tex))
                 .context("Failed to create crop texture")?;
@@ -53,8 +125,9 @@ impl CapturePipeline {
 
     /// Extract texture and metadata from WGC frame.
     ///
-    /// For window capture, crops to client area (removes title bar and borders)
-    /// using `CopySubresourceRegion` on the GPU.
+    /// For window capture, crops to client area (removes title bar and borders),
+    /// or to a sub-rect of it when `crop` is configured, using
+    /// `CopySubresourceRegion` on the GPU.
     /// `client_box` is pre-computed from `window_geometry()` to avoid redundant Win32 queries.
     pub(super) fn read_raw_frame(
         &mut self,
@@ -82,7 +155,7 @@ impl CapturePipeline {
             // SAFETY: Both textures are valid D3D11 resources with compatible formats.
             // CopySubresourceRegion copies the client_box region from source to (0,0) of dest.
             unsafe {
-                self._d3d_ctx.context.CopySubresourceRegion(
+                self.d3d_ctx.context.CopySubresourceRegion(
                     &cropped,
                     0,
                     0,
@@ -112,3 +185,35 @@ impl CapturePipeline {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(left: u32, top: u32, right: u32, bottom: u32) -> D3D11_BOX {
+        D3D11_BOX {
+            left,
+            top,
+            front: 0,
+            right,
+            bottom,
+            back: 1,
+        }
+    }
+
+    #[test]
+    fn test_sub_box_offsets_within_client() {
+        let b = sub_box(client(8, 31, 808, 631), (100, 50, 200, 150));
+        assert_eq!((b.left, b.top, b.right, b.bottom), (108, 81, 308, 231));
+    }
+
+    #[test]
+    fn test_sub_box_clamps_to_client_bounds() {
+        let b = sub_box(client(8, 31, 808, 631), (700, 500, 400, 400));
+        assert_eq!((b.left, b.top, b.right, b.bottom), (708, 531, 808, 631));
+
+        // Origin past the client area still yields a 1x1 box inside it.
+        let b = sub_box(client(8, 31, 808, 631), (5000, 5000, 10, 10));
+        assert_eq!((b.left, b.top, b.right, b.bottom), (807, 630, 808, 631));
+    }
+}
@@ -0,0 +1,375 @@
+use super::*;
+use crate::capture::target::monitor_layout;
+use windows::Win32::Foundation::{HWND, RECT};
+
+/// One monitor of a virtual-desktop capture.
+struct DesktopTile {
+    pipeline: CapturePipeline,
+    /// Top-left offset inside the composite (pixels).
+    x: u32,
+    y: u32,
+}
+
+/// Stitched capture of every monitor into one frame.
+///
+/// Each monitor runs its own [`CapturePipeline`]; frames are composited on the
+/// CPU at their virtual-desktop positions. Areas not covered by any monitor
+/// (non-rectangular layouts) are filled with transparent black.
+///
+/// All tiles share one policy so the composite has a uniform pixel format:
+/// - `Auto`: HDR monitors are tone-mapped to SDR, output is BGRA8.
+/// - `Sdr`: every monitor is captured as BGRA8.
+/// - `Hdr`: every monitor is captured as RGBA16F, including SDR monitors.
+/// - `PreferHdr`: `Hdr` when any monitor has HDR enabled, `Sdr` otherwise.
+///
+/// Tone-mapping uses each monitor's own SDR white level, so brightness can
+/// differ across the seam between an HDR and an SDR display.
+///
+/// Cost: each monitor is read back on its own, then every composite clears and
+/// fills the full frame on the CPU, on top of the readbacks (two 4K monitors
+/// side by side in RGBA16F make a 133 MB composite). Monitors can sit on different
+/// adapters, each tile owning its own D3D11 device, so there is no single
+/// device to stitch them on the GPU. Capture a single monitor when throughput
+/// matters.
+pub struct DesktopPipeline {
+    tiles: Vec<DesktopTile>,
+    width: u32,
+    height: u32,
+    output_pool: Arc<ElasticBufferPool>,
+    output_frame_bytes: usize,
+    /// Own windows hidden from the capture (see `exclude_windows`).
+    exclusion: CaptureExclusion,
+}
+
+impl CapturePipeline {
+    /// Create a pipeline that captures the whole virtual desktop as one frame.
+    ///
+    /// See [`DesktopPipeline`] for how mixed HDR/SDR setups are handled.
+    pub fn virtual_desktop(policy: CapturePolicy) -> Result<DesktopPipeline> {
+        DesktopPipeline::new(policy)
+    }
+}
+
+impl DesktopPipeline {
+    fn new(policy: CapturePolicy) -> Result<Self> {
+        enable_dpi_awareness();
+        let layout = monitor_layout()?;
+        let bounds = union_rect(layout.iter().map(|(_, rect)| *rect));
+        let width = (bounds.right - bounds.left).max(1) as u32;
+        let height = (bounds.bottom - bounds.top).max(1) as u32;
+
+        let tile_pipeline = |index: usize, policy: CapturePolicy| {
+            CapturePipelineBuilder::new()
+                .monitor(index)
+                .policy(policy)
+                .build()
+                .with_context(|| format!("failed to start capture on monitor {}", index))
+        };
+        let mut tiles = Vec::with_capacity(layout.len());
+        for (index, (_, rect)) in layout.iter().enumerate() {
+            tiles.push(DesktopTile {
+                pipeline: tile_pipeline(index, policy)?,
+                x: (rect.left - bounds.left) as u32,
+                y: (rect.top - bounds.top) as u32,
+            });
+        }
+
+        // PreferHdr resolves per monitor; with mixed HDR / SDR monitors,
+        // restart the SDR ones as Hdr so the composite keeps one format.
+        let policy = if policy == CapturePolicy::PreferHdr {
+            let hdr = tiles
+                .iter()
+                .any(|t| t.pipeline.policy() == CapturePolicy::Hdr);
+            if hdr {
+                for (index, tile) in tiles.iter_mut().enumerate() {
+                    if tile.pipeline.policy() != CapturePolicy::Hdr {
+                        tile.pipeline = tile_pipeline(index, CapturePolicy::Hdr)?;
+                    }
+                }
+            }
+            policy.resolve(hdr)
+        } else {
+            policy
+        };
+
+        let bpp = if policy == CapturePolicy::Hdr { 8 } else { 4 };
+        let output_frame_bytes = width as usize * height as usize * bpp;
+
+        Ok(Self {
+            tiles,
+            width,
+            height,
+            output_pool: ElasticBufferPool::new(output_frame_bytes),
+            output_frame_bytes,
+            exclusion: CaptureExclusion::default(),
+        })
+    }
+
+    /// Get the next frame from every monitor in `mode` and stitch them.
+    ///
+    /// See [`CapturePipeline::next_frame`].
+    pub fn next_frame(&mut self, mode: FrameMode) -> Result<CapturedFrame> {
+        let frames = self
+            .tiles
+            .iter_mut()
+            .map(|tile| tile.pipeline.next_frame(mode))
+            .collect::<Result<Vec<_>>>()?;
+        self.composite(&frames)
+    }
+
+    /// Capture a fresh frame from every monitor and stitch them.
+    pub fn capture(&mut self) -> Result<CapturedFrame> {
+        self.next_frame(FrameMode::Fresh)
+    }
+
+    /// Grab the latest frame from every monitor and stitch them.
+    pub fn grab(&mut self) -> Result<CapturedFrame> {
+        self.next_frame(FrameMode::Latest)
+    }
+
+    /// Grab with change detection; `None` when no monitor changed.
+    ///
+    /// See [`CapturePipeline::grab_changed`]. Unchanged monitors reuse their
+    /// previous frame in the composite.
+    pub fn grab_changed(&mut self) -> Result<Option<CapturedFrame>> {
+        let mut any_changed = false;
+        let mut frames = Vec::with_capacity(self.tiles.len());
+        for tile in &mut self.tiles {
+            match tile.pipeline.grab_changed()? {
+                Some(frame) => {
+                    any_changed = true;
+                    frames.push(frame);
+                }
+                None => frames.push(
+                    tile.pipeline
+                        .last_frame()
+                        .context("unchanged monitor has no previous frame")?,
+                ),
+            }
+        }
+        if !any_changed {
+            return Ok(None);
+        }
+        self.composite(&frames).map(Some)
+    }
+
+    /// Non-blocking grab; `None` when no monitor has a new frame.
+    ///
+    /// See [`CapturePipeline::try_grab`]. Monitors without a new frame reuse
+    /// their previous one in the composite; `None` as well until every
+    /// monitor has delivered a first frame.
+    pub fn try_grab(&mut self) -> Result<Option<CapturedFrame>> {
+        let mut any_new = false;
+        let mut frames = Vec::with_capacity(self.tiles.len());
+        for tile in &mut self.tiles {
+            match tile.pipeline.try_grab()? {
+                Some(frame) => {
+                    any_new = true;
+                    frames.push(frame);
+                }
+                None => match tile.pipeline.last_frame() {
+                    Some(frame) => frames.push(frame),
+                    None => return Ok(None),
+                },
+            }
+        }
+        if !any_new {
+            return Ok(None);
+        }
+        self.composite(&frames).map(Some)
+    }
+
+    /// Number of monitors in the composite.
+    pub fn monitor_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Whether any monitor has HDR enabled.
+    pub fn is_hdr(&self) -> bool {
+        self.tiles.iter().any(|tile| tile.pipeline.is_hdr())
+    }
+
+    /// Lowest refresh rate across monitors, which bounds the composite cadence.
+    pub fn refresh_rate_hz(&self) -> Option<f64> {
+        self.tiles
+            .iter()
+            .filter_map(|tile| tile.pipeline.refresh_rate_hz())
+            .reduce(f64::min)
+    }
+
+    /// Lowest measured frame rate across monitors.
+    pub fn measured_fps(&self) -> Option<f64> {
+        self.tiles
+            .iter()
+            .filter_map(|tile| tile.pipeline.measured_fps())
+            .reduce(f64::min)
+    }
+
+    /// Pause every monitor's session. See [`CapturePipeline::pause`].
+    ///
+    /// All or nothing: when one session fails to pause, the ones this call
+    /// paused are resumed before the error is returned.
+    pub fn pause(&mut self) -> Result<()> {
+        let mut paused = Vec::with_capacity(self.tiles.len());
+        for i in 0..self.tiles.len() {
+            let pipeline = &mut self.tiles[i].pipeline;
+            if pipeline.is_paused() {
+                continue;
+            }
+            if let Err(e) = pipeline.pause() {
+                for j in paused {
+                    if let Err(e) = self.tiles[j].pipeline.resume() {
+                        log::warn!("failed to resume monitor after a failed pause: {e:#}");
+                    }
+                }
+                return Err(e);
+            }
+            paused.push(i);
+        }
+        Ok(())
+    }
+
+    /// Resume every monitor's session. See [`CapturePipeline::resume`].
+    pub fn resume(&mut self) -> Result<()> {
+        self.tiles
+            .iter_mut()
+            .try_for_each(|tile| tile.pipeline.resume())
+    }
+
+    /// Hide windows of this process from every monitor.
+    ///
+    /// See [`CapturePipeline::exclude_windows`].
+    pub fn exclude_windows(&mut self, hwnds: &[HWND]) -> Result<()> {
+        self.exclusion.set(hwnds)
+    }
+
+    /// Whether [`pause`](Self::pause) is in effect.
+    pub fn is_paused(&self) -> bool {
+        self.tiles.iter().any(|tile| tile.pipeline.is_paused())
+    }
+
+    /// Put `frame` on the Windows clipboard, tone-mapping HDR frames on the
+    /// first monitor's device. See [`CapturePipeline::copy_to_clipboard`].
+    pub fn copy_to_clipboard(&self, frame: &CapturedFrame) -> Result<()> {
+        let ctx = self.tiles.first().map(|tile| &tile.pipeline.d3d_ctx);
+        frame.copy_to_clipboard_on(ctx)
+    }
+
+    /// Capture health counters summed over all monitors.
+    pub fn capture_stats(&self) -> CaptureStats {
+        self.tiles
+            .iter()
+            .map(|tile| tile.pipeline.capture_stats())
+            .fold(CaptureStats::default(), CaptureStats::merge)
+    }
+
+    fn composite(&mut self, frames: &[CapturedFrame]) -> Result<CapturedFrame> {
+        let format = frames
+            .first()
+            .map(|f| f.format)
+            .context("virtual desktop has no monitors")?;
+        if frames.iter().any(|f| f.format != format) {
+            bail!("virtual desktop tiles returned mixed pixel formats");
+        }
+
+        let bpp = CapturePipeline::frame_bytes(1, 1, format);
+        let required_len = CapturePipeline::frame_bytes(self.width, self.height, format);
+        if required_len > self.output_frame_bytes {
+            self.output_frame_bytes = required_len;
+            self.output_pool.resize_frame_bytes(required_len);
+        }
+
+        let mut pooled = self.output_pool.acquire();
+        let dst = &mut pooled.as_mut_slice()[..required_len];
+        // Recycled buffers hold a previous composite; clear uncovered areas.
+        dst.fill(0);
+
+        let dst_stride = self.width as usize * bpp;
+        for (tile, frame) in self.tiles.iter().zip(frames) {
+            // Clip to composite bounds in case the monitor resized since init.
+            let copy_w = frame.width.min(self.width.saturating_sub(tile.x)) as usize;
+            let copy_h = frame.height.min(self.height.saturating_sub(tile.y)) as usize;
+            let src_stride = frame.width as usize * bpp;
+            let src = frame.data.as_slice();
+            for row in 0..copy_h {
+                let src_off = row * src_stride;
+                let dst_off = (tile.y as usize + row) * dst_stride + tile.x as usize * bpp;
+                dst[dst_off..dst_off + copy_w * bpp]
+                    .copy_from_slice(&src[src_off..src_off + copy_w * bpp]);
+            }
+        }
+
+        let timestamp = frames.iter().map(|f| f.timestamp).fold(0.0, f64::max);
+        let (mut dst_vec, group_idx, pool) = pooled.into_parts();
+        dst_vec.truncate(required_len);
+
+        Ok(CapturedFrame {
+            data: Arc::new(SharedFrameData {
+                bytes: dst_vec,
+                pool,
+                group_idx,
+            }),
+            width: self.width,
+            height: self.height,
+            timestamp,
+            format,
+            // Mixed displays have no single native profile.
+            color_profile: ColorProfile::Srgb,
+            // Tiles are built with the default output color space.
+            color_space: OutputColorSpace::ScrgbLinear,
+            // Shared display format, or unknown when monitors differ.
+            source_color_space: uniform(frames, |f| f.source_color_space).unwrap_or_default(),
+            bits_per_channel: uniform(frames, |f| f.bits_per_channel).unwrap_or(0),
+            // Brightest white across monitors, so stats only count true highlights.
+            sdr_white_nits: frames.iter().map(|f| f.sdr_white_nits).fold(0.0, f32::max),
+            // Shared scale, or 1.0 when monitors use different scales.
+            dpi_scale: uniform(frames, |f| f.dpi_scale).unwrap_or(1.0),
+            warning: None,
+            alpha: AlphaMode::Ignore,
+        })
+    }
+}
+
+/// The value `key` yields for every frame, if they all agree.
+fn uniform<T: PartialEq>(frames: &[CapturedFrame], key: impl Fn(&CapturedFrame) -> T) -> Option<T> {
+    let mut values = frames.iter().map(key);
+    let first = values.next()?;
+    values.all(|v| v == first).then_some(first)
+}
+
+/// Bounding box of all monitor rects.
+fn union_rect(rects: impl Iterator<Item = RECT>) -> RECT {
+    rects
+        .reduce(|a, b| RECT {
+            left: a.left.min(b.left),
+            top: a.top.min(b.top),
+            right: a.right.max(b.right),
+            bottom: a.bottom.max(b.bottom),
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> RECT {
+        RECT {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn test_union_rect_side_by_side_with_negative_origin() {
+        let bounds = union_rect([rect(0, 0, 2560, 1440), rect(-1920, 200, 0, 1280)].into_iter());
+        assert_eq!(bounds, rect(-1920, 0, 2560, 1440));
+    }
+
+    #[test]
+    fn test_union_rect_empty() {
+        assert_eq!(union_rect(std::iter::empty()), RECT::default());
+    }
+}
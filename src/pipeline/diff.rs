@@ -0,0 +1,215 @@
+use super::*;
+use half::f16;
+
+use crate::color::hdr10;
+
+/// Color of changed pixels in a diff image (BGRA: opaque magenta).
+const DIFF_HIGHLIGHT: [u8; 4] = [255, 0, 255, 255];
+
+/// Unchanged pixels are drawn as gray at this fraction of their brightness,
+/// so changes stand out while the layout stays recognizable.
+const DIFF_BACKGROUND_SCALE: f32 = 0.3;
+
+/// Result of comparing two frames with [`CapturedFrame::diff`].
+#[derive(Clone)]
+pub struct DiffResult {
+    /// Number of pixels with any channel difference.
+    pub num_different: usize,
+    /// Largest per-channel difference: 8-bit steps (0–255) for BGRA8,
+    /// scRGB units for RGBA16F, 10-bit PQ code steps (0–1023) for RGB10A2.
+    pub max_delta: f32,
+    /// BGRA8 image of the frame size with changed pixels in magenta over a
+    /// dimmed grayscale copy of `self`. `None` when the frames are identical.
+    pub diff_image: Option<CapturedFrame>,
+}
+
+impl CapturedFrame {
+    /// Compare with another frame pixel by pixel, e.g. for visual-regression tests.
+    ///
+    /// Both frames must have the same size and pixel format. Color channels
+    /// are compared exactly; alpha only when either frame's alpha is
+    /// meaningful (not `AlphaMode::Ignore`).
+    pub fn diff(&self, other: &CapturedFrame) -> Result<DiffResult> {
+        if (self.width, self.height) != (other.width, other.height) {
+            bail!(
+                "can't diff frames of different sizes: {}x{} vs {}x{}",
+                self.width,
+                self.height,
+                other.width,
+                other.height
+            );
+        }
+        if self.format != other.format {
+            bail!(
+                "can't diff frames of different formats: {:?} vs {:?}",
+                self.format,
+                other.format
+            );
+        }
+
+        let channels = if self.alpha == AlphaMode::Ignore && other.alpha == AlphaMode::Ignore {
+            3
+        } else {
+            4
+        };
+        let pixels = self.width as usize * self.height as usize;
+        let mut changed = vec![false; pixels];
+        let mut num_different = 0;
+        let mut max_delta = 0.0f32;
+        let (a, b) = (self.data.as_slice(), other.data.as_slice());
+        let bpp = self.bytes_per_pixel();
+        for (i, (pa, pb)) in a.chunks_exact(bpp).zip(b.chunks_exact(bpp)).enumerate() {
+            let delta = pixel_delta(pa, pb, self.format, channels);
+            if delta > 0.0 {
+                changed[i] = true;
+                num_different += 1;
+                max_delta = max_delta.max(delta);
+            }
+        }
+
+        let diff_image = if num_different > 0 {
+            Some(self.diff_image(&changed))
+        } else {
+            None
+        };
+        Ok(DiffResult {
+            num_different,
+            max_delta,
+            diff_image,
+        })
+    }
+
+    /// Render changed pixels in magenta over a dimmed grayscale copy of `self`.
+    fn diff_image(&self, changed: &[bool]) -> CapturedFrame {
+        let len = changed.len() * 4;
+        // Pool buffers fit the frame's own format, which is at least 4 bytes/pixel.
+        let mut pooled = self.data.pool.acquire();
+        let dst = &mut pooled.as_mut_slice()[..len];
+        let bpp = self.bytes_per_pixel();
+        for ((out, src), &is_changed) in dst
+            .chunks_exact_mut(4)
+            .zip(self.data.as_slice().chunks_exact(bpp))
+            .zip(changed)
+        {
+            if is_changed {
+                out.copy_from_slice(&DIFF_HIGHLIGHT);
+            } else {
+                let gray = (background_level(src, self.format) * DIFF_BACKGROUND_SCALE * 255.0)
+                    .round() as u8;
+                out.copy_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+
+        let (mut bytes, group_idx, pool) = pooled.into_parts();
+        bytes.truncate(len);
+        CapturedFrame {
+            data: Arc::new(SharedFrameData {
+                bytes,
+                pool,
+                group_idx,
+            }),
+            format: ColorPixelFormat::Bgra8,
+            color_profile: ColorProfile::Srgb,
+            color_space: OutputColorSpace::ScrgbLinear,
+            warning: None,
+            alpha: AlphaMode::Ignore,
+            ..self.clone()
+        }
+    }
+}
+
+/// Largest absolute difference over the first `channels` channels.
+fn pixel_delta(a: &[u8], b: &[u8], format: ColorPixelFormat, channels: usize) -> f32 {
+    match format {
+        ColorPixelFormat::Bgra8 => a[..channels]
+            .iter()
+            .zip(&b[..channels])
+            .map(|(&x, &y)| x.abs_diff(y) as f32)
+            .fold(0.0, f32::max),
+        ColorPixelFormat::Rgba16f => a[..channels * 2]
+            .chunks_exact(2)
+            .zip(b[..channels * 2].chunks_exact(2))
+            .map(|(x, y)| {
+                if x == y {
+                    return 0.0;
+                }
+                let x = f16::from_le_bytes([x[0], x[1]]).to_f32();
+                let y = f16::from_le_bytes([y[0], y[1]]).to_f32();
+                // Differing bits: +0 / -0 give no delta, NaN counts as maximal.
+                let d = (x - y).abs();
+                if d.is_nan() {
+                    f32::INFINITY
+                } else {
+                    d
+                }
+            })
+            .fold(0.0, f32::max),
+        ColorPixelFormat::Rgb10a2 => {
+            let (a, b) = (
+                hdr10::unpack([a[0], a[1], a[2], a[3]]),
+                hdr10::unpack([b[0], b[1], b[2], b[3]]),
+            );
+            a[..channels]
+                .iter()
+                .zip(&b[..channels])
+                .map(|(&x, &y)| x.abs_diff(y) as f32)
+                .fold(0.0, f32::max)
+        }
+    }
+}
+
+/// Average of the color channels in 0..=1 (HDR values clamped).
+fn background_level(px: &[u8], format: ColorPixelFormat) -> f32 {
+    match format {
+        ColorPixelFormat::Bgra8 => (px[0] as f32 + px[1] as f32 + px[2] as f32) / (3.0 * 255.0),
+        ColorPixelFormat::Rgba16f => {
+            let ch = |i: usize| f16::from_le_bytes([px[i], px[i + 1]]).to_f32();
+            ((ch(0) + ch(2) + ch(4)) / 3.0).clamp(0.0, 1.0)
+        }
+        // The PQ signal is already perceptually spaced.
+        ColorPixelFormat::Rgb10a2 => {
+            let [r, g, b, _] = hdr10::unpack([px[0], px[1], px[2], px[3]]);
+            (r as f32 + g as f32 + b as f32) / (3.0 * 1023.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bgra_frame(pixels: &[[u8; 4]]) -> CapturedFrame {
+        test_frame(
+            pixels.len() as u32,
+            1,
+            ColorPixelFormat::Bgra8,
+            &pixels.concat(),
+        )
+    }
+
+    #[test]
+    fn test_diff_counts_changed_pixels_and_highlights_them() {
+        let a = bgra_frame(&[[30, 30, 30, 255], [0, 0, 0, 0], [9, 9, 9, 9]]);
+        // Pixel 1 only differs in alpha, which is ignored.
+        let b = bgra_frame(&[[30, 30, 30, 255], [0, 0, 0, 7], [9, 49, 9, 9]]);
+
+        let result = a.diff(&b).unwrap();
+        assert_eq!(result.num_different, 1);
+        assert_eq!(result.max_delta, 40.0);
+        let image = result.diff_image.unwrap();
+        assert_eq!(image.format, ColorPixelFormat::Bgra8);
+        assert_eq!(&image.data.as_slice()[8..12], &DIFF_HIGHLIGHT);
+        assert_eq!(&image.data.as_slice()[..4], &[9, 9, 9, 255]);
+
+        let same = a.diff(&a.clone()).unwrap();
+        assert_eq!(same.num_different, 0);
+        assert!(same.diff_image.is_none());
+    }
+
+    #[test]
+    fn test_diff_rejects_mismatched_sizes() {
+        let a = bgra_frame(&[[0; 4], [0; 4]]);
+        let b = bgra_frame(&[[0; 4]]);
+        assert!(a.diff(&b).is_err());
+    }
+}
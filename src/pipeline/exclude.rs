@@ -0,0 +1,34 @@
+use super::*;
+use windows::Win32::Foundation::HWND;
+
+impl CapturePipeline {
+    /// Hide windows of this process (overlays, recording UI) from the
+    /// monitor capture.
+    ///
+    /// Replaces the previously excluded set; an empty slice shows every
+    /// window again. Uses `SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE)`,
+    /// which WGC has no per-session equivalent of, so:
+    /// - only windows owned by this process can be excluded; other handles
+    ///   fail the call without changing anything,
+    /// - while set, the windows are hidden from every screen capture, not
+    ///   only this pipeline's; their previous affinity is restored when they
+    ///   leave the set and when the pipeline is dropped,
+    /// - before Windows 10 2004 (see
+    ///   [`exclusion_supported`](crate::capture::exclusion_supported)) the
+    ///   windows are captured as black rectangles instead of the content
+    ///   behind them.
+    ///
+    /// Monitor capture only: in window capture the target itself would be
+    /// the window to hide.
+    pub fn exclude_windows(&mut self, hwnds: &[HWND]) -> Result<()> {
+        if !matches!(self.target, CaptureTarget::Monitor(_)) {
+            bail!("exclude_windows() requires a monitor capture target");
+        }
+        self.exclusion.set(hwnds)
+    }
+
+    /// Windows currently hidden by [`exclude_windows`](Self::exclude_windows).
+    pub fn excluded_windows(&self) -> Vec<HWND> {
+        self.exclusion.windows()
+    }
+}
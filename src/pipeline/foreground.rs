@@ -0,0 +1,38 @@
+use super::*;
+
+/// Poll interval while waiting for the target to come to the foreground.
+const FOREGROUND_POLL_INTERVAL: Duration = Duration::from_millis(15);
+
+impl CapturePipeline {
+    /// Apply the configured [`ForegroundPolicy`] before a capture call.
+    pub(super) fn check_foreground(&mut self) -> Result<()> {
+        let timeout = match self.config.foreground {
+            ForegroundPolicy::Ignore => return Ok(()),
+            _ if self.is_foreground() => return Ok(()),
+            ForegroundPolicy::Error => return Err(CaptureError::NotForeground.into()),
+            ForegroundPolicy::Wait(timeout) => timeout,
+        };
+
+        let deadline = Instant::now() + timeout;
+        while !self.is_foreground() {
+            if Instant::now() >= deadline {
+                log::debug!("target not focused within {}ms", timeout.as_millis());
+                return Err(CaptureError::NotForeground.into());
+            }
+            std::thread::sleep(FOREGROUND_POLL_INTERVAL);
+        }
+        // Frames queued before the target got focus predate it (inactive
+        // title bar, and on monitors whatever was on top back then).
+        self.force_fresh = true;
+        Ok(())
+    }
+
+    /// Whether the target window has focus, or for monitors whether the
+    /// focused window is on the target monitor.
+    pub(super) fn is_foreground(&self) -> bool {
+        match self.target {
+            CaptureTarget::Window(hwnd) => is_foreground_window(hwnd),
+            CaptureTarget::Monitor(hmonitor) => is_foreground_on_monitor(hmonitor),
+        }
+    }
+}
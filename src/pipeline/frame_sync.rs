@@ -10,9 +10,9 @@ impl CapturePipeline {
         frame: &windows::Graphics::Capture::Direct3D11CaptureFrame,
         geometry: Option<&WindowGeometry>,
     ) -> Result<Option<(u32, u32)>> {
-        if self.capture.is_window_target() {
+        if self.capture.wgc().is_window_target() {
             if let Some(geo) = geometry {
-                let (pool_w, pool_h) = self.capture.pool_size();
+                let (pool_w, pool_h) = self.capture.wgc().pool_size();
                 if geo.frame_width != pool_w || geo.frame_height != pool_h {
                     return Ok(Some((geo.frame_width, geo.frame_height)));
                 }
@@ -28,7 +28,7 @@ impl CapturePipeline {
             return Ok(None);
         }
 
-        let (pool_w, pool_h) = self.capture.pool_size();
+        let (pool_w, pool_h) = self.capture.wgc().pool_size();
         if new_w != pool_w || new_h != pool_h {
             return Ok(Some((new_w, new_h)));
         }
@@ -47,16 +47,31 @@ impl CapturePipeline {
 
         for _ in 0..RESIZE_RETRY_LIMIT {
             // Query window geometry once per iteration (used for both resize check and crop).
-            let (pool_w, pool_h) = self.capture.pool_size();
-            let geometry = self.capture.window_geometry(pool_w, pool_h);
+            let (pool_w, pool_h) = self.capture.wgc().pool_size();
+            let geometry = self.capture.wgc().window_geometry(pool_w, pool_h);
+
+            // Minimized: the surface is stale, never crop or process it.
+            if geometry.is_none() && self.capture.is_minimized() {
+                if !self.wait_restore_if_configured()? {
+                    return Ok(None);
+                }
+                if mark_grab_sync {
+                    self.force_fresh = true;
+                }
+                if let Some(next) = self.soft_wait_frame(timeout)? {
+                    current = next;
+                    continue;
+                }
+                return Ok(None);
+            }
 
             if let Some((new_w, new_h)) = self.needs_recreate(&current, geometry.as_ref())? {
                 if mark_grab_sync {
                     self.force_fresh = true;
                 }
-                self.capture.recreate_frame_pool(new_w, new_h)?;
+                self.capture.wgc_mut().recreate_frame_pool(new_w, new_h)?;
                 // Drop the first frame after recreate to avoid stale content.
-                drop_next = true;
+                drop_next = self.config.drop_stale_after_resize;
 
                 if let Some(next) = self.soft_wait_frame(timeout)? {
                     current = next;
@@ -76,10 +91,13 @@ impl CapturePipeline {
                 return Ok(None);
             }
 
-            let client_box = if self.headless {
-                geometry.and_then(|g| g.client_box)
-            } else {
-                None
+            let client_box = match (self.config.crop, geometry.and_then(|g| g.client_box)) {
+                (Some(crop), Some(client_box)) => Some(sub_box(client_box, crop)),
+                // Client area unknown (geometry query failed): crop the whole
+                // surface rather than silently dropping the crop.
+                (Some(crop), None) => Some(sub_box(content_box(&current)?, crop)),
+                (None, client_box) if self.headless => client_box,
+                _ => None,
             };
             return self.read_raw_frame(&current, client_box).map(Some);
         }
@@ -95,30 +113,39 @@ impl CapturePipeline {
     ) -> Result<Option<windows::Graphics::Capture::Direct3D11CaptureFrame>> {
         let deadline = Instant::now() + timeout;
         loop {
-            if let Ok(f) = self.capture.try_get_next_frame() {
+            if let Ok(f) = self.capture.wgc().try_get_next_frame() {
                 return Ok(Some(f));
             }
             let remaining = deadline.saturating_duration_since(Instant::now());
             if remaining.is_zero() {
+                self.count(|s| s.timeouts += 1);
                 return Ok(None);
             }
             let timeout_ms = remaining.as_millis().min(u32::MAX as u128) as u32;
-            if self.capture.wait_for_frame(timeout_ms).is_err() {
+            if self.capture.wgc().wait_for_frame(timeout_ms).is_err() {
+                self.count(|s| s.timeouts += 1);
                 return Ok(None);
             }
         }
     }
 
     /// Wait for the next frame, returning error on timeout.
+    ///
+    /// A timeout here means the session owes a frame, which arms the watchdog.
     pub(super) fn hard_wait_frame(
-        &self,
+        &mut self,
         timeout: Duration,
     ) -> Result<windows::Graphics::Capture::Direct3D11CaptureFrame> {
-        self.soft_wait_frame(timeout)?.ok_or_else(|| {
-            anyhow::anyhow!(
-                "Timeout waiting for capture frame ({}ms)",
-                timeout.as_millis()
-            )
-        })
+        let started = Instant::now();
+        if let Some(frame) = self.soft_wait_frame(timeout)? {
+            return Ok(frame);
+        }
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            watchdog.expect_frame(started);
+        }
+        bail!(
+            "Timeout waiting for capture frame ({}ms)",
+            timeout.as_millis()
+        )
     }
 }
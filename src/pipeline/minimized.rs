@@ -0,0 +1,52 @@
+use super::*;
+
+/// Poll interval while waiting for a minimized window to be restored.
+const RESTORE_POLL_INTERVAL: Duration = Duration::from_millis(15);
+
+const MINIMIZED_ERR: &str = "Target window is minimized";
+
+impl CapturePipeline {
+    /// Apply the configured [`MinimizedPolicy`] before a capture call.
+    ///
+    /// Returns `Some(frame)` when the call should return the cached frame, or
+    /// `None` when capture should proceed (not minimized, or restored in time).
+    pub(super) fn check_minimized(&mut self) -> Result<Option<CapturedFrame>> {
+        if !self.capture.is_minimized() {
+            return Ok(None);
+        }
+        if !self.wait_restore_if_configured()? {
+            if self.cached_frame.is_none() {
+                bail!("{} and no frame has been captured yet", MINIMIZED_ERR);
+            }
+            return self.build_cached_frame().map(Some);
+        }
+        // Frames queued while minimized are stale.
+        self.force_fresh = true;
+        Ok(None)
+    }
+
+    /// Handle a minimized target per policy.
+    ///
+    /// Returns `true` once the window is restored (`WaitRestore`), `false` when
+    /// the caller should fall back to the cached frame (`ReturnCached`).
+    pub(super) fn wait_restore_if_configured(&self) -> Result<bool> {
+        let timeout = match self.config.minimized {
+            MinimizedPolicy::ReturnCached => return Ok(false),
+            MinimizedPolicy::Error => bail!(MINIMIZED_ERR),
+            MinimizedPolicy::WaitRestore(timeout) => timeout,
+        };
+
+        let deadline = Instant::now() + timeout;
+        while self.capture.is_minimized() {
+            if Instant::now() >= deadline {
+                bail!(
+                    "{} (not restored within {}ms)",
+                    MINIMIZED_ERR,
+                    timeout.as_millis()
+                );
+            }
+            std::thread::sleep(RESTORE_POLL_INTERVAL);
+        }
+        Ok(true)
+    }
+}
@@ -1,83 +1,100 @@
 use super::*;
+use windows::Win32::Graphics::Gdi::{MonitorFromWindow, HMONITOR, MONITOR_DEFAULTTONEAREST};
 
-const STABLE_FRAME_TIMEOUT_ERR: &str = "Timeout waiting for stable frame after resize";
+pub(super) const STABLE_FRAME_TIMEOUT_ERR: &str = "Timeout waiting for stable frame after resize";
 
 impl CapturePipeline {
-    fn wait_stable_and_process(&mut self, mark_grab_sync: bool) -> Result<CapturedFrame> {
+    fn wait_stable_and_process(
+        &mut self,
+        mark_grab_sync: bool,
+        readback: Readback,
+    ) -> Result<CapturedFrame> {
         let frame = self.hard_wait_frame(FIRST_FRAME_TIMEOUT)?;
         let raw = self
             .resolve_frame_after_resize(frame, FIRST_FRAME_TIMEOUT, mark_grab_sync)?
             .ok_or_else(|| anyhow::anyhow!(STABLE_FRAME_TIMEOUT_ERR))?;
-        self.process_and_cache(raw)
+        self.process_and_cache(raw, readback)
     }
 
-    fn cached_or_wait_stable(&mut self, mark_grab_sync: bool) -> Result<CapturedFrame> {
+    fn cached_or_wait_stable(
+        &mut self,
+        mark_grab_sync: bool,
+        readback: Readback,
+    ) -> Result<CapturedFrame> {
+        // No new frame: deliver copies still queued in the readback ring
+        // before repeating the cache.
+        if let Some((frame, data)) = self.read_in_flight()? {
+            return self.finish_frame(frame, data);
+        }
         if self.cached_frame.is_some() {
             return self.build_cached_frame();
         }
-        self.wait_stable_and_process(mark_grab_sync)
+        self.wait_stable_and_process(mark_grab_sync, readback)
     }
 
     /// Shared first-call logic for both capture() and grab().
-    fn handle_first_call(&mut self, mark_grab_sync: bool) -> Result<CapturedFrame> {
+    fn handle_first_call(
+        &mut self,
+        mark_grab_sync: bool,
+        readback: Readback,
+    ) -> Result<CapturedFrame> {
         self.first_call = false;
         let frame = self.hard_wait_frame(FIRST_FRAME_TIMEOUT)?;
-        if let Some(result) = self.resolve_or_cache(frame, FRESH_FRAME_TIMEOUT, mark_grab_sync)? {
+        if let Some(result) =
+            self.resolve_or_cache(frame, FRESH_FRAME_TIMEOUT, mark_grab_sync, readback)?
+        {
             return Ok(result);
         }
-        self.wait_stable_and_process(mark_grab_sync)
+        self.wait_stable_and_process(mark_grab_sync, readback)
     }
 
-    /// Screenshot mode: capture a fresh frame
-    ///
-    /// Drain backlog -> wait for DWM to push new frame, guarantees returned frame is generated after the call.
-    /// Skip drain on first call (first frame is naturally fresh).
-    /// Use fallback when screen is static to avoid long blocking.
+    /// Get the next frame with the given freshness guarantee.
     ///
-    /// Suitable for screenshot scenarios, latency ~1 VSync.
-    pub fn capture(&mut self) -> Result<CapturedFrame> {
-        if self.first_call {
-            return self.handle_first_call(false);
-        }
+    /// Both modes drain the WGC backlog and fall back to the previous frame
+    /// when the screen is static, to avoid long blocking:
+    /// - [`FrameMode::Fresh`]: wait for DWM to push a new frame, so the result
+    ///   was generated after the call (~1 VSync latency). The backlog is only
+    ///   a fallback. Skips the drain on the first call (first frame is
+    ///   naturally fresh).
+    /// - [`FrameMode::Latest`]: take the newest backlog frame and only wait
+    ///   when the pool is empty. The frame may predate the call, but latency
+    ///   is lower.
+    pub fn next_frame(&mut self, mode: FrameMode) -> Result<CapturedFrame> {
+        self.next_frame_with(mode, Readback::Full)
+    }
 
-        // Drain pool, keep last frame as fallback
-        let mut fallback = None;
-        while let Ok(f) = self.capture.try_get_next_frame() {
-            fallback = Some(f);
+    /// [`next_frame`](Self::next_frame), reading back new frames as `readback`
+    /// asks. Repeated frames are the cached full-resolution output whatever
+    /// `readback` is (see [`is_cached_output`](Self::is_cached_output)).
+    pub(super) fn next_frame_with(
+        &mut self,
+        mode: FrameMode,
+        readback: Readback,
+    ) -> Result<CapturedFrame> {
+        if self.paused {
+            return Err(CaptureError::Paused.into());
         }
-
-        // Try to get a fresh frame with short timeout
-        if let Some(fresh) = self.soft_wait_frame(FRESH_FRAME_TIMEOUT)? {
-            if let Some(result) = self.resolve_or_cache(fresh, FRESH_FRAME_TIMEOUT, false)? {
-                return Ok(result);
-            }
+        let latest = mode == FrameMode::Latest;
+        self.pipelined_readback = latest && !self.detecting_changes();
+        self.refresh_display_state();
+        self.check_watchdog()?;
+        self.check_foreground()?;
+        if matches!(self.capture, CaptureSource::Duplication(_)) {
+            return self.duplication_frame(!latest, readback);
         }
-
-        // Timeout - try fallback
-        if let Some(fb) = fallback {
-            if let Some(result) = self.resolve_or_cache(fb, FRESH_FRAME_TIMEOUT, false)? {
-                return Ok(result);
-            }
+        if let Some(frame) = self.check_minimized()? {
+            return Ok(frame);
         }
 
-        self.cached_or_wait_stable(false)
-    }
-
-    /// Continuous capture mode: grab latest available frame
-    ///
-    /// Drain backlog, keep last frame; wait for new frame when pool is empty.
-    /// Returned frame may have been generated before the call, but with lower latency.
-    /// Use fallback when screen is static to avoid long blocking.
-    ///
-    /// Suitable for high-frequency continuous capture scenarios.
-    pub fn grab(&mut self) -> Result<CapturedFrame> {
-        // If previous resize was observed in grab path, force one fresh-sync call
-        // before consuming backlog frames again.
-        if self.force_fresh {
+        // If a previous resize was observed in Latest mode, force one
+        // fresh-sync call before consuming backlog frames again.
+        if latest && self.force_fresh {
             self.force_fresh = false;
 
             if let Some(fresh) = self.soft_wait_frame(FRESH_FRAME_TIMEOUT)? {
-                if let Some(result) = self.resolve_or_cache(fresh, FRESH_FRAME_TIMEOUT, true)? {
+                if let Some(result) =
+                    self.resolve_or_cache(fresh, FRESH_FRAME_TIMEOUT, true, readback)?
+                {
                     return Ok(result);
                 }
             }
@@ -88,30 +105,132 @@ impl CapturePipeline {
         }
 
         if self.first_call {
-            return self.handle_first_call(true);
+            return self.handle_first_call(latest, readback);
         }
 
         // Drain pool, keep last frame
-        let mut latest = None;
-        while let Ok(f) = self.capture.try_get_next_frame() {
-            latest = Some(f);
+        let mut backlog = None;
+        while let Ok(f) = self.capture.wgc().try_get_next_frame() {
+            if backlog.replace(f).is_some() {
+                self.count(|s| s.frames_dropped_on_drain += 1);
+            }
         }
 
-        // Got a buffered frame - use it
-        if let Some(f) = latest {
-            if let Some(result) = self.resolve_or_cache(f, FRESH_FRAME_TIMEOUT, true)? {
-                return Ok(result);
+        // Latest: a buffered frame is good enough.
+        if latest {
+            if let Some(f) = backlog.take() {
+                if let Some(result) =
+                    self.resolve_or_cache(f, FRESH_FRAME_TIMEOUT, true, readback)?
+                {
+                    return Ok(result);
+                }
             }
         }
 
-        // Pool empty - try short wait for new frame
+        // Short wait for a new frame
         if let Some(fresh) = self.soft_wait_frame(FRESH_FRAME_TIMEOUT)? {
-            if let Some(result) = self.resolve_or_cache(fresh, FRESH_FRAME_TIMEOUT, true)? {
+            if let Some(result) =
+                self.resolve_or_cache(fresh, FRESH_FRAME_TIMEOUT, latest, readback)?
+            {
+                return Ok(result);
+            }
+        }
+
+        // Fresh: timed out, fall back to the buffered frame
+        if let Some(f) = backlog {
+            if let Some(result) = self.resolve_or_cache(f, FRESH_FRAME_TIMEOUT, false, readback)? {
                 return Ok(result);
             }
         }
 
-        self.cached_or_wait_stable(true)
+        self.cached_or_wait_stable(latest, readback)
+    }
+
+    /// Screenshot mode: capture a fresh frame.
+    ///
+    /// Same as [`next_frame(FrameMode::Fresh)`](Self::next_frame). Suitable
+    /// for screenshot scenarios, latency ~1 VSync.
+    pub fn capture(&mut self) -> Result<CapturedFrame> {
+        self.next_frame(FrameMode::Fresh)
+    }
+
+    /// Continuous capture mode: grab the latest available frame.
+    ///
+    /// Same as [`next_frame(FrameMode::Latest)`](Self::next_frame). Suitable
+    /// for high-frequency continuous capture scenarios.
+    pub fn grab(&mut self) -> Result<CapturedFrame> {
+        self.next_frame(FrameMode::Latest)
+    }
+
+    /// Policy frames are currently processed with.
+    ///
+    /// Never `PreferHdr`: it is resolved to `Hdr` or `Sdr` when the pipeline
+    /// is built.
+    pub fn policy(&self) -> CapturePolicy {
+        self.policy
+    }
+
+    /// Switch the capture policy without recreating the pipeline.
+    ///
+    /// The frame pool format is fixed when the session starts, so only
+    /// policies that can be derived from it are accepted:
+    /// - HDR frame pool (built with `Hdr`, `Auto` on an HDR monitor, or a
+    ///   forced HDR format): any policy. `Hdr` passes RGBA16F through, `Auto`
+    ///   tone-maps, `Sdr` clips at SDR white like DWM's own conversion.
+    /// - BGRA8 frame pool (built with `Sdr`, `Auto` on an SDR monitor, or a
+    ///   forced `Bgra8`): `Auto` and `Sdr`. `Hdr` fails since no HDR data is captured; build a
+    ///   new pipeline with `CapturePolicy::Hdr` instead.
+    ///
+    /// `PreferHdr` switches to `Hdr` on an HDR frame pool and to `Sdr` on a
+    /// BGRA8 one.
+    ///
+    /// Applies from the next processed frame. The cached static frame keeps
+    /// its old format until the content changes.
+    pub fn set_policy(&mut self, policy: CapturePolicy) -> Result<()> {
+        let policy = policy.resolve(self.hdr_source);
+        if policy == self.policy {
+            return Ok(());
+        }
+        if !self.hdr_source {
+            if policy == CapturePolicy::Hdr {
+                bail!(
+                    "can't switch to CapturePolicy::Hdr: the pipeline was built with {:?} \
+                     on {} target, so it captures 8-bit BGRA; create a new pipeline \
+                     with CapturePolicy::Hdr",
+                    self.config.policy,
+                    if self.target_hdr { "an HDR" } else { "an SDR" }
+                );
+            }
+            self.policy = policy;
+            self.invalidate_change_detection();
+            return Ok(());
+        }
+
+        let (device, context) = (&self.d3d_ctx.device, &self.d3d_ctx.context);
+        match policy {
+            CapturePolicy::Auto if self.tone_map_pass.is_none() => {
+                let pass = ToneMapPass::new(device, context)?;
+                self.tone_map_pass = Some(tonemap::configure_auto_tone_map(pass, &self.config)?);
+            }
+            CapturePolicy::Sdr if self.clip_pass.is_none() => {
+                let pass = ToneMapPass::with_operator(device, context, ToneMapOperator::Clip)?;
+                self.clip_pass = Some(tonemap::configure_tone_map(pass, &self.config)?);
+            }
+            CapturePolicy::Hdr
+                if self.color_space_pass.is_none()
+                    && self.config.color_space != OutputColorSpace::ScrgbLinear =>
+            {
+                self.color_space_pass = Some(ColorSpacePass::new(
+                    device,
+                    context,
+                    self.config.color_space,
+                )?);
+            }
+            _ => {}
+        }
+        self.policy = policy;
+        self.invalidate_change_detection();
+        Ok(())
     }
 
     /// Whether the target monitor has HDR enabled.
@@ -119,8 +238,165 @@ impl CapturePipeline {
         self.target_hdr
     }
 
+    /// SDR white level (nits) HDR content is tone-mapped with.
+    pub fn sdr_white_nits(&self) -> f32 {
+        self.sdr_white_nits
+    }
+
+    /// Re-query the Windows SDR white level now and return the level in use.
+    ///
+    /// Capture calls already do this once a second on HDR targets (and after
+    /// display changes), so the tone-mapping follows the "SDR content
+    /// brightness" slider; call this to pick up a change right away. Returns
+    /// the override unchanged after [`set_sdr_white_nits`](Self::set_sdr_white_nits).
+    pub fn refresh_white_level(&mut self) -> f32 {
+        self.white_level_checked = Instant::now();
+        if self.config.sdr_white_nits.is_none() {
+            let (hmonitor, _) = hdr_reference_monitor(&self.d3d_ctx, self.target);
+            let nits = white_level::query_sdr_white_level(hmonitor);
+            if nits != self.sdr_white_nits {
+                log::info!(
+                    "SDR white level changed from {} to {} nits",
+                    self.sdr_white_nits,
+                    nits
+                );
+                self.sdr_white_nits = nits;
+                self.invalidate_change_detection();
+            }
+        }
+        self.sdr_white_nits
+    }
+
+    /// Override the SDR white level (nits) used for tone-mapping.
+    ///
+    /// Applies from the next processed frame and stops the pipeline from
+    /// re-querying the Windows setting after display changes. See
+    /// [`CapturePipelineBuilder::sdr_white_nits`].
+    pub fn set_sdr_white_nits(&mut self, nits: f32) -> Result<()> {
+        builder::validate_sdr_white_nits(nits)?;
+        self.config.sdr_white_nits = Some(nits);
+        self.sdr_white_nits = nits;
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Refresh rate (Hz) of the monitor showing the target, from the active display mode.
+    ///
+    /// Queried live, so it follows mode changes and windows moving between
+    /// monitors. `None` if the display configuration can't be read.
+    pub fn refresh_rate_hz(&self) -> Option<f64> {
+        white_level::query_refresh_rate(self.target_monitor())
+    }
+
+    /// Display scale factor (1.0 = 100%) of the monitor showing the target.
+    ///
+    /// Frames are in physical pixels; divide by this to map them to logical
+    /// coordinates. Queried live, like [`refresh_rate_hz`](Self::refresh_rate_hz).
+    pub fn dpi_scale(&self) -> f32 {
+        monitor_dpi_scale(self.target_monitor())
+    }
+
+    /// Provenance for saving `frame` with
+    /// [`CapturedFrame::save_with`]: its wall-clock capture time, the target
+    /// monitor's device name (e.g. `\\.\DISPLAY1`) or window title, and the
+    /// library version.
+    pub fn save_metadata(&self, frame: &CapturedFrame) -> SaveMetadata {
+        let source = match self.target {
+            CaptureTarget::Monitor(hmonitor) => monitor_device_name(hmonitor).ok(),
+            CaptureTarget::Window(hwnd) => window_title(hwnd),
+        };
+        SaveMetadata {
+            captured_at: Some(frame.captured_at()),
+            source,
+            ..SaveMetadata::new()
+        }
+    }
+
+    /// Monitor currently showing the target.
+    fn target_monitor(&self) -> HMONITOR {
+        monitor_of(self.target)
+    }
+
+    /// Re-query SDR white level, display rotation, HDR state and display format
+    /// after a display change.
+    ///
+    /// Only the white level is polled (on HDR targets, see
+    /// `WHITE_LEVEL_POLL_INTERVAL`); the rest is a no-op unless
+    /// `watch_display_changes()` saw a change since the last call.
+    /// The frame pool format stays as chosen at creation: an SDR→HDR toggle
+    /// under `Auto` keeps receiving DWM's BGRA8 conversion, HDR→SDR keeps
+    /// RGBA16F surfaces that are tone-mapped with the new white level.
+    pub(super) fn refresh_display_state(&mut self) {
+        let generation = display_generation();
+        if generation == self.display_generation {
+            if self.target_hdr && self.white_level_checked.elapsed() >= WHITE_LEVEL_POLL_INTERVAL {
+                self.refresh_white_level();
+            }
+            return;
+        }
+        self.display_generation = generation;
+        // DWM redraws after a display change, so the session owes a frame.
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            watchdog.expect_frame(Instant::now());
+        }
+
+        self.refresh_white_level();
+        if self.config.auto_rotate {
+            self.display_orientation = query_display_orientation(self.target, &self.config);
+        }
+        let Ok(display_format) = target_display_format(&self.d3d_ctx, self.target) else {
+            return;
+        };
+        self.display_format = display_format;
+        let hdr = display_format.is_hdr();
+        if hdr != self.target_hdr {
+            log::warn!(
+                "target HDR state changed to {}; frame format is kept until the pipeline is recreated",
+                if hdr { "on" } else { "off" }
+            );
+            self.target_hdr = hdr;
+        }
+    }
+
+    /// Measured frame delivery rate (frames per second).
+    ///
+    /// Rolling average of WGC frame timestamp deltas. Matches the refresh rate
+    /// while content changes every VSync, drops when content is static, and
+    /// varies with the present rate on VRR displays. `None` until two frames arrived.
+    pub fn measured_fps(&self) -> Option<f64> {
+        self.capture.measured_fps()
+    }
+
     /// Buffer pool statistics (for diagnostics / benchmarks).
     pub fn pool_stats(&self) -> crate::memory::PoolStats {
         self.output_pool.stats()
     }
+
+    /// Capture health counters (for diagnosing dropped / repeated frames).
+    pub fn capture_stats(&self) -> CaptureStats {
+        // Pipeline-side counts include sessions replaced by the watchdog.
+        let stats = self.stats.get();
+        CaptureStats {
+            frames_received: stats.frames_received + self.capture.frames_received(),
+            frames_dropped_on_drain: stats.frames_dropped_on_drain + self.capture.frames_flushed(),
+            pool_recreates: stats.pool_recreates + self.capture.pool_recreates(),
+            ..stats
+        }
+    }
+
+    /// Update pipeline-side counters.
+    pub(super) fn count(&self, update: impl FnOnce(&mut CaptureStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+}
+
+/// Monitor currently showing `target`.
+pub(super) fn monitor_of(target: CaptureTarget) -> HMONITOR {
+    match target {
+        CaptureTarget::Monitor(hmonitor) => hmonitor,
+        // SAFETY: MonitorFromWindow accepts any HWND and falls back to the nearest monitor.
+        CaptureTarget::Window(hwnd) => unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) },
+    }
 }
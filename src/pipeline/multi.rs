@@ -0,0 +1,290 @@
+use super::modes::monitor_of;
+use super::*;
+use crate::capture::WindowSelector;
+
+/// One WGC session of a [`MultiCapture`].
+struct MultiSession {
+    id: usize,
+    capture: WGCCapture,
+    target: CaptureTarget,
+    sdr_white_nits: f32,
+    display_format: DisplayFormat,
+    output_pool: Arc<ElasticBufferPool>,
+    /// Last returned frame, for static-content fallback.
+    last_frame: Option<CapturedFrame>,
+}
+
+/// Several capture targets served by one D3D11 device.
+///
+/// Every [`CapturePipeline`] owns a device, a staging reader and GPU passes;
+/// for a dashboard showing a dozen windows that adds up. `MultiCapture` keeps
+/// one WGC session per target but shares the device, the [`TextureReader`]
+/// and the tone-map pass between them, so capturing a target costs one
+/// readback on the shared device.
+///
+/// Targets are added with [`add_monitor`](Self::add_monitor) /
+/// [`add_window`](Self::add_window), which return the id to pass to
+/// [`capture`](Self::capture). Frames are full WGC surfaces: windows include
+/// their title bar and borders, and no crop, scaling or LUT is applied. Use a
+/// [`CapturePipeline`] per target when those are needed.
+///
+/// # Examples
+/// ```no_run
+/// # use hdrcapture::capture::WindowSelector;
+/// # use hdrcapture::pipeline::{CapturePolicy, MultiCapture};
+/// let mut multi = MultiCapture::new(CapturePolicy::Auto).unwrap();
+/// let editor = multi
+///     .add_window(WindowSelector::Process("notepad.exe".to_string()))
+///     .unwrap();
+/// let screen = multi.add_monitor(0).unwrap();
+/// for id in [editor, screen] {
+///     let frame = multi.capture(id).unwrap();
+///     println!("{id}: {}x{}", frame.width, frame.height);
+/// }
+/// ```
+pub struct MultiCapture {
+    d3d_ctx: D3D11Context,
+    policy: CapturePolicy,
+    reader: TextureReader,
+    /// Shared tone-map pass, created when the first HDR target is added under Auto.
+    tone_map_pass: Option<ToneMapPass>,
+    sessions: Vec<MultiSession>,
+    next_id: usize,
+    /// Prevent Send + Sync: holds thread-affine COM resources, like
+    /// [`CapturePipeline`].
+    _not_send_sync: PhantomData<*const ()>,
+    /// COM reference on the creating thread, dropped after the sessions.
+    _com: Option<ComGuard>,
+}
+
+impl MultiCapture {
+    /// Create an empty capture set; `policy` applies to every target.
+    ///
+    /// `PreferHdr` is resolved per target, so HDR and SDR monitors in one set
+    /// return RGBA16F and BGRA8 frames respectively.
+    pub fn new(policy: CapturePolicy) -> Result<Self> {
+        enable_dpi_awareness();
+        let com = ComGuard::ensure().context("COM initialization failed")?;
+        let d3d_ctx = create_d3d11_device()?;
+        let reader = TextureReader::new(d3d_ctx.device.clone(), d3d_ctx.context.clone());
+        Ok(Self {
+            d3d_ctx,
+            policy,
+            reader,
+            tone_map_pass: None,
+            sessions: Vec::new(),
+            next_id: 0,
+            _not_send_sync: PhantomData,
+            _com: com,
+        })
+    }
+
+    /// Start capturing a monitor by index; returns the target id.
+    pub fn add_monitor(&mut self, index: usize) -> Result<usize> {
+        self.add(CaptureTarget::Monitor(find_monitor(index)?))
+    }
+
+    /// Start capturing the first window matching `selector`; returns the target id.
+    pub fn add_window(&mut self, selector: WindowSelector) -> Result<usize> {
+        self.add(CaptureTarget::Window(find_window(selector, None)?))
+    }
+
+    /// Start a WGC session for `target` on the shared device; returns its id.
+    ///
+    /// Ids are never reused within one `MultiCapture`.
+    pub fn add(&mut self, target: CaptureTarget) -> Result<usize> {
+        let capture = init_capture(
+            &self.d3d_ctx,
+            target,
+            self.policy,
+            &SessionOptions::default(),
+        )?;
+        capture.start()?;
+
+        if self.policy == CapturePolicy::Auto && capture.is_hdr() && self.tone_map_pass.is_none() {
+            self.tone_map_pass = Some(ToneMapPass::new(
+                &self.d3d_ctx.device,
+                &self.d3d_ctx.context,
+            )?);
+        }
+
+        let (width, height) = capture.pool_size();
+        let bpp = if self.policy.resolve(capture.is_hdr()) == CapturePolicy::Hdr {
+            8
+        } else {
+            4
+        };
+        let (hmonitor, _) = hdr_reference_monitor(&self.d3d_ctx, target);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sessions.push(MultiSession {
+            id,
+            capture,
+            target,
+            sdr_white_nits: white_level::query_sdr_white_level(hmonitor),
+            display_format: target_display_format(&self.d3d_ctx, target).unwrap_or_default(),
+            output_pool: ElasticBufferPool::new(width as usize * height as usize * bpp),
+            last_frame: None,
+        });
+        Ok(id)
+    }
+
+    /// Stop capturing a target. Returns `false` if the id is unknown.
+    pub fn remove(&mut self, id: usize) -> bool {
+        let before = self.sessions.len();
+        self.sessions.retain(|session| session.id != id);
+        self.sessions.len() != before
+    }
+
+    /// Ids of the active targets, in the order they were added.
+    pub fn ids(&self) -> Vec<usize> {
+        self.sessions.iter().map(|session| session.id).collect()
+    }
+
+    /// Number of active targets.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Whether no target has been added (or all were removed).
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Grab the latest frame of one target.
+    ///
+    /// Drains the target's backlog and returns the newest frame, waiting
+    /// briefly when none is queued. A target whose content is static returns
+    /// its previous frame again, like [`CapturePipeline::grab`].
+    pub fn capture(&mut self, target_id: usize) -> Result<CapturedFrame> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|session| session.id == target_id)
+            .with_context(|| format!("unknown capture target id {}", target_id))?;
+
+        let frame = {
+            let session = &mut self.sessions[index];
+            let timeout = if session.last_frame.is_some() {
+                FRESH_FRAME_TIMEOUT
+            } else {
+                FIRST_FRAME_TIMEOUT
+            };
+            match latest_frame(session, timeout)? {
+                Some(frame) => frame,
+                None => {
+                    return session.last_frame.clone().ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Timeout waiting for capture frame ({}ms)",
+                            timeout.as_millis()
+                        )
+                    })
+                }
+            }
+        };
+
+        let output = self.process(index, &frame)?;
+        self.sessions[index].last_frame = Some(output.clone());
+        Ok(output)
+    }
+
+    /// Tone-map (Auto on HDR) and read back one WGC frame with the shared reader.
+    fn process(
+        &mut self,
+        index: usize,
+        frame: &windows::Graphics::Capture::Direct3D11CaptureFrame,
+    ) -> Result<CapturedFrame> {
+        let session = &self.sessions[index];
+        let timestamp = frame.SystemRelativeTime()?.Duration as f64 / 10_000_000.0;
+        let texture = WGCCapture::frame_to_texture(frame)?;
+        // SAFETY: texture is a valid D3D11 resource obtained from the frame surface.
+        let (width, height, dxgi_format) = unsafe {
+            let mut desc = D3D11_TEXTURE2D_DESC::default();
+            texture.GetDesc(&mut desc);
+            (desc.Width, desc.Height, desc.Format)
+        };
+
+        let processed = color::process_frame(
+            ColorFrame {
+                texture,
+                width,
+                height,
+                timestamp,
+                format: CapturePipeline::color_format(dxgi_format)?,
+            },
+            self.policy.resolve(session.capture.is_hdr()),
+            self.tone_map_pass.as_mut(),
+            session.sdr_white_nits,
+        )?;
+
+        let required_len =
+            CapturePipeline::frame_bytes(processed.width, processed.height, processed.format);
+        if required_len > session.output_pool.frame_bytes() {
+            session.output_pool.resize_frame_bytes(required_len);
+        }
+        let mut pooled = session.output_pool.acquire();
+        let written = self
+            .reader
+            .read_texture_into(&processed.texture, pooled.as_mut_slice())?;
+        let (mut bytes, group_idx, pool) = pooled.into_parts();
+        bytes.truncate(written);
+
+        Ok(CapturedFrame {
+            data: Arc::new(SharedFrameData {
+                bytes,
+                pool,
+                group_idx,
+            }),
+            width: processed.width,
+            height: processed.height,
+            timestamp: processed.timestamp,
+            format: processed.format,
+            color_profile: ColorProfile::Srgb,
+            color_space: OutputColorSpace::ScrgbLinear,
+            source_color_space: session.display_format.color_space,
+            bits_per_channel: session.display_format.bits_per_channel,
+            sdr_white_nits: session.sdr_white_nits,
+            dpi_scale: monitor_dpi_scale(monitor_of(session.target)),
+            warning: None,
+            alpha: AlphaMode::Ignore,
+        })
+    }
+}
+
+/// Newest queued frame of a session, waiting up to `timeout` when none is queued.
+///
+/// Recreates the frame pool when the target was resized and takes the next
+/// frame instead. `None` on timeout.
+fn latest_frame(
+    session: &mut MultiSession,
+    timeout: Duration,
+) -> Result<Option<windows::Graphics::Capture::Direct3D11CaptureFrame>> {
+    let deadline = Instant::now() + timeout;
+    let mut latest = None;
+    for _ in 0..RESIZE_RETRY_LIMIT {
+        while let Ok(frame) = session.capture.try_get_next_frame() {
+            latest = Some(frame);
+        }
+        if latest.is_none() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let timeout_ms = remaining.as_millis().min(u32::MAX as u128) as u32;
+            if remaining.is_zero() || session.capture.wait_for_frame(timeout_ms).is_err() {
+                return Ok(None);
+            }
+            latest = session.capture.try_get_next_frame().ok();
+        }
+        let Some(frame) = latest.take() else {
+            continue;
+        };
+
+        let size = frame.ContentSize()?;
+        let (new_w, new_h) = (size.Width as u32, size.Height as u32);
+        if new_w > 0 && new_h > 0 && (new_w, new_h) != session.capture.pool_size() {
+            drop(frame);
+            session.capture.recreate_frame_pool(new_w, new_h)?;
+            continue;
+        }
+        return Ok(Some(frame));
+    }
+    Ok(None)
+}
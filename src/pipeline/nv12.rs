@@ -0,0 +1,33 @@
+use super::*;
+
+impl CapturePipeline {
+    /// Capture a fresh frame as NV12 for hardware video encoders.
+    ///
+    /// Runs the same GPU stages as [`capture_texture`](Self::capture_texture),
+    /// then converts the SDR result to NV12 (BT.709 limited range) in a
+    /// compute pass, so only 1.5 bytes per pixel are read back and no CPU
+    /// color conversion is left: the buffer can go straight to Media
+    /// Foundation or NVENC. Returns `(data, width, height)`; `data` is the
+    /// `width` × `height` Y plane followed by the interleaved UV plane,
+    /// both tightly packed. Odd sizes round the UV plane up to
+    /// `ceil(width / 2)` pairs × `ceil(height / 2)` rows; most encoders want
+    /// even sizes, see [`resize_output`](CapturePipelineBuilder::resize_output).
+    ///
+    /// Needs SDR output (`CapturePolicy::Sdr`, or `Auto`, which tone-maps
+    /// HDR targets). Only the WGC backend is supported.
+    pub fn capture_nv12(&mut self) -> Result<(Vec<u8>, u32, u32)> {
+        let frame = self.capture_gpu()?;
+        if frame.format != ColorPixelFormat::Bgra8 {
+            bail!(
+                "capture_nv12 needs SDR frames, got {:?}; use CapturePolicy::Auto or Sdr",
+                frame.format
+            );
+        }
+        if self.nv12_pass.is_none() {
+            self.nv12_pass = Some(Nv12Pass::new(&self.d3d_ctx.device, &self.d3d_ctx.context)?);
+        }
+        let pass = self.nv12_pass.as_mut().expect("NV12 pass created above");
+        let data = pass.execute(&frame.texture)?;
+        Ok((data, frame.width, frame.height))
+    }
+}
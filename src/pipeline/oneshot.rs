@@ -0,0 +1,69 @@
+use super::*;
+use crate::capture::WindowSelector;
+
+/// Capture exactly one frame: build a single-use pipeline, read one frame, tear down.
+///
+/// `target` is a builder with the target and options set. Compared to
+/// `build()` + `capture()` the pipeline is tuned for one frame: the WGC frame
+/// pool has a single buffer and no fallback state is kept. The first frame
+/// after `StartCapture` is always fresh, so no backlog is drained.
+///
+/// Most of the cold start cannot be skipped: D3D11 device creation,
+/// `GraphicsCaptureItem` / session creation and DWM composing the first frame
+/// are all required for any WGC capture. Only the setup that a single frame
+/// doesn't need is avoided. Teardown runs before this returns; the Python
+/// `screenshot()` hands the frame back first and tears down on its worker.
+///
+/// # Examples
+/// ```no_run
+/// # use hdrcapture::pipeline::CapturePipelineBuilder;
+/// let frame = hdrcapture::grab_once(CapturePipelineBuilder::new().monitor(0)).unwrap();
+/// frame.save("screenshot.png").unwrap();
+/// ```
+pub fn grab_once(target: CapturePipelineBuilder) -> Result<CapturedFrame> {
+    target.single_shot().build()?.capture()
+}
+
+/// One-liner monitor screenshot: build a pipeline, capture one frame, drop it.
+///
+/// Cold start ~79ms (device + WGC session setup). For multiple screenshots,
+/// keep a [`CapturePipeline`] alive instead. Runs through [`grab_once`].
+///
+/// # Examples
+/// ```no_run
+/// # use hdrcapture::pipeline::CapturePolicy;
+/// let frame = hdrcapture::screenshot_monitor(0, CapturePolicy::Auto).unwrap();
+/// frame.save("screenshot.png").unwrap();
+/// ```
+pub fn screenshot_monitor(index: usize, policy: CapturePolicy) -> Result<CapturedFrame> {
+    grab_once(CapturePipelineBuilder::new().monitor(index).policy(policy))
+}
+
+/// One-liner window screenshot: build a pipeline, capture one frame, drop it.
+///
+/// `headless` crops the title bar and borders. Same cold-start cost as
+/// [`screenshot_monitor`].
+///
+/// # Examples
+/// ```no_run
+/// # use hdrcapture::capture::WindowSelector;
+/// # use hdrcapture::pipeline::CapturePolicy;
+/// let frame = hdrcapture::screenshot_window(
+///     WindowSelector::Process("notepad.exe".to_string()),
+///     CapturePolicy::Auto,
+///     true,
+/// )
+/// .unwrap();
+/// ```
+pub fn screenshot_window(
+    selector: WindowSelector,
+    policy: CapturePolicy,
+    headless: bool,
+) -> Result<CapturedFrame> {
+    grab_once(
+        CapturePipelineBuilder::new()
+            .window(selector)
+            .policy(policy)
+            .headless(headless),
+    )
+}
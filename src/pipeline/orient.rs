@@ -0,0 +1,153 @@
+use super::*;
+
+impl CapturedFrame {
+    /// Copy of the frame rotated clockwise by `rotation`.
+    pub fn rotate(&self, rotation: Rotation) -> CapturedFrame {
+        self.transform(FrameTransform::rotate(rotation))
+    }
+
+    /// Mirrored copy of the frame.
+    pub fn flip(&self, axis: Axis) -> CapturedFrame {
+        self.transform(FrameTransform::flip(axis))
+    }
+
+    /// Copy of the frame with `transform` applied (rotation, then flip).
+    ///
+    /// Pixels are moved exactly, in any format; the identity transform
+    /// returns a cheap clone. To transform every frame on the GPU before
+    /// readback, use [`CapturePipelineBuilder::transform`] instead.
+    pub fn transform(&self, transform: FrameTransform) -> CapturedFrame {
+        if transform.is_identity() {
+            return self.clone();
+        }
+
+        let bpp = self.bytes_per_pixel();
+        let (width, height) = (self.width, self.height);
+        let (out_w, out_h) = transform.output_size(width, height);
+        let src = self.data.as_slice();
+        let mut pooled = self.data.pool.acquire();
+        let len = self.data.len();
+        let dst = &mut pooled.as_mut_slice()[..len];
+        for (y, row) in dst.chunks_exact_mut(out_w as usize * bpp).enumerate() {
+            for (x, pixel) in row.chunks_exact_mut(bpp).enumerate() {
+                let (sx, sy) = transform.source_pixel(x as u32, y as u32, width, height);
+                let offset = (sy as usize * width as usize + sx as usize) * bpp;
+                pixel.copy_from_slice(&src[offset..offset + bpp]);
+            }
+        }
+
+        let (mut bytes, group_idx, pool) = pooled.into_parts();
+        bytes.truncate(len);
+        CapturedFrame {
+            data: Arc::new(SharedFrameData {
+                bytes,
+                pool,
+                group_idx,
+            }),
+            width: out_w,
+            height: out_h,
+            ..self.clone()
+        }
+    }
+}
+
+impl CapturePipeline {
+    /// Transform for a processed frame: the configured one, preceded by the
+    /// display rotation correction under `auto_rotate`.
+    pub(super) fn frame_transform(&self) -> FrameTransform {
+        let mut transform = self.config.transform;
+        if let Some((rotation, desktop)) = self.display_orientation {
+            let correction = display_correction(rotation, self.capture.size(), desktop);
+            transform.rotation = correction.then(transform.rotation);
+        }
+        transform
+    }
+}
+
+/// Rotation that turns a `surface`-sized frame pool upright on a display
+/// turned by `rotation` with a `desktop`-sized desktop.
+///
+/// WGC normally delivers surfaces in desktop orientation already; only a
+/// surface that disagrees with the desktop size arrived in the panel's
+/// native orientation and needs the display rotation. Compares the
+/// uncropped surface, so crops of any aspect ratio are left alone.
+fn display_correction(rotation: Rotation, surface: (u32, u32), desktop: (u32, u32)) -> Rotation {
+    if surface == desktop {
+        Rotation::None
+    } else {
+        rotation
+    }
+}
+
+/// Display rotation of a monitor target for `auto_rotate` (`None` otherwise,
+/// or when the display mode can't be queried).
+pub(super) fn query_display_orientation(
+    target: CaptureTarget,
+    config: &PipelineConfig,
+) -> Option<(Rotation, (u32, u32))> {
+    let CaptureTarget::Monitor(hmonitor) = target else {
+        return None;
+    };
+    if !config.auto_rotate {
+        return None;
+    }
+    match monitor_orientation(hmonitor) {
+        Ok(orientation) => Some(orientation),
+        Err(e) => {
+            log::warn!("failed to query display rotation; frames are not rotated: {e:#}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_moves_pixels_and_swaps_size() {
+        // 2×1 BGRA8 frame: one blue pixel, one red pixel.
+        let frame = test_frame(
+            2,
+            1,
+            ColorPixelFormat::Bgra8,
+            &[255, 0, 0, 255, 0, 0, 255, 255],
+        );
+
+        let rotated = frame.rotate(Rotation::Cw90);
+        assert_eq!((rotated.width, rotated.height), (1, 2));
+        assert_eq!(rotated.data.as_slice(), frame.data.as_slice());
+
+        let flipped = frame.flip(Axis::Horizontal);
+        assert_eq!(flipped.data.as_slice(), &[0, 0, 255, 255, 255, 0, 0, 255]);
+        let back = rotated.rotate(Rotation::Cw270);
+        assert_eq!((back.width, back.height), (2, 1));
+    }
+
+    #[test]
+    fn test_display_correction_follows_surface_size() {
+        // Portrait desktop on a landscape panel: native-orientation surfaces
+        // are rotated, desktop-orientation ones are already upright.
+        let portrait = (1080, 1920);
+        assert_eq!(
+            display_correction(Rotation::Cw90, (1920, 1080), portrait),
+            Rotation::Cw90
+        );
+        assert_eq!(
+            display_correction(Rotation::Cw90, portrait, portrait),
+            Rotation::None
+        );
+        // Flipped landscape: WGC delivers it upright, same size as the desktop.
+        assert_eq!(
+            display_correction(Rotation::Cw180, (1920, 1080), (1920, 1080)),
+            Rotation::None
+        );
+        // Landscape desktop on a portrait panel: a portrait crop of the
+        // upright surface must not count as native orientation.
+        let landscape = (1920, 1080);
+        assert_eq!(
+            display_correction(Rotation::Cw270, landscape, landscape),
+            Rotation::None
+        );
+    }
+}
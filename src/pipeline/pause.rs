@@ -0,0 +1,47 @@
+use super::*;
+
+impl CapturePipeline {
+    /// Stop capturing while keeping the device and capture session alive.
+    ///
+    /// For apps that toggle capture on and off: [`resume`](Self::resume) is
+    /// instant, unlike building a new pipeline. While paused, the WGC
+    /// FrameArrived handler is detached and no frame is read back or
+    /// processed; `capture()` / `grab()` and the other capture calls fail
+    /// with [`CaptureError::Paused`]. Pausing twice is a no-op.
+    pub fn pause(&mut self) -> Result<()> {
+        if let CaptureSource::Wgc(capture) = &mut self.capture {
+            capture.pause()?;
+        }
+        self.paused = true;
+        Ok(())
+    }
+
+    /// Continue after [`pause`](Self::pause).
+    ///
+    /// Frames queued while paused are discarded, so the next `grab()` waits
+    /// briefly for a frame rendered after this call (falling back to the
+    /// last frame when the content is static) instead of returning one from
+    /// before the pause.
+    pub fn resume(&mut self) -> Result<()> {
+        if !self.paused {
+            return Ok(());
+        }
+        if let CaptureSource::Wgc(capture) = &mut self.capture {
+            capture.resume()?;
+        }
+        self.paused = false;
+        self.force_fresh = true;
+        self.in_flight.clear();
+        // No frames arrived on purpose; don't let the watchdog restart the session.
+        let frames_received = self.capture.frames_received();
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            watchdog.reset(frames_received);
+        }
+        Ok(())
+    }
+
+    /// Whether [`pause`](Self::pause) is in effect.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+impl CapturePipeline {
+    /// Non-blocking grab: the newest buffered frame, or `None` right away.
+    ///
+    /// Drains the frame pool like [`grab`](Self::grab) and processes the last
+    /// frame in it, but never waits: when no new frame has arrived since the
+    /// previous call (static content, or the pool is still empty after
+    /// start), it returns `None` instead of waiting or repeating the cached
+    /// frame. Meant for UI and event loops that poll once per tick on their
+    /// own schedule. GPU processing and readback of a returned frame still
+    /// take their usual time.
+    ///
+    /// Conditions `grab()` would wait out return `None` too: a resize (the
+    /// frame pool is recreated and the next poll gets a frame of the new
+    /// size), a minimized target, or a target that isn't in the foreground
+    /// under [`ForegroundPolicy::Wait`]. `MinimizedPolicy::Error` and
+    /// `ForegroundPolicy::Error` still fail, as for `grab()`. Frames are read
+    /// back directly, without the `readback_depth` ring.
+    pub fn try_grab(&mut self) -> Result<Option<CapturedFrame>> {
+        if self.paused {
+            return Err(CaptureError::Paused.into());
+        }
+        self.pipelined_readback = false;
+        self.refresh_display_state();
+        self.check_watchdog()?;
+        match self.config.foreground {
+            ForegroundPolicy::Ignore => {}
+            _ if self.is_foreground() => {}
+            ForegroundPolicy::Error => return Err(CaptureError::NotForeground.into()),
+            ForegroundPolicy::Wait(_) => return Ok(None),
+        }
+        if matches!(self.capture, CaptureSource::Duplication(_)) {
+            return self.try_duplication_frame(Duration::ZERO, Readback::Full);
+        }
+        if self.capture.is_minimized() {
+            if self.config.minimized == MinimizedPolicy::Error {
+                self.wait_restore_if_configured()?;
+            }
+            return Ok(None);
+        }
+
+        let mut backlog = None;
+        while let Ok(f) = self.capture.wgc().try_get_next_frame() {
+            if backlog.replace(f).is_some() {
+                self.count(|s| s.frames_dropped_on_drain += 1);
+            }
+        }
+        // Frames queued before a resize or while the target was covered are
+        // stale; the next poll takes the ones that arrive after.
+        if std::mem::take(&mut self.force_fresh) {
+            return Ok(None);
+        }
+        let Some(frame) = backlog else {
+            return Ok(None);
+        };
+        self.first_call = false;
+        match self.resolve_frame_after_resize(frame, Duration::ZERO, true)? {
+            Some(raw) => self.process_and_cache(raw, Readback::Full).map(Some),
+            None => Ok(None),
+        }
+    }
+}
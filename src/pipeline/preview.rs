@@ -0,0 +1,65 @@
+use super::*;
+use crate::d3d11::texture::{subsample, subsampled_size};
+
+impl CapturePipeline {
+    /// Grab a low-resolution preview, keeping every `step`-th row and column.
+    ///
+    /// Picks the frame like [`grab`](Self::grab) and runs the same GPU
+    /// stages, but reads back only the sampled pixels
+    /// ([`TextureReader::read_texture_subsampled`]), which is much cheaper
+    /// than a full frame plus a CPU resize. Meant for live thumbnails next to
+    /// full-resolution captures; previews are not used as the static-content
+    /// fallback of `capture()` / `grab()`.
+    pub fn preview(&mut self, step: u32) -> Result<CapturedFrame> {
+        if step == 0 {
+            bail!("preview step must be at least 1");
+        }
+        let frame = self.next_frame_with(FrameMode::Latest, Readback::Subsampled(step))?;
+        // No new frame was read: the result is the cached full-resolution frame.
+        if self.is_cached_output(&frame) {
+            return Ok(subsample_frame(&frame, step));
+        }
+        Ok(frame)
+    }
+
+    /// Run the GPU stages on `raw` and read back every `step`-th pixel.
+    pub(super) fn process_preview(&mut self, raw: RawFrame, step: u32) -> Result<CapturedFrame> {
+        let frame = self.process_gpu(raw)?;
+        let (bytes, width, height) =
+            self.reader
+                .read_texture_subsampled(&frame.texture, step, step)?;
+        let data = self.pooled_frame_data(&bytes);
+        let warning = self.pending_warning.take();
+        Ok(self.captured_frame(&frame, data, width, height, warning))
+    }
+}
+
+/// CPU-subsampled copy of a full-resolution frame (static-content fallback).
+pub(super) fn subsample_frame(frame: &CapturedFrame, step: u32) -> CapturedFrame {
+    let bpp = frame.bytes_per_pixel();
+    let (width, height) = subsampled_size(frame.width, frame.height, step, step);
+    let len = width as usize * height as usize * bpp;
+    let mut pooled = frame.data.pool.acquire();
+    subsample(
+        frame.data.as_slice(),
+        frame.width as usize * bpp,
+        frame.width,
+        frame.height,
+        bpp,
+        step,
+        step,
+        &mut pooled.as_mut_slice()[..len],
+    );
+    let (mut bytes, group_idx, pool) = pooled.into_parts();
+    bytes.truncate(len);
+    CapturedFrame {
+        data: Arc::new(SharedFrameData {
+            bytes,
+            pool,
+            group_idx,
+        }),
+        width,
+        height,
+        ..frame.clone()
+    }
+}
@@ -2,7 +2,139 @@ use super::*;
 
 impl CapturePipeline {
     /// Run color pipeline once and cache the final output for fallback.
-    pub(super) fn process_and_cache(&mut self, raw: RawFrame) -> Result<CapturedFrame> {
+    ///
+    /// Reduced readbacks (previews, thumbnails) are returned as they are and
+    /// not cached.
+    pub(super) fn process_and_cache(
+        &mut self,
+        raw: RawFrame,
+        readback: Readback,
+    ) -> Result<CapturedFrame> {
+        match readback {
+            Readback::Full => {}
+            Readback::Subsampled(step) => return self.process_preview(raw, step),
+            Readback::Mip(max_dim) => return self.process_thumbnail(raw, max_dim),
+        }
+
+        // grab_changed(): identical content reuses the cached output, no readback.
+        if let Some(detector) = self.change_detector.as_mut() {
+            if detector.is_unchanged(&raw)? {
+                if let Some(frame) = &self.cached_frame {
+                    return Ok(frame.clone());
+                }
+            }
+        }
+
+        let processed = self.process_gpu(raw)?;
+        let Some((frame, data)) = self.read_back(processed)? else {
+            // Pipelined readback still filling: repeat the last frame.
+            return self
+                .cached_frame
+                .clone()
+                .context("No cached frame data available");
+        };
+        self.finish_frame(frame, data)
+    }
+
+    /// Turn a read-back frame into the output frame: protection check,
+    /// publishing, blank detection, and caching for fallback.
+    pub(super) fn finish_frame(
+        &mut self,
+        frame: GpuFrame,
+        data: SharedFrameData,
+    ) -> Result<CapturedFrame> {
+        let warning = self.pending_warning.take();
+        let mut output = self.captured_frame(&frame, data, frame.width, frame.height, warning);
+        self.check_protected(&output)?;
+        self.publish_frame(&output);
+        let blank = self
+            .config
+            .blank_threshold
+            .is_some_and(|threshold| output.is_blank(threshold));
+        if blank {
+            // Unusable pixels outrank a session restart.
+            output.warning = Some(CaptureWarning::BlankFrame);
+        }
+        if !self.config.single_shot {
+            // The restart warning belongs to this frame only, not to cache
+            // fallbacks; a blank frame stays blank when repeated.
+            self.cached_frame = Some(CapturedFrame {
+                warning: blank.then_some(CaptureWarning::BlankFrame),
+                ..output.clone()
+            });
+        }
+        Ok(output)
+    }
+
+    /// Output frame for pixels read back from `frame`, `width` × `height` of
+    /// them (smaller than `frame` for previews and thumbnails), tagged with
+    /// the current display state.
+    pub(super) fn captured_frame(
+        &self,
+        frame: &GpuFrame,
+        data: SharedFrameData,
+        width: u32,
+        height: u32,
+        warning: Option<CaptureWarning>,
+    ) -> CapturedFrame {
+        CapturedFrame {
+            data: Arc::new(data),
+            width,
+            height,
+            timestamp: frame.timestamp,
+            format: frame.format,
+            color_profile: self.output_profile(frame.source_format, frame.format),
+            color_space: frame.color_space,
+            source_color_space: self.display_format.color_space,
+            bits_per_channel: self.display_format.bits_per_channel,
+            sdr_white_nits: self.sdr_white_nits,
+            dpi_scale: self.dpi_scale(),
+            warning,
+            alpha: if self.config.preserve_alpha {
+                AlphaMode::Premultiplied
+            } else {
+                AlphaMode::Ignore
+            },
+        }
+    }
+
+    /// Copy reduced readback `bytes` into a buffer from the output pool.
+    pub(super) fn pooled_frame_data(&mut self, bytes: &[u8]) -> SharedFrameData {
+        if bytes.len() > self.output_frame_bytes {
+            self.output_frame_bytes = bytes.len();
+            self.output_pool.resize_frame_bytes(bytes.len());
+        }
+        let mut pooled = self.output_pool.acquire();
+        pooled.as_mut_slice()[..bytes.len()].copy_from_slice(bytes);
+        let (mut bytes_vec, group_idx, pool) = pooled.into_parts();
+        bytes_vec.truncate(bytes.len());
+        SharedFrameData {
+            bytes: bytes_vec,
+            pool,
+            group_idx,
+        }
+    }
+
+    /// Whether `frame` is the full-resolution output cached for fallback
+    /// (new or repeated) rather than a reduced readback.
+    pub(super) fn is_cached_output(&self, frame: &CapturedFrame) -> bool {
+        self.cached_frame
+            .as_ref()
+            .is_some_and(|cached| Arc::ptr_eq(&cached.data, &frame.data))
+    }
+
+    /// Run the GPU stages (tone map, LUT, scale, color space) on a raw frame.
+    ///
+    /// The returned texture may be owned by a pass and is only valid until
+    /// the next frame is processed.
+    pub(super) fn process_gpu(&mut self, raw: RawFrame) -> Result<GpuFrame> {
+        let source_format = raw.format;
+        // `Sdr` switched to at runtime on an HDR frame pool clips like DWM's
+        // own conversion.
+        let (policy, tone_map_pass) = match (self.policy, self.clip_pass.as_mut()) {
+            (CapturePolicy::Sdr, Some(clip)) => (CapturePolicy::Auto, Some(clip)),
+            (policy, _) => (policy, self.tone_map_pass.as_mut()),
+        };
         let processed = color::process_frame(
             ColorFrame {
                 texture: raw.texture,
@@ -11,55 +143,110 @@ impl CapturePipeline {
                 timestamp: raw.timestamp,
                 format: raw.format,
             },
-            self.policy,
-            self.tone_map_pass.as_mut(),
+            policy,
+            tone_map_pass,
             self.sdr_white_nits,
         )?;
 
         let ColorFrame {
-            texture,
-            width,
-            height,
+            mut texture,
+            mut width,
+            mut height,
             timestamp,
             format,
         } = processed;
-        let required_len = Self::frame_bytes(width, height, format);
 
-        // Rebuild output pool when processed frame size grows (e.g. format/resolution change).
-        // Existing published frames keep old pool alive via Arc and are recycled independently.
-        if required_len > self.output_frame_bytes {
-            self.output_frame_bytes = required_len;
-            self.output_pool = ElasticBufferPool::new(self.output_frame_bytes);
+        // Optional LUT grade on display-encoded SDR output.
+        if let Some(pass) = self.lut_pass.as_mut() {
+            if format == ColorPixelFormat::Bgra8 {
+                texture = pass.execute(&texture, width, height)?;
+            }
         }
 
-        let mut pooled = self.output_pool.acquire();
-        let written = self
-            .reader
-            .read_texture_into(&texture, pooled.as_mut_slice())?;
-        let (mut dst_vec, group_idx, pool) = pooled.into_parts();
-        dst_vec.truncate(written);
-
-        let output = CapturedFrame {
-            data: Arc::new(SharedFrameData {
-                bytes: dst_vec,
-                pool,
-                group_idx,
-            }),
+        // Optional brightness / contrast / gamma, last step on SDR color.
+        if let Some(pass) = self.sdr_adjust_pass.as_mut() {
+            let adjust = &self.config.sdr_adjust;
+            if format == ColorPixelFormat::Bgra8 && !adjust.is_identity() {
+                texture = pass.execute(&texture, width, height, adjust)?;
+            }
+        }
+
+        // Optional rotate / flip, before scaling so `resize` sizes the final frame.
+        let transform = self.frame_transform();
+        if let Some(pass) = self.orient_pass.as_mut() {
+            if !transform.is_identity() {
+                texture = pass.execute(&texture, width, height, transform)?;
+                (width, height) = transform.output_size(width, height);
+            }
+        }
+
+        // Optional GPU resize before readback.
+        if let Some(pass) = self.scale_pass.as_mut() {
+            let (out_w, out_h) = self.config.output_size(width, height);
+            if (out_w, out_h) != (width, height) {
+                let fit = self
+                    .config
+                    .resize
+                    .map_or(ScaleFit::Stretch, |(_, _, fit)| fit);
+                texture = pass.execute(&texture, out_w, out_h, fit)?;
+                width = out_w;
+                height = out_h;
+            }
+        }
+
+        // Optional color space conversion of RGBA16F output, after scaling so
+        // filtering happens on linear scRGB.
+        let mut color_space = OutputColorSpace::ScrgbLinear;
+        if let Some(pass) = self.color_space_pass.as_mut() {
+            if format == ColorPixelFormat::Rgba16f {
+                texture = pass.execute(&texture, width, height)?;
+                color_space = self.config.color_space;
+            }
+        }
+
+        Ok(GpuFrame {
+            texture,
             width,
             height,
             timestamp,
             format,
-        };
-        self.cached_frame = Some(output.clone());
-        Ok(output)
+            source_format,
+            color_space,
+        })
+    }
+
+    /// Profile to tag an output frame with.
+    ///
+    /// Only pass-through SDR frames (BGRA8 captured from an SDR display) hold
+    /// display-encoded pixels, so only they use the display's profile when
+    /// Windows reports one. The tone-map and clip passes, and the compositor's
+    /// SDR view of an HDR display, write sRGB, so everything else is tagged sRGB.
+    pub(super) fn output_profile(
+        &self,
+        source_format: ColorPixelFormat,
+        output_format: ColorPixelFormat,
+    ) -> ColorProfile {
+        let pass_through = !self.target_hdr
+            && source_format == ColorPixelFormat::Bgra8
+            && output_format == ColorPixelFormat::Bgra8;
+        match (&self.display_profile, pass_through) {
+            (Some(profile), true) => profile.clone(),
+            _ => ColorProfile::Srgb,
+        }
     }
 
     /// Build a CapturedFrame from the cached processed output.
-    /// Only called on the fallback path (static screen, no new frames available).
+    /// Only called on the fallback path (static screen, minimized window, no new frames available).
     pub(super) fn build_cached_frame(&self) -> Result<CapturedFrame> {
-        self.cached_frame
+        let mut frame = self
+            .cached_frame
             .clone()
-            .ok_or_else(|| anyhow::anyhow!("No cached frame data available"))
+            .ok_or_else(|| anyhow::anyhow!("No cached frame data available"))?;
+        self.count(|s| s.cache_fallbacks += 1);
+        if self.capture.is_minimized() && frame.warning.is_none() {
+            frame.warning = Some(CaptureWarning::TargetMinimized);
+        }
+        Ok(frame)
     }
 
     /// Try to resolve a frame (handling resize), process it, or fall back to cache.
@@ -69,9 +256,10 @@ impl CapturePipeline {
         frame: windows::Graphics::Capture::Direct3D11CaptureFrame,
         timeout: Duration,
         mark_grab_sync: bool,
+        readback: Readback,
     ) -> Result<Option<CapturedFrame>> {
         if let Some(raw) = self.resolve_frame_after_resize(frame, timeout, mark_grab_sync)? {
-            return self.process_and_cache(raw).map(Some);
+            return self.process_and_cache(raw, readback).map(Some);
         }
         if self.cached_frame.is_some() {
             return self.build_cached_frame().map(Some);
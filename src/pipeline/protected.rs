@@ -0,0 +1,65 @@
+use super::*;
+
+/// New all-black frames in a row before a shown window counts as protected.
+const PROTECTED_BLACK_FRAMES: u32 = 5;
+
+/// Smaller windows are too often legitimately black to judge.
+const PROTECTED_MIN_SIZE: u32 = 64;
+
+impl CapturePipeline {
+    /// Fail with `CaptureError::ProtectedContent` when the target window's
+    /// content can't be captured (see [`CapturePipelineBuilder::detect_protected`]).
+    ///
+    /// Called for every new processed frame, before it is cached.
+    pub(super) fn check_protected(&mut self, frame: &CapturedFrame) -> Result<()> {
+        let CaptureTarget::Window(hwnd) = self.target else {
+            return Ok(());
+        };
+        if !self.config.detect_protected {
+            return Ok(());
+        }
+        if is_capture_excluded(hwnd) {
+            return Err(CaptureError::ProtectedContent.into());
+        }
+
+        let suspicious = frame.width >= PROTECTED_MIN_SIZE
+            && frame.height >= PROTECTED_MIN_SIZE
+            && is_window_shown(hwnd)
+            && frame.is_blank(0.0);
+        let protected;
+        (self.black_frames, protected) = count_black_frame(self.black_frames, suspicious);
+        if protected {
+            log::debug!(
+                "{} black frames in a row from a visible window; treating it as protected",
+                self.black_frames
+            );
+            return Err(CaptureError::ProtectedContent.into());
+        }
+        Ok(())
+    }
+}
+
+/// Run of suspicious black frames after one more frame, and whether it
+/// reached `PROTECTED_BLACK_FRAMES`. Any other frame resets the run.
+fn count_black_frame(run: u32, suspicious: bool) -> (u32, bool) {
+    let run = if suspicious { run + 1 } else { 0 };
+    (run, run >= PROTECTED_BLACK_FRAMES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_black_run_resets_and_trips_at_threshold() {
+        let mut run = 0;
+        for _ in 1..PROTECTED_BLACK_FRAMES {
+            let protected;
+            (run, protected) = count_black_frame(run, true);
+            assert!(!protected);
+        }
+        // One normal frame (a fade ending) starts over.
+        assert_eq!(count_black_frame(run, false), (0, false));
+        assert_eq!(count_black_frame(run, true), (PROTECTED_BLACK_FRAMES, true));
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+/// Shared-memory segment frames are published to, recreated when they
+/// outgrow it.
+pub(super) struct Publisher {
+    name: String,
+    /// `None` while a larger segment couldn't be created yet.
+    writer: Option<SharedMemoryWriter>,
+    /// Whether the current failure to publish was already logged.
+    warned: bool,
+}
+
+impl Publisher {
+    /// Copy `frame` into the segment, growing it first if needed.
+    ///
+    /// Never fails: a frame that can't be published is skipped with a
+    /// warning (logged once per outage).
+    fn publish(&mut self, frame: &CapturedFrame) {
+        let len = frame.data.as_slice().len();
+        if self.writer.as_ref().is_some_and(|w| w.capacity() < len) {
+            log::info!(
+                "{}x{} frame outgrew shared memory segment '{}'; recreating it",
+                frame.width,
+                frame.height,
+                self.name
+            );
+            if let Some(writer) = self.writer.take() {
+                writer.retire();
+            }
+        }
+        if self.writer.is_none() {
+            // The name stays taken until every reader closed the retired
+            // segment; retry on the next frame until then.
+            match SharedMemoryWriter::create(&self.name, len) {
+                Ok(writer) => self.writer = Some(writer),
+                Err(e) => return self.warn(format_args!("{e:#}")),
+            }
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        match writer.write(
+            frame.data.as_slice(),
+            frame.width,
+            frame.height,
+            frame.format,
+            frame.timestamp,
+        ) {
+            Ok(()) => self.warned = false,
+            Err(e) => self.warn(format_args!("{e:#}")),
+        }
+    }
+
+    fn warn(&mut self, reason: std::fmt::Arguments<'_>) {
+        if !std::mem::replace(&mut self.warned, true) {
+            log::warn!(
+                "not publishing frames to shared memory segment '{}': {}",
+                self.name,
+                reason
+            );
+        }
+    }
+}
+
+impl CapturePipeline {
+    /// Publish every new frame to the named shared-memory segment `name`.
+    ///
+    /// For a consumer in another process (e.g. an encoder): each frame
+    /// processed from now on is copied into the segment behind a
+    /// [`SharedFrameHeader`](crate::memory::SharedFrameHeader) with its size,
+    /// format, timestamp and a seqlock frame counter (see
+    /// [`crate::memory::shared_memory`] for the layout;
+    /// [`SharedMemoryReader`](crate::memory::SharedMemoryReader) reads it).
+    /// Repeated frames (static content, minimized window) are not published
+    /// again, so the counter only moves on new content.
+    ///
+    /// The segment is sized for the current output frame. When a later frame
+    /// doesn't fit (the window grew), the segment is retired and recreated
+    /// at the new size under the same name; readers see
+    /// [`SHARED_FRAME_RETIRED`](crate::memory::shared_memory::SHARED_FRAME_RETIRED)
+    /// and must reopen it. Until they do the name is taken, and frames are
+    /// skipped with a logged warning; publishing never fails a capture. Use
+    /// [`CapturePipelineBuilder::resize_output`] for a fixed frame size.
+    /// Replaces a previous segment; fails if another segment named `name`
+    /// exists.
+    pub fn publish_to_shared_memory(&mut self, name: &str) -> Result<()> {
+        self.shared_memory = None;
+        self.shared_memory = Some(Publisher {
+            name: name.to_owned(),
+            writer: Some(SharedMemoryWriter::create(name, self.output_frame_bytes)?),
+            warned: false,
+        });
+        Ok(())
+    }
+
+    /// Stop publishing frames and close this process's view of the segment.
+    pub fn stop_publishing(&mut self) {
+        self.shared_memory = None;
+    }
+
+    /// Copy a newly processed frame into the shared-memory segment, if any.
+    pub(super) fn publish_frame(&mut self, frame: &CapturedFrame) {
+        if let Some(publisher) = self.shared_memory.as_mut() {
+            publisher.publish(frame);
+        }
+    }
+}
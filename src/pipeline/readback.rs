@@ -0,0 +1,100 @@
+use super::*;
+
+/// A processed frame whose staging copy is queued but not read yet.
+pub(super) struct InFlightFrame {
+    /// Staging ring slot holding the copy.
+    slot: usize,
+    /// Frame metadata; the texture itself is not touched again.
+    frame: GpuFrame,
+}
+
+impl CapturePipeline {
+    /// Read a processed frame back into a pooled buffer.
+    ///
+    /// With `readback_depth` > 1 and `grab()`, the frame's staging copy is
+    /// only queued and the oldest in-flight frame is read instead, so the GPU
+    /// copy overlaps with the caller's work. Returns `None` while the ring is
+    /// still filling after a `capture()` or the pipeline start; the caller
+    /// repeats the previous frame then.
+    pub(super) fn read_back(
+        &mut self,
+        frame: GpuFrame,
+    ) -> Result<Option<(GpuFrame, SharedFrameData)>> {
+        let depth = self.config.readback_depth;
+        if !self.pipelined_readback || depth <= 1 {
+            self.in_flight.clear();
+            let data =
+                self.read_into_pool(frame.width, frame.height, frame.format, |reader, dst| {
+                    reader.read_texture_into(&frame.texture, dst)
+                })?;
+            return Ok(Some((frame, data)));
+        }
+
+        let slot = self.next_slot;
+        self.next_slot = (slot + 1) % depth;
+        self.reader.queue_copy(&frame.texture, slot)?;
+        self.in_flight.push_back(InFlightFrame { slot, frame });
+        // The very first frame has nothing to repeat; read it right away.
+        if self.in_flight.len() < depth && self.cached_frame.is_some() {
+            return Ok(None);
+        }
+
+        let InFlightFrame { slot, frame } = self
+            .in_flight
+            .pop_front()
+            .expect("in-flight queue holds the frame just queued");
+        let data =
+            self.read_into_pool(frame.width, frame.height, frame.format, |reader, dst| {
+                reader.read_queued_into(slot, dst)
+            })?;
+        Ok(Some((frame, data)))
+    }
+
+    /// Read the oldest queued frame without queuing a new one.
+    ///
+    /// Static content delivers no new frames to push the ring forward, so
+    /// `grab()` drains it this way instead of repeating the cached frame
+    /// while newer copies wait. `None` once the ring is empty. During
+    /// `grab_changed()` the drained frame counts as changed.
+    pub(super) fn read_in_flight(&mut self) -> Result<Option<(GpuFrame, SharedFrameData)>> {
+        let Some(InFlightFrame { slot, frame }) = self.in_flight.pop_front() else {
+            return Ok(None);
+        };
+        if let Some(detector) = self.change_detector.as_mut() {
+            detector.mark_drained();
+        }
+        let data =
+            self.read_into_pool(frame.width, frame.height, frame.format, |reader, dst| {
+                reader.read_queued_into(slot, dst)
+            })?;
+        Ok(Some((frame, data)))
+    }
+
+    /// Acquire a pool buffer sized for the frame and fill it with `read`.
+    fn read_into_pool(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: ColorPixelFormat,
+        read: impl FnOnce(&mut TextureReader, &mut [u8]) -> Result<usize>,
+    ) -> Result<SharedFrameData> {
+        let required_len = Self::frame_bytes(width, height, format);
+
+        // Grow pool buffers when processed frame size grows (e.g. format/resolution change).
+        // Published frames keep their buffers and are resized when they return to the pool.
+        if required_len > self.output_frame_bytes {
+            self.output_frame_bytes = required_len;
+            self.output_pool.resize_frame_bytes(required_len);
+        }
+
+        let mut pooled = self.output_pool.acquire();
+        let written = read(&mut self.reader, pooled.as_mut_slice())?;
+        let (mut bytes, group_idx, pool) = pooled.into_parts();
+        bytes.truncate(written);
+        Ok(SharedFrameData {
+            bytes,
+            pool,
+            group_idx,
+        })
+    }
+}
@@ -0,0 +1,290 @@
+use super::modes::STABLE_FRAME_TIMEOUT_ERR;
+use super::*;
+use windows::core::{Interface, PCWSTR};
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Graphics::Direct3D11::{
+    D3D11_RESOURCE_MISC_SHARED_KEYEDMUTEX, D3D11_RESOURCE_MISC_SHARED_NTHANDLE,
+};
+use windows::Win32::Graphics::Dxgi::{
+    IDXGIKeyedMutex, IDXGIResource1, DXGI_SHARED_RESOURCE_READ, DXGI_SHARED_RESOURCE_WRITE,
+};
+use windows::Win32::System::Threading::INFINITE;
+
+/// A captured frame kept on the GPU, in a texture other devices can open.
+///
+/// The texture has a keyed mutex and is exported as an NT handle: open it
+/// with `ID3D11Device1::OpenSharedResource1` or `ID3D12Device::OpenSharedHandle`,
+/// then acquire the keyed mutex with [`READY_KEY`](Self::READY_KEY) before
+/// reading. The pipeline releases it with that key once the frame is written.
+///
+/// Every [`CapturePipeline::capture_shared`] call returns a new texture, so a
+/// consumer can keep one as long as it needs. Dropping this closes the
+/// handle; devices that already opened the texture keep their reference.
+pub struct SharedTexture {
+    texture: ID3D11Texture2D,
+    keyed_mutex: IDXGIKeyedMutex,
+    handle: HANDLE,
+    pub width: u32,
+    pub height: u32,
+    /// `Bgra8` (DXGI_FORMAT_B8G8R8A8_UNORM), `Rgba16f`
+    /// (DXGI_FORMAT_R16G16B16A16_FLOAT) or `Rgb10a2`
+    /// (DXGI_FORMAT_R10G10B10A2_UNORM), as for [`CapturedFrame`].
+    pub format: ColorPixelFormat,
+    /// Encoding of `Rgba16f` pixels; always scRGB for `Bgra8`.
+    pub color_space: OutputColorSpace,
+    /// Timestamp in seconds, as for [`CapturedFrame`].
+    pub timestamp: f64,
+}
+
+impl SharedTexture {
+    /// Keyed-mutex key the texture is released with once the frame is written.
+    pub const READY_KEY: u64 = 1;
+
+    /// NT handle to the texture, valid until `self` is dropped.
+    pub fn handle(&self) -> HANDLE {
+        self.handle
+    }
+
+    /// The texture on the pipeline's own device.
+    pub fn texture(&self) -> &ID3D11Texture2D {
+        &self.texture
+    }
+
+    /// Keyed mutex of [`texture`](Self::texture), for use on the pipeline's device.
+    pub fn keyed_mutex(&self) -> &IDXGIKeyedMutex {
+        &self.keyed_mutex
+    }
+}
+
+impl Drop for SharedTexture {
+    fn drop(&mut self) {
+        // SAFETY: handle was returned by CreateSharedHandle and is closed once.
+        unsafe {
+            let _ = CloseHandle(self.handle);
+        }
+    }
+}
+
+/// A processed frame on the pipeline's own D3D11 device, borrowed from
+/// [`CapturePipeline::capture_texture`].
+///
+/// Constraints:
+/// - **Thread affinity**: the texture and [`d3d`](Self::d3d) belong to the
+///   pipeline's device and immediate context, which are not thread-safe. Use
+///   them on the pipeline's thread only (the type is `!Send`), and don't
+///   submit work to the context from elsewhere while the pipeline runs.
+/// - **Lifetime**: the borrow keeps the pipeline from capturing again while
+///   this is alive, so the contents stay put. The next `capture_texture()`
+///   (or `capture_shared()`) overwrites the same texture; to keep a frame
+///   longer, `CopyResource` it into a texture of your own before then.
+/// - **Read-only**: writing to the texture changes the frame returned for
+///   static content and by `capture_shared()`.
+///
+/// The texture is `D3D11_USAGE_DEFAULT` with `D3D11_BIND_SHADER_RESOURCE`.
+pub struct FrameTexture<'a> {
+    texture: ID3D11Texture2D,
+    d3d: &'a D3D11Context,
+    pub width: u32,
+    pub height: u32,
+    /// `Bgra8`, `Rgba16f` or `Rgb10a2`, with the DXGI formats listed on
+    /// [`SharedTexture::format`].
+    pub format: ColorPixelFormat,
+    /// Encoding of `Rgba16f` pixels; always scRGB for `Bgra8`.
+    pub color_space: OutputColorSpace,
+    /// Timestamp in seconds, as for [`CapturedFrame`].
+    pub timestamp: f64,
+    _not_send_sync: PhantomData<*const ()>,
+}
+
+impl FrameTexture<'_> {
+    /// The processed frame.
+    pub fn texture(&self) -> &ID3D11Texture2D {
+        &self.texture
+    }
+
+    /// Device the texture lives on and its immediate context.
+    pub fn d3d(&self) -> &D3D11Context {
+        self.d3d
+    }
+}
+
+impl CapturePipeline {
+    /// Capture a fresh frame into a shared GPU texture, skipping CPU readback.
+    ///
+    /// Runs the same GPU stages as [`capture`](Self::capture) (crop, tone
+    /// mapping, LUT, scaling, color space conversion) and copies the result
+    /// into a new [`SharedTexture`], for renderers or on-GPU models that read
+    /// the frame from their own D3D11/D3D12 device. Static content returns a
+    /// copy of the previous shared frame. Only the WGC backend is supported.
+    pub fn capture_shared(&mut self) -> Result<SharedTexture> {
+        let source = self.capture_gpu()?;
+        self.export_shared(&source)
+    }
+
+    /// Capture a fresh frame and return the processed texture on the
+    /// pipeline's own device, skipping CPU readback.
+    ///
+    /// Runs the same GPU stages as [`capture_shared`](Self::capture_shared)
+    /// without the cross-device copy and handle: bind the texture to your own
+    /// shaders or copy it to a staging texture on your schedule, using the
+    /// device and immediate context from [`FrameTexture::d3d`]. Static content
+    /// returns the previous frame. Only the WGC backend is supported.
+    pub fn capture_texture(&mut self) -> Result<FrameTexture<'_>> {
+        let frame = self.capture_gpu()?;
+        Ok(FrameTexture {
+            texture: frame.texture,
+            width: frame.width,
+            height: frame.height,
+            format: frame.format,
+            color_space: frame.color_space,
+            timestamp: frame.timestamp,
+            d3d: &self.d3d_ctx,
+            _not_send_sync: PhantomData,
+        })
+    }
+
+    /// Run the GPU stages on the freshest frame and return the private copy
+    /// kept for static content (see `update_shared_cache`).
+    pub(super) fn capture_gpu(&mut self) -> Result<GpuFrame> {
+        self.refresh_display_state();
+        if matches!(self.capture, CaptureSource::Duplication(_)) {
+            bail!("GPU texture capture requires the WGC backend");
+        }
+
+        let raw = match self.next_shared_raw()? {
+            Some(raw) => Some(raw),
+            None if self.shared_cache.is_none() => {
+                let frame = self.hard_wait_frame(FIRST_FRAME_TIMEOUT)?;
+                self.resolve_frame_after_resize(frame, FIRST_FRAME_TIMEOUT, false)?
+            }
+            None => None,
+        };
+        if let Some(raw) = raw {
+            let frame = self.process_gpu(raw)?;
+            self.update_shared_cache(&frame)?;
+        }
+        self.shared_cache
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!(STABLE_FRAME_TIMEOUT_ERR))
+    }
+
+    /// Freshest raw frame for `capture_gpu()`, like `capture()` picks it.
+    ///
+    /// `None` when no new frame arrived (static content, minimized target).
+    pub(super) fn next_shared_raw(&mut self) -> Result<Option<RawFrame>> {
+        if self.capture.is_minimized() && !self.wait_restore_if_configured()? {
+            return Ok(None);
+        }
+        if self.first_call {
+            self.first_call = false;
+            let frame = self.hard_wait_frame(FIRST_FRAME_TIMEOUT)?;
+            return self.resolve_frame_after_resize(frame, FRESH_FRAME_TIMEOUT, false);
+        }
+
+        let mut fallback = None;
+        while let Ok(f) = self.capture.wgc().try_get_next_frame() {
+            if fallback.replace(f).is_some() {
+                self.count(|s| s.frames_dropped_on_drain += 1);
+            }
+        }
+        if let Some(fresh) = self.soft_wait_frame(FRESH_FRAME_TIMEOUT)? {
+            if let Some(raw) = self.resolve_frame_after_resize(fresh, FRESH_FRAME_TIMEOUT, false)? {
+                return Ok(Some(raw));
+            }
+        }
+        match fallback {
+            Some(fb) => self.resolve_frame_after_resize(fb, FRESH_FRAME_TIMEOUT, false),
+            None => Ok(None),
+        }
+    }
+
+    /// Keep a private copy of the latest processed frame: pass outputs are
+    /// overwritten by the next frame, and static content needs a source.
+    fn update_shared_cache(&mut self, frame: &GpuFrame) -> Result<()> {
+        let reusable = self
+            .shared_cache
+            .as_ref()
+            .filter(|c| (c.width, c.height, c.format) == (frame.width, frame.height, frame.format))
+            .map(|c| c.texture.clone());
+        let texture = match reusable {
+            Some(texture) => texture,
+            None => self.create_output_texture(frame, 0)?,
+        };
+        // SAFETY: both textures have the same size and format.
+        unsafe { self.d3d_ctx.context.CopyResource(&texture, &frame.texture) };
+        self.shared_cache = Some(GpuFrame {
+            texture,
+            ..frame.clone()
+        });
+        Ok(())
+    }
+
+    /// Copy `source` into a new keyed-mutex texture and export its NT handle.
+    fn export_shared(&self, source: &GpuFrame) -> Result<SharedTexture> {
+        let misc = D3D11_RESOURCE_MISC_SHARED_KEYEDMUTEX.0 | D3D11_RESOURCE_MISC_SHARED_NTHANDLE.0;
+        let texture = self.create_output_texture(source, misc as u32)?;
+        let keyed_mutex: IDXGIKeyedMutex = texture.cast()?;
+        let resource: IDXGIResource1 = texture.cast()?;
+
+        // SAFETY: the texture is new, so key 0 is free; the copy is queued
+        // while the mutex is held and released to READY_KEY for the consumer.
+        let handle = unsafe {
+            keyed_mutex
+                .AcquireSync(0, INFINITE)
+                .context("Failed to acquire shared texture keyed mutex")?;
+            self.d3d_ctx.context.CopyResource(&texture, &source.texture);
+            keyed_mutex.ReleaseSync(SharedTexture::READY_KEY)?;
+            resource
+                .CreateSharedHandle(
+                    None,
+                    DXGI_SHARED_RESOURCE_READ | DXGI_SHARED_RESOURCE_WRITE,
+                    PCWSTR::null(),
+                )
+                .context("Failed to create shared texture handle")?
+        };
+
+        Ok(SharedTexture {
+            texture,
+            keyed_mutex,
+            handle,
+            width: source.width,
+            height: source.height,
+            format: source.format,
+            color_space: source.color_space,
+            timestamp: source.timestamp,
+        })
+    }
+
+    /// Default-usage texture matching `frame`'s size and format.
+    fn create_output_texture(&self, frame: &GpuFrame, misc_flags: u32) -> Result<ID3D11Texture2D> {
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: frame.width,
+            Height: frame.height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: match frame.format {
+                ColorPixelFormat::Bgra8 => DXGI_FORMAT_B8G8R8A8_UNORM,
+                ColorPixelFormat::Rgba16f => DXGI_FORMAT_R16G16B16A16_FLOAT,
+                ColorPixelFormat::Rgb10a2 => DXGI_FORMAT_R10G10B10A2_UNORM,
+            },
+            SampleDesc: windows::Win32::Graphics::Dxgi::Common::DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: misc_flags,
+        };
+
+        // SAFETY: desc is fully initialized; CreateTexture2D allocates a GPU resource.
+        unsafe {
+            let mut tex = None;
+            self.d3d_ctx
+                .device
+                .CreateTexture2D(&desc, None, Some(&mut tex))
+                .context("Failed to create shared output texture")?;
+            Ok(tex.unwrap())
+        }
+    }
+}
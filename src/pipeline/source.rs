@@ -0,0 +1,199 @@
+use super::*;
+use crate::capture::DuplicationCapture;
+
+/// Frame source behind a pipeline.
+///
+/// Desktop Duplication has no frame queue and no pool to resize, so it skips
+/// the WGC drain / resize logic entirely: `capture()` / `grab()` dispatch to
+/// [`CapturePipeline::duplication_frame`] first.
+pub(super) enum CaptureSource {
+    Wgc(WGCCapture),
+    Duplication(DuplicationCapture),
+}
+
+impl CaptureSource {
+    /// Open the configured backend for `target` and start capturing.
+    pub(super) fn open(
+        d3d_ctx: &D3D11Context,
+        target: CaptureTarget,
+        config: &PipelineConfig,
+    ) -> Result<Self> {
+        let open_wgc = || -> Result<Self> {
+            let capture = init_capture(d3d_ctx, target, config.policy, &config.session)?;
+            capture.start()?;
+            Ok(Self::Wgc(capture))
+        };
+        let open_duplication = |hmonitor| {
+            DuplicationCapture::new(d3d_ctx, hmonitor, config.policy).map(Self::Duplication)
+        };
+
+        match (config.backend, target) {
+            (CaptureBackend::DesktopDuplication, CaptureTarget::Monitor(hmonitor)) => {
+                open_duplication(hmonitor)
+            }
+            (CaptureBackend::DesktopDuplication, CaptureTarget::Window(_)) => {
+                bail!("Desktop Duplication only supports monitor capture")
+            }
+            // Duplication can't honour a forced format: no fallback then.
+            (CaptureBackend::Auto, CaptureTarget::Monitor(hmonitor))
+                if config.session.force_format.is_none() =>
+            {
+                open_wgc().or_else(|wgc_err| {
+                    log::warn!("WGC unavailable, falling back to Desktop Duplication: {wgc_err:#}");
+                    open_duplication(hmonitor)
+                        .with_context(|| format!("WGC also failed: {wgc_err:#}"))
+                })
+            }
+            (CaptureBackend::Auto | CaptureBackend::Wgc, _) => open_wgc(),
+        }
+    }
+
+    /// Whether the target monitor has HDR enabled.
+    pub(super) fn is_hdr(&self) -> bool {
+        match self {
+            Self::Wgc(capture) => capture.is_hdr(),
+            Self::Duplication(dup) => dup.is_hdr(),
+        }
+    }
+
+    /// Current source surface size.
+    pub(super) fn size(&self) -> (u32, u32) {
+        match self {
+            Self::Wgc(capture) => capture.pool_size(),
+            Self::Duplication(dup) => dup.size(),
+        }
+    }
+
+    /// Returns true when the target window is minimized (always false for monitors).
+    pub(super) fn is_minimized(&self) -> bool {
+        match self {
+            Self::Wgc(capture) => capture.is_minimized(),
+            Self::Duplication(_) => false,
+        }
+    }
+
+    pub(super) fn measured_fps(&self) -> Option<f64> {
+        match self {
+            Self::Wgc(capture) => capture.measured_fps(),
+            Self::Duplication(dup) => dup.measured_fps(),
+        }
+    }
+
+    pub(super) fn frames_received(&self) -> usize {
+        match self {
+            Self::Wgc(capture) => capture.frames_received(),
+            Self::Duplication(dup) => dup.frames_received(),
+        }
+    }
+
+    /// Queued WGC frames discarded unread; Duplication keeps no queue.
+    pub(super) fn frames_flushed(&self) -> usize {
+        match self {
+            Self::Wgc(capture) => capture.frames_flushed(),
+            Self::Duplication(_) => 0,
+        }
+    }
+
+    /// WGC frame pool recreations, or duplication recreations after access loss.
+    pub(super) fn pool_recreates(&self) -> usize {
+        match self {
+            Self::Wgc(capture) => capture.pool_recreates(),
+            Self::Duplication(dup) => dup.recreates(),
+        }
+    }
+
+    /// Stop capturing and release the source's OS resources (see
+    /// [`WGCCapture::close`]); the desktop image held by Duplication is
+    /// handed back.
+    pub(super) fn close(&mut self) {
+        match self {
+            Self::Wgc(capture) => capture.close(),
+            Self::Duplication(dup) => dup.release_frame(),
+        }
+    }
+
+    /// The WGC session. Only called on the WGC path.
+    pub(super) fn wgc(&self) -> &WGCCapture {
+        match self {
+            Self::Wgc(capture) => capture,
+            Self::Duplication(_) => unreachable!("WGC frame path used with Desktop Duplication"),
+        }
+    }
+
+    /// The WGC session, mutably. Only called on the WGC path.
+    pub(super) fn wgc_mut(&mut self) -> &mut WGCCapture {
+        match self {
+            Self::Wgc(capture) => capture,
+            Self::Duplication(_) => unreachable!("WGC frame path used with Desktop Duplication"),
+        }
+    }
+}
+
+impl CapturePipeline {
+    /// `capture()` / `grab()` for a Desktop Duplication source.
+    ///
+    /// Duplication accumulates desktop updates into one image rather than
+    /// queueing frames, so there is no backlog to drain. `fresh` waits briefly
+    /// for a new image (screenshot mode); otherwise only an image already
+    /// presented is taken. A static desktop falls back to the cached frame.
+    pub(super) fn duplication_frame(
+        &mut self,
+        fresh: bool,
+        readback: Readback,
+    ) -> Result<CapturedFrame> {
+        let timeout = if self.cached_frame.is_none() {
+            FIRST_FRAME_TIMEOUT
+        } else if fresh {
+            FRESH_FRAME_TIMEOUT
+        } else {
+            Duration::ZERO
+        };
+
+        if let Some(frame) = self.try_duplication_frame(timeout, readback)? {
+            return Ok(frame);
+        }
+        self.count(|s| s.timeouts += 1);
+        if self.cached_frame.is_some() {
+            return self.build_cached_frame();
+        }
+        bail!(
+            "Timeout waiting for capture frame ({}ms)",
+            timeout.as_millis()
+        );
+    }
+
+    /// Acquire and process a new Desktop Duplication image, waiting up to
+    /// `timeout`; `None` when the desktop didn't update meanwhile.
+    pub(super) fn try_duplication_frame(
+        &mut self,
+        timeout: Duration,
+        readback: Readback,
+    ) -> Result<Option<CapturedFrame>> {
+        let CaptureSource::Duplication(dup) = &mut self.capture else {
+            unreachable!("Desktop Duplication path used with WGC");
+        };
+        let Some(frame) = dup.acquire(timeout)? else {
+            return Ok(None);
+        };
+
+        let (width, height, format) = unsafe {
+            let mut desc = D3D11_TEXTURE2D_DESC::default();
+            frame.texture.GetDesc(&mut desc);
+            (desc.Width, desc.Height, desc.Format)
+        };
+        let raw = RawFrame {
+            texture: frame.texture,
+            width,
+            height,
+            timestamp: frame.timestamp,
+            format: Self::color_format(format)?,
+        };
+        let result = self.process_and_cache(raw, readback);
+
+        // Readback is done; hand the desktop surface back to DXGI.
+        if let CaptureSource::Duplication(dup) = &mut self.capture {
+            dup.release_frame();
+        }
+        result.map(Some)
+    }
+}
@@ -0,0 +1,173 @@
+use super::*;
+use half::f16;
+
+use crate::color::hdr10;
+
+/// Pixels sampled per frame by the stability check (on a uniform grid).
+const STABLE_SAMPLES: usize = 4096;
+
+/// A sampled pixel counts as changed when a channel moves by more than this
+/// (8-bit steps for BGRA8, 1/255 for RGBA16F).
+const STABLE_CHANNEL_TOLERANCE: f32 = 2.0 / 255.0;
+
+/// Fraction of changed samples still treated as the same image, so a
+/// blinking caret doesn't keep the frame "animating".
+const STABLE_CHANGED_FRACTION: f32 = 0.005;
+
+impl CapturePipeline {
+    /// Capture once the target has stopped animating.
+    ///
+    /// Takes a fresh frame with [`capture`](Self::capture), then keeps
+    /// grabbing one frame every `settle` until two consecutive frames are
+    /// near-identical (compared on a sparse pixel sample) and returns the
+    /// later one. When `max_wait` elapses first, the latest frame is returned
+    /// anyway. Meant for UI screenshots that should show the final state of a
+    /// fade or transition, not an intermediate frame.
+    pub fn capture_stable(
+        &mut self,
+        max_wait: Duration,
+        settle: Duration,
+    ) -> Result<CapturedFrame> {
+        let first = self.capture()?;
+        wait_stable(first, max_wait, settle, || self.grab())
+    }
+}
+
+impl DesktopPipeline {
+    /// Capture once every monitor has stopped animating.
+    ///
+    /// See [`CapturePipeline::capture_stable`].
+    pub fn capture_stable(
+        &mut self,
+        max_wait: Duration,
+        settle: Duration,
+    ) -> Result<CapturedFrame> {
+        let first = self.capture()?;
+        wait_stable(first, max_wait, settle, || self.grab())
+    }
+}
+
+/// Grab frames every `settle` until two in a row match or `max_wait` runs out.
+fn wait_stable(
+    first: CapturedFrame,
+    max_wait: Duration,
+    settle: Duration,
+    mut grab: impl FnMut() -> Result<CapturedFrame>,
+) -> Result<CapturedFrame> {
+    let deadline = Instant::now() + max_wait;
+    let mut previous = first;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            log::debug!(
+                "capture_stable: content still changing after {:?}",
+                max_wait
+            );
+            return Ok(previous);
+        }
+        std::thread::sleep(settle.min(remaining));
+
+        let frame = grab()?;
+        if frames_similar(&previous, &frame) {
+            return Ok(frame);
+        }
+        previous = frame;
+    }
+}
+
+/// Whether two frames show (nearly) the same image, judged on a pixel sample.
+fn frames_similar(a: &CapturedFrame, b: &CapturedFrame) -> bool {
+    if (a.width, a.height, a.format) != (b.width, b.height, b.format) {
+        return false;
+    }
+    if Arc::ptr_eq(&a.data, &b.data) {
+        // Static-screen fallback returned the cached frame.
+        return true;
+    }
+    let pixels = a.width as usize * a.height as usize;
+    if pixels == 0 {
+        return true;
+    }
+
+    let bpp = a.bytes_per_pixel();
+    let (a_data, b_data) = (a.data.as_slice(), b.data.as_slice());
+    let step = (pixels / STABLE_SAMPLES).max(1);
+    let mut sampled = 0usize;
+    let mut changed = 0usize;
+    for pixel in (0..pixels).step_by(step) {
+        let offset = pixel * bpp;
+        let (pa, pb) = (&a_data[offset..offset + bpp], &b_data[offset..offset + bpp]);
+        if !pixel_close(pa, pb, a.format) {
+            changed += 1;
+        }
+        sampled += 1;
+    }
+    (changed as f32) <= sampled as f32 * STABLE_CHANGED_FRACTION
+}
+
+fn pixel_close(a: &[u8], b: &[u8], format: ColorPixelFormat) -> bool {
+    match format {
+        ColorPixelFormat::Bgra8 => a
+            .iter()
+            .zip(b)
+            .all(|(&x, &y)| (x as f32 - y as f32).abs() / 255.0 <= STABLE_CHANNEL_TOLERANCE),
+        ColorPixelFormat::Rgba16f => a.chunks_exact(2).zip(b.chunks_exact(2)).all(|(x, y)| {
+            let x = f16::from_le_bytes([x[0], x[1]]).to_f32();
+            let y = f16::from_le_bytes([y[0], y[1]]).to_f32();
+            // Scale the tolerance with brightness so HDR highlights aren't held
+            // to an SDR-sized step.
+            (x - y).abs() <= STABLE_CHANNEL_TOLERANCE * x.abs().max(1.0)
+        }),
+        ColorPixelFormat::Rgb10a2 => {
+            let (a, b) = (
+                hdr10::unpack([a[0], a[1], a[2], a[3]]),
+                hdr10::unpack([b[0], b[1], b[2], b[3]]),
+            );
+            a.iter()
+                .zip(&b)
+                .take(3)
+                .all(|(&x, &y)| x.abs_diff(y) as f32 / 1023.0 <= STABLE_CHANNEL_TOLERANCE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 8×8 BGRA8 frame, `fill` called with each pixel's index.
+    fn frame(fill: impl Fn(usize, &mut [u8])) -> CapturedFrame {
+        let mut bytes = vec![0; 8 * 8 * 4];
+        for (i, px) in bytes.chunks_exact_mut(4).enumerate() {
+            fill(i, px);
+        }
+        test_frame(8, 8, ColorPixelFormat::Bgra8, &bytes)
+    }
+
+    #[test]
+    fn test_frames_similar_tolerates_noise_not_motion() {
+        let base = frame(|_, px| px.fill(100));
+        let noisy = frame(|_, px| px.fill(101));
+        let faded = frame(|_, px| px.fill(120));
+        let one_pixel = frame(|i, px| px.fill(if i == 0 { 255 } else { 100 }));
+
+        assert!(frames_similar(&base, &base.clone()));
+        assert!(frames_similar(&base, &noisy));
+        assert!(!frames_similar(&base, &faded));
+        // One of 64 pixels is above the changed-fraction threshold.
+        assert!(!frames_similar(&base, &one_pixel));
+    }
+
+    #[test]
+    fn test_wait_stable_returns_once_content_settles() {
+        let mut level = 0u8;
+        let first = frame(|_, px| px.fill(0));
+        let result = wait_stable(first, Duration::from_secs(5), Duration::ZERO, || {
+            // Fade in steps of 50 until it reaches 200, then hold.
+            level = level.saturating_add(50).min(200);
+            Ok(frame(|_, px| px.fill(level)))
+        })
+        .unwrap();
+        assert_eq!(result.data.as_slice()[0], 200);
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+use std::ops::{ControlFlow, Range};
+
+use crate::video::VideoSink;
+
+/// Highest supported streaming rate.
+const MAX_STREAM_FPS: u32 = 240;
+
+impl CapturePipeline {
+    /// Feed frames to `sink` at a constant `fps` until it ends the stream.
+    ///
+    /// Each tick `grab()`s a frame and pushes it with the tick's time since
+    /// the start (`n / fps`), so an encoder can treat the stream as constant
+    /// frame rate: static content repeats the last frame, and when a grab or
+    /// the sink overruns its slot, the frame is pushed once for every tick
+    /// that elapsed meanwhile instead of leaving a gap. Returns the number of
+    /// `push` calls, counting the one the sink stopped on. Capture and sink
+    /// errors end the stream and are returned; `finish` is only called when
+    /// the sink ends it.
+    pub fn stream_to(&mut self, sink: &mut impl VideoSink, fps: u32) -> Result<u64> {
+        if !(1..=MAX_STREAM_FPS).contains(&fps) {
+            bail!("fps must be between 1 and {}, got {}", MAX_STREAM_FPS, fps);
+        }
+        let tick_time = |tick: u64| Duration::from_secs_f64(tick as f64 / fps as f64);
+        let start = Instant::now();
+        let mut pushed = 0u64;
+        loop {
+            let frame = self.grab()?;
+            for tick in due_ticks(start.elapsed(), fps, pushed) {
+                pushed = tick + 1;
+                let timestamp = tick_time(tick);
+                if sink.push(&frame, timestamp)? == ControlFlow::Break(()) {
+                    sink.finish()?;
+                    return Ok(pushed);
+                }
+            }
+            let next_tick = start + tick_time(pushed);
+            if let Some(wait) = next_tick.checked_duration_since(Instant::now()) {
+                std::thread::sleep(wait);
+            }
+        }
+    }
+}
+
+/// Ticks to push after `pushed` ticks, `elapsed` into a stream at `fps`:
+/// every tick whose time has come, and at least the next one.
+fn due_ticks(elapsed: Duration, fps: u32, pushed: u64) -> Range<u64> {
+    let due = (elapsed.as_secs_f64() * fps as f64) as u64 + 1;
+    pushed..due.max(pushed + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_ticks_fill_overruns() {
+        // On time: one tick at a time.
+        assert_eq!(due_ticks(Duration::ZERO, 30, 0), 0..1);
+        assert_eq!(due_ticks(Duration::from_millis(34), 30, 1), 1..2);
+        // Woke early: still push the next tick.
+        assert_eq!(due_ticks(Duration::from_millis(10), 30, 1), 1..2);
+        // 100 ms stall at 30 fps: ticks 1, 2 and 3 are all due.
+        assert_eq!(due_ticks(Duration::from_millis(100), 30, 1), 1..4);
+    }
+}
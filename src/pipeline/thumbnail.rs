@@ -0,0 +1,228 @@
+use super::*;
+use half::f16;
+
+use crate::color::hdr10;
+use crate::d3d11::texture::mip_level_for;
+
+impl CapturePipeline {
+    /// Grab a thumbnail whose sides are at most `max_dim` pixels.
+    ///
+    /// Picks the frame like [`grab`](Self::grab) and runs the same GPU
+    /// stages, then reads back a single mip level
+    /// ([`TextureReader::read_texture_mip`]): the largest power-of-two
+    /// reduction that fits. Box-filtered rather than subsampled like
+    /// [`preview`](Self::preview), so fine detail averages out instead of
+    /// aliasing. Thumbnails are not used as the static-content fallback of
+    /// `capture()` / `grab()`; when no new frame arrived, the cached frame is
+    /// box-filtered on the CPU to the same size instead.
+    pub fn thumbnail(&mut self, max_dim: u32) -> Result<CapturedFrame> {
+        if max_dim == 0 {
+            bail!("thumbnail size must be at least 1");
+        }
+        let frame = self.next_frame_with(FrameMode::Latest, Readback::Mip(max_dim))?;
+        // No new frame was read: the result is the cached full-resolution frame.
+        if self.is_cached_output(&frame) {
+            return Ok(box_filter_frame(&frame, max_dim));
+        }
+        Ok(frame)
+    }
+
+    /// Average color of the current frame, in RGBA order.
+    ///
+    /// Reads back only the 1×1 mip level, so it is far cheaper than a full
+    /// frame, e.g. for ambient lighting that follows the screen. Values are
+    /// the stored encoding averaged: 0.0..=1.0 sRGB-encoded for BGRA8, PQ
+    /// signal (alpha 0.0..=1.0) for RGB10A2, and the output color space's
+    /// linear values for RGBA16F.
+    pub fn average_color(&mut self) -> Result<[f32; 4]> {
+        let frame = self.thumbnail(1)?;
+        Ok(average_rgba(frame.data.as_slice(), frame.format))
+    }
+
+    /// Run the GPU stages on `raw` and read back the mip level fitting `max_dim`.
+    pub(super) fn process_thumbnail(
+        &mut self,
+        raw: RawFrame,
+        max_dim: u32,
+    ) -> Result<CapturedFrame> {
+        let frame = self.process_gpu(raw)?;
+        let (bytes, width, height) = self.reader.read_texture_mip(&frame.texture, max_dim)?;
+        let data = self.pooled_frame_data(&bytes);
+        let warning = self.pending_warning.take();
+        Ok(self.captured_frame(&frame, data, width, height, warning))
+    }
+}
+
+/// Mean RGBA of tightly packed pixels, normalized like
+/// [`CapturePipeline::average_color`].
+fn average_rgba(data: &[u8], format: ColorPixelFormat) -> [f32; 4] {
+    average_pixels(data.chunks_exact(format.bytes_per_pixel()), format)
+}
+
+/// Mean of `pixels` in `format`, normalized like
+/// [`CapturePipeline::average_color`].
+fn average_pixels<'a>(
+    pixels: impl Iterator<Item = &'a [u8]>,
+    format: ColorPixelFormat,
+) -> [f32; 4] {
+    let mut sum = [0.0f64; 4];
+    let mut count = 0usize;
+    for px in pixels {
+        for (total, value) in sum.iter_mut().zip(decode_rgba(px, format)) {
+            *total += value as f64;
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return [0.0; 4];
+    }
+    sum.map(|total| (total / count as f64) as f32)
+}
+
+/// One pixel as normalized RGBA (see [`CapturePipeline::average_color`]).
+fn decode_rgba(px: &[u8], format: ColorPixelFormat) -> [f32; 4] {
+    match format {
+        ColorPixelFormat::Bgra8 => [px[2], px[1], px[0], px[3]].map(|c| c as f32 / 255.0),
+        ColorPixelFormat::Rgba16f => {
+            std::array::from_fn(|i| f16::from_le_bytes([px[2 * i], px[2 * i + 1]]).to_f32())
+        }
+        ColorPixelFormat::Rgb10a2 => {
+            let [r, g, b, a] = hdr10::unpack([px[0], px[1], px[2], px[3]]);
+            [
+                r as f32 / 1023.0,
+                g as f32 / 1023.0,
+                b as f32 / 1023.0,
+                a as f32 / 3.0,
+            ]
+        }
+    }
+}
+
+/// Inverse of [`decode_rgba`], rounding to the nearest code.
+fn encode_rgba([r, g, b, a]: [f32; 4], format: ColorPixelFormat, out: &mut [u8]) {
+    match format {
+        ColorPixelFormat::Bgra8 => {
+            let code = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+            out.copy_from_slice(&[code(b), code(g), code(r), code(a)]);
+        }
+        ColorPixelFormat::Rgba16f => {
+            for (dst, v) in out.chunks_exact_mut(2).zip([r, g, b, a]) {
+                dst.copy_from_slice(&f16::from_f32(v).to_le_bytes());
+            }
+        }
+        ColorPixelFormat::Rgb10a2 => {
+            let code = |v: f32, max: f32| (v * max).round().clamp(0.0, max) as u16;
+            out.copy_from_slice(&hdr10::pack([
+                code(r, 1023.0),
+                code(g, 1023.0),
+                code(b, 1023.0),
+                code(a, 3.0),
+            ]));
+        }
+    }
+}
+
+/// Copy of `frame` reduced to the mip level fitting `max_dim`
+/// ([`mip_level_for`]), every output pixel the mean of its `2^level` square
+/// block. The last row and column of blocks also take the pixels that odd
+/// sizes leave over. The CPU counterpart of the GPU mip readback, for cached
+/// frames, so both give the same size.
+fn box_filter_frame(frame: &CapturedFrame, max_dim: u32) -> CapturedFrame {
+    let (level, out_w, out_h) = mip_level_for(frame.width, frame.height, max_dim);
+    let step = 1usize << level;
+    let bpp = frame.bytes_per_pixel();
+    let (w, h) = (frame.width as usize, frame.height as usize);
+    let (out_w, out_h) = (out_w as usize, out_h as usize);
+    // Block `i` of `n` along a side of `len` pixels.
+    let span = |i: usize, n: usize, len: usize| {
+        let start = i * step;
+        let end = if i + 1 == n { len } else { start + step };
+        start..end.min(len)
+    };
+    let len = out_w * out_h * bpp;
+    let src = frame.data.as_slice();
+    let mut pooled = frame.data.pool.acquire();
+    let dst = &mut pooled.as_mut_slice()[..len];
+    for (i, out) in dst.chunks_exact_mut(bpp).enumerate() {
+        let cols = span(i % out_w, out_w, w);
+        let block = span(i / out_w, out_h, h).flat_map(|y| {
+            src[(y * w + cols.start) * bpp..(y * w + cols.end) * bpp].chunks_exact(bpp)
+        });
+        encode_rgba(average_pixels(block, frame.format), frame.format, out);
+    }
+
+    let (mut bytes, group_idx, pool) = pooled.into_parts();
+    bytes.truncate(len);
+    CapturedFrame {
+        data: Arc::new(SharedFrameData {
+            bytes,
+            pool,
+            group_idx,
+        }),
+        width: out_w as u32,
+        height: out_h as u32,
+        ..frame.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_rgba_normalizes_formats() {
+        let bgra = [0, 0, 255, 255, 0, 0, 0, 255];
+        assert_eq!(
+            average_rgba(&bgra, ColorPixelFormat::Bgra8),
+            [0.5, 0.0, 0.0, 1.0]
+        );
+
+        let hdr: Vec<u8> = [4.0f32, 1.0, 0.0, 1.0]
+            .iter()
+            .flat_map(|&v| f16::from_f32(v).to_le_bytes())
+            .collect();
+        assert_eq!(
+            average_rgba(&hdr, ColorPixelFormat::Rgba16f),
+            [4.0, 1.0, 0.0, 1.0]
+        );
+
+        let hdr10 = hdr10::pack([1023, 0, 0, 3]);
+        assert_eq!(
+            average_rgba(&hdr10, ColorPixelFormat::Rgb10a2),
+            [1.0, 0.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_box_filter_frame_averages_blocks() {
+        // 3x1 BGRA8: red, black, blue. Level 1 keeps one column, and the
+        // leftover edge pixel joins its block.
+        let pixels = [0, 0, 255, 255, 0, 0, 0, 255, 255, 0, 0, 255];
+        let frame = test_frame(3, 1, ColorPixelFormat::Bgra8, &pixels);
+
+        let small = box_filter_frame(&frame, 2);
+        assert_eq!((small.width, small.height), (1, 1));
+        assert_eq!(small.data.as_slice(), &[85, 0, 85, 255]);
+    }
+
+    #[test]
+    fn test_box_filter_frame_matches_mip_size() {
+        // read_texture_mip reads the level mip_level_for picks, so the GPU
+        // path returns exactly these sizes.
+        let cases = [
+            (1920, 1080, 700),
+            (1921, 1080, 1000),
+            (21, 9, 8),
+            (5, 3, 1),
+            (9, 2, 9),
+        ];
+        for (width, height, max_dim) in cases {
+            let bytes = vec![0; (width * height * 4) as usize];
+            let frame = test_frame(width, height, ColorPixelFormat::Bgra8, &bytes);
+            let small = box_filter_frame(&frame, max_dim);
+            let (_, mip_w, mip_h) = mip_level_for(width, height, max_dim);
+            assert_eq!((small.width, small.height), (mip_w, mip_h));
+            assert_eq!(small.data.len(), (mip_w * mip_h * 4) as usize);
+        }
+    }
+}
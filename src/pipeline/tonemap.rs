@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use super::*;
+
+impl CapturePipeline {
+    /// Replace the HDR→SDR tone-map shader with custom HLSL.
+    ///
+    /// The shader is compiled and validated before the current pass is replaced;
+    /// on error the pipeline keeps its previous shader. See
+    /// [`ToneMapPass::with_shader`] for the required entry point and bindings.
+    ///
+    /// Only takes effect under `CapturePolicy::Auto` on an HDR target, the
+    /// only case where the pipeline tone-maps.
+    pub fn set_custom_tonemap(&mut self, hlsl: &str) -> Result<()> {
+        if self.policy != CapturePolicy::Auto {
+            bail!(
+                "custom tone-map requires CapturePolicy::Auto (pipeline uses {:?})",
+                self.policy
+            );
+        }
+        let pass = ToneMapPass::with_shader(&self.d3d_ctx.device, &self.d3d_ctx.context, hlsl)?;
+        self.tone_map_pass = Some(configure_auto_tone_map(pass, &self.config)?);
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Derive the tone-map exposure from each frame's content.
+    ///
+    /// While on, every tone-mapped frame first gets a GPU luminance histogram
+    /// (a 256-byte readback that doesn't wait for the GPU, so it trails by a
+    /// frame or two); the exposure brings the median of its non-black pixels
+    /// toward a fifth of SDR white, within ±2 stops, adapting over several
+    /// frames. See [`AutoExposure`] for the defaults. Turning it off
+    /// returns to exposure 1.0.
+    ///
+    /// Only affects frames tone-mapped under `CapturePolicy::Auto`.
+    pub fn set_auto_exposure(&mut self, enable: bool) -> Result<()> {
+        let settings = enable.then(AutoExposure::default);
+        if let Some(pass) = self.tone_map_pass.as_mut() {
+            pass.set_auto_exposure(settings)?;
+        }
+        self.config.auto_exposure = settings;
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Exposure multiplier the tone-map currently applies (1.0 without
+    /// auto-exposure or before the first HDR frame).
+    pub fn exposure(&self) -> f32 {
+        self.tone_map_pass
+            .as_ref()
+            .map_or(1.0, |pass| pass.exposure())
+    }
+
+    /// Load a 3D `.cube` LUT and apply it to every following SDR frame.
+    ///
+    /// The LUT runs after HDR→SDR conversion, on display-encoded color.
+    /// RGBA16F output (`CapturePolicy::Hdr`) is not graded.
+    pub fn set_lut(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let lut = CubeLut::from_file(path)?;
+        let pass = LutPass::new(&self.d3d_ctx.device, &self.d3d_ctx.context, &lut)?;
+        self.lut_pass = Some(pass);
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Remove a LUT previously set with [`set_lut`](Self::set_lut).
+    pub fn clear_lut(&mut self) {
+        self.lut_pass = None;
+        self.invalidate_change_detection();
+    }
+
+    /// Brightness / contrast / gamma applied to SDR output.
+    pub fn sdr_adjust(&self) -> SdrAdjust {
+        self.config.sdr_adjust
+    }
+
+    /// Change the SDR brightness / contrast / gamma adjustment.
+    ///
+    /// Applies from the next processed frame; the identity turns the pass
+    /// off again. See [`CapturePipelineBuilder::sdr_adjust`].
+    pub fn set_sdr_adjust(&mut self, adjust: SdrAdjust) -> Result<()> {
+        adjust.validate()?;
+        if !adjust.is_identity() && self.sdr_adjust_pass.is_none() {
+            self.sdr_adjust_pass = Some(SdrAdjustPass::new(
+                &self.d3d_ctx.device,
+                &self.d3d_ctx.context,
+            )?);
+        }
+        self.config.sdr_adjust = adjust;
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Scale every following frame to a fixed `width` × `height` on the GPU.
+    ///
+    /// See [`CapturePipelineBuilder::resize_output`].
+    pub fn resize_output(&mut self, width: u32, height: u32, fit: ScaleFit) -> Result<()> {
+        if width == 0 || height == 0 {
+            bail!("output width and height must be >= 1");
+        }
+        if self.scale_pass.is_none() {
+            self.scale_pass = Some(ScalePass::new(&self.d3d_ctx.device, &self.d3d_ctx.context)?);
+        }
+        self.config.resize = Some((width, height, fit));
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Return to native-size output (or the builder's `downscale` and
+    /// `max_resolution`).
+    pub fn clear_output_size(&mut self) {
+        self.config.resize = None;
+        self.invalidate_change_detection();
+    }
+
+    /// Change the output size cap for following frames; `None` removes it.
+    ///
+    /// See [`CapturePipelineBuilder::max_resolution`].
+    pub fn set_max_resolution(&mut self, max: Option<(u32, u32)>) -> Result<()> {
+        if matches!(max, Some((0, _) | (_, 0))) {
+            bail!("maximum resolution width and height must be >= 1");
+        }
+        if max.is_some() && self.scale_pass.is_none() {
+            self.scale_pass = Some(ScalePass::new(&self.d3d_ctx.device, &self.d3d_ctx.context)?);
+        }
+        self.config.max_resolution = max;
+        self.invalidate_change_detection();
+        Ok(())
+    }
+
+    /// Put `frame` on the Windows clipboard, tone-mapping HDR frames on this
+    /// pipeline's device.
+    ///
+    /// Same output as [`CapturedFrame::copy_to_clipboard`], without creating
+    /// a temporary D3D11 device per call.
+    pub fn copy_to_clipboard(&self, frame: &CapturedFrame) -> Result<()> {
+        frame.copy_to_clipboard_on(Some(&self.d3d_ctx))
+    }
+}
+
+/// Apply the builder's tone-map options (thread group, auto-exposure) to a
+/// new `Auto` tone-map pass.
+pub(super) fn configure_auto_tone_map(
+    pass: ToneMapPass,
+    config: &PipelineConfig,
+) -> Result<ToneMapPass> {
+    let mut pass = configure_tone_map(pass, config)?;
+    pass.set_auto_exposure(config.auto_exposure)?;
+    Ok(pass)
+}
+
+/// Apply the builder's thread group options to a new tone-map pass.
+pub(super) fn configure_tone_map(
+    mut pass: ToneMapPass,
+    config: &PipelineConfig,
+) -> Result<ToneMapPass> {
+    match config.tonemap_thread_group {
+        Some(size) => {
+            pass.set_thread_group(size)?;
+            Ok(pass)
+        }
+        None => Ok(pass.with_auto_tune(config.tonemap_auto_tune)),
+    }
+}
@@ -1,14 +1,30 @@
-use std::path::Path;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use half::f16;
+use windows::Win32::Foundation::{FILETIME, SYSTEMTIME};
 use windows::Win32::Graphics::Direct3D11::ID3D11Texture2D;
 use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT;
+use windows::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
+use windows::Win32::System::Time::{FileTimeToSystemTime, SystemTimeToTzSpecificLocalTime};
 
-use crate::color::ColorPixelFormat;
+use crate::color::{
+    alpha, convert, hdr10, luminance, tone_map, AlphaMode, ColorPixelFormat, ColorProfile,
+    DisplayColorSpace, FrameLuminanceStats, OutputColorSpace, ToneMapOperator, SCRGB_NITS,
+};
+use crate::d3d11::D3D11Context;
+use crate::image::{clipboard, ExrOptions, ImageFormat, SaveOptions};
 use crate::memory::ElasticBufferPool;
 
 /// Single frame capture result
+///
+/// `Send + Sync`: unlike [`CapturePipeline`](super::CapturePipeline), a frame
+/// holds only CPU bytes and can be moved to (or shared with) other threads,
+/// e.g. captured on one thread and encoded on another. Dropping the last clone
+/// on any thread returns the buffer to the pipeline's pool.
 #[derive(Clone)]
 pub struct CapturedFrame {
     /// Pixel data (shared, read-only), length = width * height * bytes_per_pixel
@@ -21,32 +37,550 @@ pub struct CapturedFrame {
     pub timestamp: f64,
     /// Pixel format of `data`
     pub format: ColorPixelFormat,
+    /// Color profile embedded when saved as PNG / JPEG
+    ///
+    /// Code building a `CapturedFrame` with a struct literal must set this
+    /// field (a breaking change from earlier releases); `ColorProfile::Srgb`
+    /// is right for anything not captured from an SDR display.
+    pub color_profile: ColorProfile,
+    /// Color encoding of RGBA16F `data` (always `ScrgbLinear` for BGRA8 and
+    /// RGB10A2, which is HDR10)
+    pub color_space: OutputColorSpace,
+    /// Color space of the signal driving the source display (not of `data`)
+    pub source_color_space: DisplayColorSpace,
+    /// Bits per channel of the source display signal (0 when unknown)
+    pub bits_per_channel: u32,
+    /// SDR white level (nits) of the source display
+    pub sdr_white_nits: f32,
+    /// Display scale factor of the source monitor (1.0 = 100%)
+    pub dpi_scale: f32,
+    /// Set when the frame is not a faithful capture of the current target state
+    pub warning: Option<CaptureWarning>,
+    /// Meaning of the alpha channel (`Ignore` unless the pipeline preserves alpha)
+    pub alpha: AlphaMode,
+}
+
+/// Freshness guarantee of [`CapturePipeline::next_frame`](super::CapturePipeline::next_frame).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameMode {
+    /// A frame generated after the call (`capture()`); waits ~1 VSync.
+    #[default]
+    Fresh,
+    /// The newest frame already available (`grab()`); may predate the call.
+    Latest,
+}
+
+/// Non-fatal condition attached to a returned frame.
+///
+/// A frame carries at most one; [`BlankFrame`](Self::BlankFrame) takes
+/// precedence over the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureWarning {
+    /// Target window is minimized; the frame is the last one captured before.
+    TargetMinimized,
+    /// The watchdog restarted a capture session that had stopped delivering
+    /// frames; this is the first frame of the new session.
+    SessionRestarted,
+    /// The frame is effectively black, typically protected (DRM / HDCP)
+    /// content. Only with [`blank_detection`](super::CapturePipelineBuilder::blank_detection).
+    BlankFrame,
+}
+
+/// Value of one pixel, see [`CapturedFrame::pixel`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pixel {
+    /// BGRA8 frame: sRGB-encoded `[r, g, b, a]`.
+    Sdr([u8; 4]),
+    /// RGBA16F / RGB10A2 frame: linear `[r, g, b]` in nits, then alpha
+    /// (1.0 = opaque). BT.2020 primaries for `Rec2020Linear` frames, BT.709
+    /// otherwise (HDR10 is decoded to BT.709).
+    Hdr([f32; 4]),
+}
+
+/// Capture failures callers may want to handle specifically.
+///
+/// Returned inside the `anyhow::Error` of a capture call; match it with
+/// `err.downcast_ref::<CaptureError>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureError {
+    /// The target window's content is protected and captures as black: the
+    /// window opted out with `SetWindowDisplayAffinity`, or shows DRM / HDCP
+    /// video. See [`detect_protected`](super::CapturePipelineBuilder::detect_protected).
+    ProtectedContent,
+    /// The target isn't in the foreground. See
+    /// [`require_foreground`](super::CapturePipelineBuilder::require_foreground).
+    NotForeground,
+    /// The pipeline is paused. See [`pause`](super::CapturePipeline::pause).
+    Paused,
+}
+
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProtectedContent => f.write_str(
+                "window content is protected (DRM / HDCP or excluded from capture) \
+                 and captures as black",
+            ),
+            Self::NotForeground => f.write_str("capture target is not in the foreground"),
+            Self::Paused => f.write_str("capture is paused; call resume() first"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+/// Capture health counters, accumulated since the pipeline was created.
+///
+/// Complements [`PoolStats`](crate::memory::PoolStats), which covers CPU
+/// buffer reuse. Useful for diagnosing choppy capture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CaptureStats {
+    /// Frames taken from the WGC frame pool.
+    pub frames_received: usize,
+    /// Frames discarded unread: backlog skipped by `capture()` / `grab()` in
+    /// favor of a newer one, and frames still queued when the session is
+    /// resumed, resized, restarted or closed.
+    pub frames_dropped_on_drain: usize,
+    /// WGC frame pool recreations after a target resize.
+    pub pool_recreates: usize,
+    /// Calls answered with the cached last frame (static content, minimized window).
+    pub cache_fallbacks: usize,
+    /// Frame waits that expired without a new frame.
+    pub timeouts: usize,
+    /// Capture sessions restarted by the starvation watchdog.
+    pub session_restarts: usize,
+}
+
+impl CaptureStats {
+    /// Field-wise sum, for aggregating several pipelines.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            frames_received: self.frames_received + other.frames_received,
+            frames_dropped_on_drain: self.frames_dropped_on_drain + other.frames_dropped_on_drain,
+            pool_recreates: self.pool_recreates + other.pool_recreates,
+            cache_fallbacks: self.cache_fallbacks + other.cache_fallbacks,
+            timeouts: self.timeouts + other.timeouts,
+            session_restarts: self.session_restarts + other.session_restarts,
+        }
+    }
 }
 
 impl CapturedFrame {
     pub fn bytes_per_pixel(&self) -> usize {
+        self.format.bytes_per_pixel()
+    }
+
+    /// Luminance statistics (max / p99 / fraction above SDR white).
+    ///
+    /// Scans every pixel on the CPU; call only when needed. On an HDR display
+    /// with `CapturePolicy::Hdr`, `fraction_above_sdr > 0` means the frame
+    /// contains genuine HDR highlights. RGBA16F data is read as scRGB, so the
+    /// result is only meaningful for `OutputColorSpace::ScrgbLinear` frames.
+    pub fn luminance_stats(&self) -> FrameLuminanceStats {
+        luminance::frame_stats(self.data.as_slice(), self.format, self.sdr_white_nits)
+    }
+
+    /// Whether no pixel is brighter than `threshold`: protected (DRM / HDCP)
+    /// windows capture as solid black instead of failing.
+    ///
+    /// `threshold` is a fraction of full scale for BGRA8 and RGB10A2 (0.0 =
+    /// exactly black) and a linear scRGB value for RGBA16F. Alpha is ignored.
+    /// Stops at the first brighter pixel, so ordinary content is cheap.
+    pub fn is_blank(&self, threshold: f32) -> bool {
+        let data = self.data.as_slice();
         match self.format {
-            ColorPixelFormat::Bgra8 => 4,
-            ColorPixelFormat::Rgba16f => 8,
+            ColorPixelFormat::Bgra8 => data
+                .chunks_exact(4)
+                .all(|px| px[..3].iter().all(|&c| c as f32 / 255.0 <= threshold)),
+            ColorPixelFormat::Rgba16f => data.chunks_exact(8).all(|px| {
+                px[..6]
+                    .chunks_exact(2)
+                    .all(|c| f16::from_le_bytes([c[0], c[1]]).to_f32() <= threshold)
+            }),
+            ColorPixelFormat::Rgb10a2 => data.chunks_exact(4).all(|px| {
+                let codes = hdr10::unpack([px[0], px[1], px[2], px[3]]);
+                codes[..3].iter().all(|&c| c as f32 / 1023.0 <= threshold)
+            }),
+        }
+    }
+
+    /// Value of the pixel at `(x, y)`, e.g. for an eyedropper.
+    ///
+    /// HDR values are absolute (scRGB 1.0 = 80 nits, HDR10 is PQ), so they
+    /// don't depend on `sdr_white_nits`; compare against it to tell SDR-range
+    /// content from highlights.
+    pub fn pixel(&self, x: u32, y: u32) -> Result<Pixel> {
+        if x >= self.width || y >= self.height {
+            bail!(
+                "pixel ({}, {}) is outside the {}x{} frame",
+                x,
+                y,
+                self.width,
+                self.height
+            );
+        }
+        let data = self.data.as_slice();
+        self.format.check_len(data, self.width, self.height)?;
+        let bpp = self.bytes_per_pixel();
+        let offset = (y as usize * self.width as usize + x as usize) * bpp;
+        let px = &data[offset..offset + bpp];
+        Ok(match self.format {
+            ColorPixelFormat::Bgra8 => Pixel::Sdr([px[2], px[1], px[0], px[3]]),
+            ColorPixelFormat::Rgba16f => {
+                let ch = |i: usize| f16::from_le_bytes([px[2 * i], px[2 * i + 1]]).to_f32();
+                let linear = |v: f32| match self.color_space {
+                    OutputColorSpace::SrgbEncoded => {
+                        v.signum() * luminance::srgb_to_linear(v.abs())
+                    }
+                    OutputColorSpace::ScrgbLinear | OutputColorSpace::Rec2020Linear => v,
+                };
+                Pixel::Hdr([
+                    linear(ch(0)) * SCRGB_NITS,
+                    linear(ch(1)) * SCRGB_NITS,
+                    linear(ch(2)) * SCRGB_NITS,
+                    ch(3),
+                ])
+            }
+            ColorPixelFormat::Rgb10a2 => {
+                let [r, g, b, a] = hdr10::pixel_to_scrgb([px[0], px[1], px[2], px[3]]);
+                Pixel::Hdr([r * SCRGB_NITS, g * SCRGB_NITS, b * SCRGB_NITS, a])
+            }
+        })
+    }
+
+    /// Copy of the frame with straight (non-premultiplied) alpha.
+    ///
+    /// WGC delivers premultiplied color; PNG / TIFF expect straight alpha, so
+    /// call this before saving a frame captured with `preserve_alpha`. Frames
+    /// whose alpha is not `Premultiplied` are returned unchanged (cheap clone).
+    pub fn unpremultiply(&self) -> CapturedFrame {
+        if self.alpha != AlphaMode::Premultiplied {
+            return self.clone();
+        }
+
+        let mut pooled = self.data.pool.acquire();
+        let len = self.data.len();
+        let dst = &mut pooled.as_mut_slice()[..len];
+        dst.copy_from_slice(self.data.as_slice());
+        alpha::unpremultiply(dst, self.format);
+
+        let (mut bytes, group_idx, pool) = pooled.into_parts();
+        bytes.truncate(len);
+        CapturedFrame {
+            data: Arc::new(SharedFrameData {
+                bytes,
+                pool,
+                group_idx,
+            }),
+            alpha: AlphaMode::Straight,
+            ..self.clone()
+        }
+    }
+
+    /// SDR copy of the frame, tone-mapped with the pipeline's default operator.
+    ///
+    /// Same as [`to_bgra8`](Self::to_bgra8) with [`ToneMapOperator::Eetf`].
+    pub fn to_sdr(&self) -> Result<CapturedFrame> {
+        self.to_bgra8(ToneMapOperator::default())
+    }
+
+    /// BGRA8 copy of the frame, tone-mapping RGBA16F with `operator`.
+    ///
+    /// BGRA8 frames are returned unchanged (cheap clone). RGBA16F frames must be
+    /// scRGB (the default output color space); RGB10A2 frames are decoded from
+    /// HDR10 to scRGB first. Tone-mapping runs on a temporary
+    /// D3D11 device, so prefer `CapturePolicy::Auto` for SDR output in capture
+    /// loops.
+    pub fn to_bgra8(&self, operator: ToneMapOperator) -> Result<CapturedFrame> {
+        self.to_bgra8_on(None, operator)
+    }
+
+    /// [`to_bgra8`](Self::to_bgra8), tone-mapping on `ctx` when given instead
+    /// of a temporary device.
+    pub(super) fn to_bgra8_on(
+        &self,
+        ctx: Option<&D3D11Context>,
+        operator: ToneMapOperator,
+    ) -> Result<CapturedFrame> {
+        if self.format == ColorPixelFormat::Bgra8 {
+            return Ok(self.clone());
+        }
+        if self.color_space != OutputColorSpace::ScrgbLinear {
+            bail!(
+                "tone-mapping expects scRGB input; '{}' frames can't be converted to SDR",
+                self.color_space.name()
+            );
         }
+
+        let scrgb = match self.format {
+            ColorPixelFormat::Rgb10a2 => Cow::Owned(hdr10::hdr10_to_scrgb(self.data.as_slice())),
+            _ => Cow::Borrowed(self.data.as_slice()),
+        };
+        let bgra = match ctx {
+            Some(ctx) => tone_map::tone_map_pixels_on(
+                ctx,
+                &scrgb,
+                self.width,
+                self.height,
+                self.sdr_white_nits,
+                operator,
+            )?,
+            None => tone_map::tone_map_pixels(
+                &scrgb,
+                self.width,
+                self.height,
+                self.sdr_white_nits,
+                operator,
+            )?,
+        };
+        // The RGBA16F / RGB10A2-sized pool buffer always has room for the BGRA8 result.
+        let mut pooled = self.data.pool.acquire();
+        pooled.as_mut_slice()[..bgra.len()].copy_from_slice(&bgra);
+        let (mut bytes, group_idx, pool) = pooled.into_parts();
+        bytes.truncate(bgra.len());
+        Ok(CapturedFrame {
+            data: Arc::new(SharedFrameData {
+                bytes,
+                pool,
+                group_idx,
+            }),
+            format: ColorPixelFormat::Bgra8,
+            ..self.clone()
+        })
+    }
+
+    /// Pixels as tightly packed RGBA8 with straight alpha, e.g. for
+    /// `image::RgbaImage::from_raw`.
+    ///
+    /// HDR frames are tone-mapped with the default operator (see
+    /// [`to_sdr`](Self::to_sdr)). With `AlphaMode::Ignore` alpha is 255.
+    pub fn to_rgba8(&self) -> Result<Vec<u8>> {
+        let sdr = self.to_sdr()?.unpremultiply();
+        Ok(convert::bgra8_to_rgba8(
+            sdr.data.as_slice(),
+            sdr.alpha == AlphaMode::Ignore,
+        ))
+    }
+
+    /// Pixels as tightly packed RGB8 (alpha dropped), e.g. for
+    /// `image::RgbImage::from_raw`.
+    ///
+    /// HDR frames are tone-mapped with the default operator (see
+    /// [`to_sdr`](Self::to_sdr)).
+    pub fn to_rgb8(&self) -> Result<Vec<u8>> {
+        let sdr = self.to_sdr()?.unpremultiply();
+        Ok(convert::bgra8_to_rgb8(sdr.data.as_slice()))
+    }
+
+    /// Put the frame on the Windows clipboard (as a bitmap and as PNG).
+    ///
+    /// HDR frames are tone-mapped to SDR first (see [`to_sdr`](Self::to_sdr)),
+    /// which creates a temporary D3D11 device; while a pipeline is open use
+    /// [`CapturePipeline::copy_to_clipboard`](super::CapturePipeline::copy_to_clipboard)
+    /// to tone-map on its device. Premultiplied alpha is converted to straight.
+    pub fn copy_to_clipboard(&self) -> Result<()> {
+        self.copy_to_clipboard_on(None)
+    }
+
+    /// [`copy_to_clipboard`](Self::copy_to_clipboard), tone-mapping on `ctx`
+    /// when given.
+    pub(super) fn copy_to_clipboard_on(&self, ctx: Option<&D3D11Context>) -> Result<()> {
+        let sdr = self
+            .to_bgra8_on(ctx, ToneMapOperator::default())?
+            .unpremultiply();
+        let png = sdr.encode(ImageFormat::Png)?;
+        clipboard::set_image(sdr.data.as_slice(), sdr.width, sdr.height, sdr.alpha, &png)
     }
 
     /// Save frame to file.
     ///
     /// Format is determined by file extension:
-    /// - `.png` `.bmp` `.jpg` `.tiff` - standard formats (BGRA8 only)
+    /// - `.png` `.bmp` `.jpg` `.tiff` `.webp` - standard formats (BGRA8 only;
+    ///   HDR frames are tone-mapped first, see [`to_sdr`](Self::to_sdr))
     /// - `.jxr` - JPEG XR (both BGRA8 and RGBA16F)
+    /// - `.heic` `.heif` - HEIF (BGRA8, or RGBA16F as 10-bit HDR10)
+    ///
+    /// PNG / JPEG / WebP output embeds `color_profile`. PNG / BMP / TIFF / WebP / EXR keep the
+    /// alpha channel only when `alpha` is not `Ignore`; it is saved as stored
+    /// (see [`unpremultiply`](Self::unpremultiply)).
     pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        crate::image::save(
-            path.as_ref(),
+        self.save_with_profile(path, &self.color_profile)
+    }
+
+    /// Like [`save`](Self::save), but fail instead of tone-mapping when an
+    /// HDR frame is saved to an SDR-only format.
+    ///
+    /// For pipelines that must never write a lossy SDR rendition by accident.
+    pub fn save_strict(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.save_impl(path.as_ref(), &SaveOptions::default(), true)
+    }
+
+    /// Save into `dir` under a timestamped name, e.g.
+    /// `shot_2024-06-01_12-30-45-123.png` for prefix `"shot"` and ext `"png"`.
+    ///
+    /// The name uses the local time of [`captured_at`](Self::captured_at).
+    /// An empty `prefix` starts the name with the date. When the name is
+    /// taken (several frames in one millisecond) `_1`, `_2`, … is appended.
+    /// `dir` is created if missing. Returns the path written.
+    pub fn save_auto(&self, dir: impl AsRef<Path>, prefix: &str, ext: &str) -> Result<PathBuf> {
+        let ext = ext.trim_start_matches('.');
+        let stamp = local_time_stamp(self.captured_at())?;
+        let base = if prefix.is_empty() {
+            stamp
+        } else {
+            format!("{prefix}_{stamp}")
+        };
+        let dir = dir.as_ref();
+        let mut path = dir.join(format!("{base}.{ext}"));
+        for n in 1.. {
+            if !path.exists() {
+                break;
+            }
+            path = dir.join(format!("{base}_{n}.{ext}"));
+        }
+        self.save(&path)?;
+        Ok(path)
+    }
+
+    /// Wall-clock time the frame was captured, converted from `timestamp`.
+    ///
+    /// Frames without a capture timestamp (0, e.g. composites) report the
+    /// current time.
+    pub fn captured_at(&self) -> SystemTime {
+        let now = SystemTime::now();
+        let age = qpc_seconds().map(|qpc_now| qpc_now - self.timestamp);
+        match age {
+            Some(age) if self.timestamp > 0.0 && age.is_finite() && age >= 0.0 => {
+                now.checked_sub(Duration::from_secs_f64(age)).unwrap_or(now)
+            }
+            _ => now,
+        }
+    }
+
+    /// Encode the frame in memory, e.g. to stream over HTTP without a disk
+    /// round-trip. Same format support and tagging as [`save`](Self::save).
+    pub fn encode(&self, format: ImageFormat) -> Result<Vec<u8>> {
+        self.encode_with(format, &SaveOptions::default())
+    }
+
+    /// [`encode`](Self::encode) with explicit encoder settings and metadata,
+    /// applied like [`save_with`](Self::save_with).
+    pub fn encode_with(&self, format: ImageFormat, options: &SaveOptions) -> Result<Vec<u8>> {
+        self.encode_impl(format, options, false)
+    }
+
+    /// [`encode_with`](Self::encode_with); `strict` keeps HDR frames from being
+    /// tone-mapped for SDR-only formats, like [`save_strict`](Self::save_strict).
+    pub(crate) fn encode_impl(
+        &self,
+        format: ImageFormat,
+        options: &SaveOptions,
+        strict: bool,
+    ) -> Result<Vec<u8>> {
+        if !strict && format.is_sdr_only() && self.format != ColorPixelFormat::Bgra8 {
+            return self.to_sdr()?.encode_impl(format, options, true);
+        }
+        self.check_encoder_color_space(format)?;
+        crate::image::encode_with(
+            format,
+            self.data.as_slice(),
+            self.width,
+            self.height,
+            self.format,
+            self.alpha,
+            &self.frame_options(options),
+        )
+    }
+
+    /// Save frame to file, embedding `profile` instead of the frame's own tag.
+    pub fn save_with_profile(&self, path: impl AsRef<Path>, profile: &ColorProfile) -> Result<()> {
+        let options = SaveOptions {
+            profile: Some(profile.clone()),
+            ..Default::default()
+        };
+        self.save_with(path, &options)
+    }
+
+    /// Save frame to file with explicit encoder settings and provenance
+    /// metadata (see [`SaveOptions`]; unset fields keep the defaults of
+    /// [`save`](Self::save)).
+    ///
+    /// `options.profile` defaults to the frame's `color_profile`. For
+    /// metadata, see [`CapturePipeline::save_metadata`](super::CapturePipeline::save_metadata).
+    pub fn save_with(&self, path: impl AsRef<Path>, options: &SaveOptions) -> Result<()> {
+        self.save_impl(path.as_ref(), options, false)
+    }
+
+    /// [`save_with`](Self::save_with); `strict` keeps HDR frames from being
+    /// tone-mapped for SDR-only formats, so the encoder rejects them.
+    pub(crate) fn save_impl(&self, path: &Path, options: &SaveOptions, strict: bool) -> Result<()> {
+        if let Some(format) = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ImageFormat::from_extension)
+        {
+            if !strict && format.is_sdr_only() && self.format != ColorPixelFormat::Bgra8 {
+                let sdr = self.to_sdr()?;
+                return sdr.save_impl(path, options, true);
+            }
+            self.check_encoder_color_space(format)?;
+        }
+        crate::image::save_with(
+            path,
             self.data.as_slice(),
             self.width,
             self.height,
             self.format,
+            self.alpha,
+            &self.frame_options(options),
         )
     }
+
+    /// `options` completed from the frame: its `color_profile` when they set
+    /// none, and its `color_space` for the EXR chromaticities.
+    fn frame_options<'a>(&self, options: &'a SaveOptions) -> Cow<'a, SaveOptions> {
+        if options.profile.is_some() && options.exr.color_space == self.color_space {
+            return Cow::Borrowed(options);
+        }
+        Cow::Owned(SaveOptions {
+            profile: Some(
+                options
+                    .profile
+                    .clone()
+                    .unwrap_or_else(|| self.color_profile.clone()),
+            ),
+            exr: ExrOptions {
+                color_space: self.color_space,
+                ..options.exr
+            },
+            ..options.clone()
+        })
+    }
+
+    /// JPEG XR, HEIF and Radiance interpret RGBA16F as scRGB; other encodings
+    /// would be silently mis-rendered.
+    fn check_encoder_color_space(&self, format: ImageFormat) -> Result<()> {
+        if self.format == ColorPixelFormat::Rgba16f
+            && self.color_space != OutputColorSpace::ScrgbLinear
+            && matches!(
+                format,
+                ImageFormat::Jxr | ImageFormat::Heif | ImageFormat::Hdr
+            )
+        {
+            bail!(
+                ".{} stores RGBA16F as scRGB; '{}' frames can only be saved as .exr",
+                format.extension(),
+                self.color_space.name()
+            );
+        }
+        Ok(())
+    }
 }
 
+/// Pooled pixel buffer behind [`CapturedFrame::data`].
+///
+/// Returned to its [`ElasticBufferPool`] on drop; the pool is mutex-guarded,
+/// so release is safe from any thread.
 pub struct SharedFrameData {
     pub(super) bytes: Vec<u8>,
     pub(super) pool: Arc<ElasticBufferPool>,
@@ -96,3 +630,213 @@ pub(super) struct RawFrame {
     pub(super) timestamp: f64,
     pub(super) format: ColorPixelFormat,
 }
+
+/// What `process_and_cache` reads back of a new frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Readback {
+    /// The full frame, cached for the static-content fallback.
+    Full,
+    /// Every `n`-th row and column (`preview()`).
+    Subsampled(u32),
+    /// The mip level whose sides fit in `n` pixels (`thumbnail()`).
+    Mip(u32),
+}
+
+/// Final GPU texture of a frame, before readback.
+#[derive(Clone)]
+pub(super) struct GpuFrame {
+    pub(super) texture: ID3D11Texture2D,
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) timestamp: f64,
+    pub(super) format: ColorPixelFormat,
+    /// Format the frame was captured in (RGBA16F when tone-mapped from HDR).
+    pub(super) source_format: ColorPixelFormat,
+    pub(super) color_space: OutputColorSpace,
+}
+
+/// Current QPC time in seconds, the clock frame timestamps use.
+fn qpc_seconds() -> Option<f64> {
+    let (mut counter, mut frequency) = (0i64, 0i64);
+    // SAFETY: both calls write to caller-provided i64s.
+    unsafe {
+        QueryPerformanceCounter(&mut counter).ok()?;
+        QueryPerformanceFrequency(&mut frequency).ok()?;
+    }
+    (frequency > 0).then(|| counter as f64 / frequency as f64)
+}
+
+/// `time` in the local time zone as `YYYY-MM-DD_hh-mm-ss-mmm`.
+fn local_time_stamp(time: SystemTime) -> Result<String> {
+    // FILETIME counts 100 ns ticks since 1601-01-01 UTC.
+    const UNIX_EPOCH_TICKS: u64 = 116_444_736_000_000_000;
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let ticks = UNIX_EPOCH_TICKS + (since_epoch.as_nanos() / 100) as u64;
+    let file_time = FILETIME {
+        dwLowDateTime: ticks as u32,
+        dwHighDateTime: (ticks >> 32) as u32,
+    };
+    let (mut utc, mut local) = (SYSTEMTIME::default(), SYSTEMTIME::default());
+    // SAFETY: all pointers are to initialized locals; no time zone argument
+    // means the current one.
+    unsafe {
+        FileTimeToSystemTime(&file_time, &mut utc).context("FileTimeToSystemTime failed")?;
+        SystemTimeToTzSpecificLocalTime(None, &utc, &mut local)
+            .context("SystemTimeToTzSpecificLocalTime failed")?;
+    }
+    Ok(format_stamp(&local))
+}
+
+fn format_stamp(t: &SYSTEMTIME) -> String {
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}-{:03}",
+        t.wYear, t.wMonth, t.wDay, t.wHour, t.wMinute, t.wSecond, t.wMilliseconds
+    )
+}
+
+/// `width` × `height` frame of `data` for unit tests, with sRGB metadata
+/// and no warning. Owns a pool of its own.
+#[cfg(test)]
+pub(super) fn test_frame(
+    width: u32,
+    height: u32,
+    format: ColorPixelFormat,
+    data: &[u8],
+) -> CapturedFrame {
+    let pool = ElasticBufferPool::new(data.len());
+    let (mut bytes, group_idx, pool) = pool.acquire().into_parts();
+    bytes.truncate(data.len());
+    bytes.copy_from_slice(data);
+    CapturedFrame {
+        data: Arc::new(SharedFrameData {
+            bytes,
+            pool,
+            group_idx,
+        }),
+        width,
+        height,
+        timestamp: 0.0,
+        format,
+        color_profile: ColorProfile::Srgb,
+        color_space: OutputColorSpace::ScrgbLinear,
+        source_color_space: DisplayColorSpace::Srgb,
+        bits_per_channel: 8,
+        sdr_white_nits: 80.0,
+        dpi_scale: 1.0,
+        warning: None,
+        alpha: AlphaMode::Ignore,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_stamp_zero_pads() {
+        let t = SYSTEMTIME {
+            wYear: 2024,
+            wMonth: 6,
+            wDay: 1,
+            wHour: 9,
+            wMinute: 5,
+            wSecond: 7,
+            wMilliseconds: 23,
+            ..Default::default()
+        };
+        assert_eq!(format_stamp(&t), "2024-06-01_09-05-07-023");
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_captured_frame_is_send_sync() {
+        assert_send_sync::<CapturedFrame>();
+        assert_send_sync::<SharedFrameData>();
+        assert_send_sync::<ElasticBufferPool>();
+    }
+
+    #[test]
+    fn test_frame_dropped_on_other_thread_returns_to_pool() {
+        let frame = test_frame(2, 2, ColorPixelFormat::Bgra8, &[0; 16]);
+        let pool = Arc::clone(&frame.data.pool);
+        let free_before = pool.stats().free_frames;
+
+        std::thread::spawn(move || drop(frame)).join().unwrap();
+        assert_eq!(pool.stats().free_frames, free_before + 1);
+    }
+
+    #[test]
+    fn test_to_rgba8_and_rgb8_unpremultiply_and_swap() {
+        // Premultiplied half-transparent blue, then opaque red.
+        let pixels = [128, 0, 0, 128, 0, 0, 255, 255];
+        let frame = CapturedFrame {
+            alpha: AlphaMode::Premultiplied,
+            ..test_frame(2, 1, ColorPixelFormat::Bgra8, &pixels)
+        };
+
+        assert_eq!(frame.to_rgba8().unwrap(), [0, 0, 255, 128, 255, 0, 0, 255]);
+        assert_eq!(frame.to_rgb8().unwrap(), [0, 0, 255, 255, 0, 0]);
+    }
+
+    #[test]
+    fn test_is_blank_ignores_alpha_and_honors_threshold() {
+        let frame = |pixels: &[u8], format| test_frame(2, 1, format, pixels);
+
+        let black = frame(&[0, 0, 0, 255, 0, 0, 0, 255], ColorPixelFormat::Bgra8);
+        assert!(black.is_blank(0.0));
+        let dim = frame(&[0, 0, 0, 255, 0, 5, 0, 255], ColorPixelFormat::Bgra8);
+        assert!(!dim.is_blank(0.0));
+        assert!(dim.is_blank(0.02));
+
+        let half = f16::from_f32(0.5).to_le_bytes();
+        let mut hdr = vec![0u8; 16];
+        hdr[10..12].copy_from_slice(&half);
+        let hdr = frame(&hdr, ColorPixelFormat::Rgba16f);
+        assert!(!hdr.is_blank(0.1));
+        assert!(hdr.is_blank(0.5));
+
+        let hdr10 = frame(
+            &hdr10::pack([0, 0, 0, 3]).repeat(2),
+            ColorPixelFormat::Rgb10a2,
+        );
+        assert!(hdr10.is_blank(0.0));
+    }
+
+    #[test]
+    fn test_pixel_reads_rgba_and_nits() {
+        let frame = |pixels: &[u8], format, width| test_frame(width, 1, format, pixels);
+
+        let sdr = frame(&[1, 2, 3, 255, 10, 20, 30, 40], ColorPixelFormat::Bgra8, 2);
+        assert_eq!(sdr.pixel(1, 0).unwrap(), Pixel::Sdr([30, 20, 10, 40]));
+        assert!(sdr.pixel(2, 0).is_err());
+        assert!(sdr.pixel(0, 1).is_err());
+
+        let hdr: Vec<u8> = [2.5f32, 1.0, 0.0, 1.0]
+            .iter()
+            .flat_map(|&v| f16::from_f32(v).to_le_bytes())
+            .collect();
+        let hdr = frame(&hdr, ColorPixelFormat::Rgba16f, 1);
+        assert_eq!(
+            hdr.pixel(0, 0).unwrap(),
+            Pixel::Hdr([200.0, 80.0, 0.0, 1.0])
+        );
+
+        let white = hdr10::scrgb_to_pixel([1.0, 1.0, 1.0, 1.0]);
+        let hdr10 = frame(&white, ColorPixelFormat::Rgb10a2, 1);
+        let Pixel::Hdr([r, g, b, a]) = hdr10.pixel(0, 0).unwrap() else {
+            panic!("HDR10 frames yield nits");
+        };
+        for nits in [r, g, b] {
+            assert!((nits - 80.0).abs() < 1.0, "{nits}");
+        }
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn test_pixel_rejects_short_buffer() {
+        let short = test_frame(2, 1, ColorPixelFormat::Bgra8, &[1, 2, 3, 255]);
+        assert!(short.pixel(0, 0).is_err());
+        assert!(short.pixel(1, 0).is_err());
+    }
+}
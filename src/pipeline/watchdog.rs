@@ -0,0 +1,143 @@
+use super::*;
+
+/// Frame-starvation watchdog state (see [`CapturePipelineBuilder::watchdog`]).
+///
+/// Static content delivers no frames either, so silence alone proves
+/// nothing; the timer only runs while a frame is owed.
+pub(super) struct Watchdog {
+    timeout: Duration,
+    /// Session frame count at the last check.
+    frames_seen: usize,
+    /// Since when a frame has been expected without one arriving.
+    expected_since: Option<Instant>,
+}
+
+impl Watchdog {
+    pub(super) fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            frames_seen: 0,
+            expected_since: None,
+        }
+    }
+
+    /// Note that a frame should have arrived by now (a failed wait, a display
+    /// change). Keeps the earlier time when one is already pending.
+    pub(super) fn expect_frame(&mut self, since: Instant) {
+        self.expected_since.get_or_insert(since);
+    }
+
+    /// Record the session's frame count; true once an expected frame has
+    /// been missing for `timeout`.
+    fn starved(&mut self, frames_received: usize) -> bool {
+        if frames_received != self.frames_seen {
+            self.reset(frames_received);
+            return false;
+        }
+        self.expected_since
+            .is_some_and(|since| since.elapsed() >= self.timeout)
+    }
+
+    /// Forget pending expectations as of the given session frame count.
+    pub(super) fn reset(&mut self, frames_received: usize) {
+        self.frames_seen = frames_received;
+        self.expected_since = None;
+    }
+}
+
+impl CapturePipeline {
+    /// Restart the WGC session when a frame it owed has been missing for the
+    /// watchdog timeout.
+    ///
+    /// A frame is owed after a wait for one timed out (`hard_wait_frame`) or
+    /// the display configuration changed, which makes DWM redraw. Quiet
+    /// periods without either are static content and never trip it.
+    /// Called at the start of `next_frame()`. The new session
+    /// replaces the old one in place (same device, target and session
+    /// options); the next processed frame carries
+    /// `CaptureWarning::SessionRestarted`.
+    pub(super) fn check_watchdog(&mut self) -> Result<()> {
+        let Some(watchdog) = self.watchdog.as_mut() else {
+            return Ok(());
+        };
+        if !matches!(self.capture, CaptureSource::Wgc(_)) {
+            return Ok(());
+        }
+        // A minimized window legitimately stops producing frames.
+        if self.capture.is_minimized() {
+            watchdog.reset(self.capture.frames_received());
+            return Ok(());
+        }
+        if !watchdog.starved(self.capture.frames_received()) {
+            return Ok(());
+        }
+
+        log::warn!(
+            "no frame from the capture session for {:?}; restarting it",
+            watchdog.timeout
+        );
+        let capture = init_capture(
+            &self.d3d_ctx,
+            self.target,
+            self.config.policy,
+            &self.config.session,
+        )
+        .context("failed to restart starved capture session")?;
+        capture.start()?;
+
+        // Keep session counters cumulative across the restart, including the
+        // frames the old session still had queued.
+        self.capture.close();
+        let (received, flushed, recreates) = (
+            self.capture.frames_received(),
+            self.capture.frames_flushed(),
+            self.capture.pool_recreates(),
+        );
+        self.count(|s| {
+            s.frames_received += received;
+            s.frames_dropped_on_drain += flushed;
+            s.pool_recreates += recreates;
+            s.session_restarts += 1;
+        });
+        self.capture = CaptureSource::Wgc(capture);
+        self.first_call = true;
+        self.force_fresh = false;
+        self.pending_warning = Some(CaptureWarning::SessionRestarted);
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            watchdog.reset(0);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_fires_only_for_missing_expected_frames() {
+        let mut watchdog = Watchdog::new(Duration::from_millis(20));
+        assert!(!watchdog.starved(0));
+        assert!(!watchdog.starved(3));
+
+        // Static content: no frames, nothing expected.
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!watchdog.starved(3));
+
+        watchdog.expect_frame(Instant::now());
+        assert!(!watchdog.starved(3));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(watchdog.starved(3));
+
+        // A frame arriving settles the expectation.
+        assert!(!watchdog.starved(4));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!watchdog.starved(4));
+
+        // A failed wait that already lasted the timeout trips right away.
+        watchdog.expect_frame(Instant::now() - Duration::from_millis(30));
+        assert!(watchdog.starved(4));
+        watchdog.reset(0);
+        assert!(!watchdog.starved(0));
+    }
+}
@@ -1,8 +1,10 @@
 // PyO3 Python binding layer
 //
-// Two PyClasses:
+// PyClasses:
 // - CapturedFrame: frame container, holds pixel data, provides save() and numpy conversion
 // - Capture: reusable pipeline, delegates to a dedicated worker thread via channels
+// - FrameIterator: `capture.frames()` streaming iterator, calls grab() per step
+// - ReplayBuffer: rolling buffer of the last N seconds, captured on its own thread
 //
 // Worker thread architecture:
 // - All D3D11/COM/WGC resources live on a single worker thread (thread-affine)
@@ -10,16 +12,27 @@
 // - This eliminates the unsendable panic: Capture can be freely shared across Python threads,
 //   passed to atexit handlers, or dropped from any thread without triggering PyO3 assertions.
 
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 
-use self::api::screenshot;
-use self::capture::Capture;
+use self::api::{save_batch, screenshot, supported_formats, watch_display_changes};
+use self::capture::{Capture, FrameIterator};
 use self::frame::CapturedFrame;
+use self::replay::ReplayBuffer;
+
+create_exception!(
+    hdrcapture,
+    ProtectedContentError,
+    PyRuntimeError,
+    "Window content is protected (DRM / HDCP or excluded from capture) and captures as black."
+);
 
 mod api;
 mod capture;
 mod frame;
 mod helpers;
+mod replay;
 mod worker;
 
 /// HDR-aware screen capture library for Windows
@@ -27,6 +40,15 @@ mod worker;
 fn hdrcapture(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CapturedFrame>()?;
     m.add_class::<Capture>()?;
+    m.add_class::<FrameIterator>()?;
+    m.add_class::<ReplayBuffer>()?;
+    m.add(
+        "ProtectedContentError",
+        m.py().get_type::<ProtectedContentError>(),
+    )?;
     m.add_function(wrap_pyfunction!(screenshot, m)?)?;
+    m.add_function(wrap_pyfunction!(supported_formats, m)?)?;
+    m.add_function(wrap_pyfunction!(watch_display_changes, m)?)?;
+    m.add_function(wrap_pyfunction!(save_batch, m)?)?;
     Ok(())
 }
@@ -1,11 +1,18 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
-use super::capture::Capture;
 use super::frame::CapturedFrame;
+use super::helpers::{parse_mode, warn, warn_mode_mismatch};
+use super::worker::run_once;
+use crate::pipeline::CapturePipelineBuilder;
 
 /// One-liner screenshot: capture monitor or window
 ///
-/// Internally creates and destroys pipeline, cold start ~79ms.
+/// Captures exactly one frame on a single-use pipeline, cold start ~79ms.
+/// The frame is returned before the capture session is torn down.
 /// For multiple screenshots, use capture class to reuse the pipeline.
 ///
 /// Args:
@@ -14,13 +21,16 @@ use super::frame::CapturedFrame;
 ///     pid: Process id for window capture
 ///     hwnd: Window handle for window capture
 ///     index: Ranked window index within candidate windows
-///     mode: Capture mode — "auto", "hdr", or "sdr"
+///     mode: Capture mode — "auto", "hdr", "sdr", or "prefer_hdr"
 ///     headless: Crop title bar and borders for window capture, defaults to true
+///     border: Keep the yellow capture border visible, defaults to false
+///     lut: Path to a 3D .cube LUT applied to SDR output
 ///
 /// Returns:
 ///     CapturedFrame: Frame container, can save() or convert to numpy
 #[pyfunction]
-#[pyo3(signature = (monitor=0, window=None, pid=None, hwnd=None, index=None, mode="auto", headless=true))]
+#[pyo3(signature = (monitor=0, window=None, pid=None, hwnd=None, index=None, mode="auto", headless=true, border=false, lut=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn screenshot(
     py: Python<'_>,
     monitor: usize,
@@ -30,24 +40,96 @@ pub(crate) fn screenshot(
     index: Option<usize>,
     mode: &str,
     headless: bool,
+    border: bool,
+    lut: Option<String>,
 ) -> PyResult<CapturedFrame> {
-    // Reuse the exact same capture workflow as `capture` class methods:
-    // create -> capture one frame -> close.
-    let mut cap = if window.is_some() || pid.is_some() || hwnd.is_some() {
-        Capture::window(
-            py,
-            window.map(str::to_string),
-            pid,
-            hwnd,
-            index,
-            mode,
-            headless,
-        )?
+    let policy = parse_mode(mode)?;
+
+    if hwnd.is_some() && (pid.is_some() || window.is_some()) {
+        warn(py, "'hwnd' provided; ignoring 'pid' and 'window'.")?;
+    } else if pid.is_some() && window.is_some() {
+        warn(py, "'pid' provided; ignoring 'window'.")?;
+    }
+
+    let builder = CapturePipelineBuilder::new();
+    let builder = if let Some(hwnd) = hwnd {
+        builder.window_hwnd(hwnd).window_index(index)
+    } else if let Some(pid) = pid {
+        builder.window_pid(pid).window_index(index)
+    } else if let Some(window) = window {
+        builder.window_process(window).window_index(index)
     } else {
-        Capture::monitor(py, monitor, mode)?
+        builder.monitor(monitor)
     };
+    let builder = match lut {
+        Some(lut) => builder.lut(lut),
+        None => builder,
+    };
+    let builder = builder
+        .policy(policy)
+        .headless(headless)
+        .show_border(border);
+
+    let (frame, is_hdr) = py
+        .detach(|| run_once(builder))
+        .map_err(PyRuntimeError::new_err)?;
+    warn_mode_mismatch(py, policy, is_hdr)?;
+    Ok(CapturedFrame { inner: frame })
+}
+
+/// List output formats and whether they work on this system.
+///
+/// Codec-dependent formats (.jxr, .heic) are probed once per process.
+///
+/// Returns:
+///     list[dict]: one dict per format with ``ext``, ``supports_hdr``,
+///       ``supports_sdr`` and ``available``
+#[pyfunction]
+pub(crate) fn supported_formats(py: Python<'_>) -> PyResult<Vec<Bound<'_, PyDict>>> {
+    let formats = py.detach(crate::image::supported_formats);
+    formats
+        .into_iter()
+        .map(|info| {
+            let dict = PyDict::new(py);
+            dict.set_item("ext", info.ext)?;
+            dict.set_item("supports_hdr", info.supports_hdr)?;
+            dict.set_item("supports_sdr", info.supports_sdr)?;
+            dict.set_item("available", info.available)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// Follow display setting changes in running captures
+///
+/// Starts a background listener for display configuration changes. Afterwards,
+/// captures re-read HDR state and SDR white level on their next call whenever
+/// the user changes display settings. Calling it again is a no-op.
+#[pyfunction]
+pub(crate) fn watch_display_changes() -> PyResult<()> {
+    crate::capture::watch_display_changes().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
 
-    let result = cap.capture(py);
-    cap.close(py);
-    result
+/// Save many frames in parallel
+///
+/// Encodes on a small pool of threads (one per CPU core) with the GIL released.
+///
+/// Args:
+///     items: (frame, path) pairs; format is chosen by each path's extension
+///
+/// Returns:
+///     list[str | None]: per item, None on success or the error message
+#[pyfunction]
+pub(crate) fn save_batch<'py>(
+    py: Python<'py>,
+    items: Vec<(PyRef<'py, CapturedFrame>, PathBuf)>,
+) -> Vec<Option<String>> {
+    let frames = items
+        .into_iter()
+        .map(|(frame, path)| (frame.inner.clone(), path))
+        .collect();
+    py.detach(|| crate::pipeline::save_batch(frames))
+        .into_iter()
+        .map(|result| result.err().map(|e| format!("{e:#}")))
+        .collect()
 }
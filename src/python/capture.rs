@@ -1,19 +1,25 @@
 use std::sync::{mpsc, Mutex};
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyTimeoutError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
 use super::frame::CapturedFrame;
-use super::helpers::{parse_mode, warn_mode_mismatch};
-use super::worker::{spawn_worker, Command, Response};
-use crate::pipeline;
+use super::helpers::{
+    frame_error, parse_backend, parse_color_space, parse_fit, parse_format, parse_frame_mode,
+    parse_mode, parse_seconds, parse_timeout, parse_transform, warn, warn_mode_mismatch,
+};
+use super::worker::{spawn_worker, Command, FrameSource, Response, WorkerHandle, MAX_IN_FLIGHT};
+use crate::pipeline::{CapturePipeline, CapturePipelineBuilder, FrameMode, SdrAdjust};
 
 /// Screen/window capture pipeline
 ///
 /// Construct via class methods:
 ///   cap = capture.monitor(0)
 ///   cap = capture.window("notepad.exe")
+///   cap = capture.desktop()
 ///
 /// Supports context manager:
 ///   with capture.monitor(0) as cap:
@@ -21,26 +27,133 @@ use crate::pipeline;
 ///
 /// Thread-safe: can be shared across Python threads, passed to atexit handlers,
 /// or dropped from any thread without panic.
+///
+/// Consumer model: the pipeline lives on a dedicated worker thread. Every
+/// call sends one command and blocks (GIL released) until the worker answers
+/// it; concurrent callers are served one at a time. Calls that time out leave
+/// their command running, and once `MAX_IN_FLIGHT` are pending the next call
+/// waits for the oldest, so a slow consumer gets backpressure instead of a
+/// growing queue.
 #[pyclass(name = "capture")]
 pub(crate) struct Capture {
-    cmd_tx: Option<mpsc::Sender<Command>>,
-    resp_rx: Option<Mutex<mpsc::Receiver<Response>>>,
+    cmd_tx: Option<mpsc::SyncSender<Command>>,
+    resp_rx: Option<Mutex<ResponseChannel>>,
     handle: Option<JoinHandle<()>>,
+    /// Starts a worker for the target and options the capture was created
+    /// with; run again by `reopen()`.
+    open: Opener,
 }
 
-impl Capture {
-    fn warn(py: Python<'_>, message: &str) -> PyResult<()> {
-        let warnings = py.import("warnings")?;
-        warnings.call_method1("warn", (message,))?;
+/// Spawns a capture worker from stored construction arguments.
+type Opener = Box<dyn Fn() -> Result<WorkerHandle, String> + Send + Sync>;
+
+/// Wrap a pipeline constructor into an [`Opener`] that can be run repeatedly.
+fn opener<P, F>(build: F) -> Opener
+where
+    P: FrameSource + 'static,
+    F: Fn() -> anyhow::Result<P> + Clone + Send + Sync + 'static,
+{
+    Box::new(move || {
+        let build = build.clone();
+        spawn_worker(Box::new(move || build()))
+    })
+}
+
+/// Worker response receiver that tolerates abandoned (timed-out) calls.
+///
+/// The worker answers commands in order, so after a timeout the next
+/// `abandoned` responses belong to calls nobody waits for anymore and are
+/// discarded instead of being returned to a later caller.
+struct ResponseChannel {
+    rx: mpsc::Receiver<Response>,
+    abandoned: usize,
+}
+
+/// Why a worker call produced no response.
+enum RecvFailure {
+    Disconnected,
+    Timeout,
+}
+
+impl ResponseChannel {
+    fn new(rx: mpsc::Receiver<Response>) -> Self {
+        Self { rx, abandoned: 0 }
+    }
+
+    /// Next response from the worker, whoever it belongs to.
+    fn next(&self, deadline: Option<Instant>) -> Result<Response, RecvFailure> {
+        match deadline {
+            None => self.rx.recv().map_err(|_| RecvFailure::Disconnected),
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                self.rx.recv_timeout(remaining).map_err(|e| match e {
+                    mpsc::RecvTimeoutError::Timeout => RecvFailure::Timeout,
+                    mpsc::RecvTimeoutError::Disconnected => RecvFailure::Disconnected,
+                })
+            }
+        }
+    }
+
+    /// Wait for abandoned calls to finish until another command may be sent
+    /// without exceeding [`MAX_IN_FLIGHT`].
+    fn make_room(&mut self, deadline: Option<Instant>) -> Result<(), RecvFailure> {
+        while self.abandoned + 1 > MAX_IN_FLIGHT {
+            self.next(deadline)?;
+            self.abandoned -= 1;
+        }
         Ok(())
     }
 
+    /// Receive the response to the most recently sent command.
+    fn recv(&mut self, deadline: Option<Instant>) -> Result<Response, RecvFailure> {
+        loop {
+            let resp = match self.next(deadline) {
+                Err(RecvFailure::Timeout) => {
+                    self.abandoned += 1;
+                    return Err(RecvFailure::Timeout);
+                }
+                other => other?,
+            };
+            if self.abandoned == 0 {
+                return Ok(resp);
+            }
+            self.abandoned -= 1;
+        }
+    }
+}
+
+impl Capture {
+    /// Spawn the worker and wrap its channels.
+    fn start(open: Opener) -> PyResult<Self> {
+        let (cmd_tx, resp_rx, handle) = open().map_err(PyRuntimeError::new_err)?;
+        Ok(Capture {
+            cmd_tx: Some(cmd_tx),
+            resp_rx: Some(Mutex::new(ResponseChannel::new(resp_rx))),
+            handle: Some(handle),
+            open,
+        })
+    }
+
+    fn call(&self, py: Python<'_>, cmd: Command) -> PyResult<Response> {
+        self.call_timeout(py, cmd, None)
+    }
+
     /// Send a command and unwrap the response, erroring if already closed.
     ///
+    /// Raises `TimeoutError` when `timeout` expires first; the late response
+    /// is discarded when it arrives, so the capture stays usable. With
+    /// [`MAX_IN_FLIGHT`] calls already abandoned, the call first waits (within
+    /// `timeout`) for the oldest one to finish before sending its command.
+    ///
     /// Releases the GIL before acquiring the Mutex to prevent deadlock:
     /// without this, thread A (holds Mutex, waits for GIL) and thread B
     /// (holds GIL, waits for Mutex) would deadlock.
-    fn call(&self, py: Python<'_>, cmd: Command) -> PyResult<Response> {
+    fn call_timeout(
+        &self,
+        py: Python<'_>,
+        cmd: Command,
+        timeout: Option<Duration>,
+    ) -> PyResult<Response> {
         let tx = self
             .cmd_tx
             .as_ref()
@@ -51,27 +164,41 @@ impl Capture {
             .ok_or_else(|| PyRuntimeError::new_err("Capture is closed"))?;
 
         // Release GIL before acquiring Mutex — consistent lock ordering prevents deadlock.
+        let deadline = timeout.map(|t| Instant::now() + t);
         let (send_ok, recv_result) = py.detach(|| {
-            let rx = rx_mutex.lock();
-            match rx {
-                Ok(rx) => match tx.send(cmd) {
-                    Ok(()) => (true, rx.recv().ok()),
-                    Err(_) => (false, None),
-                },
-                Err(_) => (false, None),
+            let Ok(mut rx) = rx_mutex.lock() else {
+                return (false, Err(RecvFailure::Disconnected));
+            };
+            if let Err(failure) = rx.make_room(deadline) {
+                return (true, Err(failure));
+            }
+            // Never blocks: at most MAX_IN_FLIGHT - 1 commands are outstanding.
+            match tx.send(cmd) {
+                Ok(()) => (true, rx.recv(deadline)),
+                Err(_) => (false, Err(RecvFailure::Disconnected)),
             }
         });
 
         if !send_ok {
             return Err(PyRuntimeError::new_err("Capture is closed"));
         }
-        recv_result.ok_or_else(|| PyRuntimeError::new_err("Worker thread exited unexpectedly"))
+        recv_result.map_err(|failure| match failure {
+            RecvFailure::Timeout => PyTimeoutError::new_err(format!(
+                "capture worker did not respond within {:.3}s",
+                timeout.unwrap_or_default().as_secs_f64()
+            )),
+            RecvFailure::Disconnected => {
+                PyRuntimeError::new_err("Worker thread exited unexpectedly")
+            }
+        })
     }
 
     /// Shut down the worker thread, optionally waiting for it to finish.
     fn shutdown(&mut self, join: bool) {
         if let Some(tx) = self.cmd_tx.take() {
-            let _ = tx.send(Command::Close);
+            // A full queue means abandoned calls are still running; the worker
+            // then exits on the disconnected response channel instead.
+            let _ = tx.try_send(Command::Close);
         }
         // Drop receiver so worker can detect disconnect if Close wasn't processed.
         self.resp_rx.take();
@@ -93,29 +220,134 @@ impl Drop for Capture {
 
 #[pymethods]
 impl Capture {
-    /// Create capture pipeline by monitor index
+    /// Create capture pipeline by monitor index or device name
     ///
     /// Args:
     ///     index: Monitor index, defaults to 0
-    ///     mode: Capture mode — "auto", "hdr", or "sdr"
+    ///     mode: Capture mode — "auto", "hdr", "sdr", or "prefer_hdr"
+    ///     name: Monitor GDI device name (e.g. "\\\\.\\DISPLAY1"), overrides index
+    ///     at: Virtual-desktop point (x, y); captures the monitor containing it, overrides index
+    ///     border: Keep the yellow capture border visible, defaults to false
+    ///     lut: Path to a 3D .cube LUT applied to SDR output
+    ///     size: Fixed (width, height) output size, scaled on the GPU
+    ///     fit: How `size` handles aspect ratio — "stretch" or "letterbox"
+    ///     max_size: Largest (width, height) output; bigger frames are scaled down
+    ///       on the GPU, keeping aspect ratio
+    ///     backend: Capture API — "auto", "wgc", or "duplication"
+    ///     color_space: mode="hdr" output encoding — "scrgb", "rec2020", or "srgb"
+    ///     sdr_white: SDR white level (nits) for tone-mapping, overrides the Windows setting
+    ///     brightness: Offset added to SDR output, -1.0 to 1.0, defaults to 0.0
+    ///     contrast: SDR output contrast around mid-gray, defaults to 1.0
+    ///     gamma: SDR output midtone gamma, >1.0 brightens, defaults to 1.0
+    ///     hdr10: Capture HDR as 10-bit HDR10 ("rgb10a2") instead of "rgba16f"
+    ///     format: Capture surface format — "bgra8", "rgba16f", or "rgb10a2" — instead of
+    ///       the one `mode` picks; `mode` still decides tone-mapping (WGC only)
+    ///     watchdog: Restart the capture session when a frame it owes (after a display
+    ///       change or a timed-out wait) is missing for this many seconds
+    ///     blank_threshold: Tag frames no brighter than this with warning "blank_frame"
+    ///       (protected / DRM content), off by default
+    ///     rotate: Rotate frames clockwise on the GPU — 0, 90, 180 or 270 degrees
+    ///     flip: Mirror frames after rotating — "horizontal" or "vertical"
+    ///     auto_rotate: Undo the monitor's display rotation (portrait / flipped modes)
     #[staticmethod]
-    #[pyo3(signature = (index=0, mode="auto"))]
-    pub(crate) fn monitor(py: Python<'_>, index: usize, mode: &str) -> PyResult<Self> {
+    #[pyo3(signature = (index=0, mode="auto", *, name=None, at=None, border=false, lut=None, size=None, fit="stretch", max_size=None, backend="auto", color_space="scrgb", sdr_white=None, brightness=0.0, contrast=1.0, gamma=1.0, hdr10=false, format=None, watchdog=None, blank_threshold=None, rotate=0, flip=None, auto_rotate=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn monitor(
+        py: Python<'_>,
+        index: usize,
+        mode: &str,
+        name: Option<String>,
+        at: Option<(i32, i32)>,
+        border: bool,
+        lut: Option<String>,
+        size: Option<(u32, u32)>,
+        fit: &str,
+        max_size: Option<(u32, u32)>,
+        backend: &str,
+        color_space: &str,
+        sdr_white: Option<f32>,
+        brightness: f32,
+        contrast: f32,
+        gamma: f32,
+        hdr10: bool,
+        format: Option<&str>,
+        watchdog: Option<f64>,
+        blank_threshold: Option<f32>,
+        rotate: i32,
+        flip: Option<&str>,
+        auto_rotate: bool,
+    ) -> PyResult<Self> {
+        if name.is_some() && at.is_some() {
+            return Err(PyValueError::new_err("name and at are mutually exclusive"));
+        }
         let policy = parse_mode(mode)?;
+        let fit = parse_fit(fit)?;
+        let backend = parse_backend(backend)?;
+        let color_space = parse_color_space(color_space)?;
+        let transform = parse_transform(rotate, flip)?;
+        let format = format.map(parse_format).transpose()?;
+        let adjust = SdrAdjust {
+            brightness,
+            contrast,
+            gamma,
+        };
+        let watchdog = watchdog
+            .map(|secs| parse_seconds("watchdog", secs))
+            .transpose()?;
 
-        let (cmd_tx, resp_rx, handle) = spawn_worker(Box::new(move || {
-            pipeline::CapturePipeline::monitor(index, policy)
-        }))
-        .map_err(PyRuntimeError::new_err)?;
+        let open = opener(move || {
+            let builder = CapturePipelineBuilder::new();
+            let builder = match (&name, at) {
+                (Some(name), _) => builder.monitor_name(name.clone()),
+                (None, Some((x, y))) => builder.monitor_at(x, y),
+                (None, None) => builder.monitor(index),
+            };
+            let builder = match &lut {
+                Some(lut) => builder.lut(lut.clone()),
+                None => builder,
+            };
+            let builder = match size {
+                Some((w, h)) => builder.resize_output(w, h, fit),
+                None => builder,
+            };
+            let builder = match max_size {
+                Some((w, h)) => builder.max_resolution(w, h),
+                None => builder,
+            };
+            let builder = match sdr_white {
+                Some(nits) => builder.sdr_white_nits(nits),
+                None => builder,
+            };
+            let builder = match watchdog {
+                Some(timeout) => builder.watchdog(timeout),
+                None => builder,
+            };
+            let builder = match blank_threshold {
+                Some(threshold) => builder.blank_detection(threshold),
+                None => builder,
+            };
+            let builder = match format {
+                Some(format) => builder.force_format(format),
+                None => builder,
+            };
+            builder
+                .policy(policy)
+                .backend(backend)
+                .show_border(border)
+                .output_color_space(color_space)
+                .sdr_adjust(adjust)
+                .hdr10(hdr10)
+                .transform(transform)
+                .auto_rotate(auto_rotate)
+                .build()
+        });
 
-        // Query is_hdr for mode mismatch warning.
-        let cap = Capture {
-            cmd_tx: Some(cmd_tx),
-            resp_rx: Some(Mutex::new(resp_rx)),
-            handle: Some(handle),
-        };
-        if let Ok(Response::Bool(is_hdr)) = cap.call(py, Command::IsHdr) {
-            warn_mode_mismatch(py, policy, is_hdr)?;
+        // Query is_hdr for mode mismatch warning; a forced format is deliberate.
+        let cap = Capture::start(open)?;
+        if format.is_none() {
+            if let Ok(Response::Bool(is_hdr)) = cap.call(py, Command::IsHdr) {
+                warn_mode_mismatch(py, policy, is_hdr)?;
+            }
         }
         Ok(cap)
     }
@@ -126,53 +358,188 @@ impl Capture {
     ///     process: Process name (e.g., "notepad.exe")
     ///     pid: Target process id
     ///     hwnd: Target window handle
+    ///     app_id: AppUserModelID of a packaged (UWP / Store) app, e.g.
+    ///       "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"
     ///     index: Ranked window index within candidate windows
-    ///     mode: Capture mode — "auto", "hdr", or "sdr"
+    ///     mode: Capture mode — "auto", "hdr", "sdr", or "prefer_hdr"
     ///     headless: Crop title bar and borders, defaults to true
+    ///     border: Keep the yellow capture border visible, defaults to false
+    ///     lut: Path to a 3D .cube LUT applied to SDR output
+    ///     crop: Client-relative (x, y, width, height) sub-rect to capture
+    ///     preserve_alpha: Keep per-pixel alpha (layered / transparent windows)
+    ///     size: Fixed (width, height) output size, scaled on the GPU
+    ///     fit: How `size` handles aspect ratio — "stretch" or "letterbox"
+    ///     max_size: Largest (width, height) output; bigger frames are scaled down
+    ///       on the GPU, keeping aspect ratio
+    ///     color_space: mode="hdr" output encoding — "scrgb", "rec2020", or "srgb"
+    ///     sdr_white: SDR white level (nits) for tone-mapping, overrides the Windows setting
+    ///     brightness: Offset added to SDR output, -1.0 to 1.0, defaults to 0.0
+    ///     contrast: SDR output contrast around mid-gray, defaults to 1.0
+    ///     gamma: SDR output midtone gamma, >1.0 brightens, defaults to 1.0
+    ///     hdr10: Capture HDR as 10-bit HDR10 ("rgb10a2") instead of "rgba16f"
+    ///     format: Capture surface format — "bgra8", "rgba16f", or "rgb10a2" — instead of
+    ///       the one `mode` picks; `mode` still decides tone-mapping (WGC only)
+    ///     include_popups: Also capture the window's menus, tooltips and other owned popups
+    ///       (Windows 11; ignored on older builds)
+    ///     watchdog: Restart the capture session when a frame it owes (after a display
+    ///       change or a timed-out wait) is missing for this many seconds
+    ///     blank_threshold: Tag frames no brighter than this with warning "blank_frame"
+    ///       (protected / DRM content), off by default
+    ///     detect_protected: Raise ProtectedContentError when the window's content is
+    ///       protected (DRM / HDCP or excluded from capture), defaults to false
+    ///     rotate: Rotate frames clockwise on the GPU — 0, 90, 180 or 270 degrees
+    ///     flip: Mirror frames after rotating — "horizontal" or "vertical"
+    ///     drop_stale_after_resize: Skip the first (possibly stale) frame after a resize,
+    ///       defaults to true; false saves ~1 VSync per resize
     #[staticmethod]
-    #[pyo3(signature = (process=None, *, pid=None, hwnd=None, index=None, mode="auto", headless=true))]
+    #[pyo3(signature = (process=None, *, pid=None, hwnd=None, app_id=None, index=None, mode="auto", headless=true, border=false, lut=None, crop=None, preserve_alpha=false, size=None, fit="stretch", max_size=None, color_space="scrgb", sdr_white=None, brightness=0.0, contrast=1.0, gamma=1.0, hdr10=false, format=None, include_popups=false, watchdog=None, blank_threshold=None, detect_protected=false, rotate=0, flip=None, drop_stale_after_resize=true))]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn window(
         py: Python<'_>,
         process: Option<String>,
         pid: Option<u32>,
         hwnd: Option<isize>,
+        app_id: Option<String>,
         index: Option<usize>,
         mode: &str,
         headless: bool,
+        border: bool,
+        lut: Option<String>,
+        crop: Option<(u32, u32, u32, u32)>,
+        preserve_alpha: bool,
+        size: Option<(u32, u32)>,
+        fit: &str,
+        max_size: Option<(u32, u32)>,
+        color_space: &str,
+        sdr_white: Option<f32>,
+        brightness: f32,
+        contrast: f32,
+        gamma: f32,
+        hdr10: bool,
+        format: Option<&str>,
+        include_popups: bool,
+        watchdog: Option<f64>,
+        blank_threshold: Option<f32>,
+        detect_protected: bool,
+        rotate: i32,
+        flip: Option<&str>,
+        drop_stale_after_resize: bool,
     ) -> PyResult<Self> {
         let policy = parse_mode(mode)?;
+        let fit = parse_fit(fit)?;
+        let color_space = parse_color_space(color_space)?;
+        let transform = parse_transform(rotate, flip)?;
+        let format = format.map(parse_format).transpose()?;
+        let adjust = SdrAdjust {
+            brightness,
+            contrast,
+            gamma,
+        };
+        let watchdog = watchdog
+            .map(|secs| parse_seconds("watchdog", secs))
+            .transpose()?;
 
-        if hwnd.is_none() && pid.is_none() && process.is_none() {
+        if hwnd.is_none() && pid.is_none() && app_id.is_none() && process.is_none() {
             return Err(PyRuntimeError::new_err(
-                "window requires one of: hwnd, pid, process",
+                "window requires one of: hwnd, pid, app_id, process",
             ));
         }
 
-        if hwnd.is_some() && (pid.is_some() || process.is_some()) {
-            Self::warn(py, "'hwnd' provided; ignoring 'pid' and 'process'.")?;
-        } else if pid.is_some() && process.is_some() {
-            Self::warn(py, "'pid' provided; ignoring 'process'.")?;
+        if hwnd.is_some() && (pid.is_some() || app_id.is_some() || process.is_some()) {
+            warn(
+                py,
+                "'hwnd' provided; ignoring 'pid', 'app_id' and 'process'.",
+            )?;
+        } else if pid.is_some() && (app_id.is_some() || process.is_some()) {
+            warn(py, "'pid' provided; ignoring 'app_id' and 'process'.")?;
+        } else if app_id.is_some() && process.is_some() {
+            warn(py, "'app_id' provided; ignoring 'process'.")?;
         }
 
-        let process_for_worker = process;
+        let open = opener(move || {
+            let builder = CapturePipelineBuilder::new();
+            let builder = if let Some(hwnd) = hwnd {
+                builder.window_hwnd(hwnd)
+            } else if let Some(pid) = pid {
+                builder.window_pid(pid)
+            } else if let Some(app_id) = &app_id {
+                builder.window_app_id(app_id.clone())
+            } else {
+                builder.window_process(process.clone().unwrap_or_default())
+            };
+            let builder = match &lut {
+                Some(lut) => builder.lut(lut.clone()),
+                None => builder,
+            };
+            let builder = match crop {
+                Some((x, y, w, h)) => builder.crop(x, y, w, h),
+                None => builder,
+            };
+            let builder = match size {
+                Some((w, h)) => builder.resize_output(w, h, fit),
+                None => builder,
+            };
+            let builder = match max_size {
+                Some((w, h)) => builder.max_resolution(w, h),
+                None => builder,
+            };
+            let builder = match sdr_white {
+                Some(nits) => builder.sdr_white_nits(nits),
+                None => builder,
+            };
+            let builder = match watchdog {
+                Some(timeout) => builder.watchdog(timeout),
+                None => builder,
+            };
+            let builder = match blank_threshold {
+                Some(threshold) => builder.blank_detection(threshold),
+                None => builder,
+            };
+            let builder = match format {
+                Some(format) => builder.force_format(format),
+                None => builder,
+            };
+            builder
+                .window_index(index)
+                .policy(policy)
+                .headless(headless)
+                .show_border(border)
+                .include_popups(include_popups)
+                .preserve_alpha(preserve_alpha)
+                .drop_stale_after_resize(drop_stale_after_resize)
+                .detect_protected(detect_protected)
+                .output_color_space(color_space)
+                .sdr_adjust(adjust)
+                .hdr10(hdr10)
+                .transform(transform)
+                .build()
+        });
 
-        let (cmd_tx, resp_rx, handle) = spawn_worker(Box::new(move || {
-            pipeline::CapturePipeline::window(
-                process_for_worker.as_deref(),
-                pid,
-                hwnd,
-                index,
-                policy,
-                headless,
-            )
-        }))
-        .map_err(PyRuntimeError::new_err)?;
+        let cap = Capture::start(open)?;
+        if format.is_none() {
+            if let Ok(Response::Bool(is_hdr)) = cap.call(py, Command::IsHdr) {
+                warn_mode_mismatch(py, policy, is_hdr)?;
+            }
+        }
+        Ok(cap)
+    }
 
-        let cap = Capture {
-            cmd_tx: Some(cmd_tx),
-            resp_rx: Some(Mutex::new(resp_rx)),
-            handle: Some(handle),
-        };
+    /// Create a capture pipeline spanning all monitors.
+    ///
+    /// Each capture returns one frame covering the virtual desktop, with monitors
+    /// placed at their desktop coordinates. In "auto" mode HDR monitors are
+    /// tone-mapped to SDR so the composite is uniformly BGRA8.
+    ///
+    /// Args:
+    ///     mode: Capture mode — "auto", "hdr", "sdr", or "prefer_hdr"
+    #[staticmethod]
+    #[pyo3(signature = (mode="auto"))]
+    pub(crate) fn desktop(py: Python<'_>, mode: &str) -> PyResult<Self> {
+        let policy = parse_mode(mode)?;
+
+        let open = opener(move || CapturePipeline::virtual_desktop(policy));
+
+        let cap = Capture::start(open)?;
         if let Ok(Response::Bool(is_hdr)) = cap.call(py, Command::IsHdr) {
             warn_mode_mismatch(py, policy, is_hdr)?;
         }
@@ -188,14 +555,128 @@ impl Capture {
         }
     }
 
+    /// Refresh rate (Hz) of the monitor showing the target, or None if unknown.
+    #[getter]
+    fn refresh_rate_hz(&self, py: Python<'_>) -> PyResult<Option<f64>> {
+        match self.call(py, Command::RefreshRate)? {
+            Response::Float(v) => Ok(v),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Measured rate at which new frames arrive (rolling average), or None before two frames.
+    ///
+    /// Equals the refresh rate while content changes every VSync; lower for
+    /// static content; varies with the present rate on VRR displays.
+    #[getter]
+    fn measured_fps(&self, py: Python<'_>) -> PyResult<Option<f64>> {
+        match self.call(py, Command::MeasuredFps)? {
+            Response::Float(v) => Ok(v),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Stop capturing while keeping the capture session alive.
+    ///
+    /// For apps that toggle capture on and off: resume() is instant, unlike
+    /// opening a new Capture. While paused no frames are processed, and
+    /// capture() / grab() and the other capture calls raise RuntimeError.
+    fn pause(&self, py: Python<'_>) -> PyResult<()> {
+        match self.call(py, Command::Pause)? {
+            Response::Done(Ok(())) => Ok(()),
+            Response::Done(Err(e)) => Err(PyRuntimeError::new_err(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Continue after pause().
+    ///
+    /// Frames queued while paused are discarded, so the next grab() returns
+    /// a frame rendered after this call (or the last frame on static content).
+    fn resume(&self, py: Python<'_>) -> PyResult<()> {
+        match self.call(py, Command::Resume)? {
+            Response::Done(Ok(())) => Ok(()),
+            Response::Done(Err(e)) => Err(PyRuntimeError::new_err(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Whether the capture is paused.
+    #[getter]
+    fn paused(&self, py: Python<'_>) -> PyResult<bool> {
+        match self.call(py, Command::IsPaused)? {
+            Response::Bool(v) => Ok(v),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Hide windows of this process (overlays, recording UI) from the capture.
+    ///
+    /// Replaces the previously excluded set; an empty list shows every window
+    /// again. Only this process's own windows can be excluded, and while set
+    /// they are hidden from every screen capture, not just this one. Needs
+    /// Windows 10 2004+; older builds capture them as black rectangles.
+    ///
+    /// Args:
+    ///     hwnds: Window handles to hide
+    fn exclude_windows(&self, py: Python<'_>, hwnds: Vec<isize>) -> PyResult<()> {
+        match self.call(py, Command::ExcludeWindows(hwnds))? {
+            Response::Done(Ok(())) => Ok(()),
+            Response::Done(Err(e)) => Err(PyRuntimeError::new_err(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Copy a frame to the Windows clipboard, tone-mapping HDR frames on this
+    /// capture's GPU device.
+    ///
+    /// Same result as ``frame.to_clipboard()``, which creates a temporary
+    /// device for HDR frames on every call.
+    ///
+    /// Args:
+    ///     frame: Frame to copy (from any capture)
+    fn copy_to_clipboard(&self, py: Python<'_>, frame: PyRef<'_, CapturedFrame>) -> PyResult<()> {
+        match self.call(py, Command::CopyToClipboard(frame.inner.clone()))? {
+            Response::Done(Ok(())) => Ok(()),
+            Response::Done(Err(e)) => Err(PyRuntimeError::new_err(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Capture health counters since the pipeline was created.
+    ///
+    /// Returns:
+    ///     dict: ``frames_received``, ``frames_dropped_on_drain``,
+    ///       ``pool_recreates``, ``cache_fallbacks``, ``timeouts``,
+    ///       ``session_restarts``
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats = match self.call(py, Command::Stats)? {
+            Response::Stats(stats) => stats,
+            _ => return Err(PyRuntimeError::new_err("Unexpected worker response")),
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("frames_received", stats.frames_received)?;
+        dict.set_item("frames_dropped_on_drain", stats.frames_dropped_on_drain)?;
+        dict.set_item("pool_recreates", stats.pool_recreates)?;
+        dict.set_item("cache_fallbacks", stats.cache_fallbacks)?;
+        dict.set_item("timeouts", stats.timeouts)?;
+        dict.set_item("session_restarts", stats.session_restarts)?;
+        Ok(dict)
+    }
+
     /// Screenshot mode: capture a fresh frame
     ///
     /// Drain backlog and wait for DWM to push new frame, guarantees returned frame is generated after the call.
     /// Releases GIL during wait and readback, doesn't block other Python threads.
-    pub(crate) fn capture(&self, py: Python<'_>) -> PyResult<CapturedFrame> {
-        match self.call(py, Command::Capture)? {
+    ///
+    /// Args:
+    ///     timeout: Seconds to wait before raising TimeoutError (default: wait forever)
+    #[pyo3(signature = (timeout=None))]
+    pub(crate) fn capture(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<CapturedFrame> {
+        let timeout = parse_timeout(timeout)?;
+        match self.call_timeout(py, Command::NextFrame(FrameMode::Fresh), timeout)? {
             Response::Frame(Ok(frame)) => Ok(CapturedFrame { inner: frame }),
-            Response::Frame(Err(e)) => Err(PyRuntimeError::new_err(e)),
+            Response::Frame(Err(e)) => Err(frame_error(e)),
             _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
         }
     }
@@ -204,10 +685,140 @@ impl Capture {
     ///
     /// Drain backlog and keep last frame, wait for new frame when pool is empty. Lower latency.
     /// Releases GIL during wait and readback, doesn't block other Python threads.
-    fn grab(&self, py: Python<'_>) -> PyResult<CapturedFrame> {
-        match self.call(py, Command::Grab)? {
+    ///
+    /// Args:
+    ///     timeout: Seconds to wait before raising TimeoutError (default: wait forever)
+    #[pyo3(signature = (timeout=None))]
+    fn grab(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<CapturedFrame> {
+        let timeout = parse_timeout(timeout)?;
+        match self.call_timeout(py, Command::NextFrame(FrameMode::Latest), timeout)? {
+            Response::Frame(Ok(frame)) => Ok(CapturedFrame { inner: frame }),
+            Response::Frame(Err(e)) => Err(frame_error(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Get the next frame with an explicit freshness mode
+    ///
+    /// "fresh" is the same as capture() (frame generated after the call),
+    /// "latest" the same as grab() (newest available frame, lower latency).
+    ///
+    /// Args:
+    ///     mode: "fresh" or "latest"
+    ///     timeout: Seconds to wait before raising TimeoutError (default: wait forever)
+    #[pyo3(signature = (mode="fresh", timeout=None))]
+    fn next_frame(
+        &self,
+        py: Python<'_>,
+        mode: &str,
+        timeout: Option<f64>,
+    ) -> PyResult<CapturedFrame> {
+        let mode = parse_frame_mode(mode)?;
+        let timeout = parse_timeout(timeout)?;
+        match self.call_timeout(py, Command::NextFrame(mode), timeout)? {
+            Response::Frame(Ok(frame)) => Ok(CapturedFrame { inner: frame }),
+            Response::Frame(Err(e)) => Err(frame_error(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Iterate over streamed frames: `for frame in cap.frames(): ...`
+    ///
+    /// Each step calls grab() (GIL released while waiting). Ending or
+    /// breaking out of the loop leaves the capture open, so it can be
+    /// iterated again.
+    ///
+    /// Args:
+    ///     max_frames: Stop after this many frames (default: never stop)
+    ///     fps: Cap the rate to this many frames per second (default: as fast as frames arrive)
+    #[pyo3(signature = (max_frames=None, fps=None))]
+    fn frames(
+        slf: Py<Self>,
+        max_frames: Option<usize>,
+        fps: Option<f64>,
+    ) -> PyResult<FrameIterator> {
+        let interval = match fps {
+            Some(fps) if fps.is_finite() && fps > 0.0 => Some(Duration::from_secs_f64(1.0 / fps)),
+            Some(fps) => {
+                return Err(PyValueError::new_err(format!(
+                    "fps must be a positive number, got {}",
+                    fps
+                )))
+            }
+            None => None,
+        };
+        Ok(FrameIterator {
+            capture: slf,
+            remaining: max_frames,
+            interval,
+            next_due: None,
+        })
+    }
+
+    /// Streaming mode with change detection: grab, or None if nothing changed
+    ///
+    /// A GPU checksum of the frame is compared with the previous call's frame;
+    /// when equal, the full readback is skipped and None is returned.
+    /// Releases GIL during wait and readback.
+    ///
+    /// Args:
+    ///     timeout: Seconds to wait before raising TimeoutError (default: wait forever)
+    #[pyo3(signature = (timeout=None))]
+    fn grab_changed(
+        &self,
+        py: Python<'_>,
+        timeout: Option<f64>,
+    ) -> PyResult<Option<CapturedFrame>> {
+        let timeout = parse_timeout(timeout)?;
+        match self.call_timeout(py, Command::GrabChanged, timeout)? {
+            Response::ChangedFrame(Ok(frame)) => Ok(frame.map(|inner| CapturedFrame { inner })),
+            Response::ChangedFrame(Err(e)) => Err(frame_error(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Non-blocking grab: the newest buffered frame, or None if none arrived
+    ///
+    /// Never waits for the compositor: returns None right away when no new
+    /// frame arrived since the previous call, so a UI or event loop can poll
+    /// once per tick. Releases GIL during processing and readback.
+    ///
+    /// Args:
+    ///     timeout: Seconds to wait for the worker before raising TimeoutError
+    ///         (default: wait forever)
+    #[pyo3(signature = (timeout=None))]
+    fn try_grab(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<CapturedFrame>> {
+        let timeout = parse_timeout(timeout)?;
+        match self.call_timeout(py, Command::TryGrab, timeout)? {
+            Response::ChangedFrame(Ok(frame)) => Ok(frame.map(|inner| CapturedFrame { inner })),
+            Response::ChangedFrame(Err(e)) => Err(frame_error(e)),
+            _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
+        }
+    }
+
+    /// Capture once the target stops animating
+    ///
+    /// Takes a fresh frame, then grabs one every `settle` seconds until two in a
+    /// row are near-identical (compared on a pixel sample) and returns the later
+    /// one. After `max_wait` seconds the latest frame is returned anyway.
+    /// Use for UI screenshots that should show the end of a fade or transition.
+    /// Releases GIL while waiting.
+    ///
+    /// Args:
+    ///     max_wait: Longest time to wait for the content to settle (seconds)
+    ///     settle: Interval between the compared frames (seconds)
+    #[pyo3(signature = (max_wait=2.0, settle=0.1))]
+    fn capture_stable(
+        &self,
+        py: Python<'_>,
+        max_wait: f64,
+        settle: f64,
+    ) -> PyResult<CapturedFrame> {
+        let max_wait = parse_seconds("max_wait", max_wait)?;
+        let settle = parse_seconds("settle", settle)?;
+        match self.call(py, Command::CaptureStable { max_wait, settle })? {
             Response::Frame(Ok(frame)) => Ok(CapturedFrame { inner: frame }),
-            Response::Frame(Err(e)) => Err(PyRuntimeError::new_err(e)),
+            Response::Frame(Err(e)) => Err(frame_error(e)),
             _ => Err(PyRuntimeError::new_err("Unexpected worker response")),
         }
     }
@@ -215,7 +826,7 @@ impl Capture {
     /// Release capture resources
     pub(crate) fn close(&mut self, py: Python<'_>) {
         if let Some(tx) = self.cmd_tx.take() {
-            let _ = tx.send(Command::Close);
+            let _ = tx.try_send(Command::Close);
         }
         self.resp_rx.take();
         if let Some(h) = self.handle.take() {
@@ -226,6 +837,24 @@ impl Capture {
         }
     }
 
+    /// Start capturing again with the target and options the capture was
+    /// created with.
+    ///
+    /// Revives a capture after `close()` (or a context manager exit) without
+    /// re-specifying its arguments. The target is resolved again, so a window
+    /// selected by process name may pick a different window. An open capture
+    /// is closed first, restarting its session.
+    fn reopen(&mut self, py: Python<'_>) -> PyResult<()> {
+        self.close(py);
+        let (cmd_tx, resp_rx, handle) = py
+            .detach(|| (self.open)())
+            .map_err(PyRuntimeError::new_err)?;
+        self.cmd_tx = Some(cmd_tx);
+        self.resp_rx = Some(Mutex::new(ResponseChannel::new(resp_rx)));
+        self.handle = Some(handle);
+        Ok(())
+    }
+
     fn __enter__(slf: Py<Self>) -> Py<Self> {
         slf
     }
@@ -249,3 +878,46 @@ impl Capture {
         }
     }
 }
+
+/// Iterator returned by `capture.frames()`
+///
+/// Holds a reference to the capture, not the pipeline itself: dropping the
+/// iterator doesn't close the capture.
+#[pyclass]
+pub(crate) struct FrameIterator {
+    capture: Py<Capture>,
+    /// Frames left before StopIteration (None: unlimited).
+    remaining: Option<usize>,
+    /// Minimum time between frames (from `fps`).
+    interval: Option<Duration>,
+    /// Earliest time the next frame may be grabbed.
+    next_due: Option<Instant>,
+}
+
+#[pymethods]
+impl FrameIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<CapturedFrame>> {
+        if self.remaining == Some(0) {
+            return Ok(None);
+        }
+        if let Some(interval) = self.interval {
+            if let Some(wait) = self
+                .next_due
+                .and_then(|due| due.checked_duration_since(Instant::now()))
+            {
+                py.detach(|| std::thread::sleep(wait));
+            }
+            self.next_due = Some(Instant::now() + interval);
+        }
+
+        let frame = self.capture.bind(py).borrow().grab(py, None)?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        Ok(Some(frame))
+    }
+}
@@ -1,11 +1,18 @@
-use half::f16;
+use std::os::raw::c_int;
+use std::path::Path;
+
 use numpy::ndarray::Array3;
 use numpy::{IntoPyArray, PyArrayMethods};
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::ffi;
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyMemoryView, PyTuple};
 
-use crate::color::ColorPixelFormat;
-use crate::pipeline;
+use super::helpers::{parse_axis, parse_rotation};
+use crate::color::convert::f16_values;
+use crate::color::{hdr10, AlphaMode, ColorPixelFormat, ColorProfile};
+use crate::image::{ExrCompression, ExrOptions, ImageFormat, JpegOptions, SaveOptions};
+use crate::pipeline::{self, CaptureWarning, Pixel};
 
 /// Single frame capture result
 ///
@@ -36,38 +43,445 @@ impl CapturedFrame {
         self.inner.timestamp
     }
 
-    /// Pixel format string ("bgra8" or "rgba16f")
+    /// Pixel format string ("bgra8", "rgba16f" or "rgb10a2")
     #[getter]
     fn format(&self) -> &'static str {
         match self.inner.format {
             ColorPixelFormat::Bgra8 => "bgra8",
             ColorPixelFormat::Rgba16f => "rgba16f",
+            ColorPixelFormat::Rgb10a2 => "rgb10a2",
+        }
+    }
+
+    /// Bytes per row of pixel data (rows are tightly packed).
+    #[getter]
+    fn stride(&self) -> usize {
+        self.inner.width as usize * self.inner.bytes_per_pixel()
+    }
+
+    /// Color encoding of rgba16f data: "scrgb", "rec2020" or "srgb".
+    ///
+    /// Always "scrgb" for bgra8 frames.
+    #[getter]
+    fn color_space(&self) -> &'static str {
+        self.inner.color_space.name()
+    }
+
+    /// Color space the source display is driven with: "srgb", "pq_rec2020",
+    /// "scrgb" or "unknown".
+    ///
+    /// Describes the display signal, not the pixel data: an HDR10 display
+    /// reports "pq_rec2020" while its rgba16f frames are still encoded as
+    /// `color_space` says.
+    #[getter]
+    fn source_color_space(&self) -> &'static str {
+        self.inner.source_color_space.name()
+    }
+
+    /// Bits per channel of the source display signal (0 when unknown).
+    #[getter]
+    fn bits_per_channel(&self) -> u32 {
+        self.inner.bits_per_channel
+    }
+
+    /// Display scale factor of the source monitor (1.0 = 100%, 1.5 = 150%).
+    ///
+    /// Pixels are physical; divide coordinates by this to get logical ones.
+    #[getter]
+    fn dpi_scale(&self) -> f32 {
+        self.inner.dpi_scale
+    }
+
+    /// Non-fatal capture condition, or None.
+    ///
+    /// "target_minimized": the window is minimized and this is the last frame
+    /// captured before it was.
+    /// "session_restarted": the watchdog restarted a silent capture session;
+    /// this is its first frame.
+    /// "blank_frame": the frame is effectively black (protected content);
+    /// only with `blank_threshold`.
+    #[getter]
+    fn warning(&self) -> Option<&'static str> {
+        self.inner.warning.map(|warning| match warning {
+            CaptureWarning::TargetMinimized => "target_minimized",
+            CaptureWarning::SessionRestarted => "session_restarted",
+            CaptureWarning::BlankFrame => "blank_frame",
+        })
+    }
+
+    /// Whether no pixel is brighter than `threshold` (alpha ignored).
+    ///
+    /// Protected (DRM / HDCP) windows capture as solid black. `threshold` is
+    /// a fraction of full scale for bgra8 / rgb10a2 and a linear scRGB value
+    /// for rgba16f. Releases GIL during the scan.
+    #[pyo3(signature = (threshold=0.02))]
+    fn is_blank(&self, py: Python<'_>, threshold: f32) -> bool {
+        let inner = &self.inner;
+        py.detach(|| inner.is_blank(threshold))
+    }
+
+    /// Value of the pixel at (x, y) as an (r, g, b, a) tuple.
+    ///
+    /// bgra8: 0-255 ints (sRGB-encoded). rgba16f / rgb10a2: linear r, g, b
+    /// in nits and alpha as a float (1.0 = opaque).
+    fn pixel<'py>(&self, py: Python<'py>, x: u32, y: u32) -> PyResult<Bound<'py, PyTuple>> {
+        match self.inner.pixel(x, y) {
+            Ok(Pixel::Sdr(rgba)) => PyTuple::new(py, rgba),
+            Ok(Pixel::Hdr(rgba)) => PyTuple::new(py, rgba),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Alpha channel meaning: "ignore", "premultiplied" or "straight".
+    ///
+    /// "ignore" (default) means alpha is not meaningful and PNG / BMP / TIFF /
+    /// EXR are saved opaque.
+    #[getter]
+    fn alpha(&self) -> &'static str {
+        match self.inner.alpha {
+            AlphaMode::Ignore => "ignore",
+            AlphaMode::Premultiplied => "premultiplied",
+            AlphaMode::Straight => "straight",
         }
     }
 
+    /// Return a copy with straight (non-premultiplied) alpha.
+    ///
+    /// Use before saving a ``preserve_alpha`` capture to PNG / TIFF.
+    /// Frames that aren't premultiplied are returned unchanged.
+    fn unpremultiply(&self, py: Python<'_>) -> CapturedFrame {
+        let inner = &self.inner;
+        CapturedFrame {
+            inner: py.detach(|| inner.unpremultiply()),
+        }
+    }
+
+    /// Return a copy rotated clockwise by `degrees` (0, 90, 180 or 270).
+    ///
+    /// Width and height trade places for 90 / 270. Releases GIL during the copy.
+    fn rotate(&self, py: Python<'_>, degrees: i32) -> PyResult<CapturedFrame> {
+        let rotation = parse_rotation(degrees)?;
+        let inner = &self.inner;
+        Ok(CapturedFrame {
+            inner: py.detach(|| inner.rotate(rotation)),
+        })
+    }
+
+    /// Return a mirrored copy: `axis` is "horizontal" (left ↔ right) or
+    /// "vertical" (top ↔ bottom). Releases GIL during the copy.
+    fn flip(&self, py: Python<'_>, axis: &str) -> PyResult<CapturedFrame> {
+        let axis = parse_axis(axis)?;
+        let inner = &self.inner;
+        Ok(CapturedFrame {
+            inner: py.detach(|| inner.flip(axis)),
+        })
+    }
+
     /// Save frame to file (format determined by extension).
     ///
     /// Supported formats:
-    ///   - .png .bmp .jpg .tiff — standard formats (BGRA8 / SDR only)
+    ///   - .png .bmp .jpg .tiff .webp — standard formats (BGRA8 / SDR only)
     ///   - .jxr — JPEG XR (both BGRA8 and RGBA16F / HDR)
+    ///   - .heic .heif — HEIF (BGRA8, or RGBA16F as 10-bit HDR10)
     ///   - .exr — OpenEXR (both BGRA8 and RGBA16F / HDR)
+    ///   - .hdr — Radiance RGBE (both BGRA8 and RGBA16F / HDR, no alpha)
+    ///
+    /// PNG / JPEG / WebP embed an ICC profile: sRGB by default (display profile for
+    /// frames captured unconverted from an SDR monitor), or the file given by `icc_profile`.
+    ///
+    /// `compression` selects the EXR compression ("none", "rle", "zip", "piz";
+    /// default "rle") and is only accepted for EXR output.
+    ///
+    /// `assume_srgb` (default True) linearizes BGRA8 values when writing EXR,
+    /// since EXR readers expect linear light; False writes the sRGB-encoded
+    /// values unchanged. Only accepted for EXR output.
+    ///
+    /// `quality` sets the JPEG quality (1-100, default 90) and is only accepted
+    /// for JPEG output.
+    ///
+    /// HDR frames saved to an SDR-only format (png / bmp / jpg / tiff / webp)
+    /// are tone-mapped to BGRA8 first; `strict=True` raises instead.
+    ///
+    /// `path` may also be a writable binary file object (`io.BytesIO`, an open
+    /// file, a zip entry): the frame is encoded in memory in `format` and
+    /// passed to its `write()`. Encoder options apply as they would to a path
+    /// with that extension.
     ///
     /// Releases GIL during encoding, doesn't block other Python threads.
-    fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+    #[pyo3(signature = (path, *, format=None, icc_profile=None, compression=None, quality=None, assume_srgb=None, strict=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn save(
+        &self,
+        py: Python<'_>,
+        path: &Bound<'_, PyAny>,
+        format: Option<&str>,
+        icc_profile: Option<&str>,
+        compression: Option<&str>,
+        quality: Option<u8>,
+        assume_srgb: Option<bool>,
+        strict: bool,
+    ) -> PyResult<()> {
+        // The effective format decides which encoder options apply: the
+        // extension of a path, or `format` for a file object.
+        let (target, image_format) = match path.extract::<String>() {
+            Ok(path_str) => {
+                if format.is_some() {
+                    return Err(PyValueError::new_err(
+                        "format is only used with file objects; a path's extension selects the format",
+                    ));
+                }
+                let image_format = Path::new(&path_str)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(ImageFormat::from_extension);
+                (Ok(path_str), image_format)
+            }
+            Err(_) => {
+                if !path.hasattr("write")? {
+                    return Err(PyTypeError::new_err(
+                        "path must be a str or a binary file object with write()",
+                    ));
+                }
+                let format = format.unwrap_or("png");
+                let image_format = ImageFormat::from_extension(format.trim_start_matches('.'))
+                    .ok_or_else(|| {
+                        PyValueError::new_err(format!("unsupported format '{}'", format))
+                    })?;
+                (Err((path, image_format)), Some(image_format))
+            }
+        };
+        let is_exr = image_format == Some(ImageFormat::Exr);
+        let mut exr_options = ExrOptions::default();
+        if let Some(name) = compression {
+            if !is_exr {
+                return Err(PyValueError::new_err(
+                    "compression is only supported for .exr output",
+                ));
+            }
+            exr_options.compression = ExrCompression::from_name(name).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "invalid compression '{}': expected 'none', 'rle', 'zip', or 'piz'",
+                    name
+                ))
+            })?;
+        }
+        if let Some(assume_srgb) = assume_srgb {
+            if !is_exr {
+                return Err(PyValueError::new_err(
+                    "assume_srgb is only supported for .exr output",
+                ));
+            }
+            exr_options.assume_srgb = assume_srgb;
+        }
+        let jpeg_options = match quality {
+            Some(quality) => {
+                if image_format != Some(ImageFormat::Jpeg) {
+                    return Err(PyValueError::new_err(
+                        "quality is only supported for .jpg output",
+                    ));
+                }
+                if !(1..=100).contains(&quality) {
+                    return Err(PyValueError::new_err(format!(
+                        "quality must be 1-100, got {}",
+                        quality
+                    )));
+                }
+                JpegOptions { quality }
+            }
+            None => JpegOptions::default(),
+        };
+        let inner = &self.inner;
+        let icc_profile = icc_profile.map(str::to_string);
+        let options = move || -> anyhow::Result<SaveOptions> {
+            Ok(SaveOptions {
+                profile: icc_profile.map(ColorProfile::from_file).transpose()?,
+                exr: exr_options,
+                jpeg: jpeg_options,
+                ..Default::default()
+            })
+        };
+        match target {
+            Ok(path) => py
+                .detach(|| inner.save_impl(Path::new(&path), &options()?, strict))
+                .map_err(|e| PyRuntimeError::new_err(e.to_string())),
+            Err((fileobj, image_format)) => {
+                let bytes = py
+                    .detach(|| inner.encode_impl(image_format, &options()?, strict))
+                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+                fileobj.call_method1("write", (PyBytes::new(py, &bytes),))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Save into `dir` under a timestamped name and return the path.
+    ///
+    /// Names look like `shot_2024-06-01_12-30-45-123.png` (local capture
+    /// time, milliseconds last); `_1`, `_2`, ... is appended when taken.
+    /// `dir` is created if missing.
+    ///
+    /// Releases GIL during encoding.
+    #[pyo3(signature = (dir, prefix="", ext="png"))]
+    fn save_auto(&self, py: Python<'_>, dir: &str, prefix: &str, ext: &str) -> PyResult<String> {
+        let inner = &self.inner;
+        py.detach(|| inner.save_auto(dir, prefix, ext))
+            .map(|path| path.to_string_lossy().into_owned())
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Copy the frame to the Windows clipboard, ready to paste.
+    ///
+    /// Offered as a bitmap (CF_DIBV5) and as PNG. HDR (rgba16f, rgb10a2) frames are
+    /// tone-mapped to SDR first, since the clipboard can't hold HDR. That
+    /// creates a temporary GPU device; capture.copy_to_clipboard(frame) reuses
+    /// the capture's.
+    ///
+    /// Releases GIL during conversion and encoding.
+    fn to_clipboard(&self, py: Python<'_>) -> PyResult<()> {
         let inner = &self.inner;
-        let path = path.to_string();
-        py.detach(|| inner.save(&path))
+        py.detach(|| inner.copy_to_clipboard())
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     }
 
+    /// Encode the frame in memory and return the file bytes.
+    ///
+    /// Args:
+    ///     format: Extension naming the format ("png", "jpg", "jxr", "exr", "hdr", "heic", ...)
+    ///
+    /// Releases GIL during encoding.
+    #[pyo3(signature = (format="png"))]
+    fn to_bytes<'py>(&self, py: Python<'py>, format: &str) -> PyResult<Bound<'py, PyBytes>> {
+        let image_format = ImageFormat::from_extension(format.trim_start_matches('.'))
+            .ok_or_else(|| PyValueError::new_err(format!("unsupported format '{}'", format)))?;
+        let inner = &self.inner;
+        let bytes = py
+            .detach(|| inner.encode(image_format))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Compare with another frame of the same size and format.
+    ///
+    /// Color channels are compared exactly (alpha only for frames with
+    /// meaningful alpha). Releases GIL during the comparison.
+    ///
+    /// Returns:
+    ///     tuple: ``(num_different, max_delta, diff_frame)`` — number of changed
+    ///       pixels, largest channel difference (0–255 for bgra8, scRGB units
+    ///       for rgba16f, 10-bit code steps for rgb10a2), and a bgra8 frame
+    ///       with changed pixels in magenta over a dimmed copy of this frame
+    ///       (None when identical).
+    fn diff(
+        &self,
+        py: Python<'_>,
+        other: PyRef<'_, CapturedFrame>,
+    ) -> PyResult<(usize, f32, Option<CapturedFrame>)> {
+        let (inner, other) = (&self.inner, &other.inner);
+        let result = py
+            .detach(|| inner.diff(other))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok((
+            result.num_different,
+            result.max_delta,
+            result.diff_image.map(|inner| CapturedFrame { inner }),
+        ))
+    }
+
+    /// Luminance statistics of the frame.
+    ///
+    /// Scans every pixel; computed on demand. Releases GIL during the scan.
+    ///
+    /// Returns:
+    ///     dict: ``max_nits``, ``p99_nits`` and ``fraction_above_sdr``
+    ///       (fraction of pixels brighter than the display's SDR white level).
+    fn luminance_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let inner = &self.inner;
+        let stats = py.detach(|| inner.luminance_stats());
+        let dict = PyDict::new(py);
+        dict.set_item("max_nits", stats.max_nits)?;
+        dict.set_item("p99_nits", stats.p99_nits)?;
+        dict.set_item("fraction_above_sdr", stats.fraction_above_sdr)?;
+        Ok(dict)
+    }
+
     /// Convert to numpy array.
     ///
+    /// Args:
+    ///     channels: 4 (default) keeps alpha, 3 drops it
+    ///     order: "rgb" or "bgr" channel order; defaults to RGB for 3 channels
+    ///       and to the native order for 4 (BGRA for bgra8, RGBA for rgba16f
+    ///       and rgb10a2)
+    ///
     /// Returns:
-    ///     numpy.ndarray: shape (H, W, 4).
-    ///       - bgra8: dtype uint8, BGRA channel order
-    ///       - rgba16f: dtype float16, RGBA channel order
-    fn ndarray<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
-        self.to_ndarray(py)
+    ///     numpy.ndarray: shape (H, W, channels).
+    ///       - bgra8: dtype uint8
+    ///       - rgba16f: dtype float16
+    ///       - rgb10a2: dtype uint16, unpacked 10-bit PQ codes (0–1023) and
+    ///         2-bit alpha (0–3)
+    #[pyo3(signature = (channels=4, order=None))]
+    fn ndarray<'py>(
+        &self,
+        py: Python<'py>,
+        channels: usize,
+        order: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if channels != 3 && channels != 4 {
+            return Err(PyValueError::new_err(format!(
+                "channels must be 3 or 4, got {}",
+                channels
+            )));
+        }
+        let native_bgr = self.inner.format == ColorPixelFormat::Bgra8;
+        let want_bgr = match order {
+            // 3-channel arrays feed CV / ML code, which expects RGB.
+            None if channels == 3 => false,
+            None => native_bgr,
+            Some("rgb") => false,
+            Some("bgr") => true,
+            Some(other) => {
+                return Err(PyValueError::new_err(format!(
+                    "order must be 'rgb' or 'bgr', got '{}'",
+                    other
+                )))
+            }
+        };
+        self.to_ndarray(py, channels, native_bgr != want_bgr)
+    }
+
+    /// Read-only memoryview over the raw pixel bytes, without copying.
+    ///
+    /// Rows are `stride` bytes apart, pixels are laid out as `format` says.
+    /// The view keeps the frame (and its pooled buffer) alive until released.
+    fn buffer<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyMemoryView>> {
+        PyMemoryView::from(slf.as_any())
+    }
+
+    /// Buffer protocol: expose pixel data as read-only unsigned bytes.
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let frame = slf.borrow();
+        let data = frame.inner.data.as_slice();
+        // SAFETY: the bytes live in an Arc owned by this frame, which is never
+        // mutated after capture. PyBuffer_FillInfo takes a reference to `slf`,
+        // keeping the frame alive until the view is released, and rejects
+        // writable requests.
+        let status = unsafe {
+            ffi::PyBuffer_FillInfo(
+                view,
+                slf.as_ptr(),
+                data.as_ptr() as *mut _,
+                data.len() as ffi::Py_ssize_t,
+                1,
+                flags,
+            )
+        };
+        if status == -1 {
+            return Err(PyErr::fetch(slf.py()));
+        }
+        Ok(())
     }
 
     /// numpy __array__ protocol, enables np.array(frame) to work automatically
@@ -79,7 +493,7 @@ impl CapturedFrame {
         copy: Option<Bound<'py, PyAny>>,
     ) -> PyResult<Bound<'py, PyAny>> {
         let _ = (dtype, copy);
-        self.to_ndarray(py)
+        self.to_ndarray(py, 4, false)
     }
 
     fn __repr__(&self) -> String {
@@ -96,39 +510,83 @@ impl CapturedFrame {
 impl CapturedFrame {
     /// Internal shared numpy conversion logic.
     ///
-    /// - bgra8 → (H, W, 4) uint8
-    /// - rgba16f → (H, W, 4) float16
-    fn to_ndarray<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    /// - bgra8 → (H, W, channels) uint8
+    /// - rgba16f → (H, W, channels) float16
+    /// - rgb10a2 → (H, W, channels) uint16, one channel per packed field
+    ///
+    /// `swap_rb` exchanges the first and third channel during the copy.
+    fn to_ndarray<'py>(
+        &self,
+        py: Python<'py>,
+        channels: usize,
+        swap_rb: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
         let h = self.inner.height as usize;
         let w = self.inner.width as usize;
         let data = self.inner.data.as_slice();
+        self.inner
+            .format
+            .check_len(data, self.inner.width, self.inner.height)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
 
         match self.inner.format {
             ColorPixelFormat::Bgra8 => {
-                let array = Array3::from_shape_vec((h, w, 4), data.to_vec())
-                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-                let pyarray = array.into_pyarray(py);
-                pyarray
-                    .try_readwrite()
-                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?
-                    .make_nonwriteable();
-                Ok(pyarray.into_any())
+                let pixels = py.detach(|| repack(data, channels, swap_rb));
+                readonly_array(py, (h, w, channels), pixels)
             }
             ColorPixelFormat::Rgba16f => {
-                // SAFETY: f16 is #[repr(transparent)] over u16 (2 bytes).
-                // data length is guaranteed to be h * w * 8 by the capture pipeline.
-                let f16_slice: &[f16] = unsafe {
-                    std::slice::from_raw_parts(data.as_ptr() as *const f16, data.len() / 2)
-                };
-                let array = Array3::from_shape_vec((h, w, 4), f16_slice.to_vec())
-                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-                let pyarray = array.into_pyarray(py);
-                pyarray
-                    .try_readwrite()
-                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?
-                    .make_nonwriteable();
-                Ok(pyarray.into_any())
+                let values =
+                    f16_values(data).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+                let pixels = py.detach(|| repack(&values, channels, swap_rb));
+                readonly_array(py, (h, w, channels), pixels)
+            }
+            ColorPixelFormat::Rgb10a2 => {
+                let pixels = py.detach(|| {
+                    let unpacked: Vec<u16> = data
+                        .chunks_exact(4)
+                        .flat_map(|px| hdr10::unpack([px[0], px[1], px[2], px[3]]))
+                        .collect();
+                    repack(&unpacked, channels, swap_rb)
+                });
+                readonly_array(py, (h, w, channels), pixels)
             }
         }
     }
 }
+
+/// Copy 4-channel pixels into `channels` (3 or 4) per pixel, optionally
+/// swapping channels 0 and 2.
+fn repack<T: Copy>(src: &[T], channels: usize, swap_rb: bool) -> Vec<T> {
+    if channels == 4 && !swap_rb {
+        return src.to_vec();
+    }
+    let mut out = Vec::with_capacity(src.len() / 4 * channels);
+    for px in src.chunks_exact(4) {
+        let (first, third) = if swap_rb {
+            (px[2], px[0])
+        } else {
+            (px[0], px[2])
+        };
+        out.extend_from_slice(&[first, px[1], third]);
+        if channels == 4 {
+            out.push(px[3]);
+        }
+    }
+    out
+}
+
+/// Wrap pixel data in a read-only numpy array.
+fn readonly_array<T: numpy::Element>(
+    py: Python<'_>,
+    shape: (usize, usize, usize),
+    pixels: Vec<T>,
+) -> PyResult<Bound<'_, PyAny>> {
+    let array = Array3::from_shape_vec(shape, pixels)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let pyarray = array.into_pyarray(py);
+    pyarray
+        .try_readwrite()
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?
+        .make_nonwriteable();
+    Ok(pyarray.into_any())
+}
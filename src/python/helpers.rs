@@ -1,13 +1,131 @@
-use pyo3::exceptions::PyRuntimeError;
+use std::time::Duration;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 
+use super::worker::WorkerError;
+use super::ProtectedContentError;
+use crate::color::ColorPixelFormat;
 use crate::pipeline;
 
 pub(super) fn parse_mode(mode: &str) -> PyResult<pipeline::CapturePolicy> {
     pipeline::CapturePolicy::from_mode(mode).ok_or_else(|| {
         PyRuntimeError::new_err(format!(
-            "invalid mode '{}': expected 'auto', 'hdr', or 'sdr'",
+            "invalid mode '{}': expected 'auto', 'hdr', 'sdr', or 'prefer_hdr'",
+            mode
+        ))
+    })
+}
+
+pub(super) fn parse_fit(fit: &str) -> PyResult<pipeline::ScaleFit> {
+    match fit {
+        "stretch" => Ok(pipeline::ScaleFit::Stretch),
+        "letterbox" => Ok(pipeline::ScaleFit::Letterbox),
+        _ => Err(PyRuntimeError::new_err(format!(
+            "invalid fit '{}': expected 'stretch' or 'letterbox'",
+            fit
+        ))),
+    }
+}
+
+pub(super) fn parse_frame_mode(mode: &str) -> PyResult<pipeline::FrameMode> {
+    match mode {
+        "fresh" => Ok(pipeline::FrameMode::Fresh),
+        "latest" => Ok(pipeline::FrameMode::Latest),
+        _ => Err(PyRuntimeError::new_err(format!(
+            "invalid mode '{}': expected 'fresh' or 'latest'",
             mode
+        ))),
+    }
+}
+
+pub(super) fn parse_rotation(degrees: i32) -> PyResult<pipeline::Rotation> {
+    pipeline::Rotation::from_degrees(degrees).ok_or_else(|| {
+        PyRuntimeError::new_err(format!(
+            "invalid rotation {}: expected 0, 90, 180 or 270",
+            degrees
+        ))
+    })
+}
+
+pub(super) fn parse_axis(axis: &str) -> PyResult<pipeline::Axis> {
+    pipeline::Axis::from_name(axis).ok_or_else(|| {
+        PyRuntimeError::new_err(format!(
+            "invalid flip '{}': expected 'horizontal' or 'vertical'",
+            axis
+        ))
+    })
+}
+
+pub(super) fn parse_transform(
+    rotate: i32,
+    flip: Option<&str>,
+) -> PyResult<pipeline::FrameTransform> {
+    Ok(pipeline::FrameTransform {
+        rotation: parse_rotation(rotate)?,
+        flip: flip.map(parse_axis).transpose()?,
+    })
+}
+
+pub(super) fn parse_backend(backend: &str) -> PyResult<pipeline::CaptureBackend> {
+    match backend {
+        "auto" => Ok(pipeline::CaptureBackend::Auto),
+        "wgc" => Ok(pipeline::CaptureBackend::Wgc),
+        "duplication" => Ok(pipeline::CaptureBackend::DesktopDuplication),
+        _ => Err(PyRuntimeError::new_err(format!(
+            "invalid backend '{}': expected 'auto', 'wgc', or 'duplication'",
+            backend
+        ))),
+    }
+}
+
+pub(super) fn parse_color_space(color_space: &str) -> PyResult<pipeline::OutputColorSpace> {
+    pipeline::OutputColorSpace::from_name(color_space).ok_or_else(|| {
+        PyRuntimeError::new_err(format!(
+            "invalid color_space '{}': expected 'scrgb', 'rec2020', or 'srgb'",
+            color_space
+        ))
+    })
+}
+
+pub(super) fn parse_format(format: &str) -> PyResult<ColorPixelFormat> {
+    ColorPixelFormat::from_name(format).ok_or_else(|| {
+        PyRuntimeError::new_err(format!(
+            "invalid format '{}': expected 'bgra8', 'rgba16f', or 'rgb10a2'",
+            format
+        ))
+    })
+}
+
+/// Python exception for a failed frame call: `ProtectedContentError` for
+/// protected window content, `RuntimeError` otherwise.
+pub(super) fn frame_error(e: WorkerError) -> PyErr {
+    match e.kind {
+        Some(pipeline::CaptureError::ProtectedContent) => ProtectedContentError::new_err(e.message),
+        _ => PyRuntimeError::new_err(e.message),
+    }
+}
+
+/// Emit a Python `UserWarning`.
+pub(super) fn warn(py: Python<'_>, message: &str) -> PyResult<()> {
+    let warnings = py.import("warnings")?;
+    warnings.call_method1("warn", (message,))?;
+    Ok(())
+}
+
+/// Convert a Python timeout in seconds to a `Duration`.
+pub(super) fn parse_timeout(timeout: Option<f64>) -> PyResult<Option<Duration>> {
+    timeout
+        .map(|secs| parse_seconds("timeout", secs))
+        .transpose()
+}
+
+/// Convert a Python duration argument in seconds to a `Duration`.
+pub(super) fn parse_seconds(name: &str, secs: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(secs).map_err(|_| {
+        PyValueError::new_err(format!(
+            "{} must be a non-negative number of seconds, got {}",
+            name, secs
         ))
     })
 }
@@ -30,8 +148,7 @@ pub(super) fn warn_mode_mismatch(
     };
 
     if let Some(msg) = msg {
-        let warnings = py.import("warnings")?;
-        warnings.call_method1("warn", (msg,))?;
+        warn(py, msg)?;
     }
     Ok(())
 }
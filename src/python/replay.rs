@@ -0,0 +1,104 @@
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+use super::frame::CapturedFrame;
+use super::helpers::parse_seconds;
+use crate::replay;
+
+/// Rolling buffer of the last `seconds` of a monitor
+///
+/// Captures on a background thread at `fps` frames per second and keeps the
+/// newest `seconds * fps` frames (fewer with `max_bytes`), evicting the oldest. `dump()` returns them
+/// oldest first, e.g. to save a clip after something interesting happened.
+#[pyclass]
+pub(crate) struct ReplayBuffer {
+    inner: Option<replay::ReplayBuffer>,
+}
+
+impl ReplayBuffer {
+    fn buffer(&self) -> PyResult<&replay::ReplayBuffer> {
+        self.inner
+            .as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("ReplayBuffer is closed"))
+    }
+}
+
+#[pymethods]
+impl ReplayBuffer {
+    /// Start buffering a monitor
+    ///
+    /// Args:
+    ///     monitor: Monitor index, defaults to 0
+    ///     seconds: Length of the buffered window, defaults to 10
+    ///     fps: Capture rate, 1 to 240, defaults to 30
+    ///     max_bytes: Cap on the buffered pixel data; the oldest frames are evicted
+    ///       to stay under it. Unlimited by default
+    #[new]
+    #[pyo3(signature = (monitor=0, seconds=10.0, fps=30, *, max_bytes=None))]
+    fn new(
+        py: Python<'_>,
+        monitor: usize,
+        seconds: f64,
+        fps: u32,
+        max_bytes: Option<usize>,
+    ) -> PyResult<Self> {
+        let duration = parse_seconds("seconds", seconds)?;
+        let buffer = py
+            .detach(|| replay::ReplayBuffer::monitor(monitor, duration, fps, max_bytes))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(Self {
+            inner: Some(buffer),
+        })
+    }
+
+    /// Buffered frames, oldest first (shares pixel data with the buffer)
+    fn dump(&self) -> PyResult<Vec<CapturedFrame>> {
+        Ok(self
+            .buffer()?
+            .dump()
+            .into_iter()
+            .map(|inner| CapturedFrame { inner })
+            .collect())
+    }
+
+    /// Maximum number of frames kept (seconds × fps, rounded up)
+    #[getter]
+    fn capacity(&self) -> PyResult<usize> {
+        Ok(self.buffer()?.capacity())
+    }
+
+    /// Cap on the buffered pixel data in bytes, or None
+    #[getter]
+    fn max_bytes(&self) -> PyResult<Option<usize>> {
+        Ok(self.buffer()?.max_bytes())
+    }
+
+    /// Stop capturing and release the buffered frames
+    ///
+    /// Frames returned by `dump()` stay valid.
+    fn close(&mut self, py: Python<'_>) {
+        if let Some(buffer) = self.inner.take() {
+            // Release GIL while the capture thread finishes its last frame.
+            py.detach(|| buffer.stop());
+        }
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.buffer()?.len())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        _exc_type: Option<Bound<'_, PyAny>>,
+        _exc_val: Option<Bound<'_, PyAny>>,
+        _exc_tb: Option<Bound<'_, PyAny>>,
+    ) -> bool {
+        self.close(py);
+        false // Don't swallow exceptions
+    }
+}
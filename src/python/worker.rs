@@ -1,54 +1,235 @@
 use std::sync::mpsc;
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
+use windows::Win32::Foundation::HWND;
+
+use crate::com::ComGuard;
 use crate::pipeline;
 
+/// Commands that may be queued or running on a worker at once.
+///
+/// Each call sends one command and waits for its response, so normally only
+/// one is in flight; calls that time out leave theirs behind. Both channels
+/// are bounded to this depth, and `Capture` waits out abandoned calls before
+/// exceeding it, so a caller retrying after timeouts can't queue work (or
+/// frames) faster than the worker finishes it.
+pub(crate) const MAX_IN_FLIGHT: usize = 4;
+
 pub(crate) enum Command {
-    Capture,
-    Grab,
+    NextFrame(pipeline::FrameMode),
+    GrabChanged,
+    TryGrab,
+    CaptureStable {
+        max_wait: Duration,
+        settle: Duration,
+    },
     IsHdr,
+    RefreshRate,
+    MeasuredFps,
+    Stats,
+    Pause,
+    Resume,
+    IsPaused,
+    ExcludeWindows(Vec<isize>),
+    CopyToClipboard(pipeline::CapturedFrame),
     Close,
 }
 
 pub(crate) enum Response {
-    Frame(Result<pipeline::CapturedFrame, String>),
+    Frame(Result<pipeline::CapturedFrame, WorkerError>),
+    ChangedFrame(Result<Option<pipeline::CapturedFrame>, WorkerError>),
     Bool(bool),
+    Float(Option<f64>),
+    Stats(pipeline::CaptureStats),
+    Done(Result<(), String>),
     Closed,
 }
 
+/// Failed frame call, with the `CaptureError` behind it (if any) so Python
+/// can raise a matching exception.
+pub(crate) struct WorkerError {
+    pub(crate) message: String,
+    pub(crate) kind: Option<pipeline::CaptureError>,
+}
+
+impl From<anyhow::Error> for WorkerError {
+    fn from(e: anyhow::Error) -> Self {
+        Self {
+            message: e.to_string(),
+            kind: e.downcast_ref::<pipeline::CaptureError>().copied(),
+        }
+    }
+}
+
+/// Pipeline types the worker can drive.
+pub(crate) trait FrameSource {
+    fn next_frame(&mut self, mode: pipeline::FrameMode) -> anyhow::Result<pipeline::CapturedFrame>;
+    fn grab_changed(&mut self) -> anyhow::Result<Option<pipeline::CapturedFrame>>;
+    fn try_grab(&mut self) -> anyhow::Result<Option<pipeline::CapturedFrame>>;
+    fn capture_stable(
+        &mut self,
+        max_wait: Duration,
+        settle: Duration,
+    ) -> anyhow::Result<pipeline::CapturedFrame>;
+    fn is_hdr(&self) -> bool;
+    fn refresh_rate_hz(&self) -> Option<f64>;
+    fn measured_fps(&self) -> Option<f64>;
+    fn capture_stats(&self) -> pipeline::CaptureStats;
+    fn pause(&mut self) -> anyhow::Result<()>;
+    fn resume(&mut self) -> anyhow::Result<()>;
+    fn is_paused(&self) -> bool;
+    fn exclude_windows(&mut self, hwnds: &[HWND]) -> anyhow::Result<()>;
+    fn copy_to_clipboard(&self, frame: &pipeline::CapturedFrame) -> anyhow::Result<()>;
+}
+
+impl FrameSource for pipeline::CapturePipeline {
+    fn next_frame(&mut self, mode: pipeline::FrameMode) -> anyhow::Result<pipeline::CapturedFrame> {
+        pipeline::CapturePipeline::next_frame(self, mode)
+    }
+
+    fn grab_changed(&mut self) -> anyhow::Result<Option<pipeline::CapturedFrame>> {
+        pipeline::CapturePipeline::grab_changed(self)
+    }
+
+    fn try_grab(&mut self) -> anyhow::Result<Option<pipeline::CapturedFrame>> {
+        pipeline::CapturePipeline::try_grab(self)
+    }
+
+    fn capture_stable(
+        &mut self,
+        max_wait: Duration,
+        settle: Duration,
+    ) -> anyhow::Result<pipeline::CapturedFrame> {
+        pipeline::CapturePipeline::capture_stable(self, max_wait, settle)
+    }
+
+    fn is_hdr(&self) -> bool {
+        pipeline::CapturePipeline::is_hdr(self)
+    }
+
+    fn refresh_rate_hz(&self) -> Option<f64> {
+        pipeline::CapturePipeline::refresh_rate_hz(self)
+    }
+
+    fn measured_fps(&self) -> Option<f64> {
+        pipeline::CapturePipeline::measured_fps(self)
+    }
+
+    fn capture_stats(&self) -> pipeline::CaptureStats {
+        pipeline::CapturePipeline::capture_stats(self)
+    }
+
+    fn pause(&mut self) -> anyhow::Result<()> {
+        pipeline::CapturePipeline::pause(self)
+    }
+
+    fn resume(&mut self) -> anyhow::Result<()> {
+        pipeline::CapturePipeline::resume(self)
+    }
+
+    fn is_paused(&self) -> bool {
+        pipeline::CapturePipeline::is_paused(self)
+    }
+
+    fn exclude_windows(&mut self, hwnds: &[HWND]) -> anyhow::Result<()> {
+        pipeline::CapturePipeline::exclude_windows(self, hwnds)
+    }
+
+    fn copy_to_clipboard(&self, frame: &pipeline::CapturedFrame) -> anyhow::Result<()> {
+        pipeline::CapturePipeline::copy_to_clipboard(self, frame)
+    }
+}
+
+impl FrameSource for pipeline::DesktopPipeline {
+    fn next_frame(&mut self, mode: pipeline::FrameMode) -> anyhow::Result<pipeline::CapturedFrame> {
+        pipeline::DesktopPipeline::next_frame(self, mode)
+    }
+
+    fn grab_changed(&mut self) -> anyhow::Result<Option<pipeline::CapturedFrame>> {
+        pipeline::DesktopPipeline::grab_changed(self)
+    }
+
+    fn try_grab(&mut self) -> anyhow::Result<Option<pipeline::CapturedFrame>> {
+        pipeline::DesktopPipeline::try_grab(self)
+    }
+
+    fn capture_stable(
+        &mut self,
+        max_wait: Duration,
+        settle: Duration,
+    ) -> anyhow::Result<pipeline::CapturedFrame> {
+        pipeline::DesktopPipeline::capture_stable(self, max_wait, settle)
+    }
+
+    fn is_hdr(&self) -> bool {
+        pipeline::DesktopPipeline::is_hdr(self)
+    }
+
+    fn refresh_rate_hz(&self) -> Option<f64> {
+        pipeline::DesktopPipeline::refresh_rate_hz(self)
+    }
+
+    fn measured_fps(&self) -> Option<f64> {
+        pipeline::DesktopPipeline::measured_fps(self)
+    }
+
+    fn capture_stats(&self) -> pipeline::CaptureStats {
+        pipeline::DesktopPipeline::capture_stats(self)
+    }
+
+    fn pause(&mut self) -> anyhow::Result<()> {
+        pipeline::DesktopPipeline::pause(self)
+    }
+
+    fn resume(&mut self) -> anyhow::Result<()> {
+        pipeline::DesktopPipeline::resume(self)
+    }
+
+    fn is_paused(&self) -> bool {
+        pipeline::DesktopPipeline::is_paused(self)
+    }
+
+    fn exclude_windows(&mut self, hwnds: &[HWND]) -> anyhow::Result<()> {
+        pipeline::DesktopPipeline::exclude_windows(self, hwnds)
+    }
+
+    fn copy_to_clipboard(&self, frame: &pipeline::CapturedFrame) -> anyhow::Result<()> {
+        pipeline::DesktopPipeline::copy_to_clipboard(self, frame)
+    }
+}
+
 pub(crate) type WorkerHandle = (
-    mpsc::Sender<Command>,
+    mpsc::SyncSender<Command>,
     mpsc::Receiver<Response>,
     JoinHandle<()>,
 );
 
-/// Spawn a worker thread that owns a CapturePipeline and processes commands.
+/// Spawn a worker thread that owns a pipeline and processes commands.
 ///
 /// The worker initializes COM (MTA) before creating the pipeline, ensuring
 /// D3D11/WinRT calls succeed on the dedicated thread.
 /// Returns (sender, receiver, join_handle) on success, or an error string if
 /// pipeline creation itself failed.
-pub(crate) fn spawn_worker(
-    init: Box<dyn FnOnce() -> anyhow::Result<pipeline::CapturePipeline> + Send>,
+pub(crate) fn spawn_worker<P: FrameSource + 'static>(
+    init: Box<dyn FnOnce() -> anyhow::Result<P> + Send>,
 ) -> Result<WorkerHandle, String> {
     // Channel for init result: worker sends back Ok(()) or Err(msg) once pipeline is ready.
     let (init_tx, init_rx) = mpsc::channel::<Result<(), String>>();
-    let (cmd_tx, cmd_rx) = mpsc::channel::<Command>();
-    let (resp_tx, resp_rx) = mpsc::channel::<Response>();
+    let (cmd_tx, cmd_rx) = mpsc::sync_channel::<Command>(MAX_IN_FLIGHT);
+    let (resp_tx, resp_rx) = mpsc::sync_channel::<Response>(MAX_IN_FLIGHT);
 
     let handle = thread::Builder::new()
         .name("hdrcapture-worker".into())
         .spawn(move || {
-            // SAFETY: CoInitializeEx initializes COM on this thread.
             // MTA is required for D3D11 + WinRT interop (CreateDirect3D11DeviceFromDXGIDevice).
             // We call CoUninitialize on thread exit via _com_guard drop.
-            let _com_guard = unsafe {
-                use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
-                if let Err(e) = CoInitializeEx(None, COINIT_MULTITHREADED).ok() {
+            let _com_guard = match ComGuard::init_mta() {
+                Ok(guard) => guard,
+                Err(e) => {
                     let _ = init_tx.send(Err(format!("COM init failed: {e}")));
                     return;
                 }
-                ComGuard
             };
 
             let mut pipeline = match init() {
@@ -65,11 +246,39 @@ pub(crate) fn spawn_worker(
             // Event loop: process commands until Close or channel disconnect.
             while let Ok(cmd) = cmd_rx.recv() {
                 let resp = match cmd {
-                    Command::Capture => {
-                        Response::Frame(pipeline.capture().map_err(|e| e.to_string()))
+                    Command::NextFrame(mode) => {
+                        Response::Frame(pipeline.next_frame(mode).map_err(WorkerError::from))
                     }
-                    Command::Grab => Response::Frame(pipeline.grab().map_err(|e| e.to_string())),
+                    Command::GrabChanged => {
+                        Response::ChangedFrame(pipeline.grab_changed().map_err(WorkerError::from))
+                    }
+                    Command::TryGrab => {
+                        Response::ChangedFrame(pipeline.try_grab().map_err(WorkerError::from))
+                    }
+                    Command::CaptureStable { max_wait, settle } => Response::Frame(
+                        pipeline
+                            .capture_stable(max_wait, settle)
+                            .map_err(WorkerError::from),
+                    ),
                     Command::IsHdr => Response::Bool(pipeline.is_hdr()),
+                    Command::RefreshRate => Response::Float(pipeline.refresh_rate_hz()),
+                    Command::MeasuredFps => Response::Float(pipeline.measured_fps()),
+                    Command::Stats => Response::Stats(pipeline.capture_stats()),
+                    Command::Pause => Response::Done(pipeline.pause().map_err(|e| e.to_string())),
+                    Command::Resume => Response::Done(pipeline.resume().map_err(|e| e.to_string())),
+                    Command::IsPaused => Response::Bool(pipeline.is_paused()),
+                    Command::ExcludeWindows(raw) => {
+                        let hwnds: Vec<HWND> = raw
+                            .into_iter()
+                            .map(|h| HWND(h as *mut core::ffi::c_void))
+                            .collect();
+                        Response::Done(pipeline.exclude_windows(&hwnds).map_err(|e| e.to_string()))
+                    }
+                    Command::CopyToClipboard(frame) => Response::Done(
+                        pipeline
+                            .copy_to_clipboard(&frame)
+                            .map_err(|e| e.to_string()),
+                    ),
                     Command::Close => {
                         drop(pipeline);
                         let _ = resp_tx.send(Response::Closed);
@@ -99,14 +308,44 @@ pub(crate) fn spawn_worker(
     }
 }
 
-/// RAII guard for COM uninitialization.
-struct ComGuard;
+/// Capture one frame on a short-lived thread.
+///
+/// Returns the frame and the target's HDR state as soon as the frame is read;
+/// pipeline teardown (WGC session stop) finishes on the thread afterwards,
+/// off the caller's latency path.
+pub(crate) fn run_once(
+    builder: pipeline::CapturePipelineBuilder,
+) -> Result<(pipeline::CapturedFrame, bool), String> {
+    let (tx, rx) = mpsc::channel::<Result<(pipeline::CapturedFrame, bool), String>>();
 
-impl Drop for ComGuard {
-    fn drop(&mut self) {
-        // SAFETY: Paired with CoInitializeEx at thread start.
-        unsafe {
-            windows::Win32::System::Com::CoUninitialize();
-        }
-    }
+    thread::Builder::new()
+        .name("hdrcapture-oneshot".into())
+        .spawn(move || {
+            // Same COM setup as spawn_worker.
+            let _com_guard = match ComGuard::init_mta() {
+                Ok(guard) => guard,
+                Err(e) => {
+                    let _ = tx.send(Err(format!("COM init failed: {e}")));
+                    return;
+                }
+            };
+
+            let mut pipeline = match builder.single_shot().build() {
+                Ok(p) => p,
+                Err(e) => {
+                    let _ = tx.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let result = pipeline
+                .capture()
+                .map(|frame| (frame, pipeline.is_hdr()))
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+            // Pipeline drops here, after the caller already has the frame.
+        })
+        .map_err(|e| format!("Failed to spawn capture thread: {e}"))?;
+
+    rx.recv()
+        .unwrap_or_else(|_| Err("Capture thread exited unexpectedly".into()))
 }
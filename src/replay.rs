@@ -0,0 +1,265 @@
+// "Capture the last N seconds" replay buffer
+//
+// ReplayBuffer owns a capture pipeline on a dedicated thread that grab()s at
+// a fixed rate and keeps the newest frames in a ring sized by duration × fps
+// (and optionally a byte budget), evicting the oldest frames to make room.
+// Frames keep the buffers the pipeline's ElasticBufferPool handed out, so an
+// evicted frame's buffer goes straight back to the pool for the next grab().
+// dump() clones the frame handles, not the pixels.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::pipeline::{CapturePipelineBuilder, CapturedFrame};
+
+/// Highest supported capture rate.
+const MAX_FPS: u32 = 240;
+
+/// Rolling buffer of the most recent frames of a capture target.
+///
+/// Dropping the buffer stops its capture thread.
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use hdrcapture::replay::ReplayBuffer;
+///
+/// let replay = ReplayBuffer::monitor(0, Duration::from_secs(10), 30, None).unwrap();
+/// std::thread::sleep(Duration::from_secs(10));
+/// for (i, frame) in replay.dump().iter().enumerate() {
+///     frame.save(format!("clip_{i:04}.png")).unwrap();
+/// }
+/// ```
+pub struct ReplayBuffer {
+    ring: Arc<Mutex<VecDeque<CapturedFrame>>>,
+    capacity: usize,
+    max_bytes: Option<usize>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ReplayBuffer {
+    /// Keep the last `duration` of monitor `index`, sampled at `fps`.
+    ///
+    /// See [`new`](Self::new) for `max_bytes`.
+    pub fn monitor(
+        index: usize,
+        duration: Duration,
+        fps: u32,
+        max_bytes: Option<usize>,
+    ) -> Result<Self> {
+        Self::new(
+            CapturePipelineBuilder::new().monitor(index),
+            duration,
+            fps,
+            max_bytes,
+        )
+    }
+
+    /// Keep the last `duration` of `target`, sampled at `fps`.
+    ///
+    /// The pipeline is built on the capture thread; build errors are returned
+    /// here. Each tick `grab()`s one frame, so static content is repeated
+    /// (same `timestamp`) rather than leaving gaps, and the ring always spans
+    /// `duration` once full. A tick that overruns its slot (slow readback)
+    /// delays the next one instead of bursting. Capture failures are logged
+    /// at `warn` and the loop keeps going.
+    ///
+    /// The ring holds every frame's pixels: 10 s of a 4K RGBA16F monitor at
+    /// 30 fps is about 20 GB. `max_bytes` caps the pixel data kept; the
+    /// oldest frames are evicted to stay under it, so the ring then spans
+    /// less than `duration` (the newest frame is always kept). `None` keeps
+    /// `duration` × `fps` frames whatever their size.
+    pub fn new(
+        target: CapturePipelineBuilder,
+        duration: Duration,
+        fps: u32,
+        max_bytes: Option<usize>,
+    ) -> Result<Self> {
+        if !(1..=MAX_FPS).contains(&fps) {
+            bail!("fps must be between 1 and {}, got {}", MAX_FPS, fps);
+        }
+        let capacity = ring_capacity(duration, fps).with_context(|| {
+            format!(
+                "replay duration {:?} at {} fps is too many frames",
+                duration, fps
+            )
+        })?;
+        if capacity == 0 {
+            bail!("replay duration must be positive, got {:?}", duration);
+        }
+        if max_bytes == Some(0) {
+            bail!("replay max_bytes must be positive");
+        }
+        let byte_budget = max_bytes.unwrap_or(usize::MAX);
+
+        // Grown as frames arrive: `max_bytes` may bound the ring far below
+        // `capacity`.
+        let ring = Arc::new(Mutex::new(VecDeque::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread = {
+            let (ring, stop) = (Arc::clone(&ring), Arc::clone(&stop));
+            std::thread::Builder::new()
+                .name("hdrcapture-replay".into())
+                .spawn(move || {
+                    let mut pipeline = match target.build() {
+                        Ok(pipeline) => {
+                            let _ = ready_tx.send(Ok(()));
+                            pipeline
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                            return;
+                        }
+                    };
+
+                    let interval = Duration::from_secs_f64(1.0 / fps as f64);
+                    let mut next_tick = Instant::now();
+                    while !stop.load(Ordering::Acquire) {
+                        match pipeline.grab() {
+                            Ok(frame) => {
+                                let mut ring = ring.lock().expect("replay ring mutex poisoned");
+                                push_evicting(&mut ring, frame, capacity, byte_budget, |f| {
+                                    f.data.as_slice().len()
+                                });
+                            }
+                            Err(e) => log::warn!("replay capture failed: {e:#}"),
+                        }
+                        next_tick += interval;
+                        let now = Instant::now();
+                        match next_tick.checked_duration_since(now) {
+                            Some(wait) => std::thread::sleep(wait),
+                            None => next_tick = now,
+                        }
+                    }
+                })
+                .context("failed to spawn replay thread")?
+        };
+
+        ready_rx
+            .recv()
+            .map_err(|_| anyhow!("replay thread exited during startup"))??;
+        Ok(Self {
+            ring,
+            capacity,
+            max_bytes,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// The buffered frames, oldest first.
+    ///
+    /// Cheap: frames share their pixel buffers with the ring. Capture keeps
+    /// running; call again for a later window.
+    pub fn dump(&self) -> Vec<CapturedFrame> {
+        let ring = self.ring.lock().expect("replay ring mutex poisoned");
+        ring.iter().cloned().collect()
+    }
+
+    /// Number of frames currently buffered (at most [`capacity`](Self::capacity)).
+    pub fn len(&self) -> usize {
+        self.ring.lock().expect("replay ring mutex poisoned").len()
+    }
+
+    /// Whether no frame has been captured yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of frames kept: duration × fps, rounded up.
+    ///
+    /// Fewer are kept when [`max_bytes`](Self::max_bytes) is reached first.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Cap on the buffered pixel data, if any (see [`new`](Self::new)).
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Stop capturing and wait for the capture thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ReplayBuffer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Frames needed to cover `duration` at `fps`, `None` if that count doesn't
+/// fit in a `usize`.
+fn ring_capacity(duration: Duration, fps: u32) -> Option<usize> {
+    let frames = (duration.as_secs_f64() * fps as f64).ceil();
+    (frames < usize::MAX as f64).then_some(frames as usize)
+}
+
+/// Append `item`, dropping the oldest entries so at most `capacity` remain
+/// and their `size` sums to at most `max_bytes`. `item` itself is always kept.
+fn push_evicting<T>(
+    ring: &mut VecDeque<T>,
+    item: T,
+    capacity: usize,
+    max_bytes: usize,
+    size: impl Fn(&T) -> usize,
+) {
+    let mut total = ring.iter().map(&size).sum::<usize>() + size(&item);
+    while ring.len() >= capacity || (total > max_bytes && !ring.is_empty()) {
+        if let Some(evicted) = ring.pop_front() {
+            total -= size(&evicted);
+        }
+    }
+    ring.push_back(item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_evicts_oldest() {
+        assert_eq!(ring_capacity(Duration::from_secs(10), 30), Some(300));
+        assert_eq!(ring_capacity(Duration::from_millis(50), 30), Some(2));
+        assert_eq!(ring_capacity(Duration::ZERO, 30), Some(0));
+        assert_eq!(ring_capacity(Duration::MAX, MAX_FPS), None);
+
+        let mut ring = VecDeque::new();
+        for i in 0..5 {
+            push_evicting(&mut ring, i, 3, usize::MAX, |_| 1);
+        }
+        assert_eq!(ring, [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_ring_evicts_oldest_over_byte_budget() {
+        let mut ring = VecDeque::new();
+        for size in [4, 4, 4] {
+            push_evicting(&mut ring, size, 10, 10, |&s| s);
+        }
+        assert_eq!(ring, [4, 4]);
+
+        // A larger frame evicts as many as it needs, but is kept even alone
+        // over budget.
+        push_evicting(&mut ring, 8, 10, 10, |&s| s);
+        assert_eq!(ring, [8]);
+        push_evicting(&mut ring, 12, 10, 10, |&s| s);
+        assert_eq!(ring, [12]);
+    }
+}
@@ -7,3 +7,30 @@
 pub const HDR_TONEMAP_HLSL: &str = include_str!("shader/hdr_tonemap.hlsl");
 pub const HDR_TONEMAP_SHOULDER_HLSL: &str = include_str!("shader/hdr_tonemap_shoulder.hlsl");
 pub const HDR_TONEMAP_EETF_HLSL: &str = include_str!("shader/hdr_tonemap_eetf.hlsl");
+
+/// Embedded HLSL source for the bilinear resize stage (downscale / fixed output size).
+pub const SCALE_BILINEAR_HLSL: &str = include_str!("shader/scale_bilinear.hlsl");
+
+/// Embedded HLSL source for the 3D LUT (.cube) grading stage.
+pub const LUT_APPLY_HLSL: &str = include_str!("shader/lut_apply.hlsl");
+
+/// Embedded HLSL source for the content checksum stage (change detection).
+pub const CHECKSUM_HLSL: &str = include_str!("shader/checksum.hlsl");
+
+/// Embedded HLSL source for the RGBA16F output color space stage.
+pub const COLOR_SPACE_HLSL: &str = include_str!("shader/color_space.hlsl");
+
+/// Embedded HLSL source for the rotate / flip orientation stage.
+pub const ORIENT_HLSL: &str = include_str!("shader/orient.hlsl");
+
+/// Embedded HLSL source for the SDR brightness / contrast / gamma stage.
+pub const SDR_ADJUST_HLSL: &str = include_str!("shader/sdr_adjust.hlsl");
+
+/// Embedded HLSL source for the HDR10 (R10G10B10A2) → scRGB decode stage.
+pub const HDR10_DECODE_HLSL: &str = include_str!("shader/hdr10_decode.hlsl");
+
+/// Embedded HLSL source for the luminance histogram stage (auto-exposure).
+pub const LUMINANCE_HISTOGRAM_HLSL: &str = include_str!("shader/luminance_histogram.hlsl");
+
+/// Embedded HLSL source for the BGRA8 → NV12 video encoder stage.
+pub const BGRA_TO_NV12_HLSL: &str = include_str!("shader/bgra_to_nv12.hlsl");
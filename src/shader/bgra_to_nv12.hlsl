@@ -0,0 +1,51 @@
+// BGRA8 (sRGB) → NV12, BT.709 limited range, for hardware video encoders.
+//
+// The output is one R8 texture laid out like NV12 memory: `height` rows of
+// luma, then ceil(height / 2) rows of interleaved U, V samples. It is
+// 2 * ceil(width / 2) texels wide so the chroma rows fit odd widths; luma
+// columns past `width` stay unused. Each thread converts one 2×2 block:
+// four luma samples and the block's averaged chroma (edge blocks of odd
+// sizes average the pixels they have).
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float> OutputTexture : register(u0);
+
+static const float3 LUMA = float3(0.2126, 0.7152, 0.0722);
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    uint width, height;
+    InputTexture.GetDimensions(width, height);
+
+    uint2 origin = id.xy * 2;
+    if (origin.x >= width || origin.y >= height)
+        return;
+
+    float3 sum = float3(0.0, 0.0, 0.0);
+    float count = 0.0;
+    [unroll]
+    for (uint dy = 0; dy < 2; dy++)
+    {
+        [unroll]
+        for (uint dx = 0; dx < 2; dx++)
+        {
+            uint2 p = origin + uint2(dx, dy);
+            if (p.x < width && p.y < height)
+            {
+                float3 rgb = saturate(InputTexture[p].rgb);
+                OutputTexture[p] = (16.0 + dot(rgb, LUMA) * 219.0) / 255.0;
+                sum += rgb;
+                count += 1.0;
+            }
+        }
+    }
+
+    float3 rgb = sum / count;
+    float y = dot(rgb, LUMA);
+    float u = (rgb.b - y) / 1.8556;
+    float v = (rgb.r - y) / 1.5748;
+    uint row = height + id.y;
+    OutputTexture[uint2(origin.x, row)] = (128.0 + u * 224.0) / 255.0;
+    OutputTexture[uint2(origin.x + 1, row)] = (128.0 + v * 224.0) / 255.0;
+}
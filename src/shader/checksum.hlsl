@@ -0,0 +1,62 @@
+// 64-bit content checksum of a BGRA8 or RGBA16F texture.
+//
+// Each pixel is hashed together with its position, reduced per thread group in
+// groupshared memory, and one atomic per group folds the result into two
+// 32-bit lanes: lane 0 is a wrapping sum, lane 1 an XOR of a second hash.
+// Both reductions are order-independent, so the checksum is deterministic.
+// Loads go through float4; 8-bit UNORM and f16 values convert to f32 exactly,
+// so asuint() sees every bit of the source.
+
+Texture2D<float4> InputTexture : register(t0);
+RWByteAddressBuffer Checksum : register(u0);
+
+#define GROUP_THREADS 64
+
+groupshared uint2 partial[GROUP_THREADS];
+
+// PCG-style integer hash (Jarzynski & Olano, 2020).
+uint hash(uint v)
+{
+    uint state = v * 747796405u + 2891336453u;
+    uint word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID, uint index : SV_GroupIndex)
+{
+    uint width, height;
+    InputTexture.GetDimensions(width, height);
+
+    uint2 h = uint2(0, 0);
+    if (id.x < width && id.y < height)
+    {
+        uint4 bits = asuint(InputTexture[id.xy]);
+        uint seed = hash(id.y * width + id.x);
+        uint a = hash(seed ^ bits.x);
+        a = hash(a ^ bits.y);
+        a = hash(a ^ bits.z);
+        a = hash(a ^ bits.w);
+        h = uint2(a, hash(a ^ 0x9E3779B9u));
+    }
+    partial[index] = h;
+    GroupMemoryBarrierWithGroupSync();
+
+    [unroll]
+    for (uint stride = GROUP_THREADS / 2; stride > 0; stride >>= 1)
+    {
+        if (index < stride)
+        {
+            uint2 other = partial[index + stride];
+            partial[index] = uint2(partial[index].x + other.x, partial[index].y ^ other.y);
+        }
+        GroupMemoryBarrierWithGroupSync();
+    }
+
+    if (index == 0)
+    {
+        uint ignored;
+        Checksum.InterlockedAdd(0, partial[0].x, ignored);
+        Checksum.InterlockedXor(4, partial[0].y, ignored);
+    }
+}
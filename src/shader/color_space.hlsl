@@ -0,0 +1,45 @@
+// Output color space conversion for scRGB (R16G16B16A16_FLOAT) frames.
+//
+// Input is linear BT.709 primaries with 1.0 = 80 nits. A 3×3 gamut matrix is
+// applied (identity for scRGB, BT.709 -> BT.2020 for Rec.2020), then optionally
+// the sRGB transfer curve, extended to negative and >1.0 values by mirroring
+// so wide-gamut and HDR values survive. Output stays R16G16B16A16_FLOAT.
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+
+cbuffer ColorSpaceParams : register(b0)
+{
+    float4 gamut_row0;
+    float4 gamut_row1;
+    float4 gamut_row2;
+    uint encode_srgb;
+    uint3 _pad;
+};
+
+// sRGB OETF: linear -> sRGB nonlinear (piecewise)
+float srgb_encode(float u)
+{
+    return (u <= 0.0031308) ? (u * 12.92) : (1.055 * pow(u, 1.0 / 2.4) - 0.055);
+}
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    float4 rgba = InputTexture[id.xy];
+
+    float3 rgb = float3(
+        dot(gamut_row0.xyz, rgba.rgb),
+        dot(gamut_row1.xyz, rgba.rgb),
+        dot(gamut_row2.xyz, rgba.rgb));
+
+    if (encode_srgb != 0)
+    {
+        rgb = sign(rgb) * float3(
+            srgb_encode(abs(rgb.r)),
+            srgb_encode(abs(rgb.g)),
+            srgb_encode(abs(rgb.b)));
+    }
+
+    OutputTexture[id.xy] = float4(rgb, rgba.a);
+}
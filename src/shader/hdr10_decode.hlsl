@@ -0,0 +1,43 @@
+// HDR10 (R10G10B10A2_UNORM, BT.2020 primaries, PQ) -> scRGB (R16G16B16A16_FLOAT).
+//
+// Lets 10-bit captures share the scRGB tone-map shaders: the PQ signal is
+// decoded to absolute nits, converted to BT.709 primaries and scaled so
+// 1.0 = 80 nits. Out-of-BT.709 colors become negative, as in scRGB.
+//
+// The PQ / BT.2020 encoding is assumed, not read from the frame: WGC doesn't
+// tag captures with a color space (see init_capture in wgc.rs).
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+
+static const float M1 = 0.1593017578125;
+static const float M2 = 78.84375;
+static const float C1 = 0.8359375;
+static const float C2 = 18.8515625;
+static const float C3 = 18.6875;
+
+static const float3x3 BT2020_TO_BT709 = {
+     1.660491, -0.587641, -0.072850,
+    -0.124550,  1.132900, -0.008349,
+    -0.018151, -0.100579,  1.118730,
+};
+
+// SMPTE ST 2084 EOTF: PQ signal (0..1) -> absolute nits.
+float3 pq_decode(float3 signal)
+{
+    float3 p = pow(saturate(signal), 1.0 / M2);
+    return 10000.0 * pow(max(p - C1, 0.0) / (C2 - C3 * p), 1.0 / M1);
+}
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    uint width, height;
+    OutputTexture.GetDimensions(width, height);
+    if (id.x >= width || id.y >= height)
+        return;
+
+    float4 rgba = InputTexture[id.xy];
+    float3 rgb709 = mul(BT2020_TO_BT709, pq_decode(rgba.rgb));
+    OutputTexture[id.xy] = float4(rgb709 / 80.0, rgba.a);
+}
@@ -11,7 +11,8 @@ RWTexture2D<float4> OutputTexture : register(u0);
 cbuffer ToneMapParams : register(b0)
 {
     float sdr_white_nits;
-    float3 _pad;
+    float exposure;      // multiplier on the scRGB input (auto-exposure)
+    float2 _pad;
 };
 
 // sRGB OETF: linear -> sRGB nonlinear (piecewise)
@@ -20,10 +21,19 @@ float srgb_encode(float u)
     return (u <= 0.0031308) ? (u * 12.92) : (1.055 * pow(u, 1.0 / 2.4) - 0.055);
 }
 
-[numthreads(8, 8, 1)]
+// Thread group size, set by the host at compile time (default 8x8).
+#ifndef THREAD_GROUP_X
+#define THREAD_GROUP_X 8
+#endif
+#ifndef THREAD_GROUP_Y
+#define THREAD_GROUP_Y 8
+#endif
+
+[numthreads(THREAD_GROUP_X, THREAD_GROUP_Y, 1)]
 void main(uint3 id : SV_DispatchThreadID)
 {
     float4 rgba = InputTexture[id.xy];
+    rgba.rgb *= exposure;
 
     // 1. Normalize scRGB to SDR reference white
     //    scRGB 1.0 = 80 nits; SDR content lives at sdr_white_nits/80.
@@ -12,7 +12,8 @@ RWTexture2D<float4> OutputTexture : register(u0);
 cbuffer ToneMapParams : register(b0)
 {
     float sdr_white_nits;
-    float3 _pad;
+    float exposure;      // multiplier on the scRGB input (auto-exposure)
+    float2 _pad;
 };
 
 float srgb_encode(float u)
@@ -31,10 +32,19 @@ float shoulder(float x, float S, float R)
     return S + R * excess / (excess + R);
 }
 
-[numthreads(8, 8, 1)]
+// Thread group size, set by the host at compile time (default 8x8).
+#ifndef THREAD_GROUP_X
+#define THREAD_GROUP_X 8
+#endif
+#ifndef THREAD_GROUP_Y
+#define THREAD_GROUP_Y 8
+#endif
+
+[numthreads(THREAD_GROUP_X, THREAD_GROUP_Y, 1)]
 void main(uint3 id : SV_DispatchThreadID)
 {
     float4 rgba = InputTexture[id.xy];
+    rgba.rgb *= exposure;
 
     // 1. Normalize scRGB to SDR reference white
     float multiplier = 80.0 / max(sdr_white_nits, 1.0);
@@ -0,0 +1,52 @@
+// Luminance histogram of an scRGB (R16G16B16A16_FLOAT) texture for auto-exposure.
+//
+// Each pixel's BT.709 luminance (in nits, scRGB 1.0 = 80 nits) is binned on a
+// log2 scale: bin 0 collects everything darker than 2^LOG2_MIN_NITS (black),
+// bins 1..BIN_COUNT-1 split [LOG2_MIN_NITS, LOG2_MAX_NITS) evenly, brighter
+// pixels land in the last bin. Counts are gathered per thread group in
+// groupshared memory, then folded into the global buffer with one atomic per
+// non-empty bin.
+
+Texture2D<float4> InputTexture : register(t0);
+RWByteAddressBuffer Histogram : register(u0);
+
+#define BIN_COUNT 64
+#define LOG2_MIN_NITS -4.0
+#define LOG2_MAX_NITS 14.0
+
+groupshared uint bins[BIN_COUNT];
+
+uint luminance_bin(float3 rgb)
+{
+    float nits = dot(max(rgb, 0.0), float3(0.2126, 0.7152, 0.0722)) * 80.0;
+    float log2_nits = log2(max(nits, 1e-6));
+    if (log2_nits < LOG2_MIN_NITS)
+        return 0;
+    float t = (log2_nits - LOG2_MIN_NITS) / (LOG2_MAX_NITS - LOG2_MIN_NITS);
+    return 1 + min(uint(t * (BIN_COUNT - 1)), BIN_COUNT - 2);
+}
+
+// One thread per bin: the 8x8 group clears and flushes the 64 bins.
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID, uint index : SV_GroupIndex)
+{
+    uint width, height;
+    InputTexture.GetDimensions(width, height);
+
+    bins[index] = 0;
+    GroupMemoryBarrierWithGroupSync();
+
+    if (id.x < width && id.y < height)
+    {
+        uint ignored;
+        InterlockedAdd(bins[luminance_bin(InputTexture[id.xy].rgb)], 1, ignored);
+    }
+    GroupMemoryBarrierWithGroupSync();
+
+    uint count = bins[index];
+    if (count > 0)
+    {
+        uint ignored;
+        Histogram.InterlockedAdd(index * 4, count, ignored);
+    }
+}
@@ -0,0 +1,36 @@
+// 3D LUT application on SDR (B8G8R8A8_UNORM) frames.
+//
+// Runs after HDR->SDR conversion. The LUT is indexed by the display-encoded
+// (sRGB) color, remapped from DOMAIN_MIN..DOMAIN_MAX, and sampled trilinearly
+// through a 3D texture. Texel-center correction makes 0.0 and 1.0 land exactly
+// on the first and last LUT entries, matching .cube semantics.
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+Texture3D<float4> LutTexture : register(t1);
+SamplerState LutSampler : register(s0);
+
+cbuffer LutParams : register(b0)
+{
+    float3 domain_min;
+    float lut_size;
+    float3 domain_max;
+    float _pad;
+};
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    uint width, height;
+    OutputTexture.GetDimensions(width, height);
+    if (id.x >= width || id.y >= height)
+        return;
+
+    float4 rgba = InputTexture[id.xy];
+
+    float3 coord = saturate((rgba.rgb - domain_min) / (domain_max - domain_min));
+    coord = coord * ((lut_size - 1.0) / lut_size) + 0.5 / lut_size;
+
+    float3 graded = LutTexture.SampleLevel(LutSampler, coord, 0).rgb;
+    OutputTexture[id.xy] = float4(saturate(graded), rgba.a);
+}
@@ -0,0 +1,26 @@
+// Rotation / mirroring by whole pixels for any float4-readable format.
+//
+// Every combination of 90-degree rotations and flips is an affine integer
+// remap: output pixel (x, y) reads source pixel origin + x * step_x + y * step_y.
+// No filtering, so values (including alpha and HDR range) are copied exactly.
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+
+cbuffer OrientParams : register(b0)
+{
+    uint2 dst_size;
+    int2 origin;
+    int2 step_x;
+    int2 step_y;
+};
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    if (id.x >= dst_size.x || id.y >= dst_size.y)
+        return;
+
+    int2 src = origin + int(id.x) * step_x + int(id.y) * step_y;
+    OutputTexture[id.xy] = InputTexture[uint2(src)];
+}
@@ -0,0 +1,46 @@
+// Bilinear resize for any float4-readable format (BGRA8 or RGBA16F).
+//
+// The source is mapped onto a destination sub-rectangle (content rect);
+// pixels outside the rect are filled with opaque black (letterbox bars).
+// Filtering is done manually with Load() so no sampler state is needed.
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+
+cbuffer ScaleParams : register(b0)
+{
+    uint2 src_size;
+    uint2 dst_size;
+    uint2 rect_origin;
+    uint2 rect_size;
+};
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    if (id.x >= dst_size.x || id.y >= dst_size.y)
+        return;
+
+    if (id.x < rect_origin.x || id.y < rect_origin.y ||
+        id.x >= rect_origin.x + rect_size.x || id.y >= rect_origin.y + rect_size.y)
+    {
+        OutputTexture[id.xy] = float4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    // Pixel-center mapping from destination rect into source texture
+    float2 scale = float2(src_size) / float2(rect_size);
+    float2 pos = (float2(id.xy - rect_origin) + 0.5) * scale - 0.5;
+    pos = clamp(pos, 0.0, float2(src_size - 1));
+
+    uint2 p0 = uint2(floor(pos));
+    uint2 p1 = min(p0 + 1, src_size - 1);
+    float2 t = pos - float2(p0);
+
+    float4 c00 = InputTexture[uint2(p0.x, p0.y)];
+    float4 c10 = InputTexture[uint2(p1.x, p0.y)];
+    float4 c01 = InputTexture[uint2(p0.x, p1.y)];
+    float4 c11 = InputTexture[uint2(p1.x, p1.y)];
+
+    OutputTexture[id.xy] = lerp(lerp(c00, c10, t.x), lerp(c01, c11, t.x), t.y);
+}
@@ -0,0 +1,31 @@
+// Brightness / contrast / gamma adjustment of SDR (B8G8R8A8_UNORM) frames.
+//
+// Runs on display-encoded color after tone-mapping and the LUT. Gamma bends
+// the midtones (out = in^(1/gamma)), contrast scales around mid-gray, then
+// brightness is added. Alpha is passed through.
+
+Texture2D<float4> InputTexture : register(t0);
+RWTexture2D<float4> OutputTexture : register(u0);
+
+cbuffer AdjustParams : register(b0)
+{
+    float brightness;
+    float contrast;
+    float inv_gamma;
+    float _pad;
+};
+
+[numthreads(8, 8, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    uint width, height;
+    OutputTexture.GetDimensions(width, height);
+    if (id.x >= width || id.y >= height)
+        return;
+
+    float4 rgba = InputTexture[id.xy];
+
+    float3 rgb = pow(saturate(rgba.rgb), inv_gamma);
+    rgb = (rgb - 0.5) * contrast + 0.5 + brightness;
+    OutputTexture[id.xy] = float4(saturate(rgb), rgba.a);
+}
@@ -0,0 +1,273 @@
+// Handoff of captured frames to external video encoders
+//
+// VideoSink is the integration point: CapturePipeline::stream_to() paces a
+// capture loop and pushes one frame per tick with its presentation time.
+// RawVideoWriter is the reference sink: headerless BGRA or I420 (yuv420p)
+// frames back to back, the "rawvideo" input ffmpeg and most encoders read
+// from a file or a pipe. No media dependency is pulled in.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use crate::pipeline::CapturedFrame;
+
+/// Consumer of a paced frame stream, see
+/// [`CapturePipeline::stream_to`](crate::pipeline::CapturePipeline::stream_to).
+pub trait VideoSink {
+    /// Take the frame for the tick at `timestamp` (time since the stream
+    /// started, a multiple of the frame interval).
+    ///
+    /// Return `ControlFlow::Break(())` to end the stream (whether `frame` was
+    /// still consumed is up to the sink); an error ends it as well and is
+    /// returned by `stream_to`.
+    fn push(&mut self, frame: &CapturedFrame, timestamp: Duration) -> Result<ControlFlow<()>>;
+
+    /// Flush buffered output once the sink ended the stream. The default does
+    /// nothing.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Pixel layout written by [`RawVideoWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RawVideoFormat {
+    /// 4 bytes per pixel, B G R A; ffmpeg `bgra`.
+    #[default]
+    Bgra,
+    /// Planar 8-bit YUV 4:2:0 (full Y plane, then U and V at half
+    /// resolution, rounded up), BT.709 limited range; ffmpeg `yuv420p`.
+    /// 1.5 bytes per pixel; most encoders convert to 4:2:0 anyway.
+    I420,
+}
+
+impl RawVideoFormat {
+    /// ffmpeg `-pixel_format` name.
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            Self::Bgra => "bgra",
+            Self::I420 => "yuv420p",
+        }
+    }
+
+    /// Bytes of one `width` × `height` frame.
+    pub fn frame_bytes(self, width: u32, height: u32) -> usize {
+        let (w, h) = (width as usize, height as usize);
+        match self {
+            Self::Bgra => w * h * 4,
+            Self::I420 => w * h + 2 * w.div_ceil(2) * h.div_ceil(2),
+        }
+    }
+}
+
+/// [`VideoSink`] writing raw frames for ffmpeg's `rawvideo` demuxer.
+///
+/// The stream has no header: the reader needs the pixel format, frame size
+/// and rate (see [`ffmpeg_input_args`](Self::ffmpeg_input_args)). All frames
+/// must have the size of the first one; build the pipeline with
+/// [`resize_output`](crate::pipeline::CapturePipelineBuilder::resize_output)
+/// when the target may change size (windows). HDR frames are tone-mapped to
+/// SDR on the CPU side (see [`CapturedFrame::to_sdr`]), so prefer
+/// `CapturePolicy::Auto` for recording.
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use hdrcapture::pipeline::{CapturePipeline, CapturePolicy};
+/// use hdrcapture::video::{RawVideoFormat, RawVideoWriter};
+///
+/// // Record 10 seconds at 30 fps, then encode with
+/// // ffmpeg -f rawvideo -pixel_format yuv420p -video_size WxH -framerate 30 \
+/// //        -i screen.yuv -c:v libx264 screen.mp4
+/// let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+/// let mut sink = RawVideoWriter::create("screen.yuv", RawVideoFormat::I420)
+///     .unwrap()
+///     .with_duration(Duration::from_secs(10));
+/// cap.stream_to(&mut sink, 30).unwrap();
+/// println!("{}", sink.ffmpeg_input_args(30).unwrap().join(" "));
+/// ```
+pub struct RawVideoWriter<W: Write = BufWriter<File>> {
+    out: W,
+    format: RawVideoFormat,
+    /// Frame size, fixed by the first frame.
+    size: Option<(u32, u32)>,
+    /// Stream length after which `push` ends the stream.
+    duration: Option<Duration>,
+    frames: u64,
+    /// Conversion buffer reused across frames.
+    scratch: Vec<u8>,
+}
+
+impl RawVideoWriter {
+    /// Write to a new file at `path` (truncating an existing one).
+    pub fn create(path: impl AsRef<Path>, format: RawVideoFormat) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("failed to create video file {}", path.display()))?;
+        Ok(Self::new(BufWriter::new(file), format))
+    }
+}
+
+impl<W: Write> RawVideoWriter<W> {
+    /// Write to `out`, e.g. the stdin of an ffmpeg child process started
+    /// with `-i -` and [`ffmpeg_input_args`](Self::ffmpeg_input_args).
+    pub fn new(out: W, format: RawVideoFormat) -> Self {
+        Self {
+            out,
+            format,
+            size: None,
+            duration: None,
+            frames: 0,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// End the stream once `duration` of video has been written.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Frame size of the stream, known after the first frame.
+    pub fn frame_size(&self) -> Option<(u32, u32)> {
+        self.size
+    }
+
+    /// Number of frames written.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// ffmpeg input options describing the stream at `fps`, to put before
+    /// `-i <file>`. `None` until the first frame fixed the size.
+    pub fn ffmpeg_input_args(&self, fps: u32) -> Option<Vec<String>> {
+        let (width, height) = self.size?;
+        Some(
+            [
+                "-f",
+                "rawvideo",
+                "-pixel_format",
+                self.format.ffmpeg_name(),
+                "-video_size",
+                &format!("{width}x{height}"),
+                "-framerate",
+                &fps.to_string(),
+            ]
+            .map(String::from)
+            .to_vec(),
+        )
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.out.flush().context("failed to flush video stream")?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write> VideoSink for RawVideoWriter<W> {
+    fn push(&mut self, frame: &CapturedFrame, timestamp: Duration) -> Result<ControlFlow<()>> {
+        if self.duration.is_some_and(|duration| timestamp >= duration) {
+            return Ok(ControlFlow::Break(()));
+        }
+        let size = (frame.width, frame.height);
+        match self.size {
+            None => self.size = Some(size),
+            Some(expected) if expected != size => bail!(
+                "raw video frames must keep one size: got {}x{} after {}x{}",
+                size.0,
+                size.1,
+                expected.0,
+                expected.1
+            ),
+            Some(_) => {}
+        }
+
+        let sdr = frame.to_sdr()?.unpremultiply();
+        let bgra = sdr.data.as_slice();
+        let bytes = match self.format {
+            RawVideoFormat::Bgra => bgra,
+            RawVideoFormat::I420 => {
+                self.scratch
+                    .resize(self.format.frame_bytes(frame.width, frame.height), 0);
+                bgra_to_i420(bgra, frame.width, frame.height, &mut self.scratch);
+                &self.scratch
+            }
+        };
+        self.out
+            .write_all(bytes)
+            .context("failed to write video frame")?;
+        self.frames += 1;
+        Ok(ControlFlow::Continue(()))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush().context("failed to flush video stream")
+    }
+}
+
+/// Convert tightly packed BGRA8 (sRGB) to I420, BT.709 limited range. Chroma
+/// is the average of each 2×2 block (edge blocks of odd sizes use the pixels
+/// they have). `out` holds `RawVideoFormat::I420.frame_bytes(width, height)`.
+fn bgra_to_i420(bgra: &[u8], width: u32, height: u32, out: &mut [u8]) {
+    let (w, h) = (width as usize, height as usize);
+    let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+    let (y_plane, chroma) = out.split_at_mut(w * h);
+    let (u_plane, v_plane) = chroma.split_at_mut(cw * ch);
+
+    let rgb = |x: usize, y: usize| {
+        let p = &bgra[(y * w + x) * 4..];
+        (p[2] as f32, p[1] as f32, p[0] as f32)
+    };
+    for y in 0..h {
+        for x in 0..w {
+            let (r, g, b) = rgb(x, y);
+            let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            y_plane[y * w + x] = (16.0 + luma * 219.0 / 255.0).round() as u8;
+        }
+    }
+    for cy in 0..ch {
+        for cx in 0..cw {
+            let (mut r, mut g, mut b, mut n) = (0.0, 0.0, 0.0, 0.0);
+            for y in cy * 2..(cy * 2 + 2).min(h) {
+                for x in cx * 2..(cx * 2 + 2).min(w) {
+                    let p = rgb(x, y);
+                    (r, g, b, n) = (r + p.0, g + p.1, b + p.2, n + 1.0);
+                }
+            }
+            let (r, g, b) = (r / n, g / n, b / n);
+            let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let u = (b - luma) / 1.8556;
+            let v = (r - luma) / 1.5748;
+            u_plane[cy * cw + cx] = (128.0 + u * 224.0 / 255.0).round() as u8;
+            v_plane[cy * cw + cx] = (128.0 + v * 224.0 / 255.0).round() as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bgra_to_i420_levels() {
+        // 3×1: white, black, pure red. Odd width → 2 chroma columns.
+        let bgra = [255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 255, 255];
+        let mut out = vec![0; RawVideoFormat::I420.frame_bytes(3, 1)];
+        assert_eq!(out.len(), 3 + 2 + 2);
+        bgra_to_i420(&bgra, 3, 1, &mut out);
+
+        // Limited range luma: white 235, black 16; red 16 + 0.2126 × 219.
+        assert_eq!(&out[..3], &[235, 16, 63]);
+        // White + black average to neutral gray: no chroma.
+        assert_eq!((out[3], out[5]), (128, 128));
+        // Red: maximum V, U below neutral.
+        assert_eq!(out[6], 240);
+        assert!(out[4] < 128);
+    }
+}
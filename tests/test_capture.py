@@ -2,7 +2,10 @@
 
 from __future__ import annotations
 
+import io
+import time
 import tracemalloc
+import warnings
 from pathlib import Path
 from typing import Any
 
@@ -22,8 +25,10 @@ def test_screenshot_save_multi_format(tmp_path: Path) -> None:
         "bmp": tmp_path / "test_screenshot.bmp",
         "jpg": tmp_path / "test_screenshot.jpg",
         "tiff": tmp_path / "test_screenshot.tiff",
+        "webp": tmp_path / "test_screenshot.webp",
         "jxr": tmp_path / "test_screenshot.jxr",
         "exr": tmp_path / "test_screenshot.exr",
+        "hdr": tmp_path / "test_screenshot.hdr",
     }
 
     for path in outputs.values():
@@ -32,6 +37,47 @@ def test_screenshot_save_multi_format(tmp_path: Path) -> None:
         assert path.stat().st_size > 0
 
 
+def test_exr_compression(tmp_path: Path) -> None:
+    frame = hdrcapture.screenshot()
+
+    sizes = {}
+    for compression in ("none", "rle", "zip", "piz"):
+        path = tmp_path / f"test_{compression}.exr"
+        frame.save(str(path), compression=compression)
+        sizes[compression] = path.stat().st_size
+    assert sizes["zip"] < sizes["none"]
+
+    with pytest.raises(ValueError):
+        frame.save(str(tmp_path / "bad.exr"), compression="lzma")
+    with pytest.raises(ValueError):
+        frame.save(str(tmp_path / "bad.png"), compression="zip")
+
+
+def test_exr_assume_srgb(tmp_path: Path) -> None:
+    frame = hdrcapture.screenshot(mode="sdr")
+    frame.save(str(tmp_path / "linear.exr"))
+    frame.save(str(tmp_path / "encoded.exr"), assume_srgb=False)
+    assert (tmp_path / "linear.exr").stat().st_size > 0
+    assert (tmp_path / "encoded.exr").stat().st_size > 0
+
+    with pytest.raises(ValueError):
+        frame.save(str(tmp_path / "bad.png"), assume_srgb=False)
+
+
+def test_jpeg_quality(tmp_path: Path) -> None:
+    frame = hdrcapture.screenshot()
+
+    low, high = tmp_path / "low.jpg", tmp_path / "high.jpg"
+    frame.save(str(low), quality=20)
+    frame.save(str(high), quality=95)
+    assert low.stat().st_size < high.stat().st_size
+
+    with pytest.raises(ValueError):
+        frame.save(str(tmp_path / "bad.jpg"), quality=0)
+    with pytest.raises(ValueError):
+        frame.save(str(tmp_path / "bad.png"), quality=90)
+
+
 def test_bgra8_ndarray_conversion() -> None:
     frame = hdrcapture.screenshot()
 
@@ -42,6 +88,63 @@ def test_bgra8_ndarray_conversion() -> None:
     arr2 = np.array(frame)
     assert np.array_equal(arr, arr2)
 
+    rgb = frame.ndarray(channels=3)
+    assert rgb.shape == (frame.height, frame.width, 3)
+    assert np.array_equal(rgb, arr[..., 2::-1])
+    assert np.array_equal(frame.ndarray(channels=3, order="bgr"), arr[..., :3])
+
+
+def test_frame_reports_dpi_scale() -> None:
+    frame = hdrcapture.screenshot()
+    assert 1.0 <= frame.dpi_scale <= 5.0
+
+
+def test_frame_diff(tmp_path: Path) -> None:
+    frame = hdrcapture.screenshot()
+
+    num_different, max_delta, diff_frame = frame.diff(frame)
+    assert (num_different, max_delta, diff_frame) == (0, 0.0, None)
+
+    with hdrcapture.capture.monitor(0, size=(64, 64)) as cap:
+        small = cap.capture()
+    with pytest.raises(ValueError):
+        frame.diff(small)
+
+
+def test_max_size_caps_output() -> None:
+    native = hdrcapture.screenshot()
+    with hdrcapture.capture.monitor(0, max_size=(160, 160)) as cap:
+        frame = cap.capture()
+    assert frame.width <= 160 and frame.height <= 160
+    assert max(frame.width, frame.height) == 160
+    # Aspect ratio kept within rounding.
+    assert abs(frame.width / frame.height - native.width / native.height) < 0.05
+
+
+def test_format_forces_capture_surface() -> None:
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        with hdrcapture.capture.monitor(0, mode="hdr", format="rgba16f") as cap:
+            frame = cap.capture()
+    assert frame.format == "rgba16f"
+
+    with pytest.raises(RuntimeError, match="invalid format"):
+        hdrcapture.capture.monitor(0, format="rgb565")
+
+
+def test_buffer_is_zero_copy_view() -> None:
+    frame = hdrcapture.screenshot()
+
+    view = frame.buffer()
+    assert view.readonly
+    assert frame.stride == frame.width * 4
+    assert view.nbytes == frame.height * frame.stride
+    assert view.tobytes() == frame.ndarray().tobytes()
+
+    del frame
+    assert len(view.tobytes()) == view.nbytes
+    view.release()
+
 
 def test_capture_class_capture_and_grab() -> None:
     cap = hdrcapture.capture.monitor(0)
@@ -57,6 +160,67 @@ def test_capture_class_capture_and_grab() -> None:
         cap.close()
 
 
+def test_grab_changed() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        # The first call always reports a change.
+        first = cap.grab_changed(timeout=5.0)
+        assert first is not None
+        assert first.width > 0 and first.height > 0
+
+        # Later calls return None or a frame, depending on screen activity.
+        later = cap.grab_changed(timeout=5.0)
+        assert later is None or later.width == first.width
+
+
+def test_try_grab() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        first = cap.capture(timeout=5.0)
+
+        # Never waits: either a frame buffered since capture(), or None.
+        start = time.perf_counter()
+        frame = cap.try_grab(timeout=5.0)
+        assert frame is None or frame.width == first.width
+        assert time.perf_counter() - start < 1.0
+
+
+def test_capture_stable() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        frame = cap.capture_stable(max_wait=1.0, settle=0.05)
+        assert frame.width > 0 and frame.height > 0
+
+        with pytest.raises(ValueError):
+            cap.capture_stable(settle=-1.0)
+
+
+def test_pause_and_resume() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        first = cap.grab()
+        assert not cap.paused
+
+        cap.pause()
+        cap.pause()
+        assert cap.paused
+        with pytest.raises(RuntimeError, match="paused"):
+            cap.grab()
+
+        cap.resume()
+        assert not cap.paused
+        frame = cap.grab(timeout=5.0)
+        assert frame.width == first.width and frame.height == first.height
+
+
+def test_exclude_windows_rejects_foreign_windows() -> None:
+    import ctypes
+
+    shell_hwnd = ctypes.windll.user32.GetShellWindow()
+    with hdrcapture.capture.monitor(0) as cap:
+        cap.exclude_windows([])
+        if shell_hwnd:
+            with pytest.raises(RuntimeError, match="own windows"):
+                cap.exclude_windows([shell_hwnd])
+        assert cap.grab(timeout=5.0).width > 0
+
+
 def test_context_manager_closes_capture() -> None:
     with hdrcapture.capture.monitor(0) as cap:
         frame = cap.capture()
@@ -66,6 +230,95 @@ def test_context_manager_closes_capture() -> None:
         cap.capture()
 
 
+def test_reopen_after_close() -> None:
+    cap = hdrcapture.capture.monitor(0, mode="sdr")
+    first = cap.capture()
+    cap.close()
+    with pytest.raises(RuntimeError, match="closed"):
+        cap.capture()
+
+    cap.reopen()
+    try:
+        assert repr(cap) == "Capture(active)"
+        frame = cap.capture()
+        assert (frame.width, frame.height) == (first.width, first.height)
+        assert frame.format == first.format
+    finally:
+        cap.close()
+
+
+def test_capture_and_grab_timeout() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        frame = cap.capture(timeout=5.0)
+        assert frame.width > 0 and frame.height > 0
+        frame = cap.grab(timeout=5.0)
+        assert frame.width > 0 and frame.height > 0
+
+        with pytest.raises(ValueError):
+            cap.capture(timeout=-1.0)
+
+
+def test_next_frame_modes() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        frame = cap.next_frame()
+        assert frame.width > 0 and frame.height > 0
+        frame = cap.next_frame(mode="latest", timeout=5.0)
+        assert frame.width > 0 and frame.height > 0
+
+        with pytest.raises(RuntimeError):
+            cap.next_frame(mode="newest")
+
+
+def test_frames_iterator() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        frames = list(cap.frames(max_frames=3))
+        assert len(frames) == 3
+        assert all(f.width > 0 for f in frames)
+
+        # Stopping iteration leaves the capture open.
+        for frame in cap.frames(fps=30):
+            assert frame.height > 0
+            break
+        assert cap.grab().width > 0
+
+        with pytest.raises(ValueError):
+            cap.frames(fps=0)
+
+
+def test_frame_rotate_and_flip() -> None:
+    frame = hdrcapture.screenshot()
+    rotated = frame.rotate(90)
+    assert (rotated.width, rotated.height) == (frame.height, frame.width)
+    assert rotated.rotate(270).ndarray().tobytes() == frame.ndarray().tobytes()
+    flipped = frame.flip("vertical")
+    assert flipped.flip("vertical").ndarray().tobytes() == frame.ndarray().tobytes()
+
+    with pytest.raises(RuntimeError):
+        frame.rotate(45)
+    with pytest.raises(RuntimeError):
+        frame.flip("diagonal")
+
+
+def test_monitor_rotate_option() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        plain = cap.capture()
+    with hdrcapture.capture.monitor(0, rotate=90, auto_rotate=True) as cap:
+        frame = cap.capture()
+    assert sorted((frame.width, frame.height)) == sorted((plain.width, plain.height))
+
+
+def test_monitor_at_point() -> None:
+    # The primary monitor's top-left corner is always (0, 0).
+    with hdrcapture.capture.monitor(at=(0, 0)) as cap:
+        frame = cap.capture()
+        assert frame.width > 0 and frame.height > 0
+
+    with pytest.raises(RuntimeError):
+        hdrcapture.capture.monitor(at=(-1_000_000, -1_000_000))
+    with pytest.raises(ValueError):
+        hdrcapture.capture.monitor(name=r"\\.\DISPLAY1", at=(0, 0))
+
+
 def test_continuous_grab_latency_sanity() -> None:
     cap = hdrcapture.capture.monitor(0)
     try:
@@ -93,6 +346,9 @@ def test_error_handling_invalid_targets() -> None:
     with pytest.raises(RuntimeError):
         hdrcapture.capture.window(pid=999_999_999)
 
+    with pytest.raises(RuntimeError):
+        hdrcapture.capture.window(app_id="__Nonexistent.App_12345!App")
+
     with pytest.raises(RuntimeError):
         hdrcapture.capture.window(hwnd=0)
 
@@ -103,6 +359,10 @@ def test_error_handling_invalid_targets() -> None:
         hdrcapture.screenshot(hwnd=0)
 
 
+def test_protected_content_error_is_runtime_error() -> None:
+    assert issubclass(hdrcapture.ProtectedContentError, RuntimeError)
+
+
 def test_window_selector_priority_warnings() -> None:
     with pytest.warns(UserWarning):
         with pytest.raises(RuntimeError):
@@ -144,15 +404,189 @@ def test_capture_modes_and_invalid_mode(tmp_path: Path) -> None:
         assert hdr_jxr.exists() and hdr_jxr.stat().st_size > 0
         assert hdr_exr.exists() and hdr_exr.stat().st_size > 0
 
+        hdr_png = tmp_path / "test_hdr.png"
+        frame_hdr.save(str(hdr_png))
+        assert hdr_png.exists() and hdr_png.stat().st_size > 0
+        with pytest.raises(RuntimeError):
+            frame_hdr.save(str(tmp_path / "strict.png"), strict=True)
+
     with pytest.raises(RuntimeError):
         hdrcapture.screenshot(mode="invalid")  # type: ignore[arg-type]
 
 
+def test_prefer_hdr_mode() -> None:
+    with hdrcapture.capture.monitor(0, mode="prefer_hdr") as cap:
+        frame = cap.capture()
+        assert frame.format == ("rgba16f" if cap.is_hdr else "bgra8")
+
+
 def test_is_hdr_property_type() -> None:
     with hdrcapture.capture.monitor(0) as cap:
         assert isinstance(cap.is_hdr, bool)
 
 
+def test_source_display_format_matches_hdr_state() -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        frame = cap.grab()
+        assert frame.source_color_space in ("srgb", "pq_rec2020", "scrgb", "unknown")
+        assert isinstance(frame.bits_per_channel, int)
+        if cap.is_hdr:
+            assert frame.source_color_space == "pq_rec2020"
+
+
+def test_save_batch_reports_per_file_results(tmp_path: Path) -> None:
+    with hdrcapture.capture.monitor(0) as cap:
+        frames = [cap.grab() for _ in range(4)]
+    items = [(f, str(tmp_path / f"shot_{i}.png")) for i, f in enumerate(frames)]
+    items.append((frames[0], str(tmp_path / "bad.unknown")))
+
+    results = hdrcapture.save_batch(items)
+
+    assert results[:4] == [None] * 4
+    assert isinstance(results[4], str)
+    assert all((tmp_path / f"shot_{i}.png").exists() for i in range(4))
+
+
+def test_sdr_white_override() -> None:
+    with hdrcapture.capture.monitor(0, sdr_white=203.0) as cap:
+        assert cap.grab().width > 0
+    with pytest.raises(RuntimeError):
+        hdrcapture.capture.monitor(0, sdr_white=0.0)
+
+
+def test_sdr_adjust() -> None:
+    with hdrcapture.capture.monitor(0, mode="sdr") as cap:
+        plain = cap.capture().ndarray()
+    with hdrcapture.capture.monitor(0, mode="sdr", brightness=1.0) as cap:
+        frame = cap.capture()
+        assert frame.format == "bgra8"
+        # Full brightness offset saturates every color channel.
+        assert (frame.ndarray()[..., :3] == 255).all()
+    assert plain.shape == frame.ndarray().shape
+    with pytest.raises(RuntimeError):
+        hdrcapture.capture.monitor(0, gamma=0.0)
+
+
+def test_save_auto(tmp_path: Path) -> None:
+    frame = hdrcapture.screenshot()
+    first = Path(frame.save_auto(str(tmp_path / "shots"), "shot"))
+    second = Path(frame.save_auto(str(tmp_path / "shots"), "shot"))
+    assert first.parent == tmp_path / "shots"
+    assert first.name.startswith("shot_") and first.suffix == ".png"
+    # Same frame, same timestamp: the second name gets a counter.
+    assert second != first and second.stem == first.stem + "_1"
+    assert first.stat().st_size > 0 and second.stat().st_size > 0
+
+
+def test_save_to_file_object() -> None:
+    frame = hdrcapture.screenshot()
+    buf = io.BytesIO()
+    frame.save(buf)
+    assert buf.getvalue() == frame.to_bytes("png")
+
+    jpg = io.BytesIO()
+    frame.save(jpg, format="jpg")
+    assert jpg.getvalue()[:2] == b"\xff\xd8"
+
+    # Encoder options follow the format, as for a path.
+    small = io.BytesIO()
+    frame.save(small, format="jpg", quality=10)
+    assert len(small.getvalue()) < len(jpg.getvalue())
+    exr = io.BytesIO()
+    frame.save(exr, format="exr", compression="piz")
+    assert exr.getvalue()[:4] == b"\x76\x2f\x31\x01"
+
+    with pytest.raises(ValueError):
+        frame.save(io.BytesIO(), quality=80)
+    with pytest.raises(ValueError):
+        frame.save(io.BytesIO(), format="png", compression="zip")
+    with pytest.raises(TypeError):
+        frame.save(42)  # type: ignore[arg-type]
+
+
+def test_pixel_matches_ndarray() -> None:
+    frame = hdrcapture.screenshot()
+    b, g, r, a = frame.ndarray()[0, 0]
+    assert frame.pixel(0, 0) == (r, g, b, a)
+    with pytest.raises(ValueError):
+        frame.pixel(frame.width, 0)
+
+
+def test_blank_detection() -> None:
+    frame = hdrcapture.screenshot()
+    # Nothing is brighter than full scale.
+    assert frame.is_blank(1.0)
+    with hdrcapture.capture.monitor(0, blank_threshold=1.0) as cap:
+        assert cap.capture().warning == "blank_frame"
+    with pytest.raises(RuntimeError):
+        hdrcapture.capture.monitor(0, blank_threshold=-1.0)
+
+
+def test_hdr10_format(tmp_path: Path) -> None:
+    with hdrcapture.capture.monitor(0, mode="hdr", hdr10=True) as cap:
+        if not cap.is_hdr:
+            pytest.skip("HDR10 capture needs an HDR monitor")
+        frame = cap.capture()
+    assert frame.format == "rgb10a2"
+    arr = frame.ndarray()
+    assert arr.shape == (frame.height, frame.width, 4)
+    assert arr.dtype == np.uint16
+    assert arr.max() <= 1023
+    frame.save(str(tmp_path / "hdr10.exr"))
+    assert (tmp_path / "hdr10.exr").stat().st_size > 0
+
+
+def test_watchdog_option() -> None:
+    with hdrcapture.capture.monitor(0, watchdog=0.05) as cap:
+        cap.grab()
+        time.sleep(0.2)
+        frame = cap.grab()
+        assert frame.warning in (None, "session_restarted")
+        assert cap.stats()["session_restarts"] >= 0
+    with pytest.raises(ValueError):
+        hdrcapture.capture.monitor(0, watchdog=-1.0)
+
+
+def test_replay_buffer_keeps_last_frames() -> None:
+    with hdrcapture.ReplayBuffer(monitor=0, seconds=0.2, fps=20) as replay:
+        assert replay.capacity == 4
+        time.sleep(0.5)
+        frames = replay.dump()
+        assert 0 < len(frames) <= replay.capacity
+        timestamps = [f.timestamp for f in frames]
+        assert timestamps == sorted(timestamps)
+    # Dumped frames outlive the buffer.
+    assert frames[-1].ndarray().shape[2] == 4
+    with pytest.raises(RuntimeError):
+        len(replay)
+    with pytest.raises(RuntimeError):
+        hdrcapture.ReplayBuffer(fps=0)
+
+
+def test_replay_buffer_max_bytes_evicts_oldest() -> None:
+    with hdrcapture.ReplayBuffer(monitor=0, seconds=1.0, fps=20, max_bytes=1) as replay:
+        assert replay.max_bytes == 1
+        time.sleep(0.3)
+        # Every frame is over budget, so only the newest is kept.
+        assert len(replay.dump()) == 1
+    with pytest.raises(RuntimeError):
+        hdrcapture.ReplayBuffer(max_bytes=0)
+
+
+def test_to_clipboard() -> None:
+    with hdrcapture.capture.monitor(0, mode="hdr") as cap:
+        cap.grab().to_clipboard()
+        cap.copy_to_clipboard(cap.grab())
+
+
+def test_watch_display_changes_is_idempotent() -> None:
+    hdrcapture.watch_display_changes()
+    hdrcapture.watch_display_changes()
+    with hdrcapture.capture.monitor(0) as cap:
+        cap.grab()
+        assert isinstance(cap.is_hdr, bool)
+
+
 def test_hdr_ndarray_conversion_when_available() -> None:
     hdr_frame: Any | None = None
     with hdrcapture.capture.monitor(0, mode="hdr") as cap:
@@ -3,17 +3,35 @@
 // Tests:
 // 1. Capture each available monitor by index (with timing)
 // 2. Consecutive frame capture (drain strategy + buffer reuse)
+//    and Desktop Duplication backend, MultiCapture on a shared device,
+//    pipelines on threads without COM or with an STA, shared and same-device
+//    GPU textures, handle release on close / drop, on_frame callbacks
+//    finished by drop, runtime policy switches on fp16 and BGRA8 frame pools
 // 3. SDR: save to all supported formats with timing
-// 4. HDR: save to HDR-capable formats (jxr, exr) with timing
+// 4. HDR: save to HDR-capable formats (jxr, exr, hdr) with timing, plus EXR
+//    compression modes (size / speed trade-off)
 //
 // Results are saved to tests/results/test_report.txt
 
 use std::fmt::Write as FmtWrite;
 use std::fs;
-use std::time::Instant;
-
-use hdrcapture::capture::find_monitor;
-use hdrcapture::pipeline::{CapturePipeline, CapturePolicy};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hdrcapture::capture::{
+    find_monitor, init_capture, monitor_infos, CaptureTarget, SessionOptions,
+};
+use hdrcapture::color::ColorPixelFormat;
+use hdrcapture::image::{ExrCompression, ExrOptions, SaveOptions};
+use hdrcapture::pipeline::{
+    CaptureBackend, CapturePipeline, CapturePipelineBuilder, CapturePolicy, CapturedFrame,
+    FrameTransform, MultiCapture, Rotation, ScaleFit,
+};
+use windows::Win32::Graphics::Direct3D11::D3D11_TEXTURE2D_DESC;
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+use windows::Win32::System::Threading::{GetCurrentProcess, GetProcessHandleCount};
+use windows::Win32::UI::WindowsAndMessaging::SetCursorPos;
 
 /// Shared report buffer, written to file at the end of each test.
 fn save_report(name: &str, report: &str) {
@@ -120,6 +138,489 @@ fn test_consecutive_frames() {
     save_report("test_consecutive_frames", &report);
 }
 
+#[test]
+fn test_desktop_duplication_backend() {
+    let mut report = String::from("=== Desktop Duplication ===\n\n");
+
+    let mut pipeline = CapturePipelineBuilder::new()
+        .monitor(0)
+        .backend(CaptureBackend::DesktopDuplication)
+        .build()
+        .expect("Failed to create duplication pipeline");
+
+    // capture() waits briefly for a new image; grab() falls back to the cached
+    // frame when the desktop is static.
+    let first = pipeline.capture().unwrap();
+    let second = pipeline.grab().unwrap();
+    assert!(first.width > 0 && first.height > 0);
+    assert_eq!((first.width, first.height), (second.width, second.height));
+    assert!(
+        first.data.iter().any(|&b| b != 0),
+        "Duplication captured all black"
+    );
+
+    let line = format!(
+        "Duplication: {}x{}, format={:?}, hdr={}\n",
+        first.width,
+        first.height,
+        first.format,
+        pipeline.is_hdr()
+    );
+    print!("{}", line);
+    write!(report, "{}", line).unwrap();
+    save_report("test_desktop_duplication", &report);
+}
+
+#[test]
+fn test_multi_capture_shared_device() {
+    let mut report = String::from("=== MultiCapture ===\n\n");
+
+    let mut multi = MultiCapture::new(CapturePolicy::Auto).unwrap();
+    let first = multi.add_monitor(0).unwrap();
+    let second = multi.add_monitor(0).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(multi.ids(), vec![first, second]);
+
+    for id in [first, second, first] {
+        let t = Instant::now();
+        let frame = multi.capture(id).unwrap();
+        let capture_ms = t.elapsed().as_secs_f64() * 1000.0;
+        assert!(frame.width > 0 && frame.height > 0);
+
+        let line = format!(
+            "Target {}: {}x{}, format={:?}, capture={:.2}ms\n",
+            id, frame.width, frame.height, frame.format, capture_ms
+        );
+        print!("{}", line);
+        write!(report, "{}", line).unwrap();
+    }
+
+    assert!(multi.remove(second));
+    assert!(!multi.remove(second));
+    assert!(multi.capture(second).is_err());
+    save_report("test_multi_capture", &report);
+}
+
+#[test]
+fn test_pipeline_initializes_com_on_its_thread() {
+    // Fresh thread without COM: the pipeline joins the MTA itself, and a
+    // second pipeline after the first is dropped still works.
+    std::thread::spawn(|| {
+        for _ in 0..2 {
+            let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+            assert!(cap.capture().unwrap().width > 0);
+        }
+    })
+    .join()
+    .unwrap();
+
+    // A thread that is already an STA (e.g. a UI thread) keeps its apartment.
+    std::thread::spawn(|| {
+        // SAFETY: paired with CoUninitialize after the pipeline is dropped.
+        unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).ok().unwrap() };
+        {
+            let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+            assert!(cap.capture().unwrap().width > 0);
+        }
+        unsafe { CoUninitialize() };
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn test_capture_shared_texture() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    let first = cap.capture_shared().unwrap();
+    assert!(!first.handle().is_invalid());
+    assert!(first.width > 0 && first.height > 0);
+
+    // Each call exports a new texture; static content repeats the last frame.
+    let second = cap.capture_shared().unwrap();
+    assert_ne!(first.handle(), second.handle());
+    assert_eq!((first.width, first.height), (second.width, second.height));
+
+    // The readback path keeps working alongside.
+    let frame = cap.capture().unwrap();
+    assert_eq!((frame.width, frame.height), (first.width, first.height));
+}
+
+#[test]
+fn test_capture_texture_stays_on_gpu() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    let (width, height) = {
+        let frame = cap.capture_texture().unwrap();
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        // SAFETY: GetDesc writes into a caller-provided struct.
+        unsafe { frame.texture().GetDesc(&mut desc) };
+        assert_eq!((desc.Width, desc.Height), (frame.width, frame.height));
+        // The texture lives on the device handed out with it.
+        // SAFETY: GetDevice returns the owning device.
+        let device = unsafe { frame.texture().GetDevice() }.unwrap();
+        assert_eq!(device, frame.d3d().device);
+        (frame.width, frame.height)
+    };
+
+    // The readback path matches the GPU frame size.
+    let frame = cap.capture().unwrap();
+    assert_eq!((frame.width, frame.height), (width, height));
+}
+
+#[test]
+fn test_refresh_white_level() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    let nits = cap.refresh_white_level();
+    assert!(nits > 0.0);
+    assert_eq!(nits, cap.sdr_white_nits());
+    assert_eq!(cap.capture().unwrap().sdr_white_nits, nits);
+
+    // An explicit override is kept.
+    cap.set_sdr_white_nits(203.0).unwrap();
+    assert_eq!(cap.refresh_white_level(), 203.0);
+}
+
+#[test]
+fn test_try_grab_never_waits() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    let first = cap.capture().unwrap();
+
+    // Drain whatever arrived meanwhile; with nothing buffered, a static or
+    // busy screen both return within a fraction of a frame interval.
+    let _ = cap.try_grab().unwrap();
+    let start = Instant::now();
+    if let Some(frame) = cap.try_grab().unwrap() {
+        assert_eq!((frame.width, frame.height), (first.width, first.height));
+    }
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+fn process_handle_count() -> u32 {
+    let mut count = 0;
+    // SAFETY: the pseudo-handle needs no closing; the count is written to a local.
+    unsafe { GetProcessHandleCount(GetCurrentProcess(), &mut count) }.unwrap();
+    count
+}
+
+#[test]
+fn test_readback_ring_drains_without_new_frames() {
+    let monitor = &monitor_infos().unwrap()[0];
+    let (x, y) = (
+        monitor.rect.left + monitor.width as i32 / 2,
+        monitor.rect.top + monitor.height as i32 / 2,
+    );
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .readback_depth(2)
+        .build()
+        .unwrap();
+    let first = cap.grab().unwrap();
+
+    // A cursor move makes DWM present one new frame; its copy is queued and
+    // the grab repeats the first frame while the ring fills.
+    unsafe { SetCursorPos(x, y).unwrap() };
+    std::thread::sleep(Duration::from_millis(100));
+    unsafe { SetCursorPos(x + 1, y).unwrap() };
+    std::thread::sleep(Duration::from_millis(100));
+    let repeated = cap.grab().unwrap();
+    assert_eq!(repeated.timestamp, first.timestamp);
+
+    // Nothing new arrives now, yet the queued frame must come out.
+    std::thread::sleep(Duration::from_millis(100));
+    let drained = cap.grab().unwrap();
+    assert!(drained.timestamp > first.timestamp);
+}
+
+#[test]
+fn test_grab_changed_with_readback_ring() {
+    let monitor = &monitor_infos().unwrap()[0];
+    let (x, y) = (
+        monitor.rect.left + monitor.width as i32 / 2,
+        monitor.rect.top + monitor.height as i32 / 2,
+    );
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .readback_depth(2)
+        .build()
+        .unwrap();
+    let first = cap.grab_changed().unwrap().unwrap();
+
+    // The moved cursor changes the checksum; the returned frame must be the
+    // new one, not the cached frame the ring would repeat while filling.
+    unsafe { SetCursorPos(x, y).unwrap() };
+    std::thread::sleep(Duration::from_millis(100));
+    unsafe { SetCursorPos(x + 1, y).unwrap() };
+    std::thread::sleep(Duration::from_millis(100));
+    let changed = cap.grab_changed().unwrap().unwrap();
+    assert!(changed.timestamp > first.timestamp);
+}
+
+#[test]
+fn test_close_releases_handles() {
+    // The first pipeline sets up process-wide state (DXGI factory, thread
+    // pool workers) that stays alive; count from after it.
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    cap.capture().unwrap();
+    cap.close();
+    let before = process_handle_count();
+
+    for i in 0..100 {
+        let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+        let name = format!("hdrcapture_close_test_{}_{}", std::process::id(), i);
+        cap.publish_to_shared_memory(&name).unwrap();
+        cap.capture().unwrap();
+        if i % 2 == 0 {
+            cap.close();
+        } else {
+            drop(cap);
+        }
+    }
+
+    // Worker threads come and go; a leak would add at least one handle
+    // (event, file mapping) per pipeline.
+    let after = process_handle_count();
+    assert!(
+        after < before + 32,
+        "handle count grew from {} to {} over 100 pipelines",
+        before,
+        after
+    );
+}
+
+#[test]
+fn test_on_frame_callback_finished_after_drop() {
+    let d3d_ctx = hdrcapture::d3d11::create_d3d11_device().unwrap();
+    let monitor = find_monitor(0).unwrap();
+    let mut capture = init_capture(
+        &d3d_ctx,
+        CaptureTarget::Monitor(monitor),
+        CapturePolicy::Sdr,
+        &SessionOptions::default(),
+    )
+    .unwrap();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let running = Arc::new(AtomicBool::new(false));
+    capture
+        .on_frame({
+            let (calls, running) = (Arc::clone(&calls), Arc::clone(&running));
+            move |_frame| {
+                running.store(true, Ordering::SeqCst);
+                calls.fetch_add(1, Ordering::SeqCst);
+                // Long enough that drop usually lands mid-call.
+                std::thread::sleep(Duration::from_millis(50));
+                running.store(false, Ordering::SeqCst);
+            }
+        })
+        .unwrap();
+    capture.start().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while calls.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(
+        calls.load(Ordering::SeqCst) > 0,
+        "no frame reached the callback"
+    );
+
+    drop(capture);
+    assert!(
+        !running.load(Ordering::SeqCst),
+        "callback still running after drop"
+    );
+    let after_drop = calls.load(Ordering::SeqCst);
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(calls.load(Ordering::SeqCst), after_drop);
+}
+
+#[test]
+fn test_capture_bracketed_orders_exposures() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    if !cap.is_hdr() {
+        assert!(cap.capture_bracketed(&[0.0]).is_err());
+        println!("SKIPPED: monitor 0 is not in HDR mode");
+        return;
+    }
+
+    let bracket = cap.capture_bracketed(&[-2.0, 0.0, 2.0]).unwrap();
+    let frames = &bracket.frames;
+    assert_eq!(frames.len(), 3);
+    assert!(bracket.peak_nits >= 0.0);
+    assert!(bracket.headroom_stops >= 0.0);
+    let mean = |frame: &CapturedFrame| {
+        let data = frame.data.as_slice();
+        data.iter().map(|&b| b as u64).sum::<u64>() as f64 / data.len() as f64
+    };
+    // One source frame: same timestamp, brightness rising with the stop.
+    assert!(frames.iter().all(|f| f.timestamp == frames[0].timestamp));
+    assert!(mean(&frames[0]) <= mean(&frames[1]));
+    assert!(mean(&frames[1]) <= mean(&frames[2]));
+    // Bracketing leaves the regular exposure untouched.
+    assert_eq!(cap.exposure(), 1.0);
+}
+
+#[test]
+fn test_capture_nv12_layout() {
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Auto)
+        .resize_output(640, 360, ScaleFit::Stretch)
+        .build()
+        .unwrap();
+    let (data, width, height) = cap.capture_nv12().unwrap();
+    assert_eq!((width, height), (640, 360));
+    // Y plane plus half-resolution interleaved UV.
+    assert_eq!(data.len(), 640 * 360 * 3 / 2);
+    // Limited range: luma stays within 16..=235.
+    assert!(data[..640 * 360].iter().all(|&y| (16..=235).contains(&y)));
+}
+
+#[test]
+fn test_force_format_keeps_fp16_with_hdr_policy() {
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Hdr)
+        .force_format(ColorPixelFormat::Rgba16f)
+        .build()
+        .unwrap();
+    // RGBA16F on SDR and HDR monitors alike.
+    let frame = cap.capture().unwrap();
+    assert_eq!(frame.format, ColorPixelFormat::Rgba16f);
+    assert_eq!(
+        frame.data.len(),
+        frame.width as usize * frame.height as usize * 8
+    );
+
+    // Auto tone-maps the forced fp16 surfaces back to BGRA8.
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Auto)
+        .force_format(ColorPixelFormat::Rgba16f)
+        .build()
+        .unwrap();
+    assert_eq!(cap.capture().unwrap().format, ColorPixelFormat::Bgra8);
+
+    // A BGRA8 frame pool can't feed Hdr.
+    assert!(CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Hdr)
+        .force_format(ColorPixelFormat::Bgra8)
+        .build()
+        .is_err());
+}
+
+/// Move the cursor on monitor 0 so DWM presents a new frame, then grab it.
+///
+/// Policy changes apply to newly processed frames only; the cached frame of
+/// a static desktop keeps its old format.
+fn grab_after_cursor_move(cap: &mut CapturePipeline, step: i32) -> CapturedFrame {
+    let monitor = &monitor_infos().unwrap()[0];
+    let (x, y) = (
+        monitor.rect.left + monitor.width as i32 / 2 + step,
+        monitor.rect.top + monitor.height as i32 / 2,
+    );
+    unsafe { SetCursorPos(x, y).unwrap() };
+    std::thread::sleep(Duration::from_millis(100));
+    cap.grab().unwrap()
+}
+
+#[test]
+fn test_set_policy_on_hdr_frame_pool() {
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Hdr)
+        .force_format(ColorPixelFormat::Rgba16f)
+        .build()
+        .unwrap();
+    assert_eq!(cap.capture().unwrap().format, ColorPixelFormat::Rgba16f);
+
+    // Tone-mapped and clipped SDR both come from the same fp16 surfaces.
+    for (step, policy) in [(1, CapturePolicy::Auto), (2, CapturePolicy::Sdr)] {
+        cap.set_policy(policy).unwrap();
+        assert_eq!(cap.policy(), policy);
+        let frame = grab_after_cursor_move(&mut cap, step);
+        assert_eq!(frame.format, ColorPixelFormat::Bgra8);
+        assert_eq!(
+            frame.data.len(),
+            frame.width as usize * frame.height as usize * 4
+        );
+    }
+
+    // PreferHdr settles on Hdr for an fp16 frame pool.
+    cap.set_policy(CapturePolicy::PreferHdr).unwrap();
+    assert_eq!(cap.policy(), CapturePolicy::Hdr);
+    let frame = grab_after_cursor_move(&mut cap, 3);
+    assert_eq!(frame.format, ColorPixelFormat::Rgba16f);
+
+    // Sdr chosen at build time on fp16 surfaces takes the same clip path.
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Sdr)
+        .force_format(ColorPixelFormat::Rgba16f)
+        .build()
+        .unwrap();
+    assert_eq!(cap.capture().unwrap().format, ColorPixelFormat::Bgra8);
+}
+
+#[test]
+fn test_set_policy_on_bgra8_frame_pool() {
+    let mut cap = CapturePipelineBuilder::new()
+        .monitor(0)
+        .policy(CapturePolicy::Sdr)
+        .force_format(ColorPixelFormat::Bgra8)
+        .build()
+        .unwrap();
+    cap.capture().unwrap();
+
+    // No HDR data is captured, so Hdr is refused and the policy is kept.
+    let err = cap.set_policy(CapturePolicy::Hdr).unwrap_err();
+    assert!(err.to_string().contains("CapturePolicy::Hdr"), "{err}");
+    assert_eq!(cap.policy(), CapturePolicy::Sdr);
+
+    cap.set_policy(CapturePolicy::Auto).unwrap();
+    assert_eq!(cap.policy(), CapturePolicy::Auto);
+    let frame = grab_after_cursor_move(&mut cap, 1);
+    assert_eq!(frame.format, ColorPixelFormat::Bgra8);
+
+    // PreferHdr settles on Sdr for a BGRA8 frame pool.
+    cap.set_policy(CapturePolicy::PreferHdr).unwrap();
+    assert_eq!(cap.policy(), CapturePolicy::Sdr);
+}
+
+#[test]
+fn test_preview_subsamples_frame() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    let full = cap.capture().unwrap();
+    let preview = cap.preview(4).unwrap();
+    assert_eq!(preview.width, full.width.div_ceil(4));
+    assert_eq!(preview.height, full.height.div_ceil(4));
+    assert_eq!(
+        preview.data.len(),
+        preview.width as usize * preview.height as usize * preview.bytes_per_pixel()
+    );
+    assert!(cap.preview(0).is_err());
+
+    // Previews don't replace the full-resolution fallback frame.
+    let frame = cap.grab().unwrap();
+    assert_eq!((frame.width, frame.height), (full.width, full.height));
+}
+
+#[test]
+fn test_transform_rotates_on_gpu() {
+    let mut cap = CapturePipeline::monitor(0, CapturePolicy::Auto).unwrap();
+    let full = cap.capture().unwrap();
+
+    let mut rotated = CapturePipelineBuilder::new()
+        .monitor(0)
+        .transform(FrameTransform::rotate(Rotation::Cw90))
+        .build()
+        .unwrap();
+    let frame = rotated.capture().unwrap();
+    assert_eq!((frame.width, frame.height), (full.height, full.width));
+    // The CPU path produces the same geometry.
+    let cpu = full.rotate(Rotation::Cw90);
+    assert_eq!((cpu.width, cpu.height), (frame.width, frame.height));
+}
+
 // ---------------------------------------------------------------------------
 // SDR: multi-format save with timing
 // ---------------------------------------------------------------------------
@@ -144,7 +645,7 @@ fn test_save_sdr_formats() {
     print!("{}{}", col_header, separator);
     write!(report, "{}{}", col_header, separator).unwrap();
 
-    let extensions = ["png", "bmp", "jpg", "tiff", "jxr", "exr"];
+    let extensions = ["png", "bmp", "jpg", "tiff", "webp", "jxr", "exr", "hdr"];
 
     for ext in &extensions {
         let path = format!("tests/results/sdr_test.{}", ext);
@@ -203,7 +704,7 @@ fn test_save_hdr_formats() {
     write!(report, "{}{}", col_header, separator).unwrap();
 
     // HDR-capable formats
-    let hdr_extensions = ["jxr", "exr"];
+    let hdr_extensions = ["jxr", "exr", "hdr"];
 
     for ext in &hdr_extensions {
         let path = format!("tests/results/hdr_test.{}", ext);
@@ -222,8 +723,44 @@ fn test_save_hdr_formats() {
         write!(report, "{}", line).unwrap();
     }
 
+    // EXR compression trade-off (all lossless)
+    writeln!(report).unwrap();
+    println!();
+    let exr_header = format!("{:<8} {:>10} {:>12}\n", "exr", "time(ms)", "size(bytes)");
+    print!("{}{}", exr_header, separator);
+    write!(report, "{}{}", exr_header, separator).unwrap();
+
+    for (name, compression) in [
+        ("none", ExrCompression::None),
+        ("rle", ExrCompression::Rle),
+        ("zip", ExrCompression::Zip),
+        ("piz", ExrCompression::Piz),
+    ] {
+        let path = format!("tests/results/hdr_test_{}.exr", name);
+
+        let t = Instant::now();
+        let options = SaveOptions {
+            exr: ExrOptions {
+                compression,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        frame
+            .save_with(&path, &options)
+            .unwrap_or_else(|e| panic!("Failed to save EXR ({}): {}", name, e));
+        let ms = t.elapsed().as_secs_f64() * 1000.0;
+
+        let meta = fs::metadata(&path).unwrap();
+        assert!(meta.len() > 0, "{} EXR file is empty", name);
+
+        let line = format!("{:<8} {:>10.2} {:>12}\n", name, ms, meta.len());
+        print!("{}", line);
+        write!(report, "{}", line).unwrap();
+    }
+
     // Verify SDR-only formats correctly reject HDR data
-    let sdr_extensions = ["png", "bmp", "jpg", "tiff"];
+    let sdr_extensions = ["png", "bmp", "jpg", "tiff", "webp"];
     writeln!(report).unwrap();
     println!();
 
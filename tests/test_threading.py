@@ -121,6 +121,22 @@ def test_close_from_different_thread() -> None:
         cap.capture()
 
 
+def test_timeouts_apply_backpressure() -> None:
+    cap = hdrcapture.capture.monitor(0)
+    try:
+        # Far more near-zero timeouts than requests allowed in flight: later
+        # calls wait for abandoned ones instead of queueing without bound.
+        for _ in range(50):
+            try:
+                cap.capture(timeout=0.001)
+            except TimeoutError:
+                pass
+        frame = cap.capture(timeout=10)
+        assert frame.width > 0
+    finally:
+        cap.close()
+
+
 def test_drop_without_explicit_close() -> None:
     cap = hdrcapture.capture.monitor(0)
     _ = cap.capture()
@@ -55,3 +55,41 @@ fn test_capture_target_window() {
 
     frame.save("tests/results/window_capture.png").unwrap();
 }
+
+#[test]
+fn test_capture_child_window() {
+    use hdrcapture::pipeline::CapturePipelineBuilder;
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::UI::WindowsAndMessaging::{FindWindowExW, FindWindowW, GetClientRect};
+
+    // Notification area: a child control of explorer's taskbar.
+    let child = unsafe {
+        FindWindowW(w!("Shell_TrayWnd"), PCWSTR::null()).and_then(|taskbar| {
+            FindWindowExW(Some(taskbar), None, w!("TrayNotifyWnd"), PCWSTR::null())
+        })
+    };
+    let Ok(child) = child else {
+        println!("SKIPPED: no taskbar notification area");
+        return;
+    };
+
+    let mut client = RECT::default();
+    unsafe { GetClientRect(child, &mut client) }.unwrap();
+
+    let mut pipeline = CapturePipelineBuilder::new()
+        .window_hwnd(child.0 as isize)
+        .headless(false)
+        .build()
+        .unwrap();
+    let frame = pipeline.capture().unwrap();
+
+    // Cropped to the child, not the whole taskbar.
+    assert_eq!(frame.width, client.right as u32);
+    assert_eq!(frame.height, client.bottom as u32);
+    println!("Child window: {}x{}", frame.width, frame.height);
+
+    frame
+        .save("tests/results/child_window_capture.png")
+        .unwrap();
+}